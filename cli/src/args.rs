@@ -19,6 +19,25 @@ pub struct CliArguments {
     #[clap(long = "root", env = "TYPST_ROOT", value_name = "DIR")]
     pub root: Option<PathBuf>,
 
+    /// Caches font metadata, decoded images and downloads in this directory,
+    /// so that later invocations can skip parsing unchanged files
+    #[clap(long = "cache-dir", env = "TYPST_CACHE_DIR", value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// The maximum size of the cache directory, beyond which the oldest entries
+    /// are removed
+    #[clap(
+        long = "cache-size",
+        env = "TYPST_CACHE_SIZE",
+        value_name = "MEGABYTES",
+        default_value_t = 256
+    )]
+    pub cache_size: u64,
+
+    /// Forbids network access, so that remote files must be in the cache
+    #[clap(long = "offline", env = "TYPST_OFFLINE")]
+    pub offline: bool,
+
     /// Sets the level of logging verbosity:
     /// -v = warning & error, -vv = info, -vvv = debug, -vvvv = trace
     #[clap(short, long, action = ArgAction::Count)]
@@ -41,6 +60,30 @@ impl Display for DiagnosticFormat {
     }
 }
 
+/// Which format to export into.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
+pub enum OutputFormat {
+    Pdf,
+    Png,
+    Html,
+}
+
+/// How colors outside of the color profile's gamut are mapped.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum RenderingIntent {
+    Perceptual,
+    #[default]
+    RelativeColorimetric,
+    Saturation,
+    AbsoluteColorimetric,
+}
+
+/// An experimental language feature.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum Feature {
+    NestedDestructuring,
+}
+
 /// What to do.
 #[derive(Debug, Clone, Subcommand)]
 #[command()]
@@ -55,6 +98,19 @@ pub enum Command {
 
     /// List all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
+
+    /// Compiles two versions of a document and lists the changed lines
+    Diff(DiffCommand),
+
+    /// Lists the fonts and files a document needs as JSON, without laying it out
+    Resources(ResourcesCommand),
+
+    /// Compares the syntax trees and evaluation results of files with snapshots
+    Snapshot(SnapshotCommand),
+
+    /// Lists the words of a document with their language as JSON, for spell
+    /// checking
+    Words(WordsCommand),
 }
 
 impl Command {
@@ -63,7 +119,11 @@ impl Command {
         match self {
             Command::Compile(cmd) => Some(cmd),
             Command::Watch(cmd) => Some(cmd),
-            Command::Fonts(_) => None,
+            Command::Fonts(_)
+            | Command::Diff(_)
+            | Command::Resources(_)
+            | Command::Snapshot(_)
+            | Command::Words(_) => None,
         }
     }
 
@@ -76,12 +136,23 @@ impl Command {
 /// Compiles the input file into a PDF file
 #[derive(Debug, Clone, Parser)]
 pub struct CompileCommand {
-    /// Path to input Typst file
-    pub input: PathBuf,
+    /// Path to input Typst file, use `-` to read from stdin. If omitted, the
+    /// entry file of the `typst.toml` project manifest in the current
+    /// directory or one of its parents is compiled
+    pub input: Option<PathBuf>,
 
-    /// Path to output PDF file or PNG file(s)
+    /// Path to output PDF file, PNG file(s) or HTML file, use `-` to write to stdout
     pub output: Option<PathBuf>,
 
+    /// The format of the output, by default determined from the output path
+    #[arg(long = "format", value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// The targets of the project manifest to build, separated by commas. By
+    /// default, all targets are built
+    #[arg(long = "target", value_delimiter = ',')]
+    pub targets: Vec<String>,
+
     /// Opens the output file after compilation using the default PDF viewer
     #[arg(long = "open")]
     pub open: Option<Option<String>>,
@@ -90,6 +161,18 @@ pub struct CompileCommand {
     #[arg(long = "ppi")]
     pub ppi: Option<f32>,
 
+    /// Downscale images that exceed this DPI at their placed size in PDF export
+    #[arg(long = "max-dpi")]
+    pub max_dpi: Option<f64>,
+
+    /// Converts all colors in PDF export to `srgb`, `cmyk` or the ICC profile at the given path
+    #[arg(long = "color-profile")]
+    pub color_profile: Option<String>,
+
+    /// How colors outside of the color profile's gamut are mapped
+    #[arg(long = "rendering-intent", value_enum, default_value_t)]
+    pub rendering_intent: RenderingIntent,
+
     /// In which format to emit diagnostics
     #[clap(
         long,
@@ -101,6 +184,66 @@ pub struct CompileCommand {
     /// Produces a flamegraph of the compilation process
     #[arg(long = "flamegraph", value_name = "OUTPUT_SVG")]
     pub flamegraph: Option<Option<PathBuf>>,
+
+    /// Draws the bounds of all frames, regions and text runs and their
+    /// baselines for debugging
+    #[arg(long = "debug-frames")]
+    pub debug_frames: bool,
+
+    /// Prints a trace explaining why lines, columns and pages break
+    #[arg(long = "trace-breaks")]
+    pub trace_breaks: bool,
+
+    /// Replaces images that fail to load with placeholders instead of failing
+    /// and shows text in missing fonts in a fallback font
+    #[arg(long = "placeholders")]
+    pub placeholders: bool,
+
+    /// Warns about structural mistakes like skipped heading levels, empty
+    /// sections and duplicate labels
+    #[arg(long = "lint")]
+    pub lint: bool,
+
+    /// Enables experimental language features, separated by commas
+    #[arg(long = "features", value_enum, value_delimiter = ',')]
+    pub features: Vec<Feature>,
+
+    /// Writes the files the compilation depends on, including the fonts it
+    /// uses, into a Makefile-style dependency file
+    #[arg(long = "make-deps", value_name = "PATH")]
+    pub make_deps: Option<PathBuf>,
+
+    /// Compiles the document repeatedly without caches and prints how long
+    /// each phase took, instead of writing the output
+    #[arg(long = "bench", value_name = "RUNS")]
+    pub bench: Option<Option<usize>>,
+
+    /// Compares the benchmark with the timings in this file, or saves them
+    /// there if it doesn't exist yet
+    #[arg(long = "bench-baseline", value_name = "PATH", requires = "bench")]
+    pub bench_baseline: Option<PathBuf>,
+
+    /// In watch mode, for how many compilations unused cached results are kept
+    #[arg(long = "cache-max-age", value_name = "COMPILATIONS", default_value_t = 30)]
+    pub cache_max_age: usize,
+
+    /// Limits the memory used by an in-memory cache, given as CACHE=MEGABYTES
+    /// where CACHE is `images`, `glyphs`, `svg-glyphs` or `shaping`
+    #[arg(
+        long = "memory-limit",
+        value_name = "CACHE=MEGABYTES",
+        value_parser = parse_memory_limit
+    )]
+    pub memory_limits: Vec<(String, usize)>,
+}
+
+/// Parse a memory limit of the form `CACHE=MEGABYTES`.
+fn parse_memory_limit(value: &str) -> Result<(String, usize), String> {
+    let (name, megabytes) = value
+        .split_once('=')
+        .ok_or("expected a limit of the form CACHE=MEGABYTES")?;
+    let megabytes = megabytes.parse().map_err(|_| "expected a number of megabytes")?;
+    Ok((name.into(), megabytes))
 }
 
 /// List all discovered fonts in system and custom font paths
@@ -110,3 +253,51 @@ pub struct FontsCommand {
     #[arg(long)]
     pub variants: bool,
 }
+
+/// Compiles two versions of a document and lists the changed lines
+#[derive(Debug, Clone, Parser)]
+pub struct DiffCommand {
+    /// Path to the old version of the input Typst file
+    pub old: PathBuf,
+
+    /// Path to the new version of the input Typst file
+    pub new: PathBuf,
+
+    /// Writes a PDF that shows the old and new pages side by side, with
+    /// removed and added lines highlighted and changed pages flagged
+    #[arg(long = "output", value_name = "OUTPUT_PDF")]
+    pub output: Option<PathBuf>,
+}
+
+/// Lists the fonts and files a document needs as JSON, without laying it out
+#[derive(Debug, Clone, Parser)]
+pub struct ResourcesCommand {
+    /// Path to input Typst file
+    pub input: PathBuf,
+}
+
+/// Lists the words of a document with their language as JSON, for spell
+/// checking
+#[derive(Debug, Clone, Parser)]
+pub struct WordsCommand {
+    /// Path to input Typst file
+    pub input: PathBuf,
+
+    /// Directory with Hunspell dictionaries like `en_US.aff` and `en_US.dic`
+    /// to mark misspelled words with (requires the `hunspell` feature)
+    #[clap(long = "dictionaries", value_name = "DIR")]
+    pub dictionaries: Option<PathBuf>,
+}
+
+/// Compares the syntax trees and evaluation results of files with snapshots
+#[derive(Debug, Clone, Parser)]
+pub struct SnapshotCommand {
+    /// Paths to input Typst files, each with snapshots in `.ast` and `.eval`
+    /// files next to it
+    #[arg(required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    /// Writes the current results into the snapshot files instead of comparing
+    #[arg(long)]
+    pub bless: bool,
+}
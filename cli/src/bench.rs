@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use comemo::Track;
+use typst::diag::{bail, SourceError, StrResult};
+use typst::eval::{Route, Tracer};
+use typst::geom::Color;
+use typst::World;
+
+use crate::args::OutputFormat;
+use crate::{print_diagnostics, set_failed, CompileSettings, SystemWorld, STDIN_PATH};
+
+/// The timed phases of a compilation.
+const PHASES: [&str; 4] = ["parse", "eval", "layout", "export"];
+
+/// Compile the document repeatedly and print the median time of each phase.
+///
+/// All caches are evicted before each run, so that every run measures a cold
+/// compilation. With a baseline, the timings are compared with the ones saved
+/// in it, or saved there if it doesn't exist yet.
+pub fn bench(
+    world: &mut SystemWorld,
+    command: &CompileSettings,
+    stdin: Option<&str>,
+    runs: usize,
+) -> StrResult<()> {
+    if runs == 0 {
+        bail!("must benchmark at least one run");
+    }
+
+    let mut samples = vec![vec![]; PHASES.len()];
+    for _ in 0..runs {
+        comemo::evict(0);
+        world.reset();
+        world.main = match stdin {
+            Some(text) => world.insert(Path::new(STDIN_PATH), text.into()),
+            None => world.resolve(&command.input).map_err(|err| err.to_string())?,
+        };
+
+        match run(world, command.format, command.ppi) {
+            Ok(timings) => {
+                for (phase, timing) in samples.iter_mut().zip(timings) {
+                    phase.push(timing);
+                }
+            }
+            Err(errors) => {
+                set_failed();
+                print_diagnostics(world, errors, command.diagnostic_format)
+                    .map_err(|_| "failed to print diagnostics")?;
+                return Ok(());
+            }
+        }
+    }
+
+    let medians: Vec<Duration> = samples
+        .iter_mut()
+        .map(|phase| {
+            phase.sort();
+            phase[phase.len() / 2]
+        })
+        .collect();
+
+    let baseline = match &command.bench_baseline {
+        Some(path) if path.exists() => Some(read_baseline(path)?),
+        Some(path) => {
+            write_baseline(path, &medians)?;
+            None
+        }
+        None => None,
+    };
+
+    println!("{runs} runs, median times");
+    for (i, (phase, median)) in PHASES.iter().zip(&medians).enumerate() {
+        let min = samples[i][0];
+        let mut line = format!(
+            "{phase:<8}{:>12}{:>12}",
+            format!("{median:.2?}"),
+            format!("min {min:.2?}")
+        );
+        if let Some(base) = baseline.as_ref().and_then(|base| base.get(*phase)) {
+            let change = (median.as_secs_f64() / base - 1.0) * 100.0;
+            line.push_str(&format!(
+                "{:>14}{:>10}",
+                format!("was {:.2?}", Duration::from_secs_f64(*base)),
+                format!("{change:+.1}%")
+            ));
+        }
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+/// Compile once and time each phase.
+fn run(
+    world: &SystemWorld,
+    format: OutputFormat,
+    ppi: Option<f32>,
+) -> Result<Vec<Duration>, Vec<SourceError>> {
+    let mut timings = vec![];
+
+    let start = Instant::now();
+    black_box(typst::syntax::parse(world.main().text()));
+    timings.push(start.elapsed());
+
+    let route = Route::default();
+    let mut tracer = Tracer::default();
+    let tracked = (world as &dyn World).track();
+
+    let start = Instant::now();
+    let module =
+        typst::eval::eval(tracked, route.track(), tracer.track_mut(), world.main())
+            .map_err(|errors| *errors)?;
+    timings.push(start.elapsed());
+
+    let start = Instant::now();
+    let document = typst::model::typeset(tracked, tracer.track_mut(), &module.content())
+        .map_err(|errors| *errors)?;
+    timings.push(start.elapsed());
+
+    let start = Instant::now();
+    match format {
+        OutputFormat::Pdf => {
+            black_box(typst::export::pdf(&document).unwrap());
+        }
+        OutputFormat::Png => {
+            let ppi = ppi.unwrap_or(2.0);
+            for frame in &document.pages {
+                black_box(typst::export::render(frame, ppi, Color::WHITE));
+            }
+        }
+        OutputFormat::Html => {
+            black_box(typst::export::html(&document));
+        }
+    }
+    timings.push(start.elapsed());
+
+    Ok(timings)
+}
+
+/// Read the timings in seconds from a baseline file.
+fn read_baseline(path: &Path) -> StrResult<HashMap<String, f64>> {
+    let data = fs::read(path).map_err(|_| "failed to read benchmark baseline")?;
+    serde_json::from_slice(&data).map_err(|_| "failed to parse benchmark baseline".into())
+}
+
+/// Save the timings in seconds into a baseline file.
+fn write_baseline(path: &Path, medians: &[Duration]) -> StrResult<()> {
+    let timings: HashMap<&str, f64> = PHASES
+        .iter()
+        .zip(medians)
+        .map(|(phase, median)| (*phase, median.as_secs_f64()))
+        .collect();
+    let json = serde_json::to_string_pretty(&timings)
+        .map_err(|_| "failed to serialize benchmark baseline")?;
+    fs::write(path, json).map_err(|_| "failed to write benchmark baseline".into())
+}
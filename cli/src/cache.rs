@@ -0,0 +1,139 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use siphasher::sip128::{Hasher128, SipHasher13};
+use walkdir::WalkDir;
+
+use crate::args::CliArguments;
+
+/// A cache on disk that is shared across invocations.
+///
+/// Entries are keyed by a hash of the contents they were computed from, so
+/// that they stay valid when files are moved around or restored with new
+/// modification times, as is common in CI. Entries are grouped into one
+/// subdirectory per kind and written atomically, so that several processes
+/// can share the same cache.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    /// The directory holding the entries.
+    dir: PathBuf,
+    /// The maximum total size of the entries in bytes.
+    max_size: u64,
+}
+
+impl DiskCache {
+    /// Create a disk cache in a directory.
+    pub fn new(dir: PathBuf, max_size: u64) -> Self {
+        Self { dir, max_size }
+    }
+
+    /// Create a disk cache from the CLI arguments, if it's enabled.
+    pub fn with_arguments(args: &CliArguments) -> Option<Self> {
+        let dir = args.cache_dir.clone()?;
+        Some(Self::new(dir, args.cache_size.saturating_mul(1024 * 1024)))
+    }
+
+    /// Load and deserialize an entry.
+    pub fn get<T: DeserializeOwned>(&self, kind: &str, key: u128) -> Option<T> {
+        serde_json::from_slice(&self.get_bytes(kind, key)?).ok()
+    }
+
+    /// Serialize and store an entry.
+    pub fn put<T: Serialize>(&self, kind: &str, key: u128, value: &T) {
+        if let Ok(data) = serde_json::to_vec(value) {
+            self.put_bytes(kind, key, &data);
+        }
+    }
+
+    /// Load an entry as raw bytes.
+    pub fn get_bytes(&self, kind: &str, key: u128) -> Option<Vec<u8>> {
+        fs::read(self.path(kind, key)).ok()
+    }
+
+    /// Store raw bytes as an entry.
+    ///
+    /// Failing to write is not an error since the entry can just be computed
+    /// again the next time.
+    pub fn put_bytes(&self, kind: &str, key: u128, data: &[u8]) {
+        let path = self.path(kind, key);
+        let temp = path.with_extension(format!("tmp{}", std::process::id()));
+        let write = || -> io::Result<()> {
+            fs::create_dir_all(self.dir.join(kind))?;
+            fs::write(&temp, data)?;
+            fs::rename(&temp, &path)
+        };
+
+        if write().is_err() {
+            fs::remove_file(&temp).ok();
+        }
+    }
+
+    /// Remove the oldest entries until the cache fits into its maximum size.
+    ///
+    /// Only files that look like entries are considered, so that pointing the
+    /// cache to a directory with other contents doesn't delete them.
+    pub fn evict(&self) {
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = WalkDir::new(&self.dir)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file() && is_entry(entry.file_name()))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((modified, metadata.len(), entry.into_path()))
+            })
+            .collect();
+
+        let mut size: u64 = entries.iter().map(|&(_, len, _)| len).sum();
+        entries.sort();
+        for (_, len, path) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            if fs::remove_file(path).is_ok() {
+                size -= len;
+            }
+        }
+    }
+
+    /// The path of an entry.
+    fn path(&self, kind: &str, key: u128) -> PathBuf {
+        self.dir.join(kind).join(format!("{key:032x}"))
+    }
+}
+
+impl typst::image::RasterStore for DiskCache {
+    fn load(&self, key: u128) -> Option<Vec<u8>> {
+        self.get_bytes("images", self::key(key))
+    }
+
+    fn save(&self, key: u128, data: &[u8]) {
+        self.put_bytes("images", self::key(key), data);
+    }
+}
+
+/// Hash a value into a key for the disk cache.
+///
+/// Besides the value, the key includes the version of Typst, so that entries
+/// written by other versions are ignored.
+pub fn key(value: impl Hash) -> u128 {
+    let mut state = SipHasher13::new();
+    crate::typst_version().hash(&mut state);
+    value.hash(&mut state);
+    state.finish128().as_u128()
+}
+
+/// Whether a file name is the name of an entry.
+fn is_entry(name: &OsStr) -> bool {
+    name.to_str().map_or(false, |name| {
+        name.len() == 32 && name.bytes().all(|c| c.is_ascii_hexdigit())
+    })
+}
@@ -1,11 +1,17 @@
 mod args;
+mod bench;
+mod cache;
+mod manifest;
+mod network;
+#[cfg(feature = "hunspell")]
+mod spell;
 mod trace;
 
 use std::cell::{Cell, RefCell, RefMut};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::hash::Hash;
-use std::io::{self, IsTerminal, Write};
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
@@ -21,17 +27,25 @@ use same_file::{is_same_file, Handle};
 use siphasher::sip128::{Hasher128, SipHasher13};
 use std::cell::OnceCell;
 use termcolor::{ColorChoice, StandardStream, WriteColor};
-use typst::diag::{bail, FileError, FileResult, SourceError, StrResult};
-use typst::doc::Document;
-use typst::eval::{Datetime, Library};
+use typst::diag::{bail, FileError, FileResult, SourceError, SourceWarning, StrResult};
+use typst::doc::{Document, Frame, FrameItem, Meta};
+use typst::eval::{Datetime, Dict, Library, Value};
+use typst::export::{ColorProfile, PdfExporter, PdfOptions, ProfileSpace, Target};
 use typst::font::{Font, FontBook, FontInfo, FontVariant};
-use typst::geom::Color;
-use typst::syntax::{Source, SourceId};
+use typst::geom::{Color, Scalar};
+use typst::ide::LineDiff;
+use typst::syntax::{ast, Source, SourceId, SyntaxNode};
 use typst::util::{Buffer, PathExt};
 use typst::World;
 use walkdir::WalkDir;
 
-use crate::args::{CliArguments, Command, CompileCommand, DiagnosticFormat};
+use crate::args::{
+    CliArguments, Command, CompileCommand, DiagnosticFormat, DiffCommand, Feature,
+    OutputFormat, RenderingIntent, ResourcesCommand, SnapshotCommand, WordsCommand,
+};
+use crate::cache::DiskCache;
+use crate::manifest::{Manifest, MANIFEST_NAME};
+use crate::network::{Http, Network, LOCKFILE_NAME};
 
 type CodespanResult<T> = Result<T, CodespanError>;
 type CodespanError = codespan_reporting::files::Error;
@@ -53,9 +67,13 @@ fn main() -> ExitCode {
 
     let res = match &arguments.command {
         Command::Compile(_) | Command::Watch(_) => {
-            compile(CompileSettings::with_arguments(arguments))
+            CompileSettings::with_arguments(arguments).and_then(compile)
         }
         Command::Fonts(_) => fonts(FontsSettings::with_arguments(arguments)),
+        Command::Diff(_) => diff(DiffSettings::with_arguments(arguments)),
+        Command::Resources(_) => resources(ResourcesSettings::with_arguments(arguments)),
+        Command::Snapshot(_) => snapshot(SnapshotSettings::with_arguments(arguments)),
+        Command::Words(_) => words(WordsSettings::with_arguments(arguments)),
     };
 
     if let Err(msg) = res {
@@ -83,6 +101,25 @@ fn print_error(msg: &str) -> io::Result<()> {
     writeln!(w, ": {msg}.")
 }
 
+/// Print an application-level warning.
+fn print_warning(msg: &str) -> io::Result<()> {
+    let mut w = color_stream();
+    let styles = term::Styles::default();
+
+    w.set_color(&styles.header_warning)?;
+    write!(w, "warning")?;
+
+    w.reset()?;
+    writeln!(w, ": {msg}.")
+}
+
+/// The names of the in-memory caches whose size can be limited.
+fn memory_caches() -> Vec<&'static str> {
+    let mut caches = typst::export::MEMORY_CACHES.to_vec();
+    caches.push(typst_library::text::SHAPING_CACHE);
+    caches
+}
+
 /// Used by `args.rs`.
 fn typst_version() -> &'static str {
     env!("TYPST_VERSION")
@@ -90,10 +127,12 @@ fn typst_version() -> &'static str {
 
 /// A summary of the input arguments relevant to compilation.
 struct CompileSettings {
-    /// The path to the input file.
+    /// The path to the input file, `-` for stdin.
     input: PathBuf,
-    /// The path to the output file.
+    /// The path to the output file, `-` for stdout.
     output: PathBuf,
+    /// The format to export into.
+    format: OutputFormat,
     /// Whether to watch the input files for changes.
     watch: bool,
     /// The root directory for absolute paths.
@@ -104,8 +143,41 @@ struct CompileSettings {
     open: Option<Option<String>>,
     /// The PPI to use for PNG export.
     ppi: Option<f32>,
+    /// The maximum resolution of images in PDF export.
+    max_dpi: Option<f64>,
+    /// The color profile to convert colors to in PDF export.
+    color_profile: Option<String>,
+    /// How colors outside of the color profile's gamut are mapped.
+    rendering_intent: RenderingIntent,
     /// In which format to emit diagnostics.
     diagnostic_format: DiagnosticFormat,
+    /// Whether to draw the bounds and baselines of frames.
+    debug_frames: bool,
+    /// Whether to print why lines, columns and pages broke.
+    trace_breaks: bool,
+    /// Whether to replace images that fail to load with placeholders.
+    placeholders: bool,
+    /// Whether to warn about structural mistakes in the document.
+    lint: bool,
+    /// The experimental language features to enable.
+    features: Vec<Feature>,
+    /// Where to write the dependency file.
+    make_deps: Option<PathBuf>,
+    /// How many times to compile for a benchmark.
+    bench: Option<usize>,
+    /// The file with the benchmark timings to compare with.
+    bench_baseline: Option<PathBuf>,
+    /// For how many compilations unused cached results are kept in watch mode.
+    cache_max_age: usize,
+    /// The cache on disk that is shared across invocations.
+    disk_cache: Option<DiskCache>,
+    /// Whether network access is forbidden.
+    offline: bool,
+    /// The values that the document can read from `sys.inputs`.
+    inputs: Dict,
+    /// Whether this is the first of several targets that are built together.
+    /// Only its status clears the terminal in watch mode.
+    first_target: bool,
 }
 
 impl CompileSettings {
@@ -114,53 +186,231 @@ impl CompileSettings {
     fn new(
         input: PathBuf,
         output: Option<PathBuf>,
+        format: Option<OutputFormat>,
         watch: bool,
         root: Option<PathBuf>,
         font_paths: Vec<PathBuf>,
         open: Option<Option<String>>,
         ppi: Option<f32>,
+        max_dpi: Option<f64>,
+        color_profile: Option<String>,
+        rendering_intent: RenderingIntent,
         diagnostic_format: DiagnosticFormat,
+        debug_frames: bool,
+        trace_breaks: bool,
+        placeholders: bool,
+        lint: bool,
+        features: Vec<Feature>,
+        make_deps: Option<PathBuf>,
+        bench: Option<Option<usize>>,
+        bench_baseline: Option<PathBuf>,
+        cache_max_age: usize,
+        disk_cache: Option<DiskCache>,
+        offline: bool,
+        inputs: Dict,
     ) -> Self {
         let output = match output {
             Some(path) => path,
+            None if is_stdio(&input) => input.clone(),
             None => input.with_extension("pdf"),
         };
+        let format = format.unwrap_or_else(|| match output.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => OutputFormat::Png,
+            Some(ext) if ext.eq_ignore_ascii_case("html") => OutputFormat::Html,
+            _ => OutputFormat::Pdf,
+        });
         Self {
             input,
             output,
+            format,
             watch,
             root,
             font_paths,
             open,
             diagnostic_format,
             ppi,
+            max_dpi,
+            color_profile,
+            rendering_intent,
+            debug_frames,
+            trace_breaks,
+            placeholders,
+            lint,
+            features,
+            make_deps,
+            bench: bench.map(|runs| runs.unwrap_or(10)),
+            bench_baseline,
+            cache_max_age,
+            disk_cache,
+            offline,
+            inputs,
+            first_target: true,
         }
     }
 
-    /// Create a new compile settings from the CLI arguments and a compile command.
+    /// Create compile settings from the CLI arguments and a compile command.
+    ///
+    /// Without an input file, the settings are taken from the project manifest
+    /// with flags taking precedence. Then, there is one compile settings for
+    /// each selected target of the manifest.
     ///
     /// # Panics
     /// Panics if the command is not a compile or watch command.
-    fn with_arguments(args: CliArguments) -> Self {
+    fn with_arguments(args: CliArguments) -> StrResult<Vec<Self>> {
         let watch = matches!(args.command, Command::Watch(_));
-        let CompileCommand { input, output, open, ppi, diagnostic_format, .. } =
-            match args.command {
-                Command::Compile(command) => command,
-                Command::Watch(command) => command,
-                _ => unreachable!(),
-            };
-
-        Self::new(
+        let disk_cache = DiskCache::with_arguments(&args);
+        let CompileCommand {
             input,
             output,
-            watch,
-            args.root,
-            args.font_paths,
+            format,
+            targets,
             open,
             ppi,
+            max_dpi,
+            color_profile,
+            rendering_intent,
             diagnostic_format,
-        )
+            debug_frames,
+            trace_breaks,
+            placeholders,
+            lint,
+            features,
+            make_deps,
+            bench,
+            bench_baseline,
+            cache_max_age,
+            memory_limits,
+            ..
+        } = match args.command {
+            Command::Compile(command) => command,
+            Command::Watch(command) => command,
+            _ => unreachable!(),
+        };
+
+        let caches = memory_caches();
+        for (name, megabytes) in memory_limits {
+            if !caches.contains(&name.as_str()) {
+                bail!("unknown cache `{name}`, expected one of: {}", caches.join(", "));
+            }
+            typst::util::lru::set_limit(&name, megabytes.saturating_mul(1024 * 1024));
+        }
+
+        let mut root = args.root;
+        let mut font_paths = args.font_paths;
+        let mut builds = vec![];
+        match input {
+            Some(input) => {
+                if !targets.is_empty() {
+                    bail!("targets can only be selected from a {MANIFEST_NAME}");
+                }
+                builds.push((
+                    input,
+                    output,
+                    format,
+                    ppi,
+                    max_dpi,
+                    color_profile,
+                    Dict::new(),
+                ));
+            }
+            None => {
+                let dir = std::env::current_dir()
+                    .map_err(|_| "failed to read current directory")?;
+                let Some(manifest) = Manifest::discover(&dir)? else {
+                    bail!("no input file given and no {MANIFEST_NAME} found");
+                };
+
+                root = root.or_else(|| Some(manifest.root()));
+                font_paths.extend(manifest.font_paths());
+                let entry = manifest.entry()?;
+                let exports = manifest.exports(&targets)?;
+                if output.is_some() && exports.len() > 1 {
+                    bail!("cannot write several targets to the same output");
+                }
+
+                for export in exports {
+                    let inputs = manifest
+                        .inputs(&export)
+                        .into_iter()
+                        .map(|(key, value)| (key.into(), Value::Str(value.into())))
+                        .collect();
+                    builds.push((
+                        entry.clone(),
+                        output.clone().or_else(|| export.output(&manifest.dir)),
+                        format.or(export.format()?),
+                        ppi.or(export.ppi),
+                        max_dpi.or(export.max_dpi),
+                        color_profile
+                            .clone()
+                            .or_else(|| export.color_profile(&manifest.dir)),
+                        inputs,
+                    ));
+                }
+            }
+        }
+
+        if builds.len() > 1 && bench.is_some() {
+            bail!("cannot benchmark several targets");
+        }
+
+        if builds.len() > 1 && make_deps.is_some() {
+            bail!("cannot write a dependency file for several targets");
+        }
+
+        let settings: Vec<Self> = builds
+            .into_iter()
+            .enumerate()
+            .map(|(i, (input, output, format, ppi, max_dpi, color_profile, inputs))| {
+                let mut settings = Self::new(
+                    input,
+                    output,
+                    format,
+                    watch,
+                    root.clone(),
+                    font_paths.clone(),
+                    open.clone(),
+                    ppi,
+                    max_dpi,
+                    color_profile,
+                    rendering_intent,
+                    diagnostic_format,
+                    debug_frames,
+                    trace_breaks,
+                    placeholders,
+                    lint,
+                    features.clone(),
+                    make_deps.clone(),
+                    bench,
+                    bench_baseline.clone(),
+                    cache_max_age,
+                    disk_cache.clone(),
+                    args.offline,
+                    inputs,
+                );
+                settings.first_target = i == 0;
+                settings
+            })
+            .collect();
+
+        check_outputs(settings.iter().map(|command| command.output.as_path()))?;
+        Ok(settings)
+    }
+}
+
+/// Ensure that no two targets are written to the same file, which happens
+/// when several targets don't set an output of their own.
+fn check_outputs<'a>(outputs: impl IntoIterator<Item = &'a Path>) -> StrResult<()> {
+    let mut seen = HashSet::new();
+    for output in outputs {
+        if !seen.insert(output.normalize()) {
+            bail!(
+                "several targets would be written to {}, give each of them an \
+                 `output` in {MANIFEST_NAME}",
+                output.display()
+            );
+        }
     }
+    Ok(())
 }
 
 struct FontsSettings {
@@ -168,12 +418,18 @@ struct FontsSettings {
     font_paths: Vec<PathBuf>,
     /// Whether to include font variants
     variants: bool,
+    /// The cache on disk that is shared across invocations.
+    disk_cache: Option<DiskCache>,
 }
 
 impl FontsSettings {
     /// Create font settings from the field values.
-    fn new(font_paths: Vec<PathBuf>, variants: bool) -> Self {
-        Self { font_paths, variants }
+    fn new(
+        font_paths: Vec<PathBuf>,
+        variants: bool,
+        disk_cache: Option<DiskCache>,
+    ) -> Self {
+        Self { font_paths, variants, disk_cache }
     }
 
     /// Create a new font settings from the CLI arguments.
@@ -181,17 +437,51 @@ impl FontsSettings {
     /// # Panics
     /// Panics if the command is not a fonts command.
     fn with_arguments(args: CliArguments) -> Self {
+        let disk_cache = DiskCache::with_arguments(&args);
         match args.command {
-            Command::Fonts(command) => Self::new(args.font_paths, command.variants),
+            Command::Fonts(command) => {
+                Self::new(args.font_paths, command.variants, disk_cache)
+            }
             _ => unreachable!(),
         }
     }
 }
 
 /// Execute a compilation command.
-fn compile(mut command: CompileSettings) -> StrResult<()> {
+///
+/// Several targets are compiled one after another in the same world, so that
+/// they share fonts, files and cached results.
+fn compile(mut commands: Vec<CompileSettings>) -> StrResult<()> {
+    for command in &commands {
+        if command.watch && (is_stdio(&command.input) || is_stdio(&command.output)) {
+            bail!("cannot watch when reading from stdin or writing to stdout");
+        }
+
+        if command.open.is_some() && is_stdio(&command.output) {
+            bail!("cannot open output that is written to stdout");
+        }
+
+        if command.watch && command.bench.is_some() {
+            bail!("cannot benchmark in watch mode");
+        }
+    }
+
+    // All targets compile the same input with the same root and fonts.
+    let first = &commands[0];
+
+    // Read the input once if it comes from stdin.
+    let stdin = if is_stdio(&first.input) {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|_| "failed to read from stdin")?;
+        Some(text)
+    } else {
+        None
+    };
+
     // Determine the parent directory of the input file.
-    let parent = command
+    let parent = first
         .input
         .canonicalize()
         .ok()
@@ -200,23 +490,31 @@ fn compile(mut command: CompileSettings) -> StrResult<()> {
         .unwrap_or(Path::new("."))
         .to_owned();
 
-    let root = command.root.as_ref().unwrap_or(&parent);
+    let root = first.root.as_ref().unwrap_or(&parent);
 
     // Create the world that serves sources, fonts and files.
-    let mut world = SystemWorld::new(root.into(), &command.font_paths);
+    let mut world = SystemWorld::new(
+        root.into(),
+        &first.font_paths,
+        first.disk_cache.as_ref(),
+        first.offline,
+    );
+    world.placeholders = first.placeholders;
+
+    // Build the standard library of each target up front, so that targets
+    // with the same inputs keep sharing cached results across recompilations.
+    let libraries: Vec<_> =
+        commands.iter().map(|command| library(&world, command)).collect();
+
+    if let Some(runs) = first.bench {
+        configure(&mut world, first, &libraries[0]);
+        return crate::bench::bench(&mut world, first, stdin.as_deref(), runs);
+    }
 
     // Perform initial compilation.
-    let ok = compile_once(&mut world, &command)?;
-
-    // Open the file if requested, this must be done on the first **successful**
-    // compilation.
-    if ok {
-        if let Some(open) = command.open.take() {
-            open_file(open.as_deref(), &command.output)?;
-        }
-    }
+    compile_targets(&mut world, &mut commands, &libraries, stdin.as_deref())?;
 
-    if !command.watch {
+    if !commands[0].watch {
         return Ok(());
     }
 
@@ -247,11 +545,11 @@ fn compile(mut command: CompileSettings) -> StrResult<()> {
             .chain(std::iter::from_fn(|| rx.recv_timeout(timeout).ok()))
         {
             let event = event.map_err(|_| "failed to watch directory")?;
-            if event
-                .paths
-                .iter()
-                .all(|path| is_same_file(path, &command.output).unwrap_or(false))
-            {
+            if event.paths.iter().all(|path| {
+                commands
+                    .iter()
+                    .any(|command| is_same_file(path, &command.output).unwrap_or(false))
+            }) {
                 continue;
             }
 
@@ -259,36 +557,124 @@ fn compile(mut command: CompileSettings) -> StrResult<()> {
         }
 
         if recompile {
-            let ok = compile_once(&mut world, &command)?;
-            comemo::evict(30);
-
-            // Ipen the file if requested, this must be done on the first
-            // **successful** compilation
-            if ok {
-                if let Some(open) = command.open.take() {
-                    open_file(open.as_deref(), &command.output)?;
-                }
+            compile_targets(&mut world, &mut commands, &libraries, None)?;
+            comemo::evict(commands[0].cache_max_age);
+        }
+    }
+}
+
+/// Build the standard library for a target.
+fn library(world: &SystemWorld, command: &CompileSettings) -> Prehashed<Library> {
+    if command.features.is_empty() && command.inputs.is_empty() {
+        return world.library.clone();
+    }
+
+    let mut library = typst_library::build_with_inputs(command.inputs.clone());
+    library.features = command
+        .features
+        .iter()
+        .map(|feature| match feature {
+            Feature::NestedDestructuring => typst::eval::Feature::NestedDestructuring,
+        })
+        .collect();
+    Prehashed::new(library)
+}
+
+/// Prepare the world for compiling a target.
+fn configure(
+    world: &mut SystemWorld,
+    command: &CompileSettings,
+    library: &Prehashed<Library>,
+) {
+    world.library = library.clone();
+    world.target = match command.format {
+        OutputFormat::Pdf => Target::Pdf,
+        OutputFormat::Png => Target::Png,
+        OutputFormat::Html => Target::Html,
+    };
+}
+
+/// Compile each target a single time.
+fn compile_targets(
+    world: &mut SystemWorld,
+    commands: &mut [CompileSettings],
+    libraries: &[Prehashed<Library>],
+    stdin: Option<&str>,
+) -> StrResult<()> {
+    for (command, library) in commands.iter_mut().zip(libraries) {
+        configure(world, command, library);
+        let ok = compile_once(world, command, stdin)?;
+
+        // Open the file if requested, this must be done on the first
+        // **successful** compilation.
+        if ok {
+            if let Some(open) = command.open.take() {
+                open_file(open.as_deref(), &command.output)?;
             }
         }
     }
+    Ok(())
 }
 
 /// Compile a single time.
 ///
 /// Returns whether it compiled without errors.
 #[tracing::instrument(skip_all)]
-fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult<bool> {
+fn compile_once(
+    world: &mut SystemWorld,
+    command: &CompileSettings,
+    stdin: Option<&str>,
+) -> StrResult<bool> {
     tracing::info!("Starting compilation");
 
     status(command, Status::Compiling).unwrap();
 
     world.reset();
-    world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
+    world.main = match stdin {
+        Some(text) => world.insert(Path::new(STDIN_PATH), text.into()),
+        None => world.resolve(&command.input).map_err(|err| err.to_string())?,
+    };
 
-    match typst::compile(world) {
+    let result = typst::compile(world);
+    tracing::info!(bytes = world.memory_usage(), "Memory used by loaded files");
+    for stats in typst::util::lru::stats() {
+        tracing::info!(
+            cache = stats.name,
+            entries = stats.entries,
+            bytes = stats.size,
+            limit = stats.limit,
+            hits = stats.hits,
+            misses = stats.misses,
+            "Memory used by cache"
+        );
+    }
+
+    match result {
         // Export the PDF / PNG.
-        Ok(document) => {
+        Ok(mut document) => {
+            if command.trace_breaks {
+                eprint!("{}", break_trace(&document));
+            }
+            if command.debug_frames {
+                document.pages.iter_mut().for_each(Frame::debug_outlines);
+            }
             export(&document, command)?;
+            if command.placeholders {
+                for path in world.failed_files() {
+                    print_warning(&format!(
+                        "failed to load {}, showing a placeholder",
+                        path.display()
+                    ))
+                    .expect("failed to print warning");
+                }
+            }
+            let warnings =
+                if command.lint { typst::lint(world) } else { typst::warnings(world) };
+            print_warnings(world, warnings, command.diagnostic_format)
+                .map_err(|_| "failed to print warnings")?;
+            if let Some(path) = &command.make_deps {
+                write_make_deps(world, &command.output, path)?;
+            }
             status(command, Status::Success).unwrap();
             tracing::info!("Compilation succeeded");
             Ok(true)
@@ -306,14 +692,43 @@ fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult
     }
 }
 
+/// Load the color profile to use in PDF export.
+fn color_profile(command: &CompileSettings) -> StrResult<Option<ColorProfile>> {
+    let Some(name) = &command.color_profile else { return Ok(None) };
+    let mut profile = match name.as_str() {
+        "srgb" => ColorProfile::new(ProfileSpace::Srgb),
+        "cmyk" => ColorProfile::new(ProfileSpace::Cmyk),
+        path => {
+            let data = fs::read(path).map_err(|_| "failed to read color profile")?;
+            ColorProfile::from_icc(data.into())?
+        }
+    };
+
+    profile.intent = match command.rendering_intent {
+        RenderingIntent::Perceptual => typst::export::RenderingIntent::Perceptual,
+        RenderingIntent::RelativeColorimetric => {
+            typst::export::RenderingIntent::RelativeColorimetric
+        }
+        RenderingIntent::Saturation => typst::export::RenderingIntent::Saturation,
+        RenderingIntent::AbsoluteColorimetric => {
+            typst::export::RenderingIntent::AbsoluteColorimetric
+        }
+    };
+
+    Ok(Some(profile))
+}
+
 /// Export into the target format.
 fn export(document: &Document, command: &CompileSettings) -> StrResult<()> {
-    match command.output.extension() {
-        Some(ext) if ext.eq_ignore_ascii_case("png") => {
+    let stdout = is_stdio(&command.output);
+    match command.format {
+        OutputFormat::Png => {
             // Determine whether we have a `{n}` numbering.
             let string = command.output.to_str().unwrap_or_default();
             let numbered = string.contains("{n}");
-            if !numbered && document.pages.len() > 1 {
+            if stdout && document.pages.len() > 1 {
+                bail!("cannot write multiple PNGs to stdout");
+            } else if !numbered && document.pages.len() > 1 {
                 bail!("cannot export multiple PNGs without `{{n}}` in output path");
             }
 
@@ -326,6 +741,13 @@ fn export(document: &Document, command: &CompileSettings) -> StrResult<()> {
 
             for (i, frame) in document.pages.iter().enumerate() {
                 let pixmap = typst::export::render(frame, ppi, Color::WHITE);
+                if stdout {
+                    let buffer =
+                        pixmap.encode_png().map_err(|_| "failed to encode PNG file")?;
+                    write_stdout(&buffer)?;
+                    continue;
+                }
+
                 let path = if numbered {
                     storage = string.replace("{n}", &format!("{:0width$}", i + 1));
                     Path::new(&storage)
@@ -335,18 +757,82 @@ fn export(document: &Document, command: &CompileSettings) -> StrResult<()> {
                 pixmap.save_png(path).map_err(|_| "failed to write PNG file")?;
             }
         }
-        _ => {
-            let buffer = typst::export::pdf(document);
-            fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
+        OutputFormat::Pdf => {
+            let options = PdfOptions {
+                max_dpi: command.max_dpi,
+                color_profile: color_profile(command)?,
+                ..PdfOptions::default()
+            };
+            let mut exporter = PdfExporter::new(&options);
+            for frame in &document.pages {
+                exporter.push(frame);
+            }
+            let buffer = exporter.finish(document)?;
+            if stdout {
+                write_stdout(&buffer)?;
+            } else {
+                fs::write(&command.output, buffer)
+                    .map_err(|_| "failed to write PDF file")?;
+            }
+        }
+        OutputFormat::Html => {
+            let buffer = typst::export::html(document);
+            if stdout {
+                write_stdout(buffer.as_bytes())?;
+            } else {
+                fs::write(&command.output, buffer)
+                    .map_err(|_| "failed to write HTML file")?;
+            }
         }
     }
     Ok(())
 }
 
+/// Write the files the last compilation read into a dependency file for
+/// Make and Ninja, with the output as the target.
+fn write_make_deps(world: &SystemWorld, output: &Path, path: &Path) -> StrResult<()> {
+    fn escape(path: &Path) -> String {
+        path.to_string_lossy()
+            .replace('$', "$$")
+            .replace('#', "\\#")
+            .replace(' ', "\\ ")
+    }
+
+    let mut deps = escape(output);
+    deps.push(':');
+    for dependency in world.dependencies() {
+        deps.push_str(" \\\n  ");
+        deps.push_str(&escape(&dependency));
+    }
+    deps.push('\n');
+
+    fs::write(path, deps).map_err(|_| "failed to write dependency file".into())
+}
+
+/// The path that sources read from stdin are known by in diagnostics.
+const STDIN_PATH: &str = "<stdin>";
+
+/// Whether a path stands for stdin or stdout.
+fn is_stdio(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Write exported data to stdout.
+fn write_stdout(data: &[u8]) -> StrResult<()> {
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(data)
+        .and_then(|_| stdout.flush())
+        .map_err(|_| "failed to write to stdout".into())
+}
+
 /// Clear the terminal and render the status message.
+///
+/// The status of later targets is instead rendered below the first one's
+/// once they are compiled.
 #[tracing::instrument(skip_all)]
 fn status(command: &CompileSettings, status: Status) -> io::Result<()> {
-    if !command.watch {
+    if !command.watch || (!command.first_target && matches!(status, Status::Compiling)) {
         return Ok(());
     }
 
@@ -359,7 +845,7 @@ fn status(command: &CompileSettings, status: Status) -> io::Result<()> {
     let color = status.color();
 
     let mut w = color_stream();
-    if std::io::stderr().is_terminal() {
+    if command.first_target && std::io::stderr().is_terminal() {
         // Clear the terminal.
         write!(w, "{esc}c{esc}[1;1H")?;
     }
@@ -457,6 +943,84 @@ fn print_diagnostics(
     Ok(())
 }
 
+/// Describe why lines, columns and pages broke, one break per line in the
+/// order of the pages.
+fn break_trace(document: &Document) -> String {
+    let mut trace = String::new();
+    for (i, page) in document.pages.iter().enumerate() {
+        write_breaks(&mut trace, i + 1, page);
+    }
+    trace
+}
+
+/// Describe the breaks explained in a frame and its groups.
+fn write_breaks(trace: &mut String, page: usize, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => write_breaks(trace, page, &group.frame),
+            FrameItem::Meta(Meta::Break(explanation), _) => {
+                trace.push_str(&format!("page {page}: {}", explanation.kind.name()));
+                if let Some(text) = &explanation.text {
+                    trace.push_str(&format!(" {:?}", excerpt(text)));
+                }
+                trace.push_str(&format!(": {}", explanation.reason));
+                if let Some((needed, available)) = explanation.space {
+                    trace.push_str(&format!(
+                        " (needed {needed:?}, available {available:?})"
+                    ));
+                }
+                if let Some((Scalar(cost), Scalar(ratio))) = explanation.cost {
+                    trace.push_str(&format!(" (cost {cost:.2}, ratio {ratio:.2})"));
+                }
+                trace.push('\n');
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Shorten a text for the break trace.
+fn excerpt(text: &str) -> String {
+    const MAX: usize = 40;
+    if text.chars().count() <= MAX {
+        return text.into();
+    }
+    let mut short: String = text.chars().take(MAX - 1).collect();
+    short.push('…');
+    short
+}
+
+/// Print warnings with their source locations.
+fn print_warnings(
+    world: &SystemWorld,
+    warnings: Vec<SourceWarning>,
+    diagnostic_format: DiagnosticFormat,
+) -> Result<(), codespan_reporting::files::Error> {
+    let mut w = match diagnostic_format {
+        DiagnosticFormat::Human => color_stream(),
+        DiagnosticFormat::Short => StandardStream::stderr(ColorChoice::Never),
+    };
+
+    let mut config = term::Config { tab_width: 2, ..Default::default() };
+    if diagnostic_format == DiagnosticFormat::Short {
+        config.display_style = term::DisplayStyle::Short;
+    }
+
+    for warning in warnings {
+        let range = warning.range(world);
+        let diag = Diagnostic::warning()
+            .with_message(warning.message)
+            .with_labels(vec![Label::primary(warning.span.source(), range)])
+            .with_notes(
+                warning.hints.iter().map(|hint| format!("hint: {hint}")).collect(),
+            );
+
+        term::emit(&mut w, &config, world, &diag)?;
+    }
+
+    Ok(())
+}
+
 /// Opens the given file using:
 /// - The default file viewer if `open` is `None`.
 /// - The given viewer provided by `open` if it is `Some`.
@@ -472,7 +1036,7 @@ fn open_file(open: Option<&str>, path: &Path) -> StrResult<()> {
 
 /// Execute a font listing command.
 fn fonts(command: FontsSettings) -> StrResult<()> {
-    let mut searcher = FontSearcher::new();
+    let mut searcher = FontSearcher::new(command.disk_cache.as_ref());
     searcher.search(&command.font_paths);
 
     for (name, infos) in searcher.book.families() {
@@ -488,6 +1052,397 @@ fn fonts(command: FontsSettings) -> StrResult<()> {
     Ok(())
 }
 
+struct DiffSettings {
+    /// The path to the old version of the input file.
+    old: PathBuf,
+    /// The path to the new version of the input file.
+    new: PathBuf,
+    /// The path to write a visual diff to.
+    output: Option<PathBuf>,
+    /// The root directory for absolute paths.
+    root: Option<PathBuf>,
+    /// The paths to search for fonts.
+    font_paths: Vec<PathBuf>,
+    /// The cache on disk that is shared across invocations.
+    disk_cache: Option<DiskCache>,
+    /// Whether network access is forbidden.
+    offline: bool,
+}
+
+impl DiffSettings {
+    /// Create a new diff settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a diff command.
+    fn with_arguments(args: CliArguments) -> Self {
+        let disk_cache = DiskCache::with_arguments(&args);
+        match args.command {
+            Command::Diff(DiffCommand { old, new, output }) => Self {
+                old,
+                new,
+                output,
+                root: args.root,
+                font_paths: args.font_paths,
+                disk_cache,
+                offline: args.offline,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Execute a diff command.
+fn diff(command: DiffSettings) -> StrResult<()> {
+    let parent = command
+        .new
+        .canonicalize()
+        .ok()
+        .as_ref()
+        .and_then(|path| path.parent())
+        .unwrap_or(Path::new("."))
+        .to_owned();
+
+    let root = command.root.as_ref().unwrap_or(&parent);
+    let mut world = SystemWorld::new(
+        root.into(),
+        &command.font_paths,
+        command.disk_cache.as_ref(),
+        command.offline,
+    );
+
+    let Some(old) = compile_document(&mut world, &command.old)? else {
+        return Ok(());
+    };
+    let Some(new) = compile_document(&mut world, &command.new)? else {
+        return Ok(());
+    };
+
+    let diff = typst::ide::diff_lines(&old, &new);
+    for change in &diff {
+        match change {
+            LineDiff::Same(..) => {}
+            LineDiff::Removed(line) => println!("- [{}] {}", line.page, line.text),
+            LineDiff::Added(line) => println!("+ [{}] {}", line.page, line.text),
+        }
+    }
+
+    let pages = typst::ide::changed_pages(&diff);
+    if pages.is_empty() {
+        println!("no changes");
+    } else {
+        let list: Vec<_> = pages.iter().map(ToString::to_string).collect();
+        println!("changed pages: {}", list.join(", "));
+    }
+
+    if let Some(output) = &command.output {
+        let document = typst::ide::render_diff(&old, &new, &diff);
+        fs::write(output, typst::export::pdf(&document)?)
+            .map_err(|_| "failed to write PDF file")?;
+    }
+
+    Ok(())
+}
+
+/// Compile a file into a document, printing diagnostics on failure.
+fn compile_document(
+    world: &mut SystemWorld,
+    input: &Path,
+) -> StrResult<Option<Document>> {
+    world.reset();
+    world.main = world.resolve(input).map_err(|err| err.to_string())?;
+    match typst::compile(world) {
+        Ok(document) => Ok(Some(document)),
+        Err(errors) => {
+            set_failed();
+            print_diagnostics(world, *errors, DiagnosticFormat::Human)
+                .map_err(|_| "failed to print diagnostics")?;
+            Ok(None)
+        }
+    }
+}
+
+struct ResourcesSettings {
+    /// The path to the input file.
+    input: PathBuf,
+    /// The root directory for absolute paths.
+    root: Option<PathBuf>,
+    /// The paths to search for fonts.
+    font_paths: Vec<PathBuf>,
+    /// The cache on disk that is shared across invocations.
+    disk_cache: Option<DiskCache>,
+    /// Whether network access is forbidden.
+    offline: bool,
+}
+
+impl ResourcesSettings {
+    /// Create new resources settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a resources command.
+    fn with_arguments(args: CliArguments) -> Self {
+        let disk_cache = DiskCache::with_arguments(&args);
+        match args.command {
+            Command::Resources(ResourcesCommand { input }) => Self {
+                input,
+                root: args.root,
+                font_paths: args.font_paths,
+                disk_cache,
+                offline: args.offline,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Execute a resources command.
+fn resources(command: ResourcesSettings) -> StrResult<()> {
+    let parent = command
+        .input
+        .canonicalize()
+        .ok()
+        .as_ref()
+        .and_then(|path| path.parent())
+        .unwrap_or(Path::new("."))
+        .to_owned();
+
+    let root = command.root.as_ref().unwrap_or(&parent);
+    let mut world = SystemWorld::new(
+        root.into(),
+        &command.font_paths,
+        command.disk_cache.as_ref(),
+        command.offline,
+    );
+    world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
+
+    let families = match typst::ide::font_families(&world) {
+        Ok(families) => families,
+        Err(errors) => {
+            set_failed();
+            print_diagnostics(&world, *errors, DiagnosticFormat::Human)
+                .map_err(|_| "failed to print diagnostics")?;
+            return Ok(());
+        }
+    };
+
+    let fonts: Vec<_> = families
+        .iter()
+        .map(|family| {
+            let available =
+                world.book.select_family(&family.to_lowercase()).next().is_some();
+            serde_json::json!({ "family": family.as_str(), "available": available })
+        })
+        .collect();
+
+    let files: Vec<_> = world
+        .dependencies()
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+
+    let json = serde_json::json!({ "fonts": fonts, "files": files });
+    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+    Ok(())
+}
+
+struct WordsSettings {
+    /// The path to the input file.
+    input: PathBuf,
+    /// The root directory for absolute paths.
+    root: Option<PathBuf>,
+    /// The paths to search for fonts.
+    font_paths: Vec<PathBuf>,
+    /// The cache on disk that is shared across invocations.
+    disk_cache: Option<DiskCache>,
+    /// Whether network access is forbidden.
+    offline: bool,
+    /// The directory with the dictionaries to check the spelling with.
+    dictionaries: Option<PathBuf>,
+}
+
+impl WordsSettings {
+    /// Create new words settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a words command.
+    fn with_arguments(args: CliArguments) -> Self {
+        let disk_cache = DiskCache::with_arguments(&args);
+        match args.command {
+            Command::Words(WordsCommand { input, dictionaries }) => Self {
+                input,
+                root: args.root,
+                font_paths: args.font_paths,
+                disk_cache,
+                offline: args.offline,
+                dictionaries,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Execute a words command.
+fn words(command: WordsSettings) -> StrResult<()> {
+    let parent = command
+        .input
+        .canonicalize()
+        .ok()
+        .as_ref()
+        .and_then(|path| path.parent())
+        .unwrap_or(Path::new("."))
+        .to_owned();
+
+    let root = command.root.as_ref().unwrap_or(&parent);
+    let mut world = SystemWorld::new(
+        root.into(),
+        &command.font_paths,
+        command.disk_cache.as_ref(),
+        command.offline,
+    );
+    let Some(document) = compile_document(&mut world, &command.input)? else {
+        return Ok(());
+    };
+
+    let words = typst::ide::words(&world, &document);
+    let spelling = match &command.dictionaries {
+        Some(dir) => Some(misspelled(dir, &words)?),
+        None => None,
+    };
+
+    let words: Vec<_> = words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let location = word.source.as_ref().map(|(id, range)| {
+                let source = world.source(*id);
+                serde_json::json!({
+                    "path": source.path().to_string_lossy(),
+                    "line": source.byte_to_line(range.start).map(|line| line + 1),
+                    "column": source.byte_to_column(range.start).map(|col| col + 1),
+                    "start": range.start,
+                    "end": range.end,
+                })
+            });
+            let mut json = serde_json::json!({
+                "text": word.text.as_str(),
+                "lang": word.lang.as_str(),
+                "region": word.region.as_ref().map(|region| region.as_str()),
+                "page": word.page.get(),
+                "source": location,
+            });
+            if let Some(spelling) = &spelling {
+                json["misspelled"] = spelling[i].into();
+            }
+            json
+        })
+        .collect();
+
+    println!("{}", serde_json::to_string_pretty(&words).unwrap());
+    Ok(())
+}
+
+/// Determine which words are misspelled according to the dictionaries in a
+/// directory.
+#[cfg(feature = "hunspell")]
+fn misspelled(dir: &Path, words: &[typst::ide::Word]) -> StrResult<Vec<bool>> {
+    let mut checker = crate::spell::SpellChecker::new(dir)?;
+    Ok(words.iter().map(|word| checker.misspelled(word)).collect())
+}
+
+/// Determine which words are misspelled according to the dictionaries in a
+/// directory.
+#[cfg(not(feature = "hunspell"))]
+fn misspelled(_: &Path, _: &[typst::ide::Word]) -> StrResult<Vec<bool>> {
+    bail!("spell checking requires typst to be built with the `hunspell` feature")
+}
+
+struct SnapshotSettings {
+    /// The paths to the input files.
+    inputs: Vec<PathBuf>,
+    /// Whether to write the snapshots instead of comparing with them.
+    bless: bool,
+    /// The root directory for absolute paths.
+    root: Option<PathBuf>,
+    /// The paths to search for fonts.
+    font_paths: Vec<PathBuf>,
+    /// The cache on disk that is shared across invocations.
+    disk_cache: Option<DiskCache>,
+    /// Whether network access is forbidden.
+    offline: bool,
+}
+
+impl SnapshotSettings {
+    /// Create new snapshot settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a snapshot command.
+    fn with_arguments(args: CliArguments) -> Self {
+        let disk_cache = DiskCache::with_arguments(&args);
+        match args.command {
+            Command::Snapshot(SnapshotCommand { inputs, bless }) => Self {
+                inputs,
+                bless,
+                root: args.root,
+                font_paths: args.font_paths,
+                disk_cache,
+                offline: args.offline,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Execute a snapshot command.
+fn snapshot(command: SnapshotSettings) -> StrResult<()> {
+    let root = command.root.clone().unwrap_or_else(|| PathBuf::from("."));
+    let mut world = SystemWorld::new(
+        root,
+        &command.font_paths,
+        command.disk_cache.as_ref(),
+        command.offline,
+    );
+
+    for input in &command.inputs {
+        world.reset();
+        world.main = world.resolve(input).map_err(|err| err.to_string())?;
+        let snapshot = typst::ide::snapshot(&world);
+
+        let mut mismatches = vec![];
+        for (kind, text) in [("ast", snapshot.ast), ("eval", snapshot.eval)] {
+            let path = input.with_extension(kind);
+            if command.bless {
+                fs::write(&path, text).map_err(|_| "failed to write snapshot")?;
+                continue;
+            }
+
+            match fs::read_to_string(&path) {
+                Ok(expected) if expected == text => {}
+                Ok(expected) => {
+                    let line = expected
+                        .lines()
+                        .zip(text.lines())
+                        .position(|(a, b)| a != b)
+                        .unwrap_or_else(|| {
+                            expected.lines().count().min(text.lines().count())
+                        });
+                    mismatches.push(format!("{kind} differs from line {}", line + 1));
+                }
+                Err(_) => mismatches.push(format!("{kind} snapshot is missing")),
+            }
+        }
+
+        if command.bless {
+            println!("{}: updated snapshots", input.display());
+        } else if mismatches.is_empty() {
+            println!("{}: ok", input.display());
+        } else {
+            set_failed();
+            println!("{}: {}", input.display(), mismatches.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
 /// A world that provides access to the operating system.
 struct SystemWorld {
     root: PathBuf,
@@ -499,6 +1454,17 @@ struct SystemWorld {
     sources: FrozenVec<Box<Source>>,
     today: Cell<Option<Datetime>>,
     main: SourceId,
+    placeholders: bool,
+    /// The format the document is exported into.
+    target: Target,
+    /// Serves files from URLs.
+    network: Network,
+    /// The files downloaded so far, which are kept across resets since the
+    /// lockfile pins their contents anyway.
+    downloads: RefCell<HashMap<String, Buffer>>,
+    /// The downloads that failed during this compilation, which are retried
+    /// after a reset.
+    failed_downloads: RefCell<HashMap<String, FileError>>,
 }
 
 /// Holds details about the location of a font and lazily the font itself.
@@ -516,11 +1482,23 @@ struct PathSlot {
 }
 
 impl SystemWorld {
-    fn new(root: PathBuf, font_paths: &[PathBuf]) -> Self {
-        let mut searcher = FontSearcher::new();
+    fn new(
+        root: PathBuf,
+        font_paths: &[PathBuf],
+        disk_cache: Option<&DiskCache>,
+        offline: bool,
+    ) -> Self {
+        let mut searcher = FontSearcher::new(disk_cache);
         searcher.search(font_paths);
+        if let Some(disk_cache) = disk_cache {
+            typst::image::set_raster_store(Box::new(disk_cache.clone()));
+        }
 
+        let lockfile = root.join(LOCKFILE_NAME);
         Self {
+            network: Network::new(Box::new(Http), lockfile, disk_cache.cloned(), offline),
+            downloads: RefCell::default(),
+            failed_downloads: RefCell::default(),
             root,
             library: Prehashed::new(typst_library::build()),
             book: Prehashed::new(searcher.book),
@@ -530,6 +1508,8 @@ impl SystemWorld {
             sources: FrozenVec::new(),
             today: Cell::new(None),
             main: SourceId::detached(),
+            placeholders: false,
+            target: Target::Pdf,
         }
     }
 }
@@ -549,20 +1529,22 @@ impl World for SystemWorld {
 
     #[tracing::instrument(skip_all)]
     fn resolve(&self, path: &Path) -> FileResult<SourceId> {
-        self.slot(path)?
+        let mut fresh = false;
+        let id = self
+            .slot(path)?
             .source
             .get_or_init(|| {
-                let buf = read(path)?;
-                let text = if buf.starts_with(b"\xef\xbb\xbf") {
-                    // remove UTF-8 BOM
-                    std::str::from_utf8(&buf[3..])?.to_owned()
-                } else {
-                    // Assume UTF-8
-                    String::from_utf8(buf)?
-                };
+                fresh = true;
+                let text = decode(read(path)?)?;
                 Ok(self.insert(path, text))
             })
-            .clone()
+            .clone()?;
+
+        if fresh {
+            self.prefetch(id);
+        }
+
+        Ok(id)
     }
 
     fn source(&self, id: SourceId) -> &Source {
@@ -575,12 +1557,23 @@ impl World for SystemWorld {
 
     fn font(&self, id: usize) -> Option<Font> {
         let slot = &self.fonts[id];
-        slot.font
+        let font = slot
+            .font
             .get_or_init(|| {
                 let data = self.file(&slot.path).ok()?;
                 Font::new(data, slot.index)
             })
-            .clone()
+            .clone()?;
+
+        // A font that was loaded before the last reset has to show up in the
+        // dependencies of this compilation, too.
+        if !slot.path.as_os_str().is_empty() {
+            if let Ok(file) = self.slot(&slot.path) {
+                file.buffer.get_or_init(|| Ok(font.data().clone()));
+            }
+        }
+
+        Some(font)
     }
 
     fn file(&self, path: &Path) -> FileResult<Buffer> {
@@ -590,6 +1583,26 @@ impl World for SystemWorld {
             .clone()
     }
 
+    fn fetch(&self, url: &str) -> FileResult<Buffer> {
+        if let Some(buffer) = self.downloads.borrow().get(url) {
+            return Ok(buffer.clone());
+        }
+        if let Some(error) = self.failed_downloads.borrow().get(url) {
+            return Err(error.clone());
+        }
+
+        match self.network.fetch(url) {
+            Ok(buffer) => {
+                self.downloads.borrow_mut().insert(url.into(), buffer.clone());
+                Ok(buffer)
+            }
+            Err(error) => {
+                self.failed_downloads.borrow_mut().insert(url.into(), error.clone());
+                Err(error)
+            }
+        }
+    }
+
     fn today(&self, offset: Option<i64>) -> Option<Datetime> {
         if self.today.get().is_none() {
             let datetime = match offset {
@@ -606,6 +1619,14 @@ impl World for SystemWorld {
 
         self.today.get()
     }
+
+    fn placeholders(&self) -> bool {
+        self.placeholders
+    }
+
+    fn target(&self) -> Target {
+        self.target
+    }
 }
 
 impl SystemWorld {
@@ -637,6 +1658,71 @@ impl SystemWorld {
         id
     }
 
+    /// Load the modules that are statically imported or included by a source
+    /// file (and recursively by those modules) on multiple threads.
+    ///
+    /// Evaluation would otherwise read and parse them one after another as it
+    /// encounters the imports. The modules are still evaluated one after
+    /// another, since the memoization caches are per thread and the world
+    /// isn't shared between threads. Only files inside the project root are
+    /// loaded ahead of time. Files that fail to load are left to evaluation,
+    /// which reports the error and tries again after the next reset.
+    #[tracing::instrument(skip_all)]
+    fn prefetch(&self, id: SourceId) {
+        let mut queue = vec![id];
+        while !queue.is_empty() {
+            // Find the imported files that aren't loaded yet, deduplicated by
+            // the entity they point to.
+            let mut pending: Vec<(PathBuf, PathHash)> = vec![];
+            for id in queue.drain(..) {
+                for path in static_imports(self, self.source(id)) {
+                    if !path.starts_with(&self.root) {
+                        continue;
+                    }
+                    let Ok(slot) = self.slot(&path) else { continue };
+                    if slot.source.get().is_some() {
+                        continue;
+                    }
+                    drop(slot);
+                    let Some(Ok(hash)) = self.hashes.borrow().get(&path).cloned() else {
+                        continue;
+                    };
+                    if pending.iter().all(|(_, other)| *other != hash) {
+                        pending.push((path, hash));
+                    }
+                }
+            }
+
+            // Read the files in parallel.
+            let paths: Vec<&PathBuf> = pending.iter().map(|(path, _)| path).collect();
+            let texts = parallel_map(paths, |path| decode(read(path)?));
+
+            // Assign ids in order and parse the successfully read files in
+            // parallel.
+            let base = self.sources.len();
+            let loaded: Vec<_> = pending
+                .iter()
+                .zip(texts)
+                .filter_map(|((path, _), text)| Some((path, text.ok()?)))
+                .enumerate()
+                .map(|(i, (path, text))| {
+                    (path, SourceId::from_u16((base + i) as u16), text)
+                })
+                .collect();
+
+            let sources = parallel_map(loaded, |(path, id, text)| {
+                (path, Source::new(id, path, text))
+            });
+
+            for (path, source) in sources {
+                let id = source.id();
+                let _ = self.slot(path).map(|slot| slot.source.set(Ok(id)));
+                self.sources.push(Box::new(source));
+                queue.push(id);
+            }
+        }
+    }
+
     fn relevant(&mut self, event: &notify::Event) -> bool {
         match &event.kind {
             notify::EventKind::Any => {}
@@ -662,15 +1748,105 @@ impl SystemWorld {
                 .map_or(false, |hash| self.paths.borrow().contains_key(&hash))
     }
 
+    /// The approximate number of bytes taken up by loaded files and sources.
+    fn memory_usage(&self) -> usize {
+        self.paths
+            .borrow()
+            .values()
+            .map(|slot| {
+                let buffer = slot.buffer.get().and_then(|b| b.as_ref().ok());
+                let source = slot.source.get().and_then(|id| id.as_ref().ok());
+                buffer.map_or(0, |buffer| buffer.len())
+                    + source.map_or(0, |&id| self.source(id).text().len())
+            })
+            .sum()
+    }
+
+    /// The paths of all files that were successfully loaded since the last
+    /// reset, one for each entity and in sorted order.
+    fn dependencies(&self) -> Vec<PathBuf> {
+        let paths = self.paths.borrow();
+        let mut found: HashMap<PathHash, PathBuf> = HashMap::new();
+        for (path, hash) in self.hashes.borrow().iter() {
+            let Ok(hash) = hash else { continue };
+            let Some(slot) = paths.get(hash) else { continue };
+            let loaded = matches!(slot.buffer.get(), Some(Ok(_)))
+                || matches!(slot.source.get(), Some(Ok(_)));
+            if !loaded {
+                continue;
+            }
+
+            // Prefer the canonical path, which is absolute.
+            match found.get(hash) {
+                Some(existing) if existing.is_absolute() || !path.is_absolute() => {}
+                _ => {
+                    found.insert(*hash, path.clone());
+                }
+            }
+        }
+
+        let mut deps: Vec<_> = found.into_values().collect();
+        deps.sort();
+        deps
+    }
+
+    /// The paths of all files that failed to load since the last reset, in
+    /// sorted order.
+    fn failed_files(&self) -> Vec<PathBuf> {
+        let paths = self.paths.borrow();
+        let mut failed: Vec<_> = self
+            .hashes
+            .borrow()
+            .iter()
+            .filter(|(_, hash)| match hash {
+                Ok(hash) => matches!(
+                    paths.get(hash).and_then(|slot| slot.buffer.get()),
+                    Some(Err(_))
+                ),
+                Err(_) => true,
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        failed.sort();
+        failed.dedup();
+        failed
+    }
+
     #[tracing::instrument(skip_all)]
     fn reset(&mut self) {
         self.sources.as_mut().clear();
         self.hashes.borrow_mut().clear();
         self.paths.borrow_mut().clear();
+        self.failed_downloads.borrow_mut().clear();
         self.today.set(None);
     }
 }
 
+/// Hash the table directories of a font file or collection, together with the
+/// file's size, into a key for the disk cache.
+fn font_key(data: &[u8]) -> Option<u128> {
+    let u32_at =
+        |i: usize| data.get(i..i + 4).map(|b| u32::from_be_bytes(b.try_into().unwrap()));
+    let offsets: Vec<usize> = if data.starts_with(b"ttcf") {
+        (0..u32_at(8)? as usize)
+            .map(|i| u32_at(12 + 4 * i).map(|offset| offset as usize))
+            .collect::<Option<_>>()?
+    } else {
+        vec![0]
+    };
+
+    let directories = offsets
+        .into_iter()
+        .map(|offset| {
+            let count = data.get(offset + 4..offset + 6)?;
+            let count = u16::from_be_bytes([count[0], count[1]]) as usize;
+            data.get(offset..offset + 12 + 16 * count)
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(cache::key((data.len(), directories)))
+}
+
 /// A hash that is the same for all paths pointing to the same entity.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 struct PathHash(u128);
@@ -685,6 +1861,89 @@ impl PathHash {
     }
 }
 
+/// Decode the contents of a source file.
+fn decode(buf: Vec<u8>) -> FileResult<String> {
+    Ok(if buf.starts_with(b"\xef\xbb\xbf") {
+        // remove UTF-8 BOM
+        std::str::from_utf8(&buf[3..])?.to_owned()
+    } else {
+        // Assume UTF-8
+        String::from_utf8(buf)?
+    })
+}
+
+/// Apply a function to all items on as many threads as there are cores,
+/// keeping the items' order.
+fn parallel_map<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let len = items.len();
+    let threads = std::thread::available_parallelism()
+        .map_or(1, std::num::NonZeroUsize::get)
+        .min(len);
+
+    let queue = std::sync::Mutex::new(items.into_iter().enumerate());
+    let mut results: Vec<Option<R>> = (0..len).map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+                    loop {
+                        let next = queue.lock().unwrap().next();
+                        let Some((i, item)) = next else { break };
+                        done.push((i, f(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (i, result) in worker.join().unwrap() {
+                results[i] = Some(result);
+            }
+        }
+    });
+
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+/// Collect the paths of all imports and includes in a source file that are
+/// given as string literals.
+fn static_imports(world: &SystemWorld, source: &Source) -> Vec<PathBuf> {
+    fn walk(node: &SyntaxNode, paths: &mut Vec<String>) {
+        let expr = if let Some(import) = node.cast::<ast::ModuleImport>() {
+            Some(import.source())
+        } else {
+            node.cast::<ast::ModuleInclude>().map(|include| include.source())
+        };
+
+        if let Some(ast::Expr::Str(path)) = expr {
+            paths.push(path.get().to_string());
+        }
+
+        for child in node.children() {
+            walk(child, paths);
+        }
+    }
+
+    let mut paths = vec![];
+    walk(source.root(), &mut paths);
+
+    let dir = source.path().parent().unwrap_or(Path::new(""));
+    paths
+        .into_iter()
+        .map(|path| match path.strip_prefix('/') {
+            Some(path) => world.root.join(path).normalize(),
+            None => dir.join(path).normalize(),
+        })
+        .collect()
+}
+
 /// Read a file.
 #[tracing::instrument(skip_all)]
 fn read(path: &Path) -> FileResult<Vec<u8>> {
@@ -749,15 +2008,19 @@ impl<'a> codespan_reporting::files::Files<'a> for SystemWorld {
 }
 
 /// Searches for fonts.
-struct FontSearcher {
+struct FontSearcher<'a> {
     book: FontBook,
     fonts: Vec<FontSlot>,
+    disk_cache: Option<&'a DiskCache>,
 }
 
-impl FontSearcher {
+impl<'a> FontSearcher<'a> {
     /// Create a new, empty system searcher.
-    fn new() -> Self {
-        Self { book: FontBook::new(), fonts: vec![] }
+    ///
+    /// With a disk cache, the metadata of fonts is loaded from and stored in
+    /// it, keyed by the hash of the font file.
+    fn new(disk_cache: Option<&'a DiskCache>) -> Self {
+        Self { book: FontBook::new(), fonts: vec![], disk_cache }
     }
 
     /// Search everything that is available.
@@ -770,6 +2033,10 @@ impl FontSearcher {
         for path in font_paths {
             self.search_dir(path)
         }
+
+        if let Some(disk_cache) = self.disk_cache {
+            disk_cache.evict();
+        }
     }
 
     /// Add fonts that are embedded in the binary.
@@ -867,7 +2134,7 @@ impl FontSearcher {
         let path = path.as_ref();
         if let Ok(file) = File::open(path) {
             if let Ok(mmap) = unsafe { Mmap::map(&file) } {
-                for (i, info) in FontInfo::iter(&mmap).enumerate() {
+                for (i, info) in self.infos(&mmap).into_iter().enumerate() {
                     self.book.push(info);
                     self.fonts.push(FontSlot {
                         path: path.into(),
@@ -878,4 +2145,202 @@ impl FontSearcher {
             }
         }
     }
+
+    /// Read the metadata of the fonts in a file, from the disk cache if
+    /// possible.
+    ///
+    /// The entries are keyed by the fonts' table directories, which contain a
+    /// checksum of every table. This identifies the file without reading all
+    /// of it.
+    fn infos(&self, data: &[u8]) -> Vec<FontInfo> {
+        let Some((disk_cache, key)) = self.disk_cache.zip(font_key(data)) else {
+            return FontInfo::iter(data).collect();
+        };
+
+        if let Some(infos) = disk_cache.get("fonts", key) {
+            return infos;
+        }
+
+        let infos: Vec<FontInfo> = FontInfo::iter(data).collect();
+        disk_cache.put("fonts", key, &infos);
+        infos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A system world with the fonts in the assets.
+    fn world(root: &Path) -> SystemWorld {
+        let fonts = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../assets/fonts")
+            .canonicalize()
+            .unwrap();
+        SystemWorld::new(root.into(), &[fonts], None, true)
+    }
+
+    #[test]
+    fn test_check_outputs() {
+        let paths = [Path::new("out/a.pdf"), Path::new("out/b.pdf")];
+        assert!(check_outputs(paths).is_ok());
+
+        let paths = [Path::new("out/main.pdf"), Path::new("out/./main.pdf")];
+        let message = check_outputs(paths).unwrap_err();
+        assert!(message.contains("several targets would be written to"));
+    }
+
+    #[test]
+    fn test_font_key() {
+        let regular = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+        let bold = include_bytes!("../../assets/fonts/LinLibertine_RB.ttf");
+        let key = font_key(regular).unwrap();
+        assert_eq!(font_key(regular), Some(key));
+        assert_ne!(font_key(bold), Some(key));
+
+        // A changed table has a different checksum in the directory.
+        let mut changed = regular.to_vec();
+        changed[16] ^= 1;
+        assert_ne!(font_key(&changed), Some(key));
+        assert_eq!(font_key(&regular[..8]), None);
+    }
+
+    #[test]
+    fn test_dependencies_include_fonts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut world = world(dir.path());
+        let id = world
+            .fonts
+            .iter()
+            .position(|slot| slot.path.ends_with("LinLibertine_R.ttf"))
+            .unwrap();
+        let path = world.fonts[id].path.clone();
+
+        // The font is reported both when it is loaded and when it was
+        // already loaded before a reset.
+        for _ in 0..2 {
+            world.reset();
+            assert!(world.font(id).is_some());
+            assert!(world.dependencies().contains(&path));
+        }
+    }
+
+    #[test]
+    fn test_break_trace() {
+        use typst::doc::{Break, BreakKind};
+        use typst::geom::{Abs, Point, Size};
+
+        let explained = |explanation| {
+            let mut frame = Frame::new(Size::zero());
+            frame.push(Point::zero(), FrameItem::Meta(Meta::Hide, Size::zero()));
+            frame.explain(explanation);
+            frame
+        };
+
+        // Long lines are shortened, and measurements follow the reason.
+        let mut first = explained(Break {
+            text: Some("Lorem ipsum dolor sit amet, consectetur adipiscing elit".into()),
+            cost: Some((Scalar(1.5), Scalar(0.25))),
+            ..Break::new(BreakKind::Line, "next word does not fit")
+        });
+        first.push_frame(
+            Point::zero(),
+            explained(Break {
+                space: Some((Abs::pt(20.0), Abs::pt(10.0))),
+                ..Break::new(BreakKind::Region, "block does not fit")
+            }),
+        );
+        let second = explained(Break::new(BreakKind::Page, "end of page run"));
+        let document = Document { pages: vec![first, second], ..Document::default() };
+        assert_eq!(
+            break_trace(&document),
+            "page 1: line \"Lorem ipsum dolor sit amet, consectetur…\": \
+             next word does not fit (cost 1.50, ratio 0.25)\n\
+             page 1: region: block does not fit (needed 20pt, available 10pt)\n\
+             page 2: page: end of page run\n"
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut world = world(dir.path());
+        let text = "#image(\"missing.png\")\n\
+            #text(font: \"Missing Sans\", fallback: false)[Hello]";
+        world.main = world.insert(Path::new("main.typ"), text.into());
+
+        // Without placeholders, the missing image is an error.
+        let errors = typst::compile(&world).unwrap_err();
+        assert!(errors[0].message.starts_with("file not found"));
+
+        // With them, the image is replaced by a box and the text is shown in a
+        // fallback font instead of as tofus.
+        world.placeholders = true;
+        let document = typst::compile(&world).unwrap();
+        assert!(world.failed_files().iter().any(|path| path.ends_with("missing.png")));
+        fn glyphs(frame: &Frame, found: &mut Vec<(String, Vec<u16>)>) {
+            for (_, item) in frame.items() {
+                match item {
+                    FrameItem::Group(group) => glyphs(&group.frame, found),
+                    FrameItem::Text(text) => found.push((
+                        text.text.to_string(),
+                        text.glyphs.iter().map(|g| g.id).collect(),
+                    )),
+                    _ => {}
+                }
+            }
+        }
+        let mut found = vec![];
+        glyphs(&document.pages[0], &mut found);
+        assert!(found.iter().any(|(text, _)| text.as_str() == "missing.png"));
+        let (_, hello) = found.iter().find(|(text, _)| text.as_str() == "Hello").unwrap();
+        assert!(hello.iter().all(|&id| id != 0));
+
+        let warnings = typst::warnings(&world);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message.as_str(), "unknown font family: \"missing sans\"");
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        let write = |path: &Path, text: &[u8]| fs::write(path, text).unwrap();
+        write(
+            &root.join("main.typ"),
+            b"#import \"a.typ\": x\n\
+              #include \"sub/b.typ\"\n\
+              #import \"broken.typ\"\n\
+              #import \"../outside.typ\"",
+        );
+        write(&root.join("a.typ"), b"#let x = 1");
+        write(&root.join("sub/b.typ"), b"#import \"/c.typ\": y");
+        write(&root.join("c.typ"), b"#let y = 2");
+        write(&root.join("broken.typ"), b"\xff");
+        write(&dir.path().join("outside.typ"), b"");
+
+        let world = world(&root);
+        world.resolve(&root.join("main.typ")).unwrap();
+
+        // Transitive imports inside of the root are loaded ahead of
+        // evaluation, files outside of it and unreadable ones are not.
+        let loaded: Vec<PathBuf> = (0..world.sources.len())
+            .map(|i| world.source(SourceId::from_u16(i as u16)).path().into())
+            .collect();
+        assert_eq!(loaded.len(), 4);
+        for path in ["main.typ", "a.typ", "sub/b.typ", "c.typ"] {
+            assert!(loaded.contains(&root.join(path)), "{path} was not loaded");
+        }
+
+        // A file that failed to load is read again once evaluation asks.
+        write(&root.join("broken.typ"), b"#let z = 3");
+        assert!(world.resolve(&root.join("broken.typ")).is_ok());
+    }
+
+    #[test]
+    #[cfg(not(feature = "hunspell"))]
+    fn test_misspelled_without_hunspell() {
+        assert!(misspelled(Path::new("."), &[]).is_err());
+    }
 }
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use typst::diag::{bail, StrResult};
+
+use crate::args::OutputFormat;
+
+/// The name of the project manifest.
+pub const MANIFEST_NAME: &str = "typst.toml";
+
+/// A project manifest, which holds the settings for compiling a project so
+/// that they don't have to be passed as flags.
+///
+/// Paths in the manifest are relative to the directory containing it.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Manifest {
+    /// The directory containing the manifest.
+    #[serde(skip)]
+    pub dir: PathBuf,
+    /// The main Typst file.
+    pub entry: Option<PathBuf>,
+    /// The root for absolute paths, by default the manifest's directory.
+    pub root: Option<PathBuf>,
+    /// Additional directories to search for fonts.
+    pub font_paths: Vec<PathBuf>,
+    /// How to export the document.
+    pub export: Export,
+    /// Named build targets, each of which overrides the export settings.
+    pub targets: BTreeMap<String, Export>,
+    /// The values that the document can read from `sys.inputs`.
+    pub inputs: BTreeMap<String, String>,
+}
+
+/// The export settings of a project.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Export {
+    /// The path of the output file.
+    pub output: Option<PathBuf>,
+    /// The format to export into, `pdf`, `png` or `html`.
+    pub format: Option<String>,
+    /// The pixels per inch to use for PNG export.
+    pub ppi: Option<f32>,
+    /// The maximum resolution of images in PDF export.
+    pub max_dpi: Option<f64>,
+    /// The color profile to convert colors to in PDF export.
+    pub color_profile: Option<String>,
+    /// Additional values for `sys.inputs`, overriding the project's inputs.
+    pub inputs: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Find the manifest in the given directory or one of its ancestors and
+    /// load it.
+    pub fn discover(dir: &Path) -> StrResult<Option<Self>> {
+        for dir in dir.ancestors() {
+            let path = dir.join(MANIFEST_NAME);
+            if path.is_file() {
+                return Self::load(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load the manifest at the given path.
+    pub fn load(path: &Path) -> StrResult<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|_| format!("failed to read {}", path.display()))?;
+        let mut manifest: Self = toml::from_str(&text).map_err(|err| {
+            format!("failed to parse {} ({})", path.display(), err.message())
+        })?;
+        manifest.dir = path.parent().unwrap_or(Path::new("")).to_owned();
+        Ok(manifest)
+    }
+
+    /// The path of the main Typst file.
+    pub fn entry(&self) -> StrResult<PathBuf> {
+        match &self.entry {
+            Some(entry) => Ok(self.dir.join(entry)),
+            None => bail!("{MANIFEST_NAME} does not specify an entry file"),
+        }
+    }
+
+    /// The root for absolute paths.
+    pub fn root(&self) -> PathBuf {
+        match &self.root {
+            Some(root) => self.dir.join(root),
+            None => self.dir.clone(),
+        }
+    }
+
+    /// The additional font directories.
+    pub fn font_paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.font_paths.iter().map(|path| self.dir.join(path))
+    }
+
+    /// The export settings of the targets with the given names, or of all
+    /// targets if no names are given.
+    ///
+    /// A manifest without targets has a single unnamed one that uses the
+    /// plain export settings.
+    pub fn exports(&self, names: &[String]) -> StrResult<Vec<Export>> {
+        if names.is_empty() {
+            if self.targets.is_empty() {
+                return Ok(vec![self.export.clone()]);
+            }
+            return Ok(self
+                .targets
+                .values()
+                .map(|target| self.export.merge(target))
+                .collect());
+        }
+
+        names
+            .iter()
+            .map(|name| match self.targets.get(name) {
+                Some(target) => Ok(self.export.merge(target)),
+                None => bail!("unknown target `{name}` in {MANIFEST_NAME}"),
+            })
+            .collect()
+    }
+
+    /// The values for `sys.inputs` with the given export settings.
+    pub fn inputs(&self, export: &Export) -> BTreeMap<String, String> {
+        let mut inputs = self.inputs.clone();
+        inputs.extend(export.inputs.clone());
+        inputs
+    }
+}
+
+impl Export {
+    /// These settings, overridden by the settings of a target.
+    fn merge(&self, target: &Self) -> Self {
+        let mut inputs = self.inputs.clone();
+        inputs.extend(target.inputs.clone());
+        Self {
+            output: target.output.clone().or_else(|| self.output.clone()),
+            format: target.format.clone().or_else(|| self.format.clone()),
+            ppi: target.ppi.or(self.ppi),
+            max_dpi: target.max_dpi.or(self.max_dpi),
+            color_profile: target
+                .color_profile
+                .clone()
+                .or_else(|| self.color_profile.clone()),
+            inputs,
+        }
+    }
+
+    /// The path of the output file, relative to the manifest directory.
+    pub fn output(&self, dir: &Path) -> Option<PathBuf> {
+        self.output.as_ref().map(|output| dir.join(output))
+    }
+
+    /// The color profile to convert colors to, with paths to ICC profiles
+    /// made relative to the manifest directory.
+    pub fn color_profile(&self, dir: &Path) -> Option<String> {
+        self.color_profile.as_ref().map(|profile| match profile.as_str() {
+            "srgb" | "cmyk" => profile.clone(),
+            path => dir.join(path).to_string_lossy().into_owned(),
+        })
+    }
+
+    /// The format to export into.
+    pub fn format(&self) -> StrResult<Option<OutputFormat>> {
+        let Some(format) = &self.format else { return Ok(None) };
+        match OutputFormat::from_str(format, true) {
+            Ok(format) => Ok(Some(format)),
+            Err(_) => bail!("unknown export format `{format}` in {MANIFEST_NAME}"),
+        }
+    }
+}
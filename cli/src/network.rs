@@ -0,0 +1,275 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+use typst::diag::{FileError, FileResult};
+use typst::util::Buffer;
+
+use crate::cache::DiskCache;
+
+/// The name of the lockfile, which is placed in the root directory.
+pub const LOCKFILE_NAME: &str = "typst.lock";
+
+/// The maximum size of a downloaded file.
+const MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Downloads files over the network.
+pub trait Provider {
+    /// Download the file at the URL.
+    fn get(&self, url: &str) -> Result<Vec<u8>, String>;
+}
+
+/// Downloads files over HTTP and HTTPS.
+pub struct Http;
+
+impl Provider for Http {
+    fn get(&self, url: &str) -> Result<Vec<u8>, String> {
+        let response = ureq::get(url).call().map_err(|err| err.to_string())?;
+        let mut data = vec![];
+        response
+            .into_reader()
+            .take(MAX_SIZE + 1)
+            .read_to_end(&mut data)
+            .map_err(|err| err.to_string())?;
+        if data.len() as u64 > MAX_SIZE {
+            return Err("file is too large".into());
+        }
+        Ok(data)
+    }
+}
+
+/// Serves downloaded files, caching them on disk and checking them against a
+/// lockfile.
+///
+/// The lockfile maps each URL to the hash of the file that was downloaded from
+/// it the first time. Later downloads must match that hash, so that changes to
+/// remote files don't silently change the document. Since the disk cache is
+/// keyed by these hashes, locked files can be served without network access.
+pub struct Network {
+    /// How files are downloaded.
+    provider: Box<dyn Provider>,
+    /// Where downloaded files are cached.
+    disk_cache: Option<DiskCache>,
+    /// The path of the lockfile.
+    lockfile: PathBuf,
+    /// The contents of the lockfile, read on first use.
+    locked: RefCell<Option<BTreeMap<String, String>>>,
+    /// Whether accessing the network is forbidden.
+    offline: bool,
+}
+
+impl Network {
+    /// Create a new network that downloads through the given provider.
+    pub fn new(
+        provider: Box<dyn Provider>,
+        lockfile: PathBuf,
+        disk_cache: Option<DiskCache>,
+        offline: bool,
+    ) -> Self {
+        Self {
+            provider,
+            disk_cache,
+            lockfile,
+            locked: RefCell::new(None),
+            offline,
+        }
+    }
+
+    /// Fetch the file at the URL.
+    pub fn fetch(&self, url: &str) -> FileResult<Buffer> {
+        let expected = self.lock(url)?;
+        if let Some(hash) = &expected {
+            if let Some(data) = self.cached(hash) {
+                return Ok(data.into());
+            }
+        }
+
+        if self.offline {
+            return Err(FileError::Network(
+                "file is not cached and network access is disabled".into(),
+            ));
+        }
+
+        let data =
+            self.provider.get(url).map_err(|err| FileError::Network(err.into()))?;
+        let actual = hash(&data);
+        match expected {
+            Some(expected) if expected != actual => {
+                return Err(FileError::Network(
+                    format!(
+                        "file at {url} changed since it was recorded in {LOCKFILE_NAME}, \
+                         remove it from there to accept the new version"
+                    )
+                    .into(),
+                ));
+            }
+            Some(_) => {}
+            None => self.record(url, &actual),
+        }
+
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.put_bytes("downloads", key(&actual), &data);
+        }
+
+        Ok(data.into())
+    }
+
+    /// Load a file with the given hash from the disk cache.
+    fn cached(&self, hash: &str) -> Option<Vec<u8>> {
+        let data = self.disk_cache.as_ref()?.get_bytes("downloads", key(hash))?;
+        (hash(&data) == hash).then_some(data)
+    }
+
+    /// The hash recorded in the lockfile for a URL.
+    ///
+    /// A missing lockfile is treated as empty, but one that can't be read or
+    /// parsed is an error, so that it isn't overwritten by [`record`](Self::record).
+    fn lock(&self, url: &str) -> FileResult<Option<String>> {
+        let mut locked = self.locked.borrow_mut();
+        if locked.is_none() {
+            *locked = Some(match fs::read(&self.lockfile) {
+                Ok(data) => serde_json::from_slice(&data).map_err(|_| {
+                    FileError::Network(
+                        format!(
+                            "{LOCKFILE_NAME} is malformed, fix or remove it to \
+                             download files again"
+                        )
+                        .into(),
+                    )
+                })?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+                Err(_) => {
+                    return Err(FileError::Network(
+                        format!("failed to read {LOCKFILE_NAME}").into(),
+                    ))
+                }
+            });
+        }
+        Ok(locked.as_ref().and_then(|locked| locked.get(url).cloned()))
+    }
+
+    /// Record the hash of a URL's file in the lockfile.
+    ///
+    /// Failing to write the lockfile is not an error, but the file will then
+    /// not be checked by later compilations.
+    fn record(&self, url: &str, hash: &str) {
+        let mut locked = self.locked.borrow_mut();
+        let locked = locked.get_or_insert_with(BTreeMap::new);
+        locked.insert(url.into(), hash.into());
+        if let Ok(mut json) = serde_json::to_string_pretty(&*locked) {
+            json.push('\n');
+            fs::write(&self.lockfile, json).ok();
+        }
+    }
+}
+
+/// Hash the contents of a file as recorded in the lockfile.
+fn hash(data: &[u8]) -> String {
+    let digest = Sha256::digest(data);
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("sha256-{hex}")
+}
+
+/// The key of a downloaded file in the disk cache, which is the start of its
+/// hash.
+///
+/// Unlike other entries, this doesn't depend on the version of Typst, so that
+/// offline compilation keeps working after an update.
+fn key(hash: &str) -> u128 {
+    let hex = hash.trim_start_matches("sha256-");
+    u128::from_str_radix(hex.get(..32).unwrap_or(hex), 16).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    /// Serves fixed contents and counts the downloads.
+    struct Fixed {
+        data: &'static [u8],
+        downloads: Rc<Cell<usize>>,
+    }
+
+    impl Provider for Fixed {
+        fn get(&self, _: &str) -> Result<Vec<u8>, String> {
+            self.downloads.set(self.downloads.get() + 1);
+            Ok(self.data.to_vec())
+        }
+    }
+
+    const URL: &str = "https://example.com/logo.png";
+
+    fn network(
+        dir: &tempfile::TempDir,
+        data: &'static [u8],
+        offline: bool,
+    ) -> (Network, Rc<Cell<usize>>) {
+        let downloads = Rc::new(Cell::new(0));
+        let provider = Box::new(Fixed { data, downloads: downloads.clone() });
+        let disk_cache = DiskCache::new(dir.path().join("cache"), u64::MAX);
+        let lockfile = dir.path().join(LOCKFILE_NAME);
+        (Network::new(provider, lockfile, Some(disk_cache), offline), downloads)
+    }
+
+    fn lockfile(dir: &tempfile::TempDir) -> String {
+        fs::read_to_string(dir.path().join(LOCKFILE_NAME)).unwrap()
+    }
+
+    #[test]
+    fn test_fetch_records_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let (network, downloads) = network(&dir, b"hello", false);
+        assert_eq!(network.fetch(URL).unwrap().as_slice(), b"hello");
+        assert_eq!(downloads.get(), 1);
+        assert!(lockfile(&dir).contains(&hash(b"hello")));
+    }
+
+    #[test]
+    fn test_fetch_offline_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        network(&dir, b"hello", false).0.fetch(URL).unwrap();
+        let (network, downloads) = network(&dir, b"changed", true);
+        assert_eq!(network.fetch(URL).unwrap().as_slice(), b"hello");
+        assert_eq!(downloads.get(), 0);
+    }
+
+    #[test]
+    fn test_fetch_offline_without_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let (network, downloads) = network(&dir, b"hello", true);
+        assert!(matches!(network.fetch(URL), Err(FileError::Network(_))));
+        assert_eq!(downloads.get(), 0);
+    }
+
+    #[test]
+    fn test_fetch_changed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        network(&dir, b"hello", false).0.fetch(URL).unwrap();
+        fs::remove_dir_all(dir.path().join("cache")).unwrap();
+        let result = network(&dir, b"changed", false).0.fetch(URL);
+        let Err(FileError::Network(message)) = result else {
+            panic!("expected a network error");
+        };
+        assert!(message.contains("changed since it was recorded"));
+        assert!(lockfile(&dir).contains(&hash(b"hello")));
+    }
+
+    #[test]
+    fn test_fetch_malformed_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(LOCKFILE_NAME), "{ not json").unwrap();
+        let (network, downloads) = network(&dir, b"hello", false);
+        let Err(FileError::Network(message)) = network.fetch(URL) else {
+            panic!("expected a network error");
+        };
+        assert!(message.contains("malformed"));
+        assert_eq!(downloads.get(), 0);
+        assert_eq!(lockfile(&dir), "{ not json");
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use hunspell_rs::{CheckResult, Hunspell};
+use typst::diag::StrResult;
+use typst::ide::Word;
+
+/// Checks words with the Hunspell dictionaries in a directory.
+///
+/// The dictionary for a word is picked by its language and region: a German
+/// word in Austria is checked with `de_AT.aff` and `de_AT.dic`, falling back
+/// to `de.aff` and `de.dic`. Words in languages without a dictionary are not
+/// checked.
+pub struct SpellChecker {
+    dir: PathBuf,
+    dictionaries: HashMap<String, Option<Hunspell>>,
+}
+
+impl SpellChecker {
+    /// Create a spell checker for the dictionaries in a directory.
+    pub fn new(dir: &Path) -> StrResult<Self> {
+        if !dir.is_dir() {
+            return Err("dictionary directory does not exist".into());
+        }
+
+        Ok(Self { dir: dir.into(), dictionaries: HashMap::new() })
+    }
+
+    /// Whether a word is misspelled.
+    pub fn misspelled(&mut self, word: &Word) -> bool {
+        let lang = word.lang.as_str();
+        let mut names = vec![lang.to_string()];
+        if let Some(region) = &word.region {
+            names.insert(0, format!("{lang}_{}", region.as_str()));
+        }
+
+        for name in names {
+            if let Some(dictionary) = self.dictionary(name) {
+                let result = dictionary.check(&word.text);
+                return matches!(result, CheckResult::MissingInDictionary);
+            }
+        }
+
+        false
+    }
+
+    /// Load the dictionary with the given name, if it exists.
+    fn dictionary(&mut self, name: String) -> Option<&Hunspell> {
+        let dir = &self.dir;
+        self.dictionaries
+            .entry(name)
+            .or_insert_with_key(|name| {
+                let aff = dir.join(format!("{name}.aff"));
+                let dic = dir.join(format!("{name}.dic"));
+                if !aff.is_file() || !dic.is_file() {
+                    return None;
+                }
+                Some(Hunspell::new(aff.to_str()?, dic.to_str()?))
+            })
+            .as_ref()
+    }
+}
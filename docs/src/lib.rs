@@ -229,8 +229,11 @@ fn category_page(resolver: &dyn Resolver, category: &str) -> PageModel {
     };
 
     // Add functions.
-    for (_, value) in focus.scope().iter() {
+    for (name, value) in focus.scope().iter() {
         let Value::Func(func) = value else { continue };
+        if focus.scope().deprecation(name).is_some() {
+            continue;
+        }
         let Some(info) = func.info() else { continue };
         if info.category != category {
             continue;
@@ -426,6 +429,11 @@ fn func_model(
         .filter_map(|(_, value)| {
             let Value::Func(func) = value else { return None };
             let info = func.info().unwrap();
+            // Functions that are just namespaced re-exports of global ones
+            // are documented on their own pages.
+            if LIBRARY.global.scope().get(info.name) == Some(value) {
+                return None;
+            }
             Some(func_model(resolver, func, info, &path, id_base))
         })
         .collect();
@@ -940,6 +948,8 @@ const TYPE_ORDER: &[&str] = &[
     "fraction",
     "color",
     "datetime",
+    "duration",
+    "version",
     "string",
     "regex",
     "label",
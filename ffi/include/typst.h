@@ -0,0 +1,96 @@
+/* A C API for embedding Typst.
+ *
+ * Typical usage:
+ *
+ *     TypstCompiler *compiler = typst_compiler_new();
+ *     typst_compiler_add_font(compiler, font_data, font_len);
+ *     typst_compiler_set_file_reader(compiler, read_file, my_context);
+ *
+ *     TypstResult *result = typst_compile(compiler, source, source_len);
+ *     size_t len;
+ *     const uint8_t *pdf = typst_result_pdf(result, &len);
+ *     if (pdf == NULL) {
+ *         for (size_t i = 0; i < typst_result_diagnostic_count(result); i++) {
+ *             puts(typst_result_diagnostic_message(result, i));
+ *         }
+ *     }
+ *
+ *     typst_result_free(result);
+ *     typst_compiler_free(compiler);
+ *
+ * All strings are UTF-8. Pointers obtained from a result stay valid until
+ * the result is freed. A compiler must not be used from multiple threads at
+ * the same time.
+ */
+
+#ifndef TYPST_H
+#define TYPST_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct TypstCompiler TypstCompiler;
+typedef struct TypstResult TypstResult;
+typedef struct TypstBuffer TypstBuffer;
+
+/* Provides the contents of the file at the absolute `path` by writing them
+ * into `buffer` with `typst_buffer_write`. Returns whether the file exists. */
+typedef bool (*TypstReadFile)(void *user, const char *path, TypstBuffer *buffer);
+
+/* Create a new compiler without any fonts. */
+TypstCompiler *typst_compiler_new(void);
+
+/* Free a compiler. */
+void typst_compiler_free(TypstCompiler *compiler);
+
+/* Add all fonts in a font file or collection. Returns how many were added. */
+size_t typst_compiler_add_font(TypstCompiler *compiler, const uint8_t *data, size_t len);
+
+/* Set the callback that provides files. `user` is passed to every call and
+ * must stay valid while the compiler is used. Pass NULL to remove it. */
+void typst_compiler_set_file_reader(TypstCompiler *compiler, TypstReadFile reader, void *user);
+
+/* Set the date returned by `datetime.today()`. Returns false if the date is
+ * invalid. */
+bool typst_compiler_set_today(TypstCompiler *compiler, int32_t year, uint8_t month, uint8_t day);
+
+/* Compile a source buffer into a PDF. The source acts as the file
+ * `/main.typ`. The result must be freed with `typst_result_free`. */
+TypstResult *typst_compile(const TypstCompiler *compiler, const uint8_t *source, size_t len);
+
+/* Free a compilation result. */
+void typst_result_free(TypstResult *result);
+
+/* The PDF bytes, or NULL if compilation failed. Writes the length to `len`. */
+const uint8_t *typst_result_pdf(const TypstResult *result, size_t *len);
+
+/* The number of diagnostics. */
+size_t typst_result_diagnostic_count(const TypstResult *result);
+
+/* The message of a diagnostic, or NULL if the index is out of bounds. */
+const char *typst_result_diagnostic_message(const TypstResult *result, size_t index);
+
+/* The file path and zero-based line and column of a diagnostic. The path is
+ * empty if the diagnostic has no location. Returns false if the index is out
+ * of bounds. */
+bool typst_result_diagnostic_location(
+    const TypstResult *result,
+    size_t index,
+    const char **path,
+    size_t *line,
+    size_t *column
+);
+
+/* Append bytes to a buffer passed to a file callback. */
+void typst_buffer_write(TypstBuffer *buffer, const uint8_t *data, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,567 @@
+//! A C API for embedding Typst into applications written in other languages.
+//!
+//! The declarations of all functions are in `include/typst.h`, together with
+//! their documentation. The library is built as a shared and a static library
+//! with `cargo build -p typst-ffi --release`. It isn't part of the default
+//! build.
+//!
+//! A compiler holds the fonts and an optional callback that provides the
+//! contents of files the document imports or reads. Compiling a source buffer
+//! yields a result with either the PDF bytes or a list of diagnostics.
+//!
+//! Null pointers are accepted everywhere and treated as empty. Panics inside
+//! the compiler are caught at the boundary, since unwinding into the caller
+//! is undefined behavior. A panicking compilation yields a diagnostic.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use comemo::Prehashed;
+use elsa::FrozenVec;
+use typst::diag::{FileError, FileResult, SourceError};
+use typst::eval::{Datetime, Library};
+use typst::font::{Font, FontBook};
+use typst::syntax::{Source, SourceId};
+use typst::util::Buffer;
+use typst::World;
+
+/// A callback that provides the contents of a file.
+///
+/// Receives the user data given to `typst_compiler_set_file_reader`, the
+/// absolute path of the file and a buffer to write the contents into with
+/// `typst_buffer_write`. Returns whether the file exists.
+pub type TypstReadFile = extern "C" fn(
+    user: *mut c_void,
+    path: *const c_char,
+    buffer: *mut TypstBuffer,
+) -> bool;
+
+/// A reusable compiler with its fonts and file provider.
+pub struct TypstCompiler {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    fonts: Vec<Font>,
+    reader: Option<(TypstReadFile, *mut c_void)>,
+    today: Option<Datetime>,
+}
+
+/// A growable byte buffer that a file callback writes into.
+pub struct TypstBuffer(Vec<u8>);
+
+/// The outcome of a compilation.
+pub struct TypstResult {
+    pdf: Option<Vec<u8>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// An error message with its location.
+struct Diagnostic {
+    message: CString,
+    path: CString,
+    line: usize,
+    column: usize,
+}
+
+/// Create a new compiler without any fonts.
+#[no_mangle]
+pub extern "C" fn typst_compiler_new() -> *mut TypstCompiler {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(TypstCompiler {
+            library: Prehashed::new(typst_library::build()),
+            book: Prehashed::new(FontBook::new()),
+            fonts: vec![],
+            reader: None,
+            today: None,
+        }))
+    })
+}
+
+/// Free a compiler.
+///
+/// # Safety
+/// The compiler must have been created by `typst_compiler_new` and must not be
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn typst_compiler_free(compiler: *mut TypstCompiler) {
+    if !compiler.is_null() {
+        guard((), || drop(Box::from_raw(compiler)));
+    }
+}
+
+/// Add all fonts in a font file or collection. Returns how many fonts were
+/// added.
+///
+/// # Safety
+/// The compiler must be valid or null and, unless null, `data` must point to
+/// `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn typst_compiler_add_font(
+    compiler: *mut TypstCompiler,
+    data: *const u8,
+    len: usize,
+) -> usize {
+    let Some(compiler) = compiler.as_mut() else { return 0 };
+    let data = slice(data, len);
+    guard(0, || {
+        let before = compiler.fonts.len();
+        compiler.fonts.extend(Font::iter(Buffer::from(data.to_vec())));
+        compiler.book = Prehashed::new(FontBook::from_fonts(&compiler.fonts));
+        compiler.fonts.len() - before
+    })
+}
+
+/// Set the callback that provides the contents of files.
+///
+/// # Safety
+/// The compiler must be valid or null. The callback is called with `user`
+/// during compilation, so `user` must stay valid as long as the compiler is
+/// used.
+#[no_mangle]
+pub unsafe extern "C" fn typst_compiler_set_file_reader(
+    compiler: *mut TypstCompiler,
+    reader: Option<TypstReadFile>,
+    user: *mut c_void,
+) {
+    if let Some(compiler) = compiler.as_mut() {
+        compiler.reader = reader.map(|reader| (reader, user));
+    }
+}
+
+/// Set the date returned by `datetime.today()`. Returns false if the date is
+/// invalid. Without a date, `datetime.today()` fails.
+///
+/// # Safety
+/// The compiler must be valid or null.
+#[no_mangle]
+pub unsafe extern "C" fn typst_compiler_set_today(
+    compiler: *mut TypstCompiler,
+    year: i32,
+    month: u8,
+    day: u8,
+) -> bool {
+    let Some(compiler) = compiler.as_mut() else { return false };
+    let today = Datetime::from_ymd(year, month, day);
+    compiler.today = today;
+    today.is_some()
+}
+
+/// Compile a UTF-8 source buffer into a PDF.
+///
+/// The source is treated as the file `/main.typ`, so relative paths in it are
+/// resolved from the root. The result must be freed with `typst_result_free`.
+///
+/// # Safety
+/// The compiler must be valid or null and, unless null, `source` must point to
+/// `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn typst_compile(
+    compiler: *const TypstCompiler,
+    source: *const u8,
+    len: usize,
+) -> *mut TypstResult {
+    let bytes = slice(source, len);
+    let result = match (compiler.as_ref(), std::str::from_utf8(bytes)) {
+        (None, _) => TypstResult::failure("compiler is null"),
+        (Some(_), Err(_)) => TypstResult::failure("source is not valid UTF-8"),
+        (Some(compiler), Ok(text)) => guard(
+            TypstResult::failure("the compiler panicked, this is a bug in Typst"),
+            || compile(compiler, text),
+        ),
+    };
+    Box::into_raw(Box::new(result))
+}
+
+/// Free a compilation result.
+///
+/// # Safety
+/// The result must have been returned by `typst_compile` and must not be used
+/// afterwards. This also invalidates all pointers obtained from it.
+#[no_mangle]
+pub unsafe extern "C" fn typst_result_free(result: *mut TypstResult) {
+    if !result.is_null() {
+        guard((), || drop(Box::from_raw(result)));
+    }
+}
+
+/// The PDF bytes of a successful compilation, or null if it failed. The
+/// length is written into `len`.
+///
+/// # Safety
+/// The result must be valid or null and `len` must be writable or null.
+#[no_mangle]
+pub unsafe extern "C" fn typst_result_pdf(
+    result: *const TypstResult,
+    len: *mut usize,
+) -> *const u8 {
+    let pdf = result.as_ref().and_then(|result| result.pdf.as_ref());
+    if let Some(len) = len.as_mut() {
+        *len = pdf.map_or(0, Vec::len);
+    }
+    pdf.map_or(ptr::null(), |pdf| pdf.as_ptr())
+}
+
+/// The number of diagnostics of a compilation.
+///
+/// # Safety
+/// The result must be valid or null.
+#[no_mangle]
+pub unsafe extern "C" fn typst_result_diagnostic_count(
+    result: *const TypstResult,
+) -> usize {
+    result.as_ref().map_or(0, |result| result.diagnostics.len())
+}
+
+/// The message of a diagnostic as a null-terminated string, or null if the
+/// index is out of bounds.
+///
+/// # Safety
+/// The result must be valid or null.
+#[no_mangle]
+pub unsafe extern "C" fn typst_result_diagnostic_message(
+    result: *const TypstResult,
+    index: usize,
+) -> *const c_char {
+    result
+        .as_ref()
+        .and_then(|result| result.diagnostics.get(index))
+        .map_or(ptr::null(), |diag| diag.message.as_ptr())
+}
+
+/// The location of a diagnostic. Writes the path of the file, which is empty
+/// if the diagnostic has no location, and the zero-based line and column.
+/// Returns false if the index is out of bounds.
+///
+/// # Safety
+/// The result must be valid or null and the output pointers must be writable
+/// or null.
+#[no_mangle]
+pub unsafe extern "C" fn typst_result_diagnostic_location(
+    result: *const TypstResult,
+    index: usize,
+    path: *mut *const c_char,
+    line: *mut usize,
+    column: *mut usize,
+) -> bool {
+    let Some(diag) = result.as_ref().and_then(|result| result.diagnostics.get(index))
+    else {
+        return false;
+    };
+    if let Some(path) = path.as_mut() {
+        *path = diag.path.as_ptr();
+    }
+    if let Some(line) = line.as_mut() {
+        *line = diag.line;
+    }
+    if let Some(column) = column.as_mut() {
+        *column = diag.column;
+    }
+    true
+}
+
+/// Append bytes to a buffer.
+///
+/// # Safety
+/// The buffer must have been passed to a file callback and, unless null,
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn typst_buffer_write(
+    buffer: *mut TypstBuffer,
+    data: *const u8,
+    len: usize,
+) {
+    if let Some(buffer) = buffer.as_mut() {
+        buffer.0.extend_from_slice(slice(data, len));
+    }
+}
+
+/// Run the body of an exported function, returning the fallback if it panics.
+fn guard<T>(fallback: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(fallback)
+}
+
+/// View caller-supplied bytes as a slice. A null pointer or a zero length
+/// yields an empty slice.
+///
+/// # Safety
+/// If not null, `data` must point to `len` readable bytes that outlive the
+/// returned slice.
+unsafe fn slice<'a>(data: *const u8, len: usize) -> &'a [u8] {
+    if data.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, len)
+    }
+}
+
+impl TypstResult {
+    /// A failed compilation with a single diagnostic without location.
+    fn failure(message: &str) -> Self {
+        Self {
+            pdf: None,
+            diagnostics: vec![Diagnostic {
+                message: cstring(message),
+                path: cstring(""),
+                line: 0,
+                column: 0,
+            }],
+        }
+    }
+}
+
+/// Compile a source text.
+fn compile(compiler: &TypstCompiler, text: &str) -> TypstResult {
+    let world = FfiWorld::new(compiler, text);
+    match typst::compile(&world) {
+        Ok(document) => match typst::export::pdf(&document) {
+            Ok(pdf) => TypstResult { pdf: Some(pdf), diagnostics: vec![] },
+            Err(message) => TypstResult::failure(&message),
+        },
+        Err(errors) => TypstResult {
+            pdf: None,
+            diagnostics: errors.iter().map(|error| diagnostic(&world, error)).collect(),
+        },
+    }
+}
+
+/// Convert an error into a diagnostic with a location.
+fn diagnostic(world: &FfiWorld, error: &SourceError) -> Diagnostic {
+    let mut diag = Diagnostic {
+        message: cstring(&error.message),
+        path: cstring(""),
+        line: 0,
+        column: 0,
+    };
+
+    if !error.span.is_detached() {
+        let source = world.source(error.span.source());
+        let start = error.range(world).start;
+        diag.path = cstring(&source.path().to_string_lossy());
+        diag.line = source.byte_to_line(start).unwrap_or(0);
+        diag.column = source.byte_to_column(start).unwrap_or(0);
+    }
+
+    diag
+}
+
+/// Create a C string, dropping interior null bytes.
+fn cstring(text: &str) -> CString {
+    CString::new(text.replace('\0', "")).unwrap()
+}
+
+/// A world that gets its files from the compiler's file callback.
+struct FfiWorld<'a> {
+    compiler: &'a TypstCompiler,
+    sources: FrozenVec<Box<Source>>,
+    paths: RefCell<HashMap<PathBuf, FileResult<SourceId>>>,
+    files: RefCell<HashMap<PathBuf, FileResult<Buffer>>>,
+}
+
+impl<'a> FfiWorld<'a> {
+    fn new(compiler: &'a TypstCompiler, text: &str) -> Self {
+        let world = Self {
+            compiler,
+            sources: FrozenVec::new(),
+            paths: RefCell::default(),
+            files: RefCell::default(),
+        };
+        world.insert(Path::new("/main.typ"), text.into());
+        world
+    }
+
+    fn insert(&self, path: &Path, text: String) -> SourceId {
+        let id = SourceId::from_u16(self.sources.len() as u16);
+        self.sources.push(Box::new(Source::new(id, path, text)));
+        id
+    }
+
+    /// Read a file through the compiler's callback.
+    fn read(&self, path: &Path) -> FileResult<Vec<u8>> {
+        let not_found = || FileError::NotFound(path.into());
+        let Some((reader, user)) = self.compiler.reader else { return Err(not_found()) };
+        let path_c =
+            CString::new(path.to_string_lossy().as_bytes()).map_err(|_| not_found())?;
+        let mut buffer = TypstBuffer(vec![]);
+        if reader(user, path_c.as_ptr(), &mut buffer) {
+            Ok(buffer.0)
+        } else {
+            Err(not_found())
+        }
+    }
+}
+
+impl World for FfiWorld<'_> {
+    fn root(&self) -> &Path {
+        Path::new("/")
+    }
+
+    fn library(&self) -> &Prehashed<Library> {
+        &self.compiler.library
+    }
+
+    fn main(&self) -> &Source {
+        &self.sources[0]
+    }
+
+    fn resolve(&self, path: &Path) -> FileResult<SourceId> {
+        if let Some(result) = self.paths.borrow().get(path) {
+            return result.clone();
+        }
+
+        let result = self
+            .read(path)
+            .and_then(|data| String::from_utf8(data).map_err(|_| FileError::InvalidUtf8))
+            .map(|text| self.insert(path, text));
+        self.paths.borrow_mut().insert(path.into(), result.clone());
+        result
+    }
+
+    fn source(&self, id: SourceId) -> &Source {
+        &self.sources[id.as_u16() as usize]
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.compiler.book
+    }
+
+    fn font(&self, id: usize) -> Option<Font> {
+        self.compiler.fonts.get(id).cloned()
+    }
+
+    fn file(&self, path: &Path) -> FileResult<Buffer> {
+        self.files
+            .borrow_mut()
+            .entry(path.into())
+            .or_insert_with(|| self.read(path).map(Buffer::from))
+            .clone()
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        self.compiler.today
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    fn compile_text(compiler: *const TypstCompiler, text: &str) -> *mut TypstResult {
+        unsafe { typst_compile(compiler, text.as_ptr(), text.len()) }
+    }
+
+    fn message(result: *const TypstResult, index: usize) -> String {
+        let message = unsafe { typst_result_diagnostic_message(result, index) };
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned()
+    }
+
+    fn pdf(result: *const TypstResult) -> Option<&'static [u8]> {
+        let mut len = 0;
+        let data = unsafe { typst_result_pdf(result, &mut len) };
+        (!data.is_null()).then(|| unsafe { slice(data, len) })
+    }
+
+    #[test]
+    fn test_compile_pdf() {
+        let compiler = typst_compiler_new();
+        let result = compile_text(compiler, "#rect()");
+        assert!(pdf(result).unwrap().starts_with(b"%PDF"));
+        assert_eq!(unsafe { typst_result_diagnostic_count(result) }, 0);
+        unsafe {
+            typst_result_free(result);
+            typst_compiler_free(compiler);
+        }
+    }
+
+    #[test]
+    fn test_compile_error() {
+        let compiler = typst_compiler_new();
+        let result = compile_text(compiler, "\n #foo");
+        assert_eq!(pdf(result), None);
+        assert_eq!(unsafe { typst_result_diagnostic_count(result) }, 1);
+        assert_eq!(message(result, 0), "unknown variable: foo");
+
+        let mut path = ptr::null();
+        let (mut line, mut column) = (0, 0);
+        assert!(unsafe {
+            typst_result_diagnostic_location(result, 0, &mut path, &mut line, &mut column)
+        });
+        assert_eq!(unsafe { CStr::from_ptr(path) }.to_str(), Ok("/main.typ"));
+        assert_eq!((line, column), (1, 2));
+        assert!(unsafe { typst_result_diagnostic_message(result, 1) }.is_null());
+        unsafe {
+            typst_result_free(result);
+            typst_compiler_free(compiler);
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let compiler = typst_compiler_new();
+        let source = [0xff, 0xfe];
+        let result = unsafe { typst_compile(compiler, source.as_ptr(), source.len()) };
+        assert_eq!(pdf(result), None);
+        assert_eq!(message(result, 0), "source is not valid UTF-8");
+        unsafe {
+            typst_result_free(result);
+            typst_compiler_free(compiler);
+        }
+    }
+
+    #[test]
+    fn test_null_pointers() {
+        let result = unsafe { typst_compile(ptr::null(), ptr::null(), 0) };
+        assert_eq!(message(result, 0), "compiler is null");
+        unsafe { typst_result_free(result) };
+
+        let compiler = typst_compiler_new();
+        assert_eq!(unsafe { typst_compiler_add_font(compiler, ptr::null(), 16) }, 0);
+        let result = unsafe { typst_compile(compiler, ptr::null(), 16) };
+        assert!(pdf(result).is_some());
+        unsafe {
+            assert!(typst_result_pdf(ptr::null(), ptr::null_mut()).is_null());
+            assert_eq!(typst_result_diagnostic_count(ptr::null()), 0);
+            assert!(!typst_compiler_set_today(ptr::null_mut(), 2023, 1, 1));
+            typst_buffer_write(ptr::null_mut(), ptr::null(), 4);
+            typst_result_free(result);
+            typst_result_free(ptr::null_mut());
+            typst_compiler_free(compiler);
+            typst_compiler_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_file_reader() {
+        extern "C" fn read(
+            _: *mut c_void,
+            path: *const c_char,
+            buffer: *mut TypstBuffer,
+        ) -> bool {
+            let path = unsafe { CStr::from_ptr(path) };
+            if path.to_bytes() != b"/data.txt" {
+                return false;
+            }
+            let data = b"hello";
+            unsafe { typst_buffer_write(buffer, data.as_ptr(), data.len()) };
+            true
+        }
+
+        let compiler = typst_compiler_new();
+        unsafe { typst_compiler_set_file_reader(compiler, Some(read), ptr::null_mut()) };
+        let result = compile_text(compiler, "#assert.eq(read(\"data.txt\"), \"hello\")");
+        assert!(pdf(result).is_some());
+        unsafe { typst_result_free(result) };
+
+        let result = compile_text(compiler, "#read(\"missing.txt\")");
+        assert!(message(result, 0).starts_with("file not found"));
+        unsafe {
+            typst_result_free(result);
+            typst_compiler_free(compiler);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Fuzzing entry points for the parser and evaluator.
+//!
+//! The targets in `fuzz_targets` feed arbitrary bytes into these functions.
+//! Run them with [cargo-fuzz] on a nightly compiler, for example
+//! `cargo fuzz run parse` from the repository root. The functions can also be
+//! called directly to reproduce a crash from its input file.
+//!
+//! No input may panic or overflow the stack. Invalid input must surface as
+//! syntax or evaluation errors instead.
+//!
+//! [cargo-fuzz]: https://github.com/rust-fuzz/cargo-fuzz
+
+use std::path::Path;
+
+use comemo::{Prehashed, Track};
+use typst::diag::{FileError, FileResult};
+use typst::eval::{eval, Datetime, Library, Route, Tracer};
+use typst::font::{Font, FontBook};
+use typst::syntax::{parse, parse_code, Source, SourceId};
+use typst::util::Buffer;
+use typst::World;
+
+/// Parse the input as markup and as code and edit it incrementally.
+///
+/// Checks that the syntax trees always cover the full text.
+pub fn fuzz_parse(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+
+    let root = parse(text);
+    assert_eq!(root.len(), text.len());
+    assert_eq!(root.into_text().as_str(), text);
+
+    let root = parse_code(text);
+    assert_eq!(root.len(), text.len());
+    assert_eq!(root.into_text().as_str(), text);
+
+    // Duplicate the first half of the text to exercise the reparser.
+    let mut mid = text.len() / 2;
+    while !text.is_char_boundary(mid) {
+        mid -= 1;
+    }
+
+    let mut source = Source::detached(text);
+    source.edit(mid..mid, &text[..mid]);
+    assert_eq!(source.root().len(), source.len_bytes());
+    assert_eq!(source.root().clone().into_text().as_str(), source.text());
+}
+
+/// Evaluate the input as a source file.
+///
+/// The evaluation has no access to files or fonts.
+pub fn fuzz_eval(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    let world = FuzzWorld::new(text);
+    let route = Route::default();
+    let mut tracer = Tracer::default();
+    let _ = eval(world.track(), route.track(), tracer.track_mut(), world.main());
+}
+
+/// A world with a single source file and nothing else.
+struct FuzzWorld {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    source: Source,
+}
+
+impl FuzzWorld {
+    fn new(text: &str) -> Self {
+        Self {
+            library: Prehashed::new(typst_library::build()),
+            book: Prehashed::new(FontBook::new()),
+            source: Source::new(
+                SourceId::from_u16(0),
+                Path::new("/main.typ"),
+                text.into(),
+            ),
+        }
+    }
+}
+
+impl World for FuzzWorld {
+    fn root(&self) -> &Path {
+        Path::new("/")
+    }
+
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn main(&self) -> &Source {
+        &self.source
+    }
+
+    fn resolve(&self, path: &Path) -> FileResult<SourceId> {
+        Err(FileError::NotFound(path.into()))
+    }
+
+    fn source(&self, _: SourceId) -> &Source {
+        &self.source
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn font(&self, _: usize) -> Option<Font> {
+        None
+    }
+
+    fn file(&self, path: &Path) -> FileResult<Buffer> {
+        Err(FileError::NotFound(path.into()))
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        None
+    }
+}
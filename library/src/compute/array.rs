@@ -0,0 +1,12 @@
+//! Functions for creating and processing arrays.
+
+use typst::eval::{Module, Scope};
+
+use super::range_func;
+
+/// A module with functions for arrays.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define("range", range_func());
+    Module::new("array").with_scope(scope)
+}
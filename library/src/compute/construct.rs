@@ -3,7 +3,7 @@ use std::str::FromStr;
 
 use time::{Month, PrimitiveDateTime};
 
-use typst::eval::{Datetime, Regex};
+use typst::eval::{Datetime, Duration, Range, Regex, Version};
 
 use crate::prelude::*;
 
@@ -338,16 +338,105 @@ pub fn datetime_today(
         .ok_or("unable to get the current date")?)
 }
 
+/// Create a duration.
+///
+/// Durations can be added to and subtracted from each other and from
+/// [datetimes]($type/datetime), multiplied and divided by numbers and compared.
+/// Subtracting two datetimes of the same kind yields a duration.
+///
+/// ## Example { #example }
+/// ```example
+/// #let start = datetime(year: 2023, month: 6, day: 1)
+/// #let sprint = duration(weeks: 2)
+/// Next sprint: #(start + sprint).display() \
+/// Total: #(3 * duration(hours: 1, minutes: 20)).display()
+/// ```
+///
+/// Display: Duration
+/// Category: construct
+#[func]
+pub fn duration(
+    /// The number of seconds.
+    #[named]
+    #[default(0)]
+    seconds: i64,
+    /// The number of minutes.
+    #[named]
+    #[default(0)]
+    minutes: i64,
+    /// The number of hours.
+    #[named]
+    #[default(0)]
+    hours: i64,
+    /// The number of days.
+    #[named]
+    #[default(0)]
+    days: i64,
+    /// The number of weeks.
+    #[named]
+    #[default(0)]
+    weeks: i64,
+) -> StrResult<Duration> {
+    Ok(Duration::from_parts(weeks, days, hours, minutes, seconds)
+        .ok_or("duration is too large")?)
+}
+
+/// Create a version.
+///
+/// Versions are compared component by component, with missing components
+/// treated as zero. The first three components can be accessed as `major`,
+/// `minor` and `patch` fields, and any component with the
+/// [`at`]($type/version.at) method. The version of the running compiler is
+/// available as `sys.version`.
+///
+/// ## Example { #example }
+/// ```example
+/// #let v = version(0, 5, 0)
+/// #v.minor \
+/// #(v < version(0, 10)) \
+/// #(version("1.2") == version(1, 2, 0))
+///
+/// #assert(
+///   sys.version >= version(0, 5),
+///   message: "this template requires at least Typst 0.5",
+/// )
+/// ```
+///
+/// Display: Version
+/// Category: construct
+#[func]
+pub fn version(
+    /// The components of the version. Each argument can be an integer, an
+    /// array of integers or a string like `{"0.5.0"}`.
+    #[variadic]
+    components: Vec<VersionComponents>,
+) -> Version {
+    Version::new(components.into_iter().flat_map(|c| c.0))
+}
+
+/// One or multiple components of a version.
+pub struct VersionComponents(Vec<u32>);
+
+cast! {
+    VersionComponents,
+    v: u32 => Self(vec![v]),
+    v: Vec<u32> => Self(v),
+    v: EcoString => Self(Version::from_str(&v)?.components().to_vec()),
+}
+
 /// Create a CMYK color.
 ///
 /// This is useful if you want to target a specific printer. The conversion
 /// to RGB for display preview might differ from how your printer reproduces
 /// the color.
 ///
+/// The function is part of the `color` namespace. Its global name is
+/// deprecated.
+///
 /// ## Example { #example }
 /// ```example
 /// #square(
-///   fill: cmyk(27%, 0%, 3%, 5%)
+///   fill: color.cmyk(27%, 0%, 3%, 5%)
 /// )
 /// ````
 ///
@@ -453,6 +542,10 @@ cast! {
 /// [`str.to-unicode`]($func/str.to-unicode) and
 /// [`str.from-unicode`]($func/str.from-unicode).
 ///
+/// The string-related functions [`regex`]($func/regex),
+/// [`upper`]($func/upper) and [`lower`]($func/lower) are also available as
+/// `str.regex`, `str.upper` and `str.lower`.
+///
 /// ## Example { #example }
 /// ```example
 /// #str(10) \
@@ -468,6 +561,9 @@ cast! {
 #[scope(
     scope.define("to-unicode", str_to_unicode_func());
     scope.define("from-unicode", str_from_unicode_func());
+    scope.define("regex", regex_func());
+    scope.define("upper", crate::text::upper_func());
+    scope.define("lower", crate::text::lower_func());
     scope
 )]
 pub fn str(
@@ -664,19 +760,27 @@ pub fn regex(
     Regex::new(&regex.v).at(regex.span)
 }
 
-/// Create an array consisting of a sequence of numbers.
+/// Create a sequence of numbers.
 ///
 /// If you pass just one positional parameter, it is interpreted as the `end` of
 /// the range. If you pass two, they describe the `start` and `end` of the
 /// range.
 ///
+/// The numbers aren't stored, but computed when they are needed, so that
+/// looping over a large range is cheap. Ranges have an array's `len`, `first`,
+/// `last`, `at`, `slice`, `contains` and `rev` methods. All other array methods
+/// work on ranges, too, but produce arrays. Otherwise, a range behaves exactly
+/// like an array with the same numbers: It has the type `{"array"}`, displays
+/// like one and can be passed wherever an array is expected.
+///
 /// ## Example { #example }
 /// ```example
 /// #range(5) \
 /// #range(2, 5) \
 /// #range(20, step: 4) \
 /// #range(21, step: 4) \
-/// #range(5, 2, step: -1)
+/// #range(5, 2, step: -1) \
+/// #range(1000000).at(-1)
 /// ```
 ///
 /// Display: Range
@@ -696,7 +800,7 @@ pub fn range(
     step: NonZeroI64,
     /// The arguments.
     args: Args,
-) -> SourceResult<Array> {
+) -> SourceResult<Range> {
     let mut args = args;
     let first = args.expect::<i64>("end")?;
     let (start, end) = match args.eat::<i64>()? {
@@ -704,17 +808,7 @@ pub fn range(
         None => (0, first),
     };
 
-    let step = step.get();
-
-    let mut x = start;
-    let mut array = Array::new();
-
-    while x.cmp(&end) == 0.cmp(&step) {
-        array.push(Value::Int(x));
-        x += step;
-    }
-
-    Ok(array)
+    Ok(Range::new(start, end, step))
 }
 
 #[cfg(test)]
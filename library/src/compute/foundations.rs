@@ -1,3 +1,6 @@
+use typst::eval::EvalMode;
+use typst::model::UserElemFunc;
+
 use crate::prelude::*;
 
 /// Determine a value's type.
@@ -196,7 +199,25 @@ pub fn assert_ne(
 /// ```example
 /// #eval("1 + 1") \
 /// #eval("(1, 2, 3, 4)").len() \
-/// #eval("[*Strong text*]")
+/// #eval("[*Strong text*]") \
+/// #eval("*Markup!*", mode: "markup")
+/// ```
+///
+/// ## Sandboxing { #sandboxing }
+/// A template can evaluate content provided by its users in a restricted
+/// environment. With `{sandbox: true}`, the code cannot use the standard
+/// library and only has access to the definitions passed in the `scope`.
+/// Markup like emphasis and headings still works, since it doesn't depend on
+/// the scope.
+///
+/// ```example
+/// #let user = "_Hello_ from #name!"
+/// #eval(
+///   user,
+///   mode: "markup",
+///   scope: (name: "the sandbox"),
+///   sandbox: true,
+/// )
 /// ```
 ///
 /// Display: Evaluate
@@ -207,9 +228,106 @@ pub fn eval(
     ///
     /// The code in the string cannot interact with the file system.
     source: Spanned<String>,
+    /// The syntactical mode in which the string is parsed.
+    #[named]
+    #[default(EvalMode::Code)]
+    mode: EvalMode,
+    /// Definitions that are made available to the code, in addition to the
+    /// standard library.
+    ///
+    /// ```example
+    /// #eval("x + y", scope: (x: 1, y: 2))
+    /// ```
+    #[named]
+    #[default]
+    scope: Dict,
+    /// Whether to hide the standard library from the code, so that it can
+    /// only use the definitions in the `scope`.
+    #[named]
+    #[default(false)]
+    sandbox: bool,
+    /// Whether the code can use the variables that are visible where `eval`
+    /// is called. The code works on a copy of them, so it cannot modify the
+    /// original variables. Definitions in the `scope` take precedence.
+    ///
+    /// Note that a function body can only see the variables the function
+    /// itself refers to.
+    ///
+    /// ```example
+    /// #let name = "World"
+    /// #eval("[Hello, #name!]", inherit: true)
+    /// ```
+    #[named]
+    #[default(false)]
+    inherit: bool,
     /// The virtual machine.
     vm: &mut Vm,
 ) -> SourceResult<Value> {
     let Spanned { v: text, span } = source;
-    typst::eval::eval_string(vm.world(), &text, span)
+    let mut defs = if inherit { vm.snapshot() } else { Scope::new() };
+    for (key, value) in scope {
+        defs.define(key, value);
+    }
+    typst::eval::eval_string(vm.world(), &text, span, mode, defs, sandbox)
+}
+
+/// Declare a new element type.
+///
+/// The returned function constructs instances of the element. Like the
+/// functions of built-in elements, it can also be used as a selector in show
+/// rules, for counters and in queries. The fields of an instance can be
+/// accessed in show rules just like the fields of built-in elements.
+///
+/// ## Example { #example }
+/// ```example
+/// #let theorem = element(
+///   "theorem",
+///   fields: (title: "content", body: "content"),
+///   display: it => block[*Theorem #it.title.* #it.body],
+/// )
+///
+/// #show theorem: set text(blue)
+/// #theorem(title: [Pythagoras])[$a^2 + b^2 = c^2$]
+/// #locate(loc => [#query(theorem, loc).len() theorem])
+/// ```
+///
+/// Display: Element
+/// Category: foundations
+#[func]
+pub fn element(
+    /// The element's name.
+    name: EcoString,
+    /// The element's fields, mapping their names to the names of their
+    /// expected types as returned by [`type`]($func/type), or `{auto}` to
+    /// accept any value.
+    ///
+    /// Fields can then be given positionally in this order or by name. Fields
+    /// that are not given are `{none}`. A field of type `{"content"}` also
+    /// accepts strings and other values that can be displayed.
+    #[named]
+    #[default]
+    fields: Dict,
+    /// How to display an instance of the element if no show rule applies.
+    /// Receives the instance and returns content.
+    ///
+    /// Without a display function, the `body` field is displayed.
+    #[named]
+    #[default]
+    display: Option<Func>,
+    /// The callsite span.
+    span: Span,
+) -> SourceResult<Func> {
+    let mut list = vec![];
+    for (key, value) in fields {
+        if key.as_str() == "element" {
+            bail!(span, "the field name `element` is reserved");
+        }
+        let ty = match value {
+            Value::Auto => None,
+            v => Some(v.cast::<EcoString>().at(span)?),
+        };
+        list.push((EcoString::from(key.as_str()), ty));
+    }
+
+    Ok(UserElemFunc { name, fields: list, display, span }.into())
 }
@@ -1,9 +1,11 @@
 //! Computational functions.
 
+pub mod array;
 pub mod calc;
 mod construct;
 mod data;
 mod foundations;
+mod sys;
 
 pub use self::construct::*;
 pub use self::data::*;
@@ -12,18 +14,20 @@ pub use self::foundations::*;
 use crate::prelude::*;
 
 /// Hook up all compute definitions.
-pub(super) fn define(global: &mut Scope) {
+pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define("type", type_func());
     global.define("repr", repr_func());
     global.define("panic", panic_func());
     global.define("assert", assert_func());
     global.define("eval", eval_func());
+    global.define("element", element_func());
     global.define("int", int_func());
     global.define("float", float_func());
     global.define("luma", luma_func());
     global.define("rgb", rgb_func());
-    global.define("cmyk", cmyk_func());
     global.define("datetime", datetime_func());
+    global.define("duration", duration_func());
+    global.define("version", version_func());
     global.define("symbol", symbol_func());
     global.define("str", str_func());
     global.define("label", label_func());
@@ -35,5 +39,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define("toml", toml_func());
     global.define("yaml", yaml_func());
     global.define("xml", xml_func());
+    global.define("array", array::module());
     global.define("calc", calc::module());
+    global.define("sys", sys::module(inputs));
 }
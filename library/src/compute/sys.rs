@@ -0,0 +1,81 @@
+//! Information about the compiler.
+
+use std::str::FromStr;
+
+use typst::eval::{Module, Scope, Version};
+
+use crate::prelude::*;
+
+/// A module with information about the compiler.
+///
+/// The inputs are defined as `sys.inputs`, a dictionary of strings that the
+/// compiler was invoked with, for example from a project manifest.
+pub fn module(inputs: Dict) -> Module {
+    let mut scope = Scope::new();
+    scope.define(
+        "version",
+        Version::from_str(env!("CARGO_PKG_VERSION")).unwrap_or_default(),
+    );
+    scope.define("target", target_func());
+    scope.define("inputs", inputs);
+    Module::new("sys").with_scope(scope)
+}
+
+/// The format the document is being exported into.
+///
+/// Returns `{"pdf"}`, `{"png"}` or `{"html"}`. This lets templates include
+/// interactive elements only where they work or provide alternative content
+/// for other formats. Content that depends on the target is recompiled when
+/// the target changes, while everything else is shared between exports.
+///
+/// ## Example
+/// ```example
+/// #if sys.target() == "pdf" [
+///   Click #link("https://typst.app")[here].
+/// ] else [
+///   Visit typst.app.
+/// ]
+/// ```
+///
+/// Display: Target
+/// Category: foundations
+#[func]
+pub fn target(
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> Str {
+    vm.world().target().name().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::export::Target;
+
+    use crate::tests::TestWorld;
+
+    #[test]
+    fn test_html_export() {
+        let world = TestWorld {
+            target: Target::Html,
+            ..TestWorld::new(
+                "#set document(title: \"Report\")\n\
+                 #if sys.target() == \"html\" [Web] else [Print]\n\
+                 #pagebreak()\n\
+                 #link(\"https://typst.app\")[Link]",
+            )
+        };
+        let document = world.compile();
+
+        // The content for the HTML target was chosen and every page is
+        // embedded with its links.
+        let words: Vec<_> = typst::ide::words(&world, &document)
+            .into_iter()
+            .map(|word| word.text.to_string())
+            .collect();
+        assert_eq!(words, ["Web", "Link"]);
+        let html = typst::export::html(&document);
+        assert!(html.contains("<title>Report</title>"));
+        assert_eq!(html.matches("<svg ").count(), 2);
+        assert!(html.contains("xlink:href=\"https://typst.app\""));
+    }
+}
@@ -58,7 +58,10 @@ pub struct AlignElem {
 }
 
 impl Show for AlignElem {
-    #[tracing::instrument(name = "AlignElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "AlignElem::show", skip_all)
+    )]
     fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         Ok(self
             .body()
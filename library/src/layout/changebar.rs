@@ -0,0 +1,120 @@
+use crate::prelude::*;
+use crate::text::TextElem;
+
+/// Mark revised content with a bar in the margin.
+///
+/// Draws a vertical line alongside everything the content produces, also
+/// when it breaks across pages. The bar is placed outside of the content at
+/// the start side of the text direction. This is typically used in legal and
+/// standards documents to mark revisions.
+///
+/// ## Example { #example }
+/// ```example
+/// #lorem(10)
+///
+/// #changebar[
+///   This paragraph was revised.
+///   #lorem(15)
+/// ]
+/// ```
+///
+/// Display: Change Bar
+/// Category: layout
+#[element(Layout)]
+pub struct ChangebarElem {
+    /// How to stroke the bar. See the [line's
+    /// documentation]($func/line.stroke) for more details.
+    ///
+    /// ```example
+    /// #changebar(stroke: 2pt + red)[
+    ///   Revised in version 2.
+    /// ]
+    /// ```
+    #[resolve]
+    #[fold]
+    pub stroke: PartialStroke,
+
+    /// The distance between the bar and the content.
+    #[resolve]
+    #[default(Em::new(1.0).into())]
+    pub gap: Length,
+
+    /// The content to mark.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for ChangebarElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ChangebarElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let mut fragment = self.body().layout(vt, styles, regions)?;
+        let stroke = self.stroke(styles).unwrap_or_default();
+        let gap = self.gap(styles);
+        let rtl = TextElem::dir_in(styles) == Dir::RTL;
+
+        // Draw one bar per region the content was laid out in.
+        for frame in &mut fragment {
+            if frame.height().is_zero() {
+                continue;
+            }
+
+            let x = if rtl { frame.width() + gap } else { -gap };
+            let shape =
+                Geometry::Line(Point::with_y(frame.height())).stroked(stroke.clone());
+            frame.push(Point::with_x(x), FrameItem::Shape(shape, self.span()));
+        }
+
+        Ok(fragment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    #[test]
+    fn test_changebar() {
+        let document = compile(
+            "#set page(width: 100pt, height: 80pt, margin: 10pt)\n\
+             Before.\n\n\
+             #changebar(stroke: 2pt, gap: 4pt)[#lorem(30)]",
+        );
+
+        // Every page the content breaks across gets one bar in the margin
+        // that spans the content on that page.
+        let pt = Abs::pt;
+        assert!(document.pages.len() > 1);
+        for (i, page) in document.pages.iter().enumerate() {
+            let bars: Vec<_> = items(page)
+                .into_iter()
+                .filter_map(|(pos, item)| match item {
+                    FrameItem::Shape(shape, _) => match shape.geometry {
+                        Geometry::Line(delta) => Some((pos, delta)),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(bars.len(), 1);
+            let (pos, delta) = bars[0];
+            assert_eq!(pos.x, pt(6.0));
+            assert_eq!(delta.x, Abs::zero());
+            assert!(delta.y > Abs::zero());
+            assert!(pos.y + delta.y <= pt(70.0) + pt(0.01));
+            if i == 0 {
+                assert!(pos.y > pt(10.0));
+            } else {
+                assert_eq!(pos.y, pt(10.0));
+            }
+        }
+    }
+}
@@ -50,7 +50,10 @@ pub struct ColumnsElem {
 }
 
 impl Layout for ColumnsElem {
-    #[tracing::instrument(name = "ColumnsElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ColumnsElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -81,9 +84,13 @@ impl Layout for ColumnsElem {
             size: Size::new(width, regions.size.y),
             full: regions.full,
             backlog: &backlog,
+            widths: &[],
             last: regions.last,
+            cycle: &[],
+            cycle_widths: &[],
             expand: Axes::new(true, regions.expand.y),
             root: regions.root,
+            limit: regions.limit.map(|limit| limit.saturating_mul(columns)),
         };
 
         // Layout the children.
@@ -3,6 +3,7 @@ use typst::eval::AutoValue;
 use super::VElem;
 use crate::layout::Spacing;
 use crate::prelude::*;
+use crate::visualize::Shadow;
 
 /// An inline-level container that sizes content.
 ///
@@ -91,6 +92,18 @@ pub struct BoxElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// A drop shadow for the box. See the [shadow's
+    /// documentation]($func/shadow) for more details.
+    ///
+    /// ```example
+    /// #box(
+    ///   fill: white,
+    ///   inset: 4pt,
+    ///   shadow: shadow(blur: 3pt),
+    /// )[Lifted]
+    /// ```
+    pub shadow: Option<Shadow>,
+
     /// Whether to clip the content inside the box.
     #[default(false)]
     pub clip: bool,
@@ -101,7 +114,10 @@ pub struct BoxElem {
 }
 
 impl Layout for BoxElem {
-    #[tracing::instrument(name = "BoxElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "BoxElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -154,12 +170,17 @@ impl Layout for BoxElem {
         let stroke = self.stroke(styles).map(|s| s.map(PartialStroke::unwrap_or_default));
 
         // Add fill and/or stroke.
+        let outset = self.outset(styles);
+        let radius = self.radius(styles);
         if fill.is_some() || stroke.iter().any(Option::is_some) {
-            let outset = self.outset(styles);
-            let radius = self.radius(styles);
             frame.fill_and_stroke(fill, stroke, outset, radius, self.span());
         }
 
+        // Add the shadow below everything else.
+        if let Some(shadow) = self.shadow(styles) {
+            shadow.apply(&mut frame, styles, outset, radius, self.span());
+        }
+
         // Apply metadata.
         frame.meta(styles, false);
 
@@ -271,6 +292,10 @@ pub struct BlockElem {
     #[fold]
     pub outset: Sides<Option<Rel<Length>>>,
 
+    /// A drop shadow for the block. See the [shadow's
+    /// documentation]($func/shadow) for more details.
+    pub shadow: Option<Shadow>,
+
     /// The spacing around this block. This is shorthand to set `above` and
     /// `below` to the same value.
     ///
@@ -333,7 +358,10 @@ pub struct BlockElem {
 }
 
 impl Layout for BlockElem {
-    #[tracing::instrument(name = "BlockElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "BlockElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -368,6 +396,8 @@ impl Layout for BlockElem {
 
             let mut pod = regions;
             pod.size.x = size.x;
+            pod.widths = &[];
+            pod.cycle_widths = &[];
             pod.expand = expand;
 
             if expand.y {
@@ -394,6 +424,7 @@ impl Layout for BlockElem {
                 pod.size.y = heights[0];
                 pod.backlog = &heights[1..];
                 pod.last = None;
+                pod.cycle = &[];
             }
 
             let mut frames = body.layout(vt, styles, pod)?.into_frames();
@@ -420,8 +451,9 @@ impl Layout for BlockElem {
         let fill = self.fill(styles);
         let stroke = self.stroke(styles).map(|s| s.map(PartialStroke::unwrap_or_default));
 
-        // Add fill and/or stroke.
-        if fill.is_some() || stroke.iter().any(Option::is_some) {
+        // Add fill, stroke and/or shadow.
+        let shadow = self.shadow(styles);
+        if fill.is_some() || stroke.iter().any(Option::is_some) || shadow.is_some() {
             let mut skip = false;
             if let [first, rest @ ..] = frames.as_slice() {
                 skip = first.is_empty() && rest.iter().any(|frame| !frame.is_empty());
@@ -437,6 +469,9 @@ impl Layout for BlockElem {
                     radius,
                     self.span(),
                 );
+                if let Some(shadow) = &shadow {
+                    shadow.apply(frame, styles, outset, radius, self.span());
+                }
             }
         }
 
@@ -197,7 +197,10 @@ pub struct EnumElem {
 }
 
 impl Layout for EnumElem {
-    #[tracing::instrument(name = "EnumElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "EnumElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
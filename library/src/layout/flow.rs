@@ -25,7 +25,10 @@ pub struct FlowElem {
 }
 
 impl Layout for FlowElem {
-    #[tracing::instrument(name = "FlowElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FlowElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -35,6 +38,10 @@ impl Layout for FlowElem {
         let mut layouter = FlowLayouter::new(regions, styles);
 
         for mut child in &self.children() {
+            if layouter.truncated {
+                break;
+            }
+
             let outer = styles;
             let mut styles = styles;
             if let Some((elem, map)) = child.to_styled() {
@@ -69,8 +76,13 @@ impl Layout for FlowElem {
             } else if child.can::<dyn Layout>() {
                 layouter.layout_multiple(vt, child, styles)?;
             } else if child.is::<ColbreakElem>() {
-                if !layouter.regions.backlog.is_empty() || layouter.regions.last.is_some()
+                if (!layouter.regions.backlog.is_empty()
+                    || !layouter.regions.cycle.is_empty()
+                    || layouter.regions.last.is_some())
+                    && !layouter.truncate()
                 {
+                    layouter.explanation =
+                        Some(Break::new(BreakKind::Region, "explicit column break"));
                     layouter.finish_region()?;
                 }
             } else {
@@ -105,6 +117,11 @@ struct FlowLayouter<'a> {
     footnote_config: FootnoteConfig,
     /// Finished frames for previous regions.
     finished: Vec<Frame>,
+    /// Why the current region ends, once that is decided.
+    explanation: Option<Break>,
+    /// Whether the flow stopped laying out its children because the world
+    /// needs no more pages.
+    truncated: bool,
 }
 
 /// Cached footnote configuration.
@@ -166,11 +183,27 @@ impl<'a> FlowLayouter<'a> {
                 gap: FootnoteEntry::gap_in(styles),
             },
             finished: vec![],
+            explanation: None,
+            truncated: false,
+        }
+    }
+
+    /// Stop laying out if this is the root flow and it has filled the last
+    /// region the world needs. Returns whether the flow stopped.
+    fn truncate(&mut self) -> bool {
+        if self.root && self.regions.limit.is_some_and(|limit| limit <= 1) {
+            self.explanation =
+                Some(Break::new(BreakKind::Region, "no more pages needed"));
+            self.truncated = true;
         }
+        self.truncated
     }
 
     /// Layout vertical spacing.
-    #[tracing::instrument(name = "FlowLayouter::layout_spacing", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FlowLayouter::layout_spacing", skip_all)
+    )]
     fn layout_spacing(
         &mut self,
         vt: &mut Vt,
@@ -190,7 +223,10 @@ impl<'a> FlowLayouter<'a> {
     }
 
     /// Layout a paragraph.
-    #[tracing::instrument(name = "FlowLayouter::layout_par", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FlowLayouter::layout_par", skip_all)
+    )]
     fn layout_par(
         &mut self,
         vt: &mut Vt,
@@ -215,6 +251,16 @@ impl<'a> FlowLayouter<'a> {
 
         if let Some(first) = lines.first() {
             if !self.regions.size.y.fits(first.height()) && !self.regions.in_last() {
+                if self.truncate() {
+                    return Ok(());
+                }
+                self.explanation = Some(Break {
+                    space: Some((first.height(), self.regions.size.y)),
+                    ..Break::new(
+                        BreakKind::Region,
+                        "first line of paragraph does not fit",
+                    )
+                });
                 let carry: Vec<_> = self.items.drain(sticky..).collect();
                 self.finish_region()?;
                 for item in carry {
@@ -239,7 +285,10 @@ impl<'a> FlowLayouter<'a> {
     }
 
     /// Layout into a single region.
-    #[tracing::instrument(name = "FlowLayouter::layout_single", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FlowLayouter::layout_single", skip_all)
+    )]
     fn layout_single(
         &mut self,
         vt: &mut Vt,
@@ -272,6 +321,9 @@ impl<'a> FlowLayouter<'a> {
             }
         } else if self.regions.is_full() {
             // Skip directly if region is already full.
+            if self.truncate() {
+                return Ok(());
+            }
             self.finish_region()?;
         }
 
@@ -304,6 +356,9 @@ impl<'a> FlowLayouter<'a> {
             }
 
             if i > 0 {
+                if self.truncate() {
+                    break;
+                }
                 self.finish_region()?;
             }
 
@@ -326,8 +381,15 @@ impl<'a> FlowLayouter<'a> {
     }
 
     /// Layout a finished frame.
-    #[tracing::instrument(name = "FlowLayouter::layout_item", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FlowLayouter::layout_item", skip_all)
+    )]
     fn layout_item(&mut self, vt: &mut Vt, item: FlowItem) -> SourceResult<()> {
+        if self.truncated {
+            return Ok(());
+        }
+
         match item {
             FlowItem::Absolute(v, weak) => {
                 if weak
@@ -344,6 +406,13 @@ impl<'a> FlowLayouter<'a> {
             FlowItem::Frame { ref frame, movable, .. } => {
                 let size = frame.size();
                 if !self.regions.size.y.fits(size.y) && !self.regions.in_last() {
+                    if self.truncate() {
+                        return Ok(());
+                    }
+                    self.explanation = Some(Break {
+                        space: Some((size.y, self.regions.size.y)),
+                        ..Break::new(BreakKind::Region, "block does not fit")
+                    });
                     self.finish_region()?;
                 }
 
@@ -353,6 +422,10 @@ impl<'a> FlowLayouter<'a> {
                     find_footnotes(&mut notes, frame);
                     self.items.push(item);
                     if !self.handle_footnotes(vt, &mut notes, true, false)? {
+                        self.explanation = Some(Break::new(
+                            BreakKind::Region,
+                            "block with its footnotes does not fit",
+                        ));
                         let item = self.items.pop();
                         self.finish_region()?;
                         self.items.extend(item);
@@ -451,6 +524,15 @@ impl<'a> FlowLayouter<'a> {
             }
         }
 
+        // Mark the region's bounds, as far as they are finite.
+        let region =
+            self.initial.zip(size).map(|(r, s)| if r.is_finite() { r } else { s });
+        output.mark_region(region);
+
+        if let Some(explanation) = self.explanation.take() {
+            output.explain(explanation);
+        }
+
         // Advance to the next region.
         self.finished.push(output);
         self.regions.next();
@@ -474,7 +556,7 @@ impl<'a> FlowLayouter<'a> {
 
 impl FlowLayouter<'_> {
     /// Processes all footnotes in the frame.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn handle_footnotes(
         &mut self,
         vt: &mut Vt,
@@ -542,7 +624,7 @@ impl FlowLayouter<'_> {
     }
 
     /// Layout and save the footnote separator, typically a line.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn layout_footnote_separator(&mut self, vt: &mut Vt) -> SourceResult<()> {
         let expand = Axes::new(self.regions.expand.x, false);
         let pod = Regions::one(self.regions.base(), expand);
@@ -561,7 +643,7 @@ impl FlowLayouter<'_> {
 }
 
 /// Finds all footnotes in the frame.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn find_footnotes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
     for (_, item) in frame.items() {
         match item {
@@ -576,3 +658,110 @@ fn find_footnotes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    /// The explained breaks in a document with the indices of their pages.
+    fn breaks(document: &Document) -> Vec<(usize, Break)> {
+        let mut breaks = vec![];
+        for (i, page) in document.pages.iter().enumerate() {
+            for (_, item) in items(page) {
+                if let FrameItem::Meta(Meta::Break(explanation), _) = item {
+                    breaks.push((i, explanation.clone()));
+                }
+            }
+        }
+        breaks
+    }
+
+    #[test]
+    fn test_break_explanations() {
+        let text = "#set page(width: 120pt, height: 80pt)\n#lorem(40)";
+        let document = compile(text);
+        assert!(document.pages.len() >= 2);
+
+        // The explanations survive in the final frames, in the order of the
+        // breaks, and are the same every time.
+        let found = breaks(&document);
+        assert_eq!(found, breaks(&compile(text)));
+        let reasons: Vec<_> = found
+            .iter()
+            .map(|(i, explanation)| (*i, explanation.kind, explanation.reason))
+            .collect();
+        let orphan = "kept the first two lines together";
+        let widow = "kept the last two lines together";
+        assert!(reasons.contains(&(0, BreakKind::Line, "next word does not fit")));
+        assert!(reasons.contains(&(0, BreakKind::Orphan, orphan)));
+        assert!(reasons.contains(&(0, BreakKind::Region, "block does not fit")));
+        assert!(reasons.iter().any(|&(i, kind, _)| i == 1 && kind == BreakKind::Line));
+        assert!(reasons
+            .iter()
+            .any(|&(i, kind, _)| i == 1 && kind == BreakKind::Paragraph));
+        assert!(reasons
+            .iter()
+            .any(|&(_, kind, reason)| kind == BreakKind::Widow && reason == widow));
+        assert_eq!(
+            reasons.last().unwrap().2,
+            "end of page run (page break, new page settings or end of document)"
+        );
+
+        // Lines carry their text and regions the space that did not fit.
+        let (_, first) = &found[0];
+        assert_eq!(first.kind, BreakKind::Line);
+        assert!(first.text.as_ref().unwrap().starts_with("Lorem ipsum"));
+        for (_, explanation) in &found {
+            match explanation.kind {
+                BreakKind::Line | BreakKind::Paragraph => {
+                    assert!(explanation.text.is_some())
+                }
+                BreakKind::Region if explanation.reason == "block does not fit" => {
+                    assert!(explanation.space.is_some())
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_debug_regions() {
+        let mut document = compile(
+            "#set page(width: 100pt, height: 100pt, margin: 10pt, columns: 2)\n\
+             #lorem(60)",
+        );
+
+        // Each column is a region with the full height of the page's body.
+        let regions: Vec<_> = items(&document.pages[0])
+            .into_iter()
+            .filter_map(|(_, item)| match item {
+                FrameItem::Meta(Meta::Region, size) => Some(*size),
+                _ => None,
+            })
+            .collect();
+        let height = Abs::pt(80.0);
+        let columns = regions
+            .iter()
+            .filter(|size| size.y == height && size.x < height / 2.0)
+            .count();
+        assert_eq!(columns, 2);
+
+        // The debug outlines draw every region dashed.
+        let dashed = |frame: &Frame| {
+            items(frame)
+                .into_iter()
+                .filter(|(_, item)| match item {
+                    FrameItem::Shape(shape, _) => shape
+                        .stroke
+                        .as_ref()
+                        .is_some_and(|stroke| stroke.dash_pattern.is_some()),
+                    _ => false,
+                })
+                .count()
+        };
+        assert_eq!(dashed(&document.pages[0]), 0);
+        document.pages[0].debug_outlines();
+        assert_eq!(dashed(&document.pages[0]), regions.len());
+    }
+}
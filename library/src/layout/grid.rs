@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use crate::prelude::*;
 use crate::text::TextElem;
 
@@ -102,7 +104,10 @@ pub struct GridElem {
 }
 
 impl Layout for GridElem {
-    #[tracing::instrument(name = "GridElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "GridElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -136,10 +141,31 @@ cast! {
     values: Array => Self(values.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
 }
 
+/// How many lazily generated rows are kept at a time.
+const CHUNK: usize = 64;
+
+/// Rows that follow a grid's cells and are generated in chunks while the grid
+/// is laid out.
+pub struct LazyRows<'a> {
+    /// How many rows there are.
+    pub len: usize,
+    /// Generates the cells of the rows in a range in row-major order, with
+    /// exactly one cell per column.
+    pub generate: &'a mut dyn FnMut(&mut Vt, Range<usize>) -> SourceResult<Vec<Content>>,
+}
+
 /// Performs grid layout.
 pub struct GridLayouter<'a> {
     /// The grid cells.
     cells: &'a [Content],
+    /// The number of content rows the cells occupy.
+    explicit: usize,
+    /// The rows that follow the cells.
+    lazy: Option<LazyRows<'a>>,
+    /// The cells of the currently generated lazy rows.
+    chunk: Vec<Content>,
+    /// The index of the first lazy row in `chunk`.
+    start: usize,
     /// Whether this is an RTL grid.
     is_rtl: bool,
     /// Whether this grid has gutters.
@@ -205,6 +231,21 @@ impl<'a> GridLayouter<'a> {
         cells: &'a [Content],
         regions: Regions<'a>,
         styles: StyleChain<'a>,
+    ) -> Self {
+        Self::with_lazy_rows(tracks, gutter, cells, None, regions, styles)
+    }
+
+    /// Create a new grid layouter whose cells are followed by rows that are
+    /// only generated while the grid is laid out.
+    ///
+    /// The lazy rows start in a new row after the cells.
+    pub fn with_lazy_rows(
+        tracks: Axes<&[Sizing]>,
+        gutter: Axes<&[Sizing]>,
+        cells: &'a [Content],
+        lazy: Option<LazyRows<'a>>,
+        regions: Regions<'a>,
+        styles: StyleChain<'a>,
     ) -> Self {
         let mut cols = vec![];
         let mut rows = vec![];
@@ -214,10 +255,11 @@ impl<'a> GridLayouter<'a> {
 
         // Number of content rows: At least as many as given, but also at least
         // as many as needed to place each item.
+        let len = cells.len();
+        let explicit = len / c + (len % c).clamp(0, 1);
         let r = {
-            let len = cells.len();
             let given = tracks.y.len();
-            let needed = len / c + (len % c).clamp(0, 1);
+            let needed = explicit + lazy.as_ref().map_or(0, |lazy| lazy.len);
             given.max(needed)
         };
 
@@ -263,6 +305,10 @@ impl<'a> GridLayouter<'a> {
 
         Self {
             cells,
+            explicit,
+            lazy,
+            chunk: vec![],
+            start: 0,
             is_rtl,
             has_gutter,
             rows,
@@ -289,6 +335,7 @@ impl<'a> GridLayouter<'a> {
                 self.finish_region(vt)?;
             }
 
+            self.load(vt, y)?;
             match self.rows[y] {
                 Sizing::Auto => self.layout_auto_row(vt, y)?,
                 Sizing::Rel(v) => self.layout_relative_row(vt, v, y)?,
@@ -306,7 +353,10 @@ impl<'a> GridLayouter<'a> {
     }
 
     /// Determine all column sizes.
-    #[tracing::instrument(name = "GridLayouter::measure_columns", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "GridLayouter::measure_columns", skip_all)
+    )]
     fn measure_columns(&mut self, vt: &mut Vt) -> SourceResult<()> {
         // Sum of sizes of resolved relative tracks.
         let mut rel = Abs::zero();
@@ -357,18 +407,20 @@ impl<'a> GridLayouter<'a> {
         vt: &mut Vt,
         available: Abs,
     ) -> SourceResult<(Abs, usize)> {
-        let mut auto = Abs::zero();
-        let mut count = 0;
+        let autos: Vec<usize> = (0..self.cols.len())
+            .filter(|&x| self.cols[x] == Sizing::Auto)
+            .collect();
+        if autos.is_empty() {
+            return Ok((Abs::zero(), 0));
+        }
 
         // Determine size of auto columns by laying out all cells in those
-        // columns, measuring them and finding the largest one.
-        for (x, &col) in self.cols.iter().enumerate() {
-            if col != Sizing::Auto {
-                continue;
-            }
-
-            let mut resolved = Abs::zero();
-            for y in 0..self.rows.len() {
+        // columns, measuring them and finding the largest one. This goes row
+        // by row so that each chunk of lazy rows is only generated once.
+        let mut resolved = vec![Abs::zero(); self.cols.len()];
+        for y in 0..self.rows.len() {
+            self.load(vt, y)?;
+            for &x in &autos {
                 if let Some(cell) = self.cell(x, y) {
                     // For relative rows, we can already resolve the correct
                     // base and for auto and fr we could only guess anyway.
@@ -382,16 +434,18 @@ impl<'a> GridLayouter<'a> {
                     let size = Size::new(available, height);
                     let pod = Regions::one(size, Axes::splat(false));
                     let frame = cell.measure(vt, self.styles, pod)?.into_frame();
-                    resolved.set_max(frame.width());
+                    resolved[x].set_max(frame.width());
                 }
             }
+        }
 
-            self.rcols[x] = resolved;
-            auto += resolved;
-            count += 1;
+        let mut auto = Abs::zero();
+        for &x in &autos {
+            self.rcols[x] = resolved[x];
+            auto += resolved[x];
         }
 
-        Ok((auto, count))
+        Ok((auto, autos.len()))
     }
 
     /// Distribute remaining space to fractional columns.
@@ -449,6 +503,7 @@ impl<'a> GridLayouter<'a> {
             Some(resolved) => resolved,
             None => {
                 self.finish_region(vt)?;
+                self.load(vt, y)?;
                 self.measure_auto_row(vt, y, false)?.unwrap()
             }
         };
@@ -504,6 +559,8 @@ impl<'a> GridLayouter<'a> {
             if let Some(cell) = self.cell(x, y) {
                 let mut pod = self.regions;
                 pod.size.x = rcol;
+                pod.widths = &[];
+                pod.cycle_widths = &[];
 
                 let frames = cell.measure(vt, self.styles, pod)?.into_frames();
 
@@ -661,6 +718,7 @@ impl<'a> GridLayouter<'a> {
                 Row::Fr(v, y) => {
                     let remaining = self.regions.full - used;
                     let height = v.share(fr, remaining);
+                    self.load(vt, y)?;
                     (self.layout_single_row(vt, height, y)?, y)
                 }
             };
@@ -683,7 +741,7 @@ impl<'a> GridLayouter<'a> {
     ///
     /// Returns `None` if it's a gutter cell.
     #[track_caller]
-    fn cell(&self, mut x: usize, y: usize) -> Option<&'a Content> {
+    fn cell(&self, mut x: usize, y: usize) -> Option<&Content> {
         assert!(x < self.cols.len());
         assert!(y < self.rows.len());
 
@@ -695,14 +753,54 @@ impl<'a> GridLayouter<'a> {
         if self.has_gutter {
             // Even columns and rows are children, odd ones are gutter.
             if x % 2 == 0 && y % 2 == 0 {
-                let c = 1 + self.cols.len() / 2;
-                self.cells.get((y / 2) * c + x / 2)
+                self.content(x / 2, y / 2)
             } else {
                 None
             }
         } else {
-            let c = self.cols.len();
-            self.cells.get(y * c + x)
+            self.content(x, y)
+        }
+    }
+
+    /// Get the content of the cell in content column `x` and row `y`.
+    ///
+    /// Lazy rows must have been loaded with [`load`](Self::load).
+    fn content(&self, x: usize, y: usize) -> Option<&Content> {
+        match y.checked_sub(self.explicit) {
+            Some(i) if self.lazy.is_some() => {
+                self.chunk.get(i.checked_sub(self.start)? * self.content_cols() + x)
+            }
+            _ => self.cells.get(y * self.content_cols() + x),
+        }
+    }
+
+    /// Generate the chunk of lazy rows that contains row `y` unless it is
+    /// generated already.
+    fn load(&mut self, vt: &mut Vt, y: usize) -> SourceResult<()> {
+        if self.has_gutter && y % 2 == 1 {
+            return Ok(());
+        }
+
+        let y = if self.has_gutter { y / 2 } else { y };
+        let c = self.content_cols();
+        let Some(lazy) = &mut self.lazy else { return Ok(()) };
+        let Some(i) = y.checked_sub(self.explicit) else { return Ok(()) };
+        if i >= lazy.len || (self.start..self.start + self.chunk.len() / c).contains(&i) {
+            return Ok(());
+        }
+
+        let end = (i + CHUNK).min(lazy.len);
+        self.chunk = (lazy.generate)(vt, i..end)?;
+        self.start = i;
+        Ok(())
+    }
+
+    /// The number of columns without gutter columns.
+    fn content_cols(&self) -> usize {
+        if self.has_gutter {
+            1 + self.cols.len() / 2
+        } else {
+            self.cols.len()
         }
     }
 }
@@ -119,7 +119,10 @@ pub struct ListElem {
 }
 
 impl Layout for ListElem {
-    #[tracing::instrument(name = "ListElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ListElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
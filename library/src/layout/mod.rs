@@ -1,6 +1,7 @@
 //! Composable layouts.
 
 mod align;
+mod changebar;
 mod columns;
 mod container;
 #[path = "enum.rs"]
@@ -11,6 +12,7 @@ mod grid;
 mod hide;
 mod list;
 mod measure;
+mod overlay;
 mod pad;
 mod page;
 mod par;
@@ -24,6 +26,7 @@ mod terms;
 mod transform;
 
 pub use self::align::*;
+pub use self::changebar::*;
 pub use self::columns::*;
 pub use self::container::*;
 pub use self::enum_::*;
@@ -33,6 +36,7 @@ pub use self::grid::*;
 pub use self::hide::*;
 pub use self::list::*;
 pub use self::measure::*;
+pub use self::overlay::*;
 pub use self::pad::*;
 pub use self::page::*;
 pub use self::par::*;
@@ -67,6 +71,8 @@ use crate::visualize::{
 pub(super) fn define(global: &mut Scope) {
     global.define("page", PageElem::func());
     global.define("pagebreak", PagebreakElem::func());
+    global.define("page-override", PageOverrideElem::func());
+    global.define("watermark", watermark_func());
     global.define("v", VElem::func());
     global.define("par", ParElem::func());
     global.define("parbreak", ParbreakElem::func());
@@ -89,6 +95,9 @@ pub(super) fn define(global: &mut Scope) {
     global.define("scale", ScaleElem::func());
     global.define("rotate", RotateElem::func());
     global.define("hide", HideElem::func());
+    global.define("overlay", OverlayElem::func());
+    global.define("underlay", UnderlayElem::func());
+    global.define("changebar", ChangebarElem::func());
     global.define("measure", measure_func());
     global.define("ltr", Dir::LTR);
     global.define("rtl", Dir::RTL);
@@ -111,7 +120,10 @@ pub trait LayoutRoot {
 }
 
 impl LayoutRoot for Content {
-    #[tracing::instrument(name = "Content::layout_root", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Content::layout_root", skip_all)
+    )]
     fn layout_root(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Document> {
         #[comemo::memoize]
         fn cached(
@@ -139,6 +151,7 @@ impl LayoutRoot for Content {
                 .layout_root(&mut vt, styles)
         }
 
+        #[cfg(feature = "tracing")]
         tracing::info!("Starting layout");
         cached(
             self,
@@ -166,7 +179,10 @@ pub trait Layout {
     ///
     /// This element must be layouted again in the same order for the results to
     /// be valid.
-    #[tracing::instrument(name = "Layout::measure", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Layout::measure", skip_all)
+    )]
     fn measure(
         &self,
         vt: &mut Vt,
@@ -186,7 +202,10 @@ pub trait Layout {
 }
 
 impl Layout for Content {
-    #[tracing::instrument(name = "Content::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Content::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -221,6 +240,7 @@ impl Layout for Content {
                 .layout(&mut vt, styles, regions)
         }
 
+        #[cfg(feature = "tracing")]
         tracing::info!("Layouting `Content`");
 
         let fragment = cached(
@@ -240,7 +260,7 @@ impl Layout for Content {
 }
 
 /// Realize into an element that is capable of root-level layout.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn realize_root<'a>(
     vt: &mut Vt,
     scratch: &'a Scratch<'a>,
@@ -259,7 +279,7 @@ fn realize_root<'a>(
 }
 
 /// Realize into an element that is capable of block-level layout.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn realize_block<'a>(
     vt: &mut Vt,
     scratch: &'a Scratch<'a>,
@@ -0,0 +1,122 @@
+use crate::prelude::*;
+
+/// Stack content on top of a base in exactly the base's size.
+///
+/// The base is laid out at its natural size and determines the size of the
+/// result. The layer on top is then laid out into the same area and aligned
+/// within it. This makes it easy to stamp something onto an image or to
+/// build components without absolute coordinates.
+///
+/// ## Example { #example }
+/// ```example
+/// #overlay(
+///   rect(width: 60pt, height: 40pt, fill: aqua),
+///   circle(radius: 6pt, fill: red),
+///   alignment: top + right,
+/// )
+/// ```
+///
+/// Display: Overlay
+/// Category: layout
+#[element(Layout)]
+pub struct OverlayElem {
+    /// The content that determines the size.
+    #[required]
+    pub base: Content,
+
+    /// The content to put on top of the base.
+    #[required]
+    pub layer: Content,
+
+    /// How to align the layer within the base's area.
+    #[positional]
+    #[default(Align::CENTER_HORIZON.into())]
+    pub alignment: Axes<Option<GenAlign>>,
+}
+
+impl Layout for OverlayElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "OverlayElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let alignment = self.alignment(styles);
+        compose(vt, styles, regions, &self.base(), &self.layer(), alignment, true)
+    }
+}
+
+/// Stack content below a base in exactly the base's size.
+///
+/// This works like [`overlay`]($func/overlay), but puts the layer behind the
+/// base, for example to add a backdrop to some content.
+///
+/// ## Example { #example }
+/// ```example
+/// #underlay(
+///   text(20pt)[*Typst*],
+///   rect(width: 100%, height: 100%, fill: yellow),
+/// )
+/// ```
+///
+/// Display: Underlay
+/// Category: layout
+#[element(Layout)]
+pub struct UnderlayElem {
+    /// The content that determines the size.
+    #[required]
+    pub base: Content,
+
+    /// The content to put behind the base.
+    #[required]
+    pub layer: Content,
+
+    /// How to align the layer within the base's area.
+    #[positional]
+    #[default(Align::CENTER_HORIZON.into())]
+    pub alignment: Axes<Option<GenAlign>>,
+}
+
+impl Layout for UnderlayElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "UnderlayElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let alignment = self.alignment(styles);
+        compose(vt, styles, regions, &self.base(), &self.layer(), alignment, false)
+    }
+}
+
+/// Layout the base and the layer in the base's size and stack them.
+fn compose(
+    vt: &mut Vt,
+    styles: StyleChain,
+    regions: Regions,
+    base: &Content,
+    layer: &Content,
+    alignment: Axes<Option<GenAlign>>,
+    above: bool,
+) -> SourceResult<Fragment> {
+    let pod = Regions::one(regions.base(), Axes::splat(false));
+    let mut frame = base.layout(vt, styles, pod)?.into_frame();
+
+    let pod = Regions::one(frame.size(), Axes::splat(true));
+    let sub = layer.clone().aligned(alignment).layout(vt, styles, pod)?.into_frame();
+    if above {
+        frame.push_frame(Point::zero(), sub);
+    } else {
+        frame.prepend_frame(Point::zero(), sub);
+    }
+
+    Ok(Fragment::frame(frame))
+}
@@ -60,7 +60,10 @@ pub struct PadElem {
 }
 
 impl Layout for PadElem {
-    #[tracing::instrument(name = "PadElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PadElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -1,10 +1,12 @@
 use std::ptr;
 use std::str::FromStr;
 
-use super::{AlignElem, ColumnsElem};
+use typst::diag::check_cancelled;
+
+use super::{AlignElem, ColumnsElem, RotateElem};
 use crate::meta::{Counter, CounterKey, Numbering};
 use crate::prelude::*;
-use crate::text::TextElem;
+use crate::text::{TextElem, TextSize};
 
 /// Layouts its child onto one or multiple pages.
 ///
@@ -154,6 +156,30 @@ pub struct PageElem {
     #[default(NonZeroUsize::ONE)]
     pub columns: NonZeroUsize,
 
+    /// The rectangles on each page that the content flows through.
+    ///
+    /// Each area is a dictionary with the keys `x`, `y`, `width` and
+    /// `height`. The positions and sizes are relative to the page's content
+    /// area, that is the page without its margins. The content fills the
+    /// areas in the given order and continues in the first area of the next
+    /// page once the last one is full. A [column break]($func/colbreak) moves
+    /// to the next area.
+    ///
+    /// By default, the content area is a single rectangle that is split into
+    /// `columns`. When areas are given, `columns` is ignored.
+    ///
+    /// A paragraph that continues in an area with a different width keeps the
+    /// width of the area in which it started.
+    ///
+    /// ```example
+    /// #set page(height: 120pt, margin: 10pt, areas: (
+    ///   (x: 0pt, y: 0pt, width: 60%, height: 45%),
+    ///   (x: 40%, y: 55%, width: 60%, height: 45%),
+    /// ))
+    /// #lorem(30)
+    /// ```
+    pub areas: Vec<PageArea>,
+
     /// The page's background color.
     ///
     /// This instructs the printer to color the complete page with the given
@@ -252,7 +278,7 @@ pub struct PageElem {
     /// Content in the page's background.
     ///
     /// This content will be placed behind the page's body. It can be
-    /// used to place a background image or a watermark.
+    /// used to place a background image or a [watermark]($func/watermark).
     ///
     /// ```example
     /// #set page(background: rotate(24deg,
@@ -301,15 +327,18 @@ impl PageElem {
     /// while we post-process the pages in this function. This function returns
     /// a fragment consisting of multiple frames, one per output page of this
     /// page run.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn layout(
         &self,
         vt: &mut Vt,
         styles: StyleChain,
         mut number: NonZeroUsize,
     ) -> SourceResult<Fragment> {
+        #[cfg(feature = "tracing")]
         tracing::info!("Page layout");
 
+        let areas = self.areas(styles);
+
         // When one of the lengths is infinite the page fits its content along
         // that axis.
         let width = self.width(styles).unwrap_or(Abs::inf());
@@ -345,16 +374,24 @@ impl PageElem {
         // Realize columns.
         let mut child = self.body();
         let columns = self.columns(styles);
-        if columns.get() > 1 {
+        if columns.get() > 1 && areas.is_empty() {
             child = ColumnsElem::new(child).with_count(columns).pack();
         }
 
         let area = size - margin.sum_by_axis();
-        let mut regions = Regions::repeat(area, area.map(Abs::is_finite));
-        regions.root = true;
 
         // Layout the child.
-        let mut frames = child.layout(vt, styles, regions)?.into_frames();
+        let mut frames = if areas.is_empty() {
+            let mut regions = Regions::repeat(area, area.map(Abs::is_finite));
+            regions.root = true;
+            regions.limit = vt
+                .world
+                .page_limit()
+                .map(|limit| (limit.get() + 1).saturating_sub(number.get()));
+            child.layout(vt, styles, regions)?.into_frames()
+        } else {
+            self.layout_areas(vt, styles, &child, area, &areas)?
+        };
 
         // Align the child to the pagebreak's parity.
         if self.clear_to(styles).is_some_and(|p| !p.matches(number.get())) {
@@ -387,7 +424,27 @@ impl PageElem {
 
         // Post-process pages.
         for frame in frames.iter_mut() {
-            tracing::info!("Layouting page #{number}");
+            #[cfg(feature = "tracing")]
+            tracing::info!(page = number.get(), "Layouting page");
+            check_cancelled(vt.world)?;
+
+            // Apply the overrides placed on this page.
+            let mut header = header.clone();
+            let mut footer = footer.clone();
+            let mut background = background.clone();
+            let mut foreground = foreground.clone();
+            for elem in find_overrides(frame) {
+                for (marginal, value) in [
+                    (&mut header, elem.header(styles)),
+                    (&mut footer, elem.footer(styles)),
+                    (&mut background, elem.background(styles)),
+                    (&mut foreground, elem.foreground(styles)),
+                ] {
+                    if let Smart::Custom(value) = value {
+                        *marginal = value;
+                    }
+                }
+            }
 
             // The padded width of the page's content without margins.
             let pw = frame.width();
@@ -415,7 +472,10 @@ impl PageElem {
                 ("background", &background),
                 ("foreground", &foreground),
             ] {
+                #[cfg(feature = "tracing")]
                 tracing::info!("Layouting {name}");
+                #[cfg(not(feature = "tracing"))]
+                let _ = name;
 
                 let Some(content) = marginal else { continue };
 
@@ -436,12 +496,14 @@ impl PageElem {
                     align = Align::CENTER_HORIZON.into();
                 };
 
+                // Marginals repeat on every page, so screen readers skip them.
                 let pod = Regions::one(area, Axes::splat(true));
-                let sub = content
+                let mut sub = content
                     .clone()
                     .styled(AlignElem::set_alignment(align))
                     .layout(vt, styles, pod)?
                     .into_frame();
+                sub.artifact();
 
                 if ptr::eq(marginal, &header) || ptr::eq(marginal, &background) {
                     frame.prepend_frame(pos, sub);
@@ -457,8 +519,129 @@ impl PageElem {
             number = number.saturating_add(1);
         }
 
+        // The pages within the run were broken by their flow, but the run's
+        // last page ends with the run itself.
+        if let Some(last) = frames.last_mut() {
+            last.explain(Break::new(
+                BreakKind::Page,
+                "end of page run (page break, new page settings or end of document)",
+            ));
+        }
+
         Ok(Fragment::frames(frames))
     }
+
+    /// Layout the body into the page's areas.
+    ///
+    /// Returns one frame with the size of the content area per page.
+    fn layout_areas(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        body: &Content,
+        size: Size,
+        areas: &[PageArea],
+    ) -> SourceResult<Vec<Frame>> {
+        if !size.is_finite() {
+            bail!(self.span(), "pages with areas must have a fixed width and height");
+        }
+
+        let areas: Vec<_> = areas.iter().map(|area| area.resolve(styles, size)).collect();
+        let n = areas.len();
+
+        // The areas of the first page are followed by those of every further
+        // page, so the body is laid out in a single pass. Since the regions
+        // expand, the flow fills the remaining areas of its last page.
+        let (heights, widths): (Vec<_>, Vec<_>) =
+            areas.iter().map(|(_, size)| (size.y, size.x)).unzip();
+        let first = areas[0].1;
+        let regions = Regions {
+            size: first,
+            full: first.y,
+            backlog: &heights[1..],
+            widths: &widths[1..],
+            last: None,
+            cycle: &heights,
+            cycle_widths: &widths,
+            expand: Axes::splat(true),
+            root: true,
+            limit: None,
+        };
+
+        // Place the frames of each page at their areas.
+        let frames = body.layout(vt, styles, regions)?.into_frames();
+        let output = frames
+            .chunks(n)
+            .map(|chunk| {
+                let mut page = Frame::new(size);
+                for ((pos, _), frame) in areas.iter().zip(chunk) {
+                    page.push_frame(*pos, frame.clone());
+                }
+                page
+            })
+            .collect();
+
+        Ok(output)
+    }
+}
+
+/// Find the page overrides in a page's frame, in the order they were placed.
+fn find_overrides(frame: &Frame) -> Vec<PageOverrideElem> {
+    let mut overrides = vec![];
+    collect_overrides(&mut overrides, frame);
+    overrides
+}
+
+/// Collect the page overrides in a frame and its groups.
+fn collect_overrides(overrides: &mut Vec<PageOverrideElem>, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => collect_overrides(overrides, &group.frame),
+            FrameItem::Meta(Meta::Elem(content), _) => {
+                if let Some(elem) = content.to::<PageOverrideElem>() {
+                    overrides.push(elem.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A rectangle on a page that content flows through.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PageArea {
+    /// The position of the top-left corner.
+    pub pos: Axes<Rel<Length>>,
+    /// The width and height.
+    pub size: Axes<Rel<Length>>,
+}
+
+impl PageArea {
+    /// Resolve the position and size relative to the content area.
+    fn resolve(&self, styles: StyleChain, base: Size) -> (Point, Size) {
+        let pos = self.pos.resolve(styles).zip(base).map(|(v, b)| v.relative_to(b));
+        let size = self.size.resolve(styles).zip(base).map(|(v, b)| v.relative_to(b));
+        (pos.to_point(), size)
+    }
+}
+
+cast! {
+    PageArea,
+    self => {
+        let mut dict = Dict::new();
+        dict.insert("x".into(), self.pos.x.into_value());
+        dict.insert("y".into(), self.pos.y.into_value());
+        dict.insert("width".into(), self.size.x.into_value());
+        dict.insert("height".into(), self.size.y.into_value());
+        Value::Dict(dict)
+    },
+    mut dict: Dict => {
+        let mut take = |key| dict.take(key)?.cast::<Rel<Length>>();
+        let pos = Axes::new(take("x")?, take("y")?);
+        let size = Axes::new(take("width")?, take("height")?);
+        dict.finish(&["x", "y", "width", "height"])?;
+        Self { pos, size }
+    },
 }
 
 /// Specification of the page's margins.
@@ -664,6 +847,129 @@ pub struct PagebreakElem {
     pub to: Option<Parity>,
 }
 
+/// Overrides the header, footer, background or foreground of the page it ends
+/// up on.
+///
+/// Some pages shouldn't look like the others: The first page of a chapter
+/// typically has no header and a page with a full-bleed image shouldn't have a
+/// footer. Placing a page override anywhere in a page's content changes just
+/// that page. Setting a property to `{none}` suppresses the marginal and
+/// setting it to content replaces it. Properties that are left at `{auto}`
+/// keep the page's settings. If a page contains multiple overrides, the later
+/// ones win.
+///
+/// Combined with a show rule, overrides apply to every page with a certain
+/// element. To change a whole run of pages instead, call the
+/// [page function]($func/page) with the modified properties and the run's
+/// content.
+///
+/// ## Example { #example }
+/// ```example
+/// #set page(height: 100pt, header: [
+///   _Exercise Sheet 3_ #h(1fr) Typst Academy
+/// ])
+///
+/// #show heading.where(level: 1): it => {
+///   pagebreak(weak: true)
+///   page-override(header: none)
+///   it
+/// }
+///
+/// = Introduction
+/// #lorem(12)
+///
+/// = Exercises
+/// #lorem(12)
+/// ```
+///
+/// Display: Page Override
+/// Category: layout
+#[element(Locatable, Synthesize, Show)]
+pub struct PageOverrideElem {
+    /// The header of the page, or `{none}` to suppress it.
+    pub header: Smart<Option<Content>>,
+
+    /// The footer of the page, or `{none}` to suppress it. This includes the
+    /// page number shown due to the page's
+    /// [`numbering`]($func/page.numbering).
+    pub footer: Smart<Option<Content>>,
+
+    /// The background of the page, or `{none}` to suppress it.
+    pub background: Smart<Option<Content>>,
+
+    /// The foreground of the page, or `{none}` to suppress it.
+    pub foreground: Smart<Option<Content>>,
+}
+
+impl Synthesize for PageOverrideElem {
+    fn synthesize(&mut self, _: &mut Vt, styles: StyleChain) -> SourceResult<()> {
+        self.push_header(self.header(styles));
+        self.push_footer(self.footer(styles));
+        self.push_background(self.background(styles));
+        self.push_foreground(self.foreground(styles));
+        Ok(())
+    }
+}
+
+impl Show for PageOverrideElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PageOverrideElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// A large rotated stamp like "DRAFT" or "CONFIDENTIAL".
+///
+/// The watermark is meant to be used as the page's
+/// [background]($func/page.background), which is repeated on every page and
+/// centered on it.
+///
+/// ## Example { #example }
+/// ```example
+/// #set page(background: watermark[DRAFT])
+///
+/// = Typst's secret plans
+/// In the year 2023, we plan to take
+/// over the world (of typesetting).
+/// ```
+///
+/// Display: Watermark
+/// Category: layout
+#[func]
+pub fn watermark(
+    /// The content of the watermark.
+    body: Content,
+    /// The angle by which the watermark is rotated counter-clockwise.
+    #[named]
+    #[default(Angle::deg(45.0))]
+    angle: Angle,
+    /// How opaque the watermark is.
+    ///
+    /// Since the watermark lies behind the page's content, this is achieved
+    /// by lightening its color towards white.
+    #[named]
+    #[default(Ratio::new(0.2))]
+    opacity: Ratio,
+    /// The color of the watermark.
+    #[named]
+    #[default(Color::BLACK)]
+    fill: Color,
+    /// The size of the watermark's text.
+    #[named]
+    #[default(Abs::pt(72.0).into())]
+    size: Length,
+) -> Content {
+    let opacity = Ratio::new(opacity.get().clamp(0.0, 1.0));
+    let fill = fill.lighten(Ratio::one() - opacity);
+    let body = body
+        .styled(TextElem::set_fill(fill.into()))
+        .styled(TextElem::set_size(TextSize(size)));
+    RotateElem::new(body).with_angle(-angle).pack()
+}
+
 /// Whether something should be even or odd.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Parity {
@@ -896,3 +1202,110 @@ papers! {
     (PRESENTATION_16_9:    297.0, 167.0625, "presentation-16-9")
     (PRESENTATION_4_3:     280.0,    210.0, "presentation-4-3")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    #[test]
+    fn test_watermark() {
+        let document = compile(
+            "#set page(width: 200pt, height: 200pt)\n\
+             #set page(background: watermark[DRAFT])\n\
+             Body\n\
+             #pagebreak()\n\
+             More",
+        );
+
+        // Every page shows the stamp, rotated counter-clockwise by 45 degrees
+        // and lightened to a fifth of its color.
+        let angle = Angle::deg(-45.0);
+        let fill: Paint = Color::BLACK.lighten(Ratio::new(0.8)).into();
+        assert_eq!(document.pages.len(), 2);
+        for page in &document.pages {
+            let stamps: Vec<_> = items(page)
+                .into_iter()
+                .filter_map(|(_, item)| match item {
+                    FrameItem::Group(group) if !group.transform.is_identity() => {
+                        Some(group)
+                    }
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(stamps.len(), 1);
+            let transform = stamps[0].transform;
+            assert!((transform.sx.get() - angle.cos()).abs() < 1e-9);
+            assert!((transform.ky.get() - angle.sin()).abs() < 1e-9);
+
+            let texts: Vec<_> = items(&stamps[0].frame)
+                .into_iter()
+                .filter_map(|(_, item)| match item {
+                    FrameItem::Text(text) => {
+                        Some((text.text.as_str(), text.fill.clone(), text.size))
+                    }
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(texts, [("DRAFT", fill.clone(), Abs::pt(72.0))]);
+        }
+    }
+
+    /// The texts on the pages of a document with their pages and the areas
+    /// they lie in.
+    fn areas(text: &str) -> Vec<(usize, Option<usize>, String)> {
+        let pt = Abs::pt;
+        let area = |pos: Point| {
+            if (pt(10.0)..=pt(58.0)).contains(&pos.x) && pos.y <= pt(55.0) {
+                Some(0)
+            } else if pos.x >= pt(42.0) && pos.y >= pt(65.0) && pos.y <= pt(110.0) {
+                Some(1)
+            } else {
+                None
+            }
+        };
+
+        let document = compile(&format!(
+            "#set page(width: 100pt, height: 120pt, margin: 10pt, areas: (\n\
+               (x: 0pt, y: 0pt, width: 60%, height: 45%),\n\
+               (x: 40%, y: 55%, width: 60%, height: 45%),\n\
+             ))\n\
+             {text}"
+        ));
+
+        let mut found = vec![];
+        for (i, page) in document.pages.iter().enumerate() {
+            for (pos, item) in items(page) {
+                if let FrameItem::Text(text) = item {
+                    found.push((i, area(pos), text.text.to_string()));
+                }
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_page_areas() {
+        // Column breaks move through the areas and on to the next page.
+        let found = areas("A #colbreak() B #colbreak() C #colbreak() D #colbreak() E");
+        assert_eq!(
+            found,
+            [
+                (0, Some(0), "A".into()),
+                (0, Some(1), "B".into()),
+                (1, Some(0), "C".into()),
+                (1, Some(1), "D".into()),
+                (2, Some(0), "E".into()),
+            ]
+        );
+
+        // Long text fills both areas on every page but the last.
+        let found = areas("#lorem(300)");
+        let last = found.last().unwrap().0;
+        assert!(last >= 2);
+        assert!(found.iter().all(|(_, area, _)| area.is_some()));
+        for page in 0..last {
+            assert!(found.iter().any(|&(i, area, _)| i == page && area == Some(1)));
+        }
+    }
+}
@@ -4,6 +4,7 @@ use icu_provider_adapters::fork::ForkByKeyProvider;
 use icu_provider_blob::BlobDataProvider;
 use icu_segmenter::{LineBreakIteratorUtf8, LineSegmenter};
 use once_cell::sync::Lazy;
+use typst::diag::SourceWarning;
 use typst::eval::Tracer;
 use typst::model::DelayedErrors;
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
@@ -14,8 +15,8 @@ use crate::layout::AlignElem;
 use crate::math::EquationElem;
 use crate::prelude::*;
 use crate::text::{
-    is_gb_style, shape, LinebreakElem, Quoter, Quotes, ShapedText, SmartQuoteElem,
-    SpaceElem, TextElem,
+    is_gb_style, missing_family, shape, LinebreakElem, Quoter, Quotes, ShapedText,
+    SmartQuoteElem, SpaceElem, TextElem,
 };
 
 /// Arrange text, spacing and inline-level elements into a paragraph.
@@ -135,7 +136,10 @@ impl Construct for ParElem {
 
 impl ParElem {
     /// Layout the paragraph into a collection of lines.
-    #[tracing::instrument(name = "ParElement::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ParElement::layout", skip_all)
+    )]
     pub fn layout(
         &self,
         vt: &mut Vt,
@@ -459,9 +463,26 @@ struct Line<'a> {
     /// Whether the line ends with a hyphen or dash, either naturally or through
     /// hyphenation.
     dash: bool,
+    /// Why the line ended, once line breaking decided on it.
+    explanation: Option<Break>,
 }
 
 impl<'a> Line<'a> {
+    /// Record why the line ended, together with its cost and adjustment ratio
+    /// for optimized line breaking.
+    fn explain(&mut self, reason: &'static str, cost: Option<(f64, f64)>) {
+        let kind = if self.end == self.bidi.text.len() {
+            BreakKind::Paragraph
+        } else {
+            BreakKind::Line
+        };
+        self.explanation = Some(Break {
+            text: Some(self.bidi.text[self.trimmed.clone()].into()),
+            cost: cost.map(|(cost, ratio)| (Scalar(cost), Scalar(ratio))),
+            ..Break::new(kind, reason)
+        });
+    }
+
     /// Iterate over the line's items.
     fn items(&self) -> impl Iterator<Item = &Item<'a>> {
         self.first.iter().chain(self.inner).chain(&self.last)
@@ -577,7 +598,7 @@ fn collect<'a>(
         } else if let Some(elem) = child.to::<TextElem>() {
             let prev = full.len();
             if let Some(case) = TextElem::case_in(styles) {
-                full.push_str(&case.apply(&elem.text()));
+                full.push_str(&case.apply(&elem.text(), TextElem::lang_in(styles)));
             } else {
                 full.push_str(&elem.text());
             }
@@ -683,6 +704,15 @@ fn prepare<'a>(
         let end = cursor + segment.len();
         match segment {
             Segment::Text(_) => {
+                if vt.world.placeholders() {
+                    if let Some(family) = missing_family(vt.world, styles) {
+                        let span = spans.span_at(cursor).0;
+                        let message = eco_format!("unknown font family: {family:?}");
+                        let warning = SourceWarning::new(span, message)
+                            .with_hint("the text is shown in a fallback font");
+                        vt.tracer.warn(warning);
+                    }
+                }
                 shape_range(&mut items, vt, &bidi, cursor..end, &spans, styles);
             }
             Segment::Spacing(spacing) => match spacing {
@@ -838,7 +868,8 @@ fn linebreak_simple<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line
         // into the stack and rebuild the line from the attempt's end. The
         // resulting line cannot be broken up further.
         if !width.fits(attempt.width) {
-            if let Some((last_attempt, last_end)) = last.take() {
+            if let Some((mut last_attempt, last_end)) = last.take() {
+                last_attempt.explain("next word does not fit", None);
                 lines.push(last_attempt);
                 start = last_end;
                 attempt = line(vt, p, start..end, mandatory, hyphen);
@@ -849,6 +880,8 @@ fn linebreak_simple<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line
         // due to "\n") or if the line doesn't fit horizontally already
         // since then no shorter line will be possible.
         if mandatory || !width.fits(attempt.width) {
+            let reason = if mandatory { "mandatory break" } else { "overfull" };
+            attempt.explain(reason, None);
             lines.push(attempt);
             start = end;
             last = None;
@@ -857,7 +890,8 @@ fn linebreak_simple<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<Line
         }
     }
 
-    if let Some((line, _)) = last {
+    if let Some((mut line, _)) = last {
+        line.explain("end of paragraph", None);
         lines.push(line);
     }
 
@@ -889,6 +923,9 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
     struct Entry<'a> {
         pred: usize,
         total: Cost,
+        cost: Cost,
+        ratio: f64,
+        mandatory: bool,
         line: Line<'a>,
     }
 
@@ -903,6 +940,9 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
     let mut table = vec![Entry {
         pred: 0,
         total: 0.0,
+        cost: 0.0,
+        ratio: 0.0,
+        mandatory: false,
         line: line(vt, p, 0..0, false, false),
     }];
 
@@ -991,7 +1031,14 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
 
             // If this attempt is better than what we had before, take it!
             if best.as_ref().map_or(true, |best| best.total >= total) {
-                best = Some(Entry { pred: i, total, line: attempt });
+                best = Some(Entry {
+                    pred: i,
+                    total,
+                    cost,
+                    ratio,
+                    mandatory: mandatory || eof,
+                    line: attempt,
+                });
             }
         }
 
@@ -1003,7 +1050,15 @@ fn linebreak_optimized<'a>(vt: &Vt, p: &'a Preparation<'a>, width: Abs) -> Vec<L
     let mut idx = table.len() - 1;
     while idx != 0 {
         table.truncate(idx + 1);
-        let entry = table.pop().unwrap();
+        let mut entry = table.pop().unwrap();
+        let reason = if entry.cost >= MAX_COST {
+            "overfull"
+        } else if entry.mandatory {
+            "mandatory break"
+        } else {
+            "lowest total cost"
+        };
+        entry.line.explain(reason, Some((entry.cost, entry.ratio)));
         lines.push(entry.line);
         idx = entry.pred;
     }
@@ -1085,8 +1140,8 @@ struct Breakpoints<'a> {
     p: &'a Preparation<'a>,
     /// The inner iterator over the unicode line break opportunities.
     linebreaks: LineBreakIteratorUtf8<'a, 'a>,
-    /// Iterator over syllables of the current word.
-    syllables: Option<hypher::Syllables<'a>>,
+    /// Iterator over the lengths of the syllables of the current word.
+    syllables: Option<std::vec::IntoIter<usize>>,
     /// The current text offset.
     offset: usize,
     /// The trimmed end of the current word.
@@ -1103,7 +1158,7 @@ impl Iterator for Breakpoints<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         // If we're currently in a hyphenated "word", process the next syllable.
         if let Some(syllable) = self.syllables.as_mut().and_then(Iterator::next) {
-            self.offset += syllable.len();
+            self.offset += syllable;
             if self.offset == self.suffix {
                 self.offset = self.end;
             }
@@ -1135,12 +1190,12 @@ impl Iterator for Breakpoints<'_> {
 
         // Hyphenate the next word.
         if self.p.hyphenate != Some(false) {
-            if let Some(lang) = self.lang(self.offset) {
-                let word = &self.p.bidi.text[self.offset..self.end];
-                let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
-                if !trimmed.is_empty() {
+            let word = &self.p.bidi.text[self.offset..self.end];
+            let trimmed = word.trim_end_matches(|c: char| !c.is_alphabetic());
+            if !trimmed.is_empty() {
+                if let Some(syllables) = self.syllables(trimmed) {
                     self.suffix = self.offset + trimmed.len();
-                    self.syllables = Some(hypher::hyphenate(trimmed, lang));
+                    self.syllables = Some(syllables.into_iter());
                     return self.next();
                 }
             }
@@ -1163,15 +1218,26 @@ impl Breakpoints<'_> {
             .unwrap_or(false)
     }
 
-    /// The text language at the given offset.
-    fn lang(&self, offset: usize) -> Option<hypher::Lang> {
-        let lang = self.p.lang.or_else(|| {
-            let shaped = self.p.find(offset)?.text()?;
-            Some(TextElem::lang_in(shaped.styles))
-        })?;
+    /// The lengths of the syllables of the word at the current offset.
+    ///
+    /// Custom hyphenation exceptions are consulted before the language's
+    /// hyphenation patterns.
+    fn syllables(&self, word: &str) -> Option<Vec<usize>> {
+        let styles = self
+            .p
+            .find(self.offset)
+            .and_then(Item::text)
+            .map_or(self.p.styles, |shaped| shaped.styles);
+
+        let lang = self.p.lang.unwrap_or_else(|| TextElem::lang_in(styles));
+        let exceptions = TextElem::hyphenation_exceptions_in(styles);
+        if let Some(syllables) = exceptions.syllables(word, lang) {
+            return Some(syllables);
+        }
 
         let bytes = lang.as_str().as_bytes().try_into().ok()?;
-        hypher::Lang::from_iso(bytes)
+        let lang = hypher::Lang::from_iso(bytes)?;
+        Some(hypher::hyphenate(word, lang).map(str::len).collect())
     }
 }
 
@@ -1197,6 +1263,7 @@ fn line<'a>(
             width: Abs::zero(),
             justify,
             dash: false,
+            explanation: None,
         };
     }
 
@@ -1314,6 +1381,7 @@ fn line<'a>(
         width,
         justify,
         dash,
+        explanation: None,
     }
 }
 
@@ -1335,10 +1403,17 @@ fn finalize(
         region.x
     };
 
-    // Stack the lines into one frame per region.
+    // Stack the lines into one frame per region. Non-empty line frames carry
+    // the explanation of their break.
     let mut frames: Vec<Frame> = lines
         .iter()
-        .map(|line| commit(vt, p, line, width, region.y))
+        .map(|line| {
+            let mut frame = commit(vt, p, line, width, region.y)?;
+            if let Some(explanation) = &line.explanation {
+                frame.explain(explanation.clone());
+            }
+            Ok(frame)
+        })
         .collect::<SourceResult<_>>()?;
 
     // Prevent orphans.
@@ -1347,6 +1422,7 @@ fn finalize(
         let second = frames.remove(1);
         let first = &mut frames[0];
         merge(first, second, leading);
+        first.explain(Break::new(BreakKind::Orphan, "kept the first two lines together"));
     }
 
     // Prevent widows.
@@ -1355,6 +1431,7 @@ fn finalize(
         let second = frames.pop().unwrap();
         let first = frames.last_mut().unwrap();
         merge(first, second, leading);
+        first.explain(Break::new(BreakKind::Widow, "kept the last two lines together"));
     }
 
     Ok(Fragment::frames(frames))
@@ -1473,7 +1550,7 @@ fn commit(
                 }
             }
             Item::Text(shaped) => {
-                let frame = shaped.build(vt, justification_ratio, extra_justification);
+                let frame = shaped.build(vt, justification_ratio, extra_justification)?;
                 push(&mut offset, frame);
             }
             Item::Frame(frame) | Item::Meta(frame) => {
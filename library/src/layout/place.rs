@@ -21,9 +21,27 @@ use crate::prelude::*;
 /// )
 /// ```
 ///
+/// ## Anchored placement { #anchored }
+/// With an `anchor`, the content is placed relative to the final location of
+/// another labelled element instead of its parent container. The alignment
+/// and displacement then refer to the anchored element's frame. This is
+/// useful for annotations that point at a specific element. The anchored
+/// element must be on the same page as the placed content.
+///
+/// ```example
+/// #box(rect[Original]) <orig>
+/// #place(
+///   top + right,
+///   anchor: <orig>,
+///   dx: 8pt,
+///   dy: -8pt,
+///   circle(radius: 5pt, fill: red),
+/// )
+/// ```
+///
 /// Display: Place
 /// Category: layout
-#[element(Layout, Behave)]
+#[element(Locatable, Layout, Behave)]
 pub struct PlaceElem {
     /// Relative to which position in the parent container to place the content.
     ///
@@ -48,19 +66,52 @@ pub struct PlaceElem {
     /// The vertical displacement of the placed content.
     pub dy: Rel<Length>,
 
+    /// The label of an element to place the content relative to.
+    ///
+    /// Relative displacements are then resolved relative to the size of the
+    /// anchored element.
+    pub anchor: Option<Label>,
+
+    /// The position of the placed content in the reading order of its page.
+    ///
+    /// Screen readers read the content of an exported PDF page in the order
+    /// it was laid out in. Placed content with a negative order is instead
+    /// read before the rest of the page and content with a positive order
+    /// after it. Content with the same order is read in layout order.
+    ///
+    /// ```example
+    /// #place(bottom + right, order: -1)[
+    ///   Read first.
+    /// ]
+    /// Read second.
+    /// ```
+    pub order: Option<i64>,
+
     /// The content to place.
     #[required]
     pub body: Content,
 }
 
 impl Layout for PlaceElem {
-    #[tracing::instrument(name = "PlaceElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PlaceElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        let order = self.order(styles);
+        if let Some(anchor) = self.anchor(styles) {
+            let mut frame = vt.delayed(|vt| self.anchored(vt, styles, &anchor));
+            if let Some(order) = order {
+                frame.reading_order(order);
+            }
+            return Ok(Fragment::frame(frame));
+        }
+
         let out_of_flow = self.out_of_flow(styles);
 
         // The pod is the base area of the region because for absolute
@@ -77,6 +128,9 @@ impl Layout for PlaceElem {
             .aligned(self.alignment(styles));
 
         let mut frame = child.layout(vt, styles, pod)?.into_frame();
+        if let Some(order) = order {
+            frame.reading_order(order);
+        }
 
         // If expansion is off, zero all sizes so that we don't take up any
         // space in our parent. Otherwise, respect the expand settings.
@@ -92,7 +146,42 @@ impl PlaceElem {
     /// base origin. Instead of relative to the parent's current flow/cursor
     /// position.
     pub fn out_of_flow(&self, styles: StyleChain) -> bool {
-        self.alignment(styles).y.is_some()
+        self.alignment(styles).y.is_some() || self.anchor(styles).is_some()
+    }
+
+    /// Layout the content relative to the anchored element's frame.
+    ///
+    /// Returns a zero-sized frame whose origin is the placed element's own
+    /// position.
+    fn anchored(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        anchor: &Label,
+    ) -> SourceResult<Frame> {
+        let introspector = vt.introspector;
+        let own = introspector.position(self.0.location().unwrap());
+        let elem = introspector.query_label(anchor).at(self.span())?;
+        let Some(location) = elem.location() else {
+            bail!(self.span(), "cannot anchor to an element that has no location");
+        };
+
+        let pos = introspector.position(location);
+        if pos.page != own.page {
+            bail!(self.span(), "anchored element must be on the same page");
+        }
+
+        let size = introspector.size(location);
+        let pod = Regions::one(size, Axes::splat(true));
+        let child = self
+            .body()
+            .moved(Axes::new(self.dx(styles), self.dy(styles)))
+            .aligned(self.alignment(styles));
+
+        let mut frame = Frame::new(Size::zero());
+        let sub = child.layout(vt, styles, pod)?.into_frame();
+        frame.push_frame(pos.point - own.point, sub);
+        Ok(frame)
     }
 }
 
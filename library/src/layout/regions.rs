@@ -9,11 +9,24 @@ pub struct Regions<'a> {
     pub size: Size,
     /// The full height of the region for relative sizing.
     pub full: Abs,
-    /// The height of followup regions. The width is the same for all regions.
+    /// The height of followup regions.
     pub backlog: &'a [Abs],
+    /// The widths of the followup regions, in the same order as the
+    /// `backlog`. A region without an entry here has the same width as the
+    /// region before it. Typically empty, in which case all regions have the
+    /// same width.
+    pub widths: &'a [Abs],
     /// The height of the final region that is repeated once the backlog is
-    /// drained. The width is the same for all regions.
+    /// drained. Its width is the one of the last region in the backlog.
     pub last: Option<Abs>,
+    /// The heights of regions that are repeated in order once the backlog is
+    /// drained. If this is not empty, it takes precedence over `last`.
+    /// Typically empty.
+    pub cycle: &'a [Abs],
+    /// The widths of the repeated regions, in the same order as the `cycle`.
+    /// Like the `widths` for the backlog, a region without an entry here has
+    /// the same width as the region before it.
+    pub cycle_widths: &'a [Abs],
     /// Whether elements should expand to fill the regions instead of shrinking
     /// to fit the content.
     pub expand: Axes<bool>,
@@ -22,6 +35,11 @@ pub struct Regions<'a> {
     /// True for the padded page regions and columns directly in the page,
     /// false otherwise.
     pub root: bool,
+    /// How many regions, including the first one, a root flow needs to fill
+    /// before it may stop laying out its remaining children.
+    ///
+    /// This is set when the world only needs the first pages of a document.
+    pub limit: Option<usize>,
 }
 
 impl Regions<'_> {
@@ -31,9 +49,13 @@ impl Regions<'_> {
             size,
             full: size.y,
             backlog: &[],
+            widths: &[],
             last: None,
+            cycle: &[],
+            cycle_widths: &[],
             expand,
             root: false,
+            limit: None,
         }
     }
 
@@ -43,9 +65,13 @@ impl Regions<'_> {
             size,
             full: size.y,
             backlog: &[],
+            widths: &[],
             last: Some(size.y),
+            cycle: &[],
+            cycle_widths: &[],
             expand,
             root: false,
+            limit: None,
         }
     }
 
@@ -57,24 +83,78 @@ impl Regions<'_> {
         Size::new(self.size.x, self.full)
     }
 
+    /// The width of the `i`-th region in the backlog.
+    pub fn backlog_width(&self, i: usize) -> Abs {
+        let len = self.widths.len().min(i + 1);
+        self.widths[..len].last().copied().unwrap_or(self.size.x)
+    }
+
+    /// The width of the `i`-th region in the cycle.
+    fn cycle_width(&self, i: usize) -> Abs {
+        let len = self.cycle_widths.len().min(i + 1);
+        match self.cycle_widths[..len].last() {
+            Some(&width) => width,
+            None => self.last_width(),
+        }
+    }
+
+    /// The width of the last region in the backlog.
+    fn last_width(&self) -> Abs {
+        match self.backlog.len() {
+            0 => self.size.x,
+            n => self.backlog_width(n - 1),
+        }
+    }
+
     /// Create new regions where all sizes are mapped with `f`.
     ///
-    /// Note that since all regions must have the same width, the width returned
-    /// by `f` is ignored for the backlog and the final region.
-    pub fn map<'v, F>(&self, backlog: &'v mut Vec<Abs>, mut f: F) -> Regions<'v>
+    /// The `buf` holds the mapped heights of the backlog, followed by its
+    /// mapped widths if the regions have varying widths, followed by the same
+    /// for the cycle.
+    pub fn map<'v, F>(&self, buf: &'v mut Vec<Abs>, mut f: F) -> Regions<'v>
     where
         F: FnMut(Size) -> Size,
     {
         let x = self.size.x;
-        backlog.clear();
-        backlog.extend(self.backlog.iter().map(|&y| f(Size::new(x, y)).y));
+        let n = self.backlog.len();
+        let m = self.cycle.len();
+        let sizes: Vec<Size> = self
+            .backlog
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| f(Size::new(self.backlog_width(i), y)))
+            .collect();
+        let cycle: Vec<Size> = self
+            .cycle
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| f(Size::new(self.cycle_width(i), y)))
+            .collect();
+
+        buf.clear();
+        buf.extend(sizes.iter().map(|size| size.y));
+        if !self.widths.is_empty() {
+            buf.extend(sizes.iter().map(|size| size.x));
+        }
+        buf.extend(cycle.iter().map(|size| size.y));
+        if !self.cycle_widths.is_empty() {
+            buf.extend(cycle.iter().map(|size| size.x));
+        }
+
+        let (backlog, rest) = buf.split_at(n);
+        let (widths, rest) = rest.split_at(if self.widths.is_empty() { 0 } else { n });
+        let (cycle, cycle_widths) = rest.split_at(m);
         Regions {
             size: f(self.size),
             full: f(Size::new(x, self.full)).y,
             backlog,
-            last: self.last.map(|y| f(Size::new(x, y)).y),
+            widths,
+            last: self.last.map(|y| f(Size::new(self.last_width(), y)).y),
+            cycle,
+            cycle_widths,
             expand: self.expand,
             root: false,
+            limit: self.limit,
         }
     }
 
@@ -87,7 +167,9 @@ impl Regions<'_> {
     ///
     /// If this is true, calling `next()` will have no effect.
     pub fn in_last(&self) -> bool {
-        self.backlog.is_empty() && self.last.map_or(true, |height| self.size.y == height)
+        self.backlog.is_empty()
+            && self.cycle.is_empty()
+            && self.last.map_or(true, |height| self.size.y == height)
     }
 
     /// The same regions, but with different `root` configuration.
@@ -97,18 +179,27 @@ impl Regions<'_> {
 
     /// Advance to the next region if there is any.
     pub fn next(&mut self) {
-        if let Some(height) = self
-            .backlog
-            .split_first()
-            .map(|(first, tail)| {
-                self.backlog = tail;
-                *first
-            })
-            .or(self.last)
-        {
-            self.size.y = height;
-            self.full = height;
+        if self.backlog.is_empty() && !self.cycle.is_empty() {
+            self.backlog = self.cycle;
+            self.widths = self.cycle_widths;
         }
+
+        let height = if let Some((&first, tail)) = self.backlog.split_first() {
+            self.backlog = tail;
+            if let Some((&width, tail)) = self.widths.split_first() {
+                self.widths = tail;
+                self.size.x = width;
+            }
+            first
+        } else if let Some(last) = self.last {
+            last
+        } else {
+            return;
+        };
+
+        self.size.y = height;
+        self.limit = self.limit.map(|limit| limit.saturating_sub(1));
+        self.full = height;
     }
 
     /// An iterator that returns the sizes of the first and all following
@@ -116,10 +207,20 @@ impl Regions<'_> {
     /// [`next()`](Self::next) repeatedly until all regions are exhausted.
     /// This iterator may be infinite.
     pub fn iter(&self) -> impl Iterator<Item = Size> + '_ {
+        let n = self.backlog.len();
+        let last_x = self.last_width();
         let first = std::iter::once(self.size);
-        let backlog = self.backlog.iter();
-        let last = self.last.iter().cycle();
-        first.chain(backlog.chain(last).map(|&h| Size::new(self.size.x, h)))
+        let backlog = (0..n).map(|i| Size::new(self.backlog_width(i), self.backlog[i]));
+        let cycle = (0..self.cycle.len())
+            .cycle()
+            .map(|i| Size::new(self.cycle_width(i), self.cycle[i]));
+        let last = self
+            .last
+            .filter(|_| self.cycle.is_empty())
+            .into_iter()
+            .cycle()
+            .map(move |h| Size::new(last_x, h));
+        first.chain(backlog).chain(cycle).chain(last)
     }
 }
 
@@ -127,15 +228,20 @@ impl Debug for Regions<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.write_str("Regions ")?;
         let mut list = f.debug_list();
-        let mut prev = self.size.y;
+        let mut prev = self.size;
         list.entry(&self.size);
-        for &height in self.backlog {
-            list.entry(&Size::new(self.size.x, height));
-            prev = height;
+        for (i, &height) in self.backlog.iter().enumerate() {
+            prev = Size::new(self.backlog_width(i), height);
+            list.entry(&prev);
         }
-        if let Some(last) = self.last {
-            if last != prev {
-                list.entry(&Size::new(self.size.x, last));
+        if !self.cycle.is_empty() {
+            for i in 0..self.cycle.len() {
+                list.entry(&Size::new(self.cycle_width(i), self.cycle[i]));
+            }
+            list.entry(&(..));
+        } else if let Some(last) = self.last {
+            if last != prev.y {
+                list.entry(&Size::new(prev.x, last));
             }
             list.entry(&(..));
         }
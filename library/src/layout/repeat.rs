@@ -34,7 +34,10 @@ pub struct RepeatElem {
 }
 
 impl Layout for RepeatElem {
-    #[tracing::instrument(name = "RepeatElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "RepeatElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -38,7 +38,10 @@ pub struct StackElem {
 }
 
 impl Layout for StackElem {
-    #[tracing::instrument(name = "StackElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "StackElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -159,7 +162,10 @@ impl<'a> StackLayouter<'a> {
     }
 
     /// Add spacing along the spacing direction.
-    #[tracing::instrument(name = "StackLayouter::layout_spacing", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "StackLayouter::layout_spacing", skip_all)
+    )]
     fn layout_spacing(&mut self, spacing: Spacing) {
         match spacing {
             Spacing::Rel(v) => {
@@ -183,7 +189,10 @@ impl<'a> StackLayouter<'a> {
     }
 
     /// Layout an arbitrary block.
-    #[tracing::instrument(name = "StackLayouter::layout_block", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "StackLayouter::layout_block", skip_all)
+    )]
     fn layout_block(
         &mut self,
         vt: &mut Vt,
@@ -1,8 +1,11 @@
+use std::ops::Range;
+
 use typst::eval::{CastInfo, Reflect};
 
-use crate::layout::{AlignElem, GridLayouter, TrackSizings};
+use crate::layout::{AlignElem, GridLayouter, LazyRows, TrackSizings};
 use crate::meta::{Figurable, LocalName};
 use crate::prelude::*;
+use crate::text::locale::Term;
 
 /// A table of items.
 ///
@@ -121,13 +124,45 @@ pub struct TableElem {
     #[default(Abs::pt(5.0).into())]
     pub inset: Rel<Length>,
 
+    /// Items from which to generate further rows after the given cells.
+    ///
+    /// Each item is passed to the `row` function, which returns the cells of
+    /// its row as an array. Each generated row starts in a new row of the
+    /// table. The rows are generated in chunks while the table is laid out and
+    /// each chunk is dropped once it is laid out, so a table with many
+    /// thousands of rows from a dataset never builds content for all of them
+    /// at once. If the table has `{auto}` columns, the rows are generated twice:
+    /// once to measure the columns and once to lay them out.
+    ///
+    /// ```example
+    /// #let fruits = (
+    ///   (name: "Apples", count: 12),
+    ///   (name: "Pears", count: 7),
+    ///   (name: "Plums", count: 30),
+    /// )
+    ///
+    /// #table(
+    ///   columns: 2,
+    ///   data: fruits,
+    ///   row: fruit => ([#fruit.name], [#fruit.count]),
+    ///   [*Fruit*], [*Count*],
+    /// )
+    /// ```
+    pub data: Array,
+
+    /// The function that turns an item of `data` into the cells of a row.
+    pub row: Option<Func>,
+
     /// The contents of the table cells.
     #[variadic]
     pub children: Vec<Content>,
 }
 
 impl Layout for TableElem {
-    #[tracing::instrument(name = "TableElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "TableElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -140,31 +175,72 @@ impl Layout for TableElem {
         let tracks = Axes::new(self.columns(styles).0, self.rows(styles).0);
         let gutter = Axes::new(self.column_gutter(styles).0, self.row_gutter(styles).0);
         let cols = tracks.x.len().max(1);
+        let prepare = |vt: &mut Vt, x, y, child: Content| -> SourceResult<Content> {
+            let mut child = child.padded(Sides::splat(inset));
+            if let Smart::Custom(alignment) = align.resolve(vt, x, y)? {
+                child = child.styled(AlignElem::set_alignment(alignment));
+            }
+            Ok(child)
+        };
+
         let cells: Vec<_> = self
             .children()
             .into_iter()
             .enumerate()
-            .map(|(i, child)| {
-                let mut child = child.padded(Sides::splat(inset));
+            .map(|(i, child)| prepare(vt, i % cols, i / cols, child))
+            .collect::<SourceResult<_>>()?;
 
-                let x = i % cols;
-                let y = i / cols;
-                if let Smart::Custom(alignment) = align.resolve(vt, x, y)? {
-                    child = child.styled(AlignElem::set_alignment(alignment));
-                }
+        // Generate the rows from the data in chunks while laying out.
+        let explicit = cells.len() / cols + (cells.len() % cols).clamp(0, 1);
+        let data = self.data(styles);
+        let mut generate;
+        let lazy = if data.is_empty() {
+            None
+        } else {
+            let Some(row) = self.row(styles) else {
+                bail!(
+                    self.span(),
+                    "cannot generate rows from data without a row function"
+                );
+            };
+
+            let len = data.len();
+            generate =
+                move |vt: &mut Vt, range: Range<usize>| -> SourceResult<Vec<Content>> {
+                    let mut cells = Vec::with_capacity(range.len() * cols);
+                    for i in range {
+                        let item = data.as_slice()[i].clone();
+                        let generated: Vec<Content> =
+                            row.call_vt(vt, [item])?.cast().at(row.span())?;
+                        if generated.len() > cols {
+                            bail!(
+                                row.span(),
+                                "expected at most {cols} cells, found {}",
+                                generated.len()
+                            );
+                        }
+
+                        let start = cells.len();
+                        for (x, child) in generated.into_iter().enumerate() {
+                            cells.push(prepare(vt, x, explicit + i, child)?);
+                        }
+                        cells.resize(start + cols, Content::empty());
+                    }
+                    Ok(cells)
+                };
 
-                Ok(child)
-            })
-            .collect::<SourceResult<_>>()?;
+            Some(LazyRows { len, generate: &mut generate })
+        };
 
         let fill = self.fill(styles);
         let stroke = self.stroke(styles).map(PartialStroke::unwrap_or_default);
 
         // Prepare grid layout by unifying content and gutter tracks.
-        let layouter = GridLayouter::new(
+        let layouter = GridLayouter::with_lazy_rows(
             tracks.as_deref(),
             gutter.as_deref(),
             &cells,
+            lazy,
             regions,
             styles,
         );
@@ -293,8 +369,11 @@ impl<T: FromValue> FromValue for Celled<T> {
     fn from_value(value: Value) -> StrResult<Self> {
         match value {
             Value::Func(v) => Ok(Self::Func(v)),
-            Value::Array(array) => Ok(Self::Array(
-                array.into_iter().map(T::from_value).collect::<StrResult<_>>()?,
+            v if Array::castable(&v) => Ok(Self::Array(
+                v.cast::<Array>()?
+                    .into_iter()
+                    .map(T::from_value)
+                    .collect::<StrResult<_>>()?,
             )),
             v if T::castable(&v) => Ok(Self::Value(T::from_value(v)?)),
             v => Err(Self::error(&v)),
@@ -303,32 +382,44 @@ impl<T: FromValue> FromValue for Celled<T> {
 }
 
 impl LocalName for TableElem {
-    fn local_name(&self, lang: Lang, _: Option<Region>) -> &'static str {
-        match lang {
-            Lang::ALBANIAN => "Tabel",
-            Lang::ARABIC => "جدول",
-            Lang::BOKMÅL => "Tabell",
-            Lang::CHINESE => "表",
-            Lang::CZECH => "Tabulka",
-            Lang::DANISH => "Tabel",
-            Lang::DUTCH => "Tabel",
-            Lang::FILIPINO => "Talaan",
-            Lang::FRENCH => "Tableau",
-            Lang::GERMAN => "Tabelle",
-            Lang::ITALIAN => "Tabella",
-            Lang::NYNORSK => "Tabell",
-            Lang::POLISH => "Tabela",
-            Lang::PORTUGUESE => "Tabela",
-            Lang::RUSSIAN => "Таблица",
-            Lang::SLOVENIAN => "Tabela",
-            Lang::SPANISH => "Tabla",
-            Lang::SWEDISH => "Tabell",
-            Lang::TURKISH => "Tablo",
-            Lang::UKRAINIAN => "Таблиця",
-            Lang::VIETNAMESE => "Bảng",
-            Lang::ENGLISH | _ => "Table",
-        }
+    fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
+        Term::Table.local(lang, region)
     }
 }
 
 impl Figurable for TableElem {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::compile;
+
+    /// A table with a hundred and fifty rows of numbers and their squares.
+    fn squares(table: &str) -> Document {
+        compile(&format!(
+            "#set page(width: 200pt, height: 200pt)\n\
+             #let data = ()\n\
+             #let cells = ()\n\
+             #for n in range(150) {{\n\
+               data.push(n)\n\
+               cells += ([#n], [#(n * n)])\n\
+             }}\n\
+             #table(columns: (auto, 1fr), row-gutter: 2pt, [*N*], [*Square*], {table})"
+        ))
+    }
+
+    #[test]
+    fn test_table_data_rows() {
+        // Rows generated in chunks while laying out, with the auto column
+        // measured across all chunks, look exactly like the given cells.
+        let given = squares("..cells");
+        let generated = squares("data: data, row: n => ([#n], [#(n * n)])");
+        assert!(given.pages.len() > 2);
+        assert_eq!(given.pages.len(), generated.pages.len());
+        for (a, b) in given.pages.iter().zip(&generated.pages) {
+            let a = typst::export::render(a, 1.0, Color::WHITE);
+            let b = typst::export::render(b, 1.0, Color::WHITE);
+            assert!(a.data() == b.data());
+        }
+    }
+}
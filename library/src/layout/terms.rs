@@ -101,7 +101,10 @@ pub struct TermsElem {
 }
 
 impl Layout for TermsElem {
-    #[tracing::instrument(name = "TermsElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "TermsElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
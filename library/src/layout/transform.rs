@@ -37,7 +37,10 @@ pub struct MoveElem {
 }
 
 impl Layout for MoveElem {
-    #[tracing::instrument(name = "MoveElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "MoveElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -107,7 +110,10 @@ pub struct RotateElem {
 }
 
 impl Layout for RotateElem {
-    #[tracing::instrument(name = "RotateElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "RotateElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -176,7 +182,10 @@ pub struct ScaleElem {
 }
 
 impl Layout for ScaleElem {
-    #[tracing::instrument(name = "ScaleElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ScaleElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -14,8 +14,11 @@ pub mod symbols;
 pub mod text;
 pub mod visualize;
 
+#[cfg(test)]
+mod tests;
+
 use typst::diag::At;
-use typst::eval::{LangItems, Library, Module, Scope};
+use typst::eval::{Dict, Features, LangItems, Library, Module, Scope};
 use typst::geom::Smart;
 use typst::model::{Element, Styles};
 
@@ -23,14 +26,26 @@ use self::layout::LayoutRoot;
 
 /// Construct the standard library.
 pub fn build() -> Library {
+    build_with_inputs(Dict::new())
+}
+
+/// Construct the standard library with inputs that documents can read from
+/// `sys.inputs`.
+pub fn build_with_inputs(inputs: Dict) -> Library {
     let math = math::module();
-    let global = global(math.clone());
-    Library { global, math, styles: styles(), items: items() }
+    let global = global(math.clone(), inputs);
+    Library {
+        global,
+        math,
+        styles: styles(),
+        items: items(),
+        features: Features::default(),
+    }
 }
 
 /// Construct the module with global definitions.
-#[tracing::instrument(skip_all)]
-fn global(math: Module) -> Module {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn global(math: Module, inputs: Dict) -> Module {
     let mut global = Scope::deduplicating();
 
     // Categories.
@@ -38,7 +53,7 @@ fn global(math: Module) -> Module {
     layout::define(&mut global);
     visualize::define(&mut global);
     meta::define(&mut global);
-    compute::define(&mut global);
+    compute::define(&mut global, inputs);
     symbols::define(&mut global);
     global.define("math", math);
 
@@ -86,6 +101,8 @@ fn items() -> LangItems {
         bibliography_keys: meta::BibliographyElem::keys,
         heading: |level, title| meta::HeadingElem::new(title).with_level(level).pack(),
         heading_func: meta::HeadingElem::func(),
+        part_func: meta::PartElem::func(),
+        image_func: visualize::ImageElem::func(),
         list_item: |body| layout::ListItem::new(body).pack(),
         enum_item: |number, body| {
             let mut elem = layout::EnumItem::new(body);
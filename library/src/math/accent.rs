@@ -52,7 +52,7 @@ pub struct AccentElem {
 }
 
 impl LayoutMath for AccentElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         ctx.style(ctx.style.with_cramped(true));
         let base = ctx.layout_fragment(&self.base())?;
@@ -8,7 +8,7 @@ use super::*;
 pub struct AlignPointElem {}
 
 impl LayoutMath for AlignPointElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         ctx.push(MathFragment::Align);
         Ok(())
@@ -54,7 +54,7 @@ pub struct AttachElem {
 }
 
 impl LayoutMath for AttachElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         type GetAttachment = fn(&AttachElem, styles: StyleChain) -> Option<Content>;
         let layout_attachment = |ctx: &mut MathContext, getter: GetAttachment| {
@@ -101,7 +101,7 @@ pub struct ScriptsElem {
 }
 
 impl LayoutMath for ScriptsElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         let mut fragment = ctx.layout_fragment(&self.body())?;
         fragment.set_limits(Limits::Never);
@@ -127,7 +127,7 @@ pub struct LimitsElem {
 }
 
 impl LayoutMath for LimitsElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         let mut fragment = ctx.layout_fragment(&self.body())?;
         fragment.set_limits(Limits::Always);
@@ -0,0 +1,171 @@
+use super::*;
+
+/// A chemical formula.
+///
+/// Element symbols are set upright, counts following an element or a closing
+/// bracket become subscripts and charges become superscripts. Charges can
+/// either directly follow the element (`Na+`) or be introduced with a hat
+/// (`SO4^2-`). Reaction arrows can be written as `->`, `<-` and `<=>`.
+///
+/// ## Example { #example }
+/// ```example
+/// $ ce("2H2 + O2 -> 2H2O") $
+/// $ ce("SO4^2- + Ba^2+ -> BaSO4") $
+/// ```
+///
+/// Display: Chemical Formula
+/// Category: math
+#[func]
+pub fn ce(
+    /// The formula to typeset.
+    formula: EcoString,
+) -> Content {
+    let mut seq = vec![];
+    let mut s = formula.as_str();
+    while let Some(c) = s.chars().next() {
+        if let Some((arrow, rest)) = [("<=>", "⇌"), ("->", "→"), ("<-", "←")]
+            .iter()
+            .find_map(|&(pat, arrow)| Some((arrow, s.strip_prefix(pat)?)))
+        {
+            seq.push(TextElem::packed(arrow));
+            s = rest;
+            continue;
+        }
+
+        if c.is_whitespace() {
+            seq.push(SpaceElem::new().pack());
+            s = s.trim_start();
+            continue;
+        }
+
+        let (base, rest) = if c.is_ascii_uppercase() {
+            split(s, c.len_utf8(), |c| c.is_ascii_lowercase())
+        } else if matches!(c, ')' | ']') {
+            s.split_at(1)
+        } else {
+            // Coefficients, opening brackets and all other characters.
+            let (head, rest) = if c.is_ascii_digit() {
+                split(s, 0, |c| c.is_ascii_digit())
+            } else {
+                s.split_at(c.len_utf8())
+            };
+            seq.push(upright_text(head));
+            s = rest;
+            continue;
+        };
+
+        // Counts directly follow the element or bracket.
+        let (count, rest) = split(rest, 0, |c| c.is_ascii_digit());
+
+        // Charges are either introduced by a hat or directly follow the
+        // element (or its count) when they aren't followed by more formula.
+        let (charge, rest) = if let Some(rest) = rest.strip_prefix('^') {
+            split(rest, 0, |c| c.is_ascii_digit() || matches!(c, '+' | '-'))
+        } else if rest.starts_with(['+', '-'])
+            && rest[1..].chars().next().map_or(true, |c| !c.is_alphanumeric())
+        {
+            rest.split_at(1)
+        } else {
+            ("", rest)
+        };
+
+        let mut attach = AttachElem::new(upright_text(base));
+        if !count.is_empty() {
+            attach.push_br(Some(upright_text(count)));
+        }
+        if !charge.is_empty() {
+            attach.push_tr(Some(upright_text(&charge.replace('-', "−"))));
+        }
+
+        seq.push(attach.pack());
+        s = rest;
+    }
+
+    Content::sequence(seq)
+}
+
+/// An isotope of a chemical element.
+///
+/// ## Example { #example }
+/// ```example
+/// $ isotope("U", 235, number: 92) $
+/// ```
+///
+/// Display: Isotope
+/// Category: math
+#[func]
+pub fn isotope(
+    /// The element's symbol.
+    element: EcoString,
+    /// The mass number, placed at the top left.
+    mass: Content,
+    /// The atomic number, placed at the bottom left.
+    #[named]
+    #[default]
+    number: Option<Content>,
+) -> Content {
+    AttachElem::new(upright_text(&element))
+        .with_tl(Some(mass))
+        .with_bl(number)
+        .pack()
+}
+
+/// A physical unit.
+///
+/// The unit's factors are set upright and separated by thin spaces. Factors
+/// can be separated by spaces or `*`, exponents are introduced with a hat and
+/// a slash divides factors.
+///
+/// ## Example { #example }
+/// ```example
+/// $ g = 9.81 thin unit("m/s^2") $
+/// $ Phi = 1 thin unit("kg m^2 s^-2 A^-1") $
+/// ```
+///
+/// Display: Unit
+/// Category: math
+#[func]
+pub fn unit(
+    /// The unit to typeset.
+    unit: EcoString,
+) -> Content {
+    let mut seq = vec![];
+    for factor in unit.split(|c: char| c.is_whitespace() || c == '*') {
+        if factor.is_empty() {
+            continue;
+        }
+
+        if !seq.is_empty() {
+            seq.push(HElem::new(THIN.into()).pack());
+        }
+
+        for (j, part) in factor.split('/').enumerate() {
+            if j > 0 {
+                seq.push(upright_text("/"));
+            }
+
+            let (name, exp) = part.split_once('^').unwrap_or((part, ""));
+            let mut attach = AttachElem::new(upright_text(name));
+            if !exp.is_empty() {
+                attach.push_tr(Some(upright_text(&exp.replace('-', "−"))));
+            }
+            seq.push(attach.pack());
+        }
+    }
+
+    Content::sequence(seq)
+}
+
+/// Split off the first `skip` bytes of `s` and the following characters that
+/// match the predicate.
+fn split(s: &str, skip: usize, f: impl Fn(char) -> bool) -> (&str, &str) {
+    let len = s[skip..].find(|c| !f(c)).map_or(s.len(), |i| skip + i);
+    s.split_at(len)
+}
+
+/// Text that is set upright in math.
+fn upright_text(text: &str) -> Content {
+    MathStyleElem::new(TextElem::packed(text))
+        .with_italic(Some(false))
+        .pack()
+}
@@ -30,7 +30,7 @@ pub struct FracElem {
 }
 
 impl LayoutMath for FracElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(ctx, &self.num(), &self.denom(), false, self.span())
     }
@@ -134,7 +134,7 @@ fn layout(
             line_pos,
             FrameItem::Shape(
                 Geometry::Line(Point::with_x(line_width)).stroked(Stroke {
-                    paint: TextElem::fill_in(ctx.styles()),
+                    paint: TextElem::fill_in(ctx.styles()).as_paint(),
                     thickness,
                     ..Stroke::default()
                 }),
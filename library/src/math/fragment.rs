@@ -157,6 +157,7 @@ pub struct GlyphFragment {
     pub c: char,
     pub font: Font,
     pub lang: Lang,
+    pub region: Option<Region>,
     pub fill: Paint,
     pub width: Abs,
     pub ascent: Abs,
@@ -192,7 +193,8 @@ impl GlyphFragment {
             c,
             font: ctx.font.clone(),
             lang: TextElem::lang_in(ctx.styles()),
-            fill: TextElem::fill_in(ctx.styles()),
+            region: TextElem::region_in(ctx.styles()),
+            fill: TextElem::fill_in(ctx.styles()).as_paint(),
             style: ctx.style,
             font_size: ctx.size,
             width: Abs::zero(),
@@ -256,6 +258,7 @@ impl GlyphFragment {
             size: self.font_size,
             fill: self.fill,
             lang: self.lang,
+            region: self.region,
             text: self.c.into(),
             glyphs: vec![Glyph {
                 id: self.id.0,
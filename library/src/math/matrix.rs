@@ -1,3 +1,5 @@
+use typst::eval::Reflect;
+
 use super::*;
 
 const ROW_GAP: Em = Em::new(0.5);
@@ -27,16 +29,27 @@ pub struct VecElem {
     #[default(Some(Delimiter::Paren))]
     pub delim: Option<Delimiter>,
 
+    /// The gap between elements.
+    ///
+    /// ```example
+    /// #set math.vec(gap: 1em)
+    /// $ vec(1, 2) $
+    /// ```
+    #[resolve]
+    #[default(ROW_GAP.into())]
+    pub gap: Length,
+
     /// The elements of the vector.
     #[variadic]
     pub children: Vec<Content>,
 }
 
 impl LayoutMath for VecElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         let delim = self.delim(ctx.styles());
-        let frame = layout_vec_body(ctx, &self.children(), Align::Center)?;
+        let gap = self.gap(ctx.styles());
+        let frame = layout_vec_body(ctx, &self.children(), Align::Center, gap)?;
         layout_delimiters(
             ctx,
             frame,
@@ -80,6 +93,73 @@ pub struct MatElem {
     #[default(Some(Delimiter::Paren))]
     pub delim: Option<Delimiter>,
 
+    /// Draws augmentation lines in a matrix.
+    ///
+    /// - `{none}`: No lines are drawn.
+    /// - A single number: A vertical augmentation line is drawn after the
+    ///   specified column number. Negative numbers count from the end.
+    /// - A dictionary: With a dictionary, multiple augmentation lines can be
+    ///   drawn both horizontally and vertically. Additionally, the style of the
+    ///   lines can be set. The dictionary can contain the following keys:
+    ///   - `hline`: The offsets at which horizontal lines should be drawn.
+    ///     For example, an offset of `2` would result in a horizontal line
+    ///     being drawn after the second row of the matrix. Accepts either an
+    ///     integer for a single line, or an array of integers for multiple
+    ///     lines.
+    ///   - `vline`: The offsets at which vertical lines should be drawn.
+    ///     Works like `hline`, but for columns.
+    ///   - `stroke`: How to [stroke]($type/stroke) the line. If set to
+    ///     `{auto}`, takes on the thickness of a fraction line and square line
+    ///     caps.
+    ///
+    /// ```example
+    /// $ mat(1, 0, 1; 0, 1, 2; augment: #2) $
+    /// ```
+    ///
+    /// ```example
+    /// $ mat(0, 0, 0; 1, 1, 1; augment: #(hline: 1, stroke: 2pt + green)) $
+    /// ```
+    #[resolve]
+    pub augment: Option<Augment>,
+
+    /// The gap between rows and columns.
+    ///
+    /// This is a shorthand to set `row-gap` and `column-gap` to the same
+    /// value.
+    ///
+    /// ```example
+    /// #set math.mat(gap: 1em)
+    /// $ mat(1, 2; 3, 4) $
+    /// ```
+    #[external]
+    #[default(ROW_GAP.into())]
+    pub gap: Length,
+
+    /// The gap between rows.
+    ///
+    /// ```example
+    /// #set math.mat(row-gap: 1em)
+    /// $ mat(1, 2; 3, 4) $
+    /// ```
+    #[resolve]
+    #[parse(
+        let gap = args.named("gap")?;
+        args.named("row-gap")?.or(gap)
+    )]
+    #[default(ROW_GAP.into())]
+    pub row_gap: Length,
+
+    /// The gap between columns.
+    ///
+    /// ```example
+    /// #set math.mat(column-gap: 1em)
+    /// $ mat(1, 2; 3, 4) $
+    /// ```
+    #[resolve]
+    #[parse(args.named("column-gap")?.or(gap))]
+    #[default(COL_GAP.into())]
+    pub column_gap: Length,
+
     /// An array of arrays with the rows of the matrix.
     ///
     /// ```example
@@ -93,7 +173,7 @@ pub struct MatElem {
         let mut width = 0;
 
         let values = args.all::<Spanned<Value>>()?;
-        if values.iter().any(|spanned| matches!(spanned.v, Value::Array(_))) {
+        if values.iter().any(|spanned| Array::castable(&spanned.v)) {
             for Spanned { v, span } in values {
                 let array = v.cast::<Array>().at(span)?;
                 let row: Vec<_> = array.into_iter().map(Value::display).collect();
@@ -116,10 +196,13 @@ pub struct MatElem {
 }
 
 impl LayoutMath for MatElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
-        let delim = self.delim(ctx.styles());
-        let frame = layout_mat_body(ctx, &self.rows())?;
+        let styles = ctx.styles();
+        let delim = self.delim(styles);
+        let augment = self.augment(styles);
+        let gap = Axes::new(self.column_gap(styles), self.row_gap(styles));
+        let frame = layout_mat_body(ctx, &self.rows(), augment, gap, self.span())?;
         layout_delimiters(
             ctx,
             frame,
@@ -157,16 +240,27 @@ pub struct CasesElem {
     #[default(Delimiter::Brace)]
     pub delim: Delimiter,
 
+    /// The gap between branches.
+    ///
+    /// ```example
+    /// #set math.cases(gap: 1em)
+    /// $ x = cases(1, 2) $
+    /// ```
+    #[resolve]
+    #[default(ROW_GAP.into())]
+    pub gap: Length,
+
     /// The branches of the case distinction.
     #[variadic]
     pub children: Vec<Content>,
 }
 
 impl LayoutMath for CasesElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         let delim = self.delim(ctx.styles());
-        let frame = layout_vec_body(ctx, &self.children(), Align::Left)?;
+        let gap = self.gap(ctx.styles());
+        let frame = layout_vec_body(ctx, &self.children(), Align::Left, gap)?;
         layout_delimiters(ctx, frame, Some(delim.open()), None, self.span())
     }
 }
@@ -220,8 +314,8 @@ fn layout_vec_body(
     ctx: &mut MathContext,
     column: &[Content],
     align: Align,
+    gap: Abs,
 ) -> SourceResult<Frame> {
-    let gap = ROW_GAP.scaled(ctx);
     ctx.style(ctx.style.for_denominator());
     let mut flat = vec![];
     for child in column {
@@ -232,10 +326,13 @@ fn layout_vec_body(
 }
 
 /// Layout the inner contents of a matrix.
-fn layout_mat_body(ctx: &mut MathContext, rows: &[Vec<Content>]) -> SourceResult<Frame> {
-    let row_gap = ROW_GAP.scaled(ctx);
-    let col_gap = COL_GAP.scaled(ctx);
-
+fn layout_mat_body(
+    ctx: &mut MathContext,
+    rows: &[Vec<Content>],
+    augment: Option<Augment<Abs>>,
+    gap: Axes<Abs>,
+    span: Span,
+) -> SourceResult<Frame> {
     let ncols = rows.first().map_or(0, |row| row.len());
     let nrows = rows.len();
     if ncols == 0 || nrows == 0 {
@@ -256,10 +353,18 @@ fn layout_mat_body(ctx: &mut MathContext, rows: &[Vec<Content>]) -> SourceResult
     }
     ctx.unstyle();
 
-    let mut frame = Frame::new(Size::new(
-        Abs::zero(),
-        heights.iter().map(|&(a, b)| a + b).sum::<Abs>() + row_gap * (nrows - 1) as f64,
-    ));
+    // The vertical offsets at which the rows end.
+    let mut row_ends = vec![];
+    let mut y = Abs::zero();
+    for &(ascent, descent) in &heights {
+        y += ascent + descent;
+        row_ends.push(y);
+        y += gap.y;
+    }
+
+    let height = y - gap.y;
+    let mut frame = Frame::new(Size::new(Abs::zero(), height));
+    let mut col_ends = vec![];
     let mut x = Abs::zero();
     for col in cols {
         let AlignmentResult { points, width: rcol } = alignments(&col);
@@ -271,15 +376,110 @@ fn layout_mat_body(ctx: &mut MathContext, rows: &[Vec<Content>]) -> SourceResult
                 y + ascent - cell.ascent(),
             );
             frame.push_frame(pos, cell);
-            y += ascent + descent + row_gap;
+            y += ascent + descent + gap.y;
+        }
+        x += rcol;
+        col_ends.push(x);
+        x += gap.x;
+    }
+
+    let width = x - gap.x;
+    frame.size_mut().x = width;
+
+    if let Some(augment) = augment {
+        let thickness = scaled!(ctx, fraction_rule_thickness);
+        let stroke = augment.stroke.unwrap_or_default().unwrap_or(Stroke {
+            paint: TextElem::fill_in(ctx.styles()).as_paint(),
+            thickness,
+            line_cap: LineCap::Square,
+            ..Default::default()
+        });
+
+        for offset in augment.hline {
+            let Some(i) = resolve_offset(offset, nrows) else {
+                bail!(span, "cannot draw a horizontal line at offset {offset}");
+            };
+            let y = row_ends[i - 1] + gap.y / 2.0;
+            let line = Geometry::Line(Point::with_x(width)).stroked(stroke.clone());
+            frame.push(Point::with_y(y), FrameItem::Shape(line, span));
+        }
+
+        for offset in augment.vline {
+            let Some(i) = resolve_offset(offset, ncols) else {
+                bail!(span, "cannot draw a vertical line at offset {offset}");
+            };
+            let x = col_ends[i - 1] + gap.x / 2.0;
+            let line = Geometry::Line(Point::with_y(height)).stroked(stroke.clone());
+            frame.push(Point::with_x(x), FrameItem::Shape(line, span));
         }
-        x += rcol + col_gap;
     }
-    frame.size_mut().x = x - col_gap;
 
     Ok(frame)
 }
 
+/// Resolve a possibly negative line offset into the number of rows or columns
+/// before the line. Only offsets that lie between two rows or columns are
+/// valid.
+fn resolve_offset(offset: isize, len: usize) -> Option<usize> {
+    let i = if offset < 0 { len as isize + offset } else { offset };
+    (0 < i && i < len as isize).then_some(i as usize)
+}
+
+/// Parameters specifying how augmentation lines should be drawn on a matrix.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct Augment<T = Length> {
+    /// The offsets after which horizontal lines are drawn.
+    pub hline: Vec<isize>,
+    /// The offsets after which vertical lines are drawn.
+    pub vline: Vec<isize>,
+    /// How to stroke the lines.
+    pub stroke: Smart<PartialStroke<T>>,
+}
+
+impl Resolve for Augment {
+    type Output = Augment<Abs>;
+
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        Augment {
+            hline: self.hline,
+            vline: self.vline,
+            stroke: self.stroke.resolve(styles),
+        }
+    }
+}
+
+cast! {
+    Augment,
+    self => {
+        let stroke = self.stroke.unwrap_or_default();
+        let mut dict = dict! {
+            "hline" => self.hline,
+            "vline" => self.vline,
+        };
+        if stroke != PartialStroke::default() {
+            dict.insert("stroke".into(), stroke.into_value());
+        }
+        dict.into_value()
+    },
+    v: isize => Augment { vline: vec![v], ..Default::default() },
+    mut dict: Dict => {
+        let mut take = |key| dict.take(key).ok().map(offsets).transpose();
+        let hline = take("hline")?.unwrap_or_default();
+        let vline = take("vline")?.unwrap_or_default();
+        let stroke = dict.take("stroke").ok().map(Value::cast).transpose()?;
+        dict.finish(&["hline", "vline", "stroke"])?;
+        Augment { hline, vline, stroke: stroke.unwrap_or_default() }
+    },
+}
+
+/// Cast a single offset or an array of offsets.
+fn offsets(value: Value) -> StrResult<Vec<isize>> {
+    match value {
+        Value::Array(array) => array.into_iter().map(Value::cast).collect(),
+        v => Ok(vec![v.cast()?]),
+    }
+}
+
 /// Layout the outer wrapper around a vector's or matrices' body.
 fn layout_delimiters(
     ctx: &mut MathContext,
@@ -311,3 +511,61 @@ fn layout_delimiters(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    #[test]
+    fn test_augment_lines() {
+        let document = compile(
+            "$ mat(delim: #none, augment: #(hline: 1, vline: -1), 1, 2; 3, 4) $\n\
+             #pagebreak()\n\
+             $ mat(augment: #(vline: 1, stroke: 2pt + green), 1, 2; 3, 4) $",
+        );
+
+        // The lines and the texts with their positions and widths.
+        let collect = |page: &Frame| {
+            let (mut lines, mut texts) = (vec![], vec![]);
+            for (pos, item) in items(page) {
+                match item {
+                    FrameItem::Shape(shape, _) => {
+                        if let Geometry::Line(delta) = shape.geometry {
+                            lines.push((pos, delta, shape.stroke.clone().unwrap()));
+                        }
+                    }
+                    FrameItem::Text(text) => {
+                        texts.push((text.text.to_string(), pos, text.width()))
+                    }
+                    _ => {}
+                }
+            }
+            (lines, texts)
+        };
+
+        // The horizontal line lies between the rows and spans all columns,
+        // the vertical one lies between the columns and spans all rows.
+        let (lines, texts) = collect(&document.pages[0]);
+        let text = |s: &str| texts.iter().find(|(text, ..)| text == s).unwrap().clone();
+        let (_, one, _) = text("1");
+        let (_, two, width) = text("2");
+        let (_, three, _) = text("3");
+        assert_eq!(lines.len(), 2);
+        let (hpos, hdelta, stroke) = &lines[0];
+        assert_eq!(hdelta.y, Abs::zero());
+        assert!(one.y < hpos.y && hpos.y < three.y);
+        assert!(hpos.x <= one.x && two.x + width <= hpos.x + hdelta.x);
+        assert_eq!(stroke.line_cap, LineCap::Square);
+        let (vpos, vdelta, _) = &lines[1];
+        assert_eq!(vdelta.x, Abs::zero());
+        assert!(one.x < vpos.x && vpos.x < two.x);
+        assert!(vpos.y < one.y && three.y <= vpos.y + vdelta.y);
+
+        // A custom stroke replaces the default one.
+        let (lines, _) = collect(&document.pages[1]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].2.thickness, Abs::pt(2.0));
+        assert_eq!(lines[0].2.paint, Paint::from(Color::GREEN));
+    }
+}
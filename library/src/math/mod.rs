@@ -6,6 +6,7 @@ mod accent;
 mod align;
 mod attach;
 mod cancel;
+mod chem;
 mod delimited;
 mod frac;
 mod fragment;
@@ -22,6 +23,7 @@ pub use self::accent::*;
 pub use self::align::*;
 pub use self::attach::*;
 pub use self::cancel::*;
+pub use self::chem::*;
 pub use self::delimited::*;
 pub use self::frac::*;
 pub use self::matrix::*;
@@ -34,7 +36,6 @@ use ttf_parser::{GlyphId, Rect};
 use typst::eval::{Module, Scope};
 use typst::font::{Font, FontWeight};
 use typst::model::Guard;
-use typst::util::option_eq;
 use unicode_math_class::MathClass;
 
 use self::ctx::*;
@@ -47,6 +48,7 @@ use crate::meta::{
     Count, Counter, CounterUpdate, LocalName, Numbering, Outlinable, Refable,
 };
 use crate::prelude::*;
+use crate::text::locale::Term;
 use crate::text::{
     families, variant, FontFamily, FontList, LinebreakElem, SpaceElem, TextElem, TextSize,
 };
@@ -76,6 +78,8 @@ pub fn module() -> Module {
     math.define("overbrace", OverbraceElem::func());
     math.define("underbracket", UnderbracketElem::func());
     math.define("overbracket", OverbracketElem::func());
+    math.define("underparen", UnderparenElem::func());
+    math.define("overparen", OverparenElem::func());
     math.define("cancel", CancelElem::func());
 
     // Fractions and matrix-likes.
@@ -105,6 +109,11 @@ pub fn module() -> Module {
     math.define("script", script_func());
     math.define("sscript", sscript_func());
 
+    // Notations.
+    math.define("ce", ce_func());
+    math.define("isotope", isotope_func());
+    math.define("unit", unit_func());
+
     // Text operators.
     math.define("op", OpElem::func());
     op::define(&mut math);
@@ -209,7 +218,10 @@ impl Synthesize for EquationElem {
 }
 
 impl Show for EquationElem {
-    #[tracing::instrument(name = "EquationElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "EquationElem::show", skip_all)
+    )]
     fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let mut realized = self.clone().pack().guarded(Guard::Base(Self::func()));
         if self.block(styles) {
@@ -230,7 +242,10 @@ impl Finalize for EquationElem {
 }
 
 impl Layout for EquationElem {
-    #[tracing::instrument(name = "EquationElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "EquationElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -314,31 +329,7 @@ impl Count for EquationElem {
 
 impl LocalName for EquationElem {
     fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
-        match lang {
-            Lang::ALBANIAN => "Ekuacion",
-            Lang::ARABIC => "معادلة",
-            Lang::BOKMÅL => "Ligning",
-            Lang::CHINESE if option_eq(region, "TW") => "方程式",
-            Lang::CHINESE => "等式",
-            Lang::CZECH => "Rovnice",
-            Lang::DANISH => "Ligning",
-            Lang::DUTCH => "Vergelijking",
-            Lang::FILIPINO => "Ekwasyon",
-            Lang::FRENCH => "Équation",
-            Lang::GERMAN => "Gleichung",
-            Lang::ITALIAN => "Equazione",
-            Lang::NYNORSK => "Likning",
-            Lang::POLISH => "Równanie",
-            Lang::PORTUGUESE => "Equação",
-            Lang::RUSSIAN => "Уравнение",
-            Lang::SLOVENIAN => "Enačba",
-            Lang::SPANISH => "Ecuación",
-            Lang::SWEDISH => "Ekvation",
-            Lang::TURKISH => "Denklem",
-            Lang::UKRAINIAN => "Рівняння",
-            Lang::VIETNAMESE => "Phương trình",
-            Lang::ENGLISH | _ => "Equation",
-        }
+        Term::Equation.local(lang, region)
     }
 }
 
@@ -390,14 +381,14 @@ pub trait LayoutMath {
 }
 
 impl LayoutMath for EquationElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         self.body().layout_math(ctx)
     }
 }
 
 impl LayoutMath for Content {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         // Directly layout the body of nested equations instead of handling it
         // like a normal equation so that things like this work:
@@ -32,7 +32,7 @@ pub struct OpElem {
 }
 
 impl LayoutMath for OpElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         let fragment =
             ctx.layout_text(&TextElem::new(self.text()).spanned(self.span()))?;
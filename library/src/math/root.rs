@@ -38,7 +38,7 @@ pub struct RootElem {
 }
 
 impl LayoutMath for RootElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(ctx, self.index(ctx.styles()).as_ref(), &self.radicand(), self.span())
     }
@@ -122,7 +122,7 @@ fn layout(
         line_pos,
         FrameItem::Shape(
             Geometry::Line(Point::with_x(radicand.width())).stroked(Stroke {
-                paint: TextElem::fill_in(ctx.styles()),
+                paint: TextElem::fill_in(ctx.styles()).as_paint(),
                 thickness,
                 ..Stroke::default()
             }),
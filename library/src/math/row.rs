@@ -1,6 +1,7 @@
 use std::iter::once;
 
 use crate::layout::AlignElem;
+use crate::meta::StdElem;
 
 use super::*;
 
@@ -149,10 +150,11 @@ impl MathRow {
             }
 
             let AlignmentResult { points, width } = alignments(&rows);
+            let alternate = alternates(ctx);
             let mut frame = Frame::new(Size::zero());
 
             for (i, row) in rows.into_iter().enumerate() {
-                let sub = row.into_line_frame(&points, align);
+                let sub = row.into_line_frame(&points, align, alternate);
                 let size = frame.size_mut();
                 if i > 0 {
                     size.y += leading;
@@ -168,11 +170,11 @@ impl MathRow {
             }
             frame
         } else {
-            self.into_line_frame(points, align)
+            self.into_line_frame(points, align, alternates(ctx))
         }
     }
 
-    fn into_line_frame(self, points: &[Abs], align: Align) -> Frame {
+    fn into_line_frame(self, points: &[Abs], align: Align, alternate: bool) -> Frame {
         let ascent = self.ascent();
         let mut frame = Frame::new(Size::new(Abs::zero(), ascent + self.descent()));
         frame.set_baseline(ascent);
@@ -199,6 +201,9 @@ impl MathRow {
             move || match align {
                 Align::Left => prev_points.next(),
                 Align::Right => point_widths.next().map(|(point, width)| point - width),
+                _ if !alternate => {
+                    point_widths.next().map(|(point, width)| point - width)
+                }
                 _ => point_widths
                     .next()
                     .zip(prev_points.next())
@@ -228,6 +233,12 @@ impl MathRow {
     }
 }
 
+/// Whether columns between alignment points alternate between right and left
+/// alignment, which they only do since Typst 0.4.
+fn alternates(ctx: &MathContext) -> bool {
+    !StdElem::is_before(ctx.styles(), [0, 4])
+}
+
 impl<T: Into<MathFragment>> From<T> for MathRow {
     fn from(fragment: T) -> Self {
         Self(vec![fragment.into()])
@@ -288,7 +288,7 @@ pub struct MathStyleElem {
 }
 
 impl LayoutMath for MathStyleElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         let mut style = ctx.style;
         if let Some(variant) = self.variant(StyleChain::default()) {
@@ -403,11 +403,24 @@ pub enum MathSize {
 }
 
 impl MathSize {
+    /// The factor by which glyphs of this size are scaled down.
+    ///
+    /// Some fonts leave the scale-down constants of their MATH table at zero.
+    /// For those, we fall back to the ratios recommended by the OpenType
+    /// specification.
     pub(super) fn factor(self, ctx: &MathContext) -> f64 {
-        match self {
-            Self::Display | Self::Text => 1.0,
+        let factor = match self {
+            Self::Display | Self::Text => return 1.0,
             Self::Script => percent!(ctx, script_percent_scale_down),
             Self::ScriptScript => percent!(ctx, script_script_percent_scale_down),
+        };
+
+        if factor > 0.0 {
+            factor
+        } else if self == Self::Script {
+            0.7
+        } else {
+            0.5
         }
     }
 }
@@ -618,3 +631,41 @@ fn greek_exception(
         _ => return None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::tests::{compile, items};
+
+    #[test]
+    fn test_math_script_sizes() {
+        let document = compile(
+            "#set text(size: 10pt)\n\
+             $x^(y^z)$ and $a/b$\n\
+             $ c/d $",
+        );
+
+        let sizes: HashMap<_, _> = items(&document.pages[0])
+            .into_iter()
+            .filter_map(|(_, item)| match item {
+                FrameItem::Text(text) => Some((text.text.clone(), text.size.to_pt())),
+                _ => None,
+            })
+            .collect();
+
+        // Scripts shrink to the MATH table's script and script-script sizes.
+        // Fractions do the same for their numerator and denominator in inline
+        // math, but keep the text size in display math.
+        let close =
+            |c: char, size: f64| (sizes[c.to_string().as_str()] - size).abs() < 1e-6;
+        assert!(close('𝑥', 10.0));
+        assert!(close('𝑦', 7.0));
+        assert!(close('𝑧', 5.0));
+        assert!(close('𝑎', 7.0));
+        assert!(close('𝑏', 7.0));
+        assert!(close('𝑐', 10.0));
+        assert!(close('𝑑', 10.0));
+    }
+}
@@ -3,6 +3,7 @@ use super::*;
 const LINE_GAP: Em = Em::new(0.15);
 const BRACE_GAP: Em = Em::new(0.25);
 const BRACKET_GAP: Em = Em::new(0.25);
+const PAREN_GAP: Em = Em::new(0.25);
 
 /// A horizontal line under content.
 ///
@@ -21,7 +22,7 @@ pub struct UnderlineElem {
 }
 
 impl LayoutMath for UnderlineElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(ctx, &self.body(), &None, '\u{305}', LINE_GAP, false, self.span())
     }
@@ -44,7 +45,7 @@ pub struct OverlineElem {
 }
 
 impl LayoutMath for OverlineElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(ctx, &self.body(), &None, '\u{332}', LINE_GAP, true, self.span())
     }
@@ -71,7 +72,7 @@ pub struct UnderbraceElem {
 }
 
 impl LayoutMath for UnderbraceElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(
             ctx,
@@ -106,7 +107,7 @@ pub struct OverbraceElem {
 }
 
 impl LayoutMath for OverbraceElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(
             ctx,
@@ -141,7 +142,7 @@ pub struct UnderbracketElem {
 }
 
 impl LayoutMath for UnderbracketElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(
             ctx,
@@ -176,7 +177,7 @@ pub struct OverbracketElem {
 }
 
 impl LayoutMath for OverbracketElem {
-    #[tracing::instrument(skip(ctx))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
     fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
         layout(
             ctx,
@@ -190,6 +191,76 @@ impl LayoutMath for OverbracketElem {
     }
 }
 
+/// A horizontal parenthesis under content, with an optional annotation below.
+///
+/// ## Example { #example }
+/// ```example
+/// $ underparen(1 + 2 + ... + 5, "numbers") $
+/// ```
+///
+/// Display: Underparen
+/// Category: math
+#[element(LayoutMath)]
+pub struct UnderparenElem {
+    /// The content above the parenthesis.
+    #[required]
+    pub body: Content,
+
+    /// The optional content below the parenthesis.
+    #[positional]
+    pub annotation: Option<Content>,
+}
+
+impl LayoutMath for UnderparenElem {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
+    fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
+        layout(
+            ctx,
+            &self.body(),
+            &self.annotation(ctx.styles()),
+            '⏝',
+            PAREN_GAP,
+            false,
+            self.span(),
+        )
+    }
+}
+
+/// A horizontal parenthesis over content, with an optional annotation above.
+///
+/// ## Example { #example }
+/// ```example
+/// $ overparen(1 + 2 + ... + 5, "numbers") $
+/// ```
+///
+/// Display: Overparen
+/// Category: math
+#[element(LayoutMath)]
+pub struct OverparenElem {
+    /// The content below the parenthesis.
+    #[required]
+    pub body: Content,
+
+    /// The optional content above the parenthesis.
+    #[positional]
+    pub annotation: Option<Content>,
+}
+
+impl LayoutMath for OverparenElem {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(ctx)))]
+    fn layout_math(&self, ctx: &mut MathContext) -> SourceResult<()> {
+        layout(
+            ctx,
+            &self.body(),
+            &self.annotation(ctx.styles()),
+            '⏜',
+            PAREN_GAP,
+            true,
+            self.span(),
+        )
+    }
+}
+
 /// Layout an over- or underthing.
 fn layout(
     ctx: &mut MathContext,
@@ -268,3 +339,54 @@ pub(super) fn stack(
 
     frame
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    /// The horizontal extent and the lowest baseline of the texts on a page
+    /// that match a predicate.
+    fn extent(page: &Frame, f: impl Fn(&str) -> bool) -> (Abs, Abs, Abs) {
+        let matching: Vec<_> = items(page)
+            .into_iter()
+            .filter_map(|(pos, item)| match item {
+                FrameItem::Text(text) if f(text.text.as_str()) => {
+                    Some((pos, text.width()))
+                }
+                _ => None,
+            })
+            .collect();
+        let start = matching.iter().map(|(pos, _)| pos.x).min().unwrap();
+        let end = matching.iter().map(|(pos, width)| pos.x + *width).max().unwrap();
+        let y = matching.iter().map(|(pos, _)| pos.y).max().unwrap();
+        (start, end, y)
+    }
+
+    #[test]
+    fn test_underparen_overparen() {
+        let document = compile(
+            "$ underparen(1 + 2 + 3 + 4 + 5, \"note\") $\n\
+             #pagebreak()\n\
+             $ overparen(1 + 2 + 3 + 4 + 5, \"note\") $",
+        );
+
+        // The parenthesis stretches to the width of the body and lies between
+        // the body and the annotation.
+        for (page, paren, below) in [(0, "⏝", true), (1, "⏜", false)] {
+            let page = &document.pages[page];
+            let (paren_start, paren_end, paren_y) = extent(page, |text| text == paren);
+            let (body_start, body_end, body_y) =
+                extent(page, |text| text != paren && text != "note");
+            let (_, _, note_y) = extent(page, |text| text == "note");
+            let tolerance = Abs::pt(1.0);
+            assert!(paren_start <= body_start + tolerance);
+            assert!(paren_end >= body_end - tolerance);
+            if below {
+                assert!(body_y < paren_y && paren_y < note_y);
+            } else {
+                assert!(note_y < paren_y && paren_y < body_y);
+            }
+        }
+    }
+}
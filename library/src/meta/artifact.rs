@@ -0,0 +1,76 @@
+use crate::prelude::*;
+
+/// Mark content as decorative.
+///
+/// Screen readers and other assistive technology skip artifacts in exported
+/// PDFs, so that ornaments, separators and repeated decorations don't
+/// interrupt the text. The content is still displayed as usual. The page's
+/// header, footer, background and foreground are marked as artifacts
+/// automatically.
+///
+/// To make a document more accessible in the other direction, give images an
+/// [alternative description]($func/image.alt) and set the
+/// [language]($func/text.lang) of text that isn't in the document's main
+/// language. Such text is tagged with its language in the PDF.
+///
+/// ## Example { #example }
+/// ```example
+/// #let ornament = artifact(align(center)[❦])
+///
+/// = Chapter One
+/// #ornament
+/// It was a dark and stormy night.
+/// ```
+///
+/// Display: Artifact
+/// Category: meta
+#[element(Show)]
+pub struct ArtifactElem {
+    /// The decorative content.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for ArtifactElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ArtifactElem::show", skip(self))
+    )]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body().styled(MetaElem::set_data(vec![Meta::Artifact])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::compile;
+
+    #[test]
+    fn test_pdf_structure_tree() {
+        let document = compile(
+            "#set text(lang: \"en\")\n\
+             Hello\n\n\
+             #text(lang: \"de\")[Hallo]\n\n\
+             #artifact[Ornament]\n\n\
+             #place(bottom, order: -1)[First]",
+        );
+        let pdf = typst::export::pdf(&document).unwrap();
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        // The three texts outside of the artifact are spans in the tree and
+        // only the text in a foreign language is tagged with its language.
+        assert!(pdf.contains("/StructTreeRoot"));
+        assert!(pdf.contains("/Marked true"));
+        assert_eq!(pdf.matches("/S /Span").count(), 3);
+        assert_eq!(pdf.matches("/Lang (de)").count(), 1);
+        assert_eq!(pdf.matches("/Lang (en)").count(), 1);
+
+        // The placed text was written last, but is read first.
+        let doc = &pdf[pdf.find("/S /Document").unwrap()..];
+        let kids = &doc[doc.find("/K [").unwrap() + 4..];
+        let first = kids.split(" 0 R").next().unwrap();
+        let elem = &pdf[pdf.find(&format!("\n{first} 0 obj")).unwrap()..];
+        let elem = &elem[..elem.find("endobj").unwrap()];
+        assert!(elem.contains("/K 2"));
+    }
+}
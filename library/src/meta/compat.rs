@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+use typst::eval::Version;
+
+use crate::layout::EnumElem;
+use crate::prelude::*;
+
+/// Pin a document to the behavior of an older Typst version.
+///
+/// Sometimes, a new version of Typst changes a default or how something is
+/// laid out. Setting the language version to an earlier release restores the
+/// behavior of that release, so that long-lived templates keep their look
+/// while being migrated. Like the [document]($func/document) function, this is
+/// only used with set rules and such a rule should appear at the very start of
+/// the document: It restores old defaults by applying set rules of its own,
+/// which override your own earlier set rules for the same properties.
+///
+/// The following behavior is restored:
+/// - Before 0.4.0, [enum numbers]($func/enum.number-align) were aligned
+///   towards the start instead of the end of the text direction.
+/// - Before 0.4.0, all columns between alignment points (`&`) in
+///   [equations]($func/math.equation) were aligned to the right instead of
+///   alternating between right and left alignment.
+///
+/// No default spacing has changed between the released versions so far.
+///
+/// ## Example { #example }
+/// ```example
+/// #set std(version: "0.3")
+///
+/// 1. One
+/// 10. Ten
+/// ```
+///
+/// Display: Std
+/// Category: meta
+#[element(Construct)]
+pub struct StdElem {
+    /// The language version whose behavior to select. Can be given as a
+    /// [version]($type/version) or a string like `{"0.3"}`. Defaults to the
+    /// version of the running compiler, which is available as
+    /// [`sys.version`]($category/foundations/sys).
+    #[parse(
+        let version: Option<Spanned<LangVersion>> = args.named("version")?;
+        if let Some(Spanned { v: LangVersion(version), span }) = &version {
+            let current = current();
+            if *version > current {
+                bail!(*span, "version {version} is newer than this compiler");
+            }
+            styles.apply(compat(version));
+        }
+        version.map(|version| version.v)
+    )]
+    #[default(LangVersion(current()))]
+    pub version: LangVersion,
+}
+
+impl Construct for StdElem {
+    fn construct(_: &mut Vm, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "can only be used in set rules")
+    }
+}
+
+impl StdElem {
+    /// Whether the selected language version is older than the given one.
+    pub fn is_before(styles: StyleChain, version: [u32; 2]) -> bool {
+        Self::version_in(styles).0 < Version::new(version)
+    }
+}
+
+/// A language version, given as a version or a string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LangVersion(pub Version);
+
+cast! {
+    LangVersion,
+    self => self.0.into_value(),
+    v: Version => Self(v),
+    v: Str => Self(Version::from_str(&v)?),
+}
+
+/// The version of the running compiler.
+fn current() -> Version {
+    Version::from_str(env!("CARGO_PKG_VERSION")).unwrap_or_default()
+}
+
+/// The styles that restore the defaults of an older version.
+fn compat(version: &Version) -> Styles {
+    let mut styles = Styles::new();
+    // Math alignment checks the version itself during layout.
+    if *version < Version::new([0, 4]) {
+        styles.set(EnumElem::set_number_align(HorizontalAlign(GenAlign::Start)));
+    }
+    styles
+}
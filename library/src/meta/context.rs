@@ -71,7 +71,10 @@ struct LocateElem {
 }
 
 impl Show for LocateElem {
-    #[tracing::instrument(name = "LocateElem::show", skip(self, vt))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LocateElem::show", skip(self, vt))
+    )]
     fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
         Ok(vt.delayed(|vt| {
             let location = self.0.location().unwrap();
@@ -124,7 +127,10 @@ struct StyleElem {
 }
 
 impl Show for StyleElem {
-    #[tracing::instrument(name = "StyleElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "StyleElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         Ok(self.func().call_vt(vt, [styles.to_map()])?.display())
     }
@@ -171,9 +177,17 @@ impl Show for StyleElem {
 /// Note that this function will provide an infinite width or height if one of
 /// the page width or height is `auto`, respectively.
 ///
+/// The function also serves as a namespace for the layout functions and
+/// constants, so that they are available as `layout.page`, `layout.box`,
+/// `layout.left`, etc. even if a document shadows their global names.
+///
 /// Display: Layout
 /// Category: meta
 #[func]
+#[scope(
+    crate::layout::define(&mut scope);
+    scope
+)]
 pub fn layout(
     /// A function to call with the outer container's size. Its return value is
     /// displayed in the document.
@@ -201,7 +215,10 @@ struct LayoutElem {
 }
 
 impl Layout for LayoutElem {
-    #[tracing::instrument(name = "LayoutElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LayoutElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
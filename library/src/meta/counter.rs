@@ -145,6 +145,33 @@ use crate::prelude::*;
 /// Arabic numbers.
 /// ```
 ///
+/// ## Sharing and nesting counters { #sharing }
+/// Several kinds of elements can share one numbering sequence by passing an
+/// array of element functions or selectors as the key, for example
+/// `{counter((theorem, lemma))}` for two [user-defined
+/// elements]($func/element). With `within`, the
+/// counter restarts whenever an element matching the given selector appears
+/// and its value is prefixed with the number of such elements so far. This is
+/// how theorems, lemmas and corollaries are typically numbered per section.
+///
+/// ```example
+/// #set heading(numbering: "1.")
+/// #let thm = counter(
+///   "theorem",
+///   within: heading.where(level: 1),
+/// )
+///
+/// = Basics
+/// #thm.step()
+/// Theorem #thm.display() \
+/// #thm.step()
+/// Lemma #thm.display()
+///
+/// = Advanced
+/// #thm.step()
+/// Theorem #thm.display()
+/// ```
+///
 /// ## Time travel { #time-travel }
 /// Counters can travel through time! You can find out the final value of the
 /// counter before it is reached and even determine what the value was at any
@@ -286,20 +313,42 @@ pub fn counter(
     ///   manual updates,
     /// - If this is a `{<label>}`, counts through all elements with that label,
     /// - If this is an element function or selector, counts through its elements,
-    /// - If this is the [`page`]($func/page) function, counts through pages.
+    /// - If this is the [`page`]($func/page) function, counts through pages,
+    /// - If this is an array of element functions, selectors or labels, counts
+    ///   through the elements matching any of them in one shared sequence.
     key: CounterKey,
+    /// Restart the counter at each element matching this selector.
+    ///
+    /// The counter's value is then prefixed with the number of matching
+    /// elements before it, so that for example the second theorem in the third
+    /// section is displayed as `3.2`. Counters with the same key but a
+    /// different `within` are distinct.
+    #[named]
+    #[default]
+    within: Option<LocatableSelector>,
 ) -> Counter {
-    Counter::new(key)
+    Counter::new(key).within(within.map(|selector| selector.0))
 }
 
 /// Counts through pages, elements, and more.
 #[derive(Clone, PartialEq, Hash)]
-pub struct Counter(CounterKey);
+pub struct Counter {
+    /// The key that identifies the counter.
+    key: CounterKey,
+    /// The elements at which the counter restarts.
+    within: Option<Selector>,
+}
 
 impl Counter {
     /// Create a new counter from a key.
     pub fn new(key: CounterKey) -> Self {
-        Self(key)
+        Self { key, within: None }
+    }
+
+    /// The same counter, but restarting at each element matching the
+    /// selector.
+    pub fn within(self, within: Option<Selector>) -> Self {
+        Self { within, ..self }
     }
 
     /// The counter for the given element.
@@ -308,7 +357,7 @@ impl Counter {
     }
 
     /// Call a method on counter.
-    #[tracing::instrument(skip(vm))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(vm)))]
     pub fn call_method(
         self,
         vm: &mut Vm,
@@ -423,15 +472,23 @@ impl Counter {
             delayed,
             tracer,
         };
-        let mut state = CounterState(match &self.0 {
+        let mut state = CounterState(match &self.key {
             // special case, because pages always start at one.
             CounterKey::Page => smallvec![1],
             _ => smallvec![0],
         });
         let mut page = NonZeroUsize::ONE;
-        let mut stops = eco_vec![(state.clone(), page)];
+        let mut section = 0;
+        let mut stops = eco_vec![(self.prefixed(&state, section), page)];
 
         for elem in introspector.query(&self.selector()) {
+            if self.within.as_ref().map_or(false, |within| within.matches(&elem)) {
+                section += 1;
+                state = CounterState(smallvec![0]);
+                stops.push((self.prefixed(&state, section), page));
+                continue;
+            }
+
             if self.is_page() {
                 let prev = page;
                 page = introspector.page(elem.location().unwrap());
@@ -452,34 +509,52 @@ impl Counter {
                 state.update(&mut vt, update)?;
             }
 
-            stops.push((state.clone(), page));
+            stops.push((self.prefixed(&state, section), page));
         }
 
         Ok(stops)
     }
 
+    /// Prefix the state with the number of the section it is in, if the
+    /// counter restarts in sections.
+    fn prefixed(&self, state: &CounterState, section: usize) -> CounterState {
+        match self.within {
+            Some(_) => CounterState(
+                std::iter::once(section).chain(state.0.iter().copied()).collect(),
+            ),
+            None => state.clone(),
+        }
+    }
+
     /// The selector relevant for this counter's updates.
     fn selector(&self) -> Selector {
         let mut selector =
             Selector::Elem(UpdateElem::func(), Some(dict! { "counter" => self.clone() }));
 
-        if let CounterKey::Selector(key) = &self.0 {
+        if let CounterKey::Selector(key) = &self.key {
             selector = Selector::Or(eco_vec![selector, key.clone()]);
         }
 
+        if let Some(within) = &self.within {
+            selector = Selector::Or(eco_vec![selector, within.clone()]);
+        }
+
         selector
     }
 
     /// Whether this is the page counter.
     fn is_page(&self) -> bool {
-        self.0 == CounterKey::Page
+        self.key == CounterKey::Page
     }
 }
 
 impl Debug for Counter {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str("counter(")?;
-        self.0.fmt(f)?;
+        self.key.fmt(f)?;
+        if let Some(within) = &self.within {
+            write!(f, ", within: {within:?}")?;
+        }
         f.write_char(')')
     }
 }
@@ -504,14 +579,20 @@ cast! {
     CounterKey,
     v: Str => Self::Str(v),
     label: Label => Self::Selector(Selector::Label(label)),
-    v: ElemFunc => {
-        if v == PageElem::func() {
+    v: Func => {
+        if v.element() == Some(PageElem::func()) {
             Self::Page
         } else {
             Self::Selector(LocatableSelector::from_value(v.into_value())?.0)
         }
     },
     selector: LocatableSelector => Self::Selector(selector.0),
+    array: Array => Self::Selector(Selector::Or(
+        array
+            .into_iter()
+            .map(|v| LocatableSelector::from_value(v).map(|selector| selector.0))
+            .collect::<StrResult<_>>()?,
+    )),
 }
 
 impl Debug for CounterKey {
@@ -625,7 +706,10 @@ struct DisplayElem {
 }
 
 impl Show for DisplayElem {
-    #[tracing::instrument(name = "DisplayElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "DisplayElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         Ok(vt.delayed(|vt| {
             let location = self.0.location().unwrap();
@@ -633,7 +717,7 @@ impl Show for DisplayElem {
             let numbering = self
                 .numbering()
                 .or_else(|| {
-                    let CounterKey::Selector(Selector::Elem(func, _)) = counter.0 else {
+                    let CounterKey::Selector(Selector::Elem(func, _)) = counter.key else {
                     return None;
                 };
 
@@ -676,7 +760,10 @@ struct UpdateElem {
 }
 
 impl Show for UpdateElem {
-    #[tracing::instrument(name = "UpdateElem::show", skip(self))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "UpdateElem::show", skip(self))
+    )]
     fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
         Ok(Content::empty())
     }
@@ -43,8 +43,12 @@ impl Construct for DocumentElem {
 
 impl LayoutRoot for DocumentElem {
     /// Layout the document into a sequence of frames, one per page.
-    #[tracing::instrument(name = "DocumentElem::layout_root", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "DocumentElem::layout_root", skip_all)
+    )]
     fn layout_root(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Document> {
+        #[cfg(feature = "tracing")]
         tracing::info!("Document layout");
 
         let mut pages = vec![];
@@ -57,6 +61,11 @@ impl LayoutRoot for DocumentElem {
                 child = elem;
             }
 
+            // Skip the remaining page runs if no more pages are needed.
+            if vt.world.page_limit().is_some_and(|limit| pages.len() >= limit.get()) {
+                break;
+            }
+
             if let Some(page) = child.to::<PageElem>() {
                 let number = NonZeroUsize::ONE.saturating_add(pages.len());
                 let fragment = page.layout(vt, styles, number)?;
@@ -84,3 +93,34 @@ cast! {
     v: EcoString => Self(vec![v]),
     v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::util::hash128;
+
+    use super::*;
+    use crate::tests::TestWorld;
+
+    #[test]
+    fn test_page_limit() {
+        let world =
+            TestWorld::new("#set page(height: 100pt)\n#lorem(300)\n#pagebreak()\nEnd");
+        let full = world.compile();
+        assert!(full.pages.len() > 3);
+
+        // Layout stops in the middle of the first page run and the pages up
+        // to there are the same as in the full layout.
+        let limited = TestWorld { page_limit: NonZeroUsize::new(2), ..world };
+        let document = limited.compile();
+        assert_eq!(document.pages.len(), 2);
+        assert_eq!(hash128(&document.pages[0]), hash128(&full.pages[0]));
+
+        // Later page runs are skipped altogether.
+        let limited = TestWorld {
+            page_limit: NonZeroUsize::new(full.pages.len() - 1),
+            ..limited
+        };
+        let document = limited.compile();
+        assert_eq!(document.pages.len(), full.pages.len() - 1);
+    }
+}
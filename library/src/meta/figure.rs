@@ -1,7 +1,8 @@
 use std::str::FromStr;
 
 use super::{
-    Count, Counter, CounterKey, CounterUpdate, LocalName, Numbering, NumberingPattern,
+    local_name_override, Count, Counter, CounterKey, CounterUpdate, LocalName, Numbering,
+    NumberingPattern,
 };
 use crate::layout::{BlockElem, VElem};
 use crate::meta::{Outlinable, Refable, Supplement};
@@ -182,10 +183,15 @@ impl Synthesize for FigureElem {
                     FigureKind::Elem(func) => {
                         let empty = Content::new(*func);
                         empty.with::<dyn LocalName>().map(|c| {
-                            TextElem::packed(c.local_name(
-                                TextElem::lang_in(styles),
-                                TextElem::region_in(styles),
-                            ))
+                            TextElem::packed(
+                                local_name_override(*func, styles).unwrap_or_else(|| {
+                                    c.local_name(
+                                        TextElem::lang_in(styles),
+                                        TextElem::region_in(styles),
+                                    )
+                                    .into()
+                                }),
+                            )
                         })
                     }
                     FigureKind::Name(_) => None,
@@ -233,7 +239,10 @@ impl Synthesize for FigureElem {
 }
 
 impl Show for FigureElem {
-    #[tracing::instrument(name = "FigureElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FigureElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let mut realized = self.body();
 
@@ -71,7 +71,10 @@ impl Synthesize for FootnoteElem {
 }
 
 impl Show for FootnoteElem {
-    #[tracing::instrument(name = "FootnoteElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FootnoteElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let loc = self.0.location().unwrap();
         let numbering = self.numbering(styles);
@@ -0,0 +1,235 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use super::{pages, HeadingElem, LocalName};
+use crate::layout::ParbreakElem;
+use crate::prelude::*;
+use crate::text::locale::Term;
+use crate::text::{LinebreakElem, StrongElem, TextElem};
+
+/// Defines a term or acronym for the glossary.
+///
+/// The definition itself is invisible. Use the term with
+/// [`gls`]($func/gls) and list all definitions with
+/// [`glossary`]($func/glossary).
+///
+/// ## Example { #example }
+/// ```example
+/// #glossary-entry(
+///   "http", "HTTP",
+///   long: "Hypertext Transfer Protocol",
+/// )
+///
+/// #gls("http") is used on the web.
+/// Browsers speak #gls("http").
+/// ```
+///
+/// Display: Glossary Entry
+/// Category: meta
+#[element(Locatable, Synthesize, Show)]
+pub struct GlossaryEntryElem {
+    /// The key with which the term is used.
+    #[required]
+    pub key: EcoString,
+
+    /// The short form of the term, e.g. an acronym.
+    #[required]
+    pub short: EcoString,
+
+    /// The long form of the term. If given, the first use of the term shows
+    /// the long form followed by the short form in parentheses.
+    pub long: Option<EcoString>,
+
+    /// A description of the term for the glossary.
+    pub description: Option<Content>,
+}
+
+impl Synthesize for GlossaryEntryElem {
+    fn synthesize(&mut self, _: &mut Vt, styles: StyleChain) -> SourceResult<()> {
+        self.push_long(self.long(styles));
+        self.push_description(self.description(styles));
+        Ok(())
+    }
+}
+
+impl Show for GlossaryEntryElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "GlossaryEntryElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Uses a term defined with a [glossary entry]($func/glossary-entry).
+///
+/// The first use of a term with a long form is expanded to
+/// "Hypertext Transfer Protocol (HTTP)" while all later uses show the short
+/// form only.
+///
+/// ## Example { #example }
+/// ```example
+/// #glossary-entry("pdf", "PDF", long: "Portable Document Format")
+///
+/// Export to #gls("pdf"). \
+/// A #gls("pdf") file.
+/// ```
+///
+/// Display: Glossary Term
+/// Category: meta
+#[element(Locatable, Show)]
+pub struct GlsElem {
+    /// The key of the term.
+    #[required]
+    pub key: EcoString,
+}
+
+impl Show for GlsElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "GlsElem::show", skip_all)
+    )]
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        Ok(vt.delayed(|vt| {
+            let key = self.key();
+            let Some(entry) = find_entry(vt, &key) else {
+                bail!(self.span(), "glossary entry `{key}` is not defined");
+            };
+
+            let short = entry.short();
+            let first = uses(vt.introspector)
+                .get(&key)
+                .and_then(|locations| locations.first())
+                .map_or(true, |&first| Some(first) == self.0.location());
+
+            let text = match entry.long(styles) {
+                Some(long) if first => eco_format!("{long} ({short})"),
+                _ => short,
+            };
+
+            Ok(TextElem::packed(text))
+        }))
+    }
+}
+
+/// A glossary of all defined terms.
+///
+/// Lists all terms defined with a [glossary entry]($func/glossary-entry),
+/// sorted by their short form, together with their long form, description
+/// and links to the pages where they are used.
+///
+/// ## Example { #example }
+/// ```example
+/// #glossary-entry("tex", "TeX", description: [A typesetting system.])
+/// #glossary-entry("pdf", "PDF", long: "Portable Document Format")
+///
+/// #gls("tex") produces #gls("pdf").
+///
+/// #glossary()
+/// ```
+///
+/// Display: Glossary
+/// Category: meta
+#[element(Show, Finalize, LocalName)]
+pub struct GlossaryElem {
+    /// The title of the glossary.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($func/text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the glossary will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+}
+
+impl Show for GlossaryElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "GlossaryElem::show", skip_all)
+    )]
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles) {
+            let title = title.unwrap_or_else(|| {
+                TextElem::packed(self.local_name_in(styles)).spanned(self.span())
+            });
+
+            seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
+        }
+
+        // Sort the entries case-insensitively by their short form. Later
+        // definitions of the same key are ignored.
+        let mut keys = HashSet::new();
+        let mut entries = BTreeMap::new();
+        for elem in vt.introspector.query(&GlossaryEntryElem::func().select()) {
+            let entry = elem.to::<GlossaryEntryElem>().unwrap().clone();
+            let short = entry.short();
+            if keys.insert(entry.key()) {
+                entries.insert((short.to_lowercase(), short), entry);
+            }
+        }
+
+        let uses = uses(vt.introspector);
+        for entry in entries.into_values() {
+            seq.push(StrongElem::new(TextElem::packed(entry.short())).pack());
+            if let Some(long) = entry.long(styles) {
+                seq.push(TextElem::packed(eco_format!(" ({long})")));
+            }
+
+            if let Some(description) = entry.description(styles) {
+                seq.push(TextElem::packed(": "));
+                seq.push(description);
+            }
+
+            if let Some(locations) = uses.get(&entry.key()) {
+                seq.push(TextElem::packed(" — "));
+                seq.push(pages(vt, locations)?);
+            }
+
+            seq.push(LinebreakElem::new().pack());
+        }
+
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl Finalize for GlossaryElem {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        realized
+            .styled(HeadingElem::set_outlined(false))
+            .styled(HeadingElem::set_numbering(None))
+    }
+}
+
+impl LocalName for GlossaryElem {
+    fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
+        Term::Glossary.local(lang, region)
+    }
+}
+
+/// Find the first definition of a glossary term.
+fn find_entry(vt: &Vt, key: &str) -> Option<GlossaryEntryElem> {
+    let selector =
+        Selector::Elem(GlossaryEntryElem::func(), Some(dict! { "key" => key }));
+    let elem = vt.introspector.query_first(&selector)?;
+    elem.to::<GlossaryEntryElem>().cloned()
+}
+
+/// The places where each glossary term is used, in document order.
+///
+/// This is computed once for all terms instead of querying the uses of each
+/// term separately, which would be quadratic in the number of uses.
+#[comemo::memoize]
+fn uses(introspector: Tracked<Introspector>) -> Arc<HashMap<EcoString, Vec<Location>>> {
+    let mut uses: HashMap<_, Vec<_>> = HashMap::new();
+    for elem in introspector.query(&GlsElem::func().select()) {
+        let gls = elem.to::<GlsElem>().unwrap();
+        if let Some(location) = elem.location() {
+            uses.entry(gls.key()).or_default().push(location);
+        }
+    }
+    Arc::new(uses)
+}
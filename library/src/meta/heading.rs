@@ -1,10 +1,10 @@
 use typst::font::FontWeight;
-use typst::util::option_eq;
 
 use super::{Counter, CounterUpdate, LocalName, Numbering, Outlinable, Refable};
 use crate::layout::{BlockElem, HElem, VElem};
 use crate::meta::{Count, Supplement};
 use crate::prelude::*;
+use crate::text::locale::Term;
 use crate::text::{SpaceElem, TextElem, TextSize};
 
 /// A section heading.
@@ -117,7 +117,10 @@ impl Synthesize for HeadingElem {
 }
 
 impl Show for HeadingElem {
-    #[tracing::instrument(name = "HeadingElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "HeadingElem::show", skip_all)
+    )]
     fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let mut realized = self.body();
         if let Some(numbering) = self.numbering(styles) {
@@ -209,30 +212,6 @@ impl Outlinable for HeadingElem {
 
 impl LocalName for HeadingElem {
     fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
-        match lang {
-            Lang::ALBANIAN => "Kapitull",
-            Lang::ARABIC => "الفصل",
-            Lang::BOKMÅL => "Kapittel",
-            Lang::CHINESE if option_eq(region, "TW") => "小節",
-            Lang::CHINESE => "小节",
-            Lang::CZECH => "Kapitola",
-            Lang::DANISH => "Afsnit",
-            Lang::DUTCH => "Hoofdstuk",
-            Lang::FILIPINO => "Seksyon",
-            Lang::FRENCH => "Chapitre",
-            Lang::GERMAN => "Abschnitt",
-            Lang::ITALIAN => "Sezione",
-            Lang::NYNORSK => "Kapittel",
-            Lang::POLISH => "Sekcja",
-            Lang::PORTUGUESE => "Seção",
-            Lang::RUSSIAN => "Раздел",
-            Lang::SLOVENIAN => "Poglavje",
-            Lang::SPANISH => "Sección",
-            Lang::SWEDISH => "Kapitel",
-            Lang::TURKISH => "Bölüm",
-            Lang::UKRAINIAN => "Розділ",
-            Lang::VIETNAMESE => "Phần", // TODO: This may be wrong.
-            Lang::ENGLISH | _ => "Section",
-        }
+        Term::Section.local(lang, region)
     }
 }
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::{Counter, CounterKey, HeadingElem, LocalName, Numbering, NumberingPattern};
+use crate::layout::{HElem, ParbreakElem};
+use crate::prelude::*;
+use crate::text::locale::Term;
+use crate::text::{EmphElem, LinebreakElem, StrongElem, TextElem};
+
+/// Marks a term for the index.
+///
+/// The marker itself is invisible. The [`index`]($func/index) function lists
+/// all marked terms alphabetically together with the pages they appear on.
+///
+/// ## Example { #example }
+/// ```example
+/// Typst#index-entry("Typst") is a
+/// markup-based typesetting system.
+/// #index-entry("layout", sub: "paragraphs")
+/// #index-entry("markup", see: "syntax")
+///
+/// #index()
+/// ```
+///
+/// Display: Index Entry
+/// Category: meta
+#[element(Locatable, Synthesize, Show)]
+pub struct IndexEntryElem {
+    /// The term to list in the index.
+    #[required]
+    pub term: EcoString,
+
+    /// A sub-entry to list below the term.
+    pub sub: Option<EcoString>,
+
+    /// Another term the index should refer to. The marker's page is not
+    /// listed for the term if this is set.
+    pub see: Option<EcoString>,
+}
+
+impl Synthesize for IndexEntryElem {
+    fn synthesize(&mut self, _: &mut Vt, styles: StyleChain) -> SourceResult<()> {
+        self.push_sub(self.sub(styles));
+        self.push_see(self.see(styles));
+        Ok(())
+    }
+}
+
+impl Show for IndexEntryElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "IndexEntryElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// A back-of-book index.
+///
+/// Lists all terms marked with [`index-entry`]($func/index-entry), grouped by
+/// their first letter. The pages a term appears on are merged into ranges
+/// (like 12–15) and link to the marked places. Sub-entries are listed
+/// indented below their term and cross references are shown after the pages.
+///
+/// ## Example { #example }
+/// ```example
+/// #index-entry("Typst")
+/// #index-entry("Typst", sub: "syntax")
+/// #index-entry("LaTeX", see: "Typst")
+///
+/// #index()
+/// ```
+///
+/// Display: Index
+/// Category: meta
+#[element(Show, Finalize, LocalName)]
+pub struct IndexElem {
+    /// The title of the index.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($func/text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the index will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+}
+
+impl Show for IndexElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "IndexElem::show", skip_all)
+    )]
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles) {
+            let title = title.unwrap_or_else(|| {
+                TextElem::packed(self.local_name_in(styles)).spanned(self.span())
+            });
+
+            seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
+        }
+
+        // Collect the entries, sorted case-insensitively.
+        let mut terms: BTreeMap<(EcoString, EcoString), Term> = BTreeMap::new();
+        for elem in vt.introspector.query(&IndexEntryElem::func().select()) {
+            let entry = elem.to::<IndexEntryElem>().unwrap();
+            let location = elem.location().unwrap();
+            let term = entry.term();
+            let key = (term.to_lowercase().into(), term);
+            let term = terms.entry(key).or_default();
+            if let Some(see) = entry.see(styles) {
+                if !term.see.contains(&see) {
+                    term.see.push(see);
+                }
+            } else if let Some(sub) = entry.sub(styles) {
+                term.subs
+                    .entry((sub.to_lowercase().into(), sub))
+                    .or_default()
+                    .push(location);
+            } else {
+                term.locations.push(location);
+            }
+        }
+
+        let see = TextElem::packed(
+            Term::See.local(TextElem::lang_in(styles), TextElem::region_in(styles)),
+        );
+
+        let mut letter = None;
+        for ((key, name), term) in terms {
+            // Start a new group for each letter.
+            let first =
+                key.chars().next().map(|c| c.to_uppercase().collect::<EcoString>());
+            if first != letter {
+                if letter.is_some() {
+                    seq.push(ParbreakElem::new().pack());
+                }
+                let heading = first.clone().unwrap_or_default();
+                seq.push(StrongElem::new(TextElem::packed(heading)).pack());
+                seq.push(LinebreakElem::new().pack());
+                letter = first;
+            }
+
+            seq.push(TextElem::packed(name));
+            if !term.locations.is_empty() {
+                seq.push(TextElem::packed(", "));
+                seq.push(pages(vt, &term.locations)?);
+            }
+            if !term.see.is_empty() {
+                seq.push(TextElem::packed(if term.locations.is_empty() {
+                    " "
+                } else {
+                    "; "
+                }));
+                seq.push(EmphElem::new(see.clone()).pack());
+                seq.push(TextElem::packed(eco_format!(" {}", term.see.join(", "))));
+            }
+            seq.push(LinebreakElem::new().pack());
+
+            for ((_, sub), locations) in term.subs {
+                seq.push(HElem::new(Em::new(1.0).into()).pack());
+                seq.push(TextElem::packed(sub));
+                seq.push(TextElem::packed(", "));
+                seq.push(pages(vt, &locations)?);
+                seq.push(LinebreakElem::new().pack());
+            }
+        }
+
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl Finalize for IndexElem {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        realized
+            .styled(HeadingElem::set_outlined(false))
+            .styled(HeadingElem::set_numbering(None))
+    }
+}
+
+impl LocalName for IndexElem {
+    fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
+        Term::Index.local(lang, region)
+    }
+}
+
+/// A term in the index.
+#[derive(Default)]
+struct Term {
+    /// Where the term itself was marked.
+    locations: Vec<Location>,
+    /// The sub-entries with the places they were marked.
+    subs: BTreeMap<(EcoString, EcoString), Vec<Location>>,
+    /// Other terms the term refers to.
+    see: Vec<EcoString>,
+}
+
+/// Display the pages of some locations, merging consecutive pages into
+/// ranges.
+pub(super) fn pages(vt: &mut Vt, locations: &[Location]) -> SourceResult<Content> {
+    let mut pages: Vec<_> = locations
+        .iter()
+        .map(|&loc| (vt.introspector.page(loc), loc))
+        .collect();
+    pages.sort_by_key(|&(page, _)| page);
+    pages.dedup_by_key(|&mut (page, _)| page);
+
+    let mut seq = vec![];
+    let mut i = 0;
+    while i < pages.len() {
+        let mut j = i;
+        while j + 1 < pages.len() && pages[j + 1].0.get() == pages[j].0.get() + 1 {
+            j += 1;
+        }
+
+        if !seq.is_empty() {
+            seq.push(TextElem::packed(", "));
+        }
+
+        let mut range = page_number(vt, pages[i].1)?;
+        if j > i {
+            range += TextElem::packed("–") + page_number(vt, pages[j].1)?;
+        }
+
+        seq.push(range.linked(Destination::Location(pages[i].1)));
+        i = j + 1;
+    }
+
+    Ok(Content::sequence(seq))
+}
+
+/// Display the page number of a location with its page's numbering.
+fn page_number(vt: &mut Vt, location: Location) -> SourceResult<Content> {
+    let numbering = vt
+        .introspector
+        .page_numbering(location)
+        .cast::<Option<Numbering>>()
+        .unwrap()
+        .unwrap_or_else(|| Numbering::Pattern(NumberingPattern::from_str("1").unwrap()));
+
+    Counter::new(CounterKey::Page)
+        .at(vt, location)?
+        .display(vt, &numbering)
+}
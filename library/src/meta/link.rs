@@ -49,12 +49,23 @@ pub struct LinkElem {
     ///     counted from one, and the coordinates are relative to the page's top
     ///     left corner.
     ///
+    /// - To link into another PDF file, `dest` should be a dictionary with a
+    ///   `file` key holding the file's path relative to the exported PDF. An
+    ///   additional `page` key selects the page to open, counted from one. A
+    ///   `name` key instead selects a named destination. Typst gives each
+    ///   labelled element in its PDFs a named destination with the label's
+    ///   name, so `{(file: "thesis.pdf", name: "results")}` links to the
+    ///   element labelled `<results>` in `thesis.pdf`.
+    ///
     /// ```example
     /// = Introduction <intro>
     /// #link("mailto:hello@typst.app") \
     /// #link(<intro>)[Go to intro] \
     /// #link((page: 1, x: 0pt, y: 0pt))[
     ///   Go to top
+    /// ] \
+    /// #link((file: "notes.pdf", page: 5))[
+    ///   See the notes
     /// ]
     /// ```
     #[required]
@@ -88,7 +99,10 @@ impl LinkElem {
 }
 
 impl Show for LinkElem {
-    #[tracing::instrument(name = "LinkElem::show", skip(self, vt))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LinkElem::show", skip(self, vt))
+    )]
     fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
         let body = self.body();
         let linked = match self.dest() {
@@ -1,29 +1,39 @@
 //! Interaction between document parts.
 
+mod artifact;
 mod bibliography;
+mod compat;
 mod context;
 mod counter;
 mod document;
 mod figure;
 mod footnote;
+mod glossary;
 mod heading;
+mod index;
 mod link;
 mod numbering;
 mod outline;
+mod part;
 mod query;
 mod reference;
 mod state;
 
+pub use self::artifact::*;
 pub use self::bibliography::*;
+pub use self::compat::*;
 pub use self::context::*;
 pub use self::counter::*;
 pub use self::document::*;
 pub use self::figure::*;
 pub use self::footnote::*;
+pub use self::glossary::*;
 pub use self::heading::*;
+pub use self::index::*;
 pub use self::link::*;
 pub use self::numbering::*;
 pub use self::outline::*;
+pub use self::part::*;
 pub use self::query::*;
 pub use self::reference::*;
 pub use self::state::*;
@@ -34,14 +44,22 @@ use crate::text::TextElem;
 /// Hook up all meta definitions.
 pub(super) fn define(global: &mut Scope) {
     global.define("document", DocumentElem::func());
+    global.define("std", StdElem::func());
     global.define("ref", RefElem::func());
     global.define("link", LinkElem::func());
     global.define("outline", OutlineElem::func());
     global.define("heading", HeadingElem::func());
+    global.define("part", PartElem::func());
     global.define("figure", FigureElem::func());
+    global.define("artifact", ArtifactElem::func());
     global.define("footnote", FootnoteElem::func());
     global.define("cite", CiteElem::func());
     global.define("bibliography", BibliographyElem::func());
+    global.define("index", IndexElem::func());
+    global.define("glossary", GlossaryElem::func());
+    global.define("glossary-entry", GlossaryEntryElem::func());
+    global.define("gls", GlsElem::func());
+    global.define("index-entry", IndexEntryElem::func());
     global.define("locate", locate_func());
     global.define("style", style_func());
     global.define("layout", layout_func());
@@ -58,7 +76,42 @@ pub trait LocalName {
     fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str;
 
     /// Resolve the local name with a style chain.
-    fn local_name_in(&self, styles: StyleChain) -> &'static str {
-        self.local_name(TextElem::lang_in(styles), TextElem::region_in(styles))
+    ///
+    /// Names overridden through the text's `names` property take precedence
+    /// over the built-in translations.
+    fn local_name_in(&self, styles: StyleChain) -> EcoString
+    where
+        Self: Element,
+    {
+        local_name_override(Self::func(), styles).unwrap_or_else(|| {
+            self.local_name(TextElem::lang_in(styles), TextElem::region_in(styles))
+                .into()
+        })
+    }
+}
+
+/// Look up a document-provided name for an element in the style chain.
+///
+/// The override is either a plain string or a dictionary from language codes
+/// (optionally with a region, like `"de-CH"`) to strings.
+pub fn local_name_override(func: ElemFunc, styles: StyleChain) -> Option<EcoString> {
+    let names = TextElem::names_in(styles);
+    let value = match names.at(func.name(), None).ok()? {
+        Value::Dict(dict) => {
+            let lang = TextElem::lang_in(styles);
+            let region = TextElem::region_in(styles);
+            region
+                .and_then(|region| {
+                    let tag = eco_format!("{}-{}", lang.as_str(), region.as_str());
+                    dict.at(&tag, None).ok()
+                })
+                .or_else(|| dict.at(lang.as_str(), None).ok())?
+        }
+        value => value,
+    };
+
+    match value {
+        Value::Str(name) => Some(name.as_str().into()),
+        _ => None,
     }
 }
@@ -1,13 +1,16 @@
 use std::str::FromStr;
 
-use typst::util::option_eq;
-
 use super::{
-    Counter, CounterKey, HeadingElem, LocalName, Numbering, NumberingPattern, Refable,
+    local_name_override, Counter, CounterKey, FigureElem, HeadingElem, LocalName,
+    Numbering, NumberingPattern, Refable,
+};
+use crate::layout::{
+    BoxElem, HElem, HideElem, ParbreakElem, RepeatElem, Spacing, TableElem,
 };
-use crate::layout::{BoxElem, HElem, HideElem, ParbreakElem, RepeatElem, Spacing};
 use crate::prelude::*;
-use crate::text::{LinebreakElem, SpaceElem, TextElem};
+use crate::text::locale::Term;
+use crate::text::{LinebreakElem, RawElem, SpaceElem, TextElem};
+use crate::visualize::ImageElem;
 
 /// A table of contents, figures, or other elements.
 ///
@@ -66,6 +69,9 @@ pub struct OutlineElem {
     ///
     /// - When set to `{auto}`, an appropriate title for the
     ///   [text language]($func/text.lang) will be used. This is the default.
+    ///   Outlines of figures containing images, tables or raw text are titled
+    ///   accordingly, e.g. "List of Tables", unless the outline's name is
+    ///   overridden through the [text's names]($func/text.names).
     /// - When set to `{none}`, the outline will not have a title.
     /// - A custom title can be set by passing content.
     ///
@@ -82,10 +88,7 @@ pub struct OutlineElem {
     /// can write `{figure.where(kind: table)}`.
     ///
     /// ```example
-    /// #outline(
-    ///   title: [List of Tables],
-    ///   target: figure.where(kind: table),
-    /// )
+    /// #outline(target: figure.where(kind: table))
     ///
     /// #figure(
     ///   table(
@@ -185,19 +188,20 @@ pub struct OutlineElem {
 }
 
 impl Show for OutlineElem {
-    #[tracing::instrument(name = "OutlineElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "OutlineElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let mut seq = vec![ParbreakElem::new().pack()];
         // Build the outline title.
         if let Some(title) = self.title(styles) {
-            let title =
-                title.unwrap_or_else(|| {
-                    TextElem::packed(self.local_name(
-                        TextElem::lang_in(styles),
-                        TextElem::region_in(styles),
-                    ))
-                    .spanned(self.span())
-                });
+            let title = title.unwrap_or_else(|| {
+                let name = local_name_override(Self::func(), styles)
+                    .or_else(|| self.figure_list_name(styles).map(Into::into))
+                    .unwrap_or_else(|| self.local_name_in(styles));
+                TextElem::packed(name).spanned(self.span())
+            });
 
             seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
         }
@@ -248,6 +252,36 @@ impl Show for OutlineElem {
     }
 }
 
+impl OutlineElem {
+    /// The automatic title of an outline that lists figures of a single kind,
+    /// like "List of Tables".
+    ///
+    /// Returns `None` for other outlines and for languages without a
+    /// translation, which then use the generic outline title.
+    fn figure_list_name(&self, styles: StyleChain) -> Option<&'static str> {
+        let Selector::Elem(func, Some(fields)) = self.target(styles).0 else {
+            return None;
+        };
+
+        if func != FigureElem::func() {
+            return None;
+        }
+
+        let kind = fields.at("kind", None).ok()?.clone().cast::<ElemFunc>().ok()?;
+        let term = if kind == ImageElem::func() {
+            Term::ListOfFigures
+        } else if kind == TableElem::func() {
+            Term::ListOfTables
+        } else if kind == RawElem::func() {
+            Term::ListOfListings
+        } else {
+            return None;
+        };
+
+        term.translate(TextElem::lang_in(styles), TextElem::region_in(styles))
+    }
+}
+
 impl Finalize for OutlineElem {
     fn finalize(&self, realized: Content, _: StyleChain) -> Content {
         realized
@@ -258,31 +292,7 @@ impl Finalize for OutlineElem {
 
 impl LocalName for OutlineElem {
     fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
-        match lang {
-            Lang::ALBANIAN => "Përmbajtja",
-            Lang::ARABIC => "المحتويات",
-            Lang::BOKMÅL => "Innhold",
-            Lang::CHINESE if option_eq(region, "TW") => "目錄",
-            Lang::CHINESE => "目录",
-            Lang::CZECH => "Obsah",
-            Lang::DANISH => "Indhold",
-            Lang::DUTCH => "Inhoudsopgave",
-            Lang::FILIPINO => "Talaan ng mga Nilalaman",
-            Lang::FRENCH => "Table des matières",
-            Lang::GERMAN => "Inhaltsverzeichnis",
-            Lang::ITALIAN => "Indice",
-            Lang::NYNORSK => "Innhald",
-            Lang::POLISH => "Spis treści",
-            Lang::PORTUGUESE => "Sumário",
-            Lang::RUSSIAN => "Содержание",
-            Lang::SLOVENIAN => "Kazalo",
-            Lang::SPANISH => "Índice",
-            Lang::SWEDISH => "Innehåll",
-            Lang::TURKISH => "İçindekiler",
-            Lang::UKRAINIAN => "Зміст",
-            Lang::VIETNAMESE => "Mục lục",
-            Lang::ENGLISH | _ => "Contents",
-        }
+        Term::Contents.local(lang, region)
     }
 }
 
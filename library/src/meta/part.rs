@@ -0,0 +1,153 @@
+use std::str::FromStr;
+
+use smallvec::smallvec;
+
+use super::{
+    Counter, CounterKey, CounterState, CounterUpdate, HeadingElem, Numbering,
+    NumberingPattern,
+};
+use crate::layout::{PageElem, PagebreakElem};
+use crate::prelude::*;
+
+/// A part of a document, such as the front matter or the appendix.
+///
+/// A part starts on a new page and switches the page and heading numbering
+/// for its body:
+/// - The `{"front"}` matter has roman page numbers starting at one and
+///   unnumbered headings.
+/// - The `{"main"}` matter has arabic page numbers starting at one again.
+/// - The `{"appendix"}` numbers its headings with letters, starting at `A`.
+///
+/// Parts are typically applied to the rest of the document with an
+/// everything show rule. If a part has a title, it also appears as a
+/// top-level entry in the PDF bookmarks with the part's headings below it.
+///
+/// ## Example { #example }
+/// ```example
+/// >>> #set page(height: 80pt)
+/// #set heading(numbering: "1.")
+/// #show: part.with("front")
+/// = Preface
+///
+/// #show: part.with("main")
+/// = Introduction
+///
+/// #show: part.with("appendix", title: [Appendices])
+/// = Proofs
+/// ```
+///
+/// Display: Part
+/// Category: meta
+#[element(Locatable, Synthesize, Show)]
+pub struct PartElem {
+    /// Which part of the document this is.
+    #[required]
+    pub kind: PartKind,
+
+    /// The title of the part in the PDF bookmarks.
+    ///
+    /// The title is not displayed in the document. Parts without a title have
+    /// no bookmark.
+    pub title: Option<Content>,
+
+    /// How to number the pages of the part.
+    ///
+    /// When `{auto}`, the front matter uses roman and the main matter arabic
+    /// numbers, while the appendix continues the numbering of the pages
+    /// before it. The page counter restarts at one whenever the part sets
+    /// a numbering.
+    pub page_numbering: Smart<Option<Numbering>>,
+
+    /// How to number the headings in the part.
+    ///
+    /// When `{auto}`, the front matter's headings are unnumbered and the
+    /// appendix is numbered with `{"A.1"}`, which restarts the heading
+    /// counter. The main matter keeps the heading numbering that is set
+    /// outside of it.
+    pub heading_numbering: Smart<Option<Numbering>>,
+
+    /// The content of the part.
+    #[required]
+    pub body: Content,
+}
+
+impl Synthesize for PartElem {
+    fn synthesize(&mut self, _: &mut Vt, styles: StyleChain) -> SourceResult<()> {
+        self.push_title(self.title(styles));
+        Ok(())
+    }
+}
+
+impl Show for PartElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PartElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let kind = self.kind();
+        let mut map = Styles::new();
+        let mut seq = vec![PagebreakElem::new().with_weak(true).pack()];
+
+        let page = self
+            .page_numbering(styles)
+            .as_custom()
+            .or_else(|| kind.page_numbering());
+        if let Some(numbering) = page {
+            map.set(PageElem::set_numbering(numbering));
+            let update = CounterUpdate::Set(CounterState(smallvec![1]));
+            seq.push(Counter::new(CounterKey::Page).update(update));
+        }
+
+        let heading = self
+            .heading_numbering(styles)
+            .as_custom()
+            .or_else(|| kind.heading_numbering());
+        if let Some(numbering) = heading {
+            map.set(HeadingElem::set_numbering(numbering));
+        }
+
+        if kind == PartKind::Appendix {
+            let update = CounterUpdate::Set(CounterState(smallvec![0]));
+            seq.push(Counter::of(HeadingElem::func()).update(update));
+        }
+
+        seq.push(self.body());
+        Ok(Content::sequence(seq).styled_with_map(map))
+    }
+}
+
+/// Which part of the document a [`PartElem`] is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PartKind {
+    /// The preface, table of contents and similar material.
+    Front,
+    /// The main content.
+    Main,
+    /// Supplementary material after the main content.
+    Appendix,
+}
+
+impl PartKind {
+    /// The default page numbering, if the part changes it.
+    fn page_numbering(self) -> Option<Option<Numbering>> {
+        match self {
+            Self::Front => Some(Some(pattern("i"))),
+            Self::Main => Some(Some(pattern("1"))),
+            Self::Appendix => None,
+        }
+    }
+
+    /// The default heading numbering, if the part changes it.
+    fn heading_numbering(self) -> Option<Option<Numbering>> {
+        match self {
+            Self::Front => Some(None),
+            Self::Main => None,
+            Self::Appendix => Some(Some(pattern("A.1"))),
+        }
+    }
+}
+
+/// Create a numbering from a valid pattern.
+fn pattern(pattern: &str) -> Numbering {
+    NumberingPattern::from_str(pattern).unwrap().into()
+}
@@ -144,7 +144,10 @@ impl Synthesize for RefElem {
 }
 
 impl Show for RefElem {
-    #[tracing::instrument(name = "RefElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "RefElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         Ok(vt.delayed(|vt| {
             let target = self.target();
@@ -422,6 +422,8 @@ pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
     join: ['⨝', r: '⟖', l: '⟕', l.r: '⟗'],
     degree: ['°', c: '℃', f: '℉'],
     smash: '⨳',
+    original: '⊶',
+    image: '⊷',
 
     // Currency.
     bitcoin: '₿',
@@ -439,8 +441,18 @@ pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
     // Miscellaneous.
     ballot: ['☐', x: '☒'],
     checkmark: ['✓', light: '🗸'],
+    die: [six: '⚅', five: '⚄', four: '⚃', three: '⚂', two: '⚁', one: '⚀'],
+    errorbar: [
+        square.stroked: '⧮',
+        square.filled: '⧯',
+        diamond.stroked: '⧰',
+        diamond.filled: '⧱',
+        circle.stroked: '⧲',
+        circle.filled: '⧳',
+    ],
     floral: ['❦', l: '☙', r: '❧'],
     notes: [up: '🎜', down: '🎝'],
+    numero: '№',
     refmark: '※',
     servicemark: '℠',
     maltese: '✠',
@@ -0,0 +1,226 @@
+//! Compilation of whole documents for unit tests.
+
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use comemo::Prehashed;
+use once_cell::sync::Lazy;
+use typst::diag::{FileError, FileResult};
+use typst::doc::{Document, Frame, FrameItem};
+use typst::eval::{Datetime, Library};
+use typst::export::{PdfExporter, PdfOptions, Target};
+use typst::font::{Font, FontBook};
+use typst::geom::Point;
+use typst::ide::LineDiff;
+use typst::syntax::{Source, SourceId};
+use typst::util::Buffer;
+use typst::World;
+
+/// The library and the fonts in the assets, which are shared by all worlds.
+static ENVIRONMENT: Lazy<(Prehashed<Library>, Prehashed<FontBook>, Vec<Font>)> =
+    Lazy::new(|| {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/fonts");
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+
+        let mut fonts = vec![];
+        for path in paths {
+            fonts.extend(Font::iter(fs::read(path).unwrap().into()));
+        }
+
+        let book = FontBook::from_fonts(&fonts);
+        (Prehashed::new(crate::build()), Prehashed::new(book), fonts)
+    });
+
+/// A world with a single source file, the standard library and the fonts in
+/// the assets.
+///
+/// Unlike a detached source, the source has an id, so that spans in the
+/// document can be mapped back to it.
+pub struct TestWorld {
+    source: Source,
+    /// Whether the compilation should be aborted.
+    pub cancelled: bool,
+    /// How many pages of the document are needed.
+    pub page_limit: Option<NonZeroUsize>,
+    /// The format the document is going to be exported into.
+    pub target: Target,
+}
+
+impl TestWorld {
+    /// Create a world whose main source has the given text.
+    pub fn new(text: &str) -> Self {
+        Self {
+            source: Source::new(
+                SourceId::from_u16(0),
+                Path::new("main.typ"),
+                text.into(),
+            ),
+            cancelled: false,
+            page_limit: None,
+            target: Target::Pdf,
+        }
+    }
+
+    /// Compile the main source, failing the test if there are errors.
+    pub fn compile(&self) -> Document {
+        typst::compile(self).unwrap_or_else(|errors| {
+            let messages: Vec<_> = errors.iter().map(|error| &error.message).collect();
+            panic!("failed to compile: {messages:?}")
+        })
+    }
+}
+
+impl World for TestWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &ENVIRONMENT.0
+    }
+
+    fn main(&self) -> &Source {
+        &self.source
+    }
+
+    fn resolve(&self, path: &Path) -> FileResult<SourceId> {
+        Err(FileError::NotFound(path.into()))
+    }
+
+    fn source(&self, _: SourceId) -> &Source {
+        &self.source
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &ENVIRONMENT.1
+    }
+
+    fn font(&self, id: usize) -> Option<Font> {
+        ENVIRONMENT.2.get(id).cloned()
+    }
+
+    fn file(&self, path: &Path) -> FileResult<Buffer> {
+        Err(FileError::NotFound(path.into()))
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    fn page_limit(&self) -> Option<NonZeroUsize> {
+        self.page_limit
+    }
+
+    fn target(&self) -> Target {
+        self.target
+    }
+}
+
+/// Compile a document, failing the test if there are errors.
+pub fn compile(text: &str) -> Document {
+    TestWorld::new(text).compile()
+}
+
+/// All items in a frame and in its groups, with their positions relative to
+/// the frame.
+///
+/// A group comes right before its items. The groups' transforms are not
+/// applied to the positions.
+pub fn items(frame: &Frame) -> Vec<(Point, &FrameItem)> {
+    let mut items = vec![];
+    collect(frame, Point::zero(), &mut items);
+    items
+}
+
+fn collect<'a>(frame: &'a Frame, offset: Point, items: &mut Vec<(Point, &'a FrameItem)>) {
+    for (pos, item) in frame.items() {
+        items.push((offset + *pos, item));
+        if let FrameItem::Group(group) = item {
+            collect(&group.frame, offset + *pos, items);
+        }
+    }
+}
+
+#[test]
+fn test_words() {
+    let text = "#set page(width: 60pt)\n\
+        #set text(lang: \"en\", hyphenate: true)\n\
+        Extraordinarily #text(lang: \"de\", region: \"at\")[Grüß Gott]!";
+    let world = TestWorld::new(text);
+    let document = world.compile();
+
+    // The long word is hyphenated across lines, but still one word that
+    // points to where it was written.
+    let runs = items(&document.pages[0])
+        .into_iter()
+        .filter(|(_, item)| matches!(item, FrameItem::Text(_)))
+        .count();
+    assert!(runs > 3);
+    let words = typst::ide::words(&world, &document);
+    let texts: Vec<_> = words.iter().map(|word| word.text.as_str()).collect();
+    assert_eq!(texts, ["Extraordinarily", "Grüß", "Gott"]);
+    let (_, range) = words[0].source.clone().unwrap();
+    assert_eq!(&text[range], "Extraordinarily");
+    assert_eq!(words[1].lang.as_str(), "de");
+    assert_eq!(words[1].region.unwrap().as_str(), "AT");
+}
+
+#[test]
+fn test_cancellation() {
+    let text = "Hello";
+    let cancelled = TestWorld { cancelled: true, ..TestWorld::new(text) };
+    let errors = typst::compile(&cancelled).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message.as_str(), "compilation was cancelled");
+
+    // The same document still compiles and exports without cancellation,
+    // even though compilation is memoized.
+    let world = TestWorld::new(text);
+    let document = world.compile();
+    let options = PdfOptions::default();
+    let mut exporter = PdfExporter::new(&options);
+    let page = &document.pages[0];
+    let error = exporter.try_push(&cancelled, page).unwrap_err();
+    assert_eq!(error.as_str(), "export was cancelled");
+    assert!(exporter.try_push(&world, page).is_ok());
+    assert!(exporter.finish(&document).unwrap().starts_with(b"%PDF"));
+}
+
+#[test]
+fn test_visual_diff() {
+    let old = compile("First\n\nSecond\n#pagebreak()\nThird");
+    let new = compile("First\n\nChanged\n#pagebreak()\nThird");
+    let diff = typst::ide::diff_lines(&old, &new);
+    let removed: Vec<_> = diff
+        .iter()
+        .filter_map(|change| match change {
+            LineDiff::Removed(line) => Some(line.text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(removed, ["Second"]);
+    let pages = typst::ide::changed_pages(&diff);
+    assert_eq!(pages, [NonZeroUsize::new(1).unwrap()]);
+
+    // The old and new pages are shown side by side. The removed and the added
+    // line each get a highlight and a bar in the margin, and the changed page
+    // a bar above it.
+    let visual = typst::ide::render_diff(&old, &new, &diff);
+    assert_eq!(visual.pages.len(), 2);
+    let page = old.pages[0].size();
+    assert!(visual.pages[0].width() > page.x * 2.0);
+    assert!(visual.pages[0].height() > page.y);
+    let shapes = |frame: &Frame| {
+        items(frame)
+            .into_iter()
+            .filter(|(_, item)| matches!(item, FrameItem::Shape(..)))
+            .count()
+    };
+    assert_eq!(shapes(&visual.pages[0]), 5);
+    assert_eq!(shapes(&visual.pages[1]), 0);
+}
@@ -0,0 +1,190 @@
+use std::fmt::Write;
+
+use crate::prelude::*;
+
+/// Create a color gradient for text.
+///
+/// A gradient blends evenly between the given colors. When used as a
+/// [text fill]($func/text.fill), it spans each run of text from start to end,
+/// with each glyph taking the color at its center.
+///
+/// ## Example { #example }
+/// ```example
+/// #set text(fill: gradient(red, orange, green, blue, purple))
+/// = A rainbow heading
+/// ```
+///
+/// Display: Gradient
+/// Category: text
+#[func]
+pub fn gradient(
+    /// The colors to blend between. At least two are required.
+    #[variadic]
+    stops: Vec<Color>,
+) -> StrResult<Gradient> {
+    if stops.len() < 2 {
+        bail!("gradient needs at least two colors");
+    }
+    Ok(Gradient { stops })
+}
+
+/// A linear blend between colors.
+#[derive(Clone, PartialEq, Hash)]
+pub struct Gradient {
+    /// The evenly spaced colors to blend between.
+    stops: Vec<Color>,
+}
+
+impl Gradient {
+    /// The color at the relative position `t`, which is clamped to the
+    /// range between zero and one.
+    pub fn sample(&self, t: f64) -> Color {
+        let scaled = t.clamp(0.0, 1.0) * (self.stops.len() - 1) as f64;
+        let i = (scaled.floor() as usize).min(self.stops.len() - 2);
+        let (a, b) = (self.stops[i].to_rgba(), self.stops[i + 1].to_rgba());
+        let mix = |x: u8, y: u8| {
+            let f = scaled - i as f64;
+            (x as f64 + (y as f64 - x as f64) * f).round() as u8
+        };
+        RgbaColor::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b), mix(a.a, b.a)).into()
+    }
+}
+
+impl Debug for Gradient {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("gradient(")?;
+        for (i, stop) in self.stops.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            stop.fmt(f)?;
+        }
+        f.write_char(')')
+    }
+}
+
+cast! {
+    type Gradient: "gradient",
+}
+
+/// How to fill the glyphs of a piece of text.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum TextFill {
+    /// All glyphs have the same paint.
+    Paint(Paint),
+    /// The glyphs take their color from a gradient spanning each text run.
+    Gradient(Gradient),
+    /// A function that maps the relative positions of the glyphs in a text
+    /// run to their paints.
+    Func(Func),
+}
+
+impl TextFill {
+    /// A single paint standing in for the fill, for places where glyphs are
+    /// not painted individually. This is the first color of a gradient and
+    /// black for a function.
+    pub fn as_paint(&self) -> Paint {
+        match self {
+            Self::Paint(paint) => paint.clone(),
+            Self::Gradient(gradient) => gradient.stops[0].into(),
+            Self::Func(_) => Color::BLACK.into(),
+        }
+    }
+
+    /// Whether the glyphs may differ in their paints.
+    pub fn varies(&self) -> bool {
+        !matches!(self, Self::Paint(_))
+    }
+
+    /// The paints of the glyphs at the given relative positions in a text
+    /// run.
+    ///
+    /// A function is called once for the whole run.
+    pub fn paints(&self, vt: &mut Vt, positions: &[Ratio]) -> SourceResult<Vec<Paint>> {
+        Ok(match self {
+            Self::Paint(paint) => vec![paint.clone(); positions.len()],
+            Self::Gradient(gradient) => positions
+                .iter()
+                .map(|position| gradient.sample(position.get()).into())
+                .collect(),
+            Self::Func(func) => {
+                let array: Array = positions.iter().map(|&p| p.into_value()).collect();
+                let paints = func
+                    .call_vt(vt, [Value::Array(array)])?
+                    .cast::<RunPaints>()
+                    .at(func.span())?;
+                match paints {
+                    RunPaints::One(paint) => vec![paint; positions.len()],
+                    RunPaints::Each(paints) if paints.len() == positions.len() => paints,
+                    RunPaints::Each(paints) => bail!(
+                        func.span(),
+                        "expected {} paints, found {}",
+                        positions.len(),
+                        paints.len()
+                    ),
+                }
+            }
+        })
+    }
+}
+
+/// What a fill function returns for a text run.
+enum RunPaints {
+    /// One paint for all glyphs.
+    One(Paint),
+    /// A paint per glyph.
+    Each(Vec<Paint>),
+}
+
+cast! {
+    RunPaints,
+    paint: Paint => Self::One(paint),
+    paints: Vec<Paint> => Self::Each(paints),
+}
+
+impl From<Paint> for TextFill {
+    fn from(paint: Paint) -> Self {
+        Self::Paint(paint)
+    }
+}
+
+impl From<Color> for TextFill {
+    fn from(color: Color) -> Self {
+        Self::Paint(color.into())
+    }
+}
+
+cast! {
+    TextFill,
+    self => match self {
+        Self::Paint(paint) => paint.into_value(),
+        Self::Gradient(gradient) => gradient.into_value(),
+        Self::Func(func) => func.into_value(),
+    },
+    paint: Paint => Self::Paint(paint),
+    gradient: Gradient => Self::Gradient(gradient),
+    func: Func => Self::Func(func),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_sample() {
+        let gradient = Gradient {
+            stops: vec![
+                RgbaColor::new(0, 0, 0, 255).into(),
+                RgbaColor::new(200, 100, 0, 255).into(),
+            ],
+        };
+        let rgba = |t| gradient.sample(t).to_rgba();
+        assert_eq!(rgba(0.0), RgbaColor::new(0, 0, 0, 255));
+        assert_eq!(rgba(0.5), RgbaColor::new(100, 50, 0, 255));
+        assert_eq!(rgba(1.0), RgbaColor::new(200, 100, 0, 255));
+
+        // Positions outside of the run are clamped.
+        assert_eq!(rgba(-1.0), rgba(0.0));
+        assert_eq!(rgba(2.0), rgba(1.0));
+    }
+}
@@ -0,0 +1,400 @@
+//! Defaults that depend on the text's language and region.
+//!
+//! This collects the words Typst writes on its own, the quotes it substitutes
+//! for smart quotes and the way it writes amounts of money, so that supporting
+//! a new language only touches this module.
+
+use typst::util::option_eq;
+
+use crate::prelude::*;
+
+/// A word or phrase that Typst inserts into the document by itself.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Term {
+    /// The supplement of figures with images.
+    Figure,
+    /// The supplement of tables.
+    Table,
+    /// The supplement of equations.
+    Equation,
+    /// The supplement of figures with raw text.
+    Listing,
+    /// The supplement of headings.
+    Section,
+    /// The title of an outline.
+    Contents,
+    /// The title of an outline of image figures.
+    ListOfFigures,
+    /// The title of an outline of table figures.
+    ListOfTables,
+    /// The title of an outline of raw figures.
+    ListOfListings,
+    /// The title of a bibliography.
+    Bibliography,
+    /// The title of an index.
+    Index,
+    /// The word that introduces cross references in an index.
+    See,
+    /// The title of a glossary.
+    Glossary,
+}
+
+impl Term {
+    /// The term in the given language and (optionally) region, falling back to
+    /// English for languages without a translation.
+    pub fn local(self, lang: Lang, region: Option<Region>) -> &'static str {
+        self.translate(lang, region)
+            .or_else(|| self.translate(Lang::ENGLISH, None))
+            .unwrap()
+    }
+
+    /// The term in the given language and (optionally) region, if there is a
+    /// translation for it.
+    pub fn translate(self, lang: Lang, region: Option<Region>) -> Option<&'static str> {
+        let tw = lang == Lang::CHINESE && option_eq(region, "TW");
+        Some(match self {
+            Self::Figure => match lang {
+                Lang::ALBANIAN => "Figurë",
+                Lang::ARABIC => "شكل",
+                Lang::BOKMÅL => "Figur",
+                Lang::CHINESE => "图",
+                Lang::CZECH => "Obrázek",
+                Lang::DANISH => "Figur",
+                Lang::DUTCH => "Figuur",
+                Lang::ENGLISH => "Figure",
+                Lang::FILIPINO => "Pigura",
+                Lang::FRENCH => "Figure",
+                Lang::GERMAN => "Abbildung",
+                Lang::ITALIAN => "Figura",
+                Lang::NYNORSK => "Figur",
+                Lang::POLISH => "Rysunek",
+                Lang::PORTUGUESE => "Figura",
+                Lang::RUSSIAN => "Рисунок",
+                Lang::SLOVENIAN => "Slika",
+                Lang::SPANISH => "Figura",
+                Lang::SWEDISH => "Figur",
+                Lang::TURKISH => "Şekil",
+                Lang::UKRAINIAN => "Рисунок",
+                Lang::VIETNAMESE => "Hình",
+                _ => return None,
+            },
+            Self::Table => match lang {
+                Lang::ALBANIAN => "Tabel",
+                Lang::ARABIC => "جدول",
+                Lang::BOKMÅL => "Tabell",
+                Lang::CHINESE => "表",
+                Lang::CZECH => "Tabulka",
+                Lang::DANISH => "Tabel",
+                Lang::DUTCH => "Tabel",
+                Lang::ENGLISH => "Table",
+                Lang::FILIPINO => "Talaan",
+                Lang::FRENCH => "Tableau",
+                Lang::GERMAN => "Tabelle",
+                Lang::ITALIAN => "Tabella",
+                Lang::NYNORSK => "Tabell",
+                Lang::POLISH => "Tabela",
+                Lang::PORTUGUESE => "Tabela",
+                Lang::RUSSIAN => "Таблица",
+                Lang::SLOVENIAN => "Tabela",
+                Lang::SPANISH => "Tabla",
+                Lang::SWEDISH => "Tabell",
+                Lang::TURKISH => "Tablo",
+                Lang::UKRAINIAN => "Таблиця",
+                Lang::VIETNAMESE => "Bảng",
+                _ => return None,
+            },
+            Self::Equation => match lang {
+                Lang::ALBANIAN => "Ekuacion",
+                Lang::ARABIC => "معادلة",
+                Lang::BOKMÅL => "Ligning",
+                Lang::CHINESE if tw => "方程式",
+                Lang::CHINESE => "等式",
+                Lang::CZECH => "Rovnice",
+                Lang::DANISH => "Ligning",
+                Lang::DUTCH => "Vergelijking",
+                Lang::ENGLISH => "Equation",
+                Lang::FILIPINO => "Ekwasyon",
+                Lang::FRENCH => "Équation",
+                Lang::GERMAN => "Gleichung",
+                Lang::ITALIAN => "Equazione",
+                Lang::NYNORSK => "Likning",
+                Lang::POLISH => "Równanie",
+                Lang::PORTUGUESE => "Equação",
+                Lang::RUSSIAN => "Уравнение",
+                Lang::SLOVENIAN => "Enačba",
+                Lang::SPANISH => "Ecuación",
+                Lang::SWEDISH => "Ekvation",
+                Lang::TURKISH => "Denklem",
+                Lang::UKRAINIAN => "Рівняння",
+                Lang::VIETNAMESE => "Phương trình",
+                _ => return None,
+            },
+            Self::Listing => match lang {
+                Lang::ALBANIAN => "List",
+                Lang::ARABIC => "قائمة",
+                Lang::BOKMÅL => "Utskrift",
+                Lang::CHINESE => "代码",
+                Lang::CZECH => "Seznam",
+                Lang::DANISH => "Liste",
+                Lang::DUTCH => "Listing",
+                Lang::ENGLISH => "Listing",
+                Lang::FILIPINO => "Listahan",
+                Lang::FRENCH => "Liste",
+                Lang::GERMAN => "Listing",
+                Lang::ITALIAN => "Codice",
+                Lang::NYNORSK => "Utskrift",
+                Lang::POLISH => "Program",
+                Lang::RUSSIAN => "Листинг",
+                Lang::SLOVENIAN => "Program",
+                Lang::SWEDISH => "Listing",
+                Lang::TURKISH => "Liste",
+                Lang::UKRAINIAN => "Лістинг",
+                Lang::VIETNAMESE => "Chương trình", // TODO: This may be wrong.
+                _ => return None,
+            },
+            Self::Section => match lang {
+                Lang::ALBANIAN => "Kapitull",
+                Lang::ARABIC => "الفصل",
+                Lang::BOKMÅL => "Kapittel",
+                Lang::CHINESE if tw => "小節",
+                Lang::CHINESE => "小节",
+                Lang::CZECH => "Kapitola",
+                Lang::DANISH => "Afsnit",
+                Lang::DUTCH => "Hoofdstuk",
+                Lang::ENGLISH => "Section",
+                Lang::FILIPINO => "Seksyon",
+                Lang::FRENCH => "Chapitre",
+                Lang::GERMAN => "Abschnitt",
+                Lang::ITALIAN => "Sezione",
+                Lang::NYNORSK => "Kapittel",
+                Lang::POLISH => "Sekcja",
+                Lang::PORTUGUESE => "Seção",
+                Lang::RUSSIAN => "Раздел",
+                Lang::SLOVENIAN => "Poglavje",
+                Lang::SPANISH => "Sección",
+                Lang::SWEDISH => "Kapitel",
+                Lang::TURKISH => "Bölüm",
+                Lang::UKRAINIAN => "Розділ",
+                Lang::VIETNAMESE => "Phần", // TODO: This may be wrong.
+                _ => return None,
+            },
+            Self::Contents => match lang {
+                Lang::ALBANIAN => "Përmbajtja",
+                Lang::ARABIC => "المحتويات",
+                Lang::BOKMÅL => "Innhold",
+                Lang::CHINESE if tw => "目錄",
+                Lang::CHINESE => "目录",
+                Lang::CZECH => "Obsah",
+                Lang::DANISH => "Indhold",
+                Lang::DUTCH => "Inhoudsopgave",
+                Lang::ENGLISH => "Contents",
+                Lang::FILIPINO => "Talaan ng mga Nilalaman",
+                Lang::FRENCH => "Table des matières",
+                Lang::GERMAN => "Inhaltsverzeichnis",
+                Lang::ITALIAN => "Indice",
+                Lang::NYNORSK => "Innhald",
+                Lang::POLISH => "Spis treści",
+                Lang::PORTUGUESE => "Sumário",
+                Lang::RUSSIAN => "Содержание",
+                Lang::SLOVENIAN => "Kazalo",
+                Lang::SPANISH => "Índice",
+                Lang::SWEDISH => "Innehåll",
+                Lang::TURKISH => "İçindekiler",
+                Lang::UKRAINIAN => "Зміст",
+                Lang::VIETNAMESE => "Mục lục",
+                _ => return None,
+            },
+            Self::ListOfFigures => match lang {
+                Lang::BOKMÅL | Lang::DANISH | Lang::SWEDISH => "Figurer",
+                Lang::CHINESE if tw => "插圖",
+                Lang::CHINESE => "插图",
+                Lang::CZECH => "Seznam obrázků",
+                Lang::DUTCH => "Lijst van figuren",
+                Lang::ENGLISH => "List of Figures",
+                Lang::FRENCH => "Table des figures",
+                Lang::GERMAN => "Abbildungsverzeichnis",
+                Lang::ITALIAN => "Elenco delle figure",
+                Lang::NYNORSK => "Figurar",
+                Lang::POLISH => "Spis rysunków",
+                Lang::PORTUGUESE => "Lista de figuras",
+                Lang::RUSSIAN => "Список иллюстраций",
+                Lang::SLOVENIAN => "Slike",
+                Lang::SPANISH => "Índice de figuras",
+                Lang::TURKISH => "Şekil Listesi",
+                Lang::UKRAINIAN => "Перелік ілюстрацій",
+                _ => return None,
+            },
+            Self::ListOfTables => match lang {
+                Lang::BOKMÅL | Lang::DANISH | Lang::SWEDISH => "Tabeller",
+                Lang::CHINESE => "表格",
+                Lang::CZECH => "Seznam tabulek",
+                Lang::DUTCH => "Lijst van tabellen",
+                Lang::ENGLISH => "List of Tables",
+                Lang::FRENCH => "Liste des tableaux",
+                Lang::GERMAN => "Tabellenverzeichnis",
+                Lang::ITALIAN => "Elenco delle tabelle",
+                Lang::NYNORSK => "Tabellar",
+                Lang::POLISH => "Spis tabel",
+                Lang::PORTUGUESE => "Lista de tabelas",
+                Lang::RUSSIAN => "Список таблиц",
+                Lang::SLOVENIAN => "Tabele",
+                Lang::SPANISH => "Índice de tablas",
+                Lang::TURKISH => "Tablo Listesi",
+                Lang::UKRAINIAN => "Перелік таблиць",
+                _ => return None,
+            },
+            Self::ListOfListings => match lang {
+                Lang::ENGLISH => "List of Listings",
+                Lang::GERMAN => "Quelltextverzeichnis",
+                _ => return None,
+            },
+            Self::Bibliography => match lang {
+                Lang::ALBANIAN => "Bibliografi",
+                Lang::ARABIC => "المراجع",
+                Lang::BOKMÅL => "Bibliografi",
+                Lang::CHINESE if tw => "書目",
+                Lang::CHINESE => "参考文献",
+                Lang::CZECH => "Bibliografie",
+                Lang::DANISH => "Bibliografi",
+                Lang::DUTCH => "Bibliografie",
+                Lang::ENGLISH => "Bibliography",
+                Lang::FILIPINO => "Bibliograpiya",
+                Lang::FRENCH => "Bibliographie",
+                Lang::GERMAN => "Bibliographie",
+                Lang::ITALIAN => "Bibliografia",
+                Lang::NYNORSK => "Bibliografi",
+                Lang::POLISH => "Bibliografia",
+                Lang::PORTUGUESE => "Bibliografia",
+                Lang::RUSSIAN => "Библиография",
+                Lang::SLOVENIAN => "Literatura",
+                Lang::SPANISH => "Bibliografía",
+                Lang::SWEDISH => "Bibliografi",
+                Lang::TURKISH => "Kaynakça",
+                Lang::UKRAINIAN => "Бібліографія",
+                Lang::VIETNAMESE => "Tài liệu tham khảo",
+                _ => return None,
+            },
+            Self::Index => match lang {
+                Lang::CZECH => "Rejstřík",
+                Lang::DANISH => "Stikordsregister",
+                Lang::DUTCH => "Register",
+                Lang::ENGLISH => "Index",
+                Lang::FRENCH => "Index",
+                Lang::GERMAN => "Stichwortverzeichnis",
+                Lang::ITALIAN => "Indice analitico",
+                Lang::POLISH => "Indeks",
+                Lang::PORTUGUESE => "Índice remissivo",
+                Lang::SPANISH => "Índice alfabético",
+                Lang::SWEDISH => "Sakregister",
+                _ => return None,
+            },
+            Self::See => match lang {
+                Lang::ENGLISH => "see",
+                Lang::FRENCH => "voir",
+                Lang::GERMAN => "siehe",
+                _ => return None,
+            },
+            Self::Glossary => match lang {
+                Lang::CZECH => "Slovníček",
+                Lang::DANISH => "Ordliste",
+                Lang::DUTCH => "Woordenlijst",
+                Lang::ENGLISH => "Glossary",
+                Lang::FRENCH => "Glossaire",
+                Lang::GERMAN => "Glossar",
+                Lang::ITALIAN => "Glossario",
+                Lang::POLISH => "Słowniczek",
+                Lang::PORTUGUESE => "Glossário",
+                Lang::SPANISH => "Glosario",
+                Lang::SWEDISH => "Ordlista",
+                _ => return None,
+            },
+        })
+    }
+}
+
+/// The opening and closing single quotes and the opening and closing double
+/// quotes of a language and region, if they differ from the English ones.
+pub fn quotes(lang: Lang, region: Option<Region>) -> Option<[&'static str; 4]> {
+    let region = region.as_ref().map(Region::as_str);
+    Some(match lang.as_str() {
+        "de" if matches!(region, Some("CH" | "LI")) => ["‹", "›", "«", "»"],
+        "cs" | "da" | "de" | "et" | "is" | "lt" | "lv" | "sk" | "sl" => {
+            ["‚", "‘", "„", "“"]
+        }
+        "fr" => ["‹\u{00A0}", "\u{00A0}›", "«\u{00A0}", "\u{00A0}»"],
+        "bs" | "fi" | "sv" => ["’", "’", "”", "”"],
+        "es" if matches!(region, Some("ES") | None) => ["“", "”", "«", "»"],
+        "hu" | "pl" | "ro" => ["’", "’", "„", "”"],
+        "ru" | "no" | "nb" | "nn" | "ua" => ["’", "’", "«", "»"],
+        _ if lang.dir() == Dir::RTL => ["’", "‘", "”", "“"],
+        _ => return None,
+    })
+}
+
+/// How a language and region write amounts of money.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MoneyFormat {
+    /// The separator between groups of thousands.
+    pub group: &'static str,
+    /// The separator between the integer and the fractional part.
+    pub decimal: char,
+    /// Whether the symbol or code comes after the amount.
+    pub after: bool,
+    /// Whether a symbol is separated from the amount by a space. Codes always
+    /// are.
+    pub spaced: bool,
+}
+
+impl MoneyFormat {
+    /// The conventions of a language and region.
+    pub fn of(lang: Lang, region: Option<Region>) -> Self {
+        let new = |group, decimal, after, spaced| Self { group, decimal, after, spaced };
+        match (lang.as_str(), region.as_ref().map(Region::as_str)) {
+            ("de" | "fr" | "it", Some("CH")) => new("’", '.', false, true),
+            ("de", Some("AT")) | ("nl", _) => new(".", ',', false, true),
+            ("de" | "it" | "es" | "pt" | "da" | "tr", _) => new(".", ',', true, true),
+            ("fr" | "pl" | "cs" | "sv" | "nb" | "nn" | "ru" | "ua", _) => {
+                new("\u{202f}", ',', true, true)
+            }
+            _ => new(",", '.', false, false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_terms() {
+        let lang = |code: &str| Lang::from_str(code).unwrap();
+        let tw = Region::from_str("tw").ok();
+        assert_eq!(Term::Figure.local(lang("de"), None), "Abbildung");
+        assert_eq!(Term::Section.local(lang("zh"), None), "小节");
+        assert_eq!(Term::Section.local(lang("zh"), tw), "小節");
+        assert_eq!(Term::Glossary.local(lang("ar"), None), "Glossary");
+        assert_eq!(Term::ListOfListings.translate(lang("fr"), None), None);
+
+        // Every term has an English version to fall back to.
+        let terms = [
+            Term::Figure,
+            Term::Table,
+            Term::Equation,
+            Term::Listing,
+            Term::Section,
+            Term::Contents,
+            Term::ListOfFigures,
+            Term::ListOfTables,
+            Term::ListOfListings,
+            Term::Bibliography,
+            Term::Index,
+            Term::See,
+            Term::Glossary,
+        ];
+        for term in terms {
+            assert!(term.translate(Lang::ENGLISH, None).is_some());
+        }
+    }
+}
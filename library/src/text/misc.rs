@@ -1,4 +1,11 @@
-use super::TextElem;
+use std::collections::BTreeSet;
+
+use typst::font::FontVariant;
+
+use super::locale::MoneyFormat;
+use super::{shape, TextElem};
+use crate::layout::{Sizing, SpanMapper, TableElem, TrackSizings};
+use crate::meta::LinkElem;
 use crate::prelude::*;
 
 /// A text space.
@@ -105,7 +112,10 @@ pub struct StrongElem {
 }
 
 impl Show for StrongElem {
-    #[tracing::instrument(name = "StrongElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "StrongElem::show", skip_all)
+    )]
     fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         Ok(self.body().styled(TextElem::set_delta(Delta(self.delta(styles)))))
     }
@@ -163,7 +173,10 @@ pub struct EmphElem {
 }
 
 impl Show for EmphElem {
-    #[tracing::instrument(name = "EmphElem::show", skip(self))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "EmphElem::show", skip(self))
+    )]
     fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
         Ok(self.body().styled(TextElem::set_emph(Toggle)))
     }
@@ -189,6 +202,10 @@ impl Fold for Toggle {
 
 /// Convert text or content to lowercase.
 ///
+/// When applied to content, the case is changed during layout according to the
+/// rules of the text's [language]($func/text.lang). For instance, Turkish
+/// distinguishes between a dotted and a dotless i.
+///
 /// ## Example { #example }
 /// ```example
 /// #lower("ABC") \
@@ -208,11 +225,16 @@ pub fn lower(
 
 /// Convert text or content to uppercase.
 ///
+/// When applied to content, the case is changed during layout according to the
+/// rules of the text's [language]($func/text.lang). For instance, Turkish
+/// distinguishes between a dotted and a dotless i.
+///
 /// ## Example { #example }
 /// ```example
 /// #upper("abc") \
 /// #upper[*my text*] \
-/// #upper[ALREADY HIGH]
+/// #upper[ALREADY HIGH] \
+/// #text(lang: "tr", upper[istanbul])
 /// ```
 ///
 /// Display: Uppercase
@@ -228,7 +250,8 @@ pub fn upper(
 /// Change the case of text.
 fn case(text: Caseable, case: Case) -> Caseable {
     match text {
-        Caseable::Str(v) => Caseable::Str(case.apply(&v).into()),
+        // Strings carry no language, so they are cased with the default rules.
+        Caseable::Str(v) => Caseable::Str(case.apply(&v, Lang::ENGLISH).into()),
         Caseable::Content(v) => {
             Caseable::Content(v.styled(TextElem::set_case(Some(case))))
         }
@@ -261,8 +284,18 @@ pub enum Case {
 }
 
 impl Case {
-    /// Apply the case to a string.
-    pub fn apply(self, text: &str) -> String {
+    /// Apply the case to a string, following the casing rules of the given
+    /// language.
+    pub fn apply(self, text: &str, lang: Lang) -> String {
+        // Turkish and Azerbaijani distinguish between a dotted and a dotless
+        // i in both cases.
+        if matches!(lang.as_str(), "tr" | "az") {
+            return match self {
+                Self::Lower => text.replace('I', "ı").replace('İ', "i").to_lowercase(),
+                Self::Upper => text.replace('i', "İ").to_uppercase(),
+            };
+        }
+
         match self {
             Self::Lower => text.to_lowercase(),
             Self::Upper => text.to_uppercase(),
@@ -328,3 +361,566 @@ pub fn lorem(
 ) -> Str {
     lipsum::lipsum(words).replace("--", "–").into()
 }
+
+/// Create a table filled with blind text.
+///
+/// Each cell holds two words of blind text. With a header, the first row
+/// instead names the columns. Like [`lorem`]($func/lorem), this is useful to
+/// prototype a layout before the real content exists.
+///
+/// ## Example { #example }
+/// ```example
+/// #lorem-table(3, 4)
+/// ```
+///
+/// Display: Blind Table
+/// Category: text
+#[func]
+pub fn lorem_table(
+    /// The number of columns.
+    columns: NonZeroUsize,
+    /// The number of rows, not counting the header.
+    rows: usize,
+    /// Whether to add a header row that names the columns.
+    #[named]
+    #[default(true)]
+    header: bool,
+) -> Content {
+    let n = columns.get();
+    let text = lipsum::lipsum(2 * n * rows);
+    let words: Vec<_> = text.split_whitespace().collect();
+
+    let mut cells = vec![];
+    if header {
+        cells.extend((1..=n).map(|i| {
+            StrongElem::new(TextElem::packed(eco_format!("Column {i}"))).pack()
+        }));
+    }
+    cells.extend(
+        words
+            .chunks(2)
+            .take(n * rows)
+            .map(|pair| TextElem::packed(pair.join(" "))),
+    );
+
+    TableElem::new(cells)
+        .with_columns(TrackSizings(vec![Sizing::Auto; n]))
+        .pack()
+}
+
+/// Inspect the fonts of a family.
+///
+/// Returns a dictionary describing the fonts that are available in the given
+/// family with the following entries:
+/// - `family`: The family's name.
+/// - `variants`: An array of dictionaries with the `style`, `weight` and
+///   `stretch` of each font in the family.
+/// - `features`: The OpenType feature tags supported by any font of the family.
+/// - `metrics`: The `ascender`, `cap-height`, `x-height` and `descender` of the
+///   family's regular font, as lengths relative to the font size.
+/// - `covers`: Whether some font of the family contains all characters of the
+///   `coverage` string, if one was given.
+///
+/// ## Example { #example }
+/// ```example
+/// #let info = font-info("Linux Libertine", coverage: "Füße")
+/// #info.variants.len() variants \
+/// Small caps: #info.features.contains("smcp") \
+/// Covers "Füße": #info.covers
+/// ```
+///
+/// Display: Font Info
+/// Category: text
+#[func]
+pub fn font_info(
+    /// The font family to inspect.
+    family: Spanned<EcoString>,
+    /// A string whose characters should be checked for coverage.
+    #[named]
+    #[default]
+    coverage: Option<EcoString>,
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> SourceResult<Dict> {
+    let world = vm.world();
+    let book = world.book();
+    let lower = family.v.to_lowercase();
+    let ids: Vec<_> = book.select_family(&lower).collect();
+    if ids.is_empty() {
+        bail!(family.span, "unknown font family: {}", family.v);
+    }
+
+    let infos: Vec<_> = ids.iter().filter_map(|&id| book.info(id)).collect();
+    let variants: Array = infos
+        .iter()
+        .map(|info| {
+            let FontVariant { style, weight, stretch } = info.variant;
+            Value::Dict(dict! {
+                "style" => style,
+                "weight" => weight,
+                "stretch" => stretch,
+            })
+        })
+        .collect();
+
+    let mut features = BTreeSet::new();
+    for font in ids.iter().filter_map(|&id| world.font(id)) {
+        let tables = font.ttf().tables();
+        for table in [tables.gsub, tables.gpos].into_iter().flatten() {
+            for feature in table.features {
+                let bytes = feature.tag.to_bytes();
+                features.insert(String::from_utf8_lossy(&bytes).into_owned());
+            }
+        }
+    }
+
+    let mut dict = dict! {
+        "family" => infos[0].family.as_str(),
+        "variants" => variants,
+        "features" => features.into_iter().map(IntoValue::into_value).collect::<Array>(),
+    };
+
+    let regular = book.select(&lower, FontVariant::default()).unwrap_or(ids[0]);
+    if let Some(font) = world.font(regular) {
+        let metrics = font.metrics();
+        dict.insert(
+            "metrics".into(),
+            Value::Dict(dict! {
+                "ascender" => Length::from(metrics.ascender),
+                "cap-height" => Length::from(metrics.cap_height),
+                "x-height" => Length::from(metrics.x_height),
+                "descender" => Length::from(metrics.descender),
+            }),
+        );
+    }
+
+    if let Some(text) = coverage {
+        let covers = infos
+            .iter()
+            .any(|info| text.chars().all(|c| info.coverage.contains(c as u32)));
+        dict.insert("covers".into(), Value::Bool(covers));
+    }
+
+    Ok(dict)
+}
+
+/// Shape text and return the positions of its glyphs.
+///
+/// Returns an array with a dictionary per glyph in visual order with the
+/// following entries:
+/// - `text`: The text of the glyph's cluster. Multiple glyphs may share a
+///   cluster, for example for combining characters.
+/// - `x`: The horizontal pen position at which the glyph starts, relative to
+///   the start of the text.
+/// - `advance`: The glyph's advance width.
+/// - `dx` and `dy`: How far the glyph is offset from its pen position.
+/// - `font`: The family of the font the glyph is taken from.
+/// - `id`: The glyph's index in the font.
+///
+/// The text is shaped on a single line with the given styles. This makes it
+/// possible to build custom text effects in pure script.
+///
+/// ## Example { #example }
+/// ```example
+/// #style(styles => {
+///   for g in glyphs("Wave", styles) {
+///     let dy = calc.sin(g.x / 1pt * 10deg) * 4pt
+///     place(dx: g.x + g.dx, dy: dy + 4pt, g.text)
+///   }
+/// })
+/// ```
+///
+/// Display: Glyphs
+/// Category: text
+#[func]
+pub fn glyphs(
+    /// The text to shape.
+    text: Str,
+    /// The styles with which to shape the text.
+    styles: Styles,
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> Array {
+    let styles = StyleChain::new(&styles);
+    let mut spans = SpanMapper::new();
+    spans.push(text.len(), Span::detached());
+
+    let dir = TextElem::dir_in(styles);
+    let lang = TextElem::lang_in(styles);
+    let region = TextElem::region_in(styles);
+    let shaped = shape(&vm.vt, 0, &text, &spans, styles, dir, lang, region);
+
+    let mut x = Abs::zero();
+    let mut glyphs = Array::new();
+    for glyph in shaped.glyphs.iter() {
+        let advance = glyph.x_advance.at(shaped.size);
+        glyphs.push(Value::Dict(dict! {
+            "text" => &text[glyph.range.clone()],
+            "x" => Length::from(x),
+            "advance" => Length::from(advance),
+            "dx" => Length::from(glyph.x_offset.at(shaped.size)),
+            "dy" => Length::from(glyph.y_offset.at(shaped.size)),
+            "font" => glyph.font.info().family.as_str(),
+            "id" => glyph.glyph_id as i64,
+        }));
+        x += advance;
+    }
+
+    glyphs
+}
+
+/// An International Standard Book Number.
+///
+/// The number is checked for a valid checksum and its hyphens are replaced by
+/// non-breaking ones so that it is never split across lines.
+///
+/// ## Example { #example }
+/// ```example
+/// The book is available under
+/// #isbn("978-3-16-148410-0").
+///
+/// #show isbn: it => text(blue, it)
+/// #isbn("0-306-40615-2", prefix: false)
+/// ```
+///
+/// Display: ISBN
+/// Category: text
+#[element(Show, PlainText)]
+pub struct IsbnElem {
+    /// The ISBN-10 or ISBN-13, optionally grouped with hyphens or spaces.
+    #[required]
+    #[parse(
+        let Spanned { v, span } = args.expect::<Spanned<EcoString>>("number")?;
+        check_isbn(&v).at(span)?;
+        v
+    )]
+    pub number: EcoString,
+
+    /// Whether to put "ISBN" in front of the number.
+    #[default(true)]
+    pub prefix: bool,
+}
+
+impl Show for IsbnElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "IsbnElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let mut text = EcoString::new();
+        if self.prefix(styles) {
+            text.push_str("ISBN\u{a0}");
+        }
+        text.push_str(&unbreakable(&self.number()));
+        Ok(TextElem::packed(text))
+    }
+}
+
+impl PlainText for IsbnElem {
+    fn plain_text(&self, text: &mut EcoString) {
+        text.push_str(&self.number());
+    }
+}
+
+/// Check the digit count and checksum of an ISBN.
+fn check_isbn(number: &str) -> StrResult<()> {
+    let digits: Vec<char> = number.chars().filter(|&c| !matches!(c, '-' | ' ')).collect();
+
+    let value = |i: usize, c: char| match c {
+        '0'..='9' => Ok(c as u32 - '0' as u32),
+        'X' | 'x' if i == 9 && digits.len() == 10 => Ok(10),
+        _ => Err(eco_format!("invalid character in ISBN: {c}")),
+    };
+
+    let sum = match digits.len() {
+        10 => {
+            digits.iter().enumerate().try_fold(0, |sum, (i, &c)| {
+                Ok::<_, EcoString>(sum + (10 - i as u32) * value(i, c)?)
+            })? % 11
+        }
+        13 => {
+            digits.iter().enumerate().try_fold(0, |sum, (i, &c)| {
+                let weight = if i % 2 == 0 { 1 } else { 3 };
+                Ok::<_, EcoString>(sum + weight * value(i, c)?)
+            })? % 10
+        }
+        _ => bail!("ISBN must have 10 or 13 digits"),
+    };
+
+    if sum != 0 {
+        bail!("ISBN has invalid checksum");
+    }
+
+    Ok(())
+}
+
+/// A telephone number.
+///
+/// Spaces and hyphens within the number are replaced by non-breaking ones so
+/// that it is never split across lines. Optionally, the number can also be
+/// linked with a `tel:` URL.
+///
+/// ## Example { #example }
+/// ```example
+/// Call us at #phone("+49 30 123-4567").
+///
+/// #phone("+1 555 0100", link: true)
+/// ```
+///
+/// Display: Phone Number
+/// Category: text
+#[element(Show, PlainText)]
+pub struct PhoneElem {
+    /// The phone number.
+    #[required]
+    pub number: EcoString,
+
+    /// Whether to link the number with a `tel:` URL.
+    #[default(false)]
+    pub link: bool,
+}
+
+impl Show for PhoneElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PhoneElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let number = self.number();
+        let body = TextElem::packed(unbreakable(&number));
+        if !self.link(styles) {
+            return Ok(body);
+        }
+
+        let url: EcoString =
+            number.chars().filter(|c| c.is_ascii_digit() || *c == '+').collect();
+
+        Ok(LinkElem::new(Destination::Url(eco_format!("tel:{url}")).into(), body).pack())
+    }
+}
+
+impl PlainText for PhoneElem {
+    fn plain_text(&self, text: &mut EcoString) {
+        text.push_str(&self.number());
+    }
+}
+
+/// An amount of money.
+///
+/// The amount is rounded to the currency's minor unit, grouped into thousands
+/// and put next to the currency's symbol following the conventions of the
+/// text's [language]($func/text.lang) and [region]($func/text.region). It is
+/// never split across lines.
+///
+/// ## Example { #example }
+/// ```example
+/// #currency(1234.5, "USD") \
+/// #text(lang: "de", currency(1234.5, "EUR")) \
+/// #text(lang: "de", region: "ch", currency(-1234.5, "CHF")) \
+/// #currency(1200, "JPY", symbol: false)
+/// ```
+///
+/// Display: Currency
+/// Category: text
+#[element(Show, PlainText)]
+pub struct CurrencyElem {
+    /// The amount of money.
+    #[required]
+    #[parse(
+        let Spanned { v, span } = args.expect::<Spanned<f64>>("amount")?;
+        if !v.is_finite() {
+            bail!(span, "amount must be finite");
+        }
+        v
+    )]
+    pub amount: f64,
+
+    /// The currency's three-letter ISO 4217 code, like `{"EUR"}`.
+    #[required]
+    #[parse(
+        let Spanned { v, span } = args.expect::<Spanned<EcoString>>("code")?;
+        if v.len() != 3 || !v.bytes().all(|b| b.is_ascii_uppercase()) {
+            bail!(span, "currency code must consist of three uppercase letters");
+        }
+        v
+    )]
+    pub code: EcoString,
+
+    /// Whether to show the currency's symbol instead of its code, if it has
+    /// one.
+    ///
+    /// ```example
+    /// #currency(5, "GBP") vs.
+    /// #currency(5, "GBP", symbol: false)
+    /// ```
+    #[default(true)]
+    pub symbol: bool,
+
+    /// The number of decimal places.
+    ///
+    /// Defaults to the currency's minor unit, which is two decimal places for
+    /// most currencies.
+    ///
+    /// ```example
+    /// #currency(1999.99, "EUR", digits: 0)
+    /// ```
+    pub digits: Smart<usize>,
+}
+
+impl Show for CurrencyElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "CurrencyElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let code = self.code();
+        let digits = self.digits(styles).unwrap_or_else(|| minor_unit(&code));
+        let symbol = if self.symbol(styles) { currency_symbol(&code) } else { None };
+        let lang = TextElem::lang_in(styles);
+        let region = TextElem::region_in(styles);
+        let text = format_money(self.amount(), &code, symbol, digits, lang, region);
+        Ok(TextElem::packed(text))
+    }
+}
+
+impl PlainText for CurrencyElem {
+    fn plain_text(&self, text: &mut EcoString) {
+        text.push_str(&eco_format!("{} {}", self.code(), self.amount()));
+    }
+}
+
+/// The number of decimal places of a currency's minor unit.
+fn minor_unit(code: &str) -> usize {
+    match code {
+        "JPY" | "KRW" | "ISK" | "VND" | "CLP" => 0,
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// The symbol of a currency, if it has a widely used one.
+fn currency_symbol(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "EUR" => "€",
+        "USD" => "$",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        "KRW" => "₩",
+        _ => return None,
+    })
+}
+
+/// Format an amount of money with a currency's symbol or code.
+///
+/// The spaces in the result are non-breaking.
+fn format_money(
+    amount: f64,
+    code: &str,
+    symbol: Option<&str>,
+    digits: usize,
+    lang: Lang,
+    region: Option<Region>,
+) -> EcoString {
+    let format = MoneyFormat::of(lang, region);
+    let fixed = format!("{:.*}", digits, amount.abs());
+    let (int, frac) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+    let mut number = EcoString::new();
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            number.push_str(format.group);
+        }
+        number.push(c);
+    }
+    if !frac.is_empty() {
+        number.push(format.decimal);
+        number.push_str(frac);
+    }
+
+    let unit = symbol.unwrap_or(code);
+    let space = if symbol.is_none() || format.spaced { "\u{a0}" } else { "" };
+    let mut text = EcoString::new();
+    if amount < 0.0 && fixed.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        text.push('−');
+    }
+    if format.after {
+        text.push_str(&eco_format!("{number}{space}{unit}"));
+    } else {
+        text.push_str(&eco_format!("{unit}{space}{number}"));
+    }
+    text
+}
+
+/// Replace spaces and hyphens by their non-breaking variants.
+fn unbreakable(text: &str) -> EcoString {
+    text.chars()
+        .map(|c| match c {
+            ' ' => '\u{a0}',
+            '-' => '\u{2011}',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::tests::TestWorld;
+
+    #[test]
+    fn test_format_money() {
+        let lang = |code: &str| Lang::from_str(code).unwrap();
+        let region = |code: &str| Some(Region::from_str(code).unwrap());
+        let money = |amount, code, digits, lang, region| {
+            format_money(amount, code, currency_symbol(code), digits, lang, region)
+        };
+
+        // Thousands are grouped and the symbol is placed like in the language.
+        let en = lang("en");
+        assert_eq!(money(1234567.891, "USD", 2, en, None), "$1,234,567.89");
+        assert_eq!(money(1234.5, "EUR", 2, lang("de"), None), "1.234,50\u{a0}€");
+        assert_eq!(money(1234.5, "EUR", 2, lang("nl"), None), "€\u{a0}1.234,50");
+        assert_eq!(money(1234.5, "EUR", 2, lang("fr"), None), "1\u{202f}234,50\u{a0}€");
+
+        // Regions can deviate from their language.
+        assert_eq!(money(1234.5, "EUR", 2, lang("de"), region("at")), "€\u{a0}1.234,50");
+        assert_eq!(
+            money(1234.5, "CHF", 2, lang("de"), region("ch")),
+            "CHF\u{a0}1’234.50"
+        );
+
+        // Currencies without a symbol are written with their code.
+        assert_eq!(money(12.0, "CHF", 2, en, None), "CHF\u{a0}12.00");
+        assert_eq!(money(999.0, "JPY", 0, en, None), "¥999");
+
+        // Negative amounts get a minus sign, unless they round to zero.
+        assert_eq!(money(-20.0, "USD", 2, en, None), "−$20.00");
+        assert_eq!(money(-0.001, "USD", 2, en, None), "$0.00");
+        assert_eq!(minor_unit("JPY"), 0);
+        assert_eq!(minor_unit("EUR"), 2);
+    }
+
+    #[test]
+    fn test_locale_aware_case() {
+        let world = TestWorld::new(
+            "#upper[istanbul] #lower[ISPARTA]\n\n\
+             #set text(lang: \"tr\")\n\
+             #upper[istanbul] #lower[ISPARTA İZMİR] #upper(\"istanbul\")",
+        );
+        let document = world.compile();
+
+        // Content is cased with the rules of its language, strings with the
+        // default rules.
+        let words: Vec<_> = typst::ide::words(&world, &document)
+            .into_iter()
+            .map(|word| word.text.to_string())
+            .collect();
+        assert_eq!(
+            words,
+            ["ISTANBUL", "isparta", "İSTANBUL", "ısparta", "izmir", "ISTANBUL"]
+        );
+    }
+}
@@ -1,6 +1,9 @@
 //! Text handling.
 
+pub mod locale;
+
 mod deco;
+mod fill;
 mod misc;
 mod quotes;
 mod raw;
@@ -8,6 +11,7 @@ mod shaping;
 mod shift;
 
 pub use self::deco::*;
+pub use self::fill::*;
 pub use self::misc::*;
 pub use self::quotes::*;
 pub use self::raw::*;
@@ -19,6 +23,7 @@ use typst::font::{FontMetrics, FontStretch, FontStyle, FontWeight, VerticalFontM
 
 use crate::layout::ParElem;
 use crate::prelude::*;
+use crate::visualize::Shadow;
 
 /// Hook up all text definitions.
 pub(super) fn define(global: &mut Scope) {
@@ -36,7 +41,15 @@ pub(super) fn define(global: &mut Scope) {
     global.define("strike", StrikeElem::func());
     global.define("overline", OverlineElem::func());
     global.define("raw", RawElem::func());
+    global.define("example", ExampleElem::func());
     global.define("lorem", lorem_func());
+    global.define("lorem-table", lorem_table_func());
+    global.define("gradient", gradient_func());
+    global.define("font-info", font_info_func());
+    global.define("glyphs", glyphs_func());
+    global.define("isbn", IsbnElem::func());
+    global.define("phone", PhoneElem::func());
+    global.define("currency", CurrencyElem::func());
 }
 
 /// Customize the look and layout of text in a variety of ways.
@@ -87,7 +100,9 @@ pub struct TextElem {
     /// no glyphs are found. Instead, your text shows up in the form of "tofus":
     /// Small boxes that indicate the lack of an appropriate glyph. In the
     /// future, you will be able to instruct Typst to issue warnings so you know
-    /// something is up.
+    /// something is up. If the compiler is configured to use placeholders,
+    /// text for which none of the families is available is shown in a
+    /// fallback font, with a warning.
     ///
     /// ```example
     /// #set text(font: "Inria Serif")
@@ -175,9 +190,33 @@ pub struct TextElem {
     /// #set text(fill: red)
     /// This text is red.
     /// ```
+    ///
+    /// Instead of a single color, this can also be a
+    /// [gradient]($func/gradient) or a function. A function is called once
+    /// per run of text with an array of the glyphs' relative positions in
+    /// that run. It must return either a single paint for the whole run or an
+    /// array with one paint per glyph. Formulas use the first color of a
+    /// gradient and black for a function.
+    ///
+    /// ```example
+    /// #text(fill: gradient(blue, red))[Temperature rising]
+    ///
+    /// #let striped(positions) = range(positions.len())
+    ///   .map(i => if calc.even(i) { black } else { eastern })
+    /// #text(fill: striped)[Zebra text]
+    /// ```
     #[parse(args.named_or_find("fill")?)]
     #[default(Color::BLACK.into())]
-    pub fill: Paint,
+    pub fill: TextFill,
+
+    /// A drop shadow behind the glyphs. See the [shadow's
+    /// documentation]($func/shadow) for more details.
+    ///
+    /// ```example
+    /// #set text(shadow: shadow(offset: (1pt, 1pt), color: silver))
+    /// Embossed
+    /// ```
+    pub shadow: Option<Shadow>,
 
     /// The amount of space that should be added between characters.
     ///
@@ -289,6 +328,35 @@ pub struct TextElem {
     /// This lets the text processing pipeline make more informed choices.
     pub region: Option<Region>,
 
+    /// Overrides for the names of built-in elements, like the "Figure" in
+    /// figure supplements or the title of the outline.
+    ///
+    /// Maps element names (`figure`, `table`, `equation`, `heading`,
+    /// `outline`, `bibliography`, `raw` and `image`) to either a string or a
+    /// dictionary from language codes to strings. Language codes can include
+    /// a region, like `{"de-CH"}`. Elements without a matching override use
+    /// the built-in translation for the current language.
+    ///
+    /// ```example
+    /// #set text(names: (
+    ///   figure: (de: "Abb.", en: "Fig."),
+    ///   outline: "Overview",
+    /// ))
+    ///
+    /// #figure(
+    ///   rect[Typst],
+    ///   caption: [A box],
+    /// )
+    ///
+    /// #set text(lang: "de")
+    /// #figure(
+    ///   rect[Typst],
+    ///   caption: [Eine Box],
+    /// )
+    /// ```
+    #[fold]
+    pub names: Dict,
+
     /// The dominant direction for text and inline objects. Possible values are:
     ///
     /// - `{auto}`: Automatically infer the direction from the `lang` property.
@@ -338,6 +406,26 @@ pub struct TextElem {
     #[resolve]
     pub hyphenate: Hyphenate,
 
+    /// Custom hyphenations for specific words, which take precedence over the
+    /// language's hyphenation patterns.
+    ///
+    /// Each word is given with hyphens at its allowed break points. A word
+    /// without hyphens is never hyphenated. The exceptions can either apply to
+    /// all languages or be given in a dictionary from language codes to words,
+    /// where the key `all` again applies to all languages.
+    /// Matching is case-insensitive. To disable hyphenation in a scope
+    /// altogether, set [`hyphenate`]($func/text.hyphenate) to `{false}`
+    /// instead.
+    ///
+    /// ```example
+    /// #set page(width: 80pt)
+    /// #set text(hyphenate: true)
+    /// #set text(hyphenation-exceptions: ("Typ-st", "table"))
+    /// Typst typst typst table table table
+    /// ```
+    #[fold]
+    pub hyphenation_exceptions: HyphenationExceptions,
+
     /// Whether to apply kerning.
     ///
     /// When enabled, specific letter pairings move closer together or further
@@ -388,7 +476,9 @@ pub struct TextElem {
     /// Certain letter combinations like "fi" are often displayed as a single
     /// merged glyph called a _ligature._ Setting this to `{false}` disables
     /// these ligatures by turning off the OpenType `liga` and `clig` font
-    /// features.
+    /// features. Text is shaped separately at every style change, so
+    /// ligatures never span across differently styled pieces of text. In
+    /// [raw text]($func/raw), ligatures are disabled by default.
     ///
     /// ```example
     /// #set text(size: 20pt)
@@ -396,6 +486,9 @@ pub struct TextElem {
     ///
     /// #set text(ligatures: false)
     /// A fine ligature.
+    ///
+    /// #set text(ligatures: true)
+    /// A #text(ligatures: false)[fi]ne ligature.
     /// ```
     #[default(true)]
     pub ligatures: bool,
@@ -502,6 +595,10 @@ pub struct TextElem {
     #[fold]
     pub deco: Decoration,
 
+    /// A background fill behind the text.
+    #[internal]
+    pub background: Option<Paint>,
+
     /// A case transformation that should be applied to the text.
     #[internal]
     pub case: Option<Case>,
@@ -682,6 +779,85 @@ impl Resolve for Hyphenate {
     }
 }
 
+/// Words with custom break points for hyphenation.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct HyphenationExceptions(pub Vec<(Option<Lang>, EcoString)>);
+
+impl HyphenationExceptions {
+    /// The lengths of the syllables of a word in the given language, if there
+    /// is an exception for it.
+    pub fn syllables(&self, word: &str, lang: Lang) -> Option<Vec<usize>> {
+        let lower = word.to_lowercase();
+        let (_, exception) = self.0.iter().find(|(l, exception)| {
+            l.map_or(true, |l| l == lang)
+                && exception.replace('-', "").to_lowercase() == lower
+        })?;
+
+        // Map the syllables' character counts to byte lengths in the word.
+        let mut chars = word.chars();
+        let mut syllables = vec![];
+        for part in exception.split('-') {
+            let len = chars.by_ref().take(part.chars().count()).map(char::len_utf8).sum();
+            syllables.push(len);
+        }
+
+        (chars.next().is_none() && !syllables.contains(&0)).then_some(syllables)
+    }
+}
+
+cast! {
+    HyphenationExceptions,
+    self => {
+        let mut dict = Dict::new();
+        let mut all = Array::new();
+        for (lang, word) in self.0 {
+            let Some(lang) = lang else {
+                all.push(word.into_value());
+                continue;
+            };
+            let key = Str::from(lang.as_str());
+            match dict.at_mut(&key) {
+                Ok(Value::Array(words)) => words.push(word.into_value()),
+                _ => dict.insert(key, array![word].into_value()),
+            }
+        }
+        if dict.is_empty() {
+            all.into_value()
+        } else {
+            if !all.is_empty() {
+                dict.insert("all".into(), all.into_value());
+            }
+            dict.into_value()
+        }
+    },
+    words: Array => Self(words
+        .into_iter()
+        .map(|v| Ok((None, v.cast::<EcoString>()?)))
+        .collect::<StrResult<_>>()?),
+    langs: Dict => {
+        let mut exceptions = vec![];
+        for (key, words) in langs {
+            let lang = match key.as_str() {
+                "all" => None,
+                code => Some(code.parse::<Lang>()?),
+            };
+            for word in words.cast::<Array>()? {
+                exceptions.push((lang, word.cast::<EcoString>()?));
+            }
+        }
+        Self(exceptions)
+    },
+}
+
+impl Fold for HyphenationExceptions {
+    type Output = Self;
+
+    fn fold(mut self, outer: Self::Output) -> Self::Output {
+        self.0.extend(outer.0);
+        self
+    }
+}
+
 /// A stylistic set in a font.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct StylisticSet(u8);
@@ -1,5 +1,6 @@
 use typst::syntax::is_newline;
 
+use super::locale;
 use crate::prelude::*;
 
 /// A language-aware quote that reacts to its context.
@@ -136,19 +137,10 @@ impl<'s> Quotes<'s> {
     ///
     /// For unknown languages, the English quotes are used.
     pub fn from_lang(lang: Lang, region: Option<Region>) -> Self {
-        let region = region.as_ref().map(Region::as_str);
-        let (single_open, single_close, double_open, double_close) = match lang.as_str() {
-            "de" if matches!(region, Some("CH" | "LI")) => ("‹", "›", "«", "»"),
-            "cs" | "da" | "de" | "et" | "is" | "lt" | "lv" | "sk" | "sl" => {
-                ("‚", "‘", "„", "“")
-            }
-            "fr" => ("‹\u{00A0}", "\u{00A0}›", "«\u{00A0}", "\u{00A0}»"),
-            "bs" | "fi" | "sv" => ("’", "’", "”", "”"),
-            "es" if matches!(region, Some("ES") | None) => ("“", "”", "«", "»"),
-            "hu" | "pl" | "ro" => ("’", "’", "„", "”"),
-            "ru" | "no" | "nb" | "nn" | "ua" => ("’", "’", "«", "»"),
-            _ if lang.dir() == Dir::RTL => ("’", "‘", "”", "“"),
-            _ => return Self::default(),
+        let Some([single_open, single_close, double_open, double_close]) =
+            locale::quotes(lang, region)
+        else {
+            return Self::default();
         };
 
         Self {
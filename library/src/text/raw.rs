@@ -1,11 +1,13 @@
 use once_cell::sync::Lazy;
 use syntect::highlighting as synt;
+use typst::eval::{eval_string, EvalMode};
 use typst::syntax::{self, LinkedNode};
 
+use super::locale::Term;
 use super::{
     FontFamily, FontList, Hyphenate, LinebreakElem, SmartQuoteElem, TextElem, TextSize,
 };
-use crate::layout::BlockElem;
+use crate::layout::{BlockElem, Spacing, StackChild, StackElem};
 use crate::meta::{Figurable, LocalName};
 use crate::prelude::*;
 
@@ -126,6 +128,20 @@ pub struct RawElem {
     /// ````
     #[default(HorizontalAlign(GenAlign::Start))]
     pub align: HorizontalAlign,
+
+    /// The background of inline raw text. This option is ignored for raw
+    /// blocks, which can be filled with a
+    /// [show rule]($func/raw.block) instead.
+    ///
+    /// Unlike a [box]($func/box) around the raw text, the background is
+    /// drawn behind each line's part of the text, so the raw text can
+    /// still be broken across lines.
+    ///
+    /// ````example
+    /// #set raw(fill: luma(230))
+    /// Call `fn main()` to start.
+    /// ````
+    pub fill: Option<Paint>,
 }
 
 impl RawElem {
@@ -153,7 +169,10 @@ impl Synthesize for RawElem {
 }
 
 impl Show for RawElem {
-    #[tracing::instrument(name = "RawElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "RawElem::show", skip_all)
+    )]
     fn show(&self, _: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let text = self.text();
         let lang = self.lang(styles).as_ref().map(|s| s.to_lowercase());
@@ -214,10 +233,16 @@ impl Show for RawElem {
 }
 
 impl Finalize for RawElem {
-    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+    fn finalize(&self, realized: Content, outer: StyleChain) -> Content {
         let mut styles = Styles::new();
+        if !self.block(outer) {
+            if let Some(fill) = self.fill(outer) {
+                styles.set(TextElem::set_background(Some(fill)));
+            }
+        }
         styles.set(TextElem::set_overhang(false));
         styles.set(TextElem::set_hyphenate(Hyphenate(Smart::Custom(false))));
+        styles.set(TextElem::set_ligatures(false));
         styles.set(TextElem::set_size(TextSize(Em::new(0.8).into())));
         styles
             .set(TextElem::set_font(FontList(vec![FontFamily::new("DejaVu Sans Mono")])));
@@ -227,29 +252,8 @@ impl Finalize for RawElem {
 }
 
 impl LocalName for RawElem {
-    fn local_name(&self, lang: Lang, _: Option<Region>) -> &'static str {
-        match lang {
-            Lang::ALBANIAN => "List",
-            Lang::ARABIC => "قائمة",
-            Lang::BOKMÅL => "Utskrift",
-            Lang::CHINESE => "代码",
-            Lang::CZECH => "Seznam",
-            Lang::DANISH => "Liste",
-            Lang::DUTCH => "Listing",
-            Lang::FILIPINO => "Listahan",
-            Lang::FRENCH => "Liste",
-            Lang::GERMAN => "Listing",
-            Lang::ITALIAN => "Codice",
-            Lang::NYNORSK => "Utskrift",
-            Lang::POLISH => "Program",
-            Lang::RUSSIAN => "Листинг",
-            Lang::SLOVENIAN => "Program",
-            Lang::SWEDISH => "Listing",
-            Lang::TURKISH => "Liste",
-            Lang::UKRAINIAN => "Лістинг",
-            Lang::VIETNAMESE => "Chương trình", // TODO: This may be wrong.
-            Lang::ENGLISH | _ => "Listing",
-        }
+    fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
+        Term::Listing.local(lang, region)
     }
 }
 
@@ -261,6 +265,83 @@ impl PlainText for RawElem {
     }
 }
 
+/// Typst code together with the result of evaluating it.
+///
+/// This is useful to document Typst itself or to write literate documents in
+/// which the code that produces something is shown alongside its output. The
+/// code is evaluated in the same sandbox as with the [`eval`]($func/eval)
+/// function and thus cannot interact with the file system.
+///
+/// ## Example { #example }
+/// ```example
+/// #example("*Hello* _world_")
+/// #example("calc.pow(2, 10)", mode: "code", dir: ltr)
+/// ```
+///
+/// Display: Example
+/// Category: text
+#[element(Show)]
+pub struct ExampleElem {
+    /// The code to display and evaluate.
+    #[required]
+    pub text: EcoString,
+
+    /// The syntactical mode in which the code is parsed.
+    #[default(EvalMode::Markup)]
+    pub mode: EvalMode,
+
+    /// Whether to display the result of evaluating the code.
+    ///
+    /// ```example
+    /// #example("#lorem(5)", result: false)
+    /// ```
+    #[default(true)]
+    pub result: bool,
+
+    /// Where to place the result relative to the code.
+    ///
+    /// With `{ttb}`, the result is placed below the code. With `{ltr}`, it is
+    /// placed next to it.
+    #[default(Dir::TTB)]
+    pub dir: Dir,
+
+    /// The spacing between the code and its result.
+    #[default(Em::new(0.65).into())]
+    pub gap: Length,
+}
+
+impl Show for ExampleElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ExampleElem::show", skip_all)
+    )]
+    fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
+        let text = self.text();
+        let mode = self.mode(styles);
+        let lang = match mode {
+            EvalMode::Code => "typc",
+            EvalMode::Markup => "typ",
+        };
+
+        let code = RawElem::new(text.clone())
+            .with_block(true)
+            .with_lang(Some(lang.into()))
+            .pack();
+
+        if !self.result(styles) {
+            return Ok(code);
+        }
+
+        let result =
+            eval_string(vt.world, &text, self.span(), mode, Scope::new(), false)?
+                .display();
+        Ok(StackElem::new(vec![StackChild::Block(code), StackChild::Block(result)])
+            .with_dir(self.dir(styles))
+            .with_spacing(Some(Spacing::Rel(self.gap(styles).into())))
+            .pack())
+    }
+}
+
 /// Highlight a syntax node in a theme by calling `f` with ranges and their
 /// styles.
 fn highlight_themed<F>(
@@ -290,9 +371,9 @@ fn highlight_themed<F>(
 fn styled(piece: &str, foreground: Paint, style: synt::Style) -> Content {
     let mut body = TextElem::packed(piece);
 
-    let paint = to_typst(style.foreground).into();
+    let paint: Paint = to_typst(style.foreground).into();
     if paint != foreground {
-        body = body.styled(TextElem::set_fill(paint));
+        body = body.styled(TextElem::set_fill(paint.into()));
     }
 
     if style.font_style.contains(synt::FontStyle::BOLD) {
@@ -396,3 +477,60 @@ fn item(
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    /// The backgrounds and texts on a page.
+    fn collect(page: &Frame) -> (Vec<(Point, Size)>, Vec<(Point, &str)>) {
+        let fill = Some(Paint::Solid(LumaColor::new(230).into()));
+        let (mut fills, mut texts) = (vec![], vec![]);
+        for (pos, item) in items(page) {
+            match item {
+                FrameItem::Shape(shape, _) if shape.fill == fill => {
+                    if let Geometry::Rect(size) = shape.geometry {
+                        fills.push((pos, size));
+                    }
+                }
+                FrameItem::Text(text) => texts.push((pos, text.text.as_str())),
+                _ => {}
+            }
+        }
+        (fills, texts)
+    }
+
+    #[test]
+    fn test_raw_fill() {
+        let document = compile(
+            "#set page(width: 80pt, height: auto, margin: 10pt)\n\
+             #set raw(fill: luma(230))\n\
+             Call `a bb ccc dddd eeeee ffffff` now.\n\
+             #pagebreak()\n\
+             #raw(block: true, \"let x = 1\")",
+        );
+
+        // The inline raw text is broken across lines and each line's part
+        // gets its own background behind exactly its text.
+        let (fills, texts) = collect(&document.pages[0]);
+        let mut lines: Vec<_> = fills.iter().map(|(pos, _)| pos.y.to_raw()).collect();
+        lines.dedup();
+        assert!(lines.len() >= 2);
+        for (pos, size) in &fills {
+            let (_, text) = texts
+                .iter()
+                .find(|(start, _)| {
+                    start.x == pos.x && pos.y < start.y && start.y < pos.y + size.y
+                })
+                .unwrap();
+            assert!(!text.contains("Call") && !text.contains("now"));
+            assert!(size.x > Abs::zero());
+        }
+
+        // Raw blocks ignore the inline fill.
+        let (fills, texts) = collect(&document.pages[1]);
+        assert!(fills.is_empty());
+        assert!(!texts.is_empty());
+    }
+}
@@ -1,10 +1,13 @@
 use std::borrow::Cow;
 use std::ops::Range;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use az::SaturatingAs;
+use once_cell::sync::Lazy;
 use rustybuzz::{Feature, Tag, UnicodeBuffer};
 use typst::font::{Font, FontStyle, FontVariant};
+use typst::util::lru::Lru;
 use typst::util::SliceExt;
 use unicode_script::{Script, UnicodeScript};
 
@@ -214,10 +217,10 @@ impl<'a> ShapedText<'a> {
     /// [justifiable glyph](ShapedGlyph::is_justifiable) will get.
     pub fn build(
         &self,
-        vt: &Vt,
+        vt: &mut Vt,
         justification_ratio: f64,
         extra_justification: Abs,
-    ) -> Frame {
+    ) -> SourceResult<Frame> {
         let (top, bottom) = self.measure(vt);
         let size = Size::new(self.width, top + bottom);
 
@@ -229,12 +232,30 @@ impl<'a> ShapedText<'a> {
         let lang = TextElem::lang_in(self.styles);
         let decos = TextElem::deco_in(self.styles);
         let fill = TextElem::fill_in(self.styles);
+        let shadow = TextElem::shadow_in(self.styles);
+
+        // Determine the paint of each glyph from the position of its center.
+        // Glyphs are only split into separate items where their paints
+        // differ.
+        let mut positions = Vec::with_capacity(self.glyphs.len());
+        let mut x = Abs::zero();
+        for glyph in self.glyphs.iter() {
+            let advance = glyph.x_advance.at(self.size);
+            positions.push(if fill.varies() && self.width > Abs::zero() {
+                Ratio::new((x + advance / 2.0) / self.width)
+            } else {
+                Ratio::zero()
+            });
+            x += advance;
+        }
+        let paints = fill.paints(vt, &positions)?;
+        let glyphs: Vec<_> = self.glyphs.iter().zip(paints).collect();
 
-        for ((font, y_offset), group) in
-            self.glyphs.as_ref().group_by_key(|g| (g.font.clone(), g.y_offset))
+        for ((font, y_offset, paint), group) in
+            glyphs.group_by_key(|(g, paint)| (g.font.clone(), g.y_offset, paint.clone()))
         {
-            let mut range = group[0].range.clone();
-            for glyph in group {
+            let mut range = group[0].0.range.clone();
+            for (glyph, _) in group {
                 range.start = range.start.min(glyph.range.start);
                 range.end = range.end.max(glyph.range.end);
             }
@@ -242,7 +263,7 @@ impl<'a> ShapedText<'a> {
             let pos = Point::new(offset, top + shift - y_offset.at(self.size));
             let glyphs = group
                 .iter()
-                .map(|glyph| {
+                .map(|(glyph, _)| {
                     let adjustability_left = if justification_ratio < 0.0 {
                         glyph.shrinkability().0
                     } else {
@@ -282,12 +303,13 @@ impl<'a> ShapedText<'a> {
                 font,
                 size: self.size,
                 lang,
-                fill: fill.clone(),
+                region: self.region,
+                fill: paint,
                 text: self.text[range.start - self.base..range.end - self.base].into(),
                 glyphs,
             };
 
-            let layer = frame.layer();
+            let mut layer = frame.layer();
             let width = item.width();
 
             // Apply line decorations.
@@ -295,14 +317,33 @@ impl<'a> ShapedText<'a> {
                 decorate(&mut frame, deco, &item, shift, pos, width);
             }
 
+            // Add the shadow below the glyphs.
+            if let Some(shadow) = &shadow {
+                let delta = shadow.resolve_offset(self.styles, size);
+                let copy = TextItem { fill: shadow.color.into(), ..item.clone() };
+                let mut group = shadow.group(self.styles, Frame::new(size));
+                if group.filters.is_empty() {
+                    frame.insert(layer, pos + delta, FrameItem::Text(copy));
+                } else {
+                    group.frame.push(pos, FrameItem::Text(copy));
+                    frame.insert(layer, delta, FrameItem::Group(group));
+                }
+                layer += 1;
+            }
+
             frame.insert(layer, pos, FrameItem::Text(item));
             offset += width;
         }
 
+        // Apply the background, which spans the text's top and bottom edges.
+        if let Some(background) = TextElem::background_in(self.styles) {
+            frame.fill(background);
+        }
+
         // Apply metadata.
         frame.meta(self.styles, false);
 
-        frame
+        Ok(frame)
     }
 
     /// Measure the top and bottom extent of this text.
@@ -521,7 +562,7 @@ struct ShapingContext<'a, 'v> {
     styles: StyleChain<'a>,
     size: Abs,
     variant: FontVariant,
-    tags: Vec<rustybuzz::Feature>,
+    tags: Vec<(Tag, u32)>,
     fallback: bool,
     dir: Dir,
 }
@@ -548,7 +589,8 @@ pub fn shape<'a>(
         styles,
         variant: variant(styles),
         tags: tags(styles),
-        fallback: TextElem::fallback_in(styles),
+        fallback: TextElem::fallback_in(styles)
+            || (vt.world.placeholders() && missing_family(vt.world, styles).is_some()),
         dir,
     };
 
@@ -613,21 +655,15 @@ fn shape_segment(
 
     ctx.used.push(font.clone());
 
-    // Fill the buffer with our text.
-    let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
-    buffer.set_language(language(ctx.styles));
-    buffer.set_direction(match ctx.dir {
-        Dir::LTR => rustybuzz::Direction::LeftToRight,
-        Dir::RTL => rustybuzz::Direction::RightToLeft,
+    let ltr = match ctx.dir {
+        Dir::LTR => true,
+        Dir::RTL => false,
         _ => unimplemented!("vertical text layout"),
-    });
+    };
 
-    // Shape!
-    let buffer = rustybuzz::shape(font.rusty(), &ctx.tags, buffer);
-    let infos = buffer.glyph_infos();
-    let pos = buffer.glyph_positions();
-    let ltr = ctx.dir.is_positive();
+    // Shape! Runs that were already shaped with the same font and features
+    // are served from the cache.
+    let infos = shape_run(&font, &ctx.tags, text, &language(ctx.styles), ltr);
 
     // Collect the shaped glyphs, doing fallback and shaping parts again with
     // the next font if necessary.
@@ -647,14 +683,14 @@ fn shape_segment(
 
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
-                glyph_id: info.glyph_id as u16,
+                glyph_id: info.glyph_id,
                 // TODO: Don't ignore y_advance.
-                x_advance: font.to_em(pos[i].x_advance),
-                x_offset: font.to_em(pos[i].x_offset),
-                y_offset: font.to_em(pos[i].y_offset),
+                x_advance: font.to_em(info.x_advance),
+                x_offset: font.to_em(info.x_offset),
+                y_offset: font.to_em(info.y_offset),
                 adjustability: Adjustability::default(),
                 range: start..end,
-                safe_to_break: !info.unsafe_to_break(),
+                safe_to_break: info.safe_to_break,
                 c: text[cluster..].chars().next().unwrap(),
                 span: ctx.spans.span_at(start),
             });
@@ -705,6 +741,97 @@ fn shape_segment(
     ctx.used.pop();
 }
 
+/// A glyph resulting from shaping a run of text with a single font.
+#[derive(Debug, Clone)]
+struct RunGlyph {
+    /// The glyph's index in the font. Zero if the font has no glyph for the
+    /// cluster.
+    glyph_id: u16,
+    /// The byte offset of the glyph's cluster in the run.
+    cluster: u32,
+    /// The advance width of the glyph in font units.
+    x_advance: i32,
+    /// The horizontal offset of the glyph in font units.
+    x_offset: i32,
+    /// The vertical offset of the glyph in font units.
+    y_offset: i32,
+    /// Whether the run can be split before this glyph without reshaping.
+    safe_to_break: bool,
+}
+
+/// The key of a shaped run: its font, OpenType features, text, language and
+/// direction.
+type RunKey = (Font, Vec<(Tag, u32)>, EcoString, EcoString, bool);
+
+/// Runs that were already shaped.
+static RUNS: Lazy<Lru<RunKey, Arc<[RunGlyph]>>> =
+    Lazy::new(|| Lru::new(SHAPING_CACHE, 32 * 1024 * 1024));
+
+/// The name of the size-bounded cache of shaped runs, whose limit can be
+/// configured with [`set_limit`](typst::util::lru::set_limit).
+pub const SHAPING_CACHE: &str = "shaping";
+
+/// Shape a run of text with a single font.
+///
+/// Documents tend to contain the same runs over and over again (common words,
+/// repeated table cells, headers and footers), so the results are kept in a
+/// size-bounded cache.
+fn shape_run(
+    font: &Font,
+    features: &[(Tag, u32)],
+    text: &str,
+    lang: &str,
+    ltr: bool,
+) -> Arc<[RunGlyph]> {
+    RUNS.get_or_insert(
+        (font.clone(), features.to_vec(), text.into(), lang.into(), ltr),
+        || shape_run_uncached(font, features, text, lang, ltr),
+        |glyphs| {
+            text.len()
+                + features.len() * std::mem::size_of::<(Tag, u32)>()
+                + glyphs.len() * std::mem::size_of::<RunGlyph>()
+        },
+    )
+}
+
+/// Shape a run of text with a single font, without caching.
+fn shape_run_uncached(
+    font: &Font,
+    features: &[(Tag, u32)],
+    text: &str,
+    lang: &str,
+    ltr: bool,
+) -> Arc<[RunGlyph]> {
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_language(rustybuzz::Language::from_str(lang).unwrap());
+    buffer.set_direction(if ltr {
+        rustybuzz::Direction::LeftToRight
+    } else {
+        rustybuzz::Direction::RightToLeft
+    });
+
+    let features: Vec<_> = features
+        .iter()
+        .map(|&(tag, value)| Feature::new(tag, value, ..))
+        .collect();
+
+    let buffer = rustybuzz::shape(font.rusty(), &features, buffer);
+    buffer
+        .glyph_infos()
+        .iter()
+        .zip(buffer.glyph_positions())
+        .map(|(info, pos)| RunGlyph {
+            glyph_id: info.glyph_id as u16,
+            cluster: info.cluster,
+            x_advance: pos.x_advance,
+            x_offset: pos.x_offset,
+            y_offset: pos.y_offset,
+            safe_to_break: !info.unsafe_to_break(),
+        })
+        .collect()
+}
+
 /// Shape the text with tofus from the given font.
 fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
     let x_advance = font.advance(0).unwrap_or_default();
@@ -839,11 +966,31 @@ pub fn families(styles: StyleChain) -> impl Iterator<Item = FontFamily> + Clone
         .chain(tail.iter().copied().map(FontFamily::new))
 }
 
+/// The first of the font families set for the text if none of them is
+/// available.
+///
+/// If the compiler is configured to use placeholders, such text is shown in a
+/// fallback font.
+pub fn missing_family(
+    world: Tracked<dyn World + '_>,
+    styles: StyleChain,
+) -> Option<FontFamily> {
+    let book = world.book();
+    let families = TextElem::font_in(styles).0;
+    if families
+        .iter()
+        .any(|family| book.select_family(family.as_str()).next().is_some())
+    {
+        return None;
+    }
+    families.into_iter().next()
+}
+
 /// Collect the tags of the OpenType features to apply.
-fn tags(styles: StyleChain) -> Vec<Feature> {
+fn tags(styles: StyleChain) -> Vec<(Tag, u32)> {
     let mut tags = vec![];
     let mut feat = |tag, value| {
-        tags.push(Feature::new(Tag::from_bytes(tag), value, ..));
+        tags.push((Tag::from_bytes(tag), value));
     };
 
     // Features that are on by default in Harfbuzz are only added if disabled.
@@ -899,20 +1046,38 @@ fn tags(styles: StyleChain) -> Vec<Feature> {
         feat(b"frac", 1);
     }
 
-    for (tag, value) in TextElem::features_in(styles).0 {
-        tags.push(Feature::new(tag, value, ..))
-    }
+    tags.extend(TextElem::features_in(styles).0);
 
     tags
 }
 
 /// Process the language and and region of a style chain into a
 /// rustybuzz-compatible BCP 47 language.
-fn language(styles: StyleChain) -> rustybuzz::Language {
+fn language(styles: StyleChain) -> EcoString {
     let mut bcp: EcoString = TextElem::lang_in(styles).as_str().into();
     if let Some(region) = TextElem::region_in(styles) {
         bcp.push('-');
         bcp.push_str(region.as_str());
     }
-    rustybuzz::Language::from_str(&bcp).unwrap()
+    bcp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_run_cached() {
+        let data = include_bytes!("../../../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(typst::util::Buffer::from_static(data), 0).unwrap();
+        let features = [(Tag::from_bytes(b"liga"), 0)];
+        let first = shape_run(&font, &features, "office", "en", true);
+        let second = shape_run(&font, &features, "office", "en", true);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.len(), 6);
+
+        // Different features must not be served from the cache.
+        let ligated = shape_run(&font, &[], "office", "en", true);
+        assert!(ligated.len() < first.len());
+    }
 }
@@ -45,7 +45,10 @@ pub struct SubElem {
 }
 
 impl Show for SubElem {
-    #[tracing::instrument(name = "SubElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "SubElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let body = self.body();
         let mut transformed = None;
@@ -108,7 +111,10 @@ pub struct SuperElem {
 }
 
 impl Show for SuperElem {
-    #[tracing::instrument(name = "SuperElem::show", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "SuperElem::show", skip_all)
+    )]
     fn show(&self, vt: &mut Vt, styles: StyleChain) -> SourceResult<Content> {
         let body = self.body();
         let mut transformed = None;
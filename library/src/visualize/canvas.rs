@@ -0,0 +1,577 @@
+use std::collections::HashMap;
+
+use super::{arrowhead, clip, direction, marker_size};
+use crate::layout::ParbreakElem;
+use crate::prelude::*;
+use crate::text::SpaceElem;
+
+/// A drawing area with its own coordinate system.
+///
+/// Inside of a canvas, you can place [nodes]($func/canvas.node) at coordinates
+/// and connect them with [edges]($func/canvas.edge) or draw free-form
+/// [walks]($func/canvas.walk). These functions are part of the canvas
+/// function's scope, so you can import them with
+/// `{import canvas: node, edge, walk}`. Coordinates are given as `{(x, y)}`
+/// arrays of numbers and measured in multiples of the canvas' `length`. The
+/// x-axis points to the right and the y-axis points upwards.
+///
+/// Instead of a coordinate, you can also use the name of a node to refer to
+/// its center or a name like `{"a.north"}` or `{"a.south-east"}` to refer to
+/// a point on its border. The canvas grows to fit everything drawn on it.
+///
+/// ## Example { #example }
+/// ```example
+/// #import canvas: node, edge, walk
+/// #canvas(length: 1.5cm)[
+///   #node((0, 0), [Start], name: "a")
+///   #node((2, 0), [End], name: "b", stroke: 0.5pt)
+///   #edge("a", "b", marks: "->")
+///   #walk("b.south", (0, -1), (-2, 0), (0, 0.8), stroke: blue)
+/// ]
+/// ```
+///
+/// Display: Canvas
+/// Category: visualize
+#[element(Layout)]
+#[scope(
+    scope.define("node", NodeElem::func());
+    scope.define("edge", EdgeElem::func());
+    scope.define("walk", WalkElem::func());
+    scope
+)]
+pub struct CanvasElem {
+    /// The length of one unit in the canvas' coordinate system.
+    #[resolve]
+    #[default(Abs::cm(1.0).into())]
+    pub length: Length,
+
+    /// The nodes, edges and walks to draw.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for CanvasElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "CanvasElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let unit = self.length(styles);
+        let body = self.body();
+        let mut canvas = Canvas { unit, nodes: vec![], names: HashMap::new() };
+
+        // Place the nodes first so that edges and walks can refer to all of
+        // them.
+        visit(&body, styles, &mut |child, styles| {
+            if let Some(node) = child.to::<NodeElem>() {
+                canvas.place(vt, styles, node)?;
+            } else if !child.is::<EdgeElem>()
+                && !child.is::<WalkElem>()
+                && !child.is::<SpaceElem>()
+                && !child.is::<ParbreakElem>()
+            {
+                bail!(child.span(), "canvas can only contain nodes, edges and walks");
+            }
+            Ok(())
+        })?;
+
+        let mut lines = vec![];
+        visit(&body, styles, &mut |child, styles| {
+            if let Some(edge) = child.to::<EdgeElem>() {
+                canvas.connect(styles, edge, &mut lines)?;
+            } else if let Some(walk) = child.to::<WalkElem>() {
+                let start = canvas.resolve(&walk.start()).at(walk.span())?;
+                let mut points = vec![start];
+                for step in walk.steps() {
+                    let last = points[points.len() - 1];
+                    points.push(last + Point::new(unit * step.x.0, unit * step.y.0));
+                }
+
+                let stroke = walk.stroke(styles).map(PartialStroke::unwrap_or_default);
+                lines.push(Polyline {
+                    points,
+                    closed: walk.close(styles),
+                    fill: walk.fill(styles),
+                    stroke,
+                    span: walk.span(),
+                });
+            }
+            Ok(())
+        })?;
+
+        // Find the bounding box of everything on the canvas.
+        let mut min = Point::splat(Abs::inf());
+        let mut max = Point::splat(-Abs::inf());
+        let mut extend = |p: Point| {
+            min = min.min(p);
+            max = max.max(p);
+        };
+        for node in &canvas.nodes {
+            extend(node.center - node.half.to_point());
+            extend(node.center + node.half.to_point());
+        }
+        for line in &lines {
+            let pad = line.stroke.as_ref().map_or(Abs::zero(), |s| s.thickness / 2.0);
+            for &p in &line.points {
+                extend(p - Point::splat(pad));
+                extend(p + Point::splat(pad));
+            }
+        }
+        if min.x > max.x {
+            return Ok(Fragment::frame(Frame::new(Size::zero())));
+        }
+
+        // Flip the y-axis and move the bounding box to the origin.
+        let map = |p: Point| Point::new(p.x - min.x, max.y - p.y);
+        let mut frame = Frame::new((max - min).to_size());
+        for line in lines {
+            let mut path = Path::new();
+            for (i, &p) in line.points.iter().enumerate() {
+                if i == 0 {
+                    path.move_to(map(p));
+                } else {
+                    path.line_to(map(p));
+                }
+            }
+            if line.closed {
+                path.close_path();
+            }
+
+            let shape = Shape {
+                geometry: Geometry::Path(path),
+                fill: line.fill,
+                stroke: line.stroke,
+            };
+            frame.push(Point::zero(), FrameItem::Shape(shape, line.span));
+        }
+
+        for node in canvas.nodes {
+            let corner = node.center + Point::new(-node.half.x, node.half.y);
+            let pos = map(corner);
+            if let Some(stroke) = node.stroke {
+                let shape = Geometry::Rect(node.half * 2.0).stroked(stroke);
+                frame.push(pos, FrameItem::Shape(shape, node.span));
+            }
+            let offset = node.half * 2.0 - node.frame.size();
+            frame.push_frame(pos + (offset / 2.0).to_point(), node.frame);
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// Content placed at a point of a [canvas]($func/canvas).
+///
+/// The content is centered on the point. If the node has a name, its center
+/// and the points on its border can be used in place of coordinates in the
+/// same canvas.
+///
+/// ## Example { #example }
+/// ```example
+/// #canvas[
+///   #canvas.node((0, 0), $x$, name: "x")
+///   #canvas.node("x.east", $y$, stroke: red)
+/// ]
+/// ```
+///
+/// Display: Node
+/// Category: visualize
+#[element(Show)]
+pub struct NodeElem {
+    /// Where to place the node: A coordinate or an anchor of a previously
+    /// placed node.
+    #[required]
+    pub pos: CanvasPoint,
+
+    /// The content of the node.
+    #[required]
+    pub body: Content,
+
+    /// The name with which other elements can refer to the node.
+    pub name: Option<EcoString>,
+
+    /// How much space to leave between the content and the node's border.
+    #[resolve]
+    #[default(Em::new(0.3).into())]
+    pub inset: Length,
+
+    /// How to stroke the node's border. Defaults to no border.
+    #[resolve]
+    pub stroke: Option<PartialStroke>,
+}
+
+impl Show for NodeElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "NodeElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        bail!(self.span(), "nodes can only be used inside of a canvas");
+    }
+}
+
+/// A line between two points of a [canvas]($func/canvas).
+///
+/// If an end of the edge is the name of a node, the edge starts or ends at
+/// the node's border instead of its center.
+///
+/// ## Example { #example }
+/// ```example
+/// #import canvas: node, edge
+/// #canvas[
+///   #node((0, 0), [A], name: "a")
+///   #node((2, 1), [B], name: "b")
+///   #edge("a", "b", marks: "<->")
+///   #edge("a", (2, 0), stroke: (dash: "dashed"))
+/// ]
+/// ```
+///
+/// Display: Edge
+/// Category: visualize
+#[element(Show)]
+pub struct EdgeElem {
+    /// Where the edge starts.
+    #[required]
+    pub from: CanvasPoint,
+
+    /// Where the edge ends.
+    #[required]
+    pub to: CanvasPoint,
+
+    /// Where to draw arrowheads: One of `{"-"}`, `{"->"}`, `{"<-"}` and
+    /// `{"<->"}`.
+    #[default(EdgeMarks::None)]
+    pub marks: EdgeMarks,
+
+    /// How to stroke the edge. See the
+    /// [line's documentation]($func/line.stroke) for more details.
+    #[resolve]
+    #[fold]
+    pub stroke: PartialStroke,
+}
+
+impl Show for EdgeElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "EdgeElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        bail!(self.span(), "edges can only be used inside of a canvas");
+    }
+}
+
+/// A path through a [canvas]($func/canvas), made of relative steps.
+///
+/// ## Example { #example }
+/// ```example
+/// #canvas[
+///   #canvas.walk(
+///     (0, 0), (1, 0), (0, 1), (-1, 0),
+///     close: true,
+///     fill: aqua,
+///   )
+/// ]
+/// ```
+///
+/// Display: Walk
+/// Category: visualize
+#[element(Show)]
+pub struct WalkElem {
+    /// Where the walk starts.
+    #[required]
+    pub start: CanvasPoint,
+
+    /// The steps of the walk. Each step is an `{(x, y)}` offset from the end of
+    /// the previous step.
+    #[variadic]
+    pub steps: Vec<CanvasCoord>,
+
+    /// Whether to close the walk with a line back to its start.
+    #[default(false)]
+    pub close: bool,
+
+    /// How to fill the walk's area. Fills the area even if the walk is not
+    /// closed.
+    pub fill: Option<Paint>,
+
+    /// How to stroke the walk. See the
+    /// [line's documentation]($func/line.stroke) for more details.
+    #[resolve]
+    #[default(Some(PartialStroke::default()))]
+    pub stroke: Option<PartialStroke>,
+}
+
+impl Show for WalkElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "WalkElem::show", skip_all)
+    )]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        bail!(self.span(), "walks can only be used inside of a canvas");
+    }
+}
+
+/// A point on a canvas.
+#[derive(Debug, Clone, Hash)]
+pub enum CanvasPoint {
+    /// A coordinate in canvas units.
+    Coord(CanvasCoord),
+    /// The name of a node, optionally followed by a dot and the name of a
+    /// point on its border.
+    Anchor(EcoString),
+}
+
+cast! {
+    CanvasPoint,
+    self => match self {
+        Self::Coord(coord) => coord.into_value(),
+        Self::Anchor(anchor) => anchor.into_value(),
+    },
+    coord: CanvasCoord => Self::Coord(coord),
+    anchor: EcoString => Self::Anchor(anchor),
+}
+
+/// A coordinate or offset in canvas units.
+#[derive(Debug, Copy, Clone, Hash)]
+pub struct CanvasCoord {
+    /// The horizontal component.
+    pub x: Scalar,
+    /// The vertical component, pointing upwards.
+    pub y: Scalar,
+}
+
+cast! {
+    CanvasCoord,
+    self => array![self.x.0, self.y.0].into_value(),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Self { x: Scalar(a.cast()?), y: Scalar(b.cast()?) },
+            _ => bail!("coordinate array must contain exactly two entries"),
+        }
+    },
+}
+
+/// Where to draw the arrowheads of an edge.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EdgeMarks {
+    /// No arrowheads.
+    None,
+    /// An arrowhead at the start.
+    Start,
+    /// An arrowhead at the end.
+    End,
+    /// Arrowheads at both ends.
+    Both,
+}
+
+cast! {
+    EdgeMarks,
+    self => match self {
+        Self::None => "-",
+        Self::Start => "<-",
+        Self::End => "->",
+        Self::Both => "<->",
+    }.into_value(),
+    "-" => Self::None,
+    "<-" => Self::Start,
+    "->" => Self::End,
+    "<->" => Self::Both,
+}
+
+/// The state of a canvas during layout.
+struct Canvas {
+    /// The length of one unit.
+    unit: Abs,
+    /// The placed nodes.
+    nodes: Vec<PlacedNode>,
+    /// The indices of the named nodes.
+    names: HashMap<EcoString, usize>,
+}
+
+/// A node with its final position. Positions on the canvas have an upwards
+/// pointing y-axis.
+struct PlacedNode {
+    center: Point,
+    half: Size,
+    frame: Frame,
+    stroke: Option<Stroke>,
+    span: Span,
+}
+
+/// A straight-edged path on the canvas.
+struct Polyline {
+    points: Vec<Point>,
+    closed: bool,
+    fill: Option<Paint>,
+    stroke: Option<Stroke>,
+    span: Span,
+}
+
+impl Canvas {
+    /// Lay out a node and place it on the canvas.
+    fn place(
+        &mut self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        node: &NodeElem,
+    ) -> SourceResult<()> {
+        let center = self.resolve(&node.pos()).at(node.span())?;
+        let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+        let frame = node.body().layout(vt, styles, pod)?.into_frame();
+        let inset = node.inset(styles);
+        let half = frame.size() / 2.0 + Size::splat(inset);
+
+        if let Some(name) = node.name(styles) {
+            self.names.insert(name, self.nodes.len());
+        }
+
+        self.nodes.push(PlacedNode {
+            center,
+            half,
+            frame,
+            stroke: node.stroke(styles).map(PartialStroke::unwrap_or_default),
+            span: node.span(),
+        });
+
+        Ok(())
+    }
+
+    /// Add the line and arrowheads of an edge.
+    fn connect(
+        &self,
+        styles: StyleChain,
+        edge: &EdgeElem,
+        lines: &mut Vec<Polyline>,
+    ) -> SourceResult<()> {
+        let from = edge.from();
+        let to = edge.to();
+        let mut start = self.resolve(&from).at(edge.span())?;
+        let mut end = self.resolve(&to).at(edge.span())?;
+
+        // Stop at the borders of nodes.
+        let (a, b) = (start, end);
+        if let Some(node) = self.node(&from) {
+            start = clip(node.center, node.half, b);
+        }
+        if let Some(node) = self.node(&to) {
+            end = clip(node.center, node.half, a);
+        }
+
+        let stroke = edge.stroke(styles).unwrap_or_default();
+        let marks = edge.marks(styles);
+        let span = edge.span();
+        let mut points = vec![start, end];
+        if matches!(marks, EdgeMarks::Start | EdgeMarks::Both) {
+            if let Some((head, base)) = arrow(start, end, &stroke, span) {
+                points[0] = base;
+                lines.push(head);
+            }
+        }
+        if matches!(marks, EdgeMarks::End | EdgeMarks::Both) {
+            if let Some((head, base)) = arrow(end, start, &stroke, span) {
+                points[1] = base;
+                lines.push(head);
+            }
+        }
+
+        lines.push(Polyline {
+            points,
+            closed: false,
+            fill: None,
+            stroke: Some(stroke),
+            span,
+        });
+
+        Ok(())
+    }
+
+    /// Resolve a point on the canvas.
+    fn resolve(&self, point: &CanvasPoint) -> StrResult<Point> {
+        let anchor = match point {
+            CanvasPoint::Coord(coord) => {
+                return Ok(Point::new(self.unit * coord.x.0, self.unit * coord.y.0));
+            }
+            CanvasPoint::Anchor(anchor) => anchor,
+        };
+
+        let (name, side) = match anchor.rsplit_once('.') {
+            Some((name, side)) if !self.names.contains_key(anchor) => (name, side),
+            _ => (anchor.as_str(), "center"),
+        };
+
+        let Some(&index) = self.names.get(name) else {
+            bail!("canvas contains no node named `{name}`");
+        };
+
+        let node = &self.nodes[index];
+        let (x, y) = match side {
+            "center" => (0.0, 0.0),
+            "north" => (0.0, 1.0),
+            "south" => (0.0, -1.0),
+            "east" => (1.0, 0.0),
+            "west" => (-1.0, 0.0),
+            "north-east" => (1.0, 1.0),
+            "north-west" => (-1.0, 1.0),
+            "south-east" => (1.0, -1.0),
+            "south-west" => (-1.0, -1.0),
+            _ => bail!("unknown anchor `{side}`"),
+        };
+
+        Ok(node.center + Point::new(node.half.x * x, node.half.y * y))
+    }
+
+    /// The node a point refers to if it is the plain name of a node.
+    fn node(&self, point: &CanvasPoint) -> Option<&PlacedNode> {
+        match point {
+            CanvasPoint::Anchor(name) => self.names.get(name).map(|&i| &self.nodes[i]),
+            CanvasPoint::Coord(_) => None,
+        }
+    }
+}
+
+/// Call a function for every child of canvas content, with the styles that
+/// apply to it.
+fn visit(
+    content: &Content,
+    styles: StyleChain,
+    f: &mut dyn FnMut(&Content, StyleChain) -> SourceResult<()>,
+) -> SourceResult<()> {
+    if let Some(children) = content.to_sequence() {
+        for child in children {
+            visit(child, styles, f)?;
+        }
+    } else if let Some((child, map)) = content.to_styled() {
+        visit(child, styles.chain(map), f)?;
+    } else {
+        f(content, styles)?;
+    }
+    Ok(())
+}
+
+/// Create an arrowhead pointing at `tip`, coming from the direction of
+/// `from`. Also returns where the line should end so that it does not poke
+/// through the tip.
+fn arrow(
+    tip: Point,
+    from: Point,
+    stroke: &Stroke,
+    span: Span,
+) -> Option<(Polyline, Point)> {
+    let dir = direction(tip - from)?;
+    let size = marker_size(stroke);
+    let head = Polyline {
+        points: arrowhead(tip, dir, size).to_vec(),
+        closed: true,
+        fill: Some(stroke.paint.clone()),
+        stroke: None,
+        span,
+    };
+
+    Some((head, tip - dir * size.to_raw()))
+}
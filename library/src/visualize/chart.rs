@@ -0,0 +1,641 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use crate::prelude::*;
+use crate::text::TextElem;
+
+/// A bar chart.
+///
+/// Draws one bar per data point together with a value axis, the labels of
+/// the data points and, optionally, a legend.
+///
+/// ## Example { #example }
+/// ```example
+/// #bar-chart((
+///   ("Q1", 12),
+///   ("Q2", 18),
+///   ("Q3", 9.5),
+///   ("Q4", 21),
+/// ))
+/// ```
+///
+/// Display: Bar Chart
+/// Category: visualize
+#[element(Layout)]
+pub struct BarChartElem {
+    /// The data to plot.
+    ///
+    /// Either an array of `(label, value)` pairs or a dictionary mapping from
+    /// labels to values.
+    #[required]
+    pub data: ChartData,
+
+    /// The width of the chart, including its axis and labels.
+    #[resolve]
+    #[default(Abs::cm(8.0).into())]
+    pub width: Length,
+
+    /// The height of the chart, including its labels.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Length,
+
+    /// How to fill the bars.
+    #[default(Color::BLUE.into())]
+    pub fill: Paint,
+
+    /// How much of the space available for each bar it takes up.
+    #[default(Ratio::new(0.6))]
+    pub bar_width: Ratio,
+
+    /// A name for the plotted data. If set, it is shown in a legend above the
+    /// chart.
+    ///
+    /// ```example
+    /// #bar-chart((a: 3, b: 5), legend: [Revenue])
+    /// ```
+    pub legend: Option<Content>,
+}
+
+impl Layout for BarChartElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "BarChartElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let data = self.data();
+        let size = Size::new(self.width(styles), self.height(styles));
+        let fill = self.fill(styles);
+        let legend = self.legend(styles).map(|label| (label, fill.clone()));
+        let (mut frame, plot) =
+            layout_axes(vt, styles, &data, legend, size, self.span())?;
+
+        let width = plot.slot() * self.bar_width(styles).get();
+        let zero = plot.y(0.0);
+        for (i, &(_, Scalar(value))) in data.0.iter().enumerate() {
+            let y = plot.y(value);
+            let pos = Point::new(plot.x(i) - width / 2.0, y.min(zero));
+            let size = Size::new(width, (y - zero).abs());
+            let shape = Geometry::Rect(size).filled(fill.clone());
+            frame.prepend(pos, FrameItem::Shape(shape, self.span()));
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A line chart.
+///
+/// Connects the data points with a line and draws a value axis together with
+/// the labels of the data points and, optionally, a legend.
+///
+/// ## Example { #example }
+/// ```example
+/// #line-chart(
+///   (Mon: 3, Tue: 5, Wed: 4, Thu: 8),
+///   stroke: 2pt + eastern,
+/// )
+/// ```
+///
+/// Display: Line Chart
+/// Category: visualize
+#[element(Layout)]
+pub struct LineChartElem {
+    /// The data to plot.
+    ///
+    /// Either an array of `(label, value)` pairs or a dictionary mapping from
+    /// labels to values.
+    #[required]
+    pub data: ChartData,
+
+    /// The width of the chart, including its axis and labels.
+    #[resolve]
+    #[default(Abs::cm(8.0).into())]
+    pub width: Length,
+
+    /// The height of the chart, including its labels.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Length,
+
+    /// How to stroke the line. Defaults to a blue line with a thickness of
+    /// `{1.5pt}`. See the [line's documentation]($func/line.stroke) for more
+    /// details.
+    #[resolve]
+    #[fold]
+    pub stroke: PartialStroke,
+
+    /// Whether to mark the data points with dots.
+    #[default(true)]
+    pub marks: bool,
+
+    /// A name for the plotted data. If set, it is shown in a legend above the
+    /// chart.
+    ///
+    /// ```example
+    /// #line-chart((a: 3, b: 5), legend: [Growth])
+    /// ```
+    pub legend: Option<Content>,
+}
+
+impl Layout for LineChartElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LineChartElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let data = self.data();
+        let size = Size::new(self.width(styles), self.height(styles));
+        let stroke = self.stroke(styles).unwrap_or(Stroke {
+            paint: Color::BLUE.into(),
+            thickness: Abs::pt(1.5),
+            ..Stroke::default()
+        });
+
+        let legend = self.legend(styles).map(|label| (label, stroke.paint.clone()));
+        let (mut frame, plot) =
+            layout_axes(vt, styles, &data, legend, size, self.span())?;
+
+        let points: Vec<_> = data
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, Scalar(value)))| Point::new(plot.x(i), plot.y(value)))
+            .collect();
+
+        let mut path = Path::new();
+        for (i, &point) in points.iter().enumerate() {
+            if i == 0 {
+                path.move_to(point);
+            } else {
+                path.line_to(point);
+            }
+        }
+
+        let shape = Geometry::Path(path).stroked(stroke.clone());
+        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+
+        if self.marks(styles) {
+            let diameter = stroke.thickness * 3.0;
+            for point in points {
+                let shape =
+                    ellipse(Size::splat(diameter), Some(stroke.paint.clone()), None);
+                let pos = point - Point::splat(diameter / 2.0);
+                frame.push(pos, FrameItem::Shape(shape, self.span()));
+            }
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A pie chart.
+///
+/// Draws one slice per data point, sized by its share of the total, together
+/// with a legend.
+///
+/// ## Example { #example }
+/// ```example
+/// #pie-chart((
+///   Rent: 1200,
+///   Food: 450,
+///   Travel: 300,
+///   Other: 200,
+/// ))
+/// ```
+///
+/// Display: Pie Chart
+/// Category: visualize
+#[element(Layout)]
+pub struct PieChartElem {
+    /// The data to plot. All values must be positive.
+    ///
+    /// Either an array of `(label, value)` pairs or a dictionary mapping from
+    /// labels to values.
+    #[required]
+    pub data: ChartData,
+
+    /// The width of the chart, including its legend.
+    #[resolve]
+    #[default(Abs::cm(8.0).into())]
+    pub width: Length,
+
+    /// The height of the chart.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Length,
+
+    /// The fills of the slices. If there are more slices than fills, the
+    /// fills are repeated.
+    #[default(palette())]
+    pub fills: Vec<Paint>,
+
+    /// Whether to show a legend next to the chart.
+    #[default(true)]
+    pub legend: bool,
+}
+
+impl Layout for PieChartElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PieChartElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let data = self.data();
+        if data.0.iter().any(|&(_, Scalar(value))| value <= 0.0) {
+            bail!(self.span(), "pie chart values must be positive");
+        }
+
+        let mut fills = self.fills(styles);
+        if fills.is_empty() {
+            fills = palette();
+        }
+
+        let size = Size::new(self.width(styles), self.height(styles));
+        let mut frame = Frame::new(size);
+        let gap = Abs::pt(4.0);
+
+        // Lay out the legend to find out how much space is left for the pie.
+        let mut legend_width = Abs::zero();
+        if self.legend(styles) {
+            let labels = data
+                .0
+                .iter()
+                .map(|(label, _)| layout_label(vt, styles, label.clone()))
+                .collect::<SourceResult<Vec<_>>>()?;
+
+            let swatch = labels.iter().map(Frame::height).max().unwrap_or_default();
+            let label_width = labels.iter().map(Frame::width).max().unwrap_or_default();
+            legend_width = swatch + gap + label_width;
+
+            let total: Abs = labels.iter().map(|label| label.height() + gap).sum();
+            let mut y = (size.y - total + gap) / 2.0;
+            let x = size.x - legend_width;
+            for (i, label) in labels.into_iter().enumerate() {
+                let fill = fills[i % fills.len()].clone();
+                let height = label.height();
+                let pos = Point::new(x, y);
+                legend_entry(&mut frame, pos, swatch, gap, label, fill, self.span());
+                y += height + gap;
+            }
+        }
+
+        let radius =
+            ((size.x - legend_width - 2.0 * gap).min(size.y) / 2.0).max(Abs::zero());
+        let center = Point::new(radius, size.y / 2.0);
+        let total: f64 = data.0.iter().map(|&(_, Scalar(value))| value).sum();
+        let separator = Stroke { paint: Color::WHITE.into(), ..Stroke::default() };
+
+        // Start at the top and go clockwise.
+        let mut start = -FRAC_PI_2;
+        for (i, &(_, Scalar(value))) in data.0.iter().enumerate() {
+            let end = start + value / total * TAU;
+            let mut path = Path::new();
+            path.move_to(center);
+            arc(&mut path, center, radius, start, end);
+            path.close_path();
+
+            let shape = Shape {
+                geometry: Geometry::Path(path),
+                fill: Some(fills[i % fills.len()].clone()),
+                stroke: (data.0.len() > 1).then(|| separator.clone()),
+            };
+            frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+            start = end;
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// Labeled values to plot in a chart.
+#[derive(Debug, Clone, Hash)]
+pub struct ChartData(pub Vec<(Content, Scalar)>);
+
+cast! {
+    ChartData,
+    self => self.0
+        .into_iter()
+        .map(|(label, value)| array![label, value.0].into_value())
+        .collect::<Array>()
+        .into_value(),
+    array: Array => Self(array
+        .into_iter()
+        .map(|point| {
+            let mut iter = point.cast::<Array>()?.into_iter();
+            match (iter.next(), iter.next(), iter.next()) {
+                (Some(label), Some(value), None) => {
+                    Ok((label.cast()?, Scalar(value.cast()?)))
+                }
+                _ => bail!("data point must contain exactly two entries"),
+            }
+        })
+        .collect::<StrResult<_>>()?),
+    dict: Dict => Self(dict
+        .into_iter()
+        .map(|(key, value)| Ok((TextElem::packed(key), Scalar(value.cast()?))))
+        .collect::<StrResult<_>>()?),
+}
+
+/// The default fills of the slices of a pie chart.
+fn palette() -> Vec<Paint> {
+    [
+        Color::BLUE,
+        Color::ORANGE,
+        Color::GREEN,
+        Color::RED,
+        Color::PURPLE,
+        Color::AQUA,
+        Color::MAROON,
+        Color::OLIVE,
+    ]
+    .into_iter()
+    .map(Paint::from)
+    .collect()
+}
+
+/// The area of a chart in which the data points are plotted.
+struct Plot {
+    /// The top-left corner of the area.
+    pos: Point,
+    /// The size of the area.
+    size: Size,
+    /// The value at the bottom of the area.
+    lo: f64,
+    /// The value at the top of the area.
+    hi: f64,
+    /// The number of data points.
+    count: usize,
+}
+
+impl Plot {
+    /// The vertical position of a value.
+    fn y(&self, value: f64) -> Abs {
+        self.pos.y + self.size.y * ((self.hi - value) / (self.hi - self.lo))
+    }
+
+    /// The horizontal space available for each data point.
+    fn slot(&self) -> Abs {
+        self.size.x / self.count as f64
+    }
+
+    /// The horizontal position of the center of a data point.
+    fn x(&self, i: usize) -> Abs {
+        self.pos.x + self.slot() * (i as f64 + 0.5)
+    }
+}
+
+/// Lay out the value axis, the base line, the labels and the legend for a
+/// chart with one slot per data point.
+fn layout_axes(
+    vt: &mut Vt,
+    styles: StyleChain,
+    data: &ChartData,
+    legend: Option<(Content, Paint)>,
+    size: Size,
+    span: Span,
+) -> SourceResult<(Frame, Plot)> {
+    let lo = data.0.iter().map(|&(_, Scalar(v))| v).fold(0.0, f64::min);
+    let hi = data.0.iter().map(|&(_, Scalar(v))| v).fold(0.0, f64::max);
+    let (ticks, decimals) = ticks(lo, hi);
+
+    let tick_labels = ticks
+        .iter()
+        .map(|tick| {
+            let text = TextElem::packed(eco_format!("{tick:.decimals$}"));
+            layout_label(vt, styles, text)
+        })
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    let labels = data
+        .0
+        .iter()
+        .map(|(label, _)| layout_label(vt, styles, label.clone()))
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    let legend = match legend {
+        Some((label, fill)) => Some((layout_label(vt, styles, label)?, fill)),
+        None => None,
+    };
+
+    let gap = Abs::pt(4.0);
+    let left = tick_labels.iter().map(Frame::width).max().unwrap_or_default() + gap;
+    let mut top = tick_labels.last().map_or(Abs::zero(), |label| label.height() / 2.0);
+    if let Some((label, _)) = &legend {
+        top += label.height() + gap;
+    }
+    let bottom = labels.iter().map(Frame::height).max().unwrap_or_default() + gap;
+    let plot = Plot {
+        pos: Point::new(left, top),
+        size: Size::new(size.x - left, size.y - top - bottom).max(Size::zero()),
+        lo: ticks[0],
+        hi: ticks[ticks.len() - 1],
+        count: data.0.len().max(1),
+    };
+
+    let mut frame = Frame::new(size);
+    let stroke = Stroke { thickness: Abs::pt(0.5), ..Stroke::default() };
+    let line = |frame: &mut Frame, pos: Point, delta: Point| {
+        let shape = Geometry::Line(delta).stroked(stroke.clone());
+        frame.push(pos, FrameItem::Shape(shape, span));
+    };
+
+    // The value axis with its ticks.
+    line(&mut frame, plot.pos, Point::with_y(plot.size.y));
+    for (&tick, label) in ticks.iter().zip(tick_labels) {
+        let y = plot.y(tick);
+        line(&mut frame, Point::new(left - gap / 2.0, y), Point::with_x(gap / 2.0));
+        let pos = Point::new(left - gap - label.width(), y - label.height() / 2.0);
+        frame.push_frame(pos, label);
+    }
+
+    // The base line at zero.
+    line(&mut frame, Point::new(left, plot.y(0.0)), Point::with_x(plot.size.x));
+
+    // The labels of the data points.
+    for (i, label) in labels.into_iter().enumerate() {
+        let pos =
+            Point::new(plot.x(i) - label.width() / 2.0, plot.pos.y + plot.size.y + gap);
+        frame.push_frame(pos, label);
+    }
+
+    // The legend in the top right corner.
+    if let Some((label, fill)) = legend {
+        let swatch = label.height();
+        let pos = Point::with_x(size.x - swatch - gap - label.width());
+        legend_entry(&mut frame, pos, swatch, gap, label, fill, span);
+    }
+
+    Ok((frame, plot))
+}
+
+/// Add a legend entry with a square swatch in front of its label to a frame.
+fn legend_entry(
+    frame: &mut Frame,
+    pos: Point,
+    swatch: Abs,
+    gap: Abs,
+    label: Frame,
+    fill: Paint,
+    span: Span,
+) {
+    let shape = Geometry::Rect(Size::splat(swatch * 0.8)).filled(fill);
+    frame.push(pos + Point::splat(swatch * 0.1), FrameItem::Shape(shape, span));
+    frame.push_frame(pos + Point::with_x(swatch + gap), label);
+}
+
+/// Find evenly spaced, round tick values that cover a range and the number of
+/// decimals needed to display them.
+fn ticks(lo: f64, hi: f64) -> (Vec<f64>, usize) {
+    let range = if hi > lo { hi - lo } else { 1.0 };
+    let rough = range / 5.0;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = match rough / magnitude {
+        r if r <= 1.0 => 1.0,
+        r if r <= 2.0 => 2.0,
+        r if r <= 5.0 => 5.0,
+        _ => 10.0,
+    } * magnitude;
+
+    let first = (lo / step).floor() as i64;
+    let last = ((hi / step).ceil() as i64).max(first + 1);
+    let ticks = (first..=last).map(|i| i as f64 * step).collect();
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    (ticks, decimals)
+}
+
+/// Lay out a label at its natural size.
+fn layout_label(vt: &mut Vt, styles: StyleChain, label: Content) -> SourceResult<Frame> {
+    let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+    Ok(label.layout(vt, styles, pod)?.into_frame())
+}
+
+/// Add a circular arc around a center from one angle to another to a path,
+/// first drawing a line to the arc's start.
+fn arc(path: &mut Path, center: Point, radius: Abs, start: f64, end: f64) {
+    let point =
+        |angle: f64| center + Point::new(radius * angle.cos(), radius * angle.sin());
+
+    // Approximate the arc with one cubic bezier curve per quarter circle.
+    path.line_to(point(start));
+    let segments = ((end - start) / FRAC_PI_2).ceil().max(1.0) as usize;
+    let delta = (end - start) / segments as f64;
+    let k = 4.0 / 3.0 * (delta / 4.0).tan() * radius;
+    for i in 0..segments {
+        let a = start + i as f64 * delta;
+        let b = a + delta;
+        let c1 = point(a) + Point::new(-k * a.sin(), k * a.cos());
+        let c2 = point(b) - Point::new(-k * b.sin(), k * b.cos());
+        path.cubic_to(c1, c2, point(b));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    /// The shapes and texts on a page.
+    fn collect(page: &Frame) -> (Vec<(Point, &Shape)>, Vec<&str>) {
+        let (mut shapes, mut texts) = (vec![], vec![]);
+        for (pos, item) in items(page) {
+            match item {
+                FrameItem::Shape(shape, _) => shapes.push((pos, shape)),
+                FrameItem::Text(text) => texts.push(text.text.as_str()),
+                _ => {}
+            }
+        }
+        (shapes, texts)
+    }
+
+    /// The rectangles among some shapes.
+    fn rects(shapes: &[(Point, &Shape)]) -> Vec<(Point, Size, Option<Paint>)> {
+        shapes
+            .iter()
+            .filter_map(|(pos, shape)| match shape.geometry {
+                Geometry::Rect(size) => Some((*pos, size, shape.fill.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_charts() {
+        let document = compile(
+            "#bar-chart((a: 1, b: 2, c: 4), legend: [Revenue])\n\
+             #pagebreak()\n\
+             #line-chart((a: 1, b: 3, c: 2), stroke: red, legend: [Trend])\n\
+             #pagebreak()\n\
+             #pie-chart((a: 1, b: 2, c: 3))",
+        );
+        let blue = Some(Paint::from(Color::BLUE));
+        let red = Some(Paint::from(Color::RED));
+
+        // The bars stand on a common base line with heights proportional to
+        // their values and the legend's swatch lies above all of them.
+        let (shapes, texts) = collect(&document.pages[0]);
+        let mut found = rects(&shapes);
+        found.sort_by(|a, b| a.0.y.partial_cmp(&b.0.y).unwrap());
+        assert_eq!(found.len(), 4);
+        assert!(found.iter().all(|(_, _, fill)| *fill == blue));
+        let (swatch, bars) = (&found[0], &found[1..]);
+        assert!(bars.iter().all(|(pos, _, _)| swatch.0.y + swatch.1.y < pos.y));
+        let bottom = bars[0].0.y + bars[0].1.y;
+        assert!(bars
+            .iter()
+            .all(|(pos, size, _)| (pos.y + size.y - bottom).abs() < Abs::pt(0.01)));
+        let heights: Vec<_> = bars.iter().map(|(_, size, _)| size.y).collect();
+        assert!((heights[0] / heights[1] - 2.0).abs() < 1e-6);
+        assert!((heights[0] / heights[2] - 4.0).abs() < 1e-6);
+        assert!(texts.contains(&"Revenue"));
+
+        // The line chart draws one line, one mark per data point and a
+        // legend in the line's color.
+        let (shapes, texts) = collect(&document.pages[1]);
+        let found = rects(&shapes);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].2, red);
+        let stroked = shapes
+            .iter()
+            .filter(|(_, shape)| matches!(shape.geometry, Geometry::Path(_)))
+            .filter(|(_, shape)| {
+                shape.stroke.as_ref().map(|stroke| &stroke.paint) == red.as_ref()
+            })
+            .count();
+        let marks = shapes.iter().filter(|(_, shape)| shape.fill == red).count();
+        assert_eq!(stroked, 1);
+        assert_eq!(marks, 3 + 1);
+        assert!(texts.contains(&"Trend"));
+
+        // The pie chart has one slice and one legend entry per data point.
+        let (shapes, texts) = collect(&document.pages[2]);
+        let slices: Vec<_> = shapes
+            .iter()
+            .filter(|(_, shape)| matches!(shape.geometry, Geometry::Path(_)))
+            .map(|(_, shape)| shape.fill.clone())
+            .collect();
+        let swatches: Vec<_> =
+            rects(&shapes).into_iter().map(|(_, _, fill)| fill).collect();
+        let palette: Vec<_> = [Color::BLUE, Color::ORANGE, Color::GREEN]
+            .into_iter()
+            .map(|color| Some(Paint::from(color)))
+            .collect();
+        assert_eq!(slices, palette);
+        assert_eq!(swatches, palette);
+        assert_eq!(texts, ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,909 @@
+use crate::prelude::*;
+
+/// A QR code.
+///
+/// The code is drawn from shapes directly, so it stays crisp at any zoom
+/// level and does not need an external image. The text is encoded as UTF-8
+/// in the smallest QR code version that fits it.
+///
+/// ## Example { #example }
+/// ```example
+/// #qrcode("https://typst.app", size: 2cm)
+/// #qrcode(
+///   "Ticket #1234",
+///   error-correction: "high",
+///   fill: eastern,
+/// )
+/// ```
+///
+/// Display: QR Code
+/// Category: visualize
+#[element(Layout)]
+pub struct QrcodeElem {
+    /// The text to encode.
+    #[required]
+    pub data: EcoString,
+
+    /// How much of the code can be damaged while it still remains readable.
+    /// A higher level makes the code larger.
+    #[default(QrLevel::Medium)]
+    pub error_correction: QrLevel,
+
+    /// The width and height of the code, including its quiet zone.
+    #[resolve]
+    #[default(Abs::cm(3.0).into())]
+    pub size: Length,
+
+    /// The width of the empty border around the code, in modules.
+    ///
+    /// Scanners need a quiet zone of four modules to reliably detect a code,
+    /// but a smaller one can be used if the surroundings are empty anyway.
+    #[default(4)]
+    pub quiet_zone: usize,
+
+    /// How to fill the dark modules of the code.
+    #[default(Color::BLACK.into())]
+    pub fill: Paint,
+}
+
+impl Layout for QrcodeElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "QrcodeElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        _: &mut Vt,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let data = self.data();
+        let Some(qr) = QrCode::encode(data.as_bytes(), self.error_correction(styles))
+        else {
+            bail!(self.span(), "text is too long for a QR code");
+        };
+
+        let quiet = self.quiet_zone(styles);
+        let size = self.size(styles);
+        let module = size / (qr.size + 2 * quiet) as f64;
+        let rows: Vec<_> = (0..qr.size)
+            .map(|y| (0..qr.size).map(|x| qr.get(x, y)).collect::<Vec<_>>())
+            .collect();
+
+        let fill = self.fill(styles);
+        let mut frame = Frame::new(Size::splat(size));
+        for (y, row) in rows.iter().enumerate() {
+            let pos = Point::new(module * quiet as f64, module * (quiet + y) as f64);
+            draw_bars(&mut frame, pos, row, Size::splat(module), &fill, self.span());
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// How much redundancy a QR code contains.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum QrLevel {
+    /// About 7% of the code can be restored.
+    Low,
+    /// About 15% of the code can be restored.
+    Medium,
+    /// About 25% of the code can be restored.
+    Quartile,
+    /// About 30% of the code can be restored.
+    High,
+}
+
+impl QrLevel {
+    /// The index of the level in the error correction tables.
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The level's two bits in the format information.
+    fn format_bits(self) -> u32 {
+        match self {
+            Self::Low => 1,
+            Self::Medium => 0,
+            Self::Quartile => 3,
+            Self::High => 2,
+        }
+    }
+}
+
+/// A one-dimensional barcode.
+///
+/// Like [QR codes]($func/qrcode), barcodes are drawn from shapes directly.
+///
+/// ## Example { #example }
+/// ```example
+/// #barcode("INV-2023-0042")
+/// #barcode("4006381333931", format: "ean13")
+/// ```
+///
+/// Display: Barcode
+/// Category: visualize
+#[element(Layout)]
+pub struct BarcodeElem {
+    /// The text to encode.
+    #[required]
+    pub data: EcoString,
+
+    /// The barcode symbology to use.
+    #[default(BarcodeFormat::Code128)]
+    pub format: BarcodeFormat,
+
+    /// The width of the narrowest bar.
+    #[resolve]
+    #[default(Abs::pt(1.0).into())]
+    pub module: Length,
+
+    /// The height of the bars.
+    #[resolve]
+    #[default(Abs::cm(1.5).into())]
+    pub height: Length,
+
+    /// The width of the empty space left and right of the bars, in modules.
+    #[default(10)]
+    pub quiet_zone: usize,
+
+    /// How to fill the bars.
+    #[default(Color::BLACK.into())]
+    pub fill: Paint,
+}
+
+impl Layout for BarcodeElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "BarcodeElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        _: &mut Vt,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let data = self.data();
+        let bars = match self.format(styles) {
+            BarcodeFormat::Code128 => code128(&data),
+            BarcodeFormat::Ean13 => ean13(&data),
+        }
+        .at(self.span())?;
+
+        let quiet = self.quiet_zone(styles);
+        let module = self.module(styles);
+        let height = self.height(styles);
+        let width = module * (bars.len() + 2 * quiet) as f64;
+
+        let mut frame = Frame::new(Size::new(width, height));
+        let pos = Point::with_x(module * quiet as f64);
+        let bar = Size::new(module, height);
+        draw_bars(&mut frame, pos, &bars, bar, &self.fill(styles), self.span());
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A barcode symbology.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BarcodeFormat {
+    /// Code 128, which can encode all printable ASCII characters.
+    Code128,
+    /// EAN-13, the product code with 13 digits. If only 12 digits are given,
+    /// the check digit is computed.
+    Ean13,
+}
+
+/// Draw a row of modules, merging adjacent dark modules into one rectangle.
+fn draw_bars(
+    frame: &mut Frame,
+    pos: Point,
+    modules: &[bool],
+    module: Size,
+    fill: &Paint,
+    span: Span,
+) {
+    let mut x = 0;
+    while x < modules.len() {
+        if !modules[x] {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < modules.len() && modules[x] {
+            x += 1;
+        }
+
+        let size = Size::new(module.x * (x - start) as f64, module.y);
+        let shape = Geometry::Rect(size).filled(fill.clone());
+        let pos = pos + Point::with_x(module.x * start as f64);
+        frame.push(pos, FrameItem::Shape(shape, span));
+    }
+}
+
+/// The bar widths of the Code 128 symbols, starting with a bar.
+const CODE128: [&str; 106] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312",
+    "132212", "221213", "221312", "231212", "112232", "122132", "122231", "113222",
+    "123122", "123221", "223211", "221132", "221231", "213212", "223112", "312131",
+    "311222", "321122", "321221", "312212", "322112", "322211", "212123", "212321",
+    "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121",
+    "313121", "211331", "231131", "213113", "213311", "213131", "311123", "311321",
+    "331121", "312113", "312311", "332111", "314111", "221411", "431111", "111224",
+    "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112",
+    "421211", "212141", "214121", "412121", "111143", "111341", "131141", "114113",
+    "114311", "411113", "411311", "113141", "114131", "311141", "411131", "211412",
+    "211214", "211232",
+];
+
+/// The Code 128 stop symbol.
+const CODE128_STOP: &str = "2331112";
+
+/// Encode text as a Code 128 barcode, using code set B.
+fn code128(data: &str) -> StrResult<Vec<bool>> {
+    const START_B: usize = 104;
+
+    let mut values = vec![START_B];
+    for c in data.chars() {
+        if !(' '..='~').contains(&c) {
+            bail!("code 128 barcodes can only contain printable ASCII characters");
+        }
+        values.push(c as usize - ' ' as usize);
+    }
+
+    let checksum =
+        values.iter().enumerate().map(|(i, &v)| i.max(1) * v).sum::<usize>() % 103;
+    values.push(checksum);
+
+    let mut bars = vec![];
+    let patterns = values.iter().map(|&v| CODE128[v]).chain([CODE128_STOP]);
+    for pattern in patterns {
+        for (i, width) in pattern.bytes().enumerate() {
+            let dark = i % 2 == 0;
+            bars.extend(std::iter::repeat(dark).take((width - b'0') as usize));
+        }
+    }
+
+    Ok(bars)
+}
+
+/// The EAN-13 left-hand digit patterns with odd parity.
+const EAN13_L: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111,
+    0b0111011, 0b0110111, 0b0001011,
+];
+
+/// Which of the six left-hand digits use even parity, depending on the first
+/// digit.
+const EAN13_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101,
+    0b010110, 0b011010,
+];
+
+/// Encode digits as an EAN-13 barcode.
+fn ean13(data: &str) -> StrResult<Vec<bool>> {
+    let mut digits: Vec<u8> = data.bytes().map(|b| b.wrapping_sub(b'0')).collect();
+    if !matches!(digits.len(), 12 | 13) || digits.iter().any(|&d| d > 9) {
+        bail!("EAN-13 barcodes must consist of 12 or 13 digits");
+    }
+
+    let sum: u32 = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| if i % 2 == 0 { d as u32 } else { 3 * d as u32 })
+        .sum();
+    let check = ((10 - sum % 10) % 10) as u8;
+    if digits.len() == 13 && digits[12] != check {
+        bail!("EAN-13 check digit is wrong (expected {check})");
+    }
+    digits.truncate(12);
+    digits.push(check);
+
+    let mut bars = vec![];
+    let mut push = |pattern: u8, len: usize| {
+        bars.extend((0..len).rev().map(|i| pattern >> i & 1 == 1));
+    };
+
+    push(0b101, 3);
+    let parity = EAN13_PARITY[digits[0] as usize];
+    for (i, &d) in digits[1..7].iter().enumerate() {
+        let pattern = EAN13_L[d as usize];
+        if parity >> (5 - i) & 1 == 1 {
+            // The even parity pattern is the mirrored complement.
+            push(reverse7(!pattern & 0x7F), 7);
+        } else {
+            push(pattern, 7);
+        }
+    }
+    push(0b01010, 5);
+    for &d in &digits[7..] {
+        push(!EAN13_L[d as usize] & 0x7F, 7);
+    }
+    push(0b101, 3);
+
+    Ok(bars)
+}
+
+/// Reverse the order of the lowest seven bits.
+fn reverse7(bits: u8) -> u8 {
+    bits.reverse_bits() >> 1
+}
+
+/// The number of error correction codewords per block, by level and version.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28,
+        28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26,
+        26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+        28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26,
+        30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+        30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26,
+        28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+        30,
+    ],
+];
+
+/// The number of error correction blocks, by level and version.
+const ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12,
+        12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18,
+        20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23,
+        25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34,
+        30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// A square grid of QR code modules.
+struct QrCode {
+    /// The number of modules per side.
+    size: usize,
+    /// Whether each module is dark, row by row.
+    modules: Vec<bool>,
+    /// Whether each module belongs to a function pattern and must not be
+    /// masked.
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode bytes in the smallest version that fits them.
+    fn encode(data: &[u8], level: QrLevel) -> Option<Self> {
+        let (version, capacity) = (1..=40).find_map(|version| {
+            let count_bits = if version < 10 { 8 } else { 16 };
+            let capacity = data_codewords(version, level) * 8;
+            let needed = 4 + count_bits + 8 * data.len();
+            (data.len() < 1 << count_bits && needed <= capacity)
+                .then_some((version, capacity))
+        })?;
+
+        // Assemble the data bits in byte mode.
+        let mut bits = Bits::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, if version < 10 { 8 } else { 16 });
+        for &byte in data {
+            bits.push(byte as u32, 8);
+        }
+
+        // Terminate and pad the data.
+        bits.push(0, (capacity - bits.len).min(4));
+        bits.push(0, (8 - bits.len % 8) % 8);
+        for &pad in [0xEC, 0x11].iter().cycle() {
+            if bits.len >= capacity {
+                break;
+            }
+            bits.push(pad, 8);
+        }
+
+        let codewords = add_error_correction(&bits.bytes, version, level);
+
+        let size = version * 4 + 17;
+        let mut qr = Self {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version, level);
+        qr.draw_codewords(&codewords);
+
+        // Choose the mask with the lowest penalty.
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                qr.apply_mask(mask);
+                qr.draw_format(level, mask);
+                let penalty = qr.penalty();
+                qr.apply_mask(mask);
+                penalty
+            })
+            .unwrap();
+        qr.apply_mask(mask);
+        qr.draw_format(level, mask);
+
+        Some(qr)
+    }
+
+    /// Whether the module at the given position is dark.
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// Set a module that belongs to a function pattern.
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    /// Draw the finder, alignment and timing patterns as well as placeholders
+    /// for the format and version information.
+    fn draw_function_patterns(&mut self, version: usize, level: QrLevel) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4_isize {
+                for dx in -4..=4_isize {
+                    let (xx, yy) = (x as isize + dx, y as isize + dy);
+                    if (0..size as isize).contains(&xx)
+                        && (0..size as isize).contains(&yy)
+                    {
+                        let dist = dx.abs().max(dy.abs());
+                        self.set_function(
+                            xx as usize,
+                            yy as usize,
+                            dist != 2 && dist != 4,
+                        );
+                    }
+                }
+            }
+        }
+
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // Skip the corners with finder patterns.
+                if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                    continue;
+                }
+                for dy in 0..5 {
+                    for dx in 0..5 {
+                        let dist = (dx as isize - 2).abs().max((dy as isize - 2).abs());
+                        self.set_function(x + dx - 2, y + dy - 2, dist != 1);
+                    }
+                }
+            }
+        }
+
+        self.draw_format(level, 0);
+
+        if version >= 7 {
+            let mut rem = version as u32;
+            for _ in 0..12 {
+                rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+            }
+            let bits = (version as u32) << 12 | rem;
+            for i in 0..18 {
+                let dark = bits >> i & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    /// Draw both copies of the format information.
+    fn draw_format(&mut self, level: QrLevel, mask: u32) {
+        let data = level.format_bits() << 3 | mask;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+        let bit = |i: usize| bits >> i & 1 == 1;
+
+        let size = self.size;
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Place the codewords in the zigzag pattern, skipping function modules.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            // Skip the vertical timing pattern.
+            if right == 6 {
+                right = 5;
+            }
+
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                for x in [right, right - 1] {
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] =
+                            codewords[i / 8] >> (7 - i % 8) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Invert the non-function modules selected by a mask pattern. Applying
+    /// the same mask twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let i = y * self.size + x;
+                self.modules[i] ^= invert && !self.function[i];
+            }
+        }
+    }
+
+    /// How hard the code is to scan, as defined by the standard's penalty
+    /// rules.
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let mut penalty = 0;
+
+        // Runs of modules with the same color and finder-like patterns in
+        // rows and columns.
+        const FINDER: [bool; 11] =
+            [true, false, true, true, true, false, true, false, false, false, false];
+        for transposed in [false, true] {
+            for a in 0..size {
+                let line: Vec<bool> = (0..size)
+                    .map(|b| if transposed { self.get(a, b) } else { self.get(b, a) })
+                    .collect();
+
+                let mut run = 1;
+                for b in 1..=size {
+                    if b < size && line[b] == line[b - 1] {
+                        run += 1;
+                        continue;
+                    }
+                    if run >= 5 {
+                        penalty += run - 2;
+                    }
+                    run = 1;
+                }
+
+                for window in line.windows(11) {
+                    if window == FINDER || window.iter().rev().eq(FINDER.iter()) {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+
+        // Blocks of two by two modules with the same color.
+        for y in 1..size {
+            for x in 1..size {
+                let color = self.get(x, y);
+                if color == self.get(x - 1, y)
+                    && color == self.get(x, y - 1)
+                    && color == self.get(x - 1, y - 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // Imbalance of dark and light modules.
+        let total = size * size;
+        let dark = self.modules.iter().filter(|&&dark| dark).count();
+        let deviation = (dark * 20).abs_diff(total * 10);
+        penalty += ((deviation + total - 1) / total).saturating_sub(1) * 10;
+
+        penalty
+    }
+}
+
+/// The number of raw data modules of a version, after removing all function
+/// patterns.
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let count = version / 7 + 2;
+        result -= (25 * count - 10) * count - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+/// The number of data codewords of a version and level.
+fn data_codewords(version: usize, level: QrLevel) -> usize {
+    let l = level.index();
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[l][version] as usize
+            * ERROR_CORRECTION_BLOCKS[l][version] as usize
+}
+
+/// The centers of the alignment patterns along each axis.
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return vec![];
+    }
+
+    let count = version / 7 + 2;
+    let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let size = version * 4 + 17;
+    let mut positions: Vec<_> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Split the data into blocks, append their error correction codewords and
+/// interleave them.
+fn add_error_correction(data: &[u8], version: usize, level: QrLevel) -> Vec<u8> {
+    let l = level.index();
+    let count = ERROR_CORRECTION_BLOCKS[l][version] as usize;
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[l][version] as usize;
+    let raw = raw_data_modules(version) / 8;
+    let short_count = count - raw % count;
+    let short_len = raw / count;
+
+    let divisor = reed_solomon_divisor(ecc_len);
+    let mut blocks = vec![];
+    let mut k = 0;
+    for i in 0..count {
+        let len = short_len - ecc_len + usize::from(i >= short_count);
+        let mut block = data[k..k + len].to_vec();
+        k += len;
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        if i < short_count {
+            block.push(0);
+        }
+        block.extend(ecc);
+        blocks.push(block);
+    }
+
+    let mut result = vec![];
+    for i in 0..blocks[0].len() {
+        for (j, block) in blocks.iter().enumerate() {
+            // Skip the padding of the short blocks.
+            if i != short_len - ecc_len || j >= short_count {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+/// The generator polynomial for Reed-Solomon codes of a degree.
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0; degree];
+    result[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+/// The Reed-Solomon error correction codewords for some data.
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (x, &y) in result.iter_mut().zip(divisor) {
+            *x ^= gf_mul(y, factor);
+        }
+    }
+    result
+}
+
+/// Multiply two elements of the Galois field GF(2^8) modulo the QR code
+/// polynomial.
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+/// A buffer of bits, filled from the most significant bit.
+#[derive(Default)]
+struct Bits {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Bits {
+    /// Append the lowest `count` bits of a value.
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            if self.len % 8 == 0 {
+                self.bytes.push(0);
+            }
+            if value >> i & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 1 << (7 - self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render modules as a string of ones and zeros.
+    fn bits(modules: &[bool]) -> String {
+        modules.iter().map(|&dark| if dark { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn test_ean13() {
+        // The examples from the GS1 specification's digit tables.
+        assert_eq!(
+            bits(&ean13("5901234123457").unwrap()),
+            "10100010110100111011001100100110111101001110101010110011011011001000010101110010011101000100101",
+        );
+        assert_eq!(
+            bits(&ean13("400638133393").unwrap()),
+            "10100011010100111010111101111010001001011001101010100001010000101000010111010010000101100110101",
+        );
+
+        // A leading 7 selects the parity pattern LGLGLG.
+        assert_eq!(
+            bits(&ean13("7501031311309").unwrap()),
+            "10101100010100111001100101001110111101011001101010100001011001101100110100001011100101110100101",
+        );
+
+        assert!(ean13("5901234123458").is_err());
+        assert!(ean13("59012341234").is_err());
+        assert!(ean13("59012341234a").is_err());
+    }
+
+    #[test]
+    fn test_code128() {
+        // Start B, the letter A, the check symbol B and the stop symbol.
+        assert_eq!(
+            bits(&code128("A").unwrap()),
+            ["11010010000", "10100011000", "10001011000", "1100011101011"].concat(),
+        );
+        assert!(code128("Ä").is_err());
+    }
+
+    #[test]
+    fn test_qrcode_error_correction() {
+        // "HELLO WORLD" in version 1-M, from the Thonky QR code tutorial.
+        let data =
+            [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17];
+        let ecc = [196, 35, 39, 119, 235, 215, 231, 226, 93, 23];
+        assert_eq!(
+            add_error_correction(&data, 1, QrLevel::Medium),
+            [&data[..], &ecc].concat()
+        );
+    }
+
+    #[test]
+    fn test_qrcode_tables() {
+        assert_eq!(data_codewords(1, QrLevel::Low), 19);
+        assert_eq!(data_codewords(1, QrLevel::High), 9);
+        assert_eq!(data_codewords(40, QrLevel::Low), 2956);
+        assert_eq!(data_codewords(40, QrLevel::High), 1276);
+        assert_eq!(alignment_positions(1), []);
+        assert_eq!(alignment_positions(7), [6, 22, 38]);
+        assert_eq!(alignment_positions(32), [6, 34, 60, 86, 112, 138]);
+        assert_eq!(alignment_positions(40), [6, 30, 58, 86, 114, 142, 170]);
+    }
+
+    #[test]
+    fn test_qrcode_format_and_version() {
+        // Read back the format bits next to the top left finder pattern.
+        let format = |level| {
+            let mut qr = QrCode::encode(b"typst", level).unwrap();
+            qr.draw_format(level, 0);
+            let mut positions: Vec<_> = (0..6).map(|i| (8, i)).collect();
+            positions.extend([(8, 7), (8, 8), (7, 8)]);
+            positions.extend((9..15).map(|i| (14 - i, 8)));
+            positions
+                .iter()
+                .rev()
+                .map(|&(x, y)| if qr.get(x, y) { '1' } else { '0' })
+                .collect::<String>()
+        };
+        assert_eq!(format(QrLevel::Low), "111011111000100");
+        assert_eq!(format(QrLevel::Medium), "101010000010010");
+
+        // Version 7 is the first with version information.
+        let mut qr = QrCode {
+            size: 45,
+            modules: vec![false; 45 * 45],
+            function: vec![false; 45 * 45],
+        };
+        qr.draw_function_patterns(7, QrLevel::Medium);
+        let version: String = (0..18)
+            .rev()
+            .map(|i| if qr.get(45 - 11 + i % 3, i / 3) { '1' } else { '0' })
+            .collect();
+        assert_eq!(version, "000111110010010100");
+    }
+
+    #[test]
+    fn test_qrcode_encode() {
+        let qr = QrCode::encode(b"https://typst.app", QrLevel::Medium).unwrap();
+        assert_eq!(qr.size, 25);
+
+        // The finder patterns have a dark ring, a light ring and a dark core.
+        for (x, y) in [(0, 0), (qr.size - 7, 0), (0, qr.size - 7)] {
+            assert!((0..7).all(|i| qr.get(x + i, y) && qr.get(x, y + i)));
+            assert!((1..6).all(|i| !qr.get(x + i, y + 1)));
+            assert!(qr.get(x + 3, y + 3));
+        }
+
+        // The dark module next to the bottom left finder.
+        assert!(qr.get(8, qr.size - 8));
+        assert!(QrCode::encode(&[b'a'; 3000], QrLevel::High).is_none());
+    }
+}
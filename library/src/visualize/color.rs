@@ -0,0 +1,43 @@
+//! Named colors and color constructors.
+
+use typst::eval::{Module, Scope};
+
+use crate::compute::{cmyk_func, luma_func, rgb_func};
+use crate::prelude::*;
+
+/// A module with the named colors and the color constructors.
+///
+/// The named colors and the `luma` and `rgb` constructors are also available
+/// in the global scope.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define("luma", luma_func());
+    scope.define("rgb", rgb_func());
+    scope.define("cmyk", cmyk_func());
+    scope.define("black", Color::BLACK);
+    scope.define("gray", Color::GRAY);
+    scope.define("silver", Color::SILVER);
+    scope.define("white", Color::WHITE);
+    scope.define("navy", Color::NAVY);
+    scope.define("blue", Color::BLUE);
+    scope.define("aqua", Color::AQUA);
+    scope.define("teal", Color::TEAL);
+    scope.define("eastern", Color::EASTERN);
+    scope.define("purple", Color::PURPLE);
+    scope.define("fuchsia", Color::FUCHSIA);
+    scope.define("maroon", Color::MAROON);
+    scope.define("red", Color::RED);
+    scope.define("orange", Color::ORANGE);
+    scope.define("yellow", Color::YELLOW);
+    scope.define("olive", Color::OLIVE);
+    scope.define("green", Color::GREEN);
+    scope.define("lime", Color::LIME);
+    Module::new("color").with_scope(scope)
+}
+
+/// Hook up the global names of definitions that moved into the color module.
+///
+/// They keep working for now, but warn about their use.
+pub(super) fn define_deprecated(global: &mut Scope) {
+    global.define_deprecated("cmyk", cmyk_func(), "use `color.cmyk` instead");
+}
@@ -0,0 +1,244 @@
+use super::{direction, Marker};
+use crate::prelude::*;
+
+/// An arrow between two labelled elements.
+///
+/// The connector is drawn after layout, between the final positions of the
+/// elements with the given labels. Both elements must be on the same page as
+/// the connector. The connector itself takes up no space, so it can be put
+/// anywhere on that page.
+///
+/// ## Example { #example }
+/// ```example
+/// #box(rect[Start]) <start>
+/// #h(1fr)
+/// #box(rect[End]) <end>
+/// #v(1cm)
+/// #box(rect[Detour]) <detour>
+///
+/// #connector(<start>, <end>)
+/// #connector(<start>, <detour>, route: "orthogonal")
+/// #connector(<detour>, <end>, route: "curved", stroke: blue)
+/// ```
+///
+/// Display: Connector
+/// Category: visualize
+#[element(Locatable, Layout, Behave)]
+pub struct ConnectorElem {
+    /// The label of the element to start at.
+    #[required]
+    pub from: Label,
+
+    /// The label of the element to end at.
+    #[required]
+    pub to: Label,
+
+    /// How to route the connector between the elements.
+    #[default(Route::Straight)]
+    pub route: Route,
+
+    /// How to stroke the connector. See the
+    /// [line's documentation]($func/line.stroke) for more details.
+    #[resolve]
+    #[fold]
+    pub stroke: PartialStroke,
+
+    /// The marker at the start of the connector. See the
+    /// [line's documentation]($func/line.start-mark) for the available
+    /// markers.
+    pub start_mark: Option<Marker>,
+
+    /// The marker at the end of the connector.
+    #[default(Some(Marker::Arrow))]
+    pub end_mark: Option<Marker>,
+}
+
+impl Layout for ConnectorElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ConnectorElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let shapes = vt.delayed(|vt| self.shapes(vt, styles));
+        let mut frame = Frame::new(Size::zero());
+        for (pos, shape) in shapes {
+            frame.push(pos, FrameItem::Shape(shape, self.span()));
+        }
+        Ok(Fragment::frame(frame))
+    }
+}
+
+impl Behave for ConnectorElem {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Ignorant
+    }
+}
+
+impl ConnectorElem {
+    /// Create the connector's shapes, relative to its own position.
+    fn shapes(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+    ) -> SourceResult<Vec<(Point, Shape)>> {
+        let introspector = vt.introspector;
+        let own = introspector.position(self.0.location().unwrap());
+        let find = |label: &Label| {
+            let elem = introspector.query_label(label).at(self.span())?;
+            let location = elem.location().unwrap();
+            let pos = introspector.position(location);
+            if pos.page != own.page {
+                bail!(self.span(), "connected element must be on the same page");
+            }
+            let half = introspector.size(location) / 2.0;
+            Ok((pos.point - own.point + half.to_point(), half))
+        };
+
+        let (a, a_half) = find(&self.from())?;
+        let (b, b_half) = find(&self.to())?;
+        let route = self.route(styles);
+        let mut points = route.points(a, a_half, b, b_half);
+
+        let stroke = self.stroke(styles).unwrap_or_default();
+        let mut shapes = vec![];
+        let n = points.len();
+        if let Some(marker) = self.start_mark(styles) {
+            if let Some(dir) = direction(points[0] - points[1]) {
+                shapes.push(marker.shape(points[0], dir, &stroke));
+                points[0] -= dir * marker.inset(&stroke).to_raw();
+            }
+        }
+        if let Some(marker) = self.end_mark(styles) {
+            if let Some(dir) = direction(points[n - 1] - points[n - 2]) {
+                shapes.push(marker.shape(points[n - 1], dir, &stroke));
+                points[n - 1] -= dir * marker.inset(&stroke).to_raw();
+            }
+        }
+
+        let mut path = Path::new();
+        path.move_to(points[0]);
+        if route == Route::Curved {
+            path.cubic_to(points[1], points[2], points[3]);
+        } else {
+            for &point in &points[1..] {
+                path.line_to(point);
+            }
+        }
+
+        shapes.insert(0, (Point::zero(), Geometry::Path(path).stroked(stroke)));
+        Ok(shapes)
+    }
+}
+
+/// How a connector is routed between two elements.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Route {
+    /// A straight line between the borders of the elements.
+    Straight,
+    /// Horizontal and vertical lines between the sides of the elements that
+    /// face each other.
+    Orthogonal,
+    /// A smooth curve between the sides of the elements that face each
+    /// other.
+    Curved,
+}
+
+impl Route {
+    /// The points of the route between two rectangles, given by their centers
+    /// and half sizes. A curved route is a cubic curve through its four
+    /// points.
+    fn points(self, a: Point, a_half: Size, b: Point, b_half: Size) -> Vec<Point> {
+        if self == Self::Straight {
+            return vec![clip(a, a_half, b), clip(b, b_half, a)];
+        }
+
+        let delta = b - a;
+        let horizontal = delta.x.abs() >= delta.y.abs();
+        let (start, end) = if horizontal {
+            let sign = delta.x.to_raw().signum();
+            (a + Point::with_x(a_half.x * sign), b - Point::with_x(b_half.x * sign))
+        } else {
+            let sign = delta.y.to_raw().signum();
+            (a + Point::with_y(a_half.y * sign), b - Point::with_y(b_half.y * sign))
+        };
+
+        let mid = (start + end) / 2.0;
+        let (p, q) = if horizontal {
+            (Point::new(mid.x, start.y), Point::new(mid.x, end.y))
+        } else {
+            (Point::new(start.x, mid.y), Point::new(end.x, mid.y))
+        };
+        vec![start, p, q, end]
+    }
+}
+
+/// Move from the center of a rectangle towards a target until the border of
+/// the rectangle is hit.
+pub(super) fn clip(center: Point, half: Size, target: Point) -> Point {
+    let delta = target - center;
+    let scale = |half: Abs, delta: Abs| {
+        if delta.to_raw() != 0.0 {
+            half / delta.abs()
+        } else {
+            f64::INFINITY
+        }
+    };
+
+    let t = scale(half.x, delta.x).min(scale(half.y, delta.y));
+    if t < 1.0 {
+        center + delta * t
+    } else {
+        center
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A point at the given coordinates in points.
+    fn pt(x: f64, y: f64) -> Point {
+        Point::new(Abs::pt(x), Abs::pt(y))
+    }
+
+    #[test]
+    fn test_clip() {
+        let half = Size::new(Abs::pt(10.0), Abs::pt(5.0));
+        assert_eq!(clip(Point::zero(), half, pt(20.0, 0.0)), pt(10.0, 0.0));
+        assert_eq!(clip(Point::zero(), half, pt(0.0, -20.0)), pt(0.0, -5.0));
+        assert_eq!(clip(Point::zero(), half, pt(20.0, 20.0)), pt(5.0, 5.0));
+        assert_eq!(clip(pt(10.0, 10.0), half, pt(50.0, 10.0)), pt(20.0, 10.0));
+
+        // Targets inside of the rectangle don't move the point.
+        assert_eq!(clip(Point::zero(), half, pt(2.0, 1.0)), Point::zero());
+        assert_eq!(clip(Point::zero(), half, Point::zero()), Point::zero());
+    }
+
+    #[test]
+    fn test_route_points() {
+        let half = Size::new(Abs::pt(10.0), Abs::pt(5.0));
+        let points = |route: Route, b| route.points(Point::zero(), half, b, half);
+
+        // Straight routes go from border to border.
+        assert_eq!(
+            points(Route::Straight, pt(40.0, 15.0)),
+            [pt(10.0, 3.75), pt(30.0, 11.25)]
+        );
+
+        // The other routes leave and enter through the facing sides, with a
+        // turn halfway between them.
+        assert_eq!(
+            points(Route::Orthogonal, pt(40.0, 15.0)),
+            [pt(10.0, 0.0), pt(20.0, 0.0), pt(20.0, 15.0), pt(30.0, 15.0)]
+        );
+        assert_eq!(
+            points(Route::Curved, pt(10.0, -50.0)),
+            [pt(0.0, -5.0), pt(0.0, -25.0), pt(10.0, -25.0), pt(10.0, -45.0)]
+        );
+    }
+}
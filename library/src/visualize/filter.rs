@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+/// Apply raster filters to content.
+///
+/// Filters change how the content looks once it is rendered: It can be
+/// blurred, turned gray or made brighter or darker. Filters don't affect
+/// layout.
+///
+/// Filters are applied when the document is exported. Since PDF has no
+/// equivalent, PDF export embeds filtered content as a raster image. Text in
+/// filtered content can thus not be selected or searched in a PDF.
+///
+/// ## Example { #example }
+/// ```example
+/// #filter(grayscale: true, image("tiger.jpg", width: 2cm))
+/// #filter(blur: 2pt, image("tiger.jpg", width: 2cm))
+/// #filter(brightness: 150%, image("tiger.jpg", width: 2cm))
+/// ```
+///
+/// Display: Filter
+/// Category: visualize
+#[element(Layout)]
+pub struct FilterElem {
+    /// How much to blur the content. This is the standard deviation of a
+    /// gaussian blur. Very large blurs are capped to keep rendering fast.
+    #[resolve]
+    pub blur: Length,
+
+    /// Whether to turn the content into shades of gray.
+    #[default(false)]
+    pub grayscale: bool,
+
+    /// A factor to scale the content's brightness by. Values below `{100%}`
+    /// darken the content and values above brighten it.
+    #[default(Ratio::one())]
+    pub brightness: Ratio,
+
+    /// The content to filter.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for FilterElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FilterElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let mut filters = vec![];
+        let blur = self.blur(styles);
+        if blur > Abs::zero() {
+            filters.push(Filter::Blur(blur));
+        }
+        if self.grayscale(styles) {
+            filters.push(Filter::Grayscale);
+        }
+        let brightness = self.brightness(styles);
+        if brightness != Ratio::one() {
+            filters.push(Filter::Brightness(brightness));
+        }
+
+        let mut fragment = self.body().layout(vt, styles, regions)?;
+        for frame in &mut fragment {
+            frame.filter(filters.clone());
+        }
+        Ok(fragment)
+    }
+}
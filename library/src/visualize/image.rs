@@ -1,16 +1,36 @@
 use std::ffi::OsStr;
 use std::path::Path;
 
-use typst::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+use typst::image::{ColorSpace, Image, ImageFormat, RasterFormat, VectorFormat};
 
+use super::placeholder_frame;
 use crate::meta::{Figurable, LocalName};
 use crate::prelude::*;
 use crate::text::families;
+use crate::text::locale::Term;
 
 /// A raster or vector graphic.
 ///
 /// Supported formats are PNG, JPEG, GIF and SVG.
 ///
+/// Instead of a path, an image can also be given by an `http` or `https` URL.
+/// Whether and how the image is downloaded is up to the compiler: The command
+/// line interface, for example, caches downloads and records their contents in
+/// a lockfile, so that later compilations are reproducible and can also run
+/// offline.
+///
+/// If the compiler is configured to use placeholders, an image that fails to
+/// load is shown as a gray box with its path instead of failing the
+/// compilation.
+///
+/// JPEG images are rotated according to their EXIF orientation. If neither a
+/// width nor a height is given, an image that stores its resolution is shown
+/// at most at its natural size, so a 300 pixel wide image at 150 DPI is at
+/// most two inches wide.
+///
+/// Since PDF cannot animate, animated GIF and PNG images show a single frame.
+/// The [`image-info`]($func/image-info) function tells how many there are.
+///
 /// _Note:_ Work on SVG export is ongoing and there might be visual inaccuracies
 /// in the resulting PDF. Make sure to double-check embedded SVG images. If you
 /// have an issue, also feel free to report it on [GitHub][gh-svg].
@@ -32,13 +52,16 @@ use crate::text::families;
 /// Category: visualize
 #[element(Layout, LocalName, Figurable)]
 pub struct ImageElem {
-    /// Path to an image file.
+    /// Path to an image file or URL to download it from.
     #[required]
     #[parse(
         let Spanned { v: path, span } =
             args.expect::<Spanned<EcoString>>("path to image file")?;
-        let path: EcoString = vm.locate(&path).at(span)?.to_string_lossy().into();
-        let _ = load(vm.world(), &path, None, None).at(span)?;
+        let path = resolve(vm, path).at(span)?;
+        let loaded = load(vm.world(), &path, None, None);
+        if !vm.world().placeholders() {
+            loaded.at(span)?;
+        }
         path
     )]
     pub path: EcoString,
@@ -55,10 +78,21 @@ pub struct ImageElem {
     /// How the image should adjust itself to a given area.
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
+
+    /// Which frame of an animated image to show, starting at one.
+    ///
+    /// ```example
+    /// #image("blinker.gif", width: 40%, frame: 2)
+    /// ```
+    #[default(NonZeroUsize::ONE)]
+    pub frame: NonZeroUsize,
 }
 
 impl Layout for ImageElem {
-    #[tracing::instrument(name = "ImageElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ImageElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -67,20 +101,32 @@ impl Layout for ImageElem {
     ) -> SourceResult<Fragment> {
         let first = families(styles).next();
         let fallback_family = first.as_ref().map(|f| f.as_str());
-        let image =
-            load(vt.world, &self.path(), fallback_family, self.alt(styles)).unwrap();
+        let Ok(image) = load(vt.world, &self.path(), fallback_family, self.alt(styles))
+        else {
+            return self.layout_placeholder(vt, styles, regions);
+        };
+
+        let image = image.with_frame(self.frame(styles).get() - 1).at(self.span())?;
+
         let sizing = Axes::new(self.width(styles), self.height(styles));
-        let region = sizing
+        let mut region = sizing
             .zip(regions.base())
             .map(|(s, r)| s.map(|v| v.resolve(styles).relative_to(r)))
             .unwrap_or(regions.base());
 
         let expand = sizing.as_ref().map(Smart::is_custom) | regions.expand;
-        let region_ratio = region.x / region.y;
-
-        // Find out whether the image is wider or taller than the target size.
         let pxw = image.width() as f64;
         let pxh = image.height() as f64;
+
+        // Without an explicit size, an image that specifies its resolution
+        // is shown at most at its natural size.
+        if let (false, false, Some(dpi)) = (expand.x, expand.y, image.dpi()) {
+            let natural = Size::new(Abs::inches(pxw / dpi), Abs::inches(pxh / dpi));
+            region = region.min(natural);
+        }
+
+        // Find out whether the image is wider or taller than the target size.
+        let region_ratio = region.x / region.y;
         let px_ratio = pxw / pxh;
         let wide = px_ratio > region_ratio;
 
@@ -127,37 +173,93 @@ impl Layout for ImageElem {
     }
 }
 
+impl ImageElem {
+    /// Lay out a box showing the path of an image that failed to load.
+    fn layout_placeholder(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let size = Axes::new(self.width(styles), self.height(styles))
+            .zip(regions.base())
+            .map(|(s, r)| s.map(|v| v.resolve(styles).relative_to(r)))
+            .unwrap_or(Size::new(Abs::pt(120.0), Abs::pt(80.0)));
+
+        let path = self.path();
+        let shown = Path::new(path.as_str())
+            .strip_prefix(vt.world.root())
+            .map_or(path.clone(), |rel| rel.to_string_lossy().as_ref().into());
+
+        let mut frame = placeholder_frame(vt, styles, size, shown, self.span())?;
+        frame.meta(styles, false);
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
 impl LocalName for ImageElem {
-    fn local_name(&self, lang: Lang, _: Option<Region>) -> &'static str {
-        match lang {
-            Lang::ALBANIAN => "Figurë",
-            Lang::ARABIC => "شكل",
-            Lang::BOKMÅL => "Figur",
-            Lang::CHINESE => "图",
-            Lang::CZECH => "Obrázek",
-            Lang::DANISH => "Figur",
-            Lang::DUTCH => "Figuur",
-            Lang::FILIPINO => "Pigura",
-            Lang::FRENCH => "Figure",
-            Lang::GERMAN => "Abbildung",
-            Lang::ITALIAN => "Figura",
-            Lang::NYNORSK => "Figur",
-            Lang::POLISH => "Rysunek",
-            Lang::PORTUGUESE => "Figura",
-            Lang::RUSSIAN => "Рисунок",
-            Lang::SLOVENIAN => "Slika",
-            Lang::SPANISH => "Figura",
-            Lang::SWEDISH => "Figur",
-            Lang::TURKISH => "Şekil",
-            Lang::UKRAINIAN => "Рисунок",
-            Lang::VIETNAMESE => "Hình",
-            Lang::ENGLISH | _ => "Figure",
-        }
+    fn local_name(&self, lang: Lang, region: Option<Region>) -> &'static str {
+        Term::Figure.local(lang, region)
     }
 }
 
 impl Figurable for ImageElem {}
 
+/// Inspect an image without placing it.
+///
+/// This lets layouts branch on the properties of an image, for example to
+/// rotate photos in landscape format. Returns a dictionary with the following
+/// keys:
+/// - `width` and `height`: The size of the image in pixels.
+/// - `format`: The image format, one of `{"png"}`, `{"jpg"}`, `{"gif"}` and
+///   `{"svg"}`.
+/// - `frames`: The number of frames of an animated image, one otherwise.
+/// - `dpi`: The resolution stored in the image or `{none}` if it doesn't
+///   specify one.
+/// - `color`: The color space of the pixels, one of `{"luma"}`, `{"rgb"}` and
+///   `{"cmyk"}`, or `{none}` for vector images.
+///
+/// ## Example { #example }
+/// ```example
+/// #let info = image-info("tiger.jpg")
+/// #info.width × #info.height pixels
+/// in #upper(info.color).
+/// ```
+///
+/// Display: Image Info
+/// Category: visualize
+#[func]
+pub fn image_info(
+    /// Path to an image file or URL to download it from.
+    path: Spanned<EcoString>,
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> SourceResult<Dict> {
+    let Spanned { v: path, span } = path;
+    let full = resolve(vm, path).at(span)?;
+    let image = load(vm.world(), &full, None, None).at(span)?;
+    let format = match image.format() {
+        ImageFormat::Raster(RasterFormat::Png) => "png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "jpg",
+        ImageFormat::Raster(RasterFormat::Gif) => "gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "svg",
+    };
+
+    Ok(dict! {
+        "width" => image.width() as i64,
+        "height" => image.height() as i64,
+        "format" => format,
+        "frames" => image.frames() as i64,
+        "dpi" => image.dpi(),
+        "color" => image.color_space().map(|space| match space {
+            ColorSpace::Luma => "luma",
+            ColorSpace::Rgb => "rgb",
+            ColorSpace::Cmyk => "cmyk",
+        }),
+    })
+}
+
 /// How an image should adjust itself to a given area.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum ImageFit {
@@ -178,9 +280,16 @@ fn load(
     fallback_family: Option<&str>,
     alt: Option<EcoString>,
 ) -> StrResult<Image> {
-    let full = Path::new(full);
-    let buffer = world.file(full)?;
-    let ext = full.extension().and_then(OsStr::to_str).unwrap_or_default();
+    let (buffer, name) = if is_url(full) {
+        // Ignore the query and fragment when looking for the extension.
+        (world.fetch(full)?, full.split(['?', '#']).next().unwrap_or_default())
+    } else {
+        (world.file(Path::new(full))?, full)
+    };
+    let ext = Path::new(name)
+        .extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
     let format = match ext.to_lowercase().as_str() {
         "png" => ImageFormat::Raster(RasterFormat::Png),
         "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
@@ -190,3 +299,17 @@ fn load(
     };
     Image::with_fonts(buffer, format, world, fallback_family, alt)
 }
+
+/// Resolve the path of an image, which may also be a URL.
+fn resolve(vm: &Vm, path: EcoString) -> StrResult<EcoString> {
+    if is_url(&path) {
+        vm.check_network()?;
+        return Ok(path);
+    }
+    Ok(vm.locate(&path)?.to_string_lossy().into())
+}
+
+/// Whether a path is an `http` or `https` URL.
+fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
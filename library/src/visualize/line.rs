@@ -1,3 +1,4 @@
+use super::{direction, Marker};
 use crate::prelude::*;
 
 /// A line from one point to another.
@@ -82,10 +83,34 @@ pub struct LineElem {
     #[resolve]
     #[fold]
     pub stroke: PartialStroke,
+
+    /// The marker to draw at the start of the line. One of `{"arrow"}`,
+    /// `{"dot"}` and `{"bar"}`.
+    ///
+    /// Markers scale with the stroke's thickness and follow the line's
+    /// direction.
+    ///
+    /// ```example
+    /// #line(length: 3cm, start-mark: "dot", end-mark: "arrow")
+    /// #line(
+    ///   end: (2cm, 1cm),
+    ///   stroke: 2pt + eastern,
+    ///   start-mark: "bar",
+    ///   end-mark: "bar",
+    /// )
+    /// ```
+    pub start_mark: Option<Marker>,
+
+    /// The marker to draw at the end of the line. See `start-mark` for the
+    /// available markers.
+    pub end_mark: Option<Marker>,
 }
 
 impl Layout for LineElem {
-    #[tracing::instrument(name = "LineElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LineElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         _: &mut Vt,
@@ -110,9 +135,27 @@ impl Layout for LineElem {
         let size = start.max(start + delta).max(Size::zero());
         let target = regions.expand.select(regions.size, size);
 
+        let mut from = start.to_point();
+        let mut to = from + delta.to_point();
+        let mut markers = vec![];
+        if let Some(dir) = direction(to - from) {
+            if let Some(marker) = self.start_mark(styles) {
+                markers.push(marker.shape(from, -dir, &stroke));
+                from += dir * marker.inset(&stroke).to_raw();
+            }
+            if let Some(marker) = self.end_mark(styles) {
+                markers.push(marker.shape(to, dir, &stroke));
+                to -= dir * marker.inset(&stroke).to_raw();
+            }
+        }
+
         let mut frame = Frame::new(target);
-        let shape = Geometry::Line(delta.to_point()).stroked(stroke);
-        frame.push(start.to_point(), FrameItem::Shape(shape, self.span()));
+        let shape = Geometry::Line(to - from).stroked(stroke);
+        frame.push(from, FrameItem::Shape(shape, self.span()));
+        for (pos, shape) in markers {
+            frame.push(pos, FrameItem::Shape(shape, self.span()));
+        }
+
         Ok(Fragment::frame(frame))
     }
 }
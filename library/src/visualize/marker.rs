@@ -0,0 +1,130 @@
+use crate::prelude::*;
+
+/// A decoration at the start or end of a line or path.
+///
+/// Markers are sized relative to the thickness of the stroke they decorate
+/// and rotated to follow its direction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Marker {
+    /// A filled arrowhead.
+    Arrow,
+    /// A filled circle.
+    Dot,
+    /// A short line across the stroke.
+    Bar,
+}
+
+impl Marker {
+    /// How far the stroke must be shortened so that it does not poke through
+    /// the marker.
+    pub(super) fn inset(self, stroke: &Stroke) -> Abs {
+        match self {
+            Self::Arrow => marker_size(stroke),
+            Self::Dot | Self::Bar => Abs::zero(),
+        }
+    }
+
+    /// Create the marker for a stroke that ends at `tip`, heading into the
+    /// direction `dir`, which must have unit length.
+    pub(super) fn shape(self, tip: Point, dir: Point, stroke: &Stroke) -> (Point, Shape) {
+        let size = marker_size(stroke);
+        let paint = stroke.paint.clone();
+        match self {
+            Self::Arrow => {
+                let [a, b, c] = arrowhead(tip, dir, size);
+                let mut path = Path::new();
+                path.move_to(a);
+                path.line_to(b);
+                path.line_to(c);
+                path.close_path();
+                (Point::zero(), Geometry::Path(path).filled(paint))
+            }
+            Self::Dot => {
+                let diameter = size * 0.8;
+                let shape = ellipse(Size::splat(diameter), Some(paint), None);
+                (tip - Point::splat(diameter / 2.0), shape)
+            }
+            Self::Bar => {
+                let normal = Point::new(-dir.y, dir.x) * size.to_raw();
+                let stroke = Stroke {
+                    line_cap: LineCap::Butt,
+                    dash_pattern: None,
+                    ..stroke.clone()
+                };
+                (tip - normal / 2.0, Geometry::Line(normal).stroked(stroke))
+            }
+        }
+    }
+}
+
+/// The length of the markers on a stroke.
+pub(super) fn marker_size(stroke: &Stroke) -> Abs {
+    Abs::pt(3.0) + stroke.thickness * 3.0
+}
+
+/// The corners of an arrowhead with its tip at `tip`, pointing into the
+/// direction `dir`, which must have unit length.
+pub(super) fn arrowhead(tip: Point, dir: Point, size: Abs) -> [Point; 3] {
+    let base = tip - dir * size.to_raw();
+    let normal = Point::new(-dir.y, dir.x) * (size.to_raw() / 2.5);
+    [tip, base + normal, base - normal]
+}
+
+/// The direction of a vector as a vector of unit length, if it has one.
+pub(super) fn direction(delta: Point) -> Option<Point> {
+    let length = delta.hypot().to_raw();
+    (length > 0.0).then(|| delta / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A point at the given coordinates in points.
+    fn pt(x: f64, y: f64) -> Point {
+        Point::new(Abs::pt(x), Abs::pt(y))
+    }
+
+    #[test]
+    fn test_marker_geometry() {
+        let stroke = Stroke::default();
+        assert_eq!(marker_size(&stroke), Abs::pt(6.0));
+        assert_eq!(
+            marker_size(&Stroke { thickness: Abs::pt(2.0), ..stroke.clone() }),
+            Abs::pt(9.0)
+        );
+
+        // The arrowhead's base is as wide as 80% of its length.
+        let right = direction(pt(5.0, 0.0)).unwrap();
+        assert_eq!(right, pt(1.0, 0.0));
+        assert_eq!(
+            arrowhead(pt(10.0, 0.0), right, Abs::pt(6.0)),
+            [pt(10.0, 0.0), pt(4.0, 2.4), pt(4.0, -2.4)]
+        );
+        assert_eq!(direction(pt(3.0, 4.0)), Some(pt(0.6, 0.8)));
+        assert_eq!(direction(Point::zero()), None);
+    }
+
+    #[test]
+    fn test_marker_shapes() {
+        let stroke = Stroke::default();
+        let tip = pt(10.0, 0.0);
+        let right = pt(1.0, 0.0);
+
+        // Only arrows cover the end of the stroke.
+        assert_eq!(Marker::Arrow.inset(&stroke), Abs::pt(6.0));
+        assert_eq!(Marker::Dot.inset(&stroke), Abs::zero());
+        assert_eq!(Marker::Bar.inset(&stroke), Abs::zero());
+
+        // Dots are centered on the tip and bars cross it.
+        let (pos, dot) = Marker::Dot.shape(tip, right, &stroke);
+        assert!(pos.x.approx_eq(Abs::pt(7.6)) && pos.y.approx_eq(Abs::pt(-2.4)));
+        assert_eq!(dot.fill, Some(stroke.paint.clone()));
+        let (pos, bar) = Marker::Bar.shape(tip, right, &stroke);
+        assert_eq!(pos, pt(10.0, -3.0));
+        assert_eq!(bar.geometry, Geometry::Line(pt(0.0, 6.0)));
+        let (pos, arrow) = Marker::Arrow.shape(tip, right, &stroke);
+        assert_eq!(pos, Point::zero());
+        assert!(matches!(arrow.geometry, Geometry::Path(_)));
+    }
+}
@@ -1,22 +1,46 @@
 //! Drawing and visualization.
 
+pub mod color;
+
+mod canvas;
+mod chart;
+mod code;
+mod connector;
+mod filter;
 mod image;
 mod line;
+mod marker;
 mod path;
+mod pdf;
+mod placeholder;
 mod polygon;
+mod shadow;
 mod shape;
+mod text_path;
 
+pub use self::canvas::*;
+pub use self::chart::*;
+pub use self::code::*;
+pub use self::connector::*;
+pub use self::filter::*;
 pub use self::image::*;
 pub use self::line::*;
+pub use self::marker::*;
 pub use self::path::*;
+pub use self::pdf::*;
+pub use self::placeholder::*;
 pub use self::polygon::*;
+pub use self::shadow::*;
 pub use self::shape::*;
+pub use self::text_path::*;
 
 use crate::prelude::*;
 
 /// Hook up all visualize definitions.
 pub(super) fn define(global: &mut Scope) {
     global.define("image", ImageElem::func());
+    global.define("image-info", image_info_func());
+    global.define("pdf-info", pdf_info_func());
     global.define("line", LineElem::func());
     global.define("rect", RectElem::func());
     global.define("square", SquareElem::func());
@@ -24,22 +48,25 @@ pub(super) fn define(global: &mut Scope) {
     global.define("circle", CircleElem::func());
     global.define("polygon", PolygonElem::func());
     global.define("path", PathElem::func());
-    global.define("black", Color::BLACK);
-    global.define("gray", Color::GRAY);
-    global.define("silver", Color::SILVER);
-    global.define("white", Color::WHITE);
-    global.define("navy", Color::NAVY);
-    global.define("blue", Color::BLUE);
-    global.define("aqua", Color::AQUA);
-    global.define("teal", Color::TEAL);
-    global.define("eastern", Color::EASTERN);
-    global.define("purple", Color::PURPLE);
-    global.define("fuchsia", Color::FUCHSIA);
-    global.define("maroon", Color::MAROON);
-    global.define("red", Color::RED);
-    global.define("orange", Color::ORANGE);
-    global.define("yellow", Color::YELLOW);
-    global.define("olive", Color::OLIVE);
-    global.define("green", Color::GREEN);
-    global.define("lime", Color::LIME);
+    global.define("placeholder", PlaceholderElem::func());
+    global.define("shadow", shadow_func());
+    global.define("filter", FilterElem::func());
+    global.define("text-path", TextPathElem::func());
+    global.define("qrcode", QrcodeElem::func());
+    global.define("barcode", BarcodeElem::func());
+    global.define("canvas", CanvasElem::func());
+    global.define("connector", ConnectorElem::func());
+    global.define("bar-chart", BarChartElem::func());
+    global.define("line-chart", LineChartElem::func());
+    global.define("pie-chart", PieChartElem::func());
+
+    // The named colors are re-exported from the color module.
+    let color = color::module();
+    for (name, value) in color.scope().iter() {
+        if let Value::Color(_) = value {
+            global.define(name.clone(), value.clone());
+        }
+    }
+    global.define("color", color);
+    color::define_deprecated(global);
 }
@@ -1,6 +1,7 @@
 use kurbo::{CubicBez, ParamCurveExtrema};
 use typst::eval::Reflect;
 
+use super::{direction, Marker};
 use crate::prelude::*;
 
 use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
@@ -46,6 +47,26 @@ pub struct PathElem {
     #[default(false)]
     pub closed: bool,
 
+    /// The marker to draw at the start of an open path. One of `{"arrow"}`,
+    /// `{"dot"}` and `{"bar"}`. The marker follows the path's direction at
+    /// its start.
+    ///
+    /// ```example
+    /// #path(
+    ///   stroke: 1.5pt,
+    ///   start-mark: "dot",
+    ///   end-mark: "arrow",
+    ///   (0pt, 30pt),
+    ///   ((60pt, 0pt), (-20pt, 0pt)),
+    ///   ((100pt, 30pt), (0pt, -20pt)),
+    /// )
+    /// ```
+    pub start_mark: Option<Marker>,
+
+    /// The marker to draw at the end of an open path. See `start-mark` for the
+    /// available markers.
+    pub end_mark: Option<Marker>,
+
     /// The vertices of the path.
     ///
     /// Each vertex can be defined in 3 ways:
@@ -66,7 +87,10 @@ pub struct PathElem {
 }
 
 impl Layout for PathElem {
-    #[tracing::instrument(name = "PathElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PathElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         _: &mut Vt,
@@ -81,13 +105,55 @@ impl Layout for PathElem {
         };
 
         let vertices: Vec<PathVertex> = self.vertices();
-        let points: Vec<Point> = vertices.iter().map(|c| resolve(c.vertex())).collect();
+        let mut points: Vec<Point> =
+            vertices.iter().map(|c| resolve(c.vertex())).collect();
 
         let mut size = Size::zero();
         if points.is_empty() {
             return Ok(Fragment::frame(Frame::new(size)));
         }
 
+        // Prepare fill and stroke.
+        let fill = self.fill(styles);
+        let stroke = match self.stroke(styles) {
+            Smart::Auto if fill.is_none() => Some(Stroke::default()),
+            Smart::Auto => None,
+            Smart::Custom(stroke) => stroke.map(PartialStroke::unwrap_or_default),
+        };
+
+        // Add the markers, pulling the ends of the path back so that it does
+        // not poke through them. Moving a vertex also moves its control
+        // points, so the direction of the curve is kept.
+        let mut markers = vec![];
+        let n = points.len();
+        if let (Some(stroke), false, true) = (&stroke, self.closed(styles), n > 1) {
+            if let Some(marker) = self.start_mark(styles) {
+                let (first, second) = (vertices[0], vertices[1]);
+                let heading = [
+                    resolve(first.control_point_from()),
+                    points[1] + resolve(second.control_point_to()) - points[0],
+                    points[1] - points[0],
+                ];
+                if let Some(dir) = heading.into_iter().find_map(direction) {
+                    markers.push(marker.shape(points[0], -dir, stroke));
+                    points[0] += dir * marker.inset(stroke).to_raw();
+                }
+            }
+
+            if let Some(marker) = self.end_mark(styles) {
+                let (last, prev) = (vertices[n - 1], vertices[n - 2]);
+                let heading = [
+                    -resolve(last.control_point_to()),
+                    points[n - 1] - points[n - 2] - resolve(prev.control_point_from()),
+                    points[n - 1] - points[n - 2],
+                ];
+                if let Some(dir) = heading.into_iter().find_map(direction) {
+                    markers.push(marker.shape(points[n - 1], dir, stroke));
+                    points[n - 1] -= dir * marker.inset(stroke).to_raw();
+                }
+            }
+        }
+
         // Only create a path if there are more than zero points.
         // Construct a closed path given all points.
         let mut path = Path::new();
@@ -133,17 +199,12 @@ impl Layout for PathElem {
             path.close_path();
         }
 
-        // Prepare fill and stroke.
-        let fill = self.fill(styles);
-        let stroke = match self.stroke(styles) {
-            Smart::Auto if fill.is_none() => Some(Stroke::default()),
-            Smart::Auto => None,
-            Smart::Custom(stroke) => stroke.map(PartialStroke::unwrap_or_default),
-        };
-
         let mut frame = Frame::new(size);
         let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
         frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+        for (pos, shape) in markers {
+            frame.push(pos, FrameItem::Shape(shape, self.span()));
+        }
 
         Ok(Fragment::frame(frame))
     }
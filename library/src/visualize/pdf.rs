@@ -0,0 +1,217 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+/// Inspect a PDF file without placing it.
+///
+/// Returns a dictionary with the following keys:
+/// - `version`: The PDF version the file declares, like `{"1.7"}`.
+/// - `pages`: The number of pages.
+/// - `width` and `height`: The size of a page. If the pages have different
+///   sizes, it is unspecified which page's size this is.
+///
+/// Only the file's structure is read, the pages' contents aren't.
+///
+/// ## Example { #example }
+/// ```example
+/// #let info = pdf-info("report.pdf")
+/// The report has #info.pages pages
+/// of size #info.width by #info.height.
+/// ```
+///
+/// Display: PDF Info
+/// Category: visualize
+#[func]
+pub fn pdf_info(
+    /// Path to a PDF file.
+    path: Spanned<EcoString>,
+    /// The virtual machine.
+    vm: &mut Vm,
+) -> SourceResult<Dict> {
+    let Spanned { v: path, span } = path;
+    let path = vm.locate(&path).at(span)?;
+    let data = vm.world().file(&path).at(span)?;
+    let info = inspect(&data).at(span)?;
+    Ok(dict! {
+        "version" => info.version,
+        "pages" => info.pages as i64,
+        "width" => info.size.map(|size| Abs::pt(size.x)),
+        "height" => info.size.map(|size| Abs::pt(size.y)),
+    })
+}
+
+/// The information gathered about a PDF file.
+struct PdfInfo {
+    version: EcoString,
+    pages: usize,
+    size: Option<Axes<f64>>,
+}
+
+/// Read the version, page count and page size of a PDF file.
+///
+/// Instead of fully parsing the file, this scans the page tree dictionaries,
+/// including those in compressed object streams.
+fn inspect(data: &[u8]) -> StrResult<PdfInfo> {
+    let version = data
+        .strip_prefix(b"%PDF-")
+        .and_then(|rest| {
+            let len =
+                rest.iter().take_while(|c| c.is_ascii_digit() || **c == b'.').count();
+            std::str::from_utf8(&rest[..len]).ok()
+        })
+        .filter(|version| !version.is_empty())
+        .ok_or("file is not a valid PDF")?;
+
+    let mut segments = vec![data.to_vec()];
+    segments.extend(object_streams(data));
+
+    let mut pages = 0;
+    let mut size = None;
+    for segment in &segments {
+        for range in dicts_of_type(segment, b"/Pages") {
+            let dict = &segment[range];
+            if let Some(count) = number_after(dict, b"/Count") {
+                pages = pages.max(count as usize);
+            }
+            size = size.or_else(|| media_box(dict));
+        }
+
+        for range in dicts_of_type(segment, b"/Page") {
+            size = size.or_else(|| media_box(&segment[range]));
+        }
+    }
+
+    if pages == 0 {
+        bail!("failed to find the pages of the PDF");
+    }
+
+    Ok(PdfInfo { version: version.into(), pages, size })
+}
+
+/// Decompress the object streams of a PDF file.
+fn object_streams(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = vec![];
+    for range in dicts_of_type(data, b"/ObjStm") {
+        let rest = &data[range.end..];
+        let Some(start) = find(rest, b"stream") else { continue };
+        let mut body = &rest[start + 6..];
+        body = body.strip_prefix(b"\r").unwrap_or(body);
+        body = body.strip_prefix(b"\n").unwrap_or(body);
+        let Some(len) = find(body, b"endstream") else { continue };
+        if let Ok(inflated) = miniz_oxide::inflate::decompress_to_vec_zlib(&body[..len]) {
+            streams.push(inflated);
+        }
+    }
+    streams
+}
+
+/// Find the byte ranges of the dictionaries with the given `/Type`.
+fn dicts_of_type<'a>(
+    data: &'a [u8],
+    kind: &'a [u8],
+) -> impl Iterator<Item = Range<usize>> + 'a {
+    let mut offset = 0;
+    std::iter::from_fn(move || loop {
+        let at = offset + find(&data[offset..], b"/Type")?;
+        offset = at + 5;
+
+        let value = skip_whitespace(&data[offset..]);
+        let Some(tail) = value.strip_prefix(kind) else { continue };
+        if tail.first().map_or(false, |&c| is_regular(c)) {
+            continue;
+        }
+
+        if let Some(dict) = enclosing_dict(data, at) {
+            return Some(dict);
+        }
+    })
+}
+
+/// Find the byte range of the innermost dictionary around the given offset.
+fn enclosing_dict(data: &[u8], at: usize) -> Option<Range<usize>> {
+    let mut depth = 0;
+    let mut start = None;
+    let mut i = at;
+    while i >= 2 {
+        match &data[i - 2..i] {
+            b">>" => {
+                depth += 1;
+                i -= 2;
+            }
+            b"<<" if depth == 0 => {
+                start = Some(i - 2);
+                break;
+            }
+            b"<<" => {
+                depth -= 1;
+                i -= 2;
+            }
+            _ => i -= 1,
+        }
+    }
+
+    let start = start?;
+    let mut depth = 0;
+    let mut i = start;
+    while i + 2 <= data.len() {
+        match &data[i..i + 2] {
+            b"<<" => {
+                depth += 1;
+                i += 2;
+            }
+            b">>" => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return Some(start..i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    None
+}
+
+/// Parse the number after a key in a dictionary.
+fn number_after(dict: &[u8], key: &[u8]) -> Option<f64> {
+    let at = find(dict, key)?;
+    let rest = skip_whitespace(&dict[at + key.len()..]);
+    let len = rest
+        .iter()
+        .take_while(|&&c| c.is_ascii_digit() || matches!(c, b'.' | b'-' | b'+'))
+        .count();
+    f64::from_str(std::str::from_utf8(&rest[..len]).ok()?).ok()
+}
+
+/// Parse the `/MediaBox` of a dictionary, if it is given directly.
+fn media_box(dict: &[u8]) -> Option<Axes<f64>> {
+    let at = find(dict, b"/MediaBox")?;
+    let rest = skip_whitespace(&dict[at + 9..]).strip_prefix(b"[")?;
+    let end = rest.iter().position(|&c| c == b']')?;
+    let numbers: Vec<f64> = std::str::from_utf8(&rest[..end])
+        .ok()?
+        .split_ascii_whitespace()
+        .map(f64::from_str)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let &[x0, y0, x1, y1] = numbers.as_slice() else { return None };
+    Some(Axes::new((x1 - x0).abs(), (y1 - y0).abs()))
+}
+
+/// Find the first occurrence of a needle in a haystack.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Skip leading whitespace.
+fn skip_whitespace(data: &[u8]) -> &[u8] {
+    let len = data.iter().take_while(|c| c.is_ascii_whitespace()).count();
+    &data[len..]
+}
+
+/// Whether a character can be part of a PDF name.
+fn is_regular(c: u8) -> bool {
+    !c.is_ascii_whitespace() && !b"()<>[]{}/%".contains(&c)
+}
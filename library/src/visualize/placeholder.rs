@@ -0,0 +1,90 @@
+use crate::prelude::*;
+use crate::text::{TextElem, TextSize};
+
+/// A gray box that stands in for an image or figure.
+///
+/// This is useful to prototype a layout before the real content exists. By
+/// default, the box is labelled with its dimensions.
+///
+/// ## Example { #example }
+/// ```example
+/// #placeholder(width: 100%, height: 40pt)
+/// #placeholder(label: "Logo")
+/// ```
+///
+/// Display: Placeholder
+/// Category: visualize
+#[element(Layout)]
+pub struct PlaceholderElem {
+    /// The width of the box. Defaults to `{120pt}`.
+    pub width: Smart<Rel<Length>>,
+
+    /// The height of the box. Defaults to `{80pt}`.
+    pub height: Smart<Rel<Length>>,
+
+    /// The text in the box. When `{auto}`, shows the box's dimensions.
+    pub label: Smart<EcoString>,
+}
+
+impl Layout for PlaceholderElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PlaceholderElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let size = Axes::new(self.width(styles), self.height(styles))
+            .zip(Size::new(Abs::pt(120.0), Abs::pt(80.0)))
+            .zip(regions.base())
+            .map(|((s, default), r)| {
+                s.map_or(default, |v| v.resolve(styles).relative_to(r))
+            });
+
+        if !size.is_finite() {
+            bail!(self.span(), "cannot create placeholder with infinite size");
+        }
+
+        let label = self.label(styles).unwrap_or_else(|| {
+            eco_format!("{}pt × {}pt", size.x.to_pt().round(), size.y.to_pt().round())
+        });
+
+        let mut frame = placeholder_frame(vt, styles, size, label, self.span())?;
+        frame.meta(styles, false);
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// Create a gray box of the given size with a small label in its center.
+pub(super) fn placeholder_frame(
+    vt: &mut Vt,
+    styles: StyleChain,
+    size: Size,
+    label: EcoString,
+    span: Span,
+) -> SourceResult<Frame> {
+    let inset = Abs::pt(4.0);
+    let pod = Regions::one(
+        (size - Size::splat(2.0 * inset)).max(Size::zero()),
+        Axes::splat(false),
+    );
+    let label = TextElem::packed(label)
+        .styled(TextElem::set_size(TextSize(Abs::pt(8.0).into())))
+        .styled(TextElem::set_fill(Color::GRAY.into()))
+        .layout(vt, styles, pod)?
+        .into_frame();
+
+    let mut frame = Frame::new(size);
+    let stroke = Stroke { paint: Color::GRAY.into(), ..Stroke::default() };
+    let background = Geometry::Rect(size).filled(Color::Luma(LumaColor::new(240)).into());
+    frame.push(Point::zero(), FrameItem::Shape(background, span));
+    let border = Geometry::Rect(size).stroked(stroke);
+    frame.push(Point::zero(), FrameItem::Shape(border, span));
+    let pos = ((size - label.size()) / 2.0).max(Size::splat(inset)).to_point();
+    frame.push_frame(pos, label);
+    frame.clip();
+    Ok(frame)
+}
@@ -43,7 +43,10 @@ pub struct PolygonElem {
 }
 
 impl Layout for PolygonElem {
-    #[tracing::instrument(name = "PolygonElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "PolygonElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         _: &mut Vt,
@@ -0,0 +1,157 @@
+use crate::prelude::*;
+
+/// Create a drop shadow for boxes, blocks and text.
+///
+/// The shadow has the shape of the element's background and is drawn behind
+/// it. A blurred shadow fades out over the blur radius with a gaussian blur,
+/// so that whatever lies behind it shines through. Like other
+/// [filters]($func/filter), the blur is applied during export. PDF export
+/// embeds blurred shadows as raster images.
+///
+/// ## Example { #example }
+/// ```example
+/// #box(
+///   fill: white,
+///   stroke: 0.5pt + luma(200),
+///   inset: 8pt,
+///   radius: 4pt,
+///   shadow: shadow(offset: (2pt, 3pt), blur: 4pt),
+/// )[Floating above the page]
+///
+/// #text(16pt, shadow: shadow(color: aqua))[Shade]
+/// ```
+///
+/// Display: Shadow
+/// Category: visualize
+#[func]
+pub fn shadow(
+    /// How far to move the shadow from the element. Given as an array of a
+    /// horizontal and a vertical offset, which may be relative to the size of
+    /// the element.
+    #[named]
+    #[default(Axes::splat(Abs::pt(2.0).into()))]
+    offset: Axes<Rel<Length>>,
+    /// Over how much space the shadow fades out.
+    #[named]
+    #[default]
+    blur: Length,
+    /// The color of the shadow.
+    #[named]
+    #[default(Color::GRAY)]
+    color: Color,
+) -> Shadow {
+    Shadow { offset, blur, color }
+}
+
+/// A drop shadow.
+#[derive(Clone, PartialEq, Hash)]
+pub struct Shadow {
+    /// How far the shadow is moved from the element.
+    pub offset: Axes<Rel<Length>>,
+    /// Over how much space the shadow fades out.
+    pub blur: Length,
+    /// The shadow's color.
+    pub color: Color,
+}
+
+impl Shadow {
+    /// The shadow's offset, resolved relative to the shadowed area's size.
+    pub fn resolve_offset(&self, styles: StyleChain, size: Size) -> Point {
+        self.offset
+            .resolve(styles)
+            .zip(size)
+            .map(|(offset, size)| offset.relative_to(size))
+            .to_point()
+    }
+
+    /// Wrap the contents of a shadow in a group that blurs them.
+    pub fn group(&self, styles: StyleChain, frame: Frame) -> GroupItem {
+        let mut group = GroupItem::new(frame);
+        let blur = self.blur.resolve(styles);
+        if blur > Abs::zero() {
+            // Most of a gaussian blur lies within twice its standard deviation.
+            group.filters.push(Filter::Blur(blur / 2.0));
+        }
+        group
+    }
+
+    /// Add the shadow of a background with the given outset and radius to the
+    /// bottom of a frame.
+    pub fn apply(
+        &self,
+        frame: &mut Frame,
+        styles: StyleChain,
+        outset: Sides<Rel<Abs>>,
+        radius: Corners<Rel<Abs>>,
+        span: Span,
+    ) {
+        let outset = outset.relative_to(frame.size());
+        let size = frame.size() + outset.sum_by_axis();
+        let radius = radius.map(|side| side.relative_to(size.x.min(size.y) / 2.0));
+        let origin = Point::new(-outset.left, -outset.top)
+            + self.resolve_offset(styles, frame.size());
+
+        let mut shadow = Frame::new(size);
+        for shape in
+            rounded_rect(size, radius, Some(self.color.into()), Sides::splat(None))
+        {
+            shadow.push(Point::zero(), FrameItem::Shape(shape, span));
+        }
+
+        frame.prepend(origin, FrameItem::Group(self.group(styles, shadow)));
+    }
+}
+
+impl Debug for Shadow {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "shadow(offset: ({:?}, {:?}), blur: {:?}, color: {:?})",
+            self.offset.x, self.offset.y, self.blur, self.color
+        )
+    }
+}
+
+cast! {
+    type Shadow: "shadow",
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{compile, items};
+
+    #[test]
+    fn test_blurred_shadow() {
+        let document = compile(
+            "#set page(width: 60pt, height: 60pt, margin: 20pt)\n\
+             #let s = shadow(offset: (0pt, 0pt), blur: 4pt, color: black)\n\
+             #place(box(width: 20pt, height: 20pt, fill: white, shadow: s))\n\
+             #place(dy: 30pt, text(shadow: shadow(blur: 2pt))[A])",
+        );
+
+        // Both shadows are blurred with half their blur as standard deviation.
+        let filters: Vec<_> = items(&document.pages[0])
+            .into_iter()
+            .filter_map(|(_, item)| match item {
+                FrameItem::Group(group) => Some(group.filters.iter().copied()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(filters, [Filter::Blur(Abs::pt(2.0)), Filter::Blur(Abs::pt(1.0))]);
+
+        // The box's shadow fades out beyond its edge.
+        let pixmap = typst::export::render(&document.pages[0], 1.0, Color::WHITE);
+        let green = |x, y| pixmap.pixel(x, y).unwrap().green();
+        assert_eq!(green(30, 30), 0xff);
+        assert!((50..250).contains(&green(18, 30)));
+        assert!(green(18, 30) < green(16, 30));
+        assert!(green(5, 30) >= 250);
+
+        // PDF export embeds the blurred shadows as images with transparency.
+        let pdf = typst::export::pdf(&document).unwrap();
+        let masks = pdf.windows(6).filter(|w| *w == b"/SMask").count();
+        assert_eq!(masks, 2);
+    }
+}
@@ -133,7 +133,10 @@ pub struct RectElem {
 }
 
 impl Layout for RectElem {
-    #[tracing::instrument(name = "RectElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "RectElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -242,7 +245,10 @@ pub struct SquareElem {
 }
 
 impl Layout for SquareElem {
-    #[tracing::instrument(name = "SquareElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "SquareElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -323,7 +329,10 @@ pub struct EllipseElem {
 }
 
 impl Layout for EllipseElem {
-    #[tracing::instrument(name = "EllipseElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "EllipseElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -429,7 +438,10 @@ pub struct CircleElem {
 }
 
 impl Layout for CircleElem {
-    #[tracing::instrument(name = "CircleElem::layout", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "CircleElem::layout", skip_all)
+    )]
     fn layout(
         &self,
         vt: &mut Vt,
@@ -454,7 +466,7 @@ impl Layout for CircleElem {
 }
 
 /// Layout a shape.
-#[tracing::instrument(name = "shape::layout", skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "shape::layout", skip_all))]
 #[allow(clippy::too_many_arguments)]
 fn layout(
     vt: &mut Vt,
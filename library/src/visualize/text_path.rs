@@ -0,0 +1,263 @@
+use super::{direction, PathVertex};
+use crate::prelude::*;
+
+/// Text laid out along a circle or a path.
+///
+/// The body is first laid out as a single line. Its glyphs are then placed
+/// one by one along the path, each rotated to follow the path's direction at
+/// its center. The path serves as the baseline of the text. Content other than
+/// text, like shapes or images, is not placed along the path.
+///
+/// ## Example { #example }
+/// ```example
+/// #text-path(radius: 1.2cm)[
+///   Round and round it goes
+/// ]
+///
+/// #text-path(
+///   align: center,
+///   ((0pt, 40pt), (-20pt, 20pt)),
+///   ((120pt, 40pt), (-20pt, -40pt)),
+/// )[Follow the curve]
+/// ```
+///
+/// Display: Text Path
+/// Category: visualize
+#[element(Layout)]
+pub struct TextPathElem {
+    /// The text to place along the path.
+    #[required]
+    pub body: Content,
+
+    /// If set, the text is put onto a circle with this radius instead of
+    /// along the vertices.
+    pub radius: Option<Length>,
+
+    /// The angle on the circle at which the text is aligned. Angles grow
+    /// clockwise, starting from the right of the circle. The default puts the
+    /// text on top of the circle.
+    ///
+    /// ```example
+    /// #text-path(radius: 1cm, start: 90deg)[Upside down]
+    /// ```
+    #[default(Angle::deg(-90.0))]
+    pub start: Angle,
+
+    /// Additional spacing between the glyphs.
+    ///
+    /// ```example
+    /// #text-path(radius: 1cm, spacing: 2pt)[Airy]
+    /// ```
+    #[resolve]
+    pub spacing: Length,
+
+    /// How to align the text on the path. For an open path, the text is
+    /// aligned relative to the path's extent. For a circle, it is aligned
+    /// relative to the [start angle]($func/text-path.start): It can start at,
+    /// be centered on or end at that angle.
+    #[default(HorizontalAlign(GenAlign::Start))]
+    pub align: HorizontalAlign,
+
+    /// The vertices of the path the text follows. They are specified in the
+    /// same way as for the [`path`]($func/path.vertices) function.
+    #[variadic]
+    pub vertices: Vec<PathVertex>,
+}
+
+impl Layout for TextPathElem {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "TextPathElem::layout", skip_all)
+    )]
+    fn layout(
+        &self,
+        vt: &mut Vt,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let guide = match self.radius(styles) {
+            Some(radius) => Guide::Circle {
+                radius: radius.resolve(styles),
+                start: self.start(styles),
+            },
+            None => {
+                let resolve = |axes: Axes<Rel<Length>>| {
+                    axes.resolve(styles)
+                        .zip(regions.base())
+                        .map(|(l, b)| l.relative_to(b))
+                        .to_point()
+                };
+                let vertices = self.vertices();
+                if vertices.len() < 2 {
+                    bail!(
+                        self.span(),
+                        "text path needs a radius or at least two vertices"
+                    );
+                }
+                Guide::polyline(&vertices, resolve)
+            }
+        };
+
+        // Lay out the body on a single line and collect its glyphs with their
+        // horizontal positions.
+        let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+        let line = self.body().layout(vt, styles, pod)?.into_frame();
+        let mut glyphs = vec![];
+        collect(&line, Point::zero(), &mut glyphs);
+        glyphs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let spacing = self.spacing(styles);
+        let count = glyphs.len();
+        let width = glyphs.last().map(|(x, text)| *x + text.width()).unwrap_or_default()
+            + spacing * count.saturating_sub(1) as f64;
+        let offset = match (self.align(styles).0.resolve(styles), &guide) {
+            (Align::Center, Guide::Circle { .. }) => -width / 2.0,
+            (Align::Right, Guide::Circle { .. }) => -width,
+            (Align::Center, Guide::Polyline { length, .. }) => (*length - width) / 2.0,
+            (Align::Right, Guide::Polyline { length, .. }) => *length - width,
+            _ => Abs::zero(),
+        };
+
+        let mut frame = Frame::new(guide.size());
+        for (i, (x, text)) in glyphs.into_iter().enumerate() {
+            let advance = text.width();
+            let s = offset + x + spacing * i as f64 + advance / 2.0;
+            let (point, angle) = guide.at(s);
+
+            let mut glyph = Frame::new(Size::zero());
+            glyph.push(Point::with_x(-advance / 2.0), FrameItem::Text(text));
+            let mut group = GroupItem::new(glyph);
+            group.transform = Transform::rotate(angle);
+            frame.push(point, FrameItem::Group(group));
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// Collect the glyphs in a frame as single-glyph text items, together with
+/// their horizontal positions.
+fn collect(frame: &Frame, offset: Point, glyphs: &mut Vec<(Abs, TextItem)>) {
+    for (pos, item) in frame.items() {
+        let pos = offset + *pos;
+        match item {
+            FrameItem::Group(group) => collect(&group.frame, pos, glyphs),
+            FrameItem::Text(text) => {
+                let mut x = pos.x;
+                for glyph in &text.glyphs {
+                    let single = TextItem {
+                        glyphs: vec![Glyph { x_offset: Em::zero(), ..glyph.clone() }],
+                        ..text.clone()
+                    };
+                    glyphs.push((x + glyph.x_offset.at(text.size), single));
+                    x += glyph.x_advance.at(text.size);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The line the text is placed along.
+enum Guide {
+    /// A circle, traversed clockwise from the start angle.
+    Circle { radius: Abs, start: Angle },
+    /// A flattened path.
+    Polyline { points: Vec<(Abs, Point)>, length: Abs, size: Size },
+}
+
+impl Guide {
+    /// The number of straight segments a single curve is flattened into.
+    const SEGMENTS: usize = 32;
+
+    /// Flatten a path given by its vertices.
+    fn polyline(
+        vertices: &[PathVertex],
+        resolve: impl Fn(Axes<Rel<Length>>) -> Point,
+    ) -> Self {
+        let mut size = Size::zero();
+        let mut points = vec![];
+        let mut length = Abs::zero();
+        let mut push = |point: Point| {
+            if let Some(&(_, prev)) = points.last() {
+                length += (point - prev).hypot();
+            }
+            size.x.set_max(point.x);
+            size.y.set_max(point.y);
+            points.push((length, point));
+        };
+
+        push(resolve(vertices[0].vertex()));
+        for window in vertices.windows(2) {
+            let (from, to) = (window[0], window[1]);
+            let p0 = resolve(from.vertex());
+            let p3 = resolve(to.vertex());
+            let p1 = p0 + resolve(from.control_point_from());
+            let p2 = p3 + resolve(to.control_point_to());
+            for k in 1..=Self::SEGMENTS {
+                let t = k as f64 / Self::SEGMENTS as f64;
+                let u = 1.0 - t;
+                push(
+                    p0 * (u * u * u)
+                        + p1 * (3.0 * u * u * t)
+                        + p2 * (3.0 * u * t * t)
+                        + p3 * (t * t * t),
+                );
+            }
+        }
+
+        Self::Polyline { points, length, size }
+    }
+
+    /// The size of the frame the guide is drawn in.
+    fn size(&self) -> Size {
+        match self {
+            Self::Circle { radius, .. } => Size::splat(*radius * 2.0),
+            Self::Polyline { size, .. } => *size,
+        }
+    }
+
+    /// The point at arc length `s` and the angle of the tangent there.
+    /// Positions beyond the ends of a path continue in a straight line. On a
+    /// circle without radius, all positions fall onto the start angle.
+    fn at(&self, s: Abs) -> (Point, Angle) {
+        match self {
+            Self::Circle { radius, start } => {
+                let theta = if radius.is_zero() {
+                    *start
+                } else {
+                    *start + Angle::rad(s / *radius)
+                };
+                let center = Point::splat(*radius);
+                let point =
+                    center + Point::new(*radius * theta.cos(), *radius * theta.sin());
+                (point, theta + Angle::deg(90.0))
+            }
+            Self::Polyline { points, .. } => {
+                let i = points
+                    .windows(2)
+                    .position(|w| s <= w[1].0)
+                    .unwrap_or(points.len() - 2);
+                let (s0, a) = points[i];
+                let (s1, b) = points[i + 1];
+                let dir = direction(b - a).unwrap_or(Point::with_x(Abs::raw(1.0)));
+                let t = if s1 > s0 { (s - s0) / (s1 - s0) } else { 0.0 };
+                let point = a + (b - a) * t;
+                (point, Angle::rad(dir.y.to_raw().atan2(dir.x.to_raw())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guide_at_zero_radius() {
+        let guide = Guide::Circle { radius: Abs::zero(), start: Angle::deg(-90.0) };
+        for s in [Abs::zero(), Abs::pt(5.0), Abs::pt(-5.0)] {
+            assert_eq!(guide.at(s), (Point::zero(), Angle::zero()));
+        }
+    }
+}
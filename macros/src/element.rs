@@ -32,6 +32,7 @@ struct Field {
     resolve: bool,
     parse: Option<BlockWithReturn>,
     default: syn::Expr,
+    deprecated: Option<syn::LitStr>,
     vis: syn::Visibility,
     ident: Ident,
     ident_in: Ident,
@@ -88,6 +89,7 @@ fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
             default: parse_attr(&mut attrs, "default")?
                 .flatten()
                 .unwrap_or_else(|| parse_quote! { ::std::default::Default::default() }),
+            deprecated: parse_attr(&mut attrs, "deprecated")?.flatten(),
             vis: field.vis.clone(),
             ident: ident.clone(),
             ident_in: Ident::new(&format!("{}_in", ident), ident.span()),
@@ -419,9 +421,11 @@ fn create_param_info(field: &Field) -> TokenStream {
         fold,
         ty,
         output,
+        deprecated,
         ..
     } = field;
     let named = !positional;
+    let deprecated = quote_option(deprecated);
     let settable = field.settable();
     let default_ty = if *fold { &output } else { &ty };
     let default = quote_option(&settable.then(|| {
@@ -448,6 +452,7 @@ fn create_param_info(field: &Field) -> TokenStream {
             variadic: #variadic,
             required: #required,
             settable: #settable,
+            deprecated: #deprecated,
         }
     }
 }
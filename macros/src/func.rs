@@ -32,6 +32,7 @@ struct Param {
     named: bool,
     variadic: bool,
     default: Option<syn::Expr>,
+    deprecated: Option<syn::LitStr>,
     ident: Ident,
     ty: syn::Type,
 }
@@ -78,6 +79,7 @@ fn prepare(stream: TokenStream, item: &syn::ItemFn) -> Result<Func> {
                             || parse_quote! { ::std::default::Default::default() },
                         )
                     }),
+                    deprecated: parse_attr(&mut attrs, "deprecated")?.flatten(),
                     ident: ident.clone(),
                     ty: (*typed.ty).clone(),
                 });
@@ -207,9 +209,19 @@ fn create(func: &Func, item: &syn::ItemFn) -> TokenStream {
 
 /// Create a parameter info for a field.
 fn create_param_info(param: &Param) -> TokenStream {
-    let Param { name, docs, named, variadic, ty, default, .. } = param;
+    let Param {
+        name,
+        docs,
+        named,
+        variadic,
+        ty,
+        default,
+        deprecated,
+        ..
+    } = param;
     let positional = !named;
     let required = default.is_none();
+    let deprecated = quote_option(deprecated);
     let default = quote_option(&default.as_ref().map(|_default| {
         quote! {
             || {
@@ -234,6 +246,7 @@ fn create_param_info(param: &Param) -> TokenStream {
             variadic: #variadic,
             required: #required,
             settable: false,
+            deprecated: #deprecated,
         }
     }
 }
@@ -114,6 +114,39 @@ impl SourceError {
     }
 }
 
+/// A warning about a likely mistake in a source file.
+///
+/// Unlike errors, warnings don't prevent the document from being compiled.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SourceWarning {
+    /// The span of the node the warning is about.
+    pub span: Span,
+    /// A diagnostic message describing the problem.
+    pub message: EcoString,
+    /// Additional hints on how to resolve the problem.
+    pub hints: Vec<EcoString>,
+}
+
+impl SourceWarning {
+    /// Create a new warning.
+    pub fn new(span: Span, message: impl Into<EcoString>) -> Self {
+        Self { span, message: message.into(), hints: vec![] }
+    }
+
+    /// Attach a hint to the warning.
+    pub fn with_hint(mut self, hint: impl Into<EcoString>) -> Self {
+        self.hints.push(hint.into());
+        self
+    }
+
+    /// The range in the source file identified by
+    /// [`self.span.source()`](Span::source) where the warning should be
+    /// annotated.
+    pub fn range(&self, world: &dyn World) -> Range<usize> {
+        world.source(self.span.source()).range(self.span)
+    }
+}
+
 /// A part of an error's [trace](SourceError::trace).
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Tracepoint {
@@ -144,6 +177,19 @@ impl Display for Tracepoint {
     }
 }
 
+/// Fail with an error if the world's compilation was cancelled.
+///
+/// The error is annotated at the start of the main source file.
+pub fn check_cancelled(world: Tracked<dyn World + '_>) -> SourceResult<()> {
+    if world.cancelled() {
+        let span = world.main().root().span();
+        let error =
+            SourceError::new(span, "compilation was cancelled").with_pos(ErrorPos::Start);
+        return Err(Box::new(vec![error]));
+    }
+    Ok(())
+}
+
 /// Enrich a [`SourceResult`] with a tracepoint.
 pub trait Trace<T> {
     /// Add the tracepoint to all errors that lie outside the `span`.
@@ -210,6 +256,8 @@ pub enum FileError {
     NotSource,
     /// The file was not valid UTF-8, but should have been.
     InvalidUtf8,
+    /// A file could not be downloaded.
+    Network(EcoString),
     /// Another error.
     Other,
 }
@@ -242,6 +290,7 @@ impl Display for FileError {
             Self::IsDirectory => f.pad("failed to load file (is a directory)"),
             Self::NotSource => f.pad("not a typst source file"),
             Self::InvalidUtf8 => f.pad("file is not valid utf-8"),
+            Self::Network(message) => write!(f, "failed to download file ({message})"),
             Self::Other => f.pad("failed to load file"),
         }
     }
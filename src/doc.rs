@@ -8,11 +8,13 @@ use std::sync::Arc;
 
 use ecow::EcoString;
 
-use crate::eval::{cast, dict, Dict, Value};
+use crate::diag::bail;
+use crate::eval::{cast, dict, Dict, IntoValue, Value};
 use crate::font::Font;
 use crate::geom::{
-    self, rounded_rect, Abs, Align, Axes, Color, Corners, Dir, Em, Geometry, Length,
-    Numeric, Paint, Point, Rel, RgbaColor, Shape, Sides, Size, Stroke, Transform,
+    self, rounded_rect, Abs, Align, Axes, Color, Corners, DashPattern, Dir, Em, Geometry,
+    Length, Numeric, Paint, Point, Ratio, Rel, RgbaColor, Scalar, Shape, Sides, Size,
+    Stroke, Transform,
 };
 use crate::image::Image;
 use crate::model::{Content, Location, MetaElem, StyleChain};
@@ -264,11 +266,12 @@ impl Frame {
     /// Attach metadata from an iterator.
     pub fn meta_iter(&mut self, iter: impl IntoIterator<Item = Meta>) {
         let mut hide = false;
+        let mut artifact = false;
         for meta in iter {
-            if matches!(meta, Meta::Hide) {
-                hide = true;
-            } else {
-                self.prepend(Point::zero(), FrameItem::Meta(meta, self.size));
+            match meta {
+                Meta::Hide => hide = true,
+                Meta::Artifact => artifact = true,
+                meta => self.prepend(Point::zero(), FrameItem::Meta(meta, self.size)),
             }
         }
         if hide {
@@ -276,6 +279,28 @@ impl Frame {
                 matches!(item, FrameItem::Group(_) | FrameItem::Meta(Meta::Elem(_), _))
             });
         }
+        if artifact {
+            self.artifact();
+        }
+    }
+
+    /// Attach an explanation of why the line or region this frame holds ended.
+    ///
+    /// Empty frames stay empty, since layout treats them differently.
+    pub fn explain(&mut self, explanation: Break) {
+        if !self.is_empty() {
+            let item = FrameItem::Meta(Meta::Break(explanation), Size::zero());
+            self.push(Point::zero(), item);
+        }
+    }
+
+    /// Mark the bounds of the region this frame was laid out into.
+    ///
+    /// Empty frames stay empty, since layout treats them differently.
+    pub fn mark_region(&mut self, region: Size) {
+        if !self.is_empty() {
+            self.push(Point::zero(), FrameItem::Meta(Meta::Region, region));
+        }
     }
 
     /// Add a background fill.
@@ -320,6 +345,28 @@ impl Frame {
         }
     }
 
+    /// Mark the contents of a frame as an artifact.
+    pub fn artifact(&mut self) {
+        if !self.is_empty() {
+            self.group(|g| g.artifact = true);
+        }
+    }
+
+    /// Set the position of the frame's contents in the reading order of the
+    /// page.
+    pub fn reading_order(&mut self, order: i64) {
+        if !self.is_empty() {
+            self.group(|g| g.order = Some(order));
+        }
+    }
+
+    /// Apply raster filters to the contents of a frame.
+    pub fn filter(&mut self, filters: Vec<Filter>) {
+        if !self.is_empty() && !filters.is_empty() {
+            self.group(|g| g.filters = filters);
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -362,6 +409,54 @@ impl Frame {
         self
     }
 
+    /// Outline this frame, all nested frames, the regions they were laid out
+    /// into and all text runs and mark their baselines, for diagnosing spacing
+    /// issues.
+    ///
+    /// Frames are outlined in blue, regions dashed in fuchsia, text runs in
+    /// green and baselines in red.
+    pub fn debug_outlines(&mut self) {
+        let mut outlines = vec![];
+        for (pos, item) in Arc::make_mut(&mut self.items) {
+            match item {
+                FrameItem::Group(group) => group.frame.debug_outlines(),
+                FrameItem::Meta(Meta::Region, size) => {
+                    let stroke = Stroke {
+                        dash_pattern: Some(DashPattern {
+                            array: vec![Abs::pt(2.0), Abs::pt(2.0)],
+                            phase: Abs::zero(),
+                        }),
+                        ..debug_stroke(Color::FUCHSIA)
+                    };
+                    outlines.push((*pos, Geometry::Rect(*size).stroked(stroke)));
+                }
+                FrameItem::Text(text) => {
+                    let metrics = text.font.metrics();
+                    let ascent = metrics.ascender.at(text.size);
+                    let descent = -metrics.descender.at(text.size);
+                    let size = Size::new(text.width(), ascent + descent);
+                    let outline =
+                        Geometry::Rect(size).stroked(debug_stroke(Color::GREEN));
+                    outlines.push((*pos - Point::with_y(ascent), outline));
+                }
+                _ => {}
+            }
+        }
+
+        for (pos, outline) in outlines {
+            self.push(pos, FrameItem::Shape(outline, Span::detached()));
+        }
+
+        let outline = Geometry::Rect(self.size).stroked(debug_stroke(Color::BLUE));
+        self.push(Point::zero(), FrameItem::Shape(outline, Span::detached()));
+
+        if let Some(baseline) = self.baseline {
+            let line = Geometry::Line(Point::with_x(self.size.x))
+                .stroked(debug_stroke(Color::RED));
+            self.push(Point::with_y(baseline), FrameItem::Shape(line, Span::detached()));
+        }
+    }
+
     /// Add a green marker at a position for debugging.
     pub fn mark_point(&mut self, pos: Point) {
         let radius = Abs::pt(2.0);
@@ -390,6 +485,15 @@ impl Frame {
     }
 }
 
+/// A thin stroke for debugging outlines.
+fn debug_stroke(color: Color) -> Stroke {
+    Stroke {
+        paint: color.into(),
+        thickness: Abs::pt(0.25),
+        ..Stroke::default()
+    }
+}
+
 impl Debug for Frame {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str("Frame ")?;
@@ -435,6 +539,15 @@ pub struct GroupItem {
     pub transform: Transform,
     /// Whether the frame should be a clipping boundary.
     pub clips: bool,
+    /// Raster filters to apply to the group's rendered contents, in order.
+    pub filters: Vec<Filter>,
+    /// Whether the group's contents are decorative, so that assistive
+    /// technology should skip them.
+    pub artifact: bool,
+    /// The group's position in the reading order of its page, if it was
+    /// specified explicitly. Content without an explicit position has the
+    /// position zero.
+    pub order: Option<i64>,
 }
 
 impl GroupItem {
@@ -444,10 +557,28 @@ impl GroupItem {
             frame,
             transform: Transform::identity(),
             clips: false,
+            filters: vec![],
+            artifact: false,
+            order: None,
         }
     }
 }
 
+/// A raster filter applied to the rendered contents of a group.
+///
+/// Filters are applied by the raster exporter. The SVG exporter writes
+/// equivalent SVG filters and the PDF exporter embeds filtered groups as
+/// raster images.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Filter {
+    /// A gaussian blur with the given standard deviation.
+    Blur(Abs),
+    /// Convert the contents to shades of gray.
+    Grayscale,
+    /// Scale the brightness of the contents by a factor.
+    Brightness(Ratio),
+}
+
 impl Debug for GroupItem {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str("Group ")?;
@@ -466,6 +597,8 @@ pub struct TextItem {
     pub fill: Paint,
     /// The natural language of the text.
     pub lang: Lang,
+    /// The region of the text's language, if any.
+    pub region: Option<Region>,
     /// The item's plain text.
     pub text: EcoString,
     /// The glyphs.
@@ -628,6 +761,15 @@ pub enum Meta {
     /// in the final frames as it is removed alongside the content that should
     /// be hidden.
     Hide,
+    /// Indicates that content is decorative. This variant doesn't appear in
+    /// the final frames as the content is wrapped in an artifact group
+    /// instead.
+    Artifact,
+    /// Explains why the line or region whose frame this is attached to ended.
+    Break(Break),
+    /// Marks the bounds of the region that a flow was laid out into, which
+    /// may be larger than the flow's frame.
+    Region,
 }
 
 cast! {
@@ -641,6 +783,67 @@ impl Debug for Meta {
             Self::Elem(content) => write!(f, "Elem({:?})", content.func()),
             Self::PageNumbering(value) => write!(f, "PageNumbering({value:?})"),
             Self::Hide => f.pad("Hide"),
+            Self::Artifact => f.pad("Artifact"),
+            Self::Break(explanation) => write!(f, "Break({explanation:?})"),
+            Self::Region => f.pad("Region"),
+        }
+    }
+}
+
+/// Explains why a line, column or page ended.
+///
+/// Layout attaches these to the frames of the lines and regions it breaks, so
+/// that the final frames carry an explanation of each break decision.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Break {
+    /// What ended.
+    pub kind: BreakKind,
+    /// Why it ended.
+    pub reason: &'static str,
+    /// The text of a line, since its frame may be inlined into its parent's.
+    pub text: Option<EcoString>,
+    /// The height that was needed and the height that was available, for
+    /// regions that ended because something did not fit.
+    pub space: Option<(Abs, Abs)>,
+    /// The cost and adjustment ratio of a line chosen by optimized line
+    /// breaking.
+    pub cost: Option<(Scalar, Scalar)>,
+}
+
+impl Break {
+    /// Create a new explanation without measurements.
+    pub fn new(kind: BreakKind, reason: &'static str) -> Self {
+        Self { kind, reason, text: None, space: None, cost: None }
+    }
+}
+
+/// What a [`Break`] explains.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BreakKind {
+    /// A line of a paragraph.
+    Line,
+    /// The last line of a paragraph.
+    Paragraph,
+    /// The first two lines of a paragraph that were kept together.
+    Orphan,
+    /// The last two lines of a paragraph that were kept together.
+    Widow,
+    /// A region, like a column or the area of a page.
+    Region,
+    /// A page run.
+    Page,
+}
+
+impl BreakKind {
+    /// The lowercase name of the kind.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Line => "line",
+            Self::Paragraph => "paragraph",
+            Self::Orphan => "orphan",
+            Self::Widow => "widow",
+            Self::Region => "region",
+            Self::Page => "page",
         }
     }
 }
@@ -654,6 +857,8 @@ pub enum Destination {
     Position(Position),
     /// An unresolved link to a location in the document.
     Location(Location),
+    /// A link into another PDF file.
+    Remote(Remote),
 }
 
 cast! {
@@ -662,9 +867,14 @@ cast! {
         Self::Url(v) => v.into_value(),
         Self::Position(v) => v.into_value(),
         Self::Location(v) => v.into_value(),
+        Self::Remote(v) => v.into_value(),
     },
     v: EcoString => Self::Url(v),
-    v: Position => Self::Position(v),
+    v: Dict => if v.contains("file") {
+        Self::Remote(Value::Dict(v).cast()?)
+    } else {
+        Self::Position(Value::Dict(v).cast()?)
+    },
     v: Location => Self::Location(v),
 }
 
@@ -699,6 +909,54 @@ impl From<Position> for Dict {
     }
 }
 
+/// A destination in another PDF file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Remote {
+    /// The path of the file, relative to the linking file.
+    pub file: EcoString,
+    /// Where in the file to go.
+    pub target: RemoteTarget,
+}
+
+/// Where a link into another PDF file goes.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum RemoteTarget {
+    /// The top of a page, starting at 1.
+    Page(NonZeroUsize),
+    /// A named destination, defined by the file. Typst names the destinations
+    /// in its PDFs after the labels in the document.
+    Name(EcoString),
+}
+
+cast! {
+    Remote,
+    self => Value::Dict(self.into()),
+    mut dict: Dict => {
+        let file = dict.take("file")?.cast()?;
+        let page = dict.take("page").ok().map(Value::cast).transpose()?;
+        let name = dict.take("name").ok().map(Value::cast).transpose()?;
+        dict.finish(&["file", "page", "name"])?;
+        let target = match (page, name) {
+            (Some(_), Some(_)) => bail!("cannot link to both a page and a name"),
+            (Some(page), None) => RemoteTarget::Page(page),
+            (None, Some(name)) => RemoteTarget::Name(name),
+            (None, None) => RemoteTarget::Page(NonZeroUsize::ONE),
+        };
+        Self { file, target }
+    },
+}
+
+impl From<Remote> for Dict {
+    fn from(remote: Remote) -> Self {
+        let mut dict = dict! { "file" => remote.file };
+        match remote.target {
+            RemoteTarget::Page(page) => dict.insert("page".into(), page.into_value()),
+            RemoteTarget::Name(name) => dict.insert("name".into(), name.into_value()),
+        }
+        dict
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
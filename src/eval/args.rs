@@ -1,4 +1,5 @@
 use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
 
 use ecow::{eco_format, EcoVec};
 
@@ -28,6 +29,15 @@ pub struct Arg {
 }
 
 impl Args {
+    /// Feed the span-independent structure of the arguments into the hasher.
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.items.len());
+        for arg in &self.items {
+            arg.name.hash(state);
+            arg.value.v.hash_structure(state);
+        }
+    }
+
     /// Create positional arguments from a span and values.
     pub fn new<T: IntoValue>(span: Span, values: impl IntoIterator<Item = T>) -> Self {
         let items = values
@@ -1,11 +1,14 @@
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
 use std::ops::{Add, AddAssign};
 
 use ecow::{eco_format, EcoString, EcoVec};
 
-use super::{ops, Args, CastInfo, FromValue, Func, IntoValue, Reflect, Value, Vm};
+use super::collate::Collator;
+use super::{ops, Args, CastInfo, FromValue, Func, IntoValue, Range, Reflect, Value, Vm};
 use crate::diag::{At, SourceResult, StrResult};
+use crate::doc::Lang;
 use crate::syntax::Span;
 use crate::util::pretty_array_like;
 
@@ -149,6 +152,94 @@ impl Array {
         self.0.contains(value)
     }
 
+    /// Whether the array contains an item with the same key as the value.
+    pub fn contains_by(
+        &self,
+        vm: &mut Vm,
+        value: Value,
+        key: Option<Func>,
+    ) -> SourceResult<bool> {
+        let Some(key) = key else { return Ok(self.contains(&value)) };
+        let wanted = apply_key(vm, Some(&key), value)?;
+        Ok(self.keys(vm, Some(&key))?.contains(&wanted))
+    }
+
+    /// Remove duplicate items, keeping the first occurrence of each.
+    ///
+    /// Two items are duplicates if their keys are equal. Without a key
+    /// function, the items themselves are compared.
+    pub fn dedup(&self, vm: &mut Vm, key: Option<Func>) -> SourceResult<Self> {
+        let keys = self.keys(vm, key.as_ref())?;
+        let mut seen = vec![];
+        let mut kept = EcoVec::new();
+        for (item, key) in self.iter().zip(keys) {
+            if !seen.contains(&key) {
+                seen.push(key);
+                kept.push(item.clone());
+            }
+        }
+        Ok(kept.into())
+    }
+
+    /// The items that are in this or the other array, without duplicates.
+    pub fn union(
+        &self,
+        vm: &mut Vm,
+        other: Array,
+        key: Option<Func>,
+    ) -> SourceResult<Self> {
+        (self.clone() + other).dedup(vm, key)
+    }
+
+    /// The items of this array that are also in the other one, without
+    /// duplicates.
+    pub fn intersect(
+        &self,
+        vm: &mut Vm,
+        other: Array,
+        key: Option<Func>,
+    ) -> SourceResult<Self> {
+        self.select(vm, other, key, true)
+    }
+
+    /// The items of this array that aren't in the other one, without
+    /// duplicates.
+    pub fn difference(
+        &self,
+        vm: &mut Vm,
+        other: Array,
+        key: Option<Func>,
+    ) -> SourceResult<Self> {
+        self.select(vm, other, key, false)
+    }
+
+    /// Keep the items depending on whether the other array has an item with
+    /// the same key.
+    fn select(
+        &self,
+        vm: &mut Vm,
+        other: Array,
+        key: Option<Func>,
+        shared: bool,
+    ) -> SourceResult<Self> {
+        let theirs = other.keys(vm, key.as_ref())?;
+        let ours = self.keys(vm, key.as_ref())?;
+        let mut seen = vec![];
+        let mut kept = EcoVec::new();
+        for (item, key) in self.iter().zip(ours) {
+            if theirs.contains(&key) == shared && !seen.contains(&key) {
+                seen.push(key);
+                kept.push(item.clone());
+            }
+        }
+        Ok(kept.into())
+    }
+
+    /// The keys of the items, as determined by an optional key function.
+    fn keys(&self, vm: &mut Vm, key: Option<&Func>) -> SourceResult<Vec<Value>> {
+        self.iter().map(|item| apply_key(vm, key, item.clone())).collect()
+    }
+
     /// Return the first matching item.
     pub fn find(&self, vm: &mut Vm, func: Func) -> SourceResult<Option<Value>> {
         for item in self.iter() {
@@ -267,10 +358,13 @@ impl Array {
     pub fn flatten(&self) -> Self {
         let mut flat = EcoVec::with_capacity(self.0.len());
         for item in self.iter() {
-            if let Value::Array(nested) = item {
-                flat.extend(nested.flatten().into_iter());
-            } else {
-                flat.push(item.clone());
+            match item {
+                Value::Array(nested) => flat.extend(nested.flatten().into_iter()),
+                Value::Dyn(dynamic) if dynamic.is::<Range>() => {
+                    let range = dynamic.downcast::<Range>().unwrap();
+                    flat.extend(range.iter().map(Value::Int));
+                }
+                _ => flat.push(item.clone()),
             }
         }
         flat.into()
@@ -311,18 +405,41 @@ impl Array {
         Ok(result)
     }
 
-    /// Zips the array with another array. If the two arrays are of unequal length, it will only
-    /// zip up until the last element of the smaller array and the remaining elements will be
-    /// ignored. The return value is an array where each element is yet another array of size 2.
-    pub fn zip(&self, other: Array) -> Array {
-        self.iter()
-            .zip(other)
-            .map(|(first, second)| array![first.clone(), second].into_value())
+    /// Zips the array with other arrays. If the arrays are of unequal length, it will only
+    /// zip up until the last element of the shortest array and the remaining elements will be
+    /// ignored. The return value is an array where each element is yet another array with one
+    /// value from each of the arrays.
+    pub fn zip(&self, others: Vec<Array>) -> Array {
+        let len = others.iter().map(Array::len).fold(self.len(), usize::min);
+        (0..len)
+            .map(|i| {
+                std::iter::once(&self.0[i])
+                    .chain(others.iter().map(|other| &other.0[i]))
+                    .cloned()
+                    .collect::<Array>()
+                    .into_value()
+            })
+            .collect()
+    }
+
+    /// Splits the array into consecutive chunks of the given size.
+    ///
+    /// The last chunk may be shorter unless `exact` is set, in which case the
+    /// remaining values are dropped.
+    pub fn chunks(&self, size: NonZeroUsize, exact: bool) -> Array {
+        let size = size.get();
+        self.0
+            .chunks(size)
+            .filter(|chunk| !exact || chunk.len() == size)
+            .map(|chunk| Array::from(chunk).into_value())
             .collect()
     }
 
     /// Return a sorted version of this array, optionally by a given key function.
     ///
+    /// If a locale is given, strings are compared according to the alphabet of
+    /// that language instead of by code point.
+    ///
     /// Returns an error if two values could not be compared or if the key function (if given)
     /// yields an error.
     pub fn sorted(
@@ -330,7 +447,13 @@ impl Array {
         vm: &mut Vm,
         span: Span,
         key: Option<Func>,
+        locale: Option<Lang>,
     ) -> SourceResult<Self> {
+        let collator = locale.map(Collator::new);
+        let compare = |a: &Value, b: &Value| match (&collator, a, b) {
+            (Some(collator), Value::Str(a), Value::Str(b)) => Ok(collator.compare(a, b)),
+            _ => typst::eval::ops::compare(a, b),
+        };
         let mut result = Ok(());
         let mut vec = self.0.clone();
         let mut key_of = |x: Value| match &key {
@@ -342,14 +465,12 @@ impl Array {
         vec.make_mut().sort_by(|a, b| {
             // Until we get `try` blocks :)
             match (key_of(a.clone()), key_of(b.clone())) {
-                (Ok(a), Ok(b)) => {
-                    typst::eval::ops::compare(&a, &b).unwrap_or_else(|err| {
-                        if result.is_ok() {
-                            result = Err(err).at(span);
-                        }
-                        Ordering::Equal
-                    })
-                }
+                (Ok(a), Ok(b)) => compare(&a, &b).unwrap_or_else(|err| {
+                    if result.is_ok() {
+                        result = Err(err).at(span);
+                    }
+                    Ordering::Equal
+                }),
                 (Err(e), _) | (_, Err(e)) => {
                     if result.is_ok() {
                         result = Err(e);
@@ -392,10 +513,16 @@ impl Array {
     }
 
     /// Enumerate all items in the array.
-    pub fn enumerate(&self) -> Self {
+    pub fn enumerate(&self, start: i64) -> StrResult<Self> {
         self.iter()
             .enumerate()
-            .map(|(i, value)| array![i, value.clone()].into_value())
+            .map(|(i, value)| -> StrResult<Value> {
+                let i = i64::try_from(i)
+                    .ok()
+                    .and_then(|i| start.checked_add(i))
+                    .ok_or("array index is too large")?;
+                Ok(array![i, value.clone()].into_value())
+            })
             .collect()
     }
 }
@@ -492,6 +619,14 @@ fn array_is_empty() -> EcoString {
     "array is empty".into()
 }
 
+/// Apply an optional key function to a value.
+fn apply_key(vm: &mut Vm, key: Option<&Func>, value: Value) -> SourceResult<Value> {
+    match key {
+        Some(f) => f.call_vm(vm, Args::new(f.span(), [value])),
+        None => Ok(value),
+    }
+}
+
 /// The out of bounds access error message.
 #[cold]
 fn out_of_bounds(index: i64, len: usize) -> EcoString {
@@ -0,0 +1,223 @@
+use std::cmp::Ordering;
+
+use crate::doc::Lang;
+
+/// Compares strings according to the alphabet of a language.
+///
+/// This is a lightweight take on the Unicode Collation Algorithm for Latin
+/// scripts: Strings are first compared by their base letters, ignoring accents
+/// and case. Only if those are equal, accents and then case break the tie.
+/// Languages that treat some accented letters as separate letters of their
+/// alphabet, like the Swedish `å`, `ä` and `ö` after `z`, sort those
+/// accordingly. Characters that aren't known are compared by code point.
+#[derive(Debug, Copy, Clone)]
+pub struct Collator {
+    tailoring: &'static [(char, &'static [&'static str])],
+    /// Whether the language pairs the dotless `ı` with `I` and the dotted `i`
+    /// with `İ`.
+    turkic: bool,
+}
+
+impl Collator {
+    /// Create a collator for the given language.
+    pub fn new(lang: Lang) -> Self {
+        let tailoring: &[(char, &[&str])] = match lang.as_str() {
+            "sv" | "fi" => &[('z', &["å", "ä", "ö"])],
+            "da" | "nb" | "nn" | "no" => &[('z', &["æ", "ø", "å"])],
+            "es" => &[('n', &["ñ"])],
+            "cs" => &[
+                ('c', &["č"]),
+                ('h', &["ch"]),
+                ('r', &["ř"]),
+                ('s', &["š"]),
+                ('z', &["ž"]),
+            ],
+            "pl" => &[
+                ('a', &["ą"]),
+                ('c', &["ć"]),
+                ('e', &["ę"]),
+                ('l', &["ł"]),
+                ('n', &["ń"]),
+                ('o', &["ó"]),
+                ('s', &["ś"]),
+                ('z', &["ź", "ż"]),
+            ],
+            "tr" => &[
+                ('c', &["ç"]),
+                ('g', &["ğ"]),
+                ('h', &["ı"]),
+                ('o', &["ö"]),
+                ('s', &["ş"]),
+                ('u', &["ü"]),
+            ],
+            _ => &[],
+        };
+        let turkic = matches!(lang.as_str(), "tr" | "az");
+        Self { tailoring, turkic }
+    }
+
+    /// Compare two strings.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let a = self.elements(a);
+        let b = self.elements(b);
+        let level = |f: fn(&Element) -> u32| a.iter().map(f).cmp(b.iter().map(f));
+        level(|e| e.primary)
+            .then_with(|| level(|e| e.secondary))
+            .then_with(|| level(|e| e.tertiary))
+    }
+
+    /// Split a string into its collation elements.
+    fn elements(&self, text: &str) -> Vec<Element> {
+        let mut lower = vec![];
+        let mut upper = vec![];
+        for c in text.chars() {
+            match c {
+                'I' if self.turkic => lower.push('ı'),
+                'İ' if self.turkic => lower.push('i'),
+                _ => lower.extend(c.to_lowercase()),
+            }
+            upper.resize(lower.len(), c.is_uppercase());
+        }
+
+        let mut elements = vec![];
+        let mut i = 0;
+        while i < lower.len() {
+            let tertiary = u32::from(upper[i]);
+            if let Some((len, primary)) = self.tailored(&lower[i..]) {
+                elements.push(Element { primary, secondary: 0, tertiary });
+                i += len;
+                continue;
+            }
+
+            let c = lower[i];
+            if let Some(expansion) = expand(c) {
+                for base in expansion.chars() {
+                    let primary = weight(base);
+                    elements.push(Element { primary, secondary: 1, tertiary });
+                }
+            } else {
+                let (base, secondary) = decompose(c);
+                elements.push(Element { primary: weight(base), secondary, tertiary });
+            }
+            i += 1;
+        }
+
+        elements
+    }
+
+    /// The length and primary weight of the longest tailored letter at the
+    /// start of the text.
+    fn tailored(&self, text: &[char]) -> Option<(usize, u32)> {
+        let mut best: Option<(usize, u32)> = None;
+        for &(after, letters) in self.tailoring {
+            for (k, letter) in letters.iter().enumerate() {
+                let len = letter.chars().count();
+                if text.len() >= len
+                    && letter.chars().eq(text[..len].iter().copied())
+                    && best.map_or(true, |(best, _)| len > best)
+                {
+                    best = Some((len, weight(after) + k as u32 + 1));
+                }
+            }
+        }
+        best
+    }
+}
+
+/// A collation element with weights for the three comparison levels.
+#[derive(Debug, Copy, Clone)]
+struct Element {
+    /// The base letter.
+    primary: u32,
+    /// The accent.
+    secondary: u32,
+    /// The case.
+    tertiary: u32,
+}
+
+/// The primary weight of a base character.
+///
+/// Leaves room for tailored letters between consecutive characters.
+fn weight(base: char) -> u32 {
+    (base as u32) << 8
+}
+
+/// Letters that sort like several base letters.
+fn expand(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        _ => return None,
+    })
+}
+
+/// Split a lowercase letter into its base letter and the rank of its accent.
+fn decompose(c: char) -> (char, u32) {
+    const ACCENTED: &[(char, &str)] = &[
+        ('a', "àáâãäåāăą"),
+        ('c', "çćĉċč"),
+        ('d', "ďđ"),
+        ('e', "èéêëēĕėęě"),
+        ('g', "ĝğġģ"),
+        ('h', "ĥħ"),
+        ('i', "ìíîïĩīĭįı"),
+        ('j', "ĵ"),
+        ('k', "ķ"),
+        ('l', "ĺļľŀł"),
+        ('n', "ñńņňŉ"),
+        ('o', "òóôõöøōŏő"),
+        ('r', "ŕŗř"),
+        ('s', "śŝşš"),
+        ('t', "ţťŧ"),
+        ('u', "ùúûüũūŭůűų"),
+        ('w', "ŵ"),
+        ('y', "ýÿŷ"),
+        ('z', "źżž"),
+    ];
+
+    for &(base, variants) in ACCENTED {
+        if let Some(i) = variants.chars().position(|v| v == c) {
+            return (base, i as u32 + 1);
+        }
+    }
+
+    (c, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[track_caller]
+    fn test(lang: &str, words: &[&str]) {
+        let collator = Collator::new(Lang::from_str(lang).unwrap());
+        let mut sorted = words.to_vec();
+        sorted.sort_by(|a, b| collator.compare(a, b));
+        assert_eq!(sorted, words);
+    }
+
+    #[test]
+    fn test_collate_root() {
+        test("en", &["Äpfel", "apple", "banana", "Zebra", "zoo"]);
+        test("en", &["cote", "Cote", "côte"]);
+        test("de", &["Masse", "Maße", "Mast"]);
+    }
+
+    #[test]
+    fn test_collate_tailored() {
+        test("sv", &["Zorn", "Åsa", "Ängel", "Örn"]);
+        test("da", &["Zink", "Ærø", "Øst", "Århus"]);
+        test("es", &["nube", "Núñez", "ñandú"]);
+        test("cs", &["hrad", "chata", "ibis"]);
+        test("pl", &["lato", "łąka", "mama"]);
+    }
+
+    #[test]
+    fn test_collate_turkish_case() {
+        test("tr", &["Hasan", "ılık", "Irmak", "ikna", "İstanbul"]);
+        test("tr", &["kış", "KIŞ", "kiş", "KİŞ"]);
+    }
+}
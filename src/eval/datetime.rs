@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
@@ -6,6 +7,8 @@ use ecow::{eco_format, EcoString, EcoVec};
 use time::error::{Format, InvalidFormatDescription};
 use time::{format_description, PrimitiveDateTime};
 
+use super::Duration;
+use crate::diag::{bail, StrResult};
 use crate::eval::cast;
 use crate::util::pretty_array_like;
 
@@ -134,6 +137,61 @@ impl Datetime {
         let time = time::Time::from_hms(hour, minute, second).ok()?;
         Some(Datetime::Datetime(PrimitiveDateTime::new(date, time)))
     }
+
+    /// Move the datetime forward by a duration.
+    ///
+    /// Times wrap around at midnight. Dates can only be moved by whole days.
+    pub fn add_duration(self, duration: Duration) -> StrResult<Self> {
+        let duration = time::Duration::from(duration);
+        Ok(match self {
+            Datetime::Date(date) => {
+                if duration.whole_seconds() % (24 * 60 * 60) != 0
+                    || duration.subsec_nanoseconds() != 0
+                {
+                    bail!("cannot add a duration with a time component to a date");
+                }
+                Datetime::Date(date.checked_add(duration).ok_or("date is out of range")?)
+            }
+            Datetime::Time(time) => Datetime::Time(time + duration),
+            Datetime::Datetime(datetime) => Datetime::Datetime(
+                datetime.checked_add(duration).ok_or("datetime is out of range")?,
+            ),
+        })
+    }
+
+    /// Move the datetime backward by a duration.
+    pub fn sub_duration(self, duration: Duration) -> StrResult<Self> {
+        self.add_duration(duration.checked_neg().ok_or("duration is too large")?)
+    }
+
+    /// The duration between this and an earlier datetime of the same kind.
+    pub fn duration_since(self, other: Self) -> StrResult<Duration> {
+        Ok(match (self, other) {
+            (Datetime::Date(a), Datetime::Date(b)) => (a - b).into(),
+            (Datetime::Time(a), Datetime::Time(b)) => (a - b).into(),
+            (Datetime::Datetime(a), Datetime::Datetime(b)) => (a - b).into(),
+            _ => bail!("cannot subtract a {} from a {}", other.kind(), self.kind()),
+        })
+    }
+
+    /// Compare with another datetime of the same kind.
+    pub fn ordering(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Datetime::Date(a), Datetime::Date(b)) => Some(a.cmp(b)),
+            (Datetime::Time(a), Datetime::Time(b)) => Some(a.cmp(b)),
+            (Datetime::Datetime(a), Datetime::Datetime(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    /// A name for the kind of the datetime.
+    fn kind(&self) -> &'static str {
+        match self {
+            Datetime::Date(_) => "date",
+            Datetime::Time(_) => "time",
+            Datetime::Datetime(_) => "datetime",
+        }
+    }
 }
 
 impl Debug for Datetime {
@@ -7,6 +7,7 @@ use ecow::{eco_format, EcoString};
 
 use super::{array, Array, Str, Value};
 use crate::diag::StrResult;
+use crate::model::Fold;
 use crate::syntax::is_ident;
 use crate::util::{pretty_array_like, separated_list, ArcExt};
 
@@ -65,9 +66,12 @@ impl Dict {
     }
 
     /// Remove the value if the dictionary contains the given key.
+    ///
+    /// Like [`remove`](Self::remove), this keeps the remaining pairs in
+    /// insertion order.
     pub fn take(&mut self, key: &str) -> StrResult<Value> {
         Arc::make_mut(&mut self.0)
-            .remove(key)
+            .shift_remove(key)
             .ok_or_else(|| eco_format!("missing key: {:?}", Str::from(key)))
     }
 
@@ -188,6 +192,15 @@ impl Extend<(Str, Value)> for Dict {
     }
 }
 
+impl Fold for Dict {
+    type Output = Self;
+
+    fn fold(self, mut outer: Self::Output) -> Self::Output {
+        outer.extend(self);
+        outer
+    }
+}
+
 impl FromIterator<(Str, Value)> for Dict {
     fn from_iter<T: IntoIterator<Item = (Str, Value)>>(iter: T) -> Self {
         Self(Arc::new(iter.into_iter().collect()))
@@ -233,3 +246,115 @@ fn missing_key_no_default(key: &str) -> EcoString {
         Str::from(key)
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(String, i64),
+        Remove(String),
+        Take(String),
+        Clear,
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let key = "[a-e]{1,2}";
+        prop_oneof![
+            4 => (key, any::<i64>()).prop_map(|(k, v)| Op::Insert(k, v)),
+            2 => key.prop_map(Op::Remove),
+            2 => key.prop_map(Op::Take),
+            1 => Just(Op::Clear),
+        ]
+    }
+
+    fn pairs() -> impl Strategy<Value = Vec<(String, i64)>> {
+        prop::collection::vec(("[a-e]{1,2}", any::<i64>()), 0..16)
+    }
+
+    /// Apply an operation to a list of pairs in insertion order, which is what
+    /// the dictionary models.
+    fn apply(model: &mut Vec<(String, i64)>, op: &Op) -> Option<i64> {
+        match op {
+            Op::Insert(key, value) => {
+                match model.iter_mut().find(|(k, _)| k == key) {
+                    Some((_, v)) => *v = *value,
+                    None => model.push((key.clone(), *value)),
+                }
+                None
+            }
+            Op::Remove(key) | Op::Take(key) => {
+                let i = model.iter().position(|(k, _)| k == key)?;
+                Some(model.remove(i).1)
+            }
+            Op::Clear => {
+                model.clear();
+                None
+            }
+        }
+    }
+
+    fn to_dict(pairs: &[(String, i64)]) -> Dict {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.as_str().into(), Value::Int(*v)))
+            .collect()
+    }
+
+    fn assert_matches(dict: &Dict, model: &[(String, i64)]) -> Result<(), TestCaseError> {
+        prop_assert_eq!(dict.len(), model.len());
+        prop_assert_eq!(dict.is_empty(), model.is_empty());
+        let keys: Vec<_> = dict.iter().map(|(k, _)| k.as_str()).collect();
+        let expected: Vec<_> = model.iter().map(|(k, _)| k.as_str()).collect();
+        prop_assert_eq!(keys, expected);
+        for (key, value) in model {
+            prop_assert!(dict.contains(key));
+            prop_assert_eq!(dict.at(key, None), Ok(&Value::Int(*value)));
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_dict_ops(ops in prop::collection::vec(op(), 0..64)) {
+            let mut dict = Dict::new();
+            let mut model = vec![];
+            for op in &ops {
+                let shared = dict.clone();
+                let before = model.clone();
+                let expected = apply(&mut model, op);
+                match op {
+                    Op::Insert(key, value) => {
+                        dict.insert(key.as_str().into(), Value::Int(*value));
+                    }
+                    Op::Remove(key) => {
+                        let removed = dict.remove(key).ok();
+                        prop_assert_eq!(removed, expected.map(Value::Int));
+                    }
+                    Op::Take(key) => {
+                        let taken = dict.take(key).ok();
+                        prop_assert_eq!(taken, expected.map(Value::Int));
+                    }
+                    Op::Clear => dict.clear(),
+                }
+                assert_matches(&dict, &model)?;
+                assert_matches(&shared, &before)?;
+            }
+        }
+
+        #[test]
+        fn test_dict_add(a in pairs(), b in pairs()) {
+            let mut model = vec![];
+            for (k, v) in a.iter().chain(&b) {
+                apply(&mut model, &Op::Insert(k.clone(), *v));
+            }
+
+            let dict = to_dict(&a) + to_dict(&b);
+            assert_matches(&dict, &model)?;
+            prop_assert_eq!(dict, to_dict(&model));
+        }
+    }
+}
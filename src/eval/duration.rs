@@ -0,0 +1,206 @@
+use std::fmt::{self, Debug, Formatter, Write};
+
+use ecow::{eco_format, EcoString, EcoVec};
+
+use crate::diag::StrResult;
+use crate::eval::cast;
+use crate::util::pretty_array_like;
+
+/// A positive or negative span of time.
+#[derive(Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Duration(time::Duration);
+
+/// The units a duration can be displayed in, from largest to smallest, with
+/// their length in seconds.
+const UNITS: [(&str, i64); 5] = [
+    ("weeks", 7 * 24 * 60 * 60),
+    ("days", 24 * 60 * 60),
+    ("hours", 60 * 60),
+    ("minutes", 60),
+    ("seconds", 1),
+];
+
+impl Duration {
+    /// Create a duration from weeks, days, hours, minutes and seconds.
+    ///
+    /// Returns `None` if the duration is too large.
+    pub fn from_parts(
+        weeks: i64,
+        days: i64,
+        hours: i64,
+        minutes: i64,
+        seconds: i64,
+    ) -> Option<Self> {
+        let mut total = 0i64;
+        for (count, (_, unit)) in
+            [weeks, days, hours, minutes, seconds].into_iter().zip(UNITS)
+        {
+            total = total.checked_add(count.checked_mul(unit)?)?;
+        }
+        Some(Self(time::Duration::seconds(total)))
+    }
+
+    /// Whether the duration is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// The duration in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.0.as_seconds_f64()
+    }
+
+    /// The duration in minutes.
+    pub fn minutes(&self) -> f64 {
+        self.seconds() / 60.0
+    }
+
+    /// The duration in hours.
+    pub fn hours(&self) -> f64 {
+        self.seconds() / (60.0 * 60.0)
+    }
+
+    /// The duration in days.
+    pub fn days(&self) -> f64 {
+        self.seconds() / (24.0 * 60.0 * 60.0)
+    }
+
+    /// The duration in weeks.
+    pub fn weeks(&self) -> f64 {
+        self.seconds() / (7.0 * 24.0 * 60.0 * 60.0)
+    }
+
+    /// Add two durations.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Subtract another duration from this one.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Negate the duration.
+    pub fn checked_neg(self) -> Option<Self> {
+        self.0.checked_neg().map(Self)
+    }
+
+    /// Scale the duration by a factor.
+    pub fn checked_mul(self, factor: f64) -> Option<Self> {
+        let seconds = self.seconds() * factor;
+        (seconds.is_finite() && seconds.abs() < i64::MAX as f64)
+            .then(|| Self(time::Duration::seconds_f64(seconds)))
+    }
+
+    /// Display the duration in a certain format.
+    ///
+    /// The pattern can contain the components `[weeks]`, `[days]`, `[hours]`,
+    /// `[minutes]` and `[seconds]`. Each component shows what remains after the
+    /// larger components in the pattern have been taken out. Minutes and
+    /// seconds are padded to two digits if a larger component is present.
+    pub fn display(&self, pattern: Option<EcoString>) -> StrResult<EcoString> {
+        let pattern = pattern.as_deref().unwrap_or("[hours]:[minutes]:[seconds]");
+
+        // Parse the pattern into literal text and components.
+        let mut pieces = vec![];
+        let mut rest = pattern;
+        while let Some(start) = rest.find('[') {
+            let Some(len) = rest[start..].find(']') else {
+                return Err(eco_format!(
+                    "missing closing bracket for bracket at index {}",
+                    pattern.len() - rest.len() + start,
+                ));
+            };
+
+            let name = &rest[start + 1..start + len];
+            let Some(&(_, unit)) = UNITS.iter().find(|(n, _)| *n == name) else {
+                return Err(eco_format!(
+                    "invalid component name '{}' at index {}",
+                    name,
+                    pattern.len() - rest.len() + start + 1,
+                ));
+            };
+
+            pieces.push(Err(&rest[..start]));
+            pieces.push(Ok(unit));
+            rest = &rest[start + len + 1..];
+        }
+        pieces.push(Err(rest));
+
+        let largest = pieces.iter().filter_map(|p| p.ok()).max().unwrap_or(0);
+        let total = self.0.whole_seconds();
+        let magnitude = total.unsigned_abs();
+
+        let mut buf = EcoString::new();
+        if total < 0 {
+            buf.push('-');
+        }
+
+        for piece in pieces {
+            match piece {
+                Ok(unit) => {
+                    // Take out all larger units in the pattern.
+                    let larger = UNITS
+                        .iter()
+                        .map(|&(_, u)| u)
+                        .filter(|&u| u > unit && u <= largest)
+                        .min();
+                    let mut value = magnitude;
+                    if let Some(larger) = larger {
+                        value %= larger as u64;
+                    }
+                    value /= unit as u64;
+
+                    if unit < 60 * 60 && unit < largest {
+                        write!(buf, "{value:02}").unwrap();
+                    } else {
+                        write!(buf, "{value}").unwrap();
+                    }
+                }
+                Err(text) => buf.push_str(text),
+            }
+        }
+
+        Ok(buf)
+    }
+}
+
+impl From<time::Duration> for Duration {
+    fn from(duration: time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<Duration> for time::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl Debug for Duration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut rest = self.0.whole_seconds();
+        let mut parts = EcoVec::new();
+        for (name, unit) in &UNITS[..UNITS.len() - 1] {
+            let count = rest / unit;
+            rest %= unit;
+            if count != 0 {
+                parts.push(eco_format!("{name}: {count}"));
+            }
+        }
+
+        let nanos = self.0.subsec_nanoseconds();
+        if nanos != 0 {
+            let seconds = rest as f64 + nanos as f64 / 1e9;
+            parts.push(eco_format!("seconds: {seconds}"));
+        } else if rest != 0 || parts.is_empty() {
+            parts.push(eco_format!("seconds: {rest}"));
+        }
+
+        write!(f, "duration{}", &pretty_array_like(&parts, false))
+    }
+}
+
+cast! {
+    type Duration: "duration",
+}
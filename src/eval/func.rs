@@ -7,11 +7,14 @@ use ecow::eco_format;
 use once_cell::sync::Lazy;
 
 use super::{
-    cast, Args, CastInfo, Eval, FlowEvent, IntoValue, Route, Scope, Scopes, Tracer,
+    cast, Args, CastInfo, Dict, Eval, FlowEvent, IntoValue, Route, Scope, Scopes, Tracer,
     Value, Vm,
 };
 use crate::diag::{bail, SourceResult, StrResult};
-use crate::model::{DelayedErrors, ElemFunc, Introspector, Locator, Vt};
+use crate::model::{
+    DelayedErrors, ElemFunc, Element, Introspector, Locator, Selector, UserElem,
+    UserElemFunc, Vt,
+};
 use crate::syntax::ast::{self, AstNode, Expr, Ident};
 use crate::syntax::{SourceId, Span, SyntaxNode};
 use crate::World;
@@ -35,6 +38,8 @@ enum Repr {
     Elem(ElemFunc),
     /// A user-defined closure.
     Closure(Arc<Prehashed<Closure>>),
+    /// An element type declared in Typst code.
+    User(Arc<UserElemFunc>),
     /// A nested function with pre-applied arguments.
     With(Arc<(Func, Args)>),
 }
@@ -46,6 +51,7 @@ impl Func {
             Repr::Native(native) => Some(native.info.name),
             Repr::Elem(func) => Some(func.info().name),
             Repr::Closure(closure) => closure.name.as_deref(),
+            Repr::User(elem) => Some(&elem.name),
             Repr::With(arc) => arc.0.name(),
         }
     }
@@ -55,11 +61,47 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => Some(&native.info),
             Repr::Elem(func) => Some(func.info()),
-            Repr::Closure(_) => None,
+            Repr::Closure(_) | Repr::User(_) => None,
             Repr::With(arc) => arc.0.info(),
         }
     }
 
+    /// Feed the span-independent structure of the function into the hasher.
+    ///
+    /// Closures are hashed by the syntax of their parameters and body and by
+    /// the structure of their captured values.
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(&self.repr).hash(state);
+        match &self.repr {
+            Repr::Closure(closure) => {
+                closure.location.hash(state);
+                closure.name.as_ref().map(Ident::as_str).hash(state);
+                for (name, value) in closure.captured.iter() {
+                    name.hash(state);
+                    value.hash_structure(state);
+                }
+                state.write_usize(closure.params.len());
+                for param in &closure.params {
+                    std::mem::discriminant(param).hash(state);
+                    match param {
+                        Param::Pos(pattern) => pattern.as_untyped().hash_structure(state),
+                        Param::Named(name, default) => {
+                            name.as_str().hash(state);
+                            default.hash_structure(state);
+                        }
+                        Param::Sink(name) => name.as_ref().map(Ident::as_str).hash(state),
+                    }
+                }
+                closure.body.as_untyped().hash_structure(state);
+            }
+            Repr::With(arc) => {
+                arc.0.hash_structure(state);
+                arc.1.hash_structure(state);
+            }
+            other => other.hash(state),
+        }
+    }
+
     /// The function's span.
     pub fn span(&self) -> Span {
         self.span
@@ -67,14 +109,13 @@ impl Func {
 
     /// Attach a span to this function if it doesn't already have one.
     pub fn spanned(mut self, span: Span) -> Self {
-        if self.span.is_detached() {
-            self.span = span;
-        }
+        self.span = self.span.or(span);
         self
     }
 
     /// Call the function with the given arguments.
     pub fn call_vm(&self, vm: &mut Vm, mut args: Args) -> SourceResult<Value> {
+        #[cfg(feature = "tracing")]
         let _span = tracing::info_span!(
             "call",
             name = self.name().unwrap_or("<anon>"),
@@ -83,11 +124,13 @@ impl Func {
 
         match &self.repr {
             Repr::Native(native) => {
+                deprecated_params(vm, &native.info, &args);
                 let value = (native.func)(vm, &mut args)?;
                 args.finish()?;
                 Ok(value)
             }
             Repr::Elem(func) => {
+                deprecated_params(vm, func.info(), &args);
                 let value = func.construct(vm, &mut args)?;
                 args.finish()?;
                 Ok(Value::Content(value))
@@ -110,6 +153,11 @@ impl Func {
                     args,
                 )
             }
+            Repr::User(elem) => {
+                let value = elem.construct(self, &mut args)?;
+                args.finish()?;
+                Ok(Value::Content(value))
+            }
             Repr::With(arc) => {
                 args.items = arc.1.items.iter().cloned().chain(args.items).collect();
                 arc.0.call_vm(vm, args)
@@ -118,7 +166,7 @@ impl Func {
     }
 
     /// Call the function with a Vt.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn call_vt<T: IntoValue>(
         &self,
         vt: &mut Vt,
@@ -154,6 +202,30 @@ impl Func {
         }
     }
 
+    /// Extract the declaration of an element type from Typst code, if it is
+    /// one.
+    pub fn user_element(&self) -> Option<&UserElemFunc> {
+        match &self.repr {
+            Repr::User(elem) => Some(elem),
+            _ => None,
+        }
+    }
+
+    /// Create a selector for elements of this function, if it is a native or
+    /// user-defined element function. With `fields`, only those elements whose
+    /// fields match are selected.
+    pub fn select(&self, fields: Option<Dict>) -> Option<Selector> {
+        match &self.repr {
+            Repr::Elem(func) => Some(Selector::Elem(*func, fields)),
+            Repr::User(_) => {
+                let mut fields = fields.unwrap_or_default();
+                fields.insert("element".into(), Value::Func(self.clone()));
+                Some(Selector::Elem(UserElem::func(), Some(fields)))
+            }
+            _ => None,
+        }
+    }
+
     /// Get a field from this function's scope, if possible.
     pub fn get(&self, field: &str) -> StrResult<&Value> {
         match &self.repr {
@@ -174,6 +246,11 @@ impl Func {
             Repr::Closure(_) => {
                 Err(eco_format!("cannot access fields on user-defined functions"))
             }
+            Repr::User(elem) => Err(eco_format!(
+                "element `{}` does not contain field `{}`",
+                elem.name,
+                field
+            )),
             Repr::With(arc) => arc.0.get(field),
         }
     }
@@ -206,6 +283,12 @@ impl From<ElemFunc> for Func {
     }
 }
 
+impl From<UserElemFunc> for Func {
+    fn from(elem: UserElemFunc) -> Self {
+        Repr::User(Arc::new(elem)).into()
+    }
+}
+
 /// A Typst function defined by a native Rust function.
 pub struct NativeFunc {
     /// The function's implementation.
@@ -291,6 +374,19 @@ pub struct ParamInfo {
     pub required: bool,
     /// Is the parameter settable with a set rule?
     pub settable: bool,
+    /// A hint on what to use instead, if the parameter is deprecated.
+    pub deprecated: Option<&'static str>,
+}
+
+/// Warn about arguments for deprecated parameters.
+pub(super) fn deprecated_params(vm: &mut Vm, info: &FuncInfo, args: &Args) {
+    for arg in &args.items {
+        let Some(name) = &arg.name else { continue };
+        let Some(hint) = info.param(name).and_then(|param| param.deprecated) else {
+            continue;
+        };
+        vm.deprecated(arg.span, &eco_format!("parameter `{name}`"), hint);
+    }
 }
 
 /// A user-defined closure.
@@ -322,7 +418,7 @@ pub enum Param {
 impl Closure {
     /// Call the function in the context with the arguments.
     #[comemo::memoize]
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     #[allow(clippy::too_many_arguments)]
     fn call(
         this: &Func,
@@ -458,7 +554,7 @@ impl<'a> CapturesVisitor<'a> {
     }
 
     /// Visit any node and collect all captured variables.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn visit(&mut self, node: &SyntaxNode) {
         match node.cast() {
             // Every identifier is a potential variable that we need to capture.
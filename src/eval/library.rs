@@ -1,6 +1,7 @@
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
+use std::str::FromStr;
 
 use comemo::Tracked;
 use ecow::EcoString;
@@ -26,6 +27,70 @@ pub struct Library {
     pub styles: Styles,
     /// Defines which standard library items fulfill which syntactical roles.
     pub items: LangItems,
+    /// The experimental language features that are enabled.
+    pub features: Features,
+}
+
+/// An experimental language feature.
+///
+/// Using a feature that isn't enabled in the [library](Library) is an error.
+/// Features are either stabilized or removed in later releases.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Feature {
+    /// Destructuring into nested arrays in assignments, like
+    /// `((a, b), c) = ((1, 2), 3)`.
+    NestedDestructuring,
+}
+
+impl Feature {
+    /// All experimental features.
+    pub const ALL: &'static [Self] = &[Self::NestedDestructuring];
+
+    /// The name of the feature, as used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NestedDestructuring => "nested-destructuring",
+        }
+    }
+
+    /// A short description of the feature for error messages.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::NestedDestructuring => "nested destructuring",
+        }
+    }
+}
+
+impl FromStr for Feature {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|feature| feature.name() == s)
+            .ok_or("unknown feature")
+    }
+}
+
+/// A set of enabled experimental features.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct Features(Vec<Feature>);
+
+impl Features {
+    /// Whether the given feature is enabled.
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.0.contains(&feature)
+    }
+}
+
+impl FromIterator<Feature> for Features {
+    fn from_iter<T: IntoIterator<Item = Feature>>(iter: T) -> Self {
+        let mut features: Vec<_> = iter.into_iter().collect();
+        features.sort_by_key(|feature| feature.name());
+        features.dedup();
+        Self(features)
+    }
 }
 
 /// Definition of library items the language is aware of.
@@ -74,6 +139,10 @@ pub struct LangItems {
     pub heading: fn(level: NonZeroUsize, body: Content) -> Content,
     /// The heading function.
     pub heading_func: ElemFunc,
+    /// The function for document parts, which appear in the PDF bookmarks.
+    pub part_func: ElemFunc,
+    /// The image function.
+    pub image_func: ElemFunc,
     /// An item in a bullet list: `- ...`.
     pub list_item: fn(body: Content) -> Content,
     /// An item in an enumeration (numbered list): `+ ...` or `1. ...`.
@@ -142,6 +211,8 @@ impl Hash for LangItems {
         (self.bibliography_keys as usize).hash(state);
         self.heading.hash(state);
         self.heading_func.hash(state);
+        self.part_func.hash(state);
+        self.image_func.hash(state);
         self.list_item.hash(state);
         self.enum_item.hash(state);
         self.term_item.hash(state);
@@ -2,10 +2,10 @@
 
 use ecow::EcoString;
 
-use super::{Args, IntoValue, Str, Value, Vm};
+use super::{Args, IntoValue, Range, Str, Value, Vm};
 use crate::diag::{At, SourceResult};
-use crate::eval::Datetime;
-use crate::model::{Location, Selector};
+use crate::eval::{Datetime, Duration, Version};
+use crate::model::{Location, Selector, UserElem};
 use crate::syntax::Span;
 
 /// Call a method on a value.
@@ -70,7 +70,10 @@ pub fn call(
         },
 
         Value::Content(content) => match method {
-            "func" => content.func().into_value(),
+            "func" => match content.to::<UserElem>() {
+                Some(elem) => elem.element().into_value(),
+                None => content.func().into_value(),
+            },
             "has" => content.has(&args.expect::<EcoString>("field")?).into_value(),
             "at" => content
                 .at(&args.expect::<EcoString>("field")?, args.named("default")?)
@@ -81,6 +84,18 @@ pub fn call(
                 .ok_or("this method can only be called on content returned by query(..)")
                 .at(span)?
                 .into_value(),
+            "syntax" => {
+                let span = content.span();
+                if span.is_detached() {
+                    Value::None
+                } else {
+                    let source = vm.world().source(span.source());
+                    match source.find(span) {
+                        Some(node) => Value::Str(node.get().clone().into_text().into()),
+                        None => Value::None,
+                    }
+                }
+            }
             _ => return missing(),
         },
 
@@ -100,7 +115,19 @@ pub fn call(
                 }
                 array.slice(start, end).at(span)?.into_value()
             }
-            "contains" => array.contains(&args.expect("value")?).into_value(),
+            "contains" => array
+                .contains_by(vm, args.expect("value")?, args.named("key")?)?
+                .into_value(),
+            "dedup" => array.dedup(vm, args.named("key")?)?.into_value(),
+            "union" => array
+                .union(vm, args.expect("other")?, args.named("key")?)?
+                .into_value(),
+            "intersect" => array
+                .intersect(vm, args.expect("other")?, args.named("key")?)?
+                .into_value(),
+            "difference" => array
+                .difference(vm, args.expect("other")?, args.named("key")?)?
+                .into_value(),
             "find" => array.find(vm, args.expect("function")?)?.into_value(),
             "position" => array.position(vm, args.expect("function")?)?.into_value(),
             "filter" => array.filter(vm, args.expect("function")?)?.into_value(),
@@ -120,9 +147,21 @@ pub fn call(
                 let last = args.named("last")?;
                 array.join(sep, last).at(span)?
             }
-            "sorted" => array.sorted(vm, span, args.named("key")?)?.into_value(),
-            "zip" => array.zip(args.expect("other")?).into_value(),
-            "enumerate" => array.enumerate().into_value(),
+            "sorted" => array
+                .sorted(vm, span, args.named("key")?, args.named("locale")?)?
+                .into_value(),
+            "zip" => {
+                let mut others = vec![args.expect("other")?];
+                others.extend(args.all()?);
+                array.zip(others).into_value()
+            }
+            "enumerate" => array
+                .enumerate(args.named("start")?.unwrap_or(0))
+                .at(span)?
+                .into_value(),
+            "chunks" => array
+                .chunks(args.expect("size")?, args.named("exact")?.unwrap_or(false))
+                .into_value(),
             _ => return missing(),
         },
 
@@ -143,10 +182,9 @@ pub fn call(
             "where" => {
                 let fields = args.to_named();
                 args.items.retain(|arg| arg.name.is_none());
-                func.element()
+                func.select(Some(fields))
                     .ok_or("`where()` can only be called on element functions")
                     .at(span)?
-                    .where_(fields)
                     .into_value()
             }
             _ => return missing(),
@@ -198,6 +236,57 @@ pub fn call(
                     "second" => datetime.second().into_value(),
                     _ => return missing(),
                 }
+            } else if let Some(&duration) = dynamic.downcast::<Duration>() {
+                match method {
+                    "display" => {
+                        duration.display(args.eat()?).at(args.span)?.into_value()
+                    }
+                    "seconds" => duration.seconds().into_value(),
+                    "minutes" => duration.minutes().into_value(),
+                    "hours" => duration.hours().into_value(),
+                    "days" => duration.days().into_value(),
+                    "weeks" => duration.weeks().into_value(),
+                    _ => return missing(),
+                }
+            } else if let Some(version) = dynamic.downcast::<Version>() {
+                match method {
+                    "at" => version.at(args.expect("index")?).at(span)?.into_value(),
+                    _ => return missing(),
+                }
+            } else if let Some(&range) = dynamic.downcast::<Range>() {
+                match method {
+                    "len" => range.len().into_value(),
+                    "first" => range.first().at(span)?.into_value(),
+                    "last" => range.last().at(span)?.into_value(),
+                    "at" => range
+                        .at(args.expect("index")?, args.named("default")?)
+                        .at(span)?,
+                    "slice" => {
+                        let start = args.expect("start")?;
+                        let mut end = args.eat()?;
+                        if end.is_none() {
+                            end = args.named("count")?.map(|c: i64| start + c);
+                        }
+                        range.slice(start, end).at(span)?.into_value()
+                    }
+                    "contains" => {
+                        let value = args.expect("value")?;
+                        match args.named("key")? {
+                            Some(key) => {
+                                range.to_array().contains_by(vm, value, Some(key))?
+                            }
+                            None => range.contains(&value),
+                        }
+                        .into_value()
+                    }
+                    "rev" => range.rev().into_value(),
+                    // The other array methods work on the range's items.
+                    _ if methods_on("array").iter().any(|&(name, _)| name == method) => {
+                        let array = Value::Array(range.to_array());
+                        return call(vm, array, method, args, span);
+                    }
+                    _ => return missing(),
+                }
             } else {
                 return (vm.items.library_method)(vm, &dynamic, method, args, span);
             }
@@ -217,6 +306,7 @@ pub fn call_mut(
     mut args: Args,
     span: Span,
 ) -> SourceResult<Value> {
+    materialize(value);
     let name = value.type_name();
     let missing = || Err(missing_method(name, method)).at(span);
     let mut output = Value::None;
@@ -254,6 +344,7 @@ pub fn call_access<'a>(
     mut args: Args,
     span: Span,
 ) -> SourceResult<&'a mut Value> {
+    materialize(value);
     let name = value.type_name();
     let missing = || Err(missing_method(name, method)).at(span);
 
@@ -275,6 +366,15 @@ pub fn call_access<'a>(
     Ok(slot)
 }
 
+/// Turn a range into an array before it is modified.
+fn materialize(value: &mut Value) {
+    if let Value::Dyn(dynamic) = value {
+        if let Some(&range) = dynamic.downcast::<Range>() {
+            *value = Value::Array(range.to_array());
+        }
+    }
+}
+
 /// Whether a specific method is mutating.
 pub fn is_mutating(method: &str) -> bool {
     matches!(method, "push" | "pop" | "insert" | "remove")
@@ -320,18 +420,23 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("at", true),
             ("fields", false),
             ("location", false),
+            ("syntax", false),
         ],
         "array" => &[
             ("all", true),
             ("any", true),
             ("at", true),
+            ("chunks", true),
             ("contains", true),
+            ("dedup", false),
+            ("difference", true),
             ("filter", true),
             ("find", true),
             ("first", false),
             ("flatten", false),
             ("fold", true),
             ("insert", true),
+            ("intersect", true),
             ("split", true),
             ("join", true),
             ("last", false),
@@ -339,12 +444,15 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("map", true),
             ("pop", false),
             ("position", true),
+            ("product", false),
             ("push", true),
             ("remove", true),
             ("rev", false),
             ("slice", true),
             ("sorted", false),
-            ("enumerate", false),
+            ("sum", false),
+            ("enumerate", true),
+            ("union", true),
             ("zip", true),
         ],
         "dictionary" => &[
@@ -356,6 +464,15 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("remove", true),
             ("values", false),
         ],
+        "duration" => &[
+            ("display", true),
+            ("seconds", false),
+            ("minutes", false),
+            ("hours", false),
+            ("days", false),
+            ("weeks", false),
+        ],
+        "version" => &[("at", true)],
         "function" => &[("where", true), ("with", true)],
         "arguments" => &[("named", false), ("pos", false)],
         "location" => &[("page", false), ("position", false), ("page-numbering", false)],
@@ -6,6 +6,7 @@ mod library;
 mod cast;
 #[macro_use]
 mod array;
+mod collate;
 #[macro_use]
 mod dict;
 #[macro_use]
@@ -15,14 +16,17 @@ mod value;
 mod args;
 mod auto;
 mod datetime;
+mod duration;
 mod func;
 mod int;
 mod methods;
 mod module;
 mod none;
 pub mod ops;
+mod range;
 mod scope;
 mod symbol;
+mod version;
 
 #[doc(hidden)]
 pub use {
@@ -43,15 +47,18 @@ pub use self::cast::{
 };
 pub use self::datetime::Datetime;
 pub use self::dict::{dict, Dict};
+pub use self::duration::Duration;
 pub use self::func::{Func, FuncInfo, NativeFunc, Param, ParamInfo};
-pub use self::library::{set_lang_items, LangItems, Library};
+pub use self::library::{set_lang_items, Feature, Features, LangItems, Library};
 pub use self::methods::methods_on;
 pub use self::module::Module;
 pub use self::none::NoneValue;
+pub use self::range::Range;
 pub use self::scope::{Scope, Scopes};
 pub use self::str::{format_str, Regex, Str};
 pub use self::symbol::Symbol;
 pub use self::value::{Dynamic, Type, Value};
+pub use self::version::Version;
 
 use std::collections::HashSet;
 use std::mem;
@@ -59,7 +66,6 @@ use std::path::{Path, PathBuf};
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
 use ecow::{EcoString, EcoVec};
-use unicode_segmentation::UnicodeSegmentation;
 
 use self::func::{CapturesVisitor, Closure};
 use crate::model::{
@@ -68,12 +74,15 @@ use crate::model::{
 };
 use crate::syntax::ast::AstNode;
 use crate::syntax::{
-    ast, parse_code, Source, SourceId, Span, Spanned, SyntaxKind, SyntaxNode,
+    ast, parse, parse_code, Source, SourceId, Span, Spanned, SyntaxNode,
 };
 use crate::util::PathExt;
 use crate::World;
 use crate::{
-    diag::{bail, error, At, SourceError, SourceResult, StrResult, Trace, Tracepoint},
+    diag::{
+        bail, error, At, FileError, SourceError, SourceResult, SourceWarning, StrResult,
+        Trace, Tracepoint,
+    },
     model::DelayedErrors,
 };
 
@@ -82,7 +91,13 @@ const MAX_CALL_DEPTH: usize = 64;
 
 /// Evaluate a source file and return the resulting module.
 #[comemo::memoize]
-#[tracing::instrument(skip(world, route, tracer, source))]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        skip(world, route, tracer, source),
+        fields(path = %source.path().display()),
+    ),
+)]
 pub fn eval(
     world: Tracked<dyn World + '_>,
     route: Tracked<Route>,
@@ -134,16 +149,25 @@ pub fn eval(
     Ok(Module::new(name).with_scope(vm.scopes.top).with_content(result?))
 }
 
-/// Evaluate a string as code and return the resulting value.
+/// Evaluate a string as code or markup and return the resulting value.
 ///
-/// Everything in the output is associated with the given `span`.
+/// Everything in the output is associated with the given `span`. The code can
+/// use the bindings in `scope` in addition to the standard library. If it is
+/// `sandboxed`, the code can only use the bindings in `scope`. Either way, it
+/// cannot access the file system.
 #[comemo::memoize]
 pub fn eval_string(
     world: Tracked<dyn World + '_>,
     code: &str,
     span: Span,
+    mode: EvalMode,
+    scope: Scope,
+    sandboxed: bool,
 ) -> SourceResult<Value> {
-    let mut root = parse_code(code);
+    let mut root = match mode {
+        EvalMode::Code => parse_code(code),
+        EvalMode::Markup => parse(code),
+    };
     root.synthesize(span);
 
     let errors = root.errors();
@@ -167,12 +191,17 @@ pub fn eval_string(
     // Prepare VM.
     let route = Route::default();
     let id = SourceId::detached();
-    let scopes = Scopes::new(Some(world.library()));
+    let mut scopes = Scopes::new((!sandboxed).then(|| world.library()));
+    scopes.top = scope;
     let mut vm = Vm::new(vt, route.track(), id, scopes);
 
     // Evaluate the code.
-    let code = root.cast::<ast::Code>().unwrap();
-    let result = code.eval(&mut vm);
+    let result = match mode {
+        EvalMode::Code => root.cast::<ast::Code>().unwrap().eval(&mut vm),
+        EvalMode::Markup => {
+            root.cast::<ast::Markup>().unwrap().eval(&mut vm).map(Value::Content)
+        }
+    };
 
     // Handle control flow.
     if let Some(flow) = vm.flow {
@@ -182,6 +211,15 @@ pub fn eval_string(
     result
 }
 
+/// In which mode to evaluate a string.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum EvalMode {
+    /// Evaluate as code, as after a hashtag.
+    Code,
+    /// Evaluate as markup, like in a Typst file.
+    Markup,
+}
+
 /// A virtual machine.
 ///
 /// Holds the state needed to [evaluate](eval) Typst sources. A new
@@ -232,8 +270,35 @@ impl<'a> Vm<'a> {
         self.vt.world
     }
 
+    /// Capture the bindings that are currently visible, except for the
+    /// standard library.
+    ///
+    /// See [`Scopes::snapshot`].
+    pub fn snapshot(&self) -> Scope {
+        self.scopes.snapshot()
+    }
+
+    /// Fail unless the given experimental feature is enabled.
+    pub fn require(&self, feature: Feature, span: Span) -> SourceResult<()> {
+        if !self.world().library().features.is_enabled(feature) {
+            bail!(
+                span,
+                "{} is an experimental feature, enable it with `--features {}`",
+                feature.description(),
+                feature.name(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Warn about the use of a deprecated definition.
+    fn deprecated(&mut self, span: Span, what: &str, hint: &str) {
+        let warning = SourceWarning::new(span, eco_format!("{what} is deprecated"));
+        self.vt.tracer.warn(warning.with_hint(hint));
+    }
+
     /// Define a variable in the current scope.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn define(&mut self, var: ast::Ident, value: impl IntoValue) {
         let value = value.into_value();
         if self.traced == Some(var.span()) {
@@ -244,7 +309,7 @@ impl<'a> Vm<'a> {
 
     /// Resolve a user-entered path to be relative to the compilation
     /// environment's root.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn locate(&self, path: &str) -> StrResult<PathBuf> {
         if !self.location.is_detached() {
             if let Some(path) = path.strip_prefix('/') {
@@ -258,6 +323,17 @@ impl<'a> Vm<'a> {
 
         bail!("cannot access file system from here")
     }
+
+    /// Check that the code may download files.
+    ///
+    /// Like file system access, this is forbidden for detached code, such as
+    /// strings evaluated with `eval`.
+    pub fn check_network(&self) -> StrResult<()> {
+        if self.location.is_detached() {
+            bail!("cannot access network from here");
+        }
+        Ok(())
+    }
 }
 
 /// A control flow event that occurred during evaluation.
@@ -333,11 +409,13 @@ impl<'a> Route<'a> {
     }
 }
 
-/// Traces which values existed for an expression at a span.
+/// Traces which values existed for an expression at a span and collects the
+/// warnings raised during evaluation.
 #[derive(Default, Clone)]
 pub struct Tracer {
     span: Option<Span>,
     values: Vec<Value>,
+    warnings: Vec<SourceWarning>,
 }
 
 impl Tracer {
@@ -346,13 +424,18 @@ impl Tracer {
 
     /// Create a new tracer, possibly with a span under inspection.
     pub fn new(span: Option<Span>) -> Self {
-        Self { span, values: vec![] }
+        Self { span, values: vec![], warnings: vec![] }
     }
 
     /// Get the traced values.
     pub fn finish(self) -> Vec<Value> {
         self.values
     }
+
+    /// Get the collected warnings.
+    pub fn warnings(self) -> Vec<SourceWarning> {
+        self.warnings
+    }
 }
 
 #[comemo::track]
@@ -372,6 +455,16 @@ impl Tracer {
             self.values.push(v);
         }
     }
+
+    /// Raise a warning.
+    ///
+    /// A warning for a span that already has one is dropped, so that code in
+    /// loops and functions doesn't repeat itself.
+    pub fn warn(&mut self, warning: SourceWarning) {
+        if !self.warnings.iter().any(|w| w.span == warning.span) {
+            self.warnings.push(warning);
+        }
+    }
 }
 
 /// Evaluate an expression.
@@ -445,7 +538,7 @@ fn eval_markup(
 impl Eval for ast::Expr {
     type Output = Value;
 
-    #[tracing::instrument(name = "Expr::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Expr::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let span = self.span();
         let forbidden = |name| {
@@ -528,7 +621,7 @@ impl ast::Expr {
 impl Eval for ast::Text {
     type Output = Content;
 
-    #[tracing::instrument(name = "Text::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Text::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.text)(self.get().clone()))
     }
@@ -537,7 +630,7 @@ impl Eval for ast::Text {
 impl Eval for ast::Space {
     type Output = Content;
 
-    #[tracing::instrument(name = "Space::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Space::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.space)())
     }
@@ -546,7 +639,10 @@ impl Eval for ast::Space {
 impl Eval for ast::Linebreak {
     type Output = Content;
 
-    #[tracing::instrument(name = "Linebreak::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Linebreak::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.linebreak)())
     }
@@ -555,7 +651,10 @@ impl Eval for ast::Linebreak {
 impl Eval for ast::Parbreak {
     type Output = Content;
 
-    #[tracing::instrument(name = "Parbreak::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Parbreak::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.parbreak)())
     }
@@ -564,7 +663,7 @@ impl Eval for ast::Parbreak {
 impl Eval for ast::Escape {
     type Output = Value;
 
-    #[tracing::instrument(name = "Escape::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Escape::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Symbol(Symbol::new(self.get())))
     }
@@ -573,7 +672,10 @@ impl Eval for ast::Escape {
 impl Eval for ast::Shorthand {
     type Output = Value;
 
-    #[tracing::instrument(name = "Shorthand::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Shorthand::eval", skip_all)
+    )]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Symbol(Symbol::new(self.get())))
     }
@@ -582,7 +684,10 @@ impl Eval for ast::Shorthand {
 impl Eval for ast::SmartQuote {
     type Output = Content;
 
-    #[tracing::instrument(name = "SmartQuote::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "SmartQuote::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.smart_quote)(self.double()))
     }
@@ -591,7 +696,7 @@ impl Eval for ast::SmartQuote {
 impl Eval for ast::Strong {
     type Output = Content;
 
-    #[tracing::instrument(name = "Strong::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Strong::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.strong)(self.body().eval(vm)?))
     }
@@ -600,7 +705,7 @@ impl Eval for ast::Strong {
 impl Eval for ast::Emph {
     type Output = Content;
 
-    #[tracing::instrument(name = "Emph::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Emph::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.emph)(self.body().eval(vm)?))
     }
@@ -609,7 +714,7 @@ impl Eval for ast::Emph {
 impl Eval for ast::Raw {
     type Output = Content;
 
-    #[tracing::instrument(name = "Raw::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Raw::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let text = self.text();
         let lang = self.lang().map(Into::into);
@@ -621,7 +726,7 @@ impl Eval for ast::Raw {
 impl Eval for ast::Link {
     type Output = Content;
 
-    #[tracing::instrument(name = "Link::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Link::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.link)(self.get().clone()))
     }
@@ -630,7 +735,7 @@ impl Eval for ast::Link {
 impl Eval for ast::Label {
     type Output = Value;
 
-    #[tracing::instrument(name = "Label::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Label::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Label(Label(self.get().into())))
     }
@@ -639,7 +744,7 @@ impl Eval for ast::Label {
 impl Eval for ast::Ref {
     type Output = Content;
 
-    #[tracing::instrument(name = "Ref::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Ref::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let label = Label(self.target().into());
         let supplement = self.supplement().map(|block| block.eval(vm)).transpose()?;
@@ -650,7 +755,10 @@ impl Eval for ast::Ref {
 impl Eval for ast::Heading {
     type Output = Content;
 
-    #[tracing::instrument(name = "Heading::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Heading::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let level = self.level();
         let body = self.body().eval(vm)?;
@@ -661,7 +769,10 @@ impl Eval for ast::Heading {
 impl Eval for ast::ListItem {
     type Output = Content;
 
-    #[tracing::instrument(name = "ListItem::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ListItem::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.list_item)(self.body().eval(vm)?))
     }
@@ -670,7 +781,10 @@ impl Eval for ast::ListItem {
 impl Eval for ast::EnumItem {
     type Output = Content;
 
-    #[tracing::instrument(name = "EnumItem::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "EnumItem::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let number = self.number();
         let body = self.body().eval(vm)?;
@@ -681,7 +795,10 @@ impl Eval for ast::EnumItem {
 impl Eval for ast::TermItem {
     type Output = Content;
 
-    #[tracing::instrument(name = "TermItem::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "TermItem::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let term = self.term().eval(vm)?;
         let description = self.description().eval(vm)?;
@@ -692,7 +809,10 @@ impl Eval for ast::TermItem {
 impl Eval for ast::Equation {
     type Output = Content;
 
-    #[tracing::instrument(name = "Equation::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Equation::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let body = self.body().eval(vm)?;
         let block = self.block();
@@ -703,7 +823,7 @@ impl Eval for ast::Equation {
 impl Eval for ast::Math {
     type Output = Content;
 
-    #[tracing::instrument(name = "Math::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Math::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Content::sequence(
             self.exprs()
@@ -716,7 +836,10 @@ impl Eval for ast::Math {
 impl Eval for ast::MathIdent {
     type Output = Value;
 
-    #[tracing::instrument(name = "MathIdent::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "MathIdent::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         vm.scopes.get_in_math(self).cloned().at(self.span())
     }
@@ -725,7 +848,10 @@ impl Eval for ast::MathIdent {
 impl Eval for ast::MathAlignPoint {
     type Output = Content;
 
-    #[tracing::instrument(name = "MathAlignPoint::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "MathAlignPoint::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         Ok((vm.items.math_align_point)())
     }
@@ -734,7 +860,10 @@ impl Eval for ast::MathAlignPoint {
 impl Eval for ast::MathDelimited {
     type Output = Content;
 
-    #[tracing::instrument(name = "MathDelimited::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "MathDelimited::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let open = self.open().eval_display(vm)?;
         let body = self.body().eval(vm)?;
@@ -746,7 +875,10 @@ impl Eval for ast::MathDelimited {
 impl Eval for ast::MathAttach {
     type Output = Content;
 
-    #[tracing::instrument(name = "MathAttach::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "MathAttach::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let base = self.base().eval_display(vm)?;
         let top = self.top().map(|expr| expr.eval_display(vm)).transpose()?;
@@ -758,7 +890,10 @@ impl Eval for ast::MathAttach {
 impl Eval for ast::MathFrac {
     type Output = Content;
 
-    #[tracing::instrument(name = "MathFrac::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "MathFrac::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let num = self.num().eval_display(vm)?;
         let denom = self.denom().eval_display(vm)?;
@@ -779,16 +914,20 @@ impl Eval for ast::MathRoot {
 impl Eval for ast::Ident {
     type Output = Value;
 
-    #[tracing::instrument(name = "Ident::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Ident::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get(self).cloned().at(self.span())
+        let value = vm.scopes.get(self).cloned().at(self.span())?;
+        if let Some(hint) = vm.scopes.deprecation(self) {
+            vm.deprecated(self.span(), &eco_format!("`{}`", self.as_str()), hint);
+        }
+        Ok(value)
     }
 }
 
 impl Eval for ast::None {
     type Output = Value;
 
-    #[tracing::instrument(name = "None::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "None::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::None)
     }
@@ -797,7 +936,7 @@ impl Eval for ast::None {
 impl Eval for ast::Auto {
     type Output = Value;
 
-    #[tracing::instrument(name = "Auto::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Auto::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Auto)
     }
@@ -806,7 +945,7 @@ impl Eval for ast::Auto {
 impl Eval for ast::Bool {
     type Output = Value;
 
-    #[tracing::instrument(name = "Bool::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Bool::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Bool(self.get()))
     }
@@ -815,7 +954,7 @@ impl Eval for ast::Bool {
 impl Eval for ast::Int {
     type Output = Value;
 
-    #[tracing::instrument(name = "Int::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Int::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Int(self.get()))
     }
@@ -824,7 +963,7 @@ impl Eval for ast::Int {
 impl Eval for ast::Float {
     type Output = Value;
 
-    #[tracing::instrument(name = "Float::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Float::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Float(self.get()))
     }
@@ -833,7 +972,10 @@ impl Eval for ast::Float {
 impl Eval for ast::Numeric {
     type Output = Value;
 
-    #[tracing::instrument(name = "Numeric::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Numeric::eval", skip_all)
+    )]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::numeric(self.get()))
     }
@@ -842,7 +984,7 @@ impl Eval for ast::Numeric {
 impl Eval for ast::Str {
     type Output = Value;
 
-    #[tracing::instrument(name = "Str::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Str::eval", skip_all))]
     fn eval(&self, _: &mut Vm) -> SourceResult<Self::Output> {
         Ok(Value::Str(self.get().into()))
     }
@@ -851,7 +993,10 @@ impl Eval for ast::Str {
 impl Eval for ast::CodeBlock {
     type Output = Value;
 
-    #[tracing::instrument(name = "CodeBlock::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "CodeBlock::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         vm.scopes.enter();
         let output = self.body().eval(vm)?;
@@ -917,7 +1062,10 @@ fn eval_code(
 impl Eval for ast::ContentBlock {
     type Output = Content;
 
-    #[tracing::instrument(name = "ContentBlock::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ContentBlock::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         vm.scopes.enter();
         let content = self.body().eval(vm)?;
@@ -929,7 +1077,10 @@ impl Eval for ast::ContentBlock {
 impl Eval for ast::Parenthesized {
     type Output = Value;
 
-    #[tracing::instrument(name = "Parenthesized::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Parenthesized::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         self.expr().eval(vm)
     }
@@ -938,7 +1089,7 @@ impl Eval for ast::Parenthesized {
 impl Eval for ast::Array {
     type Output = Array;
 
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let items = self.items();
 
@@ -948,7 +1099,9 @@ impl Eval for ast::Array {
                 ast::ArrayItem::Pos(expr) => vec.push(expr.eval(vm)?),
                 ast::ArrayItem::Spread(expr) => match expr.eval(vm)? {
                     Value::None => {}
-                    Value::Array(array) => vec.extend(array.into_iter()),
+                    v if Array::castable(&v) => {
+                        vec.extend(v.cast::<Array>().at(expr.span())?)
+                    }
                     v => bail!(expr.span(), "cannot spread {} into array", v.type_name()),
                 },
             }
@@ -961,7 +1114,7 @@ impl Eval for ast::Array {
 impl Eval for ast::Dict {
     type Output = Dict;
 
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let mut map = indexmap::IndexMap::new();
 
@@ -992,7 +1145,7 @@ impl Eval for ast::Dict {
 impl Eval for ast::Unary {
     type Output = Value;
 
-    #[tracing::instrument(name = "Unary::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Unary::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.expr().eval(vm)?;
         let result = match self.op() {
@@ -1007,7 +1160,7 @@ impl Eval for ast::Unary {
 impl Eval for ast::Binary {
     type Output = Value;
 
-    #[tracing::instrument(name = "Binary::eval", skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "Binary::eval", skip_all))]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         match self.op() {
             ast::BinOp::Add => self.apply(vm, ops::add),
@@ -1024,6 +1177,7 @@ impl Eval for ast::Binary {
             ast::BinOp::Geq => self.apply(vm, ops::geq),
             ast::BinOp::In => self.apply(vm, ops::in_),
             ast::BinOp::NotIn => self.apply(vm, ops::not_in),
+            ast::BinOp::Range => self.apply(vm, ops::range),
             ast::BinOp::Assign => self.assign(vm, |_, b| Ok(b)),
             ast::BinOp::AddAssign => self.assign(vm, ops::add),
             ast::BinOp::SubAssign => self.assign(vm, ops::sub),
@@ -1082,10 +1236,19 @@ impl ast::Binary {
 impl Eval for ast::FieldAccess {
     type Output = Value;
 
-    #[tracing::instrument(name = "FieldAccess::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FieldAccess::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.target().eval(vm)?;
         let field = self.field();
+        if let Value::Module(module) = &value {
+            if let Some(hint) = module.scope().deprecation(&field) {
+                let what = eco_format!("`{}.{}`", module.name(), field.as_str());
+                vm.deprecated(field.span(), &what, hint);
+            }
+        }
         value.field(&field).at(field.span())
     }
 }
@@ -1093,7 +1256,10 @@ impl Eval for ast::FieldAccess {
 impl Eval for ast::FuncCall {
     type Output = Value;
 
-    #[tracing::instrument(name = "FuncCall::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FuncCall::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let span = self.span();
         if vm.depth >= MAX_CALL_DEPTH {
@@ -1226,7 +1392,8 @@ impl Eval for ast::Args {
                 }
                 ast::Arg::Spread(expr) => match expr.eval(vm)? {
                     Value::None => {}
-                    Value::Array(array) => {
+                    v if Array::castable(&v) => {
+                        let array = v.cast::<Array>().at(expr.span())?;
                         items.extend(array.into_iter().map(|value| Arg {
                             span,
                             name: None,
@@ -1253,7 +1420,10 @@ impl Eval for ast::Args {
 impl Eval for ast::Closure {
     type Output = Value;
 
-    #[tracing::instrument(name = "Closure::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Closure::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         // The closure's name is defined by its let binding if there's one.
         let name = self.name();
@@ -1396,7 +1566,7 @@ impl ast::Pattern {
     }
 
     /// Destruct the given value into the pattern and apply the function to each binding.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn apply<T>(&self, vm: &mut Vm, value: Value, f: T) -> SourceResult<Value>
     where
         T: Fn(&mut Vm, ast::Expr, Value) -> SourceResult<Value>,
@@ -1408,8 +1578,11 @@ impl ast::Pattern {
             }
             ast::Pattern::Placeholder(_) => Ok(Value::None),
             ast::Pattern::Destructuring(destruct) => match value {
-                Value::Array(value) => self.destruct_array(vm, value, f, destruct),
                 Value::Dict(value) => self.destruct_dict(vm, value, f, destruct),
+                value if Array::castable(&value) => {
+                    let value = value.cast::<Array>().at(self.span())?;
+                    self.destruct_array(vm, value, f, destruct)
+                }
                 _ => bail!(self.span(), "cannot destructure {}", value.type_name()),
             },
         }
@@ -1428,18 +1601,67 @@ impl ast::Pattern {
 
     /// Destruct the value into the pattern by assignment.
     pub fn assign(&self, vm: &mut Vm, value: Value) -> SourceResult<Value> {
-        self.apply(vm, value, |vm, expr, value| {
-            let location = expr.access(vm)?;
-            *location = value;
-            Ok(Value::None)
-        })
+        self.apply(vm, value, assign_nested)
+    }
+}
+
+/// Assign a value to an expression, which may itself be an array of places to
+/// destructure the value into.
+fn assign_nested(vm: &mut Vm, expr: ast::Expr, value: Value) -> SourceResult<Value> {
+    let ast::Expr::Array(array) = expr else {
+        let location = expr.access(vm)?;
+        *location = value;
+        return Ok(Value::None);
+    };
+
+    let span = array.span();
+    vm.require(Feature::NestedDestructuring, span)?;
+    if !Array::castable(&value) {
+        bail!(span, "cannot destructure {}", value.type_name());
+    }
+    let value = value.cast::<Array>().at(span)?;
+
+    let items: Vec<_> = array.items().collect();
+    let fixed = items
+        .iter()
+        .filter(|item| matches!(item, ast::ArrayItem::Pos(_)))
+        .count();
+    if items.len() > fixed + 1 {
+        bail!(span, "at most one destructuring sink is allowed");
+    }
+
+    let values = value.as_slice();
+    if values.len() < fixed {
+        bail!(span, "not enough elements to destructure");
+    } else if items.len() == fixed && values.len() > fixed {
+        bail!(span, "too many elements to destructure");
+    }
+
+    let mut i = 0;
+    for item in items {
+        match item {
+            ast::ArrayItem::Pos(expr) => {
+                assign_nested(vm, expr, values[i].clone())?;
+                i += 1;
+            }
+            ast::ArrayItem::Spread(expr) => {
+                let n = values.len() - fixed;
+                assign_nested(vm, expr, Value::Array(values[i..i + n].into()))?;
+                i += n;
+            }
+        }
     }
+
+    Ok(Value::None)
 }
 
 impl Eval for ast::LetBinding {
     type Output = Value;
 
-    #[tracing::instrument(name = "LetBinding::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LetBinding::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = match self.init() {
             Some(expr) => expr.eval(vm)?,
@@ -1487,6 +1709,7 @@ impl Eval for ast::SetRule {
             })
             .at(target.span())?;
         let args = self.args().eval(vm)?;
+        func::deprecated_params(vm, target.info(), &args);
         Ok(target.set(args)?.spanned(self.span()))
     }
 }
@@ -1516,7 +1739,10 @@ impl Eval for ast::ShowRule {
 impl Eval for ast::Conditional {
     type Output = Value;
 
-    #[tracing::instrument(name = "Conditional::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "Conditional::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let condition = self.condition();
         if condition.eval(vm)?.cast::<bool>().at(condition.span())? {
@@ -1532,7 +1758,10 @@ impl Eval for ast::Conditional {
 impl Eval for ast::WhileLoop {
     type Output = Value;
 
-    #[tracing::instrument(name = "WhileLoop::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "WhileLoop::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let flow = vm.flow.take();
         let mut output = Value::None;
@@ -1593,69 +1822,53 @@ fn is_invariant(expr: &SyntaxNode) -> bool {
 
 /// Whether the expression contains a break or return.
 fn can_diverge(expr: &SyntaxNode) -> bool {
-    matches!(expr.kind(), SyntaxKind::Break | SyntaxKind::Return)
+    expr.is::<ast::LoopBreak>()
+        || expr.is::<ast::FuncReturn>()
         || expr.children().any(can_diverge)
 }
 
 impl Eval for ast::ForLoop {
     type Output = Value;
 
-    #[tracing::instrument(name = "ForLoop::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ForLoop::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let flow = vm.flow.take();
         let mut output = Value::None;
 
-        macro_rules! iter {
-            (for $pat:ident in $iter:expr) => {{
-                vm.scopes.enter();
+        let iter = self.iter().eval(vm)?;
+        let pattern = self.pattern();
 
-                #[allow(unused_parens)]
-                for value in $iter {
-                    $pat.define(vm, value.into_value())?;
+        // The items of strings can't be destructured.
+        if !matches!(pattern, ast::Pattern::Normal(_)) && matches!(iter, Value::Str(_)) {
+            bail!(pattern.span(), "cannot destructure values of {}", iter.type_name());
+        }
 
-                    let body = self.body();
-                    let value = body.eval(vm)?;
-                    output = ops::join(output, value).at(body.span())?;
+        let values = iter.iterate().at(self.iter().span())?;
+        vm.scopes.enter();
 
-                    match vm.flow {
-                        Some(FlowEvent::Break(_)) => {
-                            vm.flow = None;
-                            break;
-                        }
-                        Some(FlowEvent::Continue(_)) => vm.flow = None,
-                        Some(FlowEvent::Return(..)) => break,
-                        None => {}
-                    }
-                }
+        for value in values {
+            pattern.define(vm, value)?;
 
-                vm.scopes.exit();
-            }};
-        }
-
-        let iter = self.iter().eval(vm)?;
-        let pattern = self.pattern();
+            let body = self.body();
+            let value = body.eval(vm)?;
+            output = ops::join(output, value).at(body.span())?;
 
-        match (&pattern, iter.clone()) {
-            (ast::Pattern::Normal(_), Value::Str(string)) => {
-                // Iterate over graphemes of string.
-                iter!(for pattern in string.as_str().graphemes(true));
-            }
-            (_, Value::Dict(dict)) => {
-                // Iterate over pairs of dict.
-                iter!(for pattern in dict.pairs());
-            }
-            (_, Value::Array(array)) => {
-                // Iterate over values of array.
-                iter!(for pattern in array);
-            }
-            (ast::Pattern::Normal(_), _) => {
-                bail!(self.iter().span(), "cannot loop over {}", iter.type_name());
-            }
-            (_, _) => {
-                bail!(pattern.span(), "cannot destructure values of {}", iter.type_name())
+            match vm.flow {
+                Some(FlowEvent::Break(_)) => {
+                    vm.flow = None;
+                    break;
+                }
+                Some(FlowEvent::Continue(_)) => vm.flow = None,
+                Some(FlowEvent::Return(..)) => break,
+                None => {}
             }
         }
 
+        vm.scopes.exit();
+
         if flow.is_some() {
             vm.flow = flow;
         }
@@ -1686,6 +1899,10 @@ fn apply_imports<V: IntoValue>(
             let scope = scope(&source_value);
             for ident in idents {
                 if let Some(value) = scope.get(&ident) {
+                    if let Some(hint) = scope.deprecation(&ident) {
+                        let what = eco_format!("`{}`", ident.as_str());
+                        vm.deprecated(ident.span(), &what, hint);
+                    }
                     vm.define(ident, value.clone());
                 } else {
                     errors.push(error!(ident.span(), "unresolved import"));
@@ -1703,7 +1920,10 @@ fn apply_imports<V: IntoValue>(
 impl Eval for ast::ModuleImport {
     type Output = Value;
 
-    #[tracing::instrument(name = "ModuleImport::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ModuleImport::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let span = self.source().span();
         let source = self.source().eval(vm)?;
@@ -1736,7 +1956,10 @@ impl Eval for ast::ModuleImport {
 impl Eval for ast::ModuleInclude {
     type Output = Content;
 
-    #[tracing::instrument(name = "ModuleInclude::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "ModuleInclude::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let span = self.source().span();
         let source = self.source().eval(vm)?;
@@ -1764,8 +1987,13 @@ fn import(
         }
     };
 
-    // Load the source file.
+    // Download remote modules.
     let world = vm.world();
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return import_url(vm, &path, span);
+    }
+
+    // Load the source file.
     let full = vm.locate(&path).at(span)?;
     let id = world.resolve(&full).at(span)?;
 
@@ -1781,10 +2009,35 @@ fn import(
         .trace(world, point, span)
 }
 
+/// Process an import of a module from an `http` or `https` URL.
+///
+/// The module is evaluated as detached code, so it can neither access the file
+/// system nor download further files.
+fn import_url(vm: &mut Vm, url: &str, span: Span) -> SourceResult<Module> {
+    vm.check_network().at(span)?;
+    let world = vm.world();
+    let data = world.fetch(url).at(span)?;
+    let text = std::str::from_utf8(&data).map_err(FileError::from).at(span)?;
+    let source = Source::detached(text);
+    let point = || Tracepoint::Import;
+    let module =
+        eval(world, vm.route, TrackedMut::reborrow_mut(&mut vm.vt.tracer), &source)
+            .trace(world, point, span)?;
+
+    // Name the module after the last segment of the URL.
+    let name = url.rsplit('/').next().unwrap_or_default();
+    let name = Path::new(name).file_stem().unwrap_or_default().to_string_lossy();
+    let scope = module.scope().clone();
+    Ok(Module::new(name).with_scope(scope).with_content(module.content()))
+}
+
 impl Eval for ast::LoopBreak {
     type Output = Value;
 
-    #[tracing::instrument(name = "LoopBreak::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LoopBreak::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         if vm.flow.is_none() {
             vm.flow = Some(FlowEvent::Break(self.span()));
@@ -1796,7 +2049,10 @@ impl Eval for ast::LoopBreak {
 impl Eval for ast::LoopContinue {
     type Output = Value;
 
-    #[tracing::instrument(name = "LoopContinue::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "LoopContinue::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         if vm.flow.is_none() {
             vm.flow = Some(FlowEvent::Continue(self.span()));
@@ -1808,7 +2064,10 @@ impl Eval for ast::LoopContinue {
 impl Eval for ast::FuncReturn {
     type Output = Value;
 
-    #[tracing::instrument(name = "FuncReturn::eval", skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "FuncReturn::eval", skip_all)
+    )]
     fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
         let value = self.body().map(|body| body.eval(vm)).transpose()?;
         if vm.flow.is_none() {
@@ -5,7 +5,11 @@ use std::fmt::Debug;
 
 use ecow::eco_format;
 
-use super::{format_str, Regex, Value};
+use std::num::NonZeroI64;
+
+use super::{
+    format_str, Datetime, Duration, Dynamic, IntoValue, Range, Regex, Value, Version,
+};
 use crate::diag::{bail, StrResult};
 use crate::geom::{Axes, Axis, GenAlign, Length, Numeric, PartialStroke, Rel, Smart};
 use Value::*;
@@ -33,6 +37,9 @@ pub fn join(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Symbol(a), Content(b)) => Content(item!(text)(a.get().into()) + b),
         (Array(a), Array(b)) => Array(a + b),
         (Dict(a), Dict(b)) => Dict(a + b),
+        (a, b) if is_range(&a) || is_range(&b) => {
+            return join(materialize(a), materialize(b))
+        }
         (a, b) => mismatch!("cannot join {} with {}", a, b),
     })
 }
@@ -47,6 +54,7 @@ pub fn pos(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(v),
         Relative(v) => Relative(v),
         Fraction(v) => Fraction(v),
+        Dyn(v) if v.is::<Duration>() => Dyn(v),
         v => mismatch!("cannot apply '+' to {}", v),
     })
 }
@@ -61,6 +69,10 @@ pub fn neg(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(-v),
         Relative(v) => Relative(-v),
         Fraction(v) => Fraction(-v),
+        Dyn(v) if v.is::<Duration>() => {
+            let duration = v.downcast::<Duration>().unwrap();
+            Value::dynamic(duration.checked_neg().ok_or("duration is too large")?)
+        }
         v => mismatch!("cannot apply '-' to {}", v),
     })
 }
@@ -104,6 +116,9 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Array(a), Array(b)) => Array(a + b),
         (Dict(a), Dict(b)) => Dict(a + b),
+        (a, b) if is_range(&a) || is_range(&b) => {
+            return add(materialize(a), materialize(b))
+        }
 
         (Color(color), Length(thickness)) | (Length(thickness), Color(color)) => {
             Value::dynamic(PartialStroke {
@@ -128,6 +143,21 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
                 }));
             };
 
+            // Durations can be added to each other and to datetimes.
+            if let Some(&b) = b.downcast::<Duration>() {
+                if let Some(&a) = a.downcast::<Duration>() {
+                    return Ok(Value::dynamic(
+                        a.checked_add(b).ok_or("duration is too large")?,
+                    ));
+                } else if let Some(&a) = a.downcast::<Datetime>() {
+                    return Ok(Value::dynamic(a.add_duration(b)?));
+                }
+            } else if let (Some(&a), Some(&b)) =
+                (a.downcast::<Duration>(), b.downcast::<Datetime>())
+            {
+                return Ok(Value::dynamic(b.add_duration(a)?));
+            }
+
             mismatch!("cannot add {} and {}", a, b);
         }
 
@@ -159,6 +189,24 @@ pub fn sub(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Fraction(a), Fraction(b)) => Fraction(a - b),
 
+        (Dyn(a), Dyn(b)) => {
+            if let Some(&b) = b.downcast::<Duration>() {
+                if let Some(&a) = a.downcast::<Duration>() {
+                    return Ok(Value::dynamic(
+                        a.checked_sub(b).ok_or("duration is too large")?,
+                    ));
+                } else if let Some(&a) = a.downcast::<Datetime>() {
+                    return Ok(Value::dynamic(a.sub_duration(b)?));
+                }
+            } else if let (Some(&a), Some(&b)) =
+                (a.downcast::<Datetime>(), b.downcast::<Datetime>())
+            {
+                return Ok(Value::dynamic(a.duration_since(b)?));
+            }
+
+            mismatch!("cannot subtract {1} from {0}", a, b);
+        }
+
         (a, b) => mismatch!("cannot subtract {1} from {0}", a, b),
     })
 }
@@ -209,9 +257,17 @@ pub fn mul(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Int(a), Str(b)) => Str(b.repeat(a)?),
         (Array(a), Int(b)) => Array(a.repeat(b)?),
         (Int(a), Array(b)) => Array(b.repeat(a)?),
+        (a, b) if is_range(&a) || is_range(&b) => {
+            return mul(materialize(a), materialize(b))
+        }
         (Content(a), b @ Int(_)) => Content(a.repeat(b.cast()?)),
         (a @ Int(_), Content(b)) => Content(b.repeat(a.cast()?)),
 
+        (Dyn(a), Int(b)) if a.is::<Duration>() => scale_duration(&a, b as f64)?,
+        (Dyn(a), Float(b)) if a.is::<Duration>() => scale_duration(&a, b)?,
+        (Int(a), Dyn(b)) if b.is::<Duration>() => scale_duration(&b, a as f64)?,
+        (Float(a), Dyn(b)) if b.is::<Duration>() => scale_duration(&b, a)?,
+
         (a, b) => mismatch!("cannot multiply {} with {}", a, b),
     })
 }
@@ -252,10 +308,24 @@ pub fn div(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Fraction(a), Float(b)) => Fraction(a / b),
         (Fraction(a), Fraction(b)) => Float(a / b),
 
+        (Dyn(a), Int(b)) if a.is::<Duration>() => scale_duration(&a, 1.0 / b as f64)?,
+        (Dyn(a), Float(b)) if a.is::<Duration>() => scale_duration(&a, 1.0 / b)?,
+        (Dyn(a), Dyn(b)) if a.is::<Duration>() && b.is::<Duration>() => {
+            let a = a.downcast::<Duration>().unwrap();
+            let b = b.downcast::<Duration>().unwrap();
+            Float(a.seconds() / b.seconds())
+        }
+
         (a, b) => mismatch!("cannot divide {} by {}", a, b),
     })
 }
 
+/// Scale a dynamic value that is known to be a duration.
+fn scale_duration(duration: &Dynamic, factor: f64) -> StrResult<Value> {
+    let duration = duration.downcast::<Duration>().unwrap();
+    Ok(Value::dynamic(duration.checked_mul(factor).ok_or("duration is too large")?))
+}
+
 /// Whether a value is a numeric zero.
 fn is_zero(v: &Value) -> bool {
     match *v {
@@ -266,6 +336,7 @@ fn is_zero(v: &Value) -> bool {
         Ratio(v) => v.is_zero(),
         Relative(v) => v.is_zero(),
         Fraction(v) => v.is_zero(),
+        Dyn(ref v) => v.downcast::<Duration>().map_or(false, Duration::is_zero),
         _ => false,
     }
 }
@@ -363,6 +434,11 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (&Ratio(a), &Relative(b)) => a == b.rel && b.abs.is_zero(),
         (&Relative(a), &Length(b)) => a.abs == b && a.rel.is_zero(),
         (&Relative(a), &Ratio(b)) => a.rel == b && a.abs.is_zero(),
+        (Dyn(a), Array(b)) | (Array(b), Dyn(a)) if a.is::<Range>() => {
+            let range = a.downcast::<Range>().unwrap();
+            range.len() == b.len()
+                && range.iter().zip(b).all(|(x, value)| equal(&Int(x), value))
+        }
 
         _ => false,
     }
@@ -380,6 +456,18 @@ pub fn compare(lhs: &Value, rhs: &Value) -> StrResult<Ordering> {
         (Relative(a), Relative(b)) => try_cmp_values(a, b)?,
         (Fraction(a), Fraction(b)) => a.cmp(b),
         (Str(a), Str(b)) => a.cmp(b),
+        (Dyn(a), Dyn(b)) => match (a.downcast::<Duration>(), b.downcast::<Duration>()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            _ => match (a.downcast::<Datetime>(), b.downcast::<Datetime>()) {
+                (Some(a), Some(b)) => a
+                    .ordering(b)
+                    .ok_or_else(|| eco_format!("cannot compare {:?} and {:?}", a, b))?,
+                _ => match (a.downcast::<Version>(), b.downcast::<Version>()) {
+                    (Some(a), Some(b)) => a.cmp(b),
+                    _ => mismatch!("cannot compare {} and {}", lhs, rhs),
+                },
+            },
+        },
 
         // Some technically different things should be comparable.
         (Int(a), Float(b)) => try_cmp_values(&(*a as f64), b)?,
@@ -417,6 +505,14 @@ pub fn not_in(lhs: Value, rhs: Value) -> StrResult<Value> {
     }
 }
 
+/// Create the range of integers between two values.
+pub fn range(lhs: Value, rhs: Value) -> StrResult<Value> {
+    Ok(match (lhs, rhs) {
+        (Int(a), Int(b)) => Range::new(a, b, NonZeroI64::new(1).unwrap()).into_value(),
+        (a, b) => mismatch!("cannot create range from {} and {}", a, b),
+    })
+}
+
 /// Test for containment.
 pub fn contains(lhs: &Value, rhs: &Value) -> Option<bool> {
     match (lhs, rhs) {
@@ -424,6 +520,24 @@ pub fn contains(lhs: &Value, rhs: &Value) -> Option<bool> {
         (Dyn(a), Str(b)) => a.downcast::<Regex>().map(|regex| regex.is_match(b)),
         (Str(a), Dict(b)) => Some(b.contains(a)),
         (a, Array(b)) => Some(b.contains(a)),
+        (a, Dyn(b)) if b.is::<Range>() => {
+            Some(b.downcast::<Range>().unwrap().contains(a))
+        }
         _ => Option::None,
     }
 }
+
+/// Whether a value is a range.
+fn is_range(value: &Value) -> bool {
+    matches!(value, Dyn(dynamic) if dynamic.is::<Range>())
+}
+
+/// Turn a range into an array, for operations that have no lazy version.
+fn materialize(value: Value) -> Value {
+    match value {
+        Dyn(dynamic) if dynamic.is::<Range>() => {
+            Array(dynamic.downcast::<Range>().unwrap().to_array())
+        }
+        v => v,
+    }
+}
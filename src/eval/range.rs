@@ -0,0 +1,195 @@
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroI64;
+
+use ecow::{eco_format, EcoString};
+
+use super::{cast, Array, Value};
+use crate::diag::StrResult;
+
+/// A sequence of integers with a constant step between them.
+///
+/// The integers are computed on demand instead of being stored, so that
+/// iterating over a large range doesn't allocate. Operations that have no
+/// lazy version, like mapping, turn the range into an array. To documents, a
+/// range is indistinguishable from an array: It has the same type name,
+/// representation and error messages.
+#[derive(Copy, Clone)]
+pub struct Range {
+    start: i64,
+    end: i64,
+    step: NonZeroI64,
+}
+
+impl Range {
+    /// Create a new range from `start` (inclusive) to `end` (exclusive).
+    pub fn new(start: i64, end: i64, step: NonZeroI64) -> Self {
+        Self { start, end, step }
+    }
+
+    /// The number of integers in the range.
+    pub fn len(&self) -> usize {
+        let (start, end, step) = (self.start as i128, self.end as i128, self.step.get());
+        let distance = if step > 0 { end - start } else { start - end };
+        let step = step.unsigned_abs() as i128;
+        if distance <= 0 {
+            0
+        } else {
+            ((distance + step - 1) / step) as usize
+        }
+    }
+
+    /// Whether the range contains no integers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The first integer in the range.
+    pub fn first(&self) -> StrResult<i64> {
+        self.get(0).ok_or_else(range_is_empty)
+    }
+
+    /// The last integer in the range.
+    pub fn last(&self) -> StrResult<i64> {
+        self.len()
+            .checked_sub(1)
+            .and_then(|i| self.get(i))
+            .ok_or_else(range_is_empty)
+    }
+
+    /// The integer at the given index, which may be negative to count from
+    /// the back.
+    pub fn at(&self, index: i64, default: Option<Value>) -> StrResult<Value> {
+        self.locate(index)
+            .and_then(|i| self.get(i))
+            .map(Value::Int)
+            .or(default)
+            .ok_or_else(|| out_of_bounds_no_default(index, self.len()))
+    }
+
+    /// The part of the range between two indices, as another range.
+    pub fn slice(&self, start: i64, end: Option<i64>) -> StrResult<Self> {
+        let len = self.len();
+        let start = self
+            .locate(start)
+            .filter(|&start| start <= len)
+            .ok_or_else(|| out_of_bounds(start, len))?;
+
+        let end = end.unwrap_or(len as i64);
+        let end = self
+            .locate(end)
+            .filter(|&end| end <= len)
+            .ok_or_else(|| out_of_bounds(end, len))?
+            .max(start);
+
+        Ok(Self::new(self.nth(start), self.nth(end), self.step))
+    }
+
+    /// Whether the range contains the value.
+    pub fn contains(&self, value: &Value) -> bool {
+        let x = match *value {
+            Value::Int(x) => x,
+            Value::Float(x) if x.fract() == 0.0 => x as i64,
+            _ => return false,
+        };
+
+        let offset = x as i128 - self.start as i128;
+        let step = self.step.get() as i128;
+        offset % step == 0 && (0..self.len() as i128).contains(&(offset / step))
+    }
+
+    /// The range in reverse order.
+    pub fn rev(&self) -> Self {
+        let Ok(last) = self.last() else { return *self };
+        let step = self.step.get();
+        let reversed = NonZeroI64::new(step.saturating_neg()).unwrap();
+        Self::new(last, self.start.saturating_sub(step), reversed)
+    }
+
+    /// Iterate over the integers in the range.
+    pub fn iter(self) -> impl Iterator<Item = i64> {
+        (0..self.len()).map(move |i| self.nth(i))
+    }
+
+    /// Collect the integers in the range into an array.
+    pub fn to_array(&self) -> Array {
+        self.iter().map(Value::Int).collect()
+    }
+
+    /// The integer at an index that may lie outside of the range.
+    fn nth(&self, index: usize) -> i64 {
+        let x = self.start as i128 + index as i128 * self.step.get() as i128;
+        x.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// The integer at the given index, if it exists.
+    fn get(&self, index: usize) -> Option<i64> {
+        (index < self.len()).then(|| self.nth(index))
+    }
+
+    /// Resolve an index, which may be negative.
+    fn locate(&self, index: i64) -> Option<usize> {
+        usize::try_from(if index >= 0 {
+            index
+        } else {
+            (self.len() as i64).checked_add(index)?
+        })
+        .ok()
+    }
+}
+
+impl Debug for Range {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.to_array(), f)
+    }
+}
+
+impl PartialEq for Range {
+    /// Ranges are equal if they contain the same integers.
+    fn eq(&self, other: &Self) -> bool {
+        let len = self.len();
+        len == other.len()
+            && (len == 0 || self.start == other.start)
+            && (len <= 1 || self.step == other.step)
+    }
+}
+
+impl Eq for Range {}
+
+impl Hash for Range {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let len = self.len();
+        len.hash(state);
+        if len > 0 {
+            self.start.hash(state);
+        }
+        if len > 1 {
+            self.step.hash(state);
+        }
+    }
+}
+
+cast! {
+    type Range: "array",
+}
+
+/// The error message when the range is empty.
+#[cold]
+fn range_is_empty() -> EcoString {
+    "array is empty".into()
+}
+
+/// The out of bounds access error message.
+#[cold]
+fn out_of_bounds(index: i64, len: usize) -> EcoString {
+    eco_format!("array index out of bounds (index: {index}, len: {len})")
+}
+
+/// The out of bounds access error message when no default value was given.
+#[cold]
+fn out_of_bounds_no_default(index: i64, len: usize) -> EcoString {
+    eco_format!(
+        "array index out of bounds (index: {index}, len: {len}) \
+         and no default value was specified",
+    )
+}
@@ -45,6 +45,15 @@ impl<'a> Scopes<'a> {
             .ok_or_else(|| unknown_variable(var))
     }
 
+    /// The deprecation hint of a variable, if it is deprecated.
+    pub fn deprecation(&self, var: &str) -> Option<&'static str> {
+        std::iter::once(&self.top)
+            .chain(self.scopes.iter().rev())
+            .chain(self.base.map(|base| base.global.scope()))
+            .find(|scope| scope.get(var).is_some())?
+            .deprecation(var)
+    }
+
     /// Try to access a variable immutably in math.
     pub fn get_in_math(&self, var: &str) -> StrResult<&Value> {
         std::iter::once(&self.top)
@@ -66,6 +75,22 @@ impl<'a> Scopes<'a> {
                 }
             })?
     }
+
+    /// Capture the currently visible bindings, except for the standard
+    /// library, in a single scope.
+    ///
+    /// Inner bindings shadow outer ones. The snapshot is independent of the
+    /// stack, so it can be used to evaluate code in a forked scope without
+    /// affecting the original one.
+    pub fn snapshot(&self) -> Scope {
+        let mut snapshot = Scope::new();
+        for scope in self.scopes.iter().chain(std::iter::once(&self.top)) {
+            for (name, slot) in &scope.0 {
+                snapshot.0.insert(name.clone(), slot.clone());
+            }
+        }
+        snapshot
+    }
 }
 
 /// The error message when a variable is not found.
@@ -106,6 +131,24 @@ impl Scope {
         self.0.insert(name, Slot::new(value.into_value(), Kind::Normal));
     }
 
+    /// Bind a value to a name that is deprecated.
+    ///
+    /// Accessing the binding raises a warning with the given hint, which should
+    /// name the replacement, like "use `calc.rem` instead".
+    #[track_caller]
+    pub fn define_deprecated(
+        &mut self,
+        name: impl Into<EcoString>,
+        value: impl IntoValue,
+        hint: &'static str,
+    ) {
+        let name = name.into();
+        self.define(name.clone(), value);
+        if let Some(slot) = self.0.get_mut(&name) {
+            slot.deprecation = Some(hint);
+        }
+    }
+
     /// Define a captured, immutable binding.
     pub fn define_captured(&mut self, var: impl Into<EcoString>, value: impl IntoValue) {
         self.0
@@ -122,6 +165,11 @@ impl Scope {
         self.0.get_mut(var).map(Slot::write)
     }
 
+    /// The deprecation hint of a binding, if it is deprecated.
+    pub fn deprecation(&self, var: &str) -> Option<&'static str> {
+        self.0.get(var).and_then(|slot| slot.deprecation)
+    }
+
     /// Iterate over all definitions.
     pub fn iter(&self) -> impl Iterator<Item = (&EcoString, &Value)> {
         self.0.iter().map(|(k, v)| (k, v.read()))
@@ -144,6 +192,8 @@ struct Slot {
     value: Value,
     /// The kind of slot, determines how the value can be accessed.
     kind: Kind,
+    /// A hint on what to use instead, if the binding is deprecated.
+    deprecation: Option<&'static str>,
 }
 
 /// The different kinds of slots.
@@ -158,7 +208,7 @@ enum Kind {
 impl Slot {
     /// Create a new slot.
     fn new(value: Value, kind: Kind) -> Self {
-        Self { value, kind }
+        Self { value, kind, deprecation: None }
     }
 
     /// Read the value.
@@ -9,7 +9,7 @@ use siphasher::sip128::{Hasher128, SipHasher13};
 
 use super::{
     cast, format_str, ops, Args, Array, CastInfo, Content, Dict, FromValue, Func,
-    IntoValue, Module, Reflect, Str, Symbol,
+    IntoValue, Module, Range, Reflect, Str, Symbol, Version,
 };
 use crate::diag::StrResult;
 use crate::geom::{Abs, Angle, Color, Em, Fr, Length, Ratio, Rel};
@@ -128,10 +128,30 @@ impl Value {
             Self::Content(content) => content.at(field, None),
             Self::Module(module) => module.get(field).cloned(),
             Self::Func(func) => func.get(field).cloned(),
+            Self::Dyn(dynamic) if dynamic.is::<Version>() => {
+                dynamic.downcast::<Version>().unwrap().field(field)
+            }
             v => Err(eco_format!("cannot access fields on type {}", v.type_name())),
         }
     }
 
+    /// Iterate over the value, like a `for` loop does.
+    ///
+    /// Strings yield their grapheme clusters, dictionaries their key-value
+    /// pairs as arrays and arrays and ranges their items.
+    pub fn iterate(self) -> StrResult<Box<dyn Iterator<Item = Value>>> {
+        Ok(match self {
+            Self::Str(string) => Box::new(string.clusters().into_iter()),
+            Self::Dict(dict) => Box::new(dict.pairs().into_iter()),
+            Self::Array(array) => Box::new(array.into_iter()),
+            Self::Dyn(dynamic) if dynamic.is::<Range>() => {
+                let range = *dynamic.downcast::<Range>().unwrap();
+                Box::new(range.iter().map(Self::Int))
+            }
+            v => return Err(eco_format!("cannot loop over {}", v.type_name())),
+        })
+    }
+
     /// Return the debug representation of the value.
     pub fn repr(&self) -> Str {
         format_str!("{:?}", self)
@@ -211,6 +231,48 @@ impl PartialOrd for Value {
     }
 }
 
+impl Value {
+    /// Feed the span-independent structure of the value into the hasher.
+    ///
+    /// Used for the [structural hash](Content::structural_hash) of content.
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Content(content) => {
+                std::mem::discriminant(self).hash(state);
+                content.hash_structure(state);
+            }
+            Self::Styles(styles) => {
+                std::mem::discriminant(self).hash(state);
+                styles.hash_structure(state);
+            }
+            Self::Array(array) => {
+                std::mem::discriminant(self).hash(state);
+                state.write_usize(array.len());
+                for item in array {
+                    item.hash_structure(state);
+                }
+            }
+            Self::Dict(dict) => {
+                std::mem::discriminant(self).hash(state);
+                state.write_usize(dict.len());
+                for (key, item) in dict {
+                    key.hash(state);
+                    item.hash_structure(state);
+                }
+            }
+            Self::Func(func) => {
+                std::mem::discriminant(self).hash(state);
+                func.hash_structure(state);
+            }
+            Self::Args(args) => {
+                std::mem::discriminant(self).hash(state);
+                args.hash_structure(state);
+            }
+            other => other.hash(state),
+        }
+    }
+}
+
 impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
         std::mem::discriminant(self).hash(state);
@@ -312,7 +374,7 @@ where
         T::TYPE_NAME
     }
 
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn hash128(&self) -> u128 {
         // Also hash the TypeId since values with different types but
         // equal data should be different.
@@ -408,12 +470,50 @@ primitive! { Content: "content",
     Str(v) => item!(text)(v.into())
 }
 primitive! { Styles: "styles", Styles }
-primitive! { Array: "array", Array }
 primitive! { Dict: "dictionary", Dict }
 primitive! { Func: "function", Func }
 primitive! { Args: "arguments", Args }
 primitive! { Module: "module", Module }
 
+// Arrays are implemented by hand since ranges can be cast into them, too.
+impl Type for Array {
+    const TYPE_NAME: &'static str = "array";
+}
+
+impl Reflect for Array {
+    fn describe() -> CastInfo {
+        CastInfo::Type(Self::TYPE_NAME)
+    }
+
+    fn castable(value: &Value) -> bool {
+        match value {
+            Value::Array(_) => true,
+            Value::Dyn(dynamic) => dynamic.is::<Range>(),
+            _ => false,
+        }
+    }
+}
+
+impl IntoValue for Array {
+    fn into_value(self) -> Value {
+        Value::Array(self)
+    }
+}
+
+impl FromValue for Array {
+    fn from_value(value: Value) -> StrResult<Self> {
+        match value {
+            Value::Array(array) => Ok(array),
+            Value::Dyn(dynamic) if dynamic.is::<Range>() => {
+                Ok(dynamic.downcast::<Range>().unwrap().to_array())
+            }
+            v => {
+                Err(eco_format!("expected {}, found {}", Self::TYPE_NAME, v.type_name()))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
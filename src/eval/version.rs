@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::str::FromStr;
+
+use ecow::{eco_format, EcoString, EcoVec};
+
+use crate::diag::StrResult;
+use crate::eval::{cast, IntoValue, Value};
+use crate::util::pretty_array_like;
+
+/// A version with an arbitrary number of components.
+///
+/// Missing components are treated as zero, so `1.2` and `1.2.0` are equal.
+#[derive(Default, Clone)]
+pub struct Version(EcoVec<u32>);
+
+impl Version {
+    /// The names of the first three components, which can be accessed as
+    /// fields.
+    pub const COMPONENTS: [&'static str; 3] = ["major", "minor", "patch"];
+
+    /// Create a new version from its components.
+    pub fn new(components: impl IntoIterator<Item = u32>) -> Self {
+        Self(components.into_iter().collect())
+    }
+
+    /// The components of the version.
+    pub fn components(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Get a component of the version, which is zero if it is missing.
+    pub fn at(&self, index: i64) -> StrResult<u32> {
+        let len = self.0.len();
+        let index = if index < 0 { index + len as i64 } else { index };
+        if index < 0 {
+            return Err(eco_format!(
+                "component index out of bounds (index: {index}, len: {len})"
+            ));
+        }
+        Ok(self.0.get(index as usize).copied().unwrap_or(0))
+    }
+
+    /// Access a named component of the version.
+    pub fn field(&self, field: &str) -> StrResult<Value> {
+        match Self::COMPONENTS.iter().position(|&name| name == field) {
+            Some(index) => Ok(self.at(index as i64)?.into_value()),
+            None => Err(eco_format!("unknown version component `{field}`")),
+        }
+    }
+
+    /// The components without trailing zeros.
+    fn significant(&self) -> &[u32] {
+        let len = self.0.iter().rposition(|&c| c != 0).map_or(0, |i| i + 1);
+        &self.0[..len]
+    }
+}
+
+impl FromStr for Version {
+    type Err = EcoString;
+
+    /// Parse a version like `0.5.0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split('.')
+            .map(|part| {
+                part.parse::<u32>()
+                    .map_err(|_| eco_format!("invalid version component `{part}`"))
+            })
+            .collect::<StrResult<EcoVec<_>>>()
+            .map(Self)
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant() == other.significant()
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.0.len().max(other.0.len());
+        let pad = |v: &Self| v.0.iter().copied().chain(iter::repeat(0)).take(len);
+        pad(self).cmp(pad(other))
+    }
+}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.significant().hash(state);
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, component) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{component}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let parts: Vec<_> = self.0.iter().map(|c| eco_format!("{c}")).collect();
+        write!(f, "version{}", &pretty_array_like(&parts, false))
+    }
+}
+
+cast! {
+    type Version: "version",
+}
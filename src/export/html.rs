@@ -0,0 +1,67 @@
+//! Exporting into HTML.
+
+use std::fmt::Write;
+
+use super::svg::{escape, svg_numbered};
+use crate::doc::Document;
+
+/// Export a document into an HTML file.
+///
+/// Each page is embedded as an inline SVG image one below the other, so the
+/// file can be opened in any browser and links remain clickable.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn html(document: &Document) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n");
+    html.push_str("<meta charset=\"utf-8\">\n");
+    if let Some(title) = &document.title {
+        writeln!(html, "<title>{}</title>", escape(title)).unwrap();
+    }
+    html.push_str("<style>svg { display: block; margin: 1em auto; }</style>\n");
+    html.push_str("</head>\n<body>\n");
+    let mut ids = 0;
+    for frame in &document.pages {
+        html.push_str(&svg_numbered(frame, &mut ids));
+        html.push('\n');
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::Frame;
+    use crate::doc::{FrameItem, GroupItem};
+    use crate::geom::{Abs, Point, Size};
+
+    #[test]
+    fn test_html_pages() {
+        let mut document = Document::default();
+        document.title = Some("Tom & Jerry".into());
+        let size = Size::new(Abs::pt(100.0), Abs::pt(50.0));
+        document.pages = vec![Frame::new(size), Frame::new(size)];
+
+        let html = html(&document);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Tom &amp; Jerry</title>"));
+        assert_eq!(html.matches("<svg ").count(), 2);
+        assert!(html.contains(r#"width="100pt" height="50pt""#));
+    }
+
+    #[test]
+    fn test_html_unique_ids() {
+        let size = Size::splat(Abs::pt(10.0));
+        let mut group = GroupItem::new(Frame::new(size));
+        group.clips = true;
+        let mut page = Frame::new(size);
+        page.push(Point::zero(), FrameItem::Group(group));
+        let mut document = Document::default();
+        document.pages = vec![page.clone(), page];
+
+        // The pages' definitions must not clash within the HTML document.
+        let html = html(&document);
+        assert_eq!(html.matches(r#"<clipPath id="c1">"#).count(), 1);
+        assert_eq!(html.matches(r#"<clipPath id="c2">"#).count(), 1);
+        assert!(html.contains("url(#c1)") && html.contains("url(#c2)"));
+    }
+}
@@ -1,7 +1,120 @@
 //! Exporting into external formats.
 
+pub mod serial;
+
+mod html;
 mod pdf;
 mod render;
+mod svg;
+
+pub use self::html::html;
+pub use self::pdf::{
+    pdf, pdf_with_options, ColorProfile, PdfExporter, PdfOptions, ProfileSpace,
+    RenderingIntent,
+};
+pub use self::render::{render, render_thumbnail, visible_pages, PageCache};
+pub use self::svg::svg;
+
+use std::io::Read;
+
+use once_cell::sync::Lazy;
+use ttf_parser::GlyphId;
+
+use crate::font::Font;
+use crate::image::{Image, ImageFormat, VectorFormat};
+use crate::util::lru::Lru;
+
+/// The format a document is exported into.
+///
+/// Scripts can query the active target with `sys.target()` to produce
+/// format-specific content.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Target {
+    /// A PDF document.
+    #[default]
+    Pdf,
+    /// Raster images of the pages.
+    Png,
+    /// An HTML document with the pages as inline SVG images.
+    Html,
+}
+
+impl Target {
+    /// The name of the target as seen by scripts.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Png => "png",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// The names of the size-bounded caches that hold images and glyphs for
+/// export. Their limits can be configured with
+/// [`set_limit`](crate::util::lru::set_limit).
+pub const MEMORY_CACHES: &[&str] =
+    &[crate::image::RASTER_CACHE, render::GLYPH_CACHE, SVG_GLYPH_CACHE];
+
+/// Glyphs whose SVG documents were already turned into images.
+static SVG_GLYPHS: Lazy<Lru<(Font, u16), Option<Image>>> =
+    Lazy::new(|| Lru::new(SVG_GLYPH_CACHE, 16 * 1024 * 1024));
+
+/// The name of the cache of images made from SVG glyphs.
+const SVG_GLYPH_CACHE: &str = "svg-glyphs";
+
+/// Turn a glyph's SVG document into an image spanning the em square
+/// horizontally and the font's ascender to descender vertically.
+///
+/// Returns `None` if the glyph has no SVG document or its document has a view
+/// box of its own. The images are cached per glyph, since the same glyphs
+/// tend to be drawn many times.
+fn svg_glyph(font: &Font, id: GlyphId) -> Option<Image> {
+    SVG_GLYPHS.get_or_insert(
+        (font.clone(), id.0),
+        || svg_glyph_impl(font, id),
+        |image| image.as_ref().map_or(0, |image| image.data().len()),
+    )
+}
+
+/// Build the image for a glyph's SVG document.
+///
+/// SVG glyphs are drawn in font units with the baseline at `y = 0`, so the
+/// document is nested in a wrapper whose view box starts at the ascender.
+fn svg_glyph_impl(font: &Font, id: GlyphId) -> Option<Image> {
+    let mut data = font.ttf().glyph_svg_image(id)?;
+
+    // Decompress SVGZ.
+    let mut decoded = vec![];
+    if data.starts_with(&[0x1f, 0x8b]) {
+        let mut decoder = flate2::read::GzDecoder::new(data);
+        decoder.read_to_end(&mut decoded).ok()?;
+        data = &decoded;
+    }
+
+    // Documents with their own view box are scaled differently, which we
+    // don't handle here.
+    let xml = std::str::from_utf8(data).ok()?;
+    let document = roxmltree::Document::parse(xml).ok()?;
+    let root = document.root_element();
+    if root.has_attribute("viewBox") {
+        return None;
+    }
+
+    let start = xml.find("<svg")?;
+    let upem = font.units_per_em();
+    let metrics = font.metrics();
+    let ascender = metrics.ascender.get() * upem;
+    let height = (metrics.ascender - metrics.descender).get() * upem;
+    let wrapped = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+         xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+         width=\"{upem}\" height=\"{height}\" \
+         viewBox=\"0 {} {upem} {height}\"><svg overflow=\"visible\"{}</svg>",
+        -ascender,
+        &xml[start + 4..],
+    );
 
-pub use self::pdf::pdf;
-pub use self::render::render;
+    Image::new(wrapped.into_bytes().into(), ImageFormat::Vector(VectorFormat::Svg), None)
+        .ok()
+}
@@ -0,0 +1,889 @@
+use std::hash::{Hash, Hasher};
+
+use super::{ColorProfile, ProfileSpace, RenderingIntent};
+use crate::diag::{bail, StrResult};
+use crate::geom::{CmykColor, Color, RgbaColor};
+use crate::util::Buffer;
+
+/// The white point of the profile connection space.
+const D50: [f32; 3] = [0.9642, 1.0, 0.8249];
+
+/// Maps linear sRGB to XYZ relative to D50, with Bradford adaptation from
+/// sRGB's D65 white point.
+const SRGB_TO_XYZ: [[f32; 3]; 3] = [
+    [0.4360747, 0.3850649, 0.1430804],
+    [0.2225045, 0.7168786, 0.0606169],
+    [0.0139322, 0.0971045, 0.7141733],
+];
+
+/// The number of samples of an inverted tone curve.
+const INVERSE_SAMPLES: usize = 4096;
+
+/// Converts sRGB colors into the space of a color profile.
+///
+/// Colors are mapped into the profile connection space and from there into
+/// the profile's space with the lookup tables of the rendering intent, like
+/// the ones of most CMYK press profiles, or with the inverse of its matrix
+/// and tone curves, like the ones of most RGB display profiles. Without ICC
+/// data, CMYK colors are computed with a simple formula.
+#[derive(Debug, Clone)]
+pub struct ColorTransform {
+    icc: Option<Buffer>,
+    intent: RenderingIntent,
+    mapping: Mapping,
+    /// Scales colors in the connection space before they are mapped. This
+    /// simulates the profile's media white for the absolute colorimetric
+    /// intent.
+    scale: [f32; 3],
+}
+
+impl ColorTransform {
+    /// Create the transform into a color profile's space.
+    ///
+    /// Returns `None` if colors are already in the profile's space.
+    pub fn new(profile: &ColorProfile) -> StrResult<Option<Self>> {
+        let Some(icc) = &profile.icc else {
+            return Ok(match profile.space {
+                ProfileSpace::Srgb => None,
+                ProfileSpace::Cmyk => Some(Self {
+                    icc: None,
+                    intent: profile.intent,
+                    mapping: Mapping::Naive,
+                    scale: [1.0; 3],
+                }),
+            });
+        };
+
+        let (mapping, scale) = match parse(icc, profile.space, profile.intent) {
+            Some(parsed) => parsed,
+            None => bail!("color profile is malformed or not supported"),
+        };
+
+        Ok(Some(Self {
+            icc: Some(icc.clone()),
+            intent: profile.intent,
+            mapping,
+            scale,
+        }))
+    }
+
+    /// The number of components of the converted colors.
+    pub fn channels(&self) -> usize {
+        match &self.mapping {
+            Mapping::Naive => 4,
+            Mapping::Matrix { .. } => 3,
+            Mapping::Lut { lut, .. } => lut.outputs,
+        }
+    }
+
+    /// Convert a color, keeping its alpha channel.
+    pub fn apply(&self, color: RgbaColor) -> Color {
+        let [a, b, c, d] = self.convert([color.r, color.g, color.b]);
+        match self.channels() {
+            3 => Color::Rgba(RgbaColor::new(a, b, c, color.a)),
+            _ => Color::Cmyk(CmykColor::new(a, b, c, d)),
+        }
+    }
+
+    /// Convert the components of an sRGB color.
+    ///
+    /// Only the first [`channels`](Self::channels) components of the result
+    /// are used.
+    pub fn convert(&self, rgb: [u8; 3]) -> [u8; 4] {
+        if let Mapping::Naive = self.mapping {
+            let CmykColor { c, m, y, k } =
+                RgbaColor::new(rgb[0], rgb[1], rgb[2], 255).to_cmyk();
+            return [c, m, y, k];
+        }
+
+        let linear = rgb.map(|c| {
+            let c = c as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        });
+
+        let mut xyz = mul(&SRGB_TO_XYZ, linear);
+        for (v, s) in xyz.iter_mut().zip(self.scale) {
+            *v *= s;
+        }
+
+        let out = match &self.mapping {
+            Mapping::Naive => unreachable!(),
+            Mapping::Matrix { inverse, curves } => {
+                let [r, g, b] = mul(inverse, xyz);
+                [curves[0].eval(r), curves[1].eval(g), curves[2].eval(b), 0.0]
+            }
+            Mapping::Lut { lut, lab } => {
+                let input = if *lab {
+                    let [l, a, b] = to_lab(xyz);
+                    if lut.legacy {
+                        [
+                            l / 100.0 * 65280.0 / 65535.0,
+                            (a + 128.0) * 256.0 / 65535.0,
+                            (b + 128.0) * 256.0 / 65535.0,
+                        ]
+                    } else {
+                        [l / 100.0, (a + 128.0) / 255.0, (b + 128.0) / 255.0]
+                    }
+                } else {
+                    xyz.map(|v| v * 32768.0 / 65535.0)
+                };
+                lut.eval(input)
+            }
+        };
+
+        out.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+}
+
+impl Hash for ColorTransform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.icc.hash(state);
+        self.intent.hash(state);
+    }
+}
+
+/// How colors are mapped from the connection space into the profile's space.
+#[derive(Debug, Clone)]
+enum Mapping {
+    /// The simple formula to CMYK used without ICC data.
+    Naive,
+    /// The inverse of an RGB profile's matrix and tone curves.
+    Matrix { inverse: [[f32; 3]; 3], curves: [Curve; 3] },
+    /// An output lookup table for colors in XYZ or, if `lab` is true, Lab.
+    Lut { lut: Lut, lab: bool },
+}
+
+/// Parse the mapping into a profile's space for a rendering intent and the
+/// scale of colors in the connection space from ICC data.
+fn parse(
+    icc: &[u8],
+    space: ProfileSpace,
+    intent: RenderingIntent,
+) -> Option<(Mapping, [f32; 3])> {
+    let channels = match (icc.get(16..20)?, space) {
+        (b"RGB ", ProfileSpace::Srgb) => 3,
+        (b"CMYK", ProfileSpace::Cmyk) => 4,
+        _ => return None,
+    };
+
+    let lab = match icc.get(20..24)? {
+        b"Lab " => true,
+        b"XYZ " => false,
+        _ => return None,
+    };
+
+    // Each intent has its own table. If a profile lacks it, another one is
+    // used, starting with the perceptual one.
+    let preferred: &[u8; 4] = match intent {
+        RenderingIntent::Perceptual => b"B2A0",
+        RenderingIntent::RelativeColorimetric | RenderingIntent::AbsoluteColorimetric => {
+            b"B2A1"
+        }
+        RenderingIntent::Saturation => b"B2A2",
+    };
+
+    let table = [preferred, b"B2A0", b"B2A1", b"B2A2"]
+        .into_iter()
+        .find_map(|sig| find_tag(icc, sig));
+
+    let mapping = match table {
+        Some(data) => {
+            let lut = Lut::parse(data, lab)?;
+            if lut.outputs != channels {
+                return None;
+            }
+            Mapping::Lut { lut, lab }
+        }
+        None if channels == 3 && !lab => {
+            let column = |sig| read_xyz(find_tag(icc, sig)?);
+            let [r, g, b] = [column(b"rXYZ")?, column(b"gXYZ")?, column(b"bXYZ")?];
+            let matrix = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+            let curve = |sig| Some(Curve::parse(find_tag(icc, sig)?)?.0.inverse());
+            Mapping::Matrix {
+                inverse: invert(matrix)?,
+                curves: [curve(b"rTRC")?, curve(b"gTRC")?, curve(b"bTRC")?],
+            }
+        }
+        None => return None,
+    };
+
+    // Absolute colorimetric conversion maps into the media white instead of
+    // the connection space's white before applying the relative mapping.
+    let mut scale = [1.0; 3];
+    if intent == RenderingIntent::AbsoluteColorimetric {
+        if let Some(white) = find_tag(icc, b"wtpt").and_then(read_xyz) {
+            scale =
+                [0, 1, 2].map(|i| if white[i] > 0.0 { D50[i] / white[i] } else { 1.0 });
+        }
+    }
+
+    Some((mapping, scale))
+}
+
+/// A lookup table from the connection space into a profile's space.
+///
+/// Colors pass through the stages in order and stages that a table doesn't
+/// have are skipped.
+#[derive(Debug, Clone)]
+struct Lut {
+    /// A matrix applied to XYZ colors before anything else.
+    pre: Option<[[f32; 3]; 3]>,
+    /// One curve per input channel.
+    b: Vec<Curve>,
+    /// A matrix with offsets.
+    matrix: Option<([[f32; 3]; 3], [f32; 3])>,
+    /// One curve per input channel after the matrix.
+    m: Vec<Curve>,
+    /// A multi-dimensional table, without which outputs equal inputs.
+    clut: Option<Clut>,
+    /// One curve per output channel.
+    a: Vec<Curve>,
+    /// The number of output channels.
+    outputs: usize,
+    /// Whether Lab colors use the legacy 16-bit encoding.
+    legacy: bool,
+}
+
+impl Lut {
+    /// Parse a `lut8Type`, `lut16Type` or `lutBToAType` tag.
+    fn parse(data: &[u8], lab: bool) -> Option<Self> {
+        let inputs = *data.get(8)? as usize;
+        let outputs = *data.get(9)? as usize;
+        if inputs != 3 || !(1..=4).contains(&outputs) {
+            return None;
+        }
+
+        match data.get(0..4)? {
+            b"mft1" | b"mft2" => {
+                let wide = data.get(0..4)? == b"mft2";
+                let grid = *data.get(10)? as usize;
+                let mut matrix = [[0.0; 3]; 3];
+                for (i, v) in matrix.iter_mut().flatten().enumerate() {
+                    *v = read_fixed(data, 12 + 4 * i)?;
+                }
+
+                let (entries, precision, start) = if wide {
+                    (read_u16(data, 48)? as usize, read_u16(data, 50)? as usize, 52)
+                } else {
+                    (256, 256, 48)
+                };
+                if entries < 2 || precision < 2 {
+                    return None;
+                }
+
+                let (size, max) = if wide { (2, 65535.0) } else { (1, 255.0) };
+                let mut at = start;
+                let mut read = |count: usize| -> Option<Vec<f32>> {
+                    let values = (0..count)
+                        .map(|i| read_sample(data, at + size * i, size).map(|v| v / max))
+                        .collect();
+                    at += size * count;
+                    values
+                };
+
+                let b = (0..inputs)
+                    .map(|_| read(entries).map(Curve::Table))
+                    .collect::<Option<_>>()?;
+                let len = grid.checked_pow(3)?.checked_mul(outputs)?;
+                let clut = Clut::new([grid; 3], outputs, read(len)?)?;
+                let a = (0..outputs)
+                    .map(|_| read(precision).map(Curve::Table))
+                    .collect::<Option<_>>()?;
+
+                Some(Self {
+                    pre: (!lab).then_some(matrix),
+                    b,
+                    matrix: None,
+                    m: vec![],
+                    clut: Some(clut),
+                    a,
+                    outputs,
+                    legacy: wide,
+                })
+            }
+            b"mBA " => {
+                let offset = |at| read_u32(data, at).map(|v| v as usize);
+                let curves = |at: usize, count: usize| -> Option<Vec<Curve>> {
+                    let mut at = at;
+                    (0..count)
+                        .map(|_| {
+                            let (curve, len) = Curve::parse(data.get(at..)?)?;
+                            at += (len + 3) & !3;
+                            Some(curve)
+                        })
+                        .collect()
+                };
+
+                let b = curves(offset(12)?, inputs)?;
+
+                let matrix = match offset(16)? {
+                    0 => None,
+                    at => {
+                        let mut matrix = [[0.0; 3]; 3];
+                        for (i, v) in matrix.iter_mut().flatten().enumerate() {
+                            *v = read_fixed(data, at + 4 * i)?;
+                        }
+                        let mut offsets = [0.0; 3];
+                        for (i, v) in offsets.iter_mut().enumerate() {
+                            *v = read_fixed(data, at + 36 + 4 * i)?;
+                        }
+                        Some((matrix, offsets))
+                    }
+                };
+
+                let m = match offset(20)? {
+                    0 => vec![],
+                    at => curves(at, inputs)?,
+                };
+
+                let clut = match offset(24)? {
+                    0 if outputs == inputs => None,
+                    0 => return None,
+                    at => {
+                        let mut grid = [0; 3];
+                        for (i, g) in grid.iter_mut().enumerate() {
+                            *g = *data.get(at + i)? as usize;
+                        }
+                        let size = *data.get(at + 16)? as usize;
+                        let max = match size {
+                            1 => 255.0,
+                            2 => 65535.0,
+                            _ => return None,
+                        };
+                        let len = grid
+                            .iter()
+                            .try_fold(outputs, |len, &g| len.checked_mul(g))?;
+                        let values = (0..len)
+                            .map(|i| read_sample(data, at + 20 + size * i, size))
+                            .map(|v| v.map(|v| v / max))
+                            .collect::<Option<_>>()?;
+                        Some(Clut::new(grid, outputs, values)?)
+                    }
+                };
+
+                let a = match offset(28)? {
+                    0 => vec![],
+                    at => curves(at, outputs)?,
+                };
+
+                Some(Self {
+                    pre: None,
+                    b,
+                    matrix,
+                    m,
+                    clut,
+                    a,
+                    outputs,
+                    legacy: false,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Map normalized input values through the table.
+    fn eval(&self, input: [f32; 3]) -> [f32; 4] {
+        let mut v = input;
+        if let Some(pre) = &self.pre {
+            v = mul(pre, v);
+        }
+
+        for (v, curve) in v.iter_mut().zip(&self.b) {
+            *v = curve.eval(*v);
+        }
+
+        if let Some((matrix, offsets)) = &self.matrix {
+            v = mul(matrix, v);
+            for (v, offset) in v.iter_mut().zip(offsets) {
+                *v += offset;
+            }
+        }
+
+        for (v, curve) in v.iter_mut().zip(&self.m) {
+            *v = curve.eval(*v);
+        }
+
+        let mut out = match &self.clut {
+            Some(clut) => clut.eval(v),
+            None => [v[0], v[1], v[2], 0.0],
+        };
+
+        for (v, curve) in out.iter_mut().zip(&self.a) {
+            *v = curve.eval(*v);
+        }
+
+        out
+    }
+}
+
+/// A three-dimensional color lookup table.
+#[derive(Debug, Clone)]
+struct Clut {
+    /// The number of grid points along each input axis.
+    grid: [usize; 3],
+    /// The number of output values per grid point.
+    outputs: usize,
+    /// The normalized output values, with the first input varying slowest.
+    values: Vec<f32>,
+}
+
+impl Clut {
+    /// Create a table, checking that it has as many values as grid points.
+    fn new(grid: [usize; 3], outputs: usize, values: Vec<f32>) -> Option<Self> {
+        let len = grid.iter().product::<usize>() * outputs;
+        if grid.iter().any(|&g| g < 2) || values.len() != len {
+            return None;
+        }
+        Some(Self { grid, outputs, values })
+    }
+
+    /// Look up normalized input values with trilinear interpolation.
+    fn eval(&self, input: [f32; 3]) -> [f32; 4] {
+        let pos = [0, 1, 2].map(|i| input[i].clamp(0.0, 1.0) * (self.grid[i] - 1) as f32);
+        let base = [0, 1, 2].map(|i| (pos[i] as usize).min(self.grid[i] - 2));
+        let frac = [0, 1, 2].map(|i| pos[i] - base[i] as f32);
+
+        let mut out = [0.0; 4];
+        for corner in 0..8 {
+            let mut weight = 1.0;
+            let mut index = 0;
+            for i in 0..3 {
+                let step = (corner >> (2 - i)) & 1;
+                weight *= if step == 1 { frac[i] } else { 1.0 - frac[i] };
+                index = index * self.grid[i] + base[i] + step;
+            }
+
+            let values = &self.values[index * self.outputs..][..self.outputs];
+            for (out, value) in out.iter_mut().zip(values) {
+                *out += weight * value;
+            }
+        }
+
+        out
+    }
+}
+
+/// A tone curve over normalized values.
+#[derive(Debug, Clone)]
+enum Curve {
+    /// Raises values to a power.
+    Gamma(f32),
+    /// Samples spaced evenly over the inputs, interpolated linearly.
+    Table(Vec<f32>),
+    /// The parameters `[g, a, b, c, d, e, f]` of the function
+    /// `(a * x + b)^g + e` for `x >= d` and `c * x + f` otherwise.
+    Parametric([f32; 7]),
+}
+
+impl Curve {
+    /// Parse a `curveType` or `parametricCurveType` and return its length in
+    /// bytes.
+    fn parse(data: &[u8]) -> Option<(Self, usize)> {
+        match data.get(0..4)? {
+            b"curv" => {
+                let count = read_u32(data, 8)? as usize;
+                let curve = match count {
+                    0 => Self::Gamma(1.0),
+                    1 => Self::Gamma(read_u16(data, 12)? as f32 / 256.0),
+                    _ => Self::Table(
+                        (0..count)
+                            .map(|i| {
+                                read_sample(data, 12 + 2 * i, 2).map(|v| v / 65535.0)
+                            })
+                            .collect::<Option<_>>()?,
+                    ),
+                };
+                Some((curve, 12 + 2 * count))
+            }
+            b"para" => {
+                let kind = read_u16(data, 8)?;
+                let count = [1, 3, 4, 5, 7].get(kind as usize).copied()?;
+                let mut p = [0.0; 7];
+                for (i, v) in p.iter_mut().take(count).enumerate() {
+                    *v = read_fixed(data, 12 + 4 * i)?;
+                }
+
+                // Bring all function types into the most general form.
+                let [g, a, b, c, d, e, f] = p;
+                let params = match kind {
+                    0 => [g, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                    1 => [g, a, b, 0.0, -b / a, 0.0, 0.0],
+                    2 => [g, a, b, 0.0, -b / a, c, c],
+                    3 => [g, a, b, c, d, 0.0, 0.0],
+                    _ => [g, a, b, c, d, e, f],
+                };
+                Some((Self::Parametric(params), 12 + 4 * count))
+            }
+            _ => None,
+        }
+    }
+
+    /// Map a value through the curve.
+    fn eval(&self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Self::Gamma(g) => x.powf(*g),
+            Self::Table(table) => {
+                let pos = x * table.len().saturating_sub(1) as f32;
+                let i = (pos as usize).min(table.len().saturating_sub(2));
+                let t = pos - i as f32;
+                match (table.get(i), table.get(i + 1)) {
+                    (Some(lo), Some(hi)) => lo + t * (hi - lo),
+                    (Some(lo), None) => *lo,
+                    _ => x,
+                }
+            }
+            Self::Parametric([g, a, b, c, d, e, f]) => {
+                if x >= *d {
+                    (a * x + b).max(0.0).powf(*g) + e
+                } else {
+                    c * x + f
+                }
+            }
+        }
+    }
+
+    /// The inverse of an increasing curve.
+    fn inverse(&self) -> Self {
+        if let Self::Gamma(g) = self {
+            if *g > 0.0 {
+                return Self::Gamma(1.0 / g);
+            }
+        }
+
+        // Find the input for each sampled output by bisection.
+        let table = (0..INVERSE_SAMPLES)
+            .map(|i| {
+                let y = i as f32 / (INVERSE_SAMPLES - 1) as f32;
+                let (mut lo, mut hi) = (0.0, 1.0);
+                for _ in 0..24 {
+                    let mid = (lo + hi) / 2.0;
+                    if self.eval(mid) < y {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                (lo + hi) / 2.0
+            })
+            .collect();
+
+        Self::Table(table)
+    }
+}
+
+/// Find the data of a tag in a profile's tag table.
+fn find_tag<'a>(icc: &'a [u8], sig: &[u8; 4]) -> Option<&'a [u8]> {
+    let count = read_u32(icc, 128)? as usize;
+    (0..count).find_map(|i| {
+        let entry = 132 + 12 * i;
+        if icc.get(entry..entry + 4)? != sig {
+            return None;
+        }
+        let offset = read_u32(icc, entry + 4)? as usize;
+        let len = read_u32(icc, entry + 8)? as usize;
+        icc.get(offset..offset.checked_add(len)?)
+    })
+}
+
+/// Read the single value of an `XYZType` tag.
+fn read_xyz(data: &[u8]) -> Option<[f32; 3]> {
+    if data.get(0..4)? != b"XYZ " {
+        return None;
+    }
+    Some([read_fixed(data, 8)?, read_fixed(data, 12)?, read_fixed(data, 16)?])
+}
+
+fn read_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Read an `s15Fixed16Number`.
+fn read_fixed(data: &[u8], at: usize) -> Option<f32> {
+    Some(read_u32(data, at)? as i32 as f32 / 65536.0)
+}
+
+/// Read an unsigned sample that is one or two bytes wide.
+fn read_sample(data: &[u8], at: usize, size: usize) -> Option<f32> {
+    match size {
+        1 => data.get(at).map(|&v| v as f32),
+        _ => read_u16(data, at).map(|v| v as f32),
+    }
+}
+
+/// Convert a color from XYZ relative to D50 to Lab.
+fn to_lab(xyz: [f32; 3]) -> [f32; 3] {
+    let f = |t: f32| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let [x, y, z] = [0, 1, 2].map(|i| f(xyz[i] / D50[i]));
+    [116.0 * y - 16.0, 500.0 * (x - y), 200.0 * (y - z)]
+}
+
+/// Multiply a vector with a matrix.
+fn mul(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+/// Invert a matrix, if it is invertible.
+fn invert(m: [[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+    let [[a, b, c], [d, e, f], [g, h, i]] = m;
+    let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+    if det.abs() < 1e-9 {
+        return None;
+    }
+
+    Some([
+        [(e * i - f * h) / det, (c * h - b * i) / det, (b * f - c * e) / det],
+        [(f * g - d * i) / det, (a * i - c * g) / det, (c * d - a * f) / det],
+        [(d * h - e * g) / det, (b * g - a * h) / det, (a * e - b * d) / det],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::doc::{Document, Frame, FrameItem};
+    use crate::export::{pdf_with_options, PdfOptions};
+    use crate::geom::{Abs, Geometry, Point, Size};
+    use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+    use crate::syntax::Span;
+
+    fn fixed(v: f32) -> [u8; 4] {
+        ((v * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    /// Assemble an ICC profile from its color spaces and tags.
+    fn profile(space: &[u8; 4], pcs: &[u8; 4], tags: &[(&[u8; 4], Vec<u8>)]) -> Buffer {
+        let mut data = vec![0; 128];
+        data[16..20].copy_from_slice(space);
+        data[20..24].copy_from_slice(pcs);
+        data[36..40].copy_from_slice(b"acsp");
+        data.extend((tags.len() as u32).to_be_bytes());
+
+        let mut offset = 132 + 12 * tags.len();
+        let mut body = vec![];
+        for (sig, tag) in tags {
+            data.extend(*sig);
+            data.extend((offset as u32).to_be_bytes());
+            data.extend((tag.len() as u32).to_be_bytes());
+            body.extend(tag);
+            while body.len() % 4 != 0 {
+                body.push(0);
+            }
+            offset = 132 + 12 * tags.len() + body.len();
+        }
+
+        data.extend(body);
+        let len = data.len() as u32;
+        data[0..4].copy_from_slice(&len.to_be_bytes());
+        data.into()
+    }
+
+    fn xyz(v: [f32; 3]) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        tag.extend(v.into_iter().flat_map(fixed));
+        tag
+    }
+
+    /// The tone curve of sRGB as a parametric curve.
+    fn srgb_curve() -> Vec<u8> {
+        let mut tag = b"para\0\0\0\0\0\x03\0\0".to_vec();
+        let params = [2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045];
+        tag.extend(params.into_iter().flat_map(fixed));
+        tag
+    }
+
+    /// An RGB profile with sRGB's primaries and tone curve.
+    fn rgb_profile(white: [f32; 3]) -> Buffer {
+        let column = |i: usize| xyz([0, 1, 2].map(|row| SRGB_TO_XYZ[row][i]));
+        profile(
+            b"RGB ",
+            b"XYZ ",
+            &[
+                (b"rXYZ", column(0)),
+                (b"gXYZ", column(1)),
+                (b"bXYZ", column(2)),
+                (b"rTRC", srgb_curve()),
+                (b"gTRC", srgb_curve()),
+                (b"bTRC", srgb_curve()),
+                (b"wtpt", xyz(white)),
+            ],
+        )
+    }
+
+    /// A `lut16Type` from Lab to CMYK with a grid of two points per axis.
+    fn lut(f: impl Fn([f32; 3]) -> [f32; 4]) -> Vec<u8> {
+        let mut tag = b"mft2\0\0\0\0\x03\x04\x02\0".to_vec();
+        for i in 0..9 {
+            tag.extend(fixed(if i % 4 == 0 { 1.0 } else { 0.0 }));
+        }
+        tag.extend(2u16.to_be_bytes());
+        tag.extend(2u16.to_be_bytes());
+        let identity = || [0u16, 65535].into_iter().flat_map(u16::to_be_bytes);
+        for _ in 0..3 {
+            tag.extend(identity());
+        }
+        for corner in 0..8 {
+            let input = [4, 2, 1].map(|bit| (corner & bit != 0) as u8 as f32);
+            for v in f(input) {
+                tag.extend(((v * 65535.0).round() as u16).to_be_bytes());
+            }
+        }
+        for _ in 0..4 {
+            tag.extend(identity());
+        }
+        tag
+    }
+
+    /// A CMYK profile whose perceptual table only uses magenta and whose
+    /// colorimetric table maps lightness to cyan.
+    fn cmyk_profile() -> Buffer {
+        profile(
+            b"CMYK",
+            b"Lab ",
+            &[
+                (b"B2A0", lut(|_| [0.0, 1.0, 0.0, 0.0])),
+                (b"B2A1", lut(|[l, _, _]| [1.0 - l, 0.0, 0.0, 0.0])),
+            ],
+        )
+    }
+
+    fn transform(icc: Buffer, intent: RenderingIntent) -> ColorTransform {
+        let mut profile = ColorProfile::from_icc(icc).unwrap();
+        profile.intent = intent;
+        ColorTransform::new(&profile).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_icc_matrix_profile() {
+        // Converting into a profile that describes sRGB keeps the colors.
+        let srgb = transform(rgb_profile(D50), RenderingIntent::default());
+        assert_eq!(srgb.channels(), 3);
+        for rgb in [[0, 0, 0], [255, 255, 255], [255, 0, 0], [12, 128, 240]] {
+            let [r, g, b, _] = srgb.convert(rgb);
+            for (a, b) in [r, g, b].into_iter().zip(rgb) {
+                assert!(a.abs_diff(b) <= 1, "{rgb:?} became {:?}", [r, g, b]);
+            }
+        }
+
+        // Absolute colorimetric conversion into a profile whose media is
+        // darker than the connection space's white brightens colors, so that
+        // they look the same on it.
+        let dark = D50.map(|v| v * 0.8);
+        let relative =
+            transform(rgb_profile(dark), RenderingIntent::RelativeColorimetric);
+        let absolute =
+            transform(rgb_profile(dark), RenderingIntent::AbsoluteColorimetric);
+        assert!(relative.convert([100; 3])[0].abs_diff(100) <= 1);
+        assert!(absolute.convert([100; 3])[0] > 105);
+    }
+
+    #[test]
+    fn test_icc_lut_profile() {
+        let relative = transform(cmyk_profile(), RenderingIntent::RelativeColorimetric);
+        assert_eq!(relative.channels(), 4);
+        assert_eq!(relative.convert([0, 0, 0]), [255, 0, 0, 0]);
+        assert!(relative.convert([255, 255, 255])[0] <= 1);
+        let gray = relative.convert([119, 119, 119])[0];
+        assert!((120..136).contains(&gray), "{gray}");
+
+        // Each intent uses its own table and falls back to the perceptual one.
+        let perceptual = transform(cmyk_profile(), RenderingIntent::Perceptual);
+        let saturation = transform(cmyk_profile(), RenderingIntent::Saturation);
+        assert_eq!(perceptual.convert([0, 0, 0]), [0, 255, 0, 0]);
+        assert_eq!(saturation.convert([0, 0, 0]), [0, 255, 0, 0]);
+        assert_eq!(
+            perceptual.apply(RgbaColor::new(10, 20, 30, 40)),
+            Color::Cmyk(CmykColor::new(0, 255, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_icc_invalid_profile() {
+        assert!(ColorProfile::from_icc(profile(b"RGB ", b"XYZ ", &[])).is_err());
+        assert!(ColorProfile::from_icc(profile(b"CMYK", b"Lab ", &[])).is_err());
+        assert!(ColorProfile::from_icc(profile(b"GRAY", b"XYZ ", &[])).is_err());
+        assert!(ColorProfile::from_icc(b"not a profile".as_slice().into()).is_err());
+
+        // A truncated table is rejected instead of read out of bounds.
+        let mut table = lut(|_| [0.0; 4]);
+        table.truncate(100);
+        let truncated = profile(b"CMYK", b"Lab ", &[(b"B2A0", table)]);
+        assert!(ColorProfile::from_icc(truncated).is_err());
+    }
+
+    #[test]
+    fn test_icc_pdf_export() {
+        let mut png = Cursor::new(vec![]);
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(1, 1))
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+        let png = Image::new(
+            png.into_inner().into(),
+            ImageFormat::Raster(RasterFormat::Png),
+            None,
+        )
+        .unwrap();
+
+        let svg = b"<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 10 10'>\
+            <rect width='10' height='10' fill='black'/></svg>";
+        let svg = Image::new(
+            svg.as_slice().into(),
+            ImageFormat::Vector(VectorFormat::Svg),
+            None,
+        )
+        .unwrap();
+
+        // At 300 dpi, the vector image becomes ten by ten pixels.
+        let size = Size::splat(Abs::pt(2.4));
+        let mut frame = Frame::new(Size::splat(Abs::pt(10.0)));
+        let shape = Geometry::Rect(size).filled(Color::BLACK.into());
+        frame.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+        frame.push(Point::zero(), FrameItem::Image(png, size, Span::detached()));
+        frame.push(Point::zero(), FrameItem::Image(svg, size, Span::detached()));
+
+        let document = Document { pages: vec![frame], title: None, author: vec![] };
+        let options = PdfOptions {
+            color_profile: Some(ColorProfile::from_icc(cmyk_profile()).unwrap()),
+            ..PdfOptions::default()
+        };
+        let pdf = pdf_with_options(&document, &options).unwrap();
+
+        // Inflate all streams that can be inflated.
+        let mut streams = vec![];
+        let mut rest = pdf.as_slice();
+        while let Some(start) = rest.windows(7).position(|w| w == b"stream\n") {
+            rest = &rest[start + 7..];
+            let end = rest.windows(10).position(|w| w == b"\nendstream").unwrap();
+            if let Ok(data) = miniz_oxide::inflate::decompress_to_vec_zlib(&rest[..end]) {
+                streams.push(data);
+            }
+            rest = &rest[end..];
+        }
+
+        // Black becomes full cyan in the shape, the raster image and the
+        // rasterized vector image.
+        let black = [255, 0, 0, 0];
+        let content = streams.iter().find(|s| s.windows(2).any(|w| w == b"re")).unwrap();
+        assert!(String::from_utf8_lossy(content).contains("1 0 0 0 sc"));
+        assert!(streams.iter().any(|s| s == &black));
+        assert!(streams
+            .iter()
+            .any(|s| { s.len() == 400 && s.chunks(4).all(|pixel| pixel == black) }));
+    }
+}
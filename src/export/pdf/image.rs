@@ -1,29 +1,64 @@
+use std::borrow::Cow;
 use std::io::Cursor;
 
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
 use image::{DynamicImage, GenericImageView, Rgba};
 use pdf_writer::{Filter, Finish};
 
-use super::{deflate, PdfContext, RefExt};
-use crate::image::{DecodedImage, Image, RasterFormat};
+use super::{deflate, ColorTransform, PdfContext, ProfileSpace, RefExt};
+use crate::diag::StrResult;
+use crate::doc::{Frame, FrameItem};
+use crate::geom::{Abs, Axes, Color, Point, RgbaColor, Size};
+use crate::image::{DecodedImage, Image, ImageFormat, RasterFormat};
+use crate::syntax::Span;
 use crate::util::Buffer;
 
+/// The resolution in dots per inch at which vector images are rasterized if
+/// there is no maximum resolution.
+const VECTOR_DPI: f64 = 300.0;
+
 /// Embed all used images into the PDF.
-#[tracing::instrument(skip_all)]
-pub fn write_images(ctx: &mut PdfContext) {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn write_images(ctx: &mut PdfContext) -> StrResult<()> {
     for image in ctx.image_map.items() {
         let image_ref = ctx.alloc.bump();
         let icc_ref = ctx.alloc.bump();
         ctx.image_refs.push(image_ref);
 
-        let width = image.width();
-        let height = image.height();
+        // The colors of vector images can't be converted in place, so they
+        // are rasterized and converted like raster images.
+        let rasterized = match image.format() {
+            ImageFormat::Vector(_) if ctx.transform.is_some() => rasterize(ctx, image),
+            _ => None,
+        };
+        let image = rasterized.as_ref().unwrap_or(image);
+
+        // Only the headers of raster images are checked when they are
+        // loaded, so their pixel data may still turn out to be corrupt.
+        let decoded = image.decoded()?;
+
+        let mut width = image.width();
+        let mut height = image.height();
 
         // Add the primary image.
-        // TODO: Error if image could not be encoded.
-        match image.decoded().as_ref() {
+        match decoded.as_ref() {
             DecodedImage::Raster(dynamic, icc, _) => {
-                // TODO: Error if image could not be encoded.
-                let (data, filter, has_color) = encode_image(image);
+                let target = downscaled_size(ctx, image);
+                if let Some(size) = target {
+                    width = size.x;
+                    height = size.y;
+                }
+
+                // When converting colors, the image's own profile is dropped.
+                let profile = ctx.options.color_profile.as_ref();
+                let transform = ctx.transform.as_ref();
+                let cmyk = profile.map_or(false, |p| p.space == ProfileSpace::Cmyk);
+                let srgb = profile.map_or(false, |p| p.space == ProfileSpace::Srgb);
+                let icc = icc.as_ref().filter(|_| transform.is_none());
+
+                let (data, filter, has_color) =
+                    encode_image(image, target, ctx.options.jpeg_quality, transform)?;
                 let mut image = ctx.writer.image_xobject(image_ref, &data);
                 image.filter(filter);
                 image.width(width as i32);
@@ -33,6 +68,14 @@ pub fn write_images(ctx: &mut PdfContext) {
                 let space = image.color_space();
                 if icc.is_some() {
                     space.icc_based(icc_ref);
+                } else if let (true, Some(profile_ref)) =
+                    (transform.is_some(), ctx.profile_ref)
+                {
+                    space.icc_based(profile_ref);
+                } else if cmyk {
+                    space.device_cmyk();
+                } else if has_color && srgb {
+                    space.srgb();
                 } else if has_color {
                     space.device_rgb();
                 } else {
@@ -42,7 +85,8 @@ pub fn write_images(ctx: &mut PdfContext) {
                 // Add a second gray-scale image containing the alpha values if
                 // this image has an alpha channel.
                 if dynamic.color().has_alpha() {
-                    let (alpha_data, alpha_filter) = encode_alpha(dynamic);
+                    let (alpha_data, alpha_filter) =
+                        encode_alpha(&resized(dynamic, target));
                     let mask_ref = ctx.alloc.bump();
                     image.s_mask(mask_ref);
                     image.finish();
@@ -81,34 +125,51 @@ pub fn write_images(ctx: &mut PdfContext) {
             }
         }
     }
+
+    Ok(())
 }
 
 /// Encode an image with a suitable filter and return the data, filter and
 /// whether the image has color.
 ///
-/// Skips the alpha channel as that's encoded separately.
+/// Skips the alpha channel as that's encoded separately. With a transform,
+/// all pixels are converted into the target color profile's space.
 #[comemo::memoize]
-#[tracing::instrument(skip_all)]
-fn encode_image(image: &Image) -> (Buffer, Filter, bool) {
-    let decoded = image.decoded();
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn encode_image(
+    image: &Image,
+    size: Option<Axes<u32>>,
+    quality: u8,
+    transform: Option<&ColorTransform>,
+) -> StrResult<(Buffer, Filter, bool)> {
+    let decoded = image.decoded()?;
     let (dynamic, format) = match decoded.as_ref() {
         DecodedImage::Raster(dynamic, _, format) => (dynamic, *format),
         _ => panic!("can only encode raster image"),
     };
 
-    match (format, dynamic) {
+    let dynamic = resized(dynamic, size);
+    if let Some(transform) = transform {
+        let (width, height) = dynamic.dimensions();
+        let channels = transform.channels();
+        let mut pixels = Vec::with_capacity(channels * width as usize * height as usize);
+        for (_, _, Rgba([r, g, b, _])) in dynamic.pixels() {
+            pixels.extend_from_slice(&transform.convert([r, g, b])[..channels]);
+        }
+
+        let data = deflate(&pixels);
+        return Ok((data.into(), Filter::FlateDecode, true));
+    }
+
+    Ok(match (format, dynamic.as_ref()) {
         // 8-bit gray JPEG.
         (RasterFormat::Jpg, DynamicImage::ImageLuma8(_)) => {
-            let mut data = Cursor::new(vec![]);
-            dynamic.write_to(&mut data, image::ImageFormat::Jpeg).unwrap();
-            (data.into_inner().into(), Filter::DctDecode, false)
+            (encode_jpeg(&dynamic, quality), Filter::DctDecode, false)
         }
 
         // 8-bit RGB JPEG (CMYK JPEGs get converted to RGB earlier).
         (RasterFormat::Jpg, DynamicImage::ImageRgb8(_)) => {
-            let mut data = Cursor::new(vec![]);
-            dynamic.write_to(&mut data, image::ImageFormat::Jpeg).unwrap();
-            (data.into_inner().into(), Filter::DctDecode, true)
+            (encode_jpeg(&dynamic, quality), Filter::DctDecode, true)
         }
 
         // TODO: Encode flate streams with PNG-predictor?
@@ -132,12 +193,172 @@ fn encode_image(image: &Image) -> (Buffer, Filter, bool) {
             let data = deflate(&pixels);
             (data.into(), Filter::FlateDecode, true)
         }
+    })
+}
+
+/// Re-encode a raster image as a JPEG with the given quality.
+fn encode_jpeg(dynamic: &DynamicImage, quality: u8) -> Buffer {
+    let mut data = Cursor::new(vec![]);
+    let mut encoder = JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100));
+    let (width, height) = dynamic.dimensions();
+    encoder.encode(dynamic.as_bytes(), width, height, dynamic.color()).unwrap();
+    data.into_inner().into()
+}
+
+/// Rasterize a vector image at its largest placed size.
+fn rasterize(ctx: &PdfContext, image: &Image) -> Option<Image> {
+    let &(w, h) = ctx.image_sizes.get(image)?;
+    let size = Size::new(Abs::pt(w as f64), Abs::pt(h as f64));
+    let mut frame = Frame::new(size);
+    frame.push(Point::zero(), FrameItem::Image(image.clone(), size, Span::detached()));
+
+    let dpi = ctx.options.max_dpi.unwrap_or(VECTOR_DPI);
+    let transparent = Color::Rgba(RgbaColor::new(0, 0, 0, 0));
+    let pixmap = crate::export::render(&frame, (dpi / 72.0) as f32, transparent);
+    let data = pixmap.encode_png().ok()?;
+    Image::new(data.into(), ImageFormat::Raster(RasterFormat::Png), None).ok()
+}
+
+/// Determine the size to which an image should be downscaled to not exceed
+/// the maximum resolution at its largest placed size.
+fn downscaled_size(ctx: &PdfContext, image: &Image) -> Option<Axes<u32>> {
+    let max_dpi = ctx.options.max_dpi?;
+    let &(w, h) = ctx.image_sizes.get(image)?;
+
+    // Scale uniformly so that neither axis exceeds the maximum resolution.
+    let scale = (w as f64 / 72.0 * max_dpi / image.width() as f64)
+        .max(h as f64 / 72.0 * max_dpi / image.height() as f64);
+    if scale.is_nan() || scale >= 1.0 {
+        return None;
+    }
+
+    let scaled = |px: u32| ((px as f64 * scale).ceil() as u32).max(1);
+    Some(Axes::new(scaled(image.width()), scaled(image.height())))
+}
+
+/// Resample an image to a new size, if any.
+fn resized(dynamic: &DynamicImage, size: Option<Axes<u32>>) -> Cow<DynamicImage> {
+    match size {
+        Some(size) => {
+            Cow::Owned(dynamic.resize_exact(size.x, size.y, FilterType::CatmullRom))
+        }
+        None => Cow::Borrowed(dynamic),
     }
 }
 
 /// Encode an image's alpha channel if present.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn encode_alpha(dynamic: &DynamicImage) -> (Vec<u8>, Filter) {
     let pixels: Vec<_> = dynamic.pixels().map(|(_, _, Rgba([_, _, _, a]))| a).collect();
     (deflate(&pixels), Filter::FlateDecode)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::Document;
+    use crate::export::{pdf_with_options, PdfOptions};
+
+    /// Encode a noisy image in a raster format.
+    fn image(width: u32, height: u32, format: RasterFormat) -> Image {
+        let pixels = image::RgbaImage::from_fn(width, height, |x, y| {
+            let v = ((x * 7 + y * 13) % 256) as u8;
+            Rgba([v, v.wrapping_mul(3), 255 - v, 128])
+        });
+        let dynamic = match format {
+            RasterFormat::Jpg => {
+                DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(pixels).to_rgb8())
+            }
+            _ => DynamicImage::ImageRgba8(pixels),
+        };
+        let mut data = Cursor::new(vec![]);
+        let output = match format {
+            RasterFormat::Jpg => image::ImageOutputFormat::Jpeg(100),
+            _ => image::ImageOutputFormat::Png,
+        };
+        dynamic.write_to(&mut data, output).unwrap();
+        Image::new(data.into_inner().into(), ImageFormat::Raster(format), None).unwrap()
+    }
+
+    /// A page with an image placed at the given sizes.
+    fn document(image: &Image, sizes: &[Size]) -> Document {
+        let mut frame = Frame::new(Size::splat(Abs::pt(100.0)));
+        for &size in sizes {
+            let item = FrameItem::Image(image.clone(), size, Span::detached());
+            frame.push(Point::zero(), item);
+        }
+        Document { pages: vec![frame], title: None, author: vec![] }
+    }
+
+    /// Export a page with an image placed at the given sizes.
+    fn export(image: &Image, sizes: &[Size], options: &PdfOptions) -> Vec<u8> {
+        pdf_with_options(&document(image, sizes), options).unwrap()
+    }
+
+    fn count(pdf: &[u8], needle: &[u8]) -> usize {
+        pdf.windows(needle.len()).filter(|w| *w == needle).count()
+    }
+
+    #[test]
+    fn test_downscale_images() {
+        let png = image(400, 200, RasterFormat::Png);
+        let sizes = [
+            Size::new(Abs::pt(72.0), Abs::pt(36.0)),
+            Size::new(Abs::pt(36.0), Abs::pt(18.0)),
+        ];
+
+        // Without a maximum resolution, the image keeps its pixels.
+        let pdf = export(&png, &sizes, &PdfOptions::default());
+        assert_eq!(count(&pdf, b"/Width 400"), 2);
+
+        // Otherwise, it is downscaled to the resolution at its largest placed
+        // size. This also applies to its alpha channel.
+        let options = PdfOptions { max_dpi: Some(100.0), ..PdfOptions::default() };
+        let pdf = export(&png, &sizes, &options);
+        assert_eq!(count(&pdf, b"/Width 400"), 0);
+        assert_eq!(count(&pdf, b"/Width 100"), 2);
+        assert_eq!(count(&pdf, b"/Height 50"), 2);
+
+        // Images that are small enough stay untouched.
+        let options = PdfOptions { max_dpi: Some(1000.0), ..PdfOptions::default() };
+        let pdf = export(&png, &sizes, &options);
+        assert_eq!(count(&pdf, b"/Width 400"), 2);
+    }
+
+    #[test]
+    fn test_jpeg_quality() {
+        let jpg = image(300, 300, RasterFormat::Jpg);
+        let sizes = [Size::splat(Abs::pt(72.0))];
+        let encode = |quality| {
+            let options = PdfOptions {
+                max_dpi: Some(150.0),
+                jpeg_quality: quality,
+                ..PdfOptions::default()
+            };
+            export(&jpg, &sizes, &options)
+        };
+
+        // Downscaled JPEGs are re-encoded with the given quality.
+        let low = encode(10);
+        let high = encode(95);
+        assert_eq!(count(&low, b"/Width 150"), 1);
+        assert_eq!(count(&low, b"/DCTDecode"), 1);
+        assert!(low.len() < high.len());
+    }
+
+    #[test]
+    fn test_corrupt_pixel_data() {
+        // Loading only reads the header, so the missing pixel data is only
+        // noticed during export, which fails instead of dropping the image.
+        let png = image(40, 40, RasterFormat::Png);
+        let data = png.data();
+        let truncated = data[..data.len() - 64].to_vec();
+        let format = ImageFormat::Raster(RasterFormat::Png);
+        let broken = Image::new(truncated.into(), format, None).unwrap();
+        assert_eq!(broken.size(), Axes::splat(40));
+
+        let document = document(&broken, &[Size::splat(Abs::pt(72.0))]);
+        let result = pdf_with_options(&document, &PdfOptions::default());
+        assert_eq!(result.unwrap_err(), "failed to decode image");
+    }
+}
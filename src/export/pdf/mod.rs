@@ -1,6 +1,7 @@
 //! Exporting into PDF documents.
 
 mod font;
+mod icc;
 mod image;
 mod outline;
 mod page;
@@ -11,37 +12,214 @@ use std::hash::Hash;
 
 use ecow::EcoString;
 use pdf_writer::types::Direction;
-use pdf_writer::{Finish, Name, PdfWriter, Ref, TextStr};
+use pdf_writer::{Filter, Finish, Name, Null, PdfWriter, Ref, Str, TextStr};
 use xmp_writer::{LangId, RenditionClass, XmpWriter};
 
-use self::page::Page;
-use crate::doc::{Document, Lang};
+use self::icc::ColorTransform;
+use self::page::{Page, StructElem};
+use crate::diag::{bail, StrResult};
+use crate::doc::{Document, Frame, Lang, Region};
 use crate::font::Font;
-use crate::geom::{Abs, Dir, Em};
+use crate::geom::{Abs, Color, Dir, Em};
 use crate::image::Image;
 use crate::model::Introspector;
+use crate::util::Buffer;
+use crate::World;
 
 /// Export a document into a PDF file.
 ///
-/// Returns the raw bytes making up the PDF file.
-#[tracing::instrument(skip_all)]
-pub fn pdf(document: &Document) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
-    page::construct_pages(&mut ctx, &document.pages);
-    font::write_fonts(&mut ctx);
-    image::write_images(&mut ctx);
-    page::write_page_tree(&mut ctx);
-    write_catalog(&mut ctx);
-    ctx.writer.finish()
+/// Returns the raw bytes making up the PDF file or an error if an image's
+/// pixel data can't be decoded.
+pub fn pdf(document: &Document) -> StrResult<Vec<u8>> {
+    pdf_with_options(document, &PdfOptions::default())
+}
+
+/// Export a document into a PDF file with custom options.
+///
+/// Returns the raw bytes making up the PDF file or an error if an image's
+/// pixel data can't be decoded.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn pdf_with_options(document: &Document, options: &PdfOptions) -> StrResult<Vec<u8>> {
+    let mut exporter = PdfExporter::new(options);
+    for frame in &document.pages {
+        exporter.push(frame);
+    }
+    exporter.finish(document)
+}
+
+/// Exports a document into a PDF file page by page.
+///
+/// Each page is encoded and compressed as soon as it is pushed, so its frame
+/// can be dropped right afterwards by embedders that produce pages one at a
+/// time. This does not bound the memory of exporting a typeset document: all
+/// of its pages are laid out before the first one can be pushed, and they stay
+/// shared with the memoization cache until it is evicted. Links are only
+/// resolved when the file is finished, so a page may link to pages that come
+/// after it.
+pub struct PdfExporter<'a>(PdfContext<'a>);
+
+impl<'a> PdfExporter<'a> {
+    /// Create a new exporter without any pages.
+    pub fn new(options: &'a PdfOptions) -> Self {
+        Self(PdfContext::new(options))
+    }
+
+    /// Encode the next page.
+    pub fn push(&mut self, frame: &Frame) {
+        self.0.introspector.push(frame);
+        page::construct_page(&mut self.0, frame);
+    }
+
+    /// Encode the next page unless the world's compilation was cancelled.
+    ///
+    /// Embedders that export long documents can use this instead of
+    /// [`push`](Self::push) to abort a stale export between pages.
+    pub fn try_push(&mut self, world: &dyn World, frame: &Frame) -> StrResult<()> {
+        if world.cancelled() {
+            bail!("export was cancelled");
+        }
+        self.push(frame);
+        Ok(())
+    }
+
+    /// Write the fonts, images, page tree and document metadata.
+    ///
+    /// Only the document's title and author are used as its pages must have
+    /// been pushed already. Returns the raw bytes making up the PDF file or an
+    /// error if an image's pixel data can't be decoded.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn finish(mut self, document: &Document) -> StrResult<Vec<u8>> {
+        let ctx = &mut self.0;
+        write_color_profile(ctx);
+        font::write_fonts(ctx);
+        image::write_images(ctx)?;
+        page::write_page_tree(ctx);
+        let struct_tree = write_struct_tree(ctx);
+        write_catalog(ctx, document, struct_tree);
+        Ok(self.0.writer.finish())
+    }
+}
+
+/// Options for PDF export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfOptions {
+    /// Resample raster images whose resolution at their largest placed size
+    /// exceeds this many dots per inch.
+    pub max_dpi: Option<f64>,
+    /// The quality from 1 to 100 with which JPEG images are re-encoded.
+    pub jpeg_quality: u8,
+    /// The color profile to convert all colors and raster images to. By
+    /// default, colors stay in the color space they were specified in.
+    pub color_profile: Option<ColorProfile>,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            max_dpi: None,
+            jpeg_quality: 75,
+            color_profile: None,
+        }
+    }
+}
+
+/// A color profile to convert the colors of a document to during export.
+///
+/// Colors and images are converted from sRGB through the profile's ICC data
+/// with the selected rendering intent. The profile is embedded as the PDF's
+/// output intent, so that printers know how the resulting values are meant to
+/// be reproduced. Without ICC data, CMYK colors are computed with a simple
+/// formula. Raster images with an embedded profile of their own are converted
+/// as if they were in sRGB. Vector images are rasterized, as their colors
+/// can't be converted in place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorProfile {
+    /// The color space of the profile.
+    space: ProfileSpace,
+    /// The ICC profile describing the output device, like a press profile
+    /// from a print shop.
+    icc: Option<Buffer>,
+    /// How colors outside of the profile's gamut are mapped into it.
+    pub intent: RenderingIntent,
+}
+
+impl ColorProfile {
+    /// Create a color profile for a color space without ICC data.
+    pub fn new(space: ProfileSpace) -> Self {
+        Self {
+            space,
+            icc: None,
+            intent: RenderingIntent::default(),
+        }
+    }
+
+    /// Create a color profile from ICC data, detecting its color space.
+    ///
+    /// Fails if the profile can't be used to convert colors into its space.
+    pub fn from_icc(icc: Buffer) -> StrResult<Self> {
+        let space = match icc.get(16..20) {
+            Some(b"RGB ") => ProfileSpace::Srgb,
+            Some(b"CMYK") => ProfileSpace::Cmyk,
+            _ => bail!("color profile must be an RGB or CMYK ICC profile"),
+        };
+        let profile = Self { icc: Some(icc), ..Self::new(space) };
+        ColorTransform::new(&profile)?;
+        Ok(profile)
+    }
+
+    /// The color space of the profile.
+    pub fn space(&self) -> ProfileSpace {
+        self.space
+    }
+}
+
+/// The color space of a [`ColorProfile`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ProfileSpace {
+    /// An RGB space. Without ICC data, this is sRGB.
+    Srgb,
+    /// A CMYK space for print.
+    Cmyk,
+}
+
+/// How colors outside of a profile's gamut are mapped into it.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RenderingIntent {
+    /// Compress the whole gamut, keeping the relations between colors.
+    Perceptual,
+    /// Keep the colors inside the gamut and clip the others.
+    #[default]
+    RelativeColorimetric,
+    /// Keep colors saturated, at the expense of their hue.
+    Saturation,
+    /// Like relative colorimetric, but also simulate the paper's white.
+    AbsoluteColorimetric,
+}
+
+impl RenderingIntent {
+    /// The PDF equivalent of the intent.
+    fn to_pdf(self) -> pdf_writer::types::RenderingIntent {
+        match self {
+            Self::Perceptual => pdf_writer::types::RenderingIntent::Perceptual,
+            Self::RelativeColorimetric => {
+                pdf_writer::types::RenderingIntent::RelativeColorimetric
+            }
+            Self::Saturation => pdf_writer::types::RenderingIntent::Saturation,
+            Self::AbsoluteColorimetric => {
+                pdf_writer::types::RenderingIntent::AbsoluteColorimetric
+            }
+        }
+    }
 }
 
 /// Identifies the color space definitions.
 const SRGB: Name<'static> = Name(b"srgb");
 const D65_GRAY: Name<'static> = Name(b"d65gray");
+const CMYK: Name<'static> = Name(b"cmyk");
 
 /// Context for exporting a whole PDF document.
 pub struct PdfContext<'a> {
-    document: &'a Document,
+    options: &'a PdfOptions,
     introspector: Introspector,
     writer: PdfWriter,
     pages: Vec<Page>,
@@ -53,6 +231,8 @@ pub struct PdfContext<'a> {
     page_refs: Vec<Ref>,
     font_map: Remapper<Font>,
     image_map: Remapper<Image>,
+    /// For each image the largest size in points at which it is placed.
+    image_sizes: HashMap<Image, (f32, f32)>,
     /// For each font a mapping from used glyphs to their text representation.
     /// May contain multiple chars in case of ligatures or similar things. The
     /// same glyph can have a different text representation within one document,
@@ -60,16 +240,29 @@ pub struct PdfContext<'a> {
     /// PDF's /ToUnicode map for glyphs that don't have an entry in the font's
     /// cmap. This is important for copy-paste and searching.
     glyph_sets: HashMap<Font, BTreeMap<u16, EcoString>>,
-    languages: HashMap<Lang, usize>,
+    /// The languages of the document's text, in the order they first occur.
+    languages: Vec<(Lang, Option<Region>)>,
+    /// The embedded ICC data of the target color profile.
+    profile_ref: Option<Ref>,
+    /// The conversion of colors into the target color profile's space, if
+    /// they need to be converted.
+    transform: Option<ColorTransform>,
+    /// The language of the first text in the document, which is declared as
+    /// the document's language. Text in other languages is tagged with its
+    /// own.
+    primary_lang: Option<(Lang, Option<Region>)>,
+    /// The elements of the structure tree, in the order their marked content
+    /// was written.
+    struct_elems: Vec<StructElem>,
 }
 
 impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+    fn new(options: &'a PdfOptions) -> Self {
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
         Self {
-            document,
-            introspector: Introspector::new(&document.pages),
+            options,
+            introspector: Introspector::new(&[]),
             writer: PdfWriter::new(),
             pages: vec![],
             page_heights: vec![],
@@ -80,22 +273,27 @@ impl<'a> PdfContext<'a> {
             image_refs: vec![],
             font_map: Remapper::new(),
             image_map: Remapper::new(),
+            image_sizes: HashMap::new(),
             glyph_sets: HashMap::new(),
-            languages: HashMap::new(),
+            languages: vec![],
+            profile_ref: None,
+            // The profile's data was checked when it was created.
+            transform: options
+                .color_profile
+                .as_ref()
+                .and_then(|profile| ColorTransform::new(profile).ok().flatten()),
+            primary_lang: None,
+            struct_elems: vec![],
         }
     }
 }
 
 /// Write the document catalog.
-#[tracing::instrument(skip_all)]
-fn write_catalog(ctx: &mut PdfContext) {
-    let lang = ctx
-        .languages
-        .iter()
-        .max_by_key(|(&lang, &count)| (count, lang))
-        .map(|(&k, _)| k);
-
-    let dir = if lang.map(Lang::dir) == Some(Dir::RTL) {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn write_catalog(ctx: &mut PdfContext, document: &Document, struct_tree: Option<Ref>) {
+    // The reading direction follows the document's declared language.
+    let lang = ctx.primary_lang;
+    let dir = if lang.map(|(lang, _)| lang.dir()) == Some(Dir::RTL) {
         Direction::R2L
     } else {
         Direction::L2R
@@ -107,12 +305,12 @@ fn write_catalog(ctx: &mut PdfContext) {
     // Write the document information.
     let mut info = ctx.writer.document_info(ctx.alloc.bump());
     let mut xmp = XmpWriter::new();
-    if let Some(title) = &ctx.document.title {
+    if let Some(title) = &document.title {
         info.title(TextStr(title));
         xmp.title([(None, title.as_str())]);
     }
 
-    let authors = &ctx.document.author;
+    let authors = &document.author;
     if !authors.is_empty() {
         info.author(TextStr(&authors.join(", ")));
         xmp.creator(authors.iter().map(|s| s.as_str()));
@@ -120,9 +318,14 @@ fn write_catalog(ctx: &mut PdfContext) {
     info.creator(TextStr("Typst"));
     info.finish();
     xmp.creator_tool("Typst");
-    xmp.num_pages(ctx.document.pages.len() as u32);
+    xmp.num_pages(ctx.page_refs.len() as u32);
     xmp.format("application/pdf");
-    xmp.language(ctx.languages.keys().map(|lang| LangId(lang.as_str())));
+    let tags: Vec<_> = ctx
+        .languages
+        .iter()
+        .map(|&(lang, region)| tag(lang, region))
+        .collect();
+    xmp.language(tags.iter().map(|tag| LangId(tag.as_str())));
     xmp.rendition_class(RenditionClass::Proof);
     xmp.pdf_version("1.7");
 
@@ -133,23 +336,211 @@ fn write_catalog(ctx: &mut PdfContext) {
     meta_stream.pair(Name(b"Subtype"), Name(b"XML"));
     meta_stream.finish();
 
+    let dests = named_destinations(ctx);
+
     // Write the document catalog.
     let mut catalog = ctx.writer.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
     catalog.viewer_preferences().direction(dir);
     catalog.pair(Name(b"Metadata"), meta_ref);
 
+    if !dests.is_empty() {
+        let mut names = catalog.insert(Name(b"Names")).dict();
+        let mut tree = names.insert(Name(b"Dests")).dict();
+        let mut array = tree.insert(Name(b"Names")).array();
+        for (name, (page, x, y)) in &dests {
+            array.item(Str(name.as_bytes()));
+            let mut dest = array.push().array();
+            dest.item(*page);
+            dest.item(Name(b"XYZ"));
+            dest.item(*x);
+            dest.item(*y);
+            dest.item(Null);
+        }
+    }
+
     if let Some(outline_root_id) = outline_root_id {
         catalog.outlines(outline_root_id);
     }
 
-    if let Some(lang) = lang {
-        catalog.lang(TextStr(lang.as_str()));
+    // Declare the color profile the colors were converted to.
+    if let Some(profile_ref) = ctx.profile_ref {
+        let mut intents = catalog.insert(Name(b"OutputIntents")).array();
+        let mut intent = intents.push().dict();
+        intent.pair(Name(b"Type"), Name(b"OutputIntent"));
+        intent.pair(Name(b"S"), Name(b"GTS_PDFX"));
+        intent.pair(Name(b"OutputConditionIdentifier"), Str(b"Custom"));
+        intent.pair(Name(b"DestOutputProfile"), profile_ref);
+    }
+
+    // Declare the language of the text whose structure element isn't tagged
+    // with its own.
+    if let Some((lang, region)) = lang {
+        catalog.lang(TextStr(&tag(lang, region)));
+    }
+
+    if let Some(struct_tree) = struct_tree {
+        catalog.pair(Name(b"StructTreeRoot"), struct_tree);
+        catalog.insert(Name(b"MarkInfo")).dict().pair(Name(b"Marked"), true);
+    }
+}
+
+/// Write the structure tree, which tells assistive technology what the marked
+/// content of the pages is and in which order to read it.
+///
+/// All elements are children of a single document element. Within a page,
+/// they are ordered by their explicit reading order position and otherwise by
+/// the order in which they were written. Artifacts are not part of the tree.
+/// Returns the tree's root, if there is any tagged content.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn write_struct_tree(ctx: &mut PdfContext) -> Option<Ref> {
+    if ctx.struct_elems.is_empty() {
+        return None;
+    }
+
+    let root_ref = ctx.alloc.bump();
+    let doc_ref = ctx.alloc.bump();
+    let elem_refs: Vec<Ref> =
+        (0..ctx.struct_elems.len()).map(|_| ctx.alloc.bump()).collect();
+
+    for (elem, &elem_ref) in ctx.struct_elems.iter().zip(&elem_refs) {
+        let mut dict = ctx.writer.indirect(elem_ref).dict();
+        dict.pair(Name(b"Type"), Name(b"StructElem"));
+        dict.pair(Name(b"S"), elem.kind);
+        dict.pair(Name(b"P"), doc_ref);
+        dict.pair(Name(b"Pg"), ctx.page_refs[elem.page]);
+        dict.pair(Name(b"K"), elem.mcid);
+        if let Some(lang) = &elem.lang {
+            dict.pair(Name(b"Lang"), TextStr(lang));
+        }
+        if let Some(alt) = &elem.alt {
+            dict.pair(Name(b"Alt"), TextStr(alt));
+        }
+    }
+
+    // The sort is stable, so elements with the same position keep the order
+    // they were written in.
+    let mut order: Vec<usize> = (0..ctx.struct_elems.len()).collect();
+    order.sort_by_key(|&i| (ctx.struct_elems[i].page, ctx.struct_elems[i].order));
+
+    let mut doc = ctx.writer.indirect(doc_ref).dict();
+    doc.pair(Name(b"Type"), Name(b"StructElem"));
+    doc.pair(Name(b"S"), Name(b"Document"));
+    doc.pair(Name(b"P"), root_ref);
+    doc.insert(Name(b"K"))
+        .array()
+        .items(order.iter().map(|&i| elem_refs[i]));
+    doc.finish();
+
+    // The parent tree maps from each page to the elements of its marked
+    // content, indexed by their identifiers. As the identifiers are assigned
+    // in the order the elements are written, the elements are already
+    // grouped by page and sorted by identifier.
+    let mut root = ctx.writer.indirect(root_ref).dict();
+    root.pair(Name(b"Type"), Name(b"StructTreeRoot"));
+    root.pair(Name(b"K"), doc_ref);
+    let mut parent_tree = root.insert(Name(b"ParentTree")).dict();
+    let mut nums = parent_tree.insert(Name(b"Nums")).array();
+    let mut start = 0;
+    while start < ctx.struct_elems.len() {
+        let page = ctx.struct_elems[start].page;
+        let end = ctx.struct_elems[start..]
+            .iter()
+            .position(|elem| elem.page != page)
+            .map_or(ctx.struct_elems.len(), |len| start + len);
+        nums.item(page as i32);
+        nums.push().array().items(elem_refs[start..end].iter().copied());
+        start = end;
+    }
+    nums.finish();
+    parent_tree.finish();
+    root.finish();
+
+    Some(root_ref)
+}
+
+/// Embed the ICC data of the target color profile, if any.
+fn write_color_profile(ctx: &mut PdfContext) {
+    let Some(profile) = &ctx.options.color_profile else { return };
+    let Some(icc) = &profile.icc else { return };
+
+    let profile_ref = ctx.alloc.bump();
+    let compressed = deflate(icc);
+    let mut stream = ctx.writer.icc_profile(profile_ref, &compressed);
+    stream.filter(Filter::FlateDecode);
+    match profile.space {
+        ProfileSpace::Srgb => {
+            stream.n(3);
+            stream.alternate().srgb();
+        }
+        ProfileSpace::Cmyk => {
+            stream.n(4);
+            stream.alternate().device_cmyk();
+        }
+    }
+    stream.finish();
+    ctx.profile_ref = Some(profile_ref);
+}
+
+/// Convert a color into the target color profile's space.
+///
+/// CMYK colors are assumed to already be in a CMYK profile's space.
+fn convert_color(ctx: &PdfContext, color: Color) -> Color {
+    let Some(profile) = &ctx.options.color_profile else { return color };
+    match (&ctx.transform, color) {
+        (_, Color::Cmyk(_)) if profile.space == ProfileSpace::Cmyk => color,
+        (Some(transform), _) => transform.apply(color.to_rgba()),
+        (None, Color::Rgba(_)) => color,
+        (None, _) => Color::Rgba(color.to_rgba()),
+    }
+}
+
+/// Whether CMYK colors are in the target color profile's ICC space instead of
+/// the device's.
+fn has_cmyk_profile(ctx: &PdfContext) -> bool {
+    ctx.options.color_profile.as_ref().map_or(false, |profile| {
+        profile.space == ProfileSpace::Cmyk && profile.icc.is_some()
+    })
+}
+
+/// Determine a named destination for each label in the document, so that
+/// other files can link to labelled elements.
+///
+/// Maps from the names to the page and the coordinates on it. The name tree
+/// requires its names to be sorted, which the map takes care of. If a label
+/// occurs multiple times, the first occurrence wins.
+fn named_destinations(ctx: &PdfContext) -> BTreeMap<EcoString, (Ref, f32, f32)> {
+    let mut dests = BTreeMap::new();
+    for elem in ctx.introspector.all() {
+        let (Some(label), Some(location)) = (elem.label(), elem.location()) else {
+            continue;
+        };
+
+        let pos = ctx.introspector.position(location);
+        let index = pos.page.get() - 1;
+        let Some(&height) = ctx.page_heights.get(index) else { continue };
+        let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
+        dests.entry(label.0.clone()).or_insert((
+            ctx.page_refs[index],
+            pos.point.x.to_f32(),
+            height - y.to_f32(),
+        ));
+    }
+    dests
+}
+
+/// Format a language and an optional region as a BCP 47 language tag.
+fn tag(lang: Lang, region: Option<Region>) -> EcoString {
+    let mut tag = EcoString::from(lang.as_str());
+    if let Some(region) = region {
+        tag.push('-');
+        tag.push_str(region.as_str());
     }
+    tag
 }
 
 /// Compress data with the DEFLATE algorithm.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn deflate(data: &[u8]) -> Vec<u8> {
     const COMPRESSION_LEVEL: u8 = 6;
     miniz_oxide::deflate::compress_to_vec_zlib(data, COMPRESSION_LEVEL)
@@ -1,17 +1,21 @@
 use std::num::NonZeroUsize;
 
+use ecow::eco_vec;
 use pdf_writer::{Finish, Ref, TextStr};
 
 use super::{AbsExt, PdfContext, RefExt};
 use crate::geom::Abs;
-use crate::model::Content;
+use crate::model::{Content, Selector};
 
 /// Construct the outline for the document.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub fn write_outline(ctx: &mut PdfContext) -> Option<Ref> {
+    let selector =
+        Selector::Or(eco_vec![item!(heading_func).select(), item!(part_func).select(),]);
+
     let mut tree: Vec<HeadingNode> = vec![];
-    for heading in ctx.introspector.query(&item!(heading_func).select()) {
-        let leaf = HeadingNode::leaf((*heading).clone());
+    for elem in ctx.introspector.query(&selector) {
+        let Some(leaf) = HeadingNode::leaf((*elem).clone()) else { continue };
 
         let mut children = &mut tree;
         while children.last().map_or(false, |last| last.level < leaf.level) {
@@ -43,21 +47,27 @@ pub fn write_outline(ctx: &mut PdfContext) -> Option<Ref> {
     Some(root_id)
 }
 
-/// A heading in the outline panel.
+/// A heading or document part in the outline panel.
 #[derive(Debug, Clone)]
 struct HeadingNode {
     element: Content,
-    level: NonZeroUsize,
+    title: Content,
+    /// The heading's level or zero for a part, so that parts contain the
+    /// headings after them.
+    level: usize,
     children: Vec<HeadingNode>,
 }
 
 impl HeadingNode {
-    fn leaf(element: Content) -> Self {
-        HeadingNode {
-            level: element.expect_field::<NonZeroUsize>("level"),
-            element,
-            children: Vec::new(),
-        }
+    fn leaf(element: Content) -> Option<Self> {
+        let (title, level) = if element.func() == item!(part_func) {
+            (element.expect_field::<Option<Content>>("title")?, 0)
+        } else {
+            let level = element.expect_field::<NonZeroUsize>("level");
+            (element.expect_field::<Content>("body"), level.get())
+        };
+
+        Some(HeadingNode { element, title, level, children: Vec::new() })
     }
 
     fn len(&self) -> usize {
@@ -66,7 +76,7 @@ impl HeadingNode {
 }
 
 /// Write an outline item and all its children.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn write_outline_item(
     ctx: &mut PdfContext,
     node: &HeadingNode,
@@ -95,8 +105,7 @@ fn write_outline_item(
         outline.count(-(node.children.len() as i32));
     }
 
-    let body = node.element.expect_field::<Content>("body");
-    outline.title(TextStr(body.plain_text().trim()));
+    outline.title(TextStr(node.title.plain_text().trim()));
 
     let loc = node.element.location().unwrap();
     let pos = ctx.introspector.position(loc);
@@ -1,29 +1,32 @@
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
 use pdf_writer::types::{
     ActionType, AnnotationType, ColorSpaceOperand, LineCapStyle, LineJoinStyle,
 };
 use pdf_writer::writers::ColorSpace;
-use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str};
+use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str, TextStr};
+use ttf_parser::GlyphId;
 
-use super::{deflate, AbsExt, EmExt, PdfContext, RefExt, D65_GRAY, SRGB};
-use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
+use super::{
+    convert_color, deflate, has_cmyk_profile, tag, AbsExt, EmExt, PdfContext,
+    ProfileSpace, RefExt, CMYK, D65_GRAY, SRGB,
+};
+use crate::doc::{
+    BreakKind, Destination, Frame, FrameItem, GroupItem, Meta, RemoteTarget, TextItem,
+};
+use crate::export::render::MAX_BLUR;
+use crate::export::svg_glyph;
 use crate::font::Font;
 use crate::geom::{
     self, Abs, Color, Em, Geometry, LineCap, LineJoin, Numeric, Paint, Point, Ratio,
     Shape, Size, Stroke, Transform,
 };
-use crate::image::Image;
+use crate::image::{Image, ImageFormat, RasterFormat};
 
-/// Construct page objects.
-#[tracing::instrument(skip_all)]
-pub fn construct_pages(ctx: &mut PdfContext, frames: &[Frame]) {
-    for frame in frames {
-        construct_page(ctx, frame);
-    }
-}
-
-/// Construct a page object.
-#[tracing::instrument(skip_all)]
+/// Construct a page object and write its content stream.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(page = ctx.page_refs.len() + 1))
+)]
 pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
     let page_ref = ctx.alloc.bump();
     ctx.page_refs.push(page_ref);
@@ -37,6 +40,10 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
         saves: vec![],
         bottom: 0.0,
         links: vec![],
+        mcid: 0,
+        artifact: false,
+        order: 0,
+        word_end: None,
     };
 
     let size = frame.size();
@@ -52,24 +59,38 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
         ty: size.y,
     });
 
+    if let Some(profile) = &ctx.parent.options.color_profile {
+        ctx.content.set_rendering_intent(profile.intent.to_pdf());
+    }
+
     // Encode the page into the content stream.
     write_frame(&mut ctx, frame);
 
+    // Write the content stream right away, so that only the compressed data
+    // is kept until the page tree is written.
+    let content_id = ctx.parent.alloc.bump();
+    let data = deflate(&ctx.content.finish());
+    ctx.parent
+        .writer
+        .stream(content_id, &data)
+        .filter(Filter::FlateDecode);
+
     let page = Page {
         size,
-        content: ctx.content,
+        content_id,
         id: ctx.page_ref,
         links: ctx.links,
+        tagged: ctx.mcid > 0,
     };
 
     ctx.parent.pages.push(page);
 }
 
 /// Write the page tree.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub fn write_page_tree(ctx: &mut PdfContext) {
-    for page in std::mem::take(&mut ctx.pages).into_iter() {
-        write_page(ctx, page);
+    for (i, page) in std::mem::take(&mut ctx.pages).into_iter().enumerate() {
+        write_page(ctx, i, page);
     }
 
     let mut pages = ctx.writer.pages(ctx.page_tree_ref);
@@ -78,9 +99,19 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
         .kids(ctx.page_refs.iter().copied());
 
     let mut resources = pages.resources();
+    // With an ICC profile, the colors were converted into its space.
+    let profile = ctx.options.color_profile.as_ref().map(|profile| profile.space);
     let mut spaces = resources.color_spaces();
-    spaces.insert(SRGB).start::<ColorSpace>().srgb();
+    match (profile, ctx.profile_ref) {
+        (Some(ProfileSpace::Srgb), Some(profile_ref)) => {
+            spaces.insert(SRGB).start::<ColorSpace>().icc_based(profile_ref);
+        }
+        _ => spaces.insert(SRGB).start::<ColorSpace>().srgb(),
+    }
     spaces.insert(D65_GRAY).start::<ColorSpace>().d65_gray();
+    if let (Some(ProfileSpace::Cmyk), Some(profile_ref)) = (profile, ctx.profile_ref) {
+        spaces.insert(CMYK).start::<ColorSpace>().icc_based(profile_ref);
+    }
     spaces.finish();
 
     let mut fonts = resources.fonts();
@@ -103,17 +134,22 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
 }
 
 /// Write a page tree node.
-#[tracing::instrument(skip_all)]
-fn write_page(ctx: &mut PdfContext, page: Page) {
-    let content_id = ctx.alloc.bump();
-
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn write_page(ctx: &mut PdfContext, index: usize, page: Page) {
     let mut page_writer = ctx.writer.page(page.id);
     page_writer.parent(ctx.page_tree_ref);
 
+    // The page's entry in the structure tree's parent tree is keyed by its
+    // index.
+    if page.tagged {
+        page_writer.pair(Name(b"StructParents"), index as i32);
+        page_writer.pair(Name(b"Tabs"), Name(b"S"));
+    }
+
     let w = page.size.x.to_f32();
     let h = page.size.y.to_f32();
     page_writer.media_box(Rect::new(0.0, 0.0, w, h));
-    page_writer.contents(content_id);
+    page_writer.contents(page.content_id);
 
     let mut annotations = page_writer.annotations();
     for (dest, rect) in page.links {
@@ -129,6 +165,22 @@ fn write_page(ctx: &mut PdfContext, page: Page) {
                     .uri(Str(uri.as_bytes()));
                 continue;
             }
+            Destination::Remote(remote) => {
+                let mut action = annotation.action();
+                action.action_type(ActionType::RemoteGoTo);
+                action.pair(Name(b"F"), Str(remote.file.as_bytes()));
+                match &remote.target {
+                    RemoteTarget::Page(page) => {
+                        let mut dest = action.insert(Name(b"D")).array();
+                        dest.item(page.get() as i32 - 1);
+                        dest.item(Name(b"Fit"));
+                    }
+                    RemoteTarget::Name(name) => {
+                        action.pair(Name(b"D"), Str(name.as_bytes()));
+                    }
+                }
+                continue;
+            }
             Destination::Position(pos) => pos,
             Destination::Location(loc) => ctx.introspector.position(loc),
         };
@@ -147,10 +199,6 @@ fn write_page(ctx: &mut PdfContext, page: Page) {
 
     annotations.finish();
     page_writer.finish();
-
-    let data = page.content.finish();
-    let data = deflate(&data);
-    ctx.writer.stream(content_id, &data).filter(Filter::FlateDecode);
 }
 
 /// Data for an exported page.
@@ -159,10 +207,28 @@ pub struct Page {
     pub id: Ref,
     /// The page's dimensions.
     pub size: Size,
-    /// The page's content stream.
-    pub content: Content,
+    /// The indirect object id of the page's content stream.
+    pub content_id: Ref,
     /// Links in the PDF coordinate system.
     pub links: Vec<(Destination, Rect)>,
+    /// Whether the page has marked content in the structure tree.
+    pub tagged: bool,
+}
+
+/// An element of the structure tree that holds marked content of a page.
+pub struct StructElem {
+    /// The structure type, like `Span` or `Figure`.
+    pub kind: Name<'static>,
+    /// The index of the page the marked content is on.
+    pub page: usize,
+    /// The identifier of the marked content within its page.
+    pub mcid: i32,
+    /// The element's position in the reading order of its page.
+    pub order: i64,
+    /// The language of the content if it differs from the document's.
+    pub lang: Option<EcoString>,
+    /// An alternative description of the content.
+    pub alt: Option<EcoString>,
 }
 
 /// An exporter for the contents of a single PDF page.
@@ -174,6 +240,15 @@ struct PageContext<'a, 'b> {
     saves: Vec<State>,
     bottom: f32,
     links: Vec<(Destination, Rect)>,
+    /// The identifier of the next marked content in the structure tree.
+    mcid: i32,
+    /// Whether the content being written is part of an artifact.
+    artifact: bool,
+    /// The reading order position of the content being written.
+    order: i64,
+    /// Where the last text run ended if it ended in the middle of a word
+    /// sequence, in page coordinates.
+    word_end: Option<Point>,
 }
 
 /// A simulated graphics state used to deduplicate graphics state changes and
@@ -224,8 +299,8 @@ impl PageContext<'_, '_> {
     fn set_fill(&mut self, fill: &Paint) {
         if self.state.fill.as_ref() != Some(fill) {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = fill;
-            match color {
+            let &Paint::Solid(color) = fill;
+            match convert_color(self.parent, color) {
                 Color::Luma(c) => {
                     self.set_fill_color_space(D65_GRAY);
                     self.content.set_fill_gray(f(c.0));
@@ -234,6 +309,10 @@ impl PageContext<'_, '_> {
                     self.set_fill_color_space(SRGB);
                     self.content.set_fill_color([f(c.r), f(c.g), f(c.b)]);
                 }
+                Color::Cmyk(c) if has_cmyk_profile(self.parent) => {
+                    self.set_fill_color_space(CMYK);
+                    self.content.set_fill_color([f(c.c), f(c.m), f(c.y), f(c.k)]);
+                }
                 Color::Cmyk(c) => {
                     self.reset_fill_color_space();
                     self.content.set_fill_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
@@ -266,8 +345,8 @@ impl PageContext<'_, '_> {
             } = stroke;
 
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = paint;
-            match color {
+            let &Paint::Solid(color) = paint;
+            match convert_color(self.parent, color) {
                 Color::Luma(c) => {
                     self.set_stroke_color_space(D65_GRAY);
                     self.content.set_stroke_gray(f(c.0));
@@ -276,6 +355,10 @@ impl PageContext<'_, '_> {
                     self.set_stroke_color_space(SRGB);
                     self.content.set_stroke_color([f(c.r), f(c.g), f(c.b)]);
                 }
+                Color::Cmyk(c) if has_cmyk_profile(self.parent) => {
+                    self.set_stroke_color_space(CMYK);
+                    self.content.set_stroke_color([f(c.c), f(c.m), f(c.y), f(c.k)]);
+                }
                 Color::Cmyk(c) => {
                     self.reset_stroke_color_space();
                     self.content.set_stroke_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
@@ -316,16 +399,70 @@ impl PageContext<'_, '_> {
     fn reset_stroke_color_space(&mut self) {
         self.state.stroke_space = None;
     }
+
+    /// Add an element to the structure tree for the next marked content.
+    ///
+    /// Returns the marked content's identifier or `None` if the content is
+    /// part of an artifact, which stays out of the tree.
+    fn tag(
+        &mut self,
+        kind: Name<'static>,
+        lang: Option<EcoString>,
+        alt: Option<EcoString>,
+    ) -> Option<i32> {
+        if self.artifact {
+            return None;
+        }
+
+        let mcid = self.mcid;
+        self.mcid += 1;
+        self.parent.struct_elems.push(StructElem {
+            kind,
+            page: self.parent.page_refs.len() - 1,
+            mcid,
+            order: self.order,
+            lang,
+            alt,
+        });
+        Some(mcid)
+    }
+
+    /// Whether a text run continues the text of the previous run on a new
+    /// line after a word gap.
+    ///
+    /// Layout trims the spaces at the ends of lines, so there is no glyph that
+    /// a viewer could map back to the space between the words.
+    fn word_gap(&self, pos: Point, text: &TextItem) -> bool {
+        let start = pos.transform(self.state.transform);
+        self.word_end.is_some_and(|end| start.y != end.y && start.x < end.x)
+            && text.text.chars().next().is_some_and(|c| !c.is_whitespace())
+    }
 }
 
 /// Encode a frame into the content stream.
 fn write_frame(ctx: &mut PageContext, frame: &Frame) {
-    for &(pos, ref item) in frame.items() {
+    let mut items = frame.items().peekable();
+    while let Some(&(pos, ref item)) = items.next() {
         let x = pos.x.to_f32();
         let y = pos.y.to_f32();
         match item {
             FrameItem::Group(group) => write_group(ctx, pos, group),
-            FrameItem::Text(text) => write_text(ctx, x, y, text),
+            FrameItem::Text(text) => {
+                // Adjacent runs with the same state are written into one
+                // text object, which keeps text-heavy content streams small.
+                let mut cluster = vec![(pos, text)];
+                if !needs_actual_text(text) && !ctx.word_gap(pos, text) {
+                    while let Some((next, FrameItem::Text(other))) =
+                        items.next_if(|&&(next, ref item)| match item {
+                            FrameItem::Text(other) => joinable(pos, text, next, other),
+                            _ => false,
+                        })
+                    {
+                        cluster.push((*next, other));
+                    }
+                }
+                write_text(ctx, &cluster);
+            }
             FrameItem::Shape(shape, _) => write_shape(ctx, x, y, shape),
             FrameItem::Image(image, size, _) => write_image(ctx, x, y, image, *size),
             FrameItem::Meta(meta, size) => match meta {
@@ -333,6 +470,13 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
                 Meta::Elem(_) => {}
                 Meta::Hide => {}
                 Meta::PageNumbering(_) => {}
+                Meta::Artifact => {}
+                // A new paragraph doesn't continue the last word.
+                Meta::Break(explanation) if explanation.kind == BreakKind::Paragraph => {
+                    ctx.word_end = None;
+                }
+                Meta::Break(_) => {}
+                Meta::Region => {}
             },
         }
     }
@@ -342,6 +486,19 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
 fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
     let translation = Transform::translate(pos.x, pos.y);
 
+    // Artifacts may not contain content of the structure tree, so nested
+    // artifacts are only marked once.
+    let artifact = group.artifact && !ctx.artifact;
+    if artifact {
+        ctx.content.begin_marked_content(Name(b"Artifact"));
+        ctx.artifact = true;
+    }
+
+    let order = ctx.order;
+    if let Some(explicit) = group.order {
+        ctx.order = explicit;
+    }
+
     ctx.save_state();
     ctx.transform(translation.pre_concat(group.transform));
 
@@ -357,54 +514,184 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
         ctx.content.end_path();
     }
 
-    write_frame(ctx, &group.frame);
+    if group.filters.is_empty() {
+        write_frame(ctx, &group.frame);
+    } else {
+        write_filtered(ctx, group);
+    }
+
     ctx.restore_state();
+    ctx.order = order;
+
+    if artifact {
+        ctx.content.end_marked_content();
+        ctx.artifact = false;
+    }
+}
+
+/// The resolution in pixels per point at which filtered groups are rasterized.
+const FILTER_RESOLUTION: f32 = 3.0;
+
+/// The largest width or height in pixels of a rasterized filtered group.
+/// Larger groups are rasterized at a lower resolution.
+const MAX_FILTER_SIZE: f32 = 4096.0;
+
+/// Encode a filtered group as a raster image with transparency.
+///
+/// PDF has no equivalent to the raster exporter's filters, so the group is
+/// rendered with them applied and embedded as an image. The image covers the
+/// space blurring spreads the group's contents by.
+fn write_filtered(ctx: &mut PageContext, group: &GroupItem) {
+    // Clamp blurs just like the raster exporter does at the filter
+    // resolution.
+    let max_sigma = Abs::pt((MAX_BLUR / FILTER_RESOLUTION) as f64);
+    let filters: Vec<_> = group
+        .filters
+        .iter()
+        .map(|filter| match *filter {
+            crate::doc::Filter::Blur(sigma) => {
+                crate::doc::Filter::Blur(sigma.min(max_sigma))
+            }
+            other => other,
+        })
+        .collect();
+
+    let spread: Abs = filters
+        .iter()
+        .map(|filter| match *filter {
+            crate::doc::Filter::Blur(sigma) => sigma * 3.0,
+            _ => Abs::zero(),
+        })
+        .sum();
+
+    let size = group.frame.size() + Size::splat(spread * 2.0);
+    let mut frame = Frame::new(size);
+    let inner = GroupItem { filters, ..GroupItem::new(group.frame.clone()) };
+    frame.push(Point::splat(spread), FrameItem::Group(inner));
+
+    let longest = size.x.max(size.y).to_f32();
+    let resolution = if longest > 0.0 {
+        FILTER_RESOLUTION.min(MAX_FILTER_SIZE / longest)
+    } else {
+        FILTER_RESOLUTION
+    };
+
+    let transparent = Color::Rgba(geom::RgbaColor::new(0, 0, 0, 0));
+    let pixmap = crate::export::render(&frame, resolution, transparent);
+    let Some(image) = pixmap.encode_png().ok().and_then(|data| {
+        Image::new(data.into(), ImageFormat::Raster(RasterFormat::Png), None).ok()
+    }) else {
+        write_frame(ctx, &group.frame);
+        return;
+    };
+
+    let pos = -spread.to_f32();
+    write_image(ctx, pos, pos, &image, size);
+}
+
+/// Whether a text run can be written into the same text object as a previous
+/// one.
+///
+/// This is the case if both share their font, fill and language, sit on the
+/// same baseline and don't need their own actual text.
+fn joinable(pos: Point, text: &TextItem, next: Point, other: &TextItem) -> bool {
+    text.font == other.font
+        && text.size == other.size
+        && text.fill == other.fill
+        && text.lang == other.lang
+        && text.region == other.region
+        && pos.y == next.y
+        && !needs_actual_text(other)
 }
 
-/// Encode a text run into the content stream.
-fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
-    *ctx.parent.languages.entry(text.lang).or_insert(0) += text.glyphs.len();
+/// Encode a cluster of text runs with the same state into the content stream.
+///
+/// The runs are written as a single `TJ` array, with the gaps between them
+/// expressed as kerning adjustments.
+fn write_text(ctx: &mut PageContext, cluster: &[(Point, &TextItem)]) {
+    let (start, text) = cluster[0];
+    let lang = (text.lang, text.region);
+    let primary = *ctx.parent.primary_lang.get_or_insert(lang);
+    if !ctx.parent.languages.contains(&lang) {
+        ctx.parent.languages.push(lang);
+    }
 
-    let glyph_set = ctx.parent.glyph_sets.entry(text.font.clone()).or_default();
-    for g in &text.glyphs {
-        let segment = &text.text[g.range()];
-        glyph_set.entry(g.id).or_insert_with(|| segment.into());
+    for &(_, run) in cluster {
+        let glyph_set = ctx.parent.glyph_sets.entry(run.font.clone()).or_default();
+        for g in &run.glyphs {
+            let segment = &run.text[g.range()];
+            glyph_set.entry(g.id).or_insert_with(|| segment.into());
+        }
+    }
+
+    // Text is added to the structure tree as a span. If it is in another
+    // language than the document's, the span is tagged with its language, so
+    // that screen readers pronounce it correctly. If the glyphs can't be
+    // mapped back to the text one by one, e.g. because of ligatures or complex
+    // shaping, we attach the original text so that copy-paste and search in
+    // viewers return it. The same goes for a run that continues on a new line
+    // after a word gap, whose actual text starts with the trimmed space.
+    let foreign = (lang != primary).then(|| tag(text.lang, text.region));
+    let mcid = ctx.tag(Name(b"Span"), foreign, None);
+    let gap = ctx.word_gap(start, text);
+    let actual = needs_actual_text(text) || gap;
+    let marked = mcid.is_some() || actual;
+    if marked {
+        let mut span = ctx.content.begin_marked_content_with_properties(Name(b"Span"));
+        let mut properties = span.properties();
+        if let Some(mcid) = mcid {
+            properties.pair(Name(b"MCID"), mcid);
+        }
+        if gap {
+            properties.pair(Name(b"ActualText"), TextStr(&eco_format!(" {}", text.text)));
+        } else if actual {
+            properties.pair(Name(b"ActualText"), TextStr(&text.text));
+        }
+        properties.finish();
+        span.finish();
     }
 
     ctx.set_fill(&text.fill);
     ctx.set_font(&text.font, text.size);
     ctx.content.begin_text();
 
-    // Positiosn the text.
+    // Position the text.
+    let (x, y) = (start.x.to_f32(), start.y.to_f32());
     ctx.content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, y]);
 
     let mut positioned = ctx.content.show_positioned();
     let mut items = positioned.items();
     let mut adjustment = Em::zero();
     let mut encoded = vec![];
+    let mut end = start.x;
 
     // Write the glyphs with kerning adjustments.
-    for glyph in &text.glyphs {
-        adjustment += glyph.x_offset;
+    for &(pos, run) in cluster {
+        adjustment += Em::from_length(pos.x - end, run.size);
+        end = pos.x + run.width();
+
+        for glyph in &run.glyphs {
+            adjustment += glyph.x_offset;
 
-        if !adjustment.is_zero() {
-            if !encoded.is_empty() {
-                items.show(Str(&encoded));
-                encoded.clear();
+            if !adjustment.is_zero() {
+                if !encoded.is_empty() {
+                    items.show(Str(&encoded));
+                    encoded.clear();
+                }
+
+                items.adjust(-adjustment.to_font_units());
+                adjustment = Em::zero();
             }
 
-            items.adjust(-adjustment.to_font_units());
-            adjustment = Em::zero();
-        }
+            encoded.push((glyph.id >> 8) as u8);
+            encoded.push((glyph.id & 0xff) as u8);
 
-        encoded.push((glyph.id >> 8) as u8);
-        encoded.push((glyph.id & 0xff) as u8);
+            if let Some(advance) = run.font.advance(glyph.id) {
+                adjustment += glyph.x_advance - advance;
+            }
 
-        if let Some(advance) = text.font.advance(glyph.id) {
-            adjustment += glyph.x_advance - advance;
+            adjustment -= glyph.x_offset;
         }
-
-        adjustment -= glyph.x_offset;
     }
 
     if !encoded.is_empty() {
@@ -414,6 +701,69 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
     items.finish();
     positioned.finish();
     ctx.content.end_text();
+
+    if marked {
+        ctx.content.end_marked_content();
+    }
+
+    let &(pos, last) = cluster.last().unwrap();
+    let end = Point::new(pos.x + last.width(), pos.y);
+    ctx.word_end = ends_word(last).then(|| end.transform(ctx.state.transform));
+
+    // Glyphs that only exist as SVG documents (e.g. in color emoji fonts)
+    // have no outline in the embedded font, so we draw them as images on top
+    // of the invisible text.
+    for &(pos, run) in cluster {
+        let mut offset = Abs::zero();
+        for glyph in &run.glyphs {
+            let id = GlyphId(glyph.id);
+            if run.font.ttf().glyph_bounding_box(id).is_none() {
+                if let Some(image) = svg_glyph(&run.font, id) {
+                    let metrics = run.font.metrics();
+                    let top = metrics.ascender.at(run.size);
+                    let size = Size::new(
+                        run.size,
+                        (metrics.ascender - metrics.descender).at(run.size),
+                    );
+                    let dx = (pos.x + offset + glyph.x_offset.at(run.size)).to_f32();
+                    write_image(ctx, dx, (pos.y - top).to_f32(), &image, size);
+                }
+            }
+            offset += glyph.x_advance.at(run.size);
+        }
+    }
+}
+
+/// Whether the glyphs of a text run can't be mapped back to the run's text
+/// one by one through a /ToUnicode map.
+///
+/// This is the case if a glyph stands for multiple characters (ligatures), if
+/// multiple glyphs share a cluster or if the shaper substituted a glyph that
+/// the font doesn't directly map the character to.
+fn needs_actual_text(text: &TextItem) -> bool {
+    let ttf = text.font.ttf();
+    let mut prev = None;
+    text.glyphs.iter().any(|glyph| {
+        let range = glyph.range();
+        let mut chars = text.text[range.clone()].chars();
+        let direct = match (chars.next(), chars.next()) {
+            (Some(c), None) => ttf.glyph_index(c) == Some(GlyphId(glyph.id)),
+            _ => false,
+        };
+        let shared = prev.replace(range.clone()) == Some(range);
+        !direct || shared
+    })
+}
+
+/// Whether a text run ends in a word that may be followed by a gap.
+///
+/// Runs that end in whitespace already contain their gap and runs that end in
+/// a hyphen continue their word on the next line.
+fn ends_word(text: &TextItem) -> bool {
+    let hyphenated = text.glyphs.last().is_some_and(|glyph| glyph.range.is_empty());
+    let last = text.text.chars().last();
+    !hyphenated
+        && last.is_some_and(|c| !c.is_whitespace() && !matches!(c, '-' | '\u{ad}'))
 }
 
 /// Encode a geometrical shape into the content stream.
@@ -494,16 +844,21 @@ fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size)
     let name = eco_format!("Im{}", ctx.parent.image_map.map(image.clone()));
     let w = size.x.to_f32();
     let h = size.y.to_f32();
+    let placed = ctx.parent.image_sizes.entry(image.clone()).or_default();
+    *placed = (placed.0.max(w), placed.1.max(h));
     ctx.content.save_state();
     ctx.content.transform([w, 0.0, 0.0, -h, x, y + h]);
 
-    if let Some(alt) = image.alt() {
-        let mut image_span =
-            ctx.content.begin_marked_content_with_properties(Name(b"Span"));
-        let mut image_alt = image_span.properties();
-        image_alt.pair(Name(b"Alt"), pdf_writer::Str(alt.as_bytes()));
-        image_alt.finish();
-        image_span.finish();
+    // Images with an alternative description are added to the structure
+    // tree as figures.
+    let mcid = image
+        .alt()
+        .and_then(|alt| ctx.tag(Name(b"Figure"), None, Some(alt.into())));
+    if let Some(mcid) = mcid {
+        let mut figure =
+            ctx.content.begin_marked_content_with_properties(Name(b"Figure"));
+        figure.properties().pair(Name(b"MCID"), mcid);
+        figure.finish();
 
         ctx.content.x_object(Name(name.as_bytes()));
         ctx.content.end_marked_content();
@@ -563,3 +918,189 @@ impl From<&LineJoin> for LineJoinStyle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doc::{Break, Glyph, Lang};
+    use crate::syntax::Span;
+    use crate::util::Buffer;
+
+    fn font() -> Font {
+        let data = include_bytes!("../../../assets/fonts/LinLibertine_R.ttf");
+        Font::new(Buffer::from_static(data), 0).unwrap()
+    }
+
+    fn run(font: &Font, text: &str, fill: Color) -> TextItem {
+        let glyphs = text
+            .char_indices()
+            .map(|(i, c)| {
+                let id = font.ttf().glyph_index(c).unwrap().0;
+                Glyph {
+                    id,
+                    x_advance: font.advance(id).unwrap(),
+                    x_offset: Em::zero(),
+                    range: i as u16..i as u16 + 1,
+                    span: (Span::detached(), 0),
+                }
+            })
+            .collect();
+
+        TextItem {
+            font: font.clone(),
+            size: Abs::pt(10.0),
+            fill: fill.into(),
+            lang: Lang::ENGLISH,
+            region: None,
+            text: text.into(),
+            glyphs,
+        }
+    }
+
+    /// Write the content stream of a frame.
+    fn content(frame: &Frame) -> Vec<u8> {
+        write(frame, |_, content| content)
+    }
+
+    /// Write a frame into a page and inspect the context and the content
+    /// stream.
+    fn write<T>(frame: &Frame, f: impl FnOnce(&PdfContext, Vec<u8>) -> T) -> T {
+        let options = Default::default();
+        let mut parent = PdfContext::new(&options);
+        parent.page_refs.push(Ref::new(1));
+        let mut ctx = PageContext {
+            parent: &mut parent,
+            page_ref: Ref::new(1),
+            content: Content::new(),
+            state: State::default(),
+            saves: vec![],
+            bottom: 0.0,
+            links: vec![],
+            mcid: 0,
+            artifact: false,
+            order: 0,
+            word_end: None,
+        };
+        write_frame(&mut ctx, frame);
+        let content = ctx.content.finish();
+        f(&parent, content)
+    }
+
+    /// Count the text objects in the content stream of a frame.
+    fn text_objects(frame: &Frame) -> usize {
+        content(frame)
+            .split(|b| b.is_ascii_whitespace())
+            .filter(|token| *token == b"BT")
+            .count()
+    }
+
+    #[test]
+    fn test_write_text_joins_runs() {
+        let font = font();
+        let hello = run(&font, "Hello", Color::BLACK);
+        let world = run(&font, "world", Color::BLACK);
+        let x = hello.width() + Abs::pt(3.0);
+
+        // Runs on the same baseline with the same state share a text object.
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(20.0)));
+        frame.push(Point::with_y(Abs::pt(10.0)), FrameItem::Text(hello.clone()));
+        frame.push(Point::new(x, Abs::pt(10.0)), FrameItem::Text(world.clone()));
+        assert_eq!(text_objects(&frame), 1);
+
+        // A different baseline or fill needs a new one.
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(40.0)));
+        frame.push(Point::with_y(Abs::pt(10.0)), FrameItem::Text(hello.clone()));
+        frame.push(Point::new(x, Abs::pt(30.0)), FrameItem::Text(world));
+        frame.push(
+            Point::new(x * 2.0, Abs::pt(30.0)),
+            FrameItem::Text(run(&font, "red", Color::RED)),
+        );
+        assert_eq!(text_objects(&frame), 3);
+    }
+
+    #[test]
+    fn test_write_text_word_gaps() {
+        let font = font();
+        let actual = |frame: &Frame| {
+            let content = content(frame);
+            let needle = b"/ActualText ( world)";
+            content.windows(needle.len()).filter(|w| *w == needle).count()
+        };
+
+        // A run that continues on the next line gets the trimmed space back.
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(40.0)));
+        let hello = run(&font, "Hello", Color::BLACK);
+        let x = hello.width() + Abs::pt(3.0);
+        frame.push(Point::with_y(Abs::pt(10.0)), FrameItem::Text(hello.clone()));
+        frame.push(
+            Point::with_y(Abs::pt(30.0)),
+            FrameItem::Text(run(&font, "world", Color::BLACK)),
+        );
+        assert_eq!(actual(&frame), 1);
+
+        // On the same line or after a hyphen, there is no gap to fill.
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(40.0)));
+        frame.push(Point::with_y(Abs::pt(10.0)), FrameItem::Text(hello));
+        frame.push(
+            Point::new(x, Abs::pt(10.0)),
+            FrameItem::Text(run(&font, "world", Color::BLACK)),
+        );
+        assert_eq!(actual(&frame), 0);
+
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(40.0)));
+        frame.push(
+            Point::with_y(Abs::pt(10.0)),
+            FrameItem::Text(run(&font, "well-", Color::BLACK)),
+        );
+        frame.push(
+            Point::with_y(Abs::pt(30.0)),
+            FrameItem::Text(run(&font, "known", Color::BLACK)),
+        );
+        assert!(!content(&frame).windows(11).any(|w| w == b"/ActualText"));
+
+        // A new paragraph starts without a gap.
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(60.0)));
+        frame.push(
+            Point::with_y(Abs::pt(10.0)),
+            FrameItem::Text(run(&font, "Hello", Color::BLACK)),
+        );
+        let end = Break::new(BreakKind::Paragraph, "end of paragraph");
+        frame.push(Point::zero(), FrameItem::Meta(Meta::Break(end), Size::zero()));
+        frame.push(
+            Point::with_y(Abs::pt(40.0)),
+            FrameItem::Text(run(&font, "world", Color::BLACK)),
+        );
+        assert_eq!(actual(&frame), 0);
+        assert!(!content(&frame).windows(11).any(|w| w == b"/ActualText"));
+    }
+
+    #[test]
+    fn test_write_filtered_bounds_pixmap() {
+        use crate::doc::Filter;
+
+        let image_size = |size: Size, sigma: Abs| {
+            let mut inner = Frame::new(size);
+            let shape = Geometry::Rect(size).filled(Color::BLACK.into());
+            inner.push(Point::zero(), FrameItem::Shape(shape, Span::detached()));
+            let mut group = GroupItem::new(inner);
+            group.filters = vec![Filter::Blur(sigma)];
+            let mut frame = Frame::new(size);
+            frame.push(Point::zero(), FrameItem::Group(group));
+            write(&frame, |parent, _| {
+                let images: Vec<_> = parent.image_map.items().collect();
+                assert_eq!(images.len(), 1);
+                (images[0].width(), images[0].height())
+            })
+        };
+
+        // The spread of a huge blur is clamped like in the raster exporter.
+        let square = Size::splat(Abs::pt(10.0));
+        assert_eq!(image_size(square, Abs::pt(2.0)), (66, 66));
+        assert_eq!(image_size(square, Abs::pt(1000.0)), (630, 630));
+
+        // Large groups are rasterized at a lower resolution.
+        let (w, h) = image_size(Size::new(Abs::pt(5000.0), Abs::pt(100.0)), Abs::zero());
+        assert_eq!(w, 4096);
+        assert!(h < 100);
+    }
+}
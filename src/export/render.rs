@@ -1,27 +1,144 @@
 //! Rendering into raster images.
 
 use std::io::Read;
+use std::ops::Range;
 use std::sync::Arc;
 
 use image::imageops::FilterType;
 use image::{GenericImageView, Rgba};
+use once_cell::sync::Lazy;
 use resvg::FitTo;
 use tiny_skia as sk;
 use ttf_parser::{GlyphId, OutlineBuilder};
 use usvg::{NodeExt, TreeParsing};
 
-use crate::doc::{Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::doc::{Document, Filter, Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::font::Font;
 use crate::geom::{
     self, Abs, Color, Geometry, LineCap, LineJoin, Paint, PathItem, Shape, Size, Stroke,
     Transform,
 };
 use crate::image::{DecodedImage, Image};
+use crate::util::hash128;
+use crate::util::lru::Lru;
 
 /// Export a frame into a raster image.
 ///
 /// This renders the frame at the given number of pixels per point and returns
 /// the resulting `tiny-skia` pixel buffer.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub fn render(frame: &Frame, pixel_per_pt: f32, fill: Color) -> sk::Pixmap {
+    render_impl(frame, pixel_per_pt, fill, false)
+}
+
+/// Export a frame into a small preview image.
+///
+/// The frame is scaled to fit into a square with a side length of `max_size`
+/// pixels. This reuses the laid out frame, so it's cheap enough to refresh a
+/// strip of page previews after every edit. To stay fast, filters are skipped
+/// and images are scaled with a cheaper, lower-quality algorithm.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn render_thumbnail(frame: &Frame, max_size: u32, fill: Color) -> sk::Pixmap {
+    let size = frame.size();
+    let longest = size.x.max(size.y).to_f32();
+    let pixel_per_pt = if longest > 0.0 { max_size as f32 / longest } else { 1.0 };
+    render_impl(frame, pixel_per_pt, fill, true)
+}
+
+/// Renders the pages of a document on demand and keeps the results.
+///
+/// Compilation is incremental, so an edit only relayouts what it affects. This
+/// cache does the same for rendering: It only renders the pages that were
+/// requested and reuses earlier renderings of pages whose frames did not
+/// change. Previewers can use this to keep scrolling through and editing long
+/// documents responsive by only rendering the pages in their viewport. With
+/// [`World::page_limit`](crate::World::page_limit), they can also stop layout
+/// after the last page in the viewport.
+pub struct PageCache {
+    pixel_per_pt: f32,
+    fill: Color,
+    pages: Vec<Option<(u128, sk::Pixmap)>>,
+}
+
+impl PageCache {
+    /// Create an empty cache for renderings with the given resolution and
+    /// background fill.
+    pub fn new(pixel_per_pt: f32, fill: Color) -> Self {
+        Self { pixel_per_pt, fill, pages: vec![] }
+    }
+
+    /// Render the pages with indices in the given range, reusing cached
+    /// renderings of unchanged pages.
+    ///
+    /// Returns the indices of the pages together with their renderings.
+    /// Indices beyond the end of the document are ignored.
+    pub fn render(
+        &mut self,
+        document: &Document,
+        pages: Range<usize>,
+    ) -> Vec<(usize, &sk::Pixmap)> {
+        // Forget about pages that don't exist anymore.
+        self.pages.resize_with(document.pages.len(), || None);
+
+        let pages =
+            pages.start.min(document.pages.len())..pages.end.min(document.pages.len());
+        for i in pages.clone() {
+            let frame = &document.pages[i];
+            let hash = hash128(frame);
+            if !matches!(&self.pages[i], Some((prev, _)) if *prev == hash) {
+                let pixmap = render(frame, self.pixel_per_pt, self.fill);
+                self.pages[i] = Some((hash, pixmap));
+            }
+        }
+
+        pages
+            .filter_map(|i| self.pages[i].as_ref().map(|(_, pixmap)| (i, pixmap)))
+            .collect()
+    }
+
+    /// Render the pages that intersect a viewport.
+    ///
+    /// See [`visible_pages`] for how the viewport is interpreted.
+    pub fn render_viewport(
+        &mut self,
+        document: &Document,
+        viewport: Range<Abs>,
+        gap: Abs,
+    ) -> Vec<(usize, &sk::Pixmap)> {
+        let pages = visible_pages(document, viewport, gap);
+        self.render(document, pages)
+    }
+}
+
+/// The indices of the pages that intersect a viewport.
+///
+/// The viewport is a vertical range in a view where the pages are stacked on
+/// top of each other with a gap of `gap` between them, starting at zero.
+pub fn visible_pages(
+    document: &Document,
+    viewport: Range<Abs>,
+    gap: Abs,
+) -> Range<usize> {
+    let mut start = None;
+    let mut end = 0;
+    let mut top = Abs::zero();
+    for (i, frame) in document.pages.iter().enumerate() {
+        let bottom = top + frame.height();
+        if bottom > viewport.start && top < viewport.end {
+            start.get_or_insert(i);
+            end = i + 1;
+        }
+        top = bottom + gap;
+    }
+
+    match start {
+        Some(start) => start..end,
+        None => 0..0,
+    }
+}
+
+/// Render a frame, optionally skipping expensive effects.
+fn render_impl(frame: &Frame, pixel_per_pt: f32, fill: Color, fast: bool) -> sk::Pixmap {
     let size = frame.size();
     let pxw = (pixel_per_pt * size.x.to_f32()).round().max(1.0) as u32;
     let pxh = (pixel_per_pt * size.y.to_f32()).round().max(1.0) as u32;
@@ -30,17 +147,20 @@ pub fn render(frame: &Frame, pixel_per_pt: f32, fill: Color) -> sk::Pixmap {
     canvas.fill(fill.into());
 
     let ts = sk::Transform::from_scale(pixel_per_pt, pixel_per_pt);
-    render_frame(&mut canvas, ts, None, frame);
+    render_frame(&mut canvas, ts, None, frame, fast);
 
     canvas
 }
 
 /// Render a frame into the canvas.
+///
+/// If `fast` is true, expensive effects are skipped or approximated.
 fn render_frame(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::Mask>,
     frame: &Frame,
+    fast: bool,
 ) {
     for (pos, item) in frame.items() {
         let x = pos.x.to_f32();
@@ -49,7 +169,7 @@ fn render_frame(
 
         match item {
             FrameItem::Group(group) => {
-                render_group(canvas, ts, mask, group);
+                render_group(canvas, ts, mask, group, fast);
             }
             FrameItem::Text(text) => {
                 render_text(canvas, ts, mask, text);
@@ -58,13 +178,16 @@ fn render_frame(
                 render_shape(canvas, ts, mask, shape);
             }
             FrameItem::Image(image, size, _) => {
-                render_image(canvas, ts, mask, image, *size);
+                render_image(canvas, ts, mask, image, *size, fast);
             }
             FrameItem::Meta(meta, _) => match meta {
                 Meta::Link(_) => {}
                 Meta::Elem(_) => {}
                 Meta::PageNumbering(_) => {}
                 Meta::Hide => {}
+                Meta::Artifact => {}
+                Meta::Break(_) => {}
+                Meta::Region => {}
             },
         }
     }
@@ -76,6 +199,7 @@ fn render_group(
     ts: sk::Transform,
     mask: Option<&sk::Mask>,
     group: &GroupItem,
+    fast: bool,
 ) {
     let ts = ts.pre_concat(group.transform.into());
 
@@ -120,7 +244,166 @@ fn render_group(
         }
     }
 
-    render_frame(canvas, ts, mask, &group.frame);
+    if group.filters.is_empty() || fast {
+        render_frame(canvas, ts, mask, &group.frame, fast);
+    } else {
+        render_filtered(canvas, ts, mask, group);
+    }
+}
+
+/// Render a group into a separate layer, filter it and blend the result with
+/// the canvas.
+///
+/// The layer covers the group's frame and the space blurring spreads it by,
+/// as far as it lies on the canvas. Contents that overflow the frame further
+/// are cut off.
+fn render_filtered(
+    canvas: &mut sk::Pixmap,
+    ts: sk::Transform,
+    mask: Option<&sk::Mask>,
+    group: &GroupItem,
+) -> Option<()> {
+    let pixel_per_pt = ts.sx.hypot(ts.ky);
+    let spread: f32 = group
+        .filters
+        .iter()
+        .map(|filter| match *filter {
+            Filter::Blur(sigma) => 3.0 * (sigma.to_f32() * pixel_per_pt).min(MAX_BLUR),
+            _ => 0.0,
+        })
+        .sum();
+
+    // Determine the layer's bounds on the canvas.
+    let (w, h) = (canvas.width() as f32, canvas.height() as f32);
+    let size = group.frame.size();
+    let (x0, y0, x1, y1) =
+        match sk::Rect::from_xywh(0.0, 0.0, size.x.to_f32(), size.y.to_f32())
+            .map(sk::PathBuilder::from_rect)
+            .and_then(|path| path.transform(ts))
+        {
+            Some(path) => {
+                let bounds = path.bounds();
+                (
+                    (bounds.left() - spread).floor().clamp(0.0, w),
+                    (bounds.top() - spread).floor().clamp(0.0, h),
+                    (bounds.right() + spread).ceil().clamp(0.0, w),
+                    (bounds.bottom() + spread).ceil().clamp(0.0, h),
+                )
+            }
+            None => (0.0, 0.0, w, h),
+        };
+
+    let mut layer = sk::Pixmap::new((x1 - x0) as u32, (y1 - y0) as u32)?;
+    let ts = ts.post_translate(-x0, -y0);
+    render_frame(&mut layer, ts, None, &group.frame, false);
+
+    for filter in &group.filters {
+        match *filter {
+            Filter::Blur(sigma) => blur(&mut layer, sigma.to_f32() * pixel_per_pt),
+            Filter::Grayscale => grayscale(&mut layer),
+            Filter::Brightness(factor) => brighten(&mut layer, factor.get() as f32),
+        }
+    }
+
+    canvas.draw_pixmap(
+        x0 as i32,
+        y0 as i32,
+        layer.as_ref(),
+        &sk::PixmapPaint::default(),
+        sk::Transform::identity(),
+        mask,
+    );
+
+    Some(())
+}
+
+/// The largest blur radius in pixels. Larger radii are clamped to keep
+/// rendering fast.
+pub(super) const MAX_BLUR: f32 = 100.0;
+
+/// Approximate a gaussian blur with the given standard deviation in pixels by
+/// three successive box blurs.
+fn blur(pixmap: &mut sk::Pixmap, sigma: f32) {
+    let sigma = sigma.min(MAX_BLUR);
+    if sigma < 0.1 {
+        return;
+    }
+
+    // Determine the box sizes whose combination best matches the gaussian.
+    let n = 3.0;
+    let ideal = (12.0 * sigma * sigma / n + 1.0).sqrt();
+    let mut lower = ideal.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let lower = lower.max(1);
+    let lower_f = lower as f32;
+    let count =
+        ((12.0 * sigma * sigma - n * lower_f * lower_f - 4.0 * n * lower_f - 3.0 * n)
+            / (-4.0 * lower_f - 4.0))
+            .round() as i32;
+
+    let (w, h) = (pixmap.width() as usize, pixmap.height() as usize);
+    let data = pixmap.data_mut();
+    let mut buf = vec![0; data.len()];
+    for i in 0..3 {
+        let size = if i < count { lower } else { lower + 2 };
+        let radius = (size as usize - 1) / 2;
+        box_blur(data, &mut buf, w, 4, w * 4, h, radius);
+        box_blur(&buf, data, h, w * 4, 4, w, radius);
+    }
+}
+
+/// Blur the premultiplied RGBA pixels in `src` along one axis into `dst`.
+///
+/// There are `lines` lines of `len` pixels each. Consecutive pixels in a line
+/// are `step` bytes apart, while consecutive lines start `stride` bytes
+/// apart. Pixels outside of the pixmap count as transparent.
+fn box_blur(
+    src: &[u8],
+    dst: &mut [u8],
+    len: usize,
+    step: usize,
+    stride: usize,
+    lines: usize,
+    radius: usize,
+) {
+    let div = 2 * radius as u32 + 1;
+    for line in 0..lines {
+        let base = line * stride;
+        for c in 0..4 {
+            let at = |i: usize| src[base + i * step + c] as u32;
+            let mut sum: u32 = (0..=radius.min(len - 1)).map(at).sum();
+            for i in 0..len {
+                dst[base + i * step + c] = ((sum + div / 2) / div) as u8;
+                if i + radius + 1 < len {
+                    sum += at(i + radius + 1);
+                }
+                if i >= radius {
+                    sum -= at(i - radius);
+                }
+            }
+        }
+    }
+}
+
+/// Convert premultiplied RGBA pixels to shades of gray.
+fn grayscale(pixmap: &mut sk::Pixmap) {
+    for px in pixmap.data_mut().chunks_exact_mut(4) {
+        let luma = 0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32;
+        let luma = (luma.round() as u8).min(px[3]);
+        px[..3].fill(luma);
+    }
+}
+
+/// Scale the color channels of premultiplied RGBA pixels by a factor.
+fn brighten(pixmap: &mut sk::Pixmap, factor: f32) {
+    for px in pixmap.data_mut().chunks_exact_mut(4) {
+        let alpha = px[3];
+        for c in &mut px[..3] {
+            *c = (*c as f32 * factor).round().clamp(0.0, alpha as f32) as u8;
+        }
+    }
 }
 
 /// Render a text run into the canvas.
@@ -262,7 +545,7 @@ fn render_bitmap_glyph(
     let dx = (raster.x as f32) / (image.width() as f32) * size;
     let dy = (raster.y as f32) / (image.height() as f32) * size;
     let ts = ts.pre_translate(dx, -size - dy);
-    render_image(canvas, ts, mask, &image, Size::new(w, h))
+    render_image(canvas, ts, mask, &image, Size::new(w, h), false)
 }
 
 /// Render an outline glyph into the canvas. This is the "normal" case.
@@ -296,11 +579,13 @@ fn render_outline_glyph(
         return Some(());
     }
 
-    // Rasterize the glyph with `pixglyph`.
-    // Try to retrieve a prepared glyph or prepare it from scratch if it
-    // doesn't exist, yet.
-    let glyph = pixglyph::Glyph::load(text.font.ttf(), id)?;
-    let bitmap = glyph.rasterize(ts.tx, ts.ty, ppem);
+    // Rasterize the glyph with `pixglyph`. The coverage mask only depends on
+    // the fractional part of the glyph's position, so we split it off and
+    // shift the cached mask by the integer part.
+    let (dx, sx) = split_subpixel(ts.tx);
+    let (dy, sy) = split_subpixel(ts.ty);
+    let size = (ppem * SIZE_STEPS).round() as u16;
+    let bitmap = rasterize_glyph(&text.font, id.0, size, sx, sy)?;
 
     // If we have a clip mask we first render to a pixmap that we then blend
     // with our canvas
@@ -322,8 +607,8 @@ fn render_outline_glyph(
             }
         }
 
-        let left = bitmap.left;
-        let top = bitmap.top;
+        let left = bitmap.left + dx;
+        let top = bitmap.top + dy;
 
         canvas.draw_pixmap(
             left - 1,
@@ -342,9 +627,9 @@ fn render_outline_glyph(
         let mh = bitmap.height as i32;
 
         // Determine the pixel bounding box that we actually need to draw.
-        let left = bitmap.left;
+        let left = bitmap.left + dx;
         let right = left + mw;
-        let top = bitmap.top;
+        let top = bitmap.top + dy;
         let bottom = top + mh;
 
         // Premultiply the text color.
@@ -377,6 +662,59 @@ fn render_outline_glyph(
     }
 }
 
+/// Into how many steps a pixel is divided for positioning cached glyphs.
+const SUBPIXEL_STEPS: f32 = 4.0;
+
+/// Into how many steps a pixel is divided for sizing cached glyphs.
+const SIZE_STEPS: f32 = 16.0;
+
+/// The key of a rasterized glyph: its font, id, size in size steps and
+/// subpixel offsets.
+type GlyphKey = (Font, u16, u16, u8, u8);
+
+/// Glyphs that were already rasterized.
+static GLYPHS: Lazy<Lru<GlyphKey, Option<Arc<pixglyph::Bitmap>>>> =
+    Lazy::new(|| Lru::new(GLYPH_CACHE, 16 * 1024 * 1024));
+
+/// The name of the cache of rasterized glyphs.
+pub(super) const GLYPH_CACHE: &str = "glyphs";
+
+/// Split a pixel coordinate into its integer part and its fractional part in
+/// subpixel steps.
+fn split_subpixel(v: f32) -> (i32, u8) {
+    let steps = (v * SUBPIXEL_STEPS).round();
+    let whole = (steps / SUBPIXEL_STEPS).floor();
+    (whole as i32, (steps - whole * SUBPIXEL_STEPS) as u8)
+}
+
+/// Rasterize a glyph outline into a coverage mask.
+///
+/// Text-heavy pages contain the same glyphs at the same sizes over and over
+/// again, so the masks are kept in a size-bounded cache. Sizes and offsets
+/// are rounded to steps that are too small to see, so that glyphs whose
+/// positions differ by floating point noise share an entry.
+fn rasterize_glyph(
+    font: &Font,
+    id: u16,
+    size: u16,
+    x: u8,
+    y: u8,
+) -> Option<Arc<pixglyph::Bitmap>> {
+    GLYPHS.get_or_insert(
+        (font.clone(), id, size, x, y),
+        || {
+            let glyph = pixglyph::Glyph::load(font.ttf(), GlyphId(id))?;
+            let bitmap = glyph.rasterize(
+                x as f32 / SUBPIXEL_STEPS,
+                y as f32 / SUBPIXEL_STEPS,
+                size as f32 / SIZE_STEPS,
+            );
+            Some(Arc::new(bitmap))
+        },
+        |bitmap| bitmap.as_ref().map_or(0, |bitmap| bitmap.coverage.len()),
+    )
+}
+
 /// Render a geometrical shape into the canvas.
 fn render_shape(
     canvas: &mut sk::Pixmap,
@@ -484,6 +822,7 @@ fn render_image(
     mask: Option<&sk::Mask>,
     image: &Image,
     size: Size,
+    fast: bool,
 ) -> Option<()> {
     let view_width = size.x.to_f32();
     let view_height = size.y.to_f32();
@@ -493,7 +832,7 @@ fn render_image(
     let w = (scale * view_width.max(aspect * view_height)).ceil() as u32;
     let h = ((w as f32) / aspect).ceil() as u32;
 
-    let pixmap = scaled_texture(image, w, h)?;
+    let pixmap = scaled_texture(image, w, h, fast)?;
     let scale_x = view_width / pixmap.width() as f32;
     let scale_y = view_height / pixmap.height() as f32;
 
@@ -515,14 +854,21 @@ fn render_image(
 }
 
 /// Prepare a texture for an image at a scaled size.
+///
+/// If `fast` is true, a cheaper scaling algorithm is used.
 #[comemo::memoize]
-fn scaled_texture(image: &Image, w: u32, h: u32) -> Option<Arc<sk::Pixmap>> {
+fn scaled_texture(image: &Image, w: u32, h: u32, fast: bool) -> Option<Arc<sk::Pixmap>> {
     let mut pixmap = sk::Pixmap::new(w, h)?;
-    match image.decoded().as_ref() {
+    match image.decoded().ok()?.as_ref() {
         DecodedImage::Raster(dynamic, _, _) => {
             let downscale = w < image.width();
-            let filter =
-                if downscale { FilterType::Lanczos3 } else { FilterType::CatmullRom };
+            let filter = if fast {
+                FilterType::Triangle
+            } else if downscale {
+                FilterType::Lanczos3
+            } else {
+                FilterType::CatmullRom
+            };
             let buf = dynamic.resize(w, h, filter);
             for ((_, _, src), dest) in buf.pixels().zip(pixmap.pixels_mut()) {
                 let Rgba([r, g, b, a]) = src;
@@ -645,3 +991,113 @@ fn alpha_mul(color: u32, scale: u32) -> u32 {
     let ag = ((color >> 8) & mask) * scale;
     (rb & mask) | (ag & !mask)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::Span;
+    use crate::util::Buffer;
+
+    #[test]
+    fn test_visible_pages() {
+        let page = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(100.0)));
+        let document = Document { pages: vec![page; 4], ..Default::default() };
+        let visible = |start, end| {
+            visible_pages(&document, Abs::pt(start)..Abs::pt(end), Abs::pt(10.0))
+        };
+        assert_eq!(visible(0.0, 50.0), 0..1);
+        assert_eq!(visible(50.0, 150.0), 0..2);
+        assert_eq!(visible(100.0, 110.0), 0..0);
+        assert_eq!(visible(200.0, 1000.0), 1..4);
+        assert_eq!(visible(1000.0, 2000.0), 0..0);
+    }
+
+    #[test]
+    fn test_page_cache() {
+        let small = Frame::new(Size::new(Abs::pt(10.0), Abs::pt(10.0)));
+        let large = Frame::new(Size::new(Abs::pt(20.0), Abs::pt(20.0)));
+        let mut cache = PageCache::new(1.0, Color::WHITE);
+
+        let pages = vec![small.clone(); 3];
+        let document = Document { pages, ..Default::default() };
+        let rendered = cache.render(&document, 1..5);
+        let indices: Vec<_> = rendered.iter().map(|&(i, _)| i).collect();
+        assert_eq!(indices, [1, 2]);
+
+        // Pages whose frames changed are rendered again.
+        let document = Document { pages: vec![small, large], ..Default::default() };
+        let rendered = cache.render(&document, 0..2);
+        let widths: Vec<_> = rendered.iter().map(|(_, pixmap)| pixmap.width()).collect();
+        assert_eq!(widths, [10, 20]);
+    }
+
+    #[test]
+    fn test_render_filtered_group() {
+        let size = Size::splat(Abs::pt(10.0));
+        let red = Color::Rgba(geom::RgbaColor::new(0xff, 0x00, 0x00, 0xff));
+        let rect = Geometry::Rect(size).filled(red.into());
+        let mut group = Frame::new(size);
+        group.push(geom::Point::zero(), FrameItem::Shape(rect, Span::detached()));
+        group.filter(vec![Filter::Blur(Abs::pt(2.0))]);
+        let mut page = Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push_frame(geom::Point::splat(Abs::pt(45.0)), group);
+
+        // The center of the group keeps its color, the blur spreads it beyond
+        // the group's frame and the rest of the page stays untouched.
+        let pixmap = render(&page, 1.0, Color::WHITE);
+        let green = |x, y| pixmap.pixel(x, y).unwrap().green();
+        assert!(green(50, 50) < 100);
+        assert!((100..250).contains(&green(43, 50)));
+        assert_eq!(green(10, 10), 0xff);
+        assert_eq!(green(50, 90), 0xff);
+    }
+
+    #[test]
+    fn test_render_color_filters() {
+        let size = Size::splat(Abs::pt(10.0));
+        let red = Color::Rgba(geom::RgbaColor::new(0xff, 0x00, 0x00, 0xff));
+        let rect = Geometry::Rect(size).filled(red.into());
+        let center = |filters: Vec<Filter>| {
+            let mut page = Frame::new(size);
+            let item = FrameItem::Shape(rect.clone(), Span::detached());
+            page.push(geom::Point::zero(), item);
+            page.filter(filters);
+            let pixel = render(&page, 1.0, Color::WHITE).pixel(5, 5).unwrap();
+            (pixel.red(), pixel.green(), pixel.blue())
+        };
+
+        // Filters are applied in order.
+        let half = Filter::Brightness(geom::Ratio::new(0.5));
+        assert_eq!(center(vec![]), (255, 0, 0));
+        assert_eq!(center(vec![Filter::Grayscale]), (54, 54, 54));
+        assert_eq!(center(vec![half]), (128, 0, 0));
+        assert_eq!(center(vec![Filter::Grayscale, half]), (27, 27, 27));
+    }
+
+    #[test]
+    fn test_split_subpixel() {
+        assert_eq!(split_subpixel(3.0), (3, 0));
+        assert_eq!(split_subpixel(3.26), (3, 1));
+        assert_eq!(split_subpixel(3.5), (3, 2));
+        assert_eq!(split_subpixel(3.99), (4, 0));
+        assert_eq!(split_subpixel(-0.3), (-1, 3));
+    }
+
+    #[test]
+    fn test_rasterize_glyph_cached() {
+        let data = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(Buffer::from_static(data), 0).unwrap();
+        let id = font.ttf().glyph_index('g').unwrap().0;
+
+        // Positions that differ by less than a subpixel step share a mask.
+        let (_, x1) = split_subpixel(10.26);
+        let (_, x2) = split_subpixel(10.24);
+        let first = rasterize_glyph(&font, id, 160, x1, 0).unwrap();
+        let second = rasterize_glyph(&font, id, 160, x2, 0).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Different offsets don't.
+        let other = rasterize_glyph(&font, id, 160, 2, 0).unwrap();
+        assert!(!Arc::ptr_eq(&first, &other));
+    }
+}
@@ -0,0 +1,554 @@
+//! A serializable representation of laid-out documents.
+//!
+//! The types in this module mirror the frames of a [`Document`](doc::Document)
+//! in a self-contained form that only consists of plain data. They implement
+//! `serde`'s traits, so they can be written in any format `serde` supports and
+//! drawn by a separate renderer process or a web frontend without linking the
+//! compiler.
+//!
+//! All lengths are given in points and all coordinates are relative to the
+//! top left corner of the containing frame. Colors are given as hexadecimal
+//! RGBA strings, like `#ff4136ff`. Fonts and images are stored once in tables
+//! at the document level and referenced by index.
+//!
+//! The representation is versioned by [`VERSION`]. Changes that would break
+//! existing readers bump the version.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::doc::{self, Destination, FrameItem, Meta};
+use crate::font::Font;
+use crate::geom::{self, Geometry, LineCap, LineJoin, Paint, PathItem};
+use crate::image::{ImageFormat, RasterFormat, VectorFormat};
+
+/// The version of the serialized representation.
+pub const VERSION: u32 = 2;
+
+/// Convert a document into its serializable representation.
+pub fn serialize(document: &doc::Document) -> Document {
+    let mut tables = Tables::default();
+    let pages = document.pages.iter().map(|page| tables.frame(page)).collect();
+    Document {
+        version: VERSION,
+        title: document.title.as_ref().map(ToString::to_string),
+        fonts: tables.fonts,
+        images: tables.images,
+        pages,
+    }
+}
+
+/// A laid-out document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    /// The version of the representation, see [`VERSION`].
+    pub version: u32,
+    /// The document's title.
+    pub title: Option<String>,
+    /// The fonts used by the text runs.
+    pub fonts: Vec<FontData>,
+    /// The images placed in the document.
+    pub images: Vec<ImageData>,
+    /// The page frames.
+    pub pages: Vec<Frame>,
+}
+
+/// A font file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FontData {
+    /// The font's family name.
+    pub family: String,
+    /// The index of the font in its collection file.
+    pub index: u32,
+    /// The raw font file.
+    pub data: Vec<u8>,
+}
+
+/// An image file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageData {
+    /// The image's format: `png`, `jpg`, `gif` or `svg`.
+    pub format: String,
+    /// The image's width in pixels.
+    pub width: u32,
+    /// The image's height in pixels.
+    pub height: u32,
+    /// The raw image file.
+    pub data: Vec<u8>,
+}
+
+/// A frame with items at fixed positions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// The frame's width.
+    pub width: f64,
+    /// The frame's height.
+    pub height: f64,
+    /// The items in the frame, from bottom to top.
+    pub items: Vec<Item>,
+}
+
+/// An item in a frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Item {
+    /// A nested frame.
+    Group {
+        x: f64,
+        y: f64,
+        /// The transformation as `[sx, ky, kx, sy, tx, ty]`.
+        transform: [f64; 6],
+        /// Whether the group's contents are clipped to its frame.
+        clips: bool,
+        /// Raster filters to apply to the group.
+        filters: Vec<Filter>,
+        frame: Frame,
+    },
+    /// A run of glyphs, positioned at its baseline.
+    Text {
+        x: f64,
+        y: f64,
+        /// The index of the font in the document's font table.
+        font: usize,
+        size: f64,
+        fill: String,
+        /// The run's plain text.
+        text: String,
+        glyphs: Vec<Glyph>,
+    },
+    /// A geometric shape.
+    Shape {
+        x: f64,
+        y: f64,
+        geometry: Shape,
+        fill: Option<String>,
+        stroke: Option<Stroke>,
+    },
+    /// An image.
+    Image {
+        x: f64,
+        y: f64,
+        /// The index of the image in the document's image table.
+        image: usize,
+        width: f64,
+        height: f64,
+    },
+    /// A link area.
+    Link { x: f64, y: f64, width: f64, height: f64, target: Link },
+}
+
+/// A glyph in a text run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Glyph {
+    /// The glyph's index in the font.
+    pub id: u16,
+    /// The advance width of the glyph, in units of the font size.
+    pub x_advance: f64,
+    /// The horizontal offset of the glyph, in units of the font size.
+    pub x_offset: f64,
+    /// The range of the glyph in the run's text, in bytes.
+    pub start: u16,
+    pub end: u16,
+}
+
+/// The geometry of a shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Shape {
+    /// A line to a point, relative to the shape's position.
+    Line { x: f64, y: f64 },
+    /// A rectangle with its origin in the top left corner.
+    Rect { width: f64, height: f64 },
+    /// A bezier path.
+    Path { items: Vec<PathSegment> },
+}
+
+/// A segment of a bezier path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum PathSegment {
+    MoveTo { x: f64, y: f64 },
+    LineTo { x: f64, y: f64 },
+    CubicTo { x1: f64, y1: f64, x2: f64, y2: f64, x: f64, y: f64 },
+    ClosePath,
+}
+
+/// How a shape is stroked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stroke {
+    pub paint: String,
+    pub thickness: f64,
+    /// One of `butt`, `round` and `square`.
+    pub line_cap: String,
+    /// One of `miter`, `round` and `bevel`.
+    pub line_join: String,
+    /// The lengths of alternating dashes and gaps, if the stroke is dashed.
+    pub dash_array: Option<Vec<f64>>,
+    /// How far into the dash array the stroke starts.
+    pub dash_phase: f64,
+    pub miter_limit: f64,
+}
+
+/// A raster filter applied to a group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Filter {
+    /// A gaussian blur with the given standard deviation.
+    Blur {
+        sigma: f64,
+    },
+    Grayscale,
+    /// Scale the brightness by a factor.
+    Brightness {
+        factor: f64,
+    },
+}
+
+/// The target of a link.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Link {
+    /// A URL.
+    Url { url: String },
+    /// A point on a page. Pages start at 1.
+    Position { page: usize, x: f64, y: f64 },
+    /// A page or named destination in another PDF file.
+    File { file: String, page: Option<usize>, name: Option<String> },
+}
+
+/// Collects the fonts and images of a document while its frames are
+/// converted.
+#[derive(Default)]
+struct Tables {
+    fonts: Vec<FontData>,
+    font_indices: HashMap<Font, usize>,
+    images: Vec<ImageData>,
+    image_indices: HashMap<crate::image::Image, usize>,
+}
+
+impl Tables {
+    fn frame(&mut self, frame: &doc::Frame) -> Frame {
+        let mut items = vec![];
+        for (pos, item) in frame.items() {
+            let (x, y) = (pos.x.to_pt(), pos.y.to_pt());
+            items.push(match item {
+                FrameItem::Group(group) => {
+                    let geom::Transform { sx, ky, kx, sy, tx, ty } = group.transform;
+                    Item::Group {
+                        x,
+                        y,
+                        transform: [
+                            sx.get(),
+                            ky.get(),
+                            kx.get(),
+                            sy.get(),
+                            tx.to_pt(),
+                            ty.to_pt(),
+                        ],
+                        clips: group.clips,
+                        filters: group.filters.iter().map(filter).collect(),
+                        frame: self.frame(&group.frame),
+                    }
+                }
+                FrameItem::Text(text) => Item::Text {
+                    x,
+                    y,
+                    font: self.font(&text.font),
+                    size: text.size.to_pt(),
+                    fill: paint(&text.fill),
+                    text: text.text.to_string(),
+                    glyphs: text
+                        .glyphs
+                        .iter()
+                        .map(|glyph| Glyph {
+                            id: glyph.id,
+                            x_advance: glyph.x_advance.get(),
+                            x_offset: glyph.x_offset.get(),
+                            start: glyph.range.start,
+                            end: glyph.range.end,
+                        })
+                        .collect(),
+                },
+                FrameItem::Shape(shape, _) => Item::Shape {
+                    x,
+                    y,
+                    geometry: geometry(&shape.geometry),
+                    fill: shape.fill.as_ref().map(paint),
+                    stroke: shape.stroke.as_ref().map(stroke),
+                },
+                FrameItem::Image(image, size, _) => Item::Image {
+                    x,
+                    y,
+                    image: self.image(image),
+                    width: size.x.to_pt(),
+                    height: size.y.to_pt(),
+                },
+                FrameItem::Meta(Meta::Link(dest), size) => {
+                    let target = match dest {
+                        Destination::Url(url) => Link::Url { url: url.to_string() },
+                        Destination::Position(position) => Link::Position {
+                            page: position.page.get(),
+                            x: position.point.x.to_pt(),
+                            y: position.point.y.to_pt(),
+                        },
+                        Destination::Remote(remote) => {
+                            let (page, name) = match &remote.target {
+                                doc::RemoteTarget::Page(page) => (Some(page.get()), None),
+                                doc::RemoteTarget::Name(name) => {
+                                    (None, Some(name.to_string()))
+                                }
+                            };
+                            Link::File { file: remote.file.to_string(), page, name }
+                        }
+                        // Unresolved links don't lead anywhere.
+                        Destination::Location(_) => continue,
+                    };
+                    Item::Link {
+                        x,
+                        y,
+                        width: size.x.to_pt(),
+                        height: size.y.to_pt(),
+                        target,
+                    }
+                }
+                FrameItem::Meta(_, _) => continue,
+            });
+        }
+
+        Frame {
+            width: frame.width().to_pt(),
+            height: frame.height().to_pt(),
+            items,
+        }
+    }
+
+    fn font(&mut self, font: &Font) -> usize {
+        *self.font_indices.entry(font.clone()).or_insert_with(|| {
+            self.fonts.push(FontData {
+                family: font.info().family.clone(),
+                index: font.index(),
+                data: font.data().to_vec(),
+            });
+            self.fonts.len() - 1
+        })
+    }
+
+    fn image(&mut self, image: &crate::image::Image) -> usize {
+        *self.image_indices.entry(image.clone()).or_insert_with(|| {
+            let format = match image.format() {
+                ImageFormat::Raster(RasterFormat::Png) => "png",
+                ImageFormat::Raster(RasterFormat::Jpg) => "jpg",
+                ImageFormat::Raster(RasterFormat::Gif) => "gif",
+                ImageFormat::Vector(VectorFormat::Svg) => "svg",
+            };
+            self.images.push(ImageData {
+                format: format.into(),
+                width: image.width(),
+                height: image.height(),
+                data: image.data().to_vec(),
+            });
+            self.images.len() - 1
+        })
+    }
+}
+
+fn geometry(geometry: &Geometry) -> Shape {
+    match geometry {
+        Geometry::Line(point) => Shape::Line { x: point.x.to_pt(), y: point.y.to_pt() },
+        Geometry::Rect(size) => {
+            Shape::Rect { width: size.x.to_pt(), height: size.y.to_pt() }
+        }
+        Geometry::Path(path) => Shape::Path {
+            items: path
+                .0
+                .iter()
+                .map(|item| match *item {
+                    PathItem::MoveTo(p) => {
+                        PathSegment::MoveTo { x: p.x.to_pt(), y: p.y.to_pt() }
+                    }
+                    PathItem::LineTo(p) => {
+                        PathSegment::LineTo { x: p.x.to_pt(), y: p.y.to_pt() }
+                    }
+                    PathItem::CubicTo(a, b, p) => PathSegment::CubicTo {
+                        x1: a.x.to_pt(),
+                        y1: a.y.to_pt(),
+                        x2: b.x.to_pt(),
+                        y2: b.y.to_pt(),
+                        x: p.x.to_pt(),
+                        y: p.y.to_pt(),
+                    },
+                    PathItem::ClosePath => PathSegment::ClosePath,
+                })
+                .collect(),
+        },
+    }
+}
+
+fn stroke(stroke: &geom::Stroke) -> Stroke {
+    Stroke {
+        paint: paint(&stroke.paint),
+        thickness: stroke.thickness.to_pt(),
+        line_cap: match stroke.line_cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
+        .into(),
+        line_join: match stroke.line_join {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        }
+        .into(),
+        dash_array: stroke
+            .dash_pattern
+            .as_ref()
+            .map(|pattern| pattern.array.iter().map(|l| l.to_pt()).collect()),
+        dash_phase: stroke.dash_pattern.as_ref().map_or(0.0, |p| p.phase.to_pt()),
+        miter_limit: stroke.miter_limit.0,
+    }
+}
+
+fn filter(filter: &doc::Filter) -> Filter {
+    match *filter {
+        doc::Filter::Blur(sigma) => Filter::Blur { sigma: sigma.to_pt() },
+        doc::Filter::Grayscale => Filter::Grayscale,
+        doc::Filter::Brightness(factor) => Filter::Brightness { factor: factor.get() },
+    }
+}
+
+fn paint(paint: &Paint) -> String {
+    let Paint::Solid(color) = paint;
+    let c = color.to_rgba();
+    format!("#{:02x}{:02x}{:02x}{:02x}", c.r, c.g, c.b, c.a)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::doc::{GroupItem, Lang, Position, TextItem};
+    use crate::geom::{Abs, Angle, Color, Em, Point, Size, Transform};
+    use crate::image::Image;
+    use crate::syntax::Span;
+    use crate::util::Buffer;
+
+    fn text(font: &Font, text: &str) -> FrameItem {
+        let glyphs = text
+            .char_indices()
+            .map(|(i, c)| {
+                let id = font.ttf().glyph_index(c).unwrap().0;
+                doc::Glyph {
+                    id,
+                    x_advance: font.advance(id).unwrap(),
+                    x_offset: Em::zero(),
+                    range: i as u16..i as u16 + 1,
+                    span: (Span::detached(), 0),
+                }
+            })
+            .collect();
+
+        FrameItem::Text(TextItem {
+            font: font.clone(),
+            size: Abs::pt(10.0),
+            fill: Color::BLACK.into(),
+            lang: Lang::ENGLISH,
+            region: None,
+            text: text.into(),
+            glyphs,
+        })
+    }
+
+    fn document() -> doc::Document {
+        let data = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+        let font = Font::new(Buffer::from_static(data), 0).unwrap();
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="2"/>"#;
+        let format = ImageFormat::Vector(VectorFormat::Svg);
+        let image = Image::new(Buffer::from_static(svg), format, None).unwrap();
+        let size = Size::new(Abs::pt(20.0), Abs::pt(10.0));
+
+        let mut inner = doc::Frame::new(size);
+        let mut path = geom::Path::new();
+        path.move_to(Point::zero());
+        path.line_to(Point::with_x(Abs::pt(5.0)));
+        path.close_path();
+        let stroke = geom::Stroke { thickness: Abs::pt(2.0), ..Default::default() };
+        inner.push(
+            Point::zero(),
+            FrameItem::Shape(Geometry::Path(path).stroked(stroke), Span::detached()),
+        );
+        inner
+            .push(Point::zero(), FrameItem::Image(image.clone(), size, Span::detached()));
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::rotate(Angle::deg(30.0));
+        group.clips = true;
+
+        let mut page = doc::Frame::new(Size::splat(Abs::pt(100.0)));
+        page.push(Point::splat(Abs::pt(10.0)), text(&font, "Hello"));
+        page.push(Point::splat(Abs::pt(20.0)), text(&font, "world"));
+        page.push(Point::zero(), FrameItem::Group(group));
+        page.push(Point::zero(), FrameItem::Image(image, size, Span::detached()));
+        let dest = Destination::Position(Position {
+            page: NonZeroUsize::new(1).unwrap(),
+            point: Point::with_y(Abs::pt(5.0)),
+        });
+        page.push(Point::zero(), FrameItem::Meta(Meta::Link(dest), size));
+
+        let mut document = doc::Document::default();
+        document.title = Some("Round trip".into());
+        document.pages = vec![page.clone(), page];
+        document
+    }
+
+    #[test]
+    fn test_serialize_shares_fonts_and_images() {
+        let serialized = serialize(&document());
+        assert_eq!(serialized.version, VERSION);
+        assert_eq!(serialized.fonts.len(), 1);
+        assert_eq!(serialized.fonts[0].family, "Linux Libertine");
+        assert_eq!(serialized.images.len(), 1);
+        assert_eq!(serialized.images[0].format, "svg");
+        assert_eq!(serialized.pages.len(), 2);
+
+        let items = &serialized.pages[0].items;
+        assert_eq!(items.len(), 5);
+        assert!(matches!(
+            &items[1],
+            Item::Text { x, font: 0, text, glyphs, .. }
+                if *x == 20.0 && text == "world" && glyphs.len() == 5
+        ));
+        let Item::Group { transform, clips, frame, .. } = &items[2] else {
+            panic!("expected group");
+        };
+        assert!(*clips);
+        assert!((transform[0] - 30f64.to_radians().cos()).abs() < 1e-9);
+        assert!(matches!(frame.items[1], Item::Image { image: 0, .. }));
+        assert!(matches!(
+            &frame.items[0],
+            Item::Shape { fill: None, stroke: Some(stroke), .. }
+                if stroke.thickness == 2.0 && stroke.paint == "#000000ff"
+        ));
+        assert_eq!(
+            items[4],
+            Item::Link {
+                x: 0.0,
+                y: 0.0,
+                width: 20.0,
+                height: 10.0,
+                target: Link::Position { page: 1, x: 0.0, y: 5.0 },
+            }
+        );
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let serialized = serialize(&document());
+        let json = serde_json::to_string(&serialized).unwrap();
+        assert!(json.contains(r#""kind":"text""#));
+        assert!(json.contains(r#""kind":"move-to""#));
+        let deserialized: Document = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, serialized);
+    }
+}
@@ -0,0 +1,578 @@
+//! Exporting into SVG.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use ttf_parser::{GlyphId, OutlineBuilder};
+
+use crate::doc::{Destination, Filter, Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::export::svg_glyph;
+use crate::font::Font;
+use crate::geom::{Abs, Geometry, LineCap, LineJoin, Paint, PathItem, Shape, Size};
+use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+
+/// Export a frame into an SVG image.
+///
+/// Glyphs are embedded as paths, so the result doesn't depend on any fonts
+/// being installed. Glyphs that come with an SVG document, like those of color
+/// emoji fonts, are embedded as images instead. Images are embedded as data
+/// URLs.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn svg(frame: &Frame) -> String {
+    svg_numbered(frame, &mut 0)
+}
+
+/// Export a frame into an SVG image, numbering its definitions after the
+/// given number of definitions that were already written.
+///
+/// Inline SVG images in one HTML document share their ids, so each page
+/// continues the numbering of the previous one.
+pub(super) fn svg_numbered(frame: &Frame, ids: &mut usize) -> String {
+    let mut writer = SvgWriter { ids: *ids, ..Default::default() };
+    let mut body = String::new();
+    writer.frame(&mut body, frame);
+    *ids = writer.ids;
+
+    let size = frame.size();
+    let mut svg = String::new();
+    write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 {w} {h}" width="{w}pt" height="{h}pt">"#,
+        w = num(size.x.to_pt()),
+        h = num(size.y.to_pt()),
+    )
+    .unwrap();
+    if !writer.defs.is_empty() {
+        write!(svg, "<defs>{}</defs>", writer.defs).unwrap();
+    }
+    svg.push_str(&body);
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Writes the contents of frames, collecting shared definitions.
+#[derive(Default)]
+struct SvgWriter {
+    /// Glyph outlines, clip paths and filters referenced by the body.
+    defs: String,
+    /// The definitions of glyphs that were already defined or `None` for
+    /// glyphs without an outline or SVG document.
+    glyphs: HashMap<(Font, u16), Option<GlyphDef>>,
+    /// The number of definitions so far.
+    ids: usize,
+}
+
+impl SvgWriter {
+    /// Allocate an id for a definition.
+    fn id(&mut self) -> usize {
+        self.ids += 1;
+        self.ids
+    }
+
+    fn frame(&mut self, out: &mut String, frame: &Frame) {
+        for (pos, item) in frame.items() {
+            let (x, y) = (pos.x.to_pt(), pos.y.to_pt());
+            let translate = if x == 0.0 && y == 0.0 {
+                String::new()
+            } else {
+                format!(r#" transform="translate({} {})""#, num(x), num(y))
+            };
+
+            match item {
+                FrameItem::Group(group) => self.group(out, &translate, group),
+                FrameItem::Text(text) => self.text(out, &translate, text),
+                FrameItem::Shape(shape, _) => {
+                    write!(out, "<path{translate}").unwrap();
+                    shape_attrs(out, shape);
+                    out.push_str("/>");
+                }
+                FrameItem::Image(image, size, _) => {
+                    image_elem(out, &translate, image, *size);
+                }
+                FrameItem::Meta(Meta::Link(Destination::Url(url)), size) => {
+                    write!(
+                        out,
+                        r#"<a xlink:href="{}"><rect{translate} width="{}" height="{}" fill="transparent"/></a>"#,
+                        escape(url),
+                        num(size.x.to_pt()),
+                        num(size.y.to_pt()),
+                    )
+                    .unwrap();
+                }
+                FrameItem::Meta(_, _) => {}
+            }
+        }
+    }
+
+    fn group(&mut self, out: &mut String, translate: &str, group: &GroupItem) {
+        write!(out, "<g{translate}>").unwrap();
+
+        let mut closing = 0;
+        let ts = group.transform;
+        if !ts.is_identity() {
+            write!(
+                out,
+                r#"<g transform="matrix({} {} {} {} {} {})">"#,
+                num(ts.sx.get()),
+                num(ts.ky.get()),
+                num(ts.kx.get()),
+                num(ts.sy.get()),
+                num(ts.tx.to_pt()),
+                num(ts.ty.to_pt()),
+            )
+            .unwrap();
+            closing += 1;
+        }
+
+        if group.clips {
+            let id = self.id();
+            let size = group.frame.size();
+            write!(
+                self.defs,
+                r#"<clipPath id="c{id}"><rect width="{}" height="{}"/></clipPath>"#,
+                num(size.x.to_pt()),
+                num(size.y.to_pt()),
+            )
+            .unwrap();
+            write!(out, r#"<g clip-path="url(#c{id})">"#).unwrap();
+            closing += 1;
+        }
+
+        if !group.filters.is_empty() {
+            let id = self.id();
+            write!(
+                self.defs,
+                r#"<filter id="f{id}" x="-50%" y="-50%" width="200%" height="200%" color-interpolation-filters="sRGB">"#
+            )
+            .unwrap();
+            for filter in &group.filters {
+                match *filter {
+                    Filter::Blur(sigma) => write!(
+                        self.defs,
+                        r#"<feGaussianBlur stdDeviation="{}"/>"#,
+                        num(sigma.to_pt())
+                    )
+                    .unwrap(),
+                    Filter::Grayscale => self
+                        .defs
+                        .push_str(r#"<feColorMatrix type="saturate" values="0"/>"#),
+                    Filter::Brightness(factor) => {
+                        let slope = num(factor.get());
+                        write!(
+                            self.defs,
+                            r#"<feComponentTransfer><feFuncR type="linear" slope="{slope}"/><feFuncG type="linear" slope="{slope}"/><feFuncB type="linear" slope="{slope}"/></feComponentTransfer>"#
+                        )
+                        .unwrap()
+                    }
+                }
+            }
+            self.defs.push_str("</filter>");
+            write!(out, r#"<g filter="url(#f{id})">"#).unwrap();
+            closing += 1;
+        }
+
+        self.frame(out, &group.frame);
+        for _ in 0..closing {
+            out.push_str("</g>");
+        }
+        out.push_str("</g>");
+    }
+
+    fn text(&mut self, out: &mut String, translate: &str, text: &TextItem) {
+        write!(out, "<g{translate}").unwrap();
+        paint_attrs(out, "fill", &text.fill);
+        out.push('>');
+
+        let scale = text.size.to_pt() / text.font.units_per_em();
+        let mut x = Abs::zero();
+        for glyph in &text.glyphs {
+            let offset = x + glyph.x_offset.at(text.size);
+            x += glyph.x_advance.at(text.size);
+            let Some(def) = self.glyph(&text.font, glyph.id) else { continue };
+            let (id, flip) = match def {
+                GlyphDef::Outline(id) => (id, -scale),
+                GlyphDef::Image(id) => (id, scale),
+            };
+            write!(
+                out,
+                r##"<use xlink:href="#g{id}" transform="translate({} 0) scale({} {})"/>"##,
+                num(offset.to_pt()),
+                num(scale),
+                num(flip),
+            )
+            .unwrap();
+        }
+
+        out.push_str("</g>");
+    }
+
+    /// A glyph's definition, preferring its SVG document over its outline.
+    fn glyph(&mut self, font: &Font, glyph: u16) -> Option<GlyphDef> {
+        if let Some(&def) = self.glyphs.get(&(font.clone(), glyph)) {
+            return def;
+        }
+
+        let def = if let Some(image) = svg_glyph(font, GlyphId(glyph)) {
+            // The image spans the em square and the font's ascender to
+            // descender, in font units.
+            let id = self.id();
+            let upem = font.units_per_em();
+            let metrics = font.metrics();
+            let attrs =
+                format!(r#" id="g{id}" y="{}""#, num(-metrics.ascender.get() * upem));
+            let size = Size::new(
+                Abs::pt(upem),
+                Abs::pt((metrics.ascender - metrics.descender).get() * upem),
+            );
+            image_elem(&mut self.defs, &attrs, &image, size);
+            Some(GlyphDef::Image(id))
+        } else {
+            let mut builder = SvgPathBuilder(String::new());
+            let id = font
+                .ttf()
+                .outline_glyph(GlyphId(glyph), &mut builder)
+                .map(|_| self.id());
+            if let Some(id) = id {
+                write!(self.defs, r#"<path id="g{id}" d="{}"/>"#, builder.0.trim_end())
+                    .unwrap();
+            }
+            id.map(GlyphDef::Outline)
+        };
+
+        self.glyphs.insert((font.clone(), glyph), def);
+        def
+    }
+}
+
+/// The definition of a glyph.
+#[derive(Copy, Clone)]
+enum GlyphDef {
+    /// An outline path in font units, whose y-axis points up.
+    Outline(usize),
+    /// An image in font units, whose y-axis points down.
+    Image(usize),
+}
+
+/// Write the geometry, fill and stroke attributes of a shape.
+fn shape_attrs(out: &mut String, shape: &Shape) {
+    out.push_str(r#" d=""#);
+    match &shape.geometry {
+        Geometry::Line(target) => {
+            write!(out, "M0 0L{} {}", num(target.x.to_pt()), num(target.y.to_pt()))
+                .unwrap();
+        }
+        Geometry::Rect(size) => {
+            let (w, h) = (num(size.x.to_pt()), num(size.y.to_pt()));
+            write!(out, "M0 0H{w}V{h}H0Z").unwrap();
+        }
+        Geometry::Path(path) => {
+            for item in &path.0 {
+                match item {
+                    PathItem::MoveTo(p) => {
+                        write!(out, "M{} {}", num(p.x.to_pt()), num(p.y.to_pt())).unwrap()
+                    }
+                    PathItem::LineTo(p) => {
+                        write!(out, "L{} {}", num(p.x.to_pt()), num(p.y.to_pt())).unwrap()
+                    }
+                    PathItem::CubicTo(a, b, p) => write!(
+                        out,
+                        "C{} {} {} {} {} {}",
+                        num(a.x.to_pt()),
+                        num(a.y.to_pt()),
+                        num(b.x.to_pt()),
+                        num(b.y.to_pt()),
+                        num(p.x.to_pt()),
+                        num(p.y.to_pt()),
+                    )
+                    .unwrap(),
+                    PathItem::ClosePath => out.push('Z'),
+                }
+            }
+        }
+    }
+    out.push('"');
+
+    match &shape.fill {
+        Some(fill) => paint_attrs(out, "fill", fill),
+        None => out.push_str(r#" fill="none""#),
+    }
+
+    if let Some(stroke) = &shape.stroke {
+        paint_attrs(out, "stroke", &stroke.paint);
+        write!(out, r#" stroke-width="{}""#, num(stroke.thickness.to_pt())).unwrap();
+        let cap = match stroke.line_cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        };
+        let join = match stroke.line_join {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        };
+        write!(out, r#" stroke-linecap="{cap}" stroke-linejoin="{join}""#).unwrap();
+        write!(out, r#" stroke-miterlimit="{}""#, num(stroke.miter_limit.0)).unwrap();
+        if let Some(pattern) = &stroke.dash_pattern {
+            let array: Vec<_> = pattern.array.iter().map(|l| num(l.to_pt())).collect();
+            write!(
+                out,
+                r#" stroke-dasharray="{}" stroke-dashoffset="{}""#,
+                array.join(" "),
+                num(pattern.phase.to_pt()),
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Write an attribute for a paint, together with its opacity.
+fn paint_attrs(out: &mut String, name: &str, paint: &Paint) {
+    let Paint::Solid(color) = paint;
+    let c = color.to_rgba();
+    write!(out, r##" {name}="#{:02x}{:02x}{:02x}""##, c.r, c.g, c.b).unwrap();
+    if c.a != u8::MAX {
+        write!(out, r#" {name}-opacity="{}""#, num(c.a as f64 / 255.0)).unwrap();
+    }
+}
+
+/// Write an image element with the given attributes and the image embedded as
+/// a data URL.
+fn image_elem(out: &mut String, attrs: &str, image: &Image, size: Size) {
+    let mime = match image.format() {
+        ImageFormat::Raster(RasterFormat::Png) => "image/png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "image/jpeg",
+        ImageFormat::Raster(RasterFormat::Gif) => "image/gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "image/svg+xml",
+    };
+    write!(
+        out,
+        r#"<image{attrs} width="{}" height="{}" preserveAspectRatio="none" xlink:href="data:{mime};base64,{}"/>"#,
+        num(size.x.to_pt()),
+        num(size.y.to_pt()),
+        base64(image.data()),
+    )
+    .unwrap();
+}
+
+/// Converts glyph outlines into SVG path data.
+struct SvgPathBuilder(String);
+
+impl OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "M{} {}", x, y).unwrap();
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        write!(self.0, "L{} {}", x, y).unwrap();
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        write!(self.0, "Q{} {} {} {}", x1, y1, x, y).unwrap();
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        write!(self.0, "C{} {} {} {} {} {}", x1, y1, x2, y2, x, y).unwrap();
+    }
+
+    fn close(&mut self) {
+        self.0.push('Z');
+    }
+}
+
+/// Format a number with at most three decimal places.
+fn num(value: f64) -> String {
+    let mut s = format!("{value:.3}");
+    if s.contains('.') {
+        let trimmed = s.trim_end_matches('0').trim_end_matches('.').len();
+        s.truncate(trimmed);
+    }
+    if s == "-0" {
+        s.remove(0);
+    }
+    s
+}
+
+/// Escape text for use in an attribute value or element content.
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Encode data in base64.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::doc::{Glyph, Lang};
+    use crate::geom::{
+        Color, DashPattern, Em, Point, Ratio, RgbaColor, Stroke, Transform,
+    };
+    use crate::syntax::Span;
+    use crate::util::Buffer;
+
+    fn run(file: &str, c: char) -> TextItem {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts").join(file);
+        let font = Font::new(Buffer::from(std::fs::read(path).unwrap()), 0).unwrap();
+        let id = font.ttf().glyph_index(c).unwrap().0;
+        let glyph = Glyph {
+            id,
+            x_advance: font.advance(id).unwrap(),
+            x_offset: Em::zero(),
+            range: 0..c.len_utf8() as u16,
+            span: (Span::detached(), 0),
+        };
+
+        TextItem {
+            font,
+            size: Abs::pt(10.0),
+            fill: Color::BLACK.into(),
+            lang: Lang::ENGLISH,
+            region: None,
+            text: c.to_string().into(),
+            glyphs: vec![glyph],
+        }
+    }
+
+    #[test]
+    fn test_svg_glyphs() {
+        let emoji = run("TwitterColorEmoji.ttf", '😀');
+        let letter = run("LinLibertine_R.ttf", 'A');
+        let mut frame = Frame::new(Size::splat(Abs::pt(40.0)));
+        frame.push(Point::with_y(Abs::pt(10.0)), FrameItem::Text(emoji.clone()));
+        frame.push(Point::with_y(Abs::pt(30.0)), FrameItem::Text(emoji));
+        frame.push(Point::new(Abs::pt(20.0), Abs::pt(10.0)), FrameItem::Text(letter));
+
+        // The emoji's SVG document is defined once as an image and the letter's
+        // outline as a path.
+        let output = svg(&frame);
+        assert_eq!(output.matches("<image id=").count(), 1);
+        assert_eq!(output.matches("<path id=").count(), 1);
+        assert_eq!(output.matches("<use ").count(), 3);
+    }
+
+    #[test]
+    fn test_svg_shapes_and_links() {
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(50.0)));
+        let rect = Geometry::Rect(Size::new(Abs::pt(20.0), Abs::pt(10.0)));
+        let fill = Color::Rgba(RgbaColor::new(255, 0, 0, 128)).into();
+        frame.push(Point::zero(), FrameItem::Shape(rect.filled(fill), Span::detached()));
+        let stroke = Stroke {
+            line_cap: LineCap::Round,
+            dash_pattern: Some(DashPattern {
+                array: vec![Abs::pt(2.0), Abs::pt(1.0)],
+                phase: Abs::pt(0.5),
+            }),
+            ..Default::default()
+        };
+        let line = Geometry::Line(Point::new(Abs::pt(30.0), Abs::pt(0.0)));
+        frame.push(
+            Point::new(Abs::pt(5.0), Abs::pt(2.5)),
+            FrameItem::Shape(line.stroked(stroke), Span::detached()),
+        );
+        let url = Destination::Url("https://typst.app/?a=1&b=2".into());
+        let size = Size::new(Abs::pt(10.0), Abs::pt(5.0));
+        frame.push(Point::zero(), FrameItem::Meta(Meta::Link(url), size));
+
+        let output = svg(&frame);
+        assert!(output.starts_with("<svg "));
+        assert!(output.contains(r#"viewBox="0 0 100 50" width="100pt" height="50pt""#));
+        assert!(!output.contains("<defs>"));
+        assert!(output.contains(
+            r##"<path d="M0 0H20V10H0Z" fill="#ff0000" fill-opacity="0.502"/>"##
+        ));
+        assert!(output.contains(
+            r##"<path transform="translate(5 2.5)" d="M0 0L30 0" fill="none" stroke="#000000" stroke-width="1" stroke-linecap="round" stroke-linejoin="miter" stroke-miterlimit="4" stroke-dasharray="2 1" stroke-dashoffset="0.5"/>"##
+        ));
+        assert!(output.contains(r#"<a xlink:href="https://typst.app/?a=1&amp;b=2">"#));
+        assert!(output.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_svg_groups() {
+        let mut inner = Frame::new(Size::splat(Abs::pt(10.0)));
+        let square = Geometry::Rect(Size::splat(Abs::pt(10.0)));
+        inner.push(
+            Point::zero(),
+            FrameItem::Shape(square.filled(Color::BLACK.into()), Span::detached()),
+        );
+
+        let mut plain = GroupItem::new(inner.clone());
+        plain.transform = Transform::scale(Ratio::new(2.0), Ratio::one());
+        let mut clipped = GroupItem::new(inner.clone());
+        clipped.clips = true;
+        let mut filtered = GroupItem::new(inner);
+        filtered.filters = vec![Filter::Blur(Abs::pt(2.0)), Filter::Grayscale];
+
+        let mut frame = Frame::new(Size::splat(Abs::pt(50.0)));
+        frame.push(Point::zero(), FrameItem::Group(plain));
+        frame.push(Point::with_x(Abs::pt(20.0)), FrameItem::Group(clipped));
+        frame.push(Point::with_y(Abs::pt(20.0)), FrameItem::Group(filtered));
+
+        // Each group is opened and closed once for itself and once for each
+        // of its transform, clip and filters.
+        let output = svg(&frame);
+        assert_eq!(output.matches("<g").count(), output.matches("</g>").count());
+        assert!(output.contains(r#"<g><g transform="matrix(2 0 0 1 0 0)"><path "#));
+        assert!(output
+            .contains(r#"<clipPath id="c1"><rect width="10" height="10"/></clipPath>"#));
+        assert!(output.contains(
+            r#"<g transform="translate(20 0)"><g clip-path="url(#c1)"><path "#
+        ));
+        assert!(output.contains(r#"<feGaussianBlur stdDeviation="2"/><feColorMatrix type="saturate" values="0"/></filter>"#));
+        assert!(output
+            .contains(r#"<g transform="translate(0 20)"><g filter="url(#f2)"><path "#));
+    }
+
+    #[test]
+    fn test_svg_images() {
+        let data = br#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="2"/>"#;
+        let format = ImageFormat::Vector(VectorFormat::Svg);
+        let image = Image::new(Buffer::from_static(data), format, None).unwrap();
+        let mut frame = Frame::new(Size::splat(Abs::pt(50.0)));
+        let size = Size::new(Abs::pt(40.0), Abs::pt(20.0));
+        frame.push(
+            Point::splat(Abs::pt(5.0)),
+            FrameItem::Image(image, size, Span::detached()),
+        );
+
+        let output = svg(&frame);
+        let expected = format!(
+            r#"<image transform="translate(5 5)" width="40" height="20" preserveAspectRatio="none" xlink:href="data:image/svg+xml;base64,{}"/>"#,
+            base64(data),
+        );
+        assert!(output.contains(&expected));
+    }
+
+    #[test]
+    fn test_svg_num_and_base64() {
+        assert_eq!(num(1.0), "1");
+        assert_eq!(num(0.1234), "0.123");
+        assert_eq!(num(2.5), "2.5");
+        assert_eq!(num(-0.0001), "0");
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"Man"), "TWFu");
+    }
+}
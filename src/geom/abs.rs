@@ -103,6 +103,10 @@ impl Abs {
     /// Whether the other absolute length fits into this one (i.e. is smaller).
     /// Allows for a bit of slack.
     pub fn fits(self, other: Self) -> bool {
+        debug_assert!(
+            !self.to_raw().is_nan() && !other.to_raw().is_nan(),
+            "length is NaN"
+        );
         self.0 + 1e-6 >= other.0
     }
 
@@ -154,6 +154,21 @@ impl RgbaColor {
         Self { r, g, b, a }
     }
 
+    /// Convert this color to CMYK, ignoring the alpha channel.
+    ///
+    /// This is the naive conversion without a color profile. Grays only use
+    /// the black component.
+    pub fn to_cmyk(self) -> CmykColor {
+        let max = self.r.max(self.g).max(self.b);
+        if max == 0 {
+            return CmykColor::new(0, 0, 0, u8::MAX);
+        }
+
+        let k = u8::MAX - max;
+        let f = |c: u8| round_u8((max - c) as f64 / max as f64 * 255.0);
+        CmykColor::new(f(self.r), f(self.g), f(self.b), k)
+    }
+
     /// Lighten this color by a factor.
     ///
     /// The alpha channel is not affected.
@@ -383,4 +398,19 @@ mod tests {
         test("hmmm", "color string contains non-hexadecimal letters");
         test("14B2AH", "color string contains non-hexadecimal letters");
     }
+
+    #[test]
+    fn test_rgba_to_cmyk() {
+        #[track_caller]
+        fn test(rgba: RgbaColor, cmyk: CmykColor) {
+            assert_eq!(rgba.to_cmyk(), cmyk);
+            assert_eq!(cmyk.to_rgba(), RgbaColor { a: 255, ..rgba });
+        }
+
+        test(RgbaColor::new(0, 0, 0, 255), CmykColor::new(0, 0, 0, 255));
+        test(RgbaColor::new(255, 255, 255, 255), CmykColor::new(0, 0, 0, 0));
+        test(RgbaColor::new(255, 0, 0, 128), CmykColor::new(0, 255, 255, 0));
+        test(RgbaColor::new(0x80, 0x80, 0x80, 255), CmykColor::new(0, 0, 0, 0x7f));
+        test(RgbaColor::new(0, 0x66, 0xcc, 255), CmykColor::new(255, 128, 0, 0x33));
+    }
 }
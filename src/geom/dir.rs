@@ -77,3 +77,21 @@ impl Debug for Dir {
 cast! {
     type Dir: "direction",
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir_round_trips() {
+        for dir in [Dir::LTR, Dir::RTL, Dir::TTB, Dir::BTT] {
+            assert_eq!(dir.inv().inv(), dir);
+            assert_eq!(dir.inv().axis(), dir.axis());
+            assert_ne!(dir.inv().is_positive(), dir.is_positive());
+            assert_eq!(dir.axis().dir(dir.is_positive()), dir);
+            assert_eq!(dir.axis().other().other(), dir.axis());
+            assert_eq!(dir.start().inv(), dir.end());
+            assert_eq!(dir.inv().start(), dir.end());
+        }
+    }
+}
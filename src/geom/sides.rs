@@ -266,3 +266,21 @@ impl<T: Fold> Fold for Sides<Option<T>> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_round_trips() {
+        for side in [Side::Left, Side::Top, Side::Right, Side::Bottom] {
+            assert_eq!(side.inv().inv(), side);
+            assert_eq!(side.next_cw().next_ccw(), side);
+            assert_eq!(side.next_ccw().next_cw(), side);
+            assert_eq!(side.next_cw().next_cw(), side.inv());
+            assert_eq!(side.inv().axis(), side.axis());
+            assert_eq!(side.next_cw().axis(), side.axis().other());
+            assert_eq!(side.end_corner(), side.next_cw().start_corner());
+        }
+    }
+}
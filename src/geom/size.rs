@@ -76,3 +76,48 @@ assign_impl!(Size -= Size);
 assign_impl!(Size += Size);
 assign_impl!(Size *= f64);
 assign_impl!(Size /= f64);
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn size() -> impl Strategy<Value = Size> {
+        (-1e6..1e6, -1e6..1e6).prop_map(|(x, y)| Size::new(Abs::pt(x), Abs::pt(y)))
+    }
+
+    fn approx_eq(a: Size, b: Size) -> bool {
+        a.x.approx_eq(b.x) && a.y.approx_eq(b.y)
+    }
+
+    proptest! {
+        #[test]
+        fn test_size_add_sub(a in size(), b in size(), c in size()) {
+            prop_assert_eq!(a + b, b + a);
+            prop_assert_eq!(a + Size::zero(), a);
+            prop_assert_eq!(a - a, Size::zero());
+            prop_assert_eq!(-(-a), a);
+            prop_assert_eq!(a - b, a + -b);
+            prop_assert!(approx_eq((a + b) - b, a));
+            prop_assert!(approx_eq((a + b) + c, a + (b + c)));
+        }
+
+        #[test]
+        fn test_size_scale(a in size(), b in size(), k in 0.1..10.0, l in 0.1..10.0) {
+            prop_assert_eq!(a * 1.0, a);
+            prop_assert_eq!(a * 2.0, a + a);
+            prop_assert_eq!(a * k, k * a);
+            prop_assert!(approx_eq((a * k) / k, a));
+            prop_assert!(approx_eq((a + b) * k, a * k + b * k));
+            prop_assert!(approx_eq(a * (k * l), (a * k) * l));
+        }
+
+        #[test]
+        fn test_size_fits(a in size(), b in size()) {
+            let grown = a + b.map(Abs::abs);
+            prop_assert!(a.fits(a));
+            prop_assert!(grown.fits(a));
+        }
+    }
+}
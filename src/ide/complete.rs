@@ -345,6 +345,11 @@ fn complete_field_accesses(ctx: &mut CompletionContext) -> bool {
     false
 }
 
+/// The detail shown for a symbol completion: its codepoint.
+fn symbol_detail(c: char) -> EcoString {
+    eco_format!("U+{:04X}", c as u32)
+}
+
 /// Add completions for all fields on a value.
 fn field_access_completions(ctx: &mut CompletionContext, value: &Value) {
     for &(method, args) in methods_on(value.type_name()) {
@@ -368,7 +373,7 @@ fn field_access_completions(ctx: &mut CompletionContext, value: &Value) {
                         kind: CompletionKind::Symbol(modified.get()),
                         label: modifier.into(),
                         apply: None,
-                        detail: None,
+                        detail: Some(symbol_detail(modified.get())),
                     });
                 }
             }
@@ -1012,7 +1017,7 @@ impl<'a> CompletionContext<'a> {
         }
 
         let detail = docs.map(Into::into).or_else(|| match value {
-            Value::Symbol(_) => None,
+            Value::Symbol(symbol) => Some(symbol_detail(symbol.get())),
             Value::Func(func) => func.info().map(|info| plain_docs_sentence(info.docs)),
             v => Some(v.repr().into()),
         });
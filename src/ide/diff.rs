@@ -0,0 +1,366 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+
+use crate::doc::{Document, Frame, FrameItem};
+use crate::geom::{Abs, Color, Geometry, Paint, Point, RgbaColor, Size, Transform};
+use crate::syntax::Span;
+
+/// A line of text in a laid out document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// The page the line is on.
+    pub page: NonZeroUsize,
+    /// The position of the line's first text run on its page.
+    pub pos: Point,
+    /// The width of the line.
+    pub width: Abs,
+    /// The font size of the line's first text run.
+    pub size: Abs,
+    /// The line's text.
+    pub text: EcoString,
+    /// The source code location of the line's first glyph.
+    pub span: Span,
+}
+
+/// How a line changed between two versions of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineDiff {
+    /// The line is in both versions, first in the old and then in the new one.
+    Same(TextLine, TextLine),
+    /// The line is only in the old version.
+    Removed(TextLine),
+    /// The line is only in the new version.
+    Added(TextLine),
+}
+
+/// Extract the lines of text of a document in reading order.
+///
+/// Text runs are merged into a line as long as they continue on the same
+/// baseline to the right of each other.
+pub fn text_lines(document: &Document) -> Vec<TextLine> {
+    let mut lines = vec![];
+    for (i, frame) in document.pages.iter().enumerate() {
+        let page = NonZeroUsize::new(i + 1).unwrap();
+        let mut runs = vec![];
+        collect_runs(frame, Transform::identity(), &mut runs);
+
+        let mut current: Option<(TextLine, Abs)> = None;
+        for run in runs {
+            if let Some((line, end)) = &mut current {
+                let tolerance = run.size * 0.5;
+                if (run.pos.y - line.pos.y).abs() <= tolerance
+                    && run.pos.x >= *end - tolerance
+                {
+                    if run.pos.x > *end + run.size * 0.1 && !line.text.ends_with(' ') {
+                        line.text.push(' ');
+                    }
+                    line.text.push_str(&run.text);
+                    *end = run.pos.x + run.width;
+                    line.width = *end - line.pos.x;
+                    continue;
+                }
+            }
+
+            let line = TextLine {
+                page,
+                pos: run.pos,
+                width: run.width,
+                size: run.size,
+                text: run.text,
+                span: run.span,
+            };
+            if let Some((line, _)) = current.replace((line, run.pos.x + run.width)) {
+                lines.push(line);
+            }
+        }
+
+        lines.extend(current.map(|(line, _)| line));
+    }
+
+    for line in &mut lines {
+        line.text = line.text.trim().into();
+    }
+
+    lines.retain(|line| !line.text.is_empty());
+    lines
+}
+
+/// Compute a line-level diff between two versions of a document.
+///
+/// Lines are compared by their text only, so lines that moved to another
+/// page without changing are considered the same.
+pub fn diff_lines(old: &Document, new: &Document) -> Vec<LineDiff> {
+    diff_text_lines(&text_lines(old), &text_lines(new))
+}
+
+/// Compute a diff between two sequences of lines.
+///
+/// The diff keeps the longest common subsequence of the lines. It is computed
+/// with Hirschberg's algorithm, which needs memory linear in the number of
+/// lines, so that long documents can be compared.
+pub fn diff_text_lines(old: &[TextLine], new: &[TextLine]) -> Vec<LineDiff> {
+    let mut diff = vec![];
+    diff_range(old, new, &mut diff);
+    diff
+}
+
+/// Append the diff of two ranges of lines.
+fn diff_range(old: &[TextLine], new: &[TextLine], diff: &mut Vec<LineDiff>) {
+    // Lines at the start and the end that didn't change don't need to go
+    // through the search.
+    let same = |(a, b): &(&TextLine, &TextLine)| a.text == b.text;
+    let prefix = old.iter().zip(new).take_while(same).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(same)
+        .count();
+
+    let pairs = |old: &[TextLine], new: &[TextLine]| {
+        old.iter()
+            .zip(new)
+            .map(|(a, b)| LineDiff::Same(a.clone(), b.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    diff.extend(pairs(&old[..prefix], &new[..prefix]));
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    if old_mid.is_empty() || new_mid.is_empty() {
+        diff.extend(old_mid.iter().cloned().map(LineDiff::Removed));
+        diff.extend(new_mid.iter().cloned().map(LineDiff::Added));
+    } else if let [line] = old_mid {
+        match new_mid.iter().position(|other| other.text == line.text) {
+            Some(j) => {
+                diff.extend(new_mid[..j].iter().cloned().map(LineDiff::Added));
+                diff.push(LineDiff::Same(line.clone(), new_mid[j].clone()));
+                diff.extend(new_mid[j + 1..].iter().cloned().map(LineDiff::Added));
+            }
+            None => {
+                diff.push(LineDiff::Removed(line.clone()));
+                diff.extend(new_mid.iter().cloned().map(LineDiff::Added));
+            }
+        }
+    } else {
+        // Split the old lines in half and find the split of the new lines
+        // through which a longest common subsequence passes.
+        let mid = old_mid.len() / 2;
+        let forward = lcs_lengths(old_mid[..mid].iter(), new_mid.iter());
+        let backward = lcs_lengths(old_mid[mid..].iter().rev(), new_mid.iter().rev());
+        let m = new_mid.len();
+        let split = (0..=m)
+            .max_by_key(|&j| (forward[j] + backward[m - j], m - j))
+            .unwrap();
+        diff_range(&old_mid[..mid], &new_mid[..split], diff);
+        diff_range(&old_mid[mid..], &new_mid[split..], diff);
+    }
+
+    diff.extend(pairs(&old[old.len() - suffix..], &new[new.len() - suffix..]));
+}
+
+/// The lengths of the longest common subsequences of the old lines and each
+/// prefix of the new lines.
+fn lcs_lengths<'a, I>(old: I, new: I) -> Vec<usize>
+where
+    I: Iterator<Item = &'a TextLine> + Clone,
+{
+    let mut row = vec![0; new.clone().count() + 1];
+    for a in old {
+        let mut diagonal = 0;
+        for (j, b) in new.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a.text == b.text { diagonal + 1 } else { above.max(row[j]) };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+/// The pages of the new version of a document that differ from the old one.
+///
+/// A page differs if lines were added to it or removed from the place in the
+/// new version that corresponds to it.
+pub fn changed_pages(diff: &[LineDiff]) -> Vec<NonZeroUsize> {
+    let mut pages = vec![];
+    let mut page = NonZeroUsize::new(1).unwrap();
+    for change in diff {
+        match change {
+            LineDiff::Same(_, new) => page = new.page,
+            LineDiff::Added(new) => {
+                page = new.page;
+                pages.push(page);
+            }
+            LineDiff::Removed(_) => pages.push(page),
+        }
+    }
+
+    pages.sort();
+    pages.dedup();
+    pages
+}
+
+/// Render a visual diff of two versions of a document.
+///
+/// Each page of the result shows a page of the old version next to the page
+/// of the new version with the same number. Removed lines are highlighted in
+/// red on the old pages and added lines in green on the new ones. The
+/// highlights lie behind the text and are repeated as bars in the left margin,
+/// so that they also show on pages with a background. New pages that changed
+/// are flagged with a bar above them.
+pub fn render_diff(old: &Document, new: &Document, diff: &[LineDiff]) -> Document {
+    let red = Color::Rgba(RgbaColor::new(0xff, 0x41, 0x36, 0xff));
+    let green = Color::Rgba(RgbaColor::new(0x2e, 0xcc, 0x40, 0xff));
+    let light_red = Color::Rgba(RgbaColor::new(0xff, 0xd6, 0xd3, 0xff));
+    let light_green = Color::Rgba(RgbaColor::new(0xd4, 0xf5, 0xd9, 0xff));
+    let gap = Abs::pt(20.0);
+    let flag = Abs::pt(6.0);
+
+    let changed = changed_pages(diff);
+    let count = old.pages.len().max(new.pages.len());
+    let mut pages = Vec::with_capacity(count);
+    for i in 0..count {
+        let page = NonZeroUsize::new(i + 1).unwrap();
+        let removed = diff.iter().filter_map(|change| match change {
+            LineDiff::Removed(line) if line.page == page => Some(line),
+            _ => None,
+        });
+        let added = diff.iter().filter_map(|change| match change {
+            LineDiff::Added(line) if line.page == page => Some(line),
+            _ => None,
+        });
+
+        let (left, right) = (old.pages.get(i), new.pages.get(i));
+        let size = |frame: Option<&Frame>| {
+            frame.or(left).or(right).map_or(Size::zero(), Frame::size)
+        };
+        let (left_size, right_size) = (size(left), size(right));
+        let height = left_size.y.max(right_size.y) + flag * 2.0;
+
+        let mut frame = Frame::new(Size::new(left_size.x + gap + right_size.x, height));
+        if let Some(left) = left {
+            let pos = Point::with_y(flag * 2.0);
+            frame.push_frame(pos, highlight(left, removed, light_red.into(), red.into()));
+        }
+        if let Some(right) = right {
+            let pos = Point::new(left_size.x + gap, flag * 2.0);
+            frame.push_frame(
+                pos,
+                highlight(right, added, light_green.into(), green.into()),
+            );
+        }
+        if changed.contains(&page) {
+            let bar = Geometry::Rect(Size::new(right_size.x, flag)).filled(red.into());
+            let pos = Point::with_x(left_size.x + gap);
+            frame.push(pos, FrameItem::Shape(bar, Span::detached()));
+        }
+
+        pages.push(frame);
+    }
+
+    Document { pages, ..Default::default() }
+}
+
+/// Highlight lines on a page behind the text and in its left margin.
+fn highlight<'a>(
+    page: &Frame,
+    lines: impl Iterator<Item = &'a TextLine>,
+    fill: Paint,
+    bar: Paint,
+) -> Frame {
+    let mut frame = Frame::new(page.size());
+    let mut bars = vec![];
+    for line in lines {
+        let top = line.pos.y - line.size * 0.8;
+        let rect = Geometry::Rect(Size::new(line.width, line.size)).filled(fill.clone());
+        frame.push(Point::new(line.pos.x, top), FrameItem::Shape(rect, Span::detached()));
+        let rect = Geometry::Rect(Size::new(Abs::pt(3.0), line.size)).filled(bar.clone());
+        bars.push((Point::with_y(top), FrameItem::Shape(rect, Span::detached())));
+    }
+
+    frame.push_frame(Point::zero(), page.clone());
+    for (pos, item) in bars {
+        frame.push(pos, item);
+    }
+
+    frame
+}
+
+/// A run of text positioned on a page.
+struct Run {
+    pos: Point,
+    width: Abs,
+    size: Abs,
+    text: EcoString,
+    span: Span,
+}
+
+/// Collect the text runs of a frame in the order they were laid out.
+fn collect_runs(frame: &Frame, ts: Transform, runs: &mut Vec<Run>) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                collect_runs(&group.frame, ts, runs);
+            }
+            FrameItem::Text(text) => runs.push(Run {
+                pos: pos.transform(ts),
+                width: text.width(),
+                size: text.size,
+                text: text.text.clone(),
+                span: text.glyphs.first().map_or(Span::detached(), |g| g.span.0),
+            }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One line per character.
+    fn lines(text: &str) -> Vec<TextLine> {
+        text.chars()
+            .map(|c| TextLine {
+                page: NonZeroUsize::new(1).unwrap(),
+                pos: Point::zero(),
+                width: Abs::zero(),
+                size: Abs::zero(),
+                text: c.into(),
+                span: Span::detached(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_text_lines() {
+        #[track_caller]
+        fn test(old: &str, new: &str, expected: &str) {
+            let diff = diff_text_lines(&lines(old), &lines(new));
+            let mut found = String::new();
+            for change in &diff {
+                let (sign, line) = match change {
+                    LineDiff::Same(line, _) => (' ', line),
+                    LineDiff::Removed(line) => ('-', line),
+                    LineDiff::Added(line) => ('+', line),
+                };
+                found.push(sign);
+                found.push_str(&line.text);
+            }
+            assert_eq!(found, expected);
+        }
+
+        test("abc", "abc", " a b c");
+        test("", "ab", "+a+b");
+        test("ab", "", "-a-b");
+        test("abc", "xyz", "-a-b-c+x+y+z");
+        test("axbyc", "abc", " a-x b-y c");
+        test("abcd", "acbd", " a-b c+b d");
+        test("abcabba", "cbabac", "-a-b c-a b+a b a+c");
+    }
+}
@@ -48,6 +48,8 @@ pub fn jump_from_click(
                             .get_or_insert_with(|| Introspector::new(frames))
                             .position(*loc),
                     ),
+                    // Other files can't be shown in place of this one.
+                    Destination::Remote(_) => continue,
                 });
             }
         }
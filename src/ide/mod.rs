@@ -2,15 +2,26 @@
 
 mod analyze;
 mod complete;
+mod diff;
 mod highlight;
 mod jump;
+mod resources;
+mod snapshot;
 mod tooltip;
+mod words;
 
 pub use self::analyze::analyze_labels;
 pub use self::complete::{autocomplete, Completion, CompletionKind};
+pub use self::diff::{
+    changed_pages, diff_lines, diff_text_lines, render_diff, text_lines, LineDiff,
+    TextLine,
+};
 pub use self::highlight::{highlight, highlight_html, Tag};
 pub use self::jump::{jump_from_click, jump_from_cursor, Jump};
+pub use self::resources::font_families;
+pub use self::snapshot::{snapshot, Snapshot};
 pub use self::tooltip::{tooltip, Tooltip};
+pub use self::words::{words, Word};
 
 use std::fmt::Write;
 
@@ -0,0 +1,101 @@
+use comemo::Track;
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::eval::{eval, Route, Tracer, Value};
+use crate::model::{Content, ElemFunc, Style, Styles};
+use crate::World;
+
+/// Find the font families that the main source file's content requests.
+///
+/// Evaluates the main source file without laying it out, so the files it
+/// imports or reads are loaded through the world as a side effect. Includes
+/// the default families of the text element. Families that are only set by
+/// show rules are not found as those run during layout.
+pub fn font_families(world: &dyn World) -> SourceResult<Vec<EcoString>> {
+    let route = Route::default();
+    let mut tracer = Tracer::default();
+    let world = world.track();
+    let module = eval(world, route.track(), tracer.track_mut(), world.main())?;
+
+    let mut families = vec![];
+    let library = world.library();
+    let Some(Value::Func(func)) = library.global.scope().get("text") else {
+        return Ok(families);
+    };
+    let Some(text) = func.element() else { return Ok(families) };
+
+    if let Some(default) = func
+        .info()
+        .and_then(|info| info.params.iter().find(|param| param.name == "font"))
+        .and_then(|param| param.default)
+    {
+        collect_value(&default(), &mut families);
+    }
+
+    collect_styles(&library.styles, text, &mut families);
+    collect_content(&module.content(), text, &mut families);
+    Ok(families)
+}
+
+/// Collect the families from text font properties in content.
+fn collect_content(content: &Content, text: ElemFunc, families: &mut Vec<EcoString>) {
+    if let Some(children) = content.to_sequence() {
+        for child in children {
+            collect_content(child, text, families);
+        }
+        return;
+    }
+
+    if let Some((child, styles)) = content.to_styled() {
+        collect_styles(styles, text, families);
+        collect_content(child, text, families);
+        return;
+    }
+
+    for (_, value) in content.fields_ref() {
+        collect_fields(value, text, families);
+    }
+}
+
+/// Collect the families from content nested in a field value.
+fn collect_fields(value: &Value, text: ElemFunc, families: &mut Vec<EcoString>) {
+    match value {
+        Value::Content(content) => collect_content(content, text, families),
+        Value::Array(array) => {
+            for item in array.iter() {
+                collect_fields(item, text, families);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect the families from text font properties in styles.
+fn collect_styles(styles: &Styles, text: ElemFunc, families: &mut Vec<EcoString>) {
+    for style in styles.iter() {
+        if let Style::Property(property) = style {
+            if property.is(text, "font") {
+                collect_value(property.value(), families);
+            }
+        }
+    }
+}
+
+/// Collect the families from a font family string or array of strings.
+fn collect_value(value: &Value, families: &mut Vec<EcoString>) {
+    match value {
+        Value::Str(family) => {
+            let family = EcoString::from(family.as_str());
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        }
+        Value::Array(array) => {
+            for item in array.iter() {
+                collect_value(item, families);
+            }
+        }
+        _ => {}
+    }
+}
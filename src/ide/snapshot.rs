@@ -0,0 +1,53 @@
+use std::fmt::Write;
+
+use comemo::Track;
+
+use crate::eval::{eval, Route, Tracer};
+use crate::World;
+
+/// Textual snapshots of how the main source file parses and evaluates.
+///
+/// The snapshots are deterministic, so they can be stored as golden files and
+/// compared against in tests to catch regressions in the parser and
+/// evaluator that don't show up in the rendered output.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snapshot {
+    /// The syntax tree.
+    pub ast: String,
+    /// The module's top-level bindings and content, or the evaluation errors.
+    pub eval: String,
+}
+
+/// Take snapshots of the main source file's syntax tree and evaluation
+/// result.
+pub fn snapshot(world: &dyn World) -> Snapshot {
+    let source = world.main();
+    let ast = format!("{:#?}\n", source.root());
+
+    let route = Route::default();
+    let mut tracer = Tracer::default();
+    let mut out = String::new();
+    match eval(world.track(), route.track(), tracer.track_mut(), source) {
+        Ok(module) => {
+            out.push_str("Bindings:\n");
+            for (name, value) in module.scope().iter() {
+                writeln!(out, "  {name} = {value:?}").unwrap();
+            }
+            writeln!(out, "Content:\n{:#?}", module.content()).unwrap();
+        }
+        Err(errors) => {
+            out.push_str("Errors:\n");
+            for error in errors.iter() {
+                if error.span.is_detached() {
+                    writeln!(out, "  {}", error.message).unwrap();
+                    continue;
+                }
+                let range = error.range(world);
+                writeln!(out, "  {}-{}: {}", range.start, range.end, error.message)
+                    .unwrap();
+            }
+        }
+    }
+
+    Snapshot { ast, eval: out }
+}
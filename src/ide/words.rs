@@ -0,0 +1,145 @@
+use std::num::NonZeroUsize;
+use std::ops::Range;
+
+use ecow::EcoString;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::doc::{Document, Frame, FrameItem, Lang, Region, TextItem};
+use crate::syntax::{SourceId, SyntaxKind};
+use crate::World;
+
+/// A word in a laid out document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Word {
+    /// The word's text.
+    pub text: EcoString,
+    /// The language of the word, as configured by the active `lang` setting.
+    pub lang: Lang,
+    /// The region of the word's language, if any.
+    pub region: Option<Region>,
+    /// The page the word is on.
+    pub page: NonZeroUsize,
+    /// The source file and byte range the word was written at.
+    ///
+    /// This is `None` for words that don't appear verbatim in the markup, for
+    /// example because they were produced by a function or a show rule.
+    pub source: Option<(SourceId, Range<usize>)>,
+}
+
+/// Extract the words of a document in the order they were laid out.
+///
+/// This is the realized text, after show rules and text transformations were
+/// applied, so that spell checkers see what the reader sees. The words carry
+/// their language so that the right dictionary can be picked, and if possible
+/// their location in the source code to report misspellings at.
+///
+/// A word that is split across text runs, like at a hyphenated line break, is
+/// returned as a whole if its parts are adjacent in the source code.
+pub fn words(world: &dyn World, document: &Document) -> Vec<Word> {
+    let mut words = vec![];
+    for (i, frame) in document.pages.iter().enumerate() {
+        let page = NonZeroUsize::new(i + 1).unwrap();
+        collect_words(world, frame, page, &mut words, &mut false);
+    }
+    words
+}
+
+/// Collect the words of a frame.
+///
+/// The `open` flag tracks whether the last word so far ended with its text
+/// run, so that the next run may continue it.
+fn collect_words(
+    world: &dyn World,
+    frame: &Frame,
+    page: NonZeroUsize,
+    words: &mut Vec<Word>,
+    open: &mut bool,
+) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                collect_words(world, &group.frame, page, words, open)
+            }
+            FrameItem::Text(text) => {
+                let continues = std::mem::take(open);
+                for (offset, word) in text.text.split_word_bound_indices() {
+                    if !word.chars().any(char::is_alphabetic) {
+                        continue;
+                    }
+
+                    let range = offset..offset + word.len();
+                    let source = locate(world, text, range.clone());
+                    *open = range.end == text.text.len();
+                    if continues && offset == 0 && join(words.last_mut(), word, &source) {
+                        continue;
+                    }
+
+                    words.push(Word {
+                        text: word.into(),
+                        lang: text.lang,
+                        region: text.region,
+                        page,
+                        source,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Append a word fragment to the previous word if they are adjacent in the
+/// source code. Returns whether it was appended.
+fn join(
+    previous: Option<&mut Word>,
+    fragment: &str,
+    source: &Option<(SourceId, Range<usize>)>,
+) -> bool {
+    let Some(previous) = previous else { return false };
+    let (Some((id, range)), Some((other, next))) = (&mut previous.source, source) else {
+        return false;
+    };
+
+    if *id != *other || range.end != next.start {
+        return false;
+    }
+
+    previous.text.push_str(fragment);
+    range.end = next.end;
+    true
+}
+
+/// Find the source range of a range in a text item's text.
+///
+/// Only succeeds if all of the range's glyphs stem from the same markup text.
+fn locate(
+    world: &dyn World,
+    text: &TextItem,
+    range: Range<usize>,
+) -> Option<(SourceId, Range<usize>)> {
+    let glyphs: Vec<_> = text
+        .glyphs
+        .iter()
+        .filter(|glyph| {
+            glyph.range().start < range.end && range.start < glyph.range().end
+        })
+        .collect();
+
+    let first = glyphs.iter().min_by_key(|glyph| glyph.range().start)?;
+    let last = glyphs.iter().max_by_key(|glyph| glyph.range().end)?;
+    let span = first.span.0;
+    if span.is_detached() || glyphs.iter().any(|glyph| glyph.span.0 != span) {
+        return None;
+    }
+
+    let source = world.source(span.source());
+    let node = source.find(span)?;
+    if node.kind() != SyntaxKind::Text {
+        return None;
+    }
+
+    let node_range = node.range();
+    let start = node_range.start + usize::from(first.span.1);
+    let end = node_range.start + usize::from(last.span.1) + last.range().len();
+    Some((source.id(), start.min(node_range.end)..end.min(node_range.end)))
+}
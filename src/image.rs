@@ -12,20 +12,27 @@ use image::codecs::gif::GifDecoder;
 use image::codecs::jpeg::JpegDecoder;
 use image::codecs::png::PngDecoder;
 use image::io::Limits;
-use image::{ImageDecoder, ImageResult};
+use image::{AnimationDecoder, ImageDecoder, ImageResult};
+use once_cell::sync::{Lazy, OnceCell};
 use usvg::{TreeParsing, TreeTextToPath};
 
-use crate::diag::{format_xml_like_error, StrResult};
+use crate::diag::{bail, format_xml_like_error, StrResult};
 use crate::font::Font;
 use crate::geom::Axes;
-use crate::util::Buffer;
+use crate::util::lru::Lru;
+use crate::util::{hash128, Buffer};
 use crate::World;
 
 /// A raster or vector image.
 ///
 /// Values of this type are cheap to clone and hash.
 #[derive(Clone, Hash, Eq, PartialEq)]
-pub struct Image(Arc<Prehashed<Repr>>);
+pub struct Image {
+    /// The image itself.
+    repr: Arc<Prehashed<Repr>>,
+    /// The frame of an animated image that is shown.
+    frame: usize,
+}
 
 /// The internal representation.
 #[derive(Hash)]
@@ -36,6 +43,8 @@ struct Repr {
     format: ImageFormat,
     /// The size of the image.
     size: Axes<u32>,
+    /// The number of frames of the image.
+    frames: usize,
     /// A loader for fonts referenced by an image (currently, only applies to
     /// SVG).
     loader: PreparedLoader,
@@ -52,20 +61,15 @@ impl Image {
         alt: Option<EcoString>,
     ) -> StrResult<Self> {
         let loader = PreparedLoader::default();
-        let decoded = match format {
-            ImageFormat::Raster(format) => decode_raster(&data, format)?,
+        let size = match format {
+            ImageFormat::Raster(format) => raster_size(&data, format)?,
             ImageFormat::Vector(VectorFormat::Svg) => {
-                decode_svg(&data, (&loader as &dyn SvgFontLoader).track())?
+                decode_svg(&data, (&loader as &dyn SvgFontLoader).track())?.size()
             }
         };
 
-        Ok(Self(Arc::new(Prehashed::new(Repr {
-            data,
-            format,
-            size: decoded.size(),
-            loader,
-            alt,
-        }))))
+        let frames = count_frames(&data, format);
+        Ok(Self::from_repr(Repr { data, format, size, frames, loader, alt }))
     }
 
     /// Create a font-dependant image from a buffer and a format.
@@ -78,35 +82,68 @@ impl Image {
         alt: Option<EcoString>,
     ) -> StrResult<Self> {
         let loader = WorldLoader::new(world, fallback_family);
-        let decoded = match format {
-            ImageFormat::Raster(format) => decode_raster(&data, format)?,
+        let size = match format {
+            ImageFormat::Raster(format) => raster_size(&data, format)?,
             ImageFormat::Vector(VectorFormat::Svg) => {
-                decode_svg(&data, (&loader as &dyn SvgFontLoader).track())?
+                decode_svg(&data, (&loader as &dyn SvgFontLoader).track())?.size()
             }
         };
 
-        Ok(Self(Arc::new(Prehashed::new(Repr {
+        let frames = count_frames(&data, format);
+        Ok(Self::from_repr(Repr {
             data,
             format,
-            size: decoded.size(),
+            size,
+            frames,
             loader: loader.into_prepared(),
             alt,
-        }))))
+        }))
+    }
+
+    /// Wrap a representation, showing its first frame.
+    fn from_repr(repr: Repr) -> Self {
+        Self { repr: Arc::new(Prehashed::new(repr)), frame: 0 }
+    }
+
+    /// Select the frame of an animated image that is shown, starting at zero.
+    ///
+    /// Since PDF cannot animate, only the selected frame is embedded.
+    pub fn with_frame(mut self, frame: usize) -> StrResult<Self> {
+        let frames = self.frames();
+        if frame >= frames {
+            if frames == 1 {
+                bail!("image has only one frame");
+            }
+            bail!("image has only {frames} frames");
+        }
+        self.frame = frame;
+        Ok(self)
     }
 
     /// The raw image data.
     pub fn data(&self) -> &Buffer {
-        &self.0.data
+        &self.repr.data
     }
 
     /// The format of the image.
     pub fn format(&self) -> ImageFormat {
-        self.0.format
+        self.repr.format
     }
 
     /// The size of the image in pixels.
     pub fn size(&self) -> Axes<u32> {
-        self.0.size
+        self.repr.size
+    }
+
+    /// The number of frames of the image. This is one for images that
+    /// aren't animated.
+    pub fn frames(&self) -> usize {
+        self.repr.frames
+    }
+
+    /// The frame that is shown, starting at zero.
+    pub fn frame(&self) -> usize {
+        self.frame
     }
 
     /// The width of the image in pixels.
@@ -121,18 +158,43 @@ impl Image {
 
     /// A text describing the image.
     pub fn alt(&self) -> Option<&str> {
-        self.0.alt.as_deref()
+        self.repr.alt.as_deref()
+    }
+
+    /// The resolution of the image in dots per inch, if the image specifies
+    /// it.
+    pub fn dpi(&self) -> Option<f64> {
+        match self.format() {
+            ImageFormat::Raster(RasterFormat::Png) => png_dpi(self.data()),
+            ImageFormat::Raster(RasterFormat::Jpg) => jpeg_dpi(self.data()),
+            _ => None,
+        }
+    }
+
+    /// The color space the image's pixels are stored in, if it is a raster
+    /// image.
+    pub fn color_space(&self) -> Option<ColorSpace> {
+        match self.format() {
+            ImageFormat::Raster(RasterFormat::Png) => png_color_space(self.data()),
+            ImageFormat::Raster(RasterFormat::Jpg) => jpeg_color_space(self.data()),
+            ImageFormat::Raster(RasterFormat::Gif) => Some(ColorSpace::Rgb),
+            ImageFormat::Vector(_) => None,
+        }
     }
 
     /// The decoded version of the image.
-    pub fn decoded(&self) -> Arc<DecodedImage> {
+    ///
+    /// Raster images are only decoded into memory when this is first called,
+    /// typically during export. Layout only needs the size, which is read from
+    /// the image's header, so only the header is checked when the image is
+    /// created and corrupt pixel data fails the export.
+    pub fn decoded(&self) -> StrResult<Arc<DecodedImage>> {
         match self.format() {
-            ImageFormat::Raster(format) => decode_raster(self.data(), format),
+            ImageFormat::Raster(format) => decode_raster(self.data(), format, self.frame),
             ImageFormat::Vector(VectorFormat::Svg) => {
-                decode_svg(self.data(), (&self.0.loader as &dyn SvgFontLoader).track())
+                decode_svg(self.data(), (&self.repr.loader as &dyn SvgFontLoader).track())
             }
         }
-        .unwrap()
     }
 }
 
@@ -142,6 +204,7 @@ impl Debug for Image {
             .field("format", &self.format())
             .field("width", &self.width())
             .field("height", &self.height())
+            .field("frame", &self.frame())
             .field("alt", &self.alt())
             .finish()
     }
@@ -198,10 +261,21 @@ impl From<ttf_parser::RasterImageFormat> for ImageFormat {
     }
 }
 
+/// The color space of a raster image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ColorSpace {
+    /// Shades of gray.
+    Luma,
+    /// Red, green and blue.
+    Rgb,
+    /// Cyan, magenta, yellow and black, typically used for print.
+    Cmyk,
+}
+
 /// A decoded image.
 pub enum DecodedImage {
     /// A decoded pixel raster with its ICC profile.
-    Raster(image::DynamicImage, Option<IccProfile>, RasterFormat),
+    Raster(Arc<image::DynamicImage>, Option<IccProfile>, RasterFormat),
     /// An decoded SVG tree.
     Svg(usvg::Tree),
 }
@@ -230,11 +304,149 @@ impl DecodedImage {
 }
 
 /// Raw data for of an ICC profile.
+#[derive(Clone)]
 pub struct IccProfile(pub Vec<u8>);
 
-/// Decode a raster image.
-#[comemo::memoize]
-fn decode_raster(data: &Buffer, format: RasterFormat) -> StrResult<Arc<DecodedImage>> {
+/// Persists decoded raster images across compilations, for example on disk.
+pub trait RasterStore: Send + Sync {
+    /// Load the raster stored under the key.
+    fn load(&self, key: u128) -> Option<Vec<u8>>;
+
+    /// Store a raster under the key.
+    fn save(&self, key: u128, data: &[u8]);
+}
+
+/// Where decoded raster images are persisted.
+static STORE: OnceCell<Box<dyn RasterStore>> = OnceCell::new();
+
+/// Persist decoded raster images in the given store, so that later
+/// compilations don't need to decode them again.
+///
+/// The store can only be set once per process.
+pub fn set_raster_store(store: Box<dyn RasterStore>) {
+    STORE.set(store).ok();
+}
+
+/// Decoded raster images by their data, format and frame.
+///
+/// Decoded pixels are much larger than the encoded data, so they are kept in
+/// a size-bounded cache instead of being memoized.
+static RASTERS: Lazy<Lru<(Buffer, RasterFormat, usize), Pixels>> =
+    Lazy::new(|| Lru::new(RASTER_CACHE, 256 * 1024 * 1024));
+
+/// The name of the cache of decoded raster images.
+pub(crate) const RASTER_CACHE: &str = "images";
+
+/// The pixels and ICC profile of a decoded frame.
+type Pixels = StrResult<(Arc<image::DynamicImage>, Option<IccProfile>)>;
+
+/// Decode a frame of a raster image.
+fn decode_raster(
+    data: &Buffer,
+    format: RasterFormat,
+    frame: usize,
+) -> StrResult<Arc<DecodedImage>> {
+    let (dynamic, icc) = RASTERS.get_or_insert(
+        (data.clone(), format, frame),
+        || {
+            let key = hash128(&(data, format, frame));
+            let store = STORE.get();
+            if let Some(stored) = store.and_then(|store| store.load(key)) {
+                if let Some((dynamic, icc)) = read_stored(&stored) {
+                    return Ok((Arc::new(dynamic), icc));
+                }
+            }
+
+            let (dynamic, icc) = decode_pixels(data, format, frame)?;
+            if let Some(store) = store {
+                if let Some(stored) = write_stored(&dynamic, icc.as_ref()) {
+                    store.save(key, &stored);
+                }
+            }
+            Ok((Arc::new(dynamic), icc))
+        },
+        |pixels| match pixels {
+            Ok((dynamic, icc)) => {
+                dynamic.as_bytes().len() + icc.as_ref().map_or(0, |icc| icc.0.len())
+            }
+            Err(message) => message.len(),
+        },
+    )?;
+    Ok(Arc::new(DecodedImage::Raster(dynamic, icc, format)))
+}
+
+/// The start of a stored raster.
+const STORED_MAGIC: &[u8; 4] = b"TPX1";
+
+/// Serialize decoded pixels for a [`RasterStore`].
+///
+/// The format is the magic bytes, the color type, the width and height, the
+/// length of the ICC profile, the profile itself and finally the pixels. Only
+/// 8-bit images are stored.
+fn write_stored(
+    dynamic: &image::DynamicImage,
+    icc: Option<&IccProfile>,
+) -> Option<Vec<u8>> {
+    let color = match dynamic {
+        image::DynamicImage::ImageLuma8(_) => 0,
+        image::DynamicImage::ImageLumaA8(_) => 1,
+        image::DynamicImage::ImageRgb8(_) => 2,
+        image::DynamicImage::ImageRgba8(_) => 3,
+        _ => return None,
+    };
+
+    let icc = icc.map_or(&[][..], |icc| &icc.0);
+    let mut data = Vec::with_capacity(17 + icc.len() + dynamic.as_bytes().len());
+    data.extend(STORED_MAGIC);
+    data.push(color);
+    data.extend(dynamic.width().to_le_bytes());
+    data.extend(dynamic.height().to_le_bytes());
+    data.extend(u32::try_from(icc.len()).ok()?.to_le_bytes());
+    data.extend(icc);
+    data.extend(dynamic.as_bytes());
+    Some(data)
+}
+
+/// Deserialize pixels written by [`write_stored`].
+fn read_stored(data: &[u8]) -> Option<(image::DynamicImage, Option<IccProfile>)> {
+    use image::{DynamicImage, ImageBuffer};
+
+    let rest = data.strip_prefix(STORED_MAGIC)?;
+    let (&color, rest) = rest.split_first()?;
+    let u32_at =
+        |i: usize| Some(u32::from_le_bytes(rest.get(i..i + 4)?.try_into().ok()?));
+    let (width, height, len) = (u32_at(0)?, u32_at(4)?, u32_at(8)? as usize);
+    let end = len.checked_add(12)?;
+    let icc = rest.get(12..end)?;
+    let pixels = rest.get(end..)?.to_vec();
+
+    // Reject truncated or otherwise damaged entries.
+    let channels = match color {
+        0..=3 => color as usize + 1,
+        _ => return None,
+    };
+    if (width as usize).checked_mul(height as usize)?.checked_mul(channels)?
+        != pixels.len()
+    {
+        return None;
+    }
+
+    let dynamic = match color {
+        0 => DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, pixels)?),
+        1 => DynamicImage::ImageLumaA8(ImageBuffer::from_raw(width, height, pixels)?),
+        2 => DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, pixels)?),
+        _ => DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, pixels)?),
+    };
+    let icc = (!icc.is_empty()).then(|| IccProfile(icc.to_vec()));
+    Some((dynamic, icc))
+}
+
+/// Decode the pixels and ICC profile of a frame of a raster image.
+fn decode_pixels(
+    data: &Buffer,
+    format: RasterFormat,
+    frame: usize,
+) -> StrResult<(image::DynamicImage, Option<IccProfile>)> {
     fn decode_with<'a, T: ImageDecoder<'a>>(
         decoder: ImageResult<T>,
     ) -> ImageResult<(image::DynamicImage, Option<IccProfile>)> {
@@ -245,15 +457,231 @@ fn decode_raster(data: &Buffer, format: RasterFormat) -> StrResult<Arc<DecodedIm
         Ok((dynamic, icc))
     }
 
+    fn decode_frame<'a, T: AnimationDecoder<'a>>(
+        decoder: ImageResult<T>,
+        frame: usize,
+    ) -> ImageResult<(image::DynamicImage, Option<IccProfile>)> {
+        let Some(frame) = decoder?.into_frames().nth(frame) else {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        };
+        Ok((image::DynamicImage::ImageRgba8(frame?.into_buffer()), None))
+    }
+
     let cursor = io::Cursor::new(data);
-    let (dynamic, icc) = match format {
+    let (mut dynamic, icc) = match format {
+        RasterFormat::Gif if frame > 0 => decode_frame(GifDecoder::new(cursor), frame),
+        RasterFormat::Png if frame > 0 => {
+            decode_frame(PngDecoder::new(cursor).map(PngDecoder::apng), frame)
+        }
         RasterFormat::Jpg => decode_with(JpegDecoder::new(cursor)),
         RasterFormat::Png => decode_with(PngDecoder::new(cursor)),
         RasterFormat::Gif => decode_with(GifDecoder::new(cursor)),
     }
     .map_err(format_image_error)?;
 
-    Ok(Arc::new(DecodedImage::Raster(dynamic, icc, format)))
+    // Apply the orientation that cameras store in the EXIF metadata.
+    if format == RasterFormat::Jpg {
+        dynamic = match jpeg_orientation(data) {
+            Some(2) => dynamic.fliph(),
+            Some(3) => dynamic.rotate180(),
+            Some(4) => dynamic.flipv(),
+            Some(5) => dynamic.rotate90().fliph(),
+            Some(6) => dynamic.rotate90(),
+            Some(7) => dynamic.rotate270().fliph(),
+            Some(8) => dynamic.rotate270(),
+            _ => dynamic,
+        };
+    }
+
+    Ok((dynamic, icc))
+}
+
+/// Determine the size of a raster image from its header, without decoding
+/// the pixel data.
+fn raster_size(data: &Buffer, format: RasterFormat) -> StrResult<Axes<u32>> {
+    let cursor = io::Cursor::new(data);
+    let (width, height) = match format {
+        RasterFormat::Jpg => JpegDecoder::new(cursor).map(|d| d.dimensions()),
+        RasterFormat::Png => PngDecoder::new(cursor).map(|d| d.dimensions()),
+        RasterFormat::Gif => GifDecoder::new(cursor).map(|d| d.dimensions()),
+    }
+    .map_err(format_image_error)?;
+
+    // The EXIF orientations 5 to 8 swap the axes.
+    let swap =
+        format == RasterFormat::Jpg && matches!(jpeg_orientation(data), Some(5..=8));
+    Ok(if swap { Axes::new(height, width) } else { Axes::new(width, height) })
+}
+
+/// Count the frames of an animated GIF or PNG. Other images have one frame.
+fn count_frames(data: &[u8], format: ImageFormat) -> usize {
+    let frames = match format {
+        ImageFormat::Raster(RasterFormat::Gif) => gif_frames(data),
+        ImageFormat::Raster(RasterFormat::Png) => apng_frames(data),
+        _ => None,
+    };
+    frames.unwrap_or(1).max(1)
+}
+
+/// Count the image descriptors of a GIF file.
+fn gif_frames(data: &[u8]) -> Option<usize> {
+    // Skips a sequence of data sub-blocks and returns the rest.
+    fn skip_blocks(mut rest: &[u8]) -> Option<&[u8]> {
+        loop {
+            let (&len, tail) = rest.split_first()?;
+            rest = tail.get(usize::from(len)..)?;
+            if len == 0 {
+                return Some(rest);
+            }
+        }
+    }
+
+    // The size of a color table, given the packed field that announces it.
+    let table = |packed: u8| if packed & 0x80 != 0 { 3 << ((packed & 7) + 1) } else { 0 };
+
+    let packed = *data.get(10)?;
+    let mut rest = data.get(13 + table(packed)..)?;
+    let mut frames = 0;
+    loop {
+        let (&kind, tail) = rest.split_first()?;
+        rest = match kind {
+            // An extension with its label.
+            0x21 => skip_blocks(tail.get(1..)?)?,
+            // An image descriptor with an optional local color table and the
+            // minimum LZW code size.
+            0x2C => {
+                frames += 1;
+                let packed = *tail.get(8)?;
+                skip_blocks(tail.get(9 + table(packed) + 1..)?)?
+            }
+            // The trailer or data we don't understand.
+            _ => return Some(frames),
+        };
+    }
+}
+
+/// Read the number of frames of an animated PNG from its `acTL` chunk.
+fn apng_frames(data: &[u8]) -> Option<usize> {
+    let mut rest = data.strip_prefix(b"\x89PNG\r\n\x1a\n")?;
+    while rest.len() >= 12 {
+        let len = u32::from_be_bytes(rest[..4].try_into().ok()?) as usize;
+        let kind = &rest[4..8];
+        let chunk = rest.get(8..8 + len)?;
+        match kind {
+            b"acTL" if len == 8 => {
+                return Some(u32::from_be_bytes(chunk[..4].try_into().ok()?) as usize);
+            }
+            b"IDAT" | b"IEND" => return None,
+            _ => {}
+        }
+        rest = rest.get(12 + len..)?;
+    }
+    None
+}
+
+/// Iterate over the segments of a JPEG file up to the start of the image data.
+fn jpeg_segments(data: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    let mut rest = data.strip_prefix(&[0xFF, 0xD8]).unwrap_or_default();
+    std::iter::from_fn(move || {
+        let &[0xFF, marker, hi, lo, ..] = rest else { return None };
+        let len = u16::from_be_bytes([hi, lo]) as usize;
+        if marker == 0xDA || len < 2 || rest.len() < 2 + len {
+            return None;
+        }
+        let segment = &rest[4..2 + len];
+        rest = &rest[2 + len..];
+        Some((marker, segment))
+    })
+}
+
+/// Read the EXIF orientation (1 to 8) of a JPEG file.
+fn jpeg_orientation(data: &[u8]) -> Option<u16> {
+    let (_, segment) = jpeg_segments(data)
+        .find(|(marker, segment)| *marker == 0xE1 && segment.starts_with(b"Exif\0\0"))?;
+
+    let tiff = &segment[6..];
+    let big = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+
+    let u16_at = |i: usize| {
+        let bytes = [*tiff.get(i)?, *tiff.get(i + 1)?];
+        Some(if big { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    };
+    let u32_at = |i: usize| {
+        let bytes = tiff.get(i..i + 4)?.try_into().ok()?;
+        Some(if big { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    };
+
+    // Search the first image file directory for the orientation tag.
+    let ifd = u32_at(4)? as usize;
+    let count = u16_at(ifd)? as usize;
+    (0..count)
+        .map(|i| ifd + 2 + 12 * i)
+        .find(|&entry| u16_at(entry) == Some(0x0112))
+        .and_then(|entry| u16_at(entry + 8))
+        .filter(|orientation| (1..=8).contains(orientation))
+}
+
+/// Read the resolution of a JPEG file from its JFIF header.
+fn jpeg_dpi(data: &[u8]) -> Option<f64> {
+    let (_, segment) = jpeg_segments(data)
+        .find(|(marker, segment)| *marker == 0xE0 && segment.starts_with(b"JFIF\0"))?;
+    let &[unit, hi, lo] = segment.get(7..10)? else { return None };
+    let density = u16::from_be_bytes([hi, lo]) as f64;
+    match unit {
+        1 if density > 0.0 => Some(density),
+        2 if density > 0.0 => Some(density * 2.54),
+        _ => None,
+    }
+}
+
+/// Read the color space of a JPEG file from its frame header.
+fn jpeg_color_space(data: &[u8]) -> Option<ColorSpace> {
+    let (_, segment) = jpeg_segments(data).find(|(marker, _)| {
+        matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC)
+    })?;
+    match segment.get(5)? {
+        1 => Some(ColorSpace::Luma),
+        3 => Some(ColorSpace::Rgb),
+        4 => Some(ColorSpace::Cmyk),
+        _ => None,
+    }
+}
+
+/// Read the color space of a PNG file from its `IHDR` chunk.
+fn png_color_space(data: &[u8]) -> Option<ColorSpace> {
+    let header = data.strip_prefix(b"\x89PNG\r\n\x1a\n")?;
+    if header.get(4..8)? != b"IHDR" {
+        return None;
+    }
+    match header.get(17)? {
+        0 | 4 => Some(ColorSpace::Luma),
+        2 | 3 | 6 => Some(ColorSpace::Rgb),
+        _ => None,
+    }
+}
+
+/// Read the resolution of a PNG file from its `pHYs` chunk.
+fn png_dpi(data: &[u8]) -> Option<f64> {
+    let mut rest = data.strip_prefix(b"\x89PNG\r\n\x1a\n")?;
+    while rest.len() >= 12 {
+        let len = u32::from_be_bytes(rest[..4].try_into().ok()?) as usize;
+        let kind = &rest[4..8];
+        let chunk = rest.get(8..8 + len)?;
+        match kind {
+            b"pHYs" if len == 9 && chunk[8] == 1 => {
+                let ppm = u32::from_be_bytes(chunk[..4].try_into().ok()?) as f64;
+                return (ppm > 0.0).then_some(ppm * 0.0254);
+            }
+            b"IDAT" | b"IEND" => return None,
+            _ => {}
+        }
+        rest = rest.get(12 + len..)?;
+    }
+    None
 }
 
 /// Decode an SVG image.
@@ -447,3 +875,85 @@ fn format_usvg_error(error: usvg::Error) -> EcoString {
         usvg::Error::ParsingFailed(error) => format_xml_like_error("svg", error),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_raster_round_trip() {
+        let pixels = image::RgbaImage::from_fn(3, 2, |x, y| {
+            image::Rgba([x as u8, y as u8, 7, 255])
+        });
+        let dynamic = image::DynamicImage::ImageRgba8(pixels);
+        let icc = IccProfile(vec![1, 2, 3]);
+        let stored = write_stored(&dynamic, Some(&icc)).unwrap();
+
+        let (read, read_icc) = read_stored(&stored).unwrap();
+        assert_eq!(read.as_bytes(), dynamic.as_bytes());
+        assert_eq!((read.width(), read.height()), (3, 2));
+        assert_eq!(read_icc.unwrap().0, icc.0);
+
+        // Truncated entries are rejected.
+        assert!(read_stored(&stored[..stored.len() - 1]).is_none());
+        assert!(read_stored(b"TPX1").is_none());
+    }
+
+    /// The position right after the first occurrence of a needle.
+    fn after(data: &[u8], needle: &[u8]) -> usize {
+        data.windows(needle.len()).position(|w| w == needle).unwrap() + needle.len()
+    }
+
+    #[test]
+    fn test_jpeg_metadata() {
+        // A 16x8 pixel JPEG with 144 DPI that is rotated by 90 degrees.
+        let data = include_bytes!("../assets/files/rotated.jpg");
+        assert_eq!(jpeg_orientation(data), Some(6));
+        assert_eq!(jpeg_dpi(data), Some(144.0));
+
+        let format = ImageFormat::Raster(RasterFormat::Jpg);
+        let image = Image::new(Buffer::from_static(data), format, None).unwrap();
+        assert_eq!(image.size(), Axes::new(8, 16));
+        assert_eq!(image.dpi(), Some(144.0));
+        assert_eq!(image.decoded().unwrap().size(), Axes::new(8, 16));
+
+        // The density may also be given per centimeter or not at all.
+        let unit = after(data, b"JFIF\0") + 2;
+        let mut edited = data.to_vec();
+        edited[unit] = 2;
+        assert_eq!(jpeg_dpi(&edited), Some(144.0 * 2.54));
+        edited[unit] = 0;
+        assert_eq!(jpeg_dpi(&edited), None);
+
+        // Orientations that don't exist are ignored.
+        let value = after(data, &[0x01, 0x12, 0x00, 0x03]) + 5;
+        let mut edited = data.to_vec();
+        edited[value] = 1;
+        assert_eq!(jpeg_orientation(&edited), Some(1));
+        edited[value] = 9;
+        assert_eq!(jpeg_orientation(&edited), None);
+        assert_eq!(jpeg_orientation(b"\xFF\xD8\xFF\xD9"), None);
+    }
+
+    #[test]
+    fn test_png_dpi() {
+        // A 60x30 pixel PNG with 5669 pixels per meter.
+        let data = include_bytes!("../assets/files/dpi.png");
+        let dpi = 5669.0 * 0.0254;
+        assert_eq!(png_dpi(data), Some(dpi));
+
+        let format = ImageFormat::Raster(RasterFormat::Png);
+        let image = Image::new(Buffer::from_static(data), format, None).unwrap();
+        assert_eq!(image.size(), Axes::new(60, 30));
+        assert_eq!(image.dpi(), Some(dpi));
+
+        // A density without a unit only gives the aspect ratio of the pixels.
+        let mut edited = data.to_vec();
+        edited[after(data, b"pHYs") + 8] = 0;
+        assert_eq!(png_dpi(&edited), None);
+
+        // Images without a `pHYs` chunk don't specify their resolution.
+        let data = include_bytes!("../assets/files/truncated.png");
+        assert_eq!(png_dpi(data), None);
+    }
+}
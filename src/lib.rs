@@ -19,6 +19,14 @@
 //!   These frames can finally be exported into an output format (currently
 //!   supported are [PDF] and [raster images]).
 //!
+//! # Instrumentation
+//! With the `tracing` feature, all of these steps emit `tracing` spans and
+//! events. Parsing and evaluation spans carry the `path` of the file they
+//! process and page layout and PDF export report the `page` they work on, so
+//! that applications embedding the compiler can install a subscriber to show
+//! progress or collect timings. Without the feature, no instrumentation is
+//! compiled in.
+//!
 //! [tokens]: syntax::SyntaxKind
 //! [parsed]: syntax::parse
 //! [syntax tree]: syntax::SyntaxNode
@@ -52,19 +60,21 @@ pub mod image;
 pub mod model;
 pub mod syntax;
 
+use std::num::NonZeroUsize;
 use std::path::Path;
 
 use comemo::{Prehashed, Track, TrackedMut};
 
-use crate::diag::{FileResult, SourceResult};
+use crate::diag::{check_cancelled, FileError, FileResult, SourceResult, SourceWarning};
 use crate::doc::Document;
 use crate::eval::{Datetime, Library, Route, Tracer};
+use crate::export::Target;
 use crate::font::{Font, FontBook};
 use crate::syntax::{Source, SourceId};
 use crate::util::Buffer;
 
 /// Compile a source file into a fully layouted document.
-#[tracing::instrument(skip(world))]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(world)))]
 pub fn compile(world: &dyn World) -> SourceResult<Document> {
     let route = Route::default();
     let mut tracer = Tracer::default();
@@ -74,6 +84,7 @@ pub fn compile(world: &dyn World) -> SourceResult<Document> {
     let mut tracer = tracer.track_mut();
 
     // Evaluate the source file into a module.
+    #[cfg(feature = "tracing")]
     tracing::info!("Starting evaluation");
     let module = eval::eval(
         world,
@@ -83,9 +94,59 @@ pub fn compile(world: &dyn World) -> SourceResult<Document> {
     )?;
 
     // Typeset the module's contents.
+    check_cancelled(world)?;
     model::typeset(world, tracer, &module.content())
 }
 
+/// Collect the warnings raised while compiling a source file, like those
+/// about deprecated definitions or missing fonts.
+///
+/// Returns no warnings if the file fails to compile, since the errors are
+/// reported by [`compile`] then. Evaluation and typesetting are memoized, so
+/// collecting the warnings after compiling is cheap.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(world)))]
+pub fn warnings(world: &dyn World) -> Vec<SourceWarning> {
+    let route = Route::default();
+    let mut tracer = Tracer::default();
+    let world = world.track();
+    let result = {
+        let mut tracer = tracer.track_mut();
+        eval::eval(
+            world,
+            route.track(),
+            TrackedMut::reborrow_mut(&mut tracer),
+            world.main(),
+        )
+        .and_then(|module| model::typeset(world, tracer, &module.content()))
+    };
+    match result {
+        Ok(_) => tracer.warnings(),
+        Err(_) => vec![],
+    }
+}
+
+/// Check the structure of a source file for likely mistakes.
+///
+/// The result includes the [compilation warnings](warnings). Returns no
+/// warnings if the file fails to evaluate, since the errors are reported by
+/// [`compile`] then. Compilation is memoized, so linting after compiling is
+/// cheap.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(world)))]
+pub fn lint(world: &dyn World) -> Vec<SourceWarning> {
+    let mut warnings = warnings(world);
+    let route = Route::default();
+    let mut tracer = Tracer::default();
+    let world = world.track();
+    let result = eval::eval(world, route.track(), tracer.track_mut(), world.main());
+    match result {
+        Ok(module) => {
+            warnings.extend(model::lint(&world.library().items, &module.content()));
+            warnings
+        }
+        Err(_) => vec![],
+    }
+}
+
 /// The environment in which typesetting occurs.
 #[comemo::track]
 pub trait World {
@@ -117,9 +178,72 @@ pub trait World {
     /// Try to access a file at a path.
     fn file(&self, path: &Path) -> FileResult<Buffer>;
 
+    /// Try to download a file from an `http` or `https` URL.
+    ///
+    /// The world decides whether the network may be accessed at all and may
+    /// serve files from a cache instead.
+    ///
+    /// Defaults to failing because no network access is available.
+    fn fetch(&self, url: &str) -> FileResult<Buffer> {
+        let _ = url;
+        Err(FileError::Network("network access is not available".into()))
+    }
+
     /// Get the current date.
     ///
     /// If no offset is specified, the local date should be chosen. Otherwise,
     /// the UTC date should be chosen with the corresponding offset in hours.
     fn today(&self, offset: Option<i64>) -> Option<Datetime>;
+
+    /// Whether the compilation should be aborted.
+    ///
+    /// The compiler checks this between phases, layout iterations and pages
+    /// and fails with an error once it returns `true`. PDF export checks it
+    /// between pages when they are pushed with
+    /// [`PdfExporter::try_push`](export::PdfExporter::try_push). This lets
+    /// editors and services abort stale compilations promptly.
+    ///
+    /// Defaults to `false`.
+    fn cancelled(&self) -> bool {
+        false
+    }
+
+    /// How many pages of the document are needed.
+    ///
+    /// Once layout has produced this many pages, it stops laying out the rest
+    /// of the document. It may produce a few more pages, for instance when
+    /// footnotes move to the next page. Previews can use this to only lay out
+    /// the pages up to the end of their viewport after an edit, while pages
+    /// before it are mostly reused from the incremental cache. Content that
+    /// depends on later pages, like the final value of a counter, is then
+    /// computed as if the document ended there.
+    ///
+    /// Defaults to `None`, meaning that all pages are needed.
+    fn page_limit(&self) -> Option<NonZeroUsize> {
+        None
+    }
+
+    /// Whether images that fail to load are replaced by placeholders.
+    ///
+    /// A placeholder is a box showing the image's path, so that previews keep
+    /// working while assets are still being prepared. Otherwise, a missing
+    /// image is an error. Likewise, text for which none of the requested font
+    /// families is available is shown in a fallback font with a
+    /// [warning](crate::warnings), even if fallback is disabled.
+    ///
+    /// Defaults to `false`.
+    fn placeholders(&self) -> bool {
+        false
+    }
+
+    /// The format the document is going to be exported into.
+    ///
+    /// Since this is queried through the tracked world, content that depends
+    /// on the target is only recompiled when the target actually changes, and
+    /// memoized results are never shared across targets.
+    ///
+    /// Defaults to [`Target::Pdf`].
+    fn target(&self) -> Target {
+        Target::Pdf
+    }
 }
@@ -1,10 +1,13 @@
 use std::any::TypeId;
 use std::fmt::{self, Debug, Formatter, Write};
+use std::hash::{Hash, Hasher};
 use std::iter::Sum;
+use std::mem;
 use std::ops::{Add, AddAssign};
 
 use comemo::Prehashed;
 use ecow::{eco_format, EcoString, EcoVec};
+use siphasher::sip128::{Hasher128, SipHasher13};
 
 use super::{
     element, Behave, Behaviour, ElemFunc, Element, Fold, Guard, Label, Locatable,
@@ -367,7 +370,7 @@ impl Content {
     /// Queries the content tree for all elements that match the given selector.
     ///
     /// Elements produced in `show` rules will not be included in the results.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn query(&self, selector: Selector) -> Vec<&Content> {
         let mut results = Vec::new();
         self.traverse(&mut |element| {
@@ -382,7 +385,7 @@ impl Content {
     /// selector.
     ///
     /// Elements produced in `show` rules will not be included in the results.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn query_first(&self, selector: Selector) -> Option<&Content> {
         let mut result = None;
         self.traverse(&mut |element| {
@@ -435,6 +438,32 @@ impl Content {
             }
         }
     }
+
+    /// Compute a 128-bit hash of the content that ignores spans.
+    ///
+    /// Unlike the regular hash, this stays the same when the content is
+    /// evaluated again from a source file in which only unrelated parts
+    /// changed. It is thus suited to detect real changes in a document.
+    pub fn structural_hash(&self) -> u128 {
+        let mut state = SipHasher13::new();
+        self.hash_structure(&mut state);
+        state.finish128().as_u128()
+    }
+
+    /// Feed the span-independent structure of this content into the hasher.
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        self.func.hash(state);
+        for attr in &self.attrs {
+            mem::discriminant(attr).hash(state);
+            match attr {
+                Attr::Span(_) => {}
+                Attr::Value(value) => value.hash_structure(state),
+                Attr::Child(child) => child.hash_structure(state),
+                Attr::Styles(styles) => styles.hash_structure(state),
+                other => other.hash(state),
+            }
+        }
+    }
 }
 
 impl Debug for Content {
@@ -612,3 +641,28 @@ fn missing_field_no_default(key: &str) -> EcoString {
         Str::from(key)
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Property;
+    use crate::syntax::SourceId;
+    use crate::util::hash128;
+
+    #[test]
+    fn test_structural_hash_ignores_spans() {
+        let make = |number| {
+            let span = Span::new(SourceId::from_u16(0), number);
+            let property = Property::new(SequenceElem::func(), "key", 1);
+            Content::sequence([Content::empty().spanned(span), Content::empty()])
+                .with_field("body", Content::empty().spanned(span))
+                .styled_with_map(Styles::from(Style::from(property)).spanned(span))
+                .spanned(span)
+        };
+
+        let (a, b) = (make(2), make(3));
+        assert_ne!(hash128(&a), hash128(&b));
+        assert_eq!(a.structural_hash(), b.structural_hash());
+        assert_ne!(a.structural_hash(), make(2).with_field("key", 2).structural_hash());
+    }
+}
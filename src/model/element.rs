@@ -4,9 +4,12 @@ use std::hash::{Hash, Hasher};
 
 use once_cell::sync::Lazy;
 
-use super::{Content, Selector, Styles};
-use crate::diag::SourceResult;
+use ecow::EcoString;
+
+use super::{element, Content, Locatable, Selector, Show, StyleChain, Styles, Vt};
+use crate::diag::{bail, At, SourceResult};
 use crate::eval::{cast, Args, Dict, Func, FuncInfo, Value, Vm};
+use crate::syntax::{Span, Spanned};
 
 /// A document element.
 pub trait Element: Construct + Set + Sized + 'static {
@@ -132,3 +135,78 @@ pub struct NativeElemFunc {
     /// Details about the function.
     pub info: Lazy<FuncInfo>,
 }
+
+/// An element type declared in Typst code.
+///
+/// Instances of such an element are [`UserElem`]s whose `element` field holds
+/// the function that constructed them. This way, selectors, show rules,
+/// counters and queries can tell them apart like native elements.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct UserElemFunc {
+    /// The element's name.
+    pub name: EcoString,
+    /// The element's fields in order, with the names of their expected types.
+    pub fields: Vec<(EcoString, Option<EcoString>)>,
+    /// How to display the element if no show rule applies.
+    pub display: Option<Func>,
+    /// Where the element was declared. Distinguishes elements with the same
+    /// name and fields.
+    pub span: Span,
+}
+
+impl UserElemFunc {
+    /// Construct an instance of the element created by `func`.
+    ///
+    /// Fields can be given positionally in declaration order or by name. Fields
+    /// that are not given are `{none}`.
+    pub fn construct(&self, func: &Func, args: &mut Args) -> SourceResult<Content> {
+        let mut content = Content::new(UserElem::func());
+        content.push_field("element", func.clone());
+        for (name, ty) in &self.fields {
+            let Some(Spanned { v, span }) = (match args.named::<Spanned<Value>>(name)? {
+                Some(value) => Some(value),
+                None => args.eat()?,
+            }) else {
+                content.push_field(name.clone(), Value::None);
+                continue;
+            };
+
+            let value = match ty.as_deref() {
+                Some("content") => Value::Content(v.cast::<Content>().at(span)?),
+                Some(ty) if v.type_name() != ty => {
+                    bail!(span, "expected {}, found {}", ty, v.type_name())
+                }
+                _ => v,
+            };
+
+            content.push_field(name.clone(), value);
+        }
+
+        Ok(content)
+    }
+}
+
+/// An instance of an element type declared in Typst code.
+///
+/// Display: Element
+/// Category: special
+#[element(Locatable, Show)]
+pub struct UserElem {}
+
+impl UserElem {
+    /// The function that constructed this element.
+    pub fn element(&self) -> Func {
+        self.0.field("element").unwrap().cast().unwrap()
+    }
+}
+
+impl Show for UserElem {
+    fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        let func = self.element();
+        let display = func.user_element().and_then(|elem| elem.display.clone());
+        Ok(match display {
+            Some(display) => display.call_vt(vt, [self.0.clone()])?.display(),
+            None => self.0.field("body").map(Value::display).unwrap_or_default(),
+        })
+    }
+}
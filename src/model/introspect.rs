@@ -12,7 +12,7 @@ use super::{Content, Selector};
 use crate::diag::{bail, StrResult};
 use crate::doc::{Frame, FrameItem, Meta, Position};
 use crate::eval::{cast, Value};
-use crate::geom::{Point, Transform};
+use crate::geom::{Point, Size, Transform};
 use crate::model::Label;
 use crate::util::NonZeroExt;
 
@@ -164,8 +164,9 @@ impl<'a> Locator<'a> {
 pub struct Introspector {
     /// The number of pages in the document.
     pages: usize,
-    /// All introspectable elements.
-    elems: IndexMap<Location, (Prehashed<Content>, Position)>,
+    /// All introspectable elements with their positions and the sizes of the
+    /// frames they were first laid out into.
+    elems: IndexMap<Location, (Prehashed<Content>, Position, Size)>,
     /// The page numberings, indexed by page number minus 1.
     page_numberings: Vec<Value>,
     /// Caches queries done on the introspector. This is important because
@@ -177,23 +178,30 @@ pub struct Introspector {
 
 impl Introspector {
     /// Create a new introspector.
-    #[tracing::instrument(skip(frames))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(frames)))]
     pub fn new(frames: &[Frame]) -> Self {
         let mut introspector = Self {
-            pages: frames.len(),
+            pages: 0,
             elems: IndexMap::new(),
             page_numberings: vec![],
             queries: RefCell::default(),
         };
-        for (i, frame) in frames.iter().enumerate() {
-            let page = NonZeroUsize::new(1 + i).unwrap();
-            introspector.extract(frame, page, Transform::identity());
+        for frame in frames {
+            introspector.push(frame);
         }
         introspector
     }
 
+    /// Add the metadata of the next page.
+    pub fn push(&mut self, frame: &Frame) {
+        self.pages += 1;
+        self.queries.get_mut().clear();
+        let page = NonZeroUsize::new(self.pages).unwrap();
+        self.extract(frame, page, Transform::identity());
+    }
+
     /// Extract metadata from a frame.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn extract(&mut self, frame: &Frame, page: NonZeroUsize, ts: Transform) {
         for (pos, item) in frame.items() {
             match item {
@@ -203,13 +211,17 @@ impl Introspector {
                         .pre_concat(group.transform);
                     self.extract(&group.frame, page, ts);
                 }
-                FrameItem::Meta(Meta::Elem(content), _)
+                FrameItem::Meta(Meta::Elem(content), size)
                     if !self.elems.contains_key(&content.location().unwrap()) =>
                 {
                     let pos = pos.transform(ts);
                     let ret = self.elems.insert(
                         content.location().unwrap(),
-                        (Prehashed::new(content.clone()), Position { page, point: pos }),
+                        (
+                            Prehashed::new(content.clone()),
+                            Position { page, point: pos },
+                            *size,
+                        ),
                     );
                     assert!(ret.is_none(), "duplicate locations");
                 }
@@ -223,12 +235,12 @@ impl Introspector {
 
     /// Iterate over all locatable elements.
     pub fn all(&self) -> impl Iterator<Item = &Prehashed<Content>> + '_ {
-        self.elems.values().map(|(c, _)| c)
+        self.elems.values().map(|(c, _, _)| c)
     }
 
     /// Get an element by its location.
     fn get(&self, location: &Location) -> Option<&Prehashed<Content>> {
-        self.elems.get(location).map(|(elem, _)| elem)
+        self.elems.get(location).map(|(elem, _, _)| elem)
     }
 
     /// Get the index of this element among all.
@@ -340,9 +352,18 @@ impl Introspector {
     pub fn position(&self, location: Location) -> Position {
         self.elems
             .get(&location)
-            .map(|(_, loc)| *loc)
+            .map(|(_, loc, _)| *loc)
             .unwrap_or(Position { page: NonZeroUsize::ONE, point: Point::zero() })
     }
+
+    /// Find the size of the frame the element at the given location was first
+    /// laid out into.
+    pub fn size(&self, location: Location) -> Size {
+        self.elems
+            .get(&location)
+            .map(|(_, _, size)| *size)
+            .unwrap_or_default()
+    }
 }
 
 impl Default for Introspector {
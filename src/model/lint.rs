@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+
+use ecow::{eco_format, EcoString};
+
+use super::{Content, ElemFunc, Label};
+use crate::diag::SourceWarning;
+use crate::eval::{LangItems, Value};
+use crate::syntax::Span;
+
+/// Check content for likely mistakes in the structure of the document.
+///
+/// This warns about:
+/// - headings that skip a level, like a third-level heading directly below a
+///   first-level one,
+/// - sections without any content before the next section starts,
+/// - labels that are attached to more than one element,
+/// - images without alternative text, which the tagged PDF export can't
+///   describe to screen readers.
+///
+/// The checks only see the content as it was written. Content produced by
+/// show rules is not considered.
+pub fn lint(items: &LangItems, content: &Content) -> Vec<SourceWarning> {
+    let mut linter = Linter {
+        heading: items.heading_func,
+        image: items.image_func,
+        space: (items.space)().func(),
+        parbreak: (items.parbreak)().func(),
+        tokens: vec![],
+        labels: HashMap::new(),
+        warnings: vec![],
+    };
+    linter.walk(content);
+    linter.finish()
+}
+
+/// Walks the content tree and collects warnings.
+struct Linter {
+    heading: ElemFunc,
+    image: ElemFunc,
+    space: ElemFunc,
+    parbreak: ElemFunc,
+    tokens: Vec<Token>,
+    labels: HashMap<Label, Span>,
+    warnings: Vec<SourceWarning>,
+}
+
+/// A piece of content that is relevant for the structure.
+enum Token {
+    /// A heading with its level.
+    Heading(NonZeroUsize, Span),
+    /// Anything other than a heading or whitespace.
+    Content,
+}
+
+impl Linter {
+    /// Visit content and its children in document order.
+    fn walk(&mut self, content: &Content) {
+        if let Some(label) = content.label() {
+            let span = content.span();
+            if let Some(first) = self.labels.get(label) {
+                if !first.is_detached() && !span.is_detached() {
+                    self.warn(
+                        span,
+                        eco_format!("label `{label:?}` occurs multiple times"),
+                    );
+                }
+            } else {
+                self.labels.insert(label.clone(), span);
+            }
+        }
+
+        if let Some(children) = content.to_sequence() {
+            for child in children {
+                self.walk(child);
+            }
+            return;
+        }
+
+        if let Some((child, _)) = content.to_styled() {
+            self.walk(child);
+            return;
+        }
+
+        let func = content.func();
+        if func == self.heading {
+            let level = content.cast_field("level").unwrap_or(NonZeroUsize::ONE);
+            self.tokens.push(Token::Heading(level, content.span()));
+            return;
+        }
+
+        if func == self.image
+            && !matches!(content.field("alt"), Some(Value::Str(alt)) if !alt.is_empty())
+        {
+            self.warn(content.span(), "image has no alternative text");
+        }
+
+        let mut leaf = true;
+        for (_, value) in content.fields_ref() {
+            leaf &= !self.walk_value(value);
+        }
+
+        if leaf && func != self.space && func != self.parbreak {
+            self.tokens.push(Token::Content);
+        }
+    }
+
+    /// Visit the content in a field value. Returns whether there was any.
+    fn walk_value(&mut self, value: &Value) -> bool {
+        match value {
+            Value::Content(content) => {
+                self.walk(content);
+                true
+            }
+            Value::Array(array) => {
+                let mut any = false;
+                for value in array {
+                    any |= self.walk_value(value);
+                }
+                any
+            }
+            _ => false,
+        }
+    }
+
+    /// Check the collected headings.
+    fn finish(mut self) -> Vec<SourceWarning> {
+        let tokens = std::mem::take(&mut self.tokens);
+        let mut previous: Option<NonZeroUsize> = None;
+
+        for (i, token) in tokens.iter().enumerate() {
+            let &Token::Heading(level, span) = token else { continue };
+
+            if let Some(prev) = previous {
+                if level.get() > prev.get() + 1 {
+                    self.warn(
+                        span,
+                        eco_format!(
+                            "heading level jumps from {} to {}",
+                            prev.get(),
+                            level.get()
+                        ),
+                    );
+                }
+            }
+
+            let empty = match tokens.get(i + 1) {
+                Some(Token::Heading(next, _)) => *next <= level,
+                Some(Token::Content) => false,
+                None => true,
+            };
+
+            if empty {
+                self.warn(span, "section is empty");
+            }
+
+            previous = Some(level);
+        }
+
+        self.warnings
+    }
+
+    /// Record a warning, unless it points into nowhere.
+    fn warn(&mut self, span: Span, message: impl Into<EcoString>) {
+        if !span.is_detached() {
+            self.warnings.push(SourceWarning::new(span, message));
+        }
+    }
+}
@@ -4,6 +4,7 @@ mod content;
 mod element;
 mod introspect;
 mod label;
+mod lint;
 mod realize;
 mod selector;
 mod styles;
@@ -12,9 +13,12 @@ mod styles;
 pub use typst_macros::element;
 
 pub use self::content::{Content, MetaElem, PlainText};
-pub use self::element::{Construct, ElemFunc, Element, NativeElemFunc, Set};
+pub use self::element::{
+    Construct, ElemFunc, Element, NativeElemFunc, Set, UserElem, UserElemFunc,
+};
 pub use self::introspect::{Introspector, Location, Locator};
 pub use self::label::{Label, Unlabellable};
+pub use self::lint::lint;
 pub use self::realize::{
     applicable, realize, Behave, Behaviour, Finalize, Guard, Locatable, Show, Synthesize,
 };
@@ -28,19 +32,20 @@ use std::mem::ManuallyDrop;
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
 
-use crate::diag::{SourceError, SourceResult};
+use crate::diag::{check_cancelled, SourceError, SourceResult};
 use crate::doc::Document;
 use crate::eval::Tracer;
 use crate::World;
 
 /// Typeset content into a fully layouted document.
 #[comemo::memoize]
-#[tracing::instrument(skip(world, tracer, content))]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(world, tracer, content)))]
 pub fn typeset(
     world: Tracked<dyn World + '_>,
     mut tracer: TrackedMut<Tracer>,
     content: &Content,
 ) -> SourceResult<Document> {
+    #[cfg(feature = "tracing")]
     tracing::info!("Starting typesetting");
 
     let library = world.library();
@@ -57,7 +62,9 @@ pub fn typeset(
     // Relayout until all introspections stabilize.
     // If that doesn't happen within five attempts, we give up.
     loop {
-        tracing::info!("Layout iteration {iter}");
+        #[cfg(feature = "tracing")]
+        tracing::info!(iteration = iter, "Layout iteration");
+        check_cancelled(world)?;
 
         delayed = DelayedErrors::default();
 
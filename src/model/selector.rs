@@ -1,5 +1,7 @@
 use std::any::{Any, TypeId};
 use std::fmt::{self, Debug, Formatter, Write};
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::sync::Arc;
 
 use ecow::{eco_format, EcoString, EcoVec};
@@ -39,6 +41,34 @@ pub enum Selector {
 }
 
 impl Selector {
+    /// Feed the span-independent structure of the selector into the hasher.
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Self::Elem(func, dict) => {
+                func.hash(state);
+                dict.is_some().hash(state);
+                for (key, value) in dict.iter().flatten() {
+                    key.hash(state);
+                    value.hash_structure(state);
+                }
+            }
+            Self::Or(selectors) | Self::And(selectors) => {
+                state.write_usize(selectors.len());
+                for selector in selectors {
+                    selector.hash_structure(state);
+                }
+            }
+            Self::Before { selector, end: other, inclusive }
+            | Self::After { selector, start: other, inclusive } => {
+                selector.hash_structure(state);
+                other.hash_structure(state);
+                inclusive.hash(state);
+            }
+            other => other.hash(state),
+        }
+    }
+
     /// Define a simple text selector.
     pub fn text(text: &str) -> Self {
         Self::Regex(Regex::new(&regex::escape(text)).unwrap())
@@ -154,9 +184,8 @@ impl Debug for Selector {
 cast! {
     type Selector: "selector",
     func: Func => func
-        .element()
-        .ok_or("only element functions can be used as selectors")?
-        .select(),
+        .select(None)
+        .ok_or("only element functions can be used as selectors")?,
     label: Label => Self::Label(label),
     text: EcoString => Self::text(&text),
     regex: Regex => Self::Regex(regex),
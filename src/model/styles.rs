@@ -1,10 +1,12 @@
 use std::fmt::{self, Debug, Formatter, Write};
+use std::hash::{Hash, Hasher};
 use std::iter;
 use std::mem;
 use std::ptr;
 
 use comemo::Prehashed;
 use ecow::{eco_vec, EcoString, EcoVec};
+use siphasher::sip128::{Hasher128, SipHasher13};
 
 use super::{Content, ElemFunc, Element, Selector, Vt};
 use crate::diag::{SourceResult, Trace, Tracepoint};
@@ -26,6 +28,11 @@ impl Styles {
         self.0.is_empty()
     }
 
+    /// Iterate over the contained styles.
+    pub fn iter(&self) -> impl Iterator<Item = &Style> {
+        self.0.iter().map(|style| &**style)
+    }
+
     /// Set an inner value for a style property.
     ///
     /// If the property needs folding and the value is already contained in the
@@ -77,6 +84,21 @@ impl Styles {
             Style::Recipe(recipe) => recipe.is_of(func).then_some(Some(recipe.span)),
         })
     }
+
+    /// Compute a 128-bit hash of the styles that ignores spans.
+    pub fn structural_hash(&self) -> u128 {
+        let mut state = SipHasher13::new();
+        self.hash_structure(&mut state);
+        state.finish128().as_u128()
+    }
+
+    /// Feed the span-independent structure of the styles into the hasher.
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.len());
+        for style in &self.0 {
+            style.hash_structure(state);
+        }
+    }
 }
 
 impl From<Style> for Styles {
@@ -176,6 +198,11 @@ impl Property {
     pub fn is_of(&self, element: ElemFunc) -> bool {
         self.element == element
     }
+
+    /// The property's value.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
 }
 
 impl Debug for Property {
@@ -270,6 +297,32 @@ pub enum Transform {
     Style(Styles),
 }
 
+impl Style {
+    /// Feed the span-independent structure of the style into the hasher.
+    fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Self::Property(property) => {
+                property.element.hash(state);
+                property.name.hash(state);
+                property.value.hash_structure(state);
+            }
+            Self::Recipe(recipe) => {
+                recipe.selector.is_some().hash(state);
+                if let Some(selector) = &recipe.selector {
+                    selector.hash_structure(state);
+                }
+                mem::discriminant(&recipe.transform).hash(state);
+                match &recipe.transform {
+                    Transform::Content(content) => content.hash_structure(state),
+                    Transform::Style(styles) => styles.hash_structure(state),
+                    Transform::Func(func) => func.hash_structure(state),
+                }
+            }
+        }
+    }
+}
+
 impl Debug for Transform {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
@@ -430,6 +483,15 @@ impl<'a> StyleChain<'a> {
         suffix
     }
 
+    /// Compute a 128-bit hash of the chain that ignores spans.
+    pub fn structural_hash(self) -> u128 {
+        let mut state = SipHasher13::new();
+        for entry in self.entries() {
+            entry.hash_structure(&mut state);
+        }
+        state.finish128().as_u128()
+    }
+
     /// Iterate over the entries of the chain.
     fn entries(self) -> Entries<'a> {
         Entries { inner: [].as_slice().iter(), links: self.links() }
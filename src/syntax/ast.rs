@@ -1,6 +1,9 @@
 //! A typed layer over the untyped syntax tree.
 //!
-//! The AST is rooted in the [`Markup`] node.
+//! The AST is rooted in the [`Markup`] node. Each typed node wraps an untyped
+//! [`SyntaxNode`] and can be created from one with [`SyntaxNode::cast`]. The
+//! enums in this module may gain variants in any release and are thus marked
+//! as non-exhaustive.
 
 use std::num::NonZeroUsize;
 use std::ops::Deref;
@@ -75,6 +78,7 @@ impl Markup {
 
 /// An expression in markup, math or code.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum Expr {
     /// Plain text without markup.
     Text(Text),
@@ -1004,6 +1008,7 @@ impl Numeric {
 
 /// Unit of a numeric value.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
 pub enum Unit {
     /// An absolute length unit.
     Length(AbsUnit),
@@ -1129,6 +1134,7 @@ impl Array {
 
 /// An item in an array.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum ArrayItem {
     /// A bare expression: `12`.
     Pos(Expr),
@@ -1166,6 +1172,7 @@ impl Dict {
 
 /// An item in an dictionary expression.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum DictItem {
     /// A named pair: `thickness: 3pt`.
     Named(Named),
@@ -1258,6 +1265,7 @@ impl Unary {
 
 /// A unary operator.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
 pub enum UnOp {
     /// The plus operator: `+`.
     Pos,
@@ -1281,7 +1289,7 @@ impl UnOp {
     /// The precedence of this operator.
     pub fn precedence(self) -> usize {
         match self {
-            Self::Pos | Self::Neg => 7,
+            Self::Pos | Self::Neg => 8,
             Self::Not => 4,
         }
     }
@@ -1331,6 +1339,7 @@ impl Binary {
 
 /// A binary operator.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
 pub enum BinOp {
     /// The addition operator: `+`.
     Add,
@@ -1362,6 +1371,8 @@ pub enum BinOp {
     In,
     /// The inversed containment operator: `not in`.
     NotIn,
+    /// The range operator: `..`.
+    Range,
     /// The add-assign operator: `+=`.
     AddAssign,
     /// The subtract-assign oeprator: `-=`.
@@ -1390,6 +1401,7 @@ impl BinOp {
             SyntaxKind::GtEq => Self::Geq,
             SyntaxKind::Eq => Self::Assign,
             SyntaxKind::In => Self::In,
+            SyntaxKind::Dots => Self::Range,
             SyntaxKind::PlusEq => Self::AddAssign,
             SyntaxKind::HyphEq => Self::SubAssign,
             SyntaxKind::StarEq => Self::MulAssign,
@@ -1401,10 +1413,11 @@ impl BinOp {
     /// The precedence of this operator.
     pub fn precedence(self) -> usize {
         match self {
-            Self::Mul => 6,
-            Self::Div => 6,
-            Self::Add => 5,
-            Self::Sub => 5,
+            Self::Mul => 7,
+            Self::Div => 7,
+            Self::Add => 6,
+            Self::Sub => 6,
+            Self::Range => 5,
             Self::Eq => 4,
             Self::Neq => 4,
             Self::Lt => 4,
@@ -1440,6 +1453,7 @@ impl BinOp {
             Self::Geq => Assoc::Left,
             Self::In => Assoc::Left,
             Self::NotIn => Assoc::Left,
+            Self::Range => Assoc::Left,
             Self::Assign => Assoc::Right,
             Self::AddAssign => Assoc::Right,
             Self::SubAssign => Assoc::Right,
@@ -1465,6 +1479,7 @@ impl BinOp {
             Self::Geq => ">=",
             Self::In => "in",
             Self::NotIn => "not in",
+            Self::Range => "..",
             Self::Assign => "=",
             Self::AddAssign => "+=",
             Self::SubAssign => "-=",
@@ -1531,6 +1546,7 @@ impl Args {
 
 /// An argument to a function call.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum Arg {
     /// A positional argument: `12`.
     Pos(Expr),
@@ -1618,6 +1634,7 @@ node! {
 
 /// A parameter to a closure.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum Param {
     /// A positional parameter: `x`.
     Pos(Pattern),
@@ -1669,6 +1686,7 @@ impl Destructuring {
 
 /// The kind of an element in a destructuring pattern.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum DestructuringKind {
     /// An expression: `x`.
     Normal(Expr),
@@ -1702,6 +1720,7 @@ impl AstNode for DestructuringKind {
 
 /// The kind of a pattern.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum Pattern {
     /// A single expression: `x`.
     Normal(Expr),
@@ -1752,6 +1771,7 @@ node! {
 }
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum LetBindingKind {
     /// A normal binding: `let x = 1`.
     Normal(Pattern),
@@ -1942,7 +1962,7 @@ impl ModuleImport {
         self.0.children().find_map(|node| match node.kind() {
             SyntaxKind::Star => Some(Imports::Wildcard),
             SyntaxKind::ImportItems => {
-                let items = node.children().filter_map(SyntaxNode::cast).collect();
+                let items = node.cast::<ImportItems>()?.idents().collect();
                 Some(Imports::Items(items))
             }
             _ => Option::None,
@@ -1950,8 +1970,21 @@ impl ModuleImport {
     }
 }
 
+node! {
+    /// The items in an import: `a, b, c`.
+    ImportItems
+}
+
+impl ImportItems {
+    /// The identifiers of the imported items.
+    pub fn idents(&self) -> impl DoubleEndedIterator<Item = Ident> + '_ {
+        self.0.children().filter_map(SyntaxNode::cast)
+    }
+}
+
 /// The items that ought to be imported from a file.
 #[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
 pub enum Imports {
     /// All items in the scope of the file should be imported.
     Wildcard,
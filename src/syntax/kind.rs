@@ -1,8 +1,12 @@
 /// A syntactical building block of a Typst file.
 ///
 /// Can be created by the lexer or by the parser.
+///
+/// New kinds may be added in any release, so matches on this type need a
+/// wildcard arm.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum SyntaxKind {
     /// Markup.
     Markup,
@@ -262,6 +266,132 @@ pub enum SyntaxKind {
 }
 
 impl SyntaxKind {
+    /// All syntax kinds, in the order of their declaration.
+    pub const ALL: &'static [Self] = &[
+        Self::Markup,
+        Self::Text,
+        Self::Space,
+        Self::Linebreak,
+        Self::Parbreak,
+        Self::Escape,
+        Self::Shorthand,
+        Self::SmartQuote,
+        Self::Strong,
+        Self::Emph,
+        Self::Raw,
+        Self::Link,
+        Self::Label,
+        Self::Ref,
+        Self::RefMarker,
+        Self::Heading,
+        Self::HeadingMarker,
+        Self::ListItem,
+        Self::ListMarker,
+        Self::EnumItem,
+        Self::EnumMarker,
+        Self::TermItem,
+        Self::TermMarker,
+        Self::Equation,
+        Self::Math,
+        Self::MathIdent,
+        Self::MathAlignPoint,
+        Self::MathDelimited,
+        Self::MathAttach,
+        Self::MathFrac,
+        Self::MathRoot,
+        Self::Hashtag,
+        Self::LeftBrace,
+        Self::RightBrace,
+        Self::LeftBracket,
+        Self::RightBracket,
+        Self::LeftParen,
+        Self::RightParen,
+        Self::Comma,
+        Self::Semicolon,
+        Self::Colon,
+        Self::Star,
+        Self::Underscore,
+        Self::Dollar,
+        Self::Plus,
+        Self::Minus,
+        Self::Slash,
+        Self::Hat,
+        Self::Dot,
+        Self::Eq,
+        Self::EqEq,
+        Self::ExclEq,
+        Self::Lt,
+        Self::LtEq,
+        Self::Gt,
+        Self::GtEq,
+        Self::PlusEq,
+        Self::HyphEq,
+        Self::StarEq,
+        Self::SlashEq,
+        Self::Dots,
+        Self::Arrow,
+        Self::Root,
+        Self::Not,
+        Self::And,
+        Self::Or,
+        Self::None,
+        Self::Auto,
+        Self::Let,
+        Self::Set,
+        Self::Show,
+        Self::If,
+        Self::Else,
+        Self::For,
+        Self::In,
+        Self::While,
+        Self::Break,
+        Self::Continue,
+        Self::Return,
+        Self::Import,
+        Self::Include,
+        Self::As,
+        Self::Code,
+        Self::Ident,
+        Self::Bool,
+        Self::Int,
+        Self::Float,
+        Self::Numeric,
+        Self::Str,
+        Self::CodeBlock,
+        Self::ContentBlock,
+        Self::Parenthesized,
+        Self::Array,
+        Self::Dict,
+        Self::Named,
+        Self::Keyed,
+        Self::Unary,
+        Self::Binary,
+        Self::FieldAccess,
+        Self::FuncCall,
+        Self::Args,
+        Self::Spread,
+        Self::Closure,
+        Self::Params,
+        Self::LetBinding,
+        Self::SetRule,
+        Self::ShowRule,
+        Self::Conditional,
+        Self::WhileLoop,
+        Self::ForLoop,
+        Self::ModuleImport,
+        Self::ImportItems,
+        Self::ModuleInclude,
+        Self::LoopBreak,
+        Self::LoopContinue,
+        Self::FuncReturn,
+        Self::Destructuring,
+        Self::DestructAssignment,
+        Self::LineComment,
+        Self::BlockComment,
+        Self::Error,
+        Self::Eof,
+    ];
+
     /// Is this a bracket, brace, or parenthesis?
     pub fn is_grouping(self) -> bool {
         matches!(
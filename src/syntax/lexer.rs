@@ -648,6 +648,16 @@ fn keyword(ident: &str) -> Option<SyntaxKind> {
     })
 }
 
+/// Split markup into tokens without parsing it.
+///
+/// As there is no parser to switch modes, embedded code and math are lexed as
+/// markup, too. This is only used for benchmarking.
+#[doc(hidden)]
+pub fn tokenize(text: &str) -> impl Iterator<Item = SyntaxKind> + '_ {
+    let mut lexer = Lexer::new(text, LexMode::Markup);
+    std::iter::from_fn(move || Some(lexer.next()).filter(|&kind| kind != SyntaxKind::Eof))
+}
+
 /// Whether this character denotes a newline.
 #[inline]
 pub fn is_newline(character: char) -> bool {
@@ -1,6 +1,16 @@
 //! Syntax definition, parsing, and highlighting.
+//!
+//! This module is meant to be used by tools outside of the compiler, like
+//! linters, formatters and documentation generators. The untyped
+//! [`SyntaxNode`] tree is the source of truth: it covers every byte of the
+//! source text and each node knows its [kind](SyntaxKind) and [span](Span).
+//! The [`ast`] module provides a typed view of it and the [`visit`] module
+//! walks it. Breaking changes to these APIs only happen in semver-incompatible
+//! releases, while new kinds of nodes and expressions can be added in any
+//! release.
 
 pub mod ast;
+pub mod visit;
 
 mod kind;
 mod lexer;
@@ -11,7 +21,7 @@ mod source;
 mod span;
 
 pub use self::kind::SyntaxKind;
-pub use self::lexer::{is_ident, is_newline};
+pub use self::lexer::{is_ident, is_newline, tokenize};
 pub use self::node::{ErrorPos, LinkedChildren, LinkedNode, SyntaxNode};
 pub use self::parser::{parse, parse_code};
 pub use self::source::{Source, SourceId};
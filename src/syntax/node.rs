@@ -1,4 +1,5 @@
 use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::{Deref, Range};
 use std::rc::Rc;
 use std::sync::Arc;
@@ -109,6 +110,17 @@ impl SyntaxNode {
         }
     }
 
+    /// Feed the kinds and texts of the node and its descendants into the
+    /// hasher, ignoring their spans.
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        self.kind().hash(state);
+        self.text().hash(state);
+        state.write_usize(self.children().len());
+        for child in self.children() {
+            child.hash_structure(state);
+        }
+    }
+
     /// Whether the node can be cast to the given AST node.
     pub fn is<T: AstNode>(&self) -> bool {
         self.cast::<T>().is_some()
@@ -154,6 +166,32 @@ impl SyntaxNode {
         }
     }
 
+    /// Whether the two nodes share the same underlying storage.
+    ///
+    /// Inner and error nodes are reference-counted, so a clone of a tree and
+    /// the tree resulting from an incremental edit of that clone still share
+    /// all subtrees that weren't affected by the edit. This makes it cheap to
+    /// find out which results computed for a previous tree can be reused.
+    /// Leaf nodes aren't shared and are compared by value instead.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Repr::Leaf(a), Repr::Leaf(b)) => a == b,
+            (Repr::Inner(a), Repr::Inner(b)) => Arc::ptr_eq(a, b),
+            (Repr::Error(a), Repr::Error(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Whether the two nodes are equal apart from their spans.
+    pub fn spanless_eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Repr::Leaf(a), Repr::Leaf(b)) => a.spanless_eq(b),
+            (Repr::Inner(a), Repr::Inner(b)) => Arc::ptr_eq(a, b) || a.spanless_eq(b),
+            (Repr::Error(a), Repr::Error(b)) => a.spanless_eq(b),
+            _ => false,
+        }
+    }
+
     /// Set a synthetic span for the node and all its descendants.
     pub fn synthesize(&mut self, span: Span) {
         match &mut self.0 {
@@ -190,7 +228,7 @@ impl SyntaxNode {
     }
 
     /// Assign spans to each node.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub(super) fn numberize(
         &mut self,
         id: SourceId,
@@ -313,6 +351,11 @@ impl LeafNode {
     fn len(&self) -> usize {
         self.text.len()
     }
+
+    /// Whether the two leaf nodes are equal apart from their spans.
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.text == other.text
+    }
 }
 
 impl Debug for LeafNode {
@@ -508,6 +551,20 @@ impl InnerNode {
         }
     }
 
+    /// Whether the two inner nodes are equal apart from their spans.
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.len == other.len
+            && self.descendants == other.descendants
+            && self.erroneous == other.erroneous
+            && self.children.len() == other.children.len()
+            && self
+                .children
+                .iter()
+                .zip(&other.children)
+                .all(|(a, b)| a.spanless_eq(b))
+    }
+
     /// Update this node after changes were made to one of its children.
     fn update_parent(
         &mut self,
@@ -565,6 +622,11 @@ impl ErrorNode {
     fn len(&self) -> usize {
         self.text.len()
     }
+
+    /// Whether the two error nodes are equal apart from their spans.
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.message == other.message && self.text == other.text && self.pos == other.pos
+    }
 }
 
 impl Debug for ErrorNode {
@@ -873,8 +935,11 @@ impl std::error::Error for Unnumberable {}
 
 #[cfg(test)]
 mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::path::Path;
+
     use super::*;
-    use crate::syntax::Source;
+    use crate::syntax::{parse, Source};
 
     #[test]
     fn test_linked_node() {
@@ -891,6 +956,52 @@ mod tests {
         assert_eq!(prev.text(), "set");
     }
 
+    #[test]
+    fn test_hash_structure_ignores_spans() {
+        let hash = |source: &Source| {
+            let mut state = DefaultHasher::new();
+            source.root().hash_structure(&mut state);
+            state.finish()
+        };
+
+        let text = "#f(a, [b])";
+        let a = Source::detached(text);
+        let b = Source::new(SourceId::from_u16(1), Path::new("b.typ"), text.into());
+        assert_ne!(a.root().span(), b.root().span());
+        assert_eq!(hash(&a), hash(&b));
+        assert_ne!(hash(&a), hash(&Source::detached("#f(a, [c])")));
+    }
+
+    #[test]
+    fn test_subtree_sharing_after_edit() {
+        let mut source = Source::detached("#f[a]\n\n#g[b]");
+        let prev = source.root().clone();
+        source.edit(10..11, "c");
+
+        let first = |root: &SyntaxNode| root.children().nth(1).unwrap().clone();
+        let last = |root: &SyntaxNode| root.children().last().unwrap().clone();
+        assert!(first(&prev).ptr_eq(&first(source.root())));
+        assert!(!last(&prev).ptr_eq(&last(source.root())));
+        assert!(!prev.ptr_eq(source.root()));
+    }
+
+    #[test]
+    fn test_subtree_sharing_after_markup_reparse() {
+        let mut source = Source::detached("*a* *b* *c* *d*");
+        let prev = source.root().clone();
+        source.edit(9..10, "x");
+
+        // The neighbours of the edited node are reparsed, but stay shared
+        // because they came out the same.
+        let next = source.root();
+        let child = |root: &SyntaxNode, i| root.children().nth(i).unwrap().clone();
+        assert!(child(&prev, 2).ptr_eq(&child(next, 2)));
+        assert!(child(&prev, 6).ptr_eq(&child(next, 6)));
+        assert!(!child(&prev, 4).spanless_eq(&child(next, 4)));
+        assert!(next.spanless_eq(&parse(source.text())));
+        assert!(!next.spanless_eq(&prev));
+    }
+
     #[test]
     fn test_linked_node_non_trivia_leaf() {
         let source = Source::detached("#set fun(12pt, red)");
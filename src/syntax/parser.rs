@@ -32,6 +32,7 @@ fn markup(
     mut stop: impl FnMut(&Parser) -> bool,
 ) {
     let m = p.marker();
+    p.depth += 1;
     let mut nesting: usize = 0;
     while !p.eof() {
         match p.current() {
@@ -56,6 +57,7 @@ fn markup(
             p.unexpected();
         }
     }
+    p.depth -= 1;
     p.wrap(m, SyntaxKind::Markup);
 }
 
@@ -91,6 +93,12 @@ pub(super) fn reparse_markup(
 }
 
 fn markup_expr(p: &mut Parser, at_start: &mut bool) {
+    if p.depth >= MAX_DEPTH && p.at_nesting(*at_start) {
+        p.too_deep();
+        *at_start = false;
+        return;
+    }
+
     match p.current() {
         SyntaxKind::Space
         | SyntaxKind::Parbreak
@@ -243,6 +251,10 @@ fn math_expr(p: &mut Parser) {
 }
 
 fn math_expr_prec(p: &mut Parser, min_prec: usize, stop: SyntaxKind) {
+    if !p.descend() {
+        return;
+    }
+
     let m = p.marker();
     let mut continuable = false;
     match p.current() {
@@ -343,6 +355,8 @@ fn math_expr_prec(p: &mut Parser, min_prec: usize, stop: SyntaxKind) {
 
         p.wrap(m, kind);
     }
+
+    p.ascend();
 }
 
 fn maybe_delimited(p: &mut Parser, allow_fence: bool) -> bool {
@@ -589,6 +603,10 @@ fn code_expr_prec(
     min_prec: usize,
     allow_destructuring: bool,
 ) {
+    if !p.descend() {
+        return;
+    }
+
     let m = p.marker();
     if let (false, Some(op)) = (atomic, ast::UnOp::from_kind(p.current())) {
         p.eat();
@@ -650,6 +668,8 @@ fn code_expr_prec(
 
         break;
     }
+
+    p.ascend();
 }
 
 fn code_primary(p: &mut Parser, atomic: bool, allow_destructuring: bool) {
@@ -1372,6 +1392,12 @@ fn validate_pattern<'a>(
     }
 }
 
+/// How deeply markup, code, and math may nest before the parser gives up.
+///
+/// Keeps the parser and everything that walks the syntax tree recursively
+/// from overflowing the stack on pathological input.
+const MAX_DEPTH: usize = 256;
+
 /// Manages parsing of a stream of tokens.
 struct Parser<'s> {
     text: &'s str,
@@ -1383,6 +1409,7 @@ struct Parser<'s> {
     nodes: Vec<SyntaxNode>,
     stop_at_newline: Vec<bool>,
     balanced: bool,
+    depth: usize,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -1403,6 +1430,7 @@ impl<'s> Parser<'s> {
             nodes: vec![],
             stop_at_newline: vec![],
             balanced: true,
+            depth: 0,
         }
     }
 
@@ -1619,6 +1647,46 @@ impl<'s> Parser<'s> {
         self.nodes.insert(m.0, error);
     }
 
+    fn descend(&mut self) -> bool {
+        if self.depth >= MAX_DEPTH {
+            self.too_deep();
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+
+    fn ascend(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn at_nesting(&self, at_start: bool) -> bool {
+        match self.current {
+            SyntaxKind::Hashtag
+            | SyntaxKind::Star
+            | SyntaxKind::Underscore
+            | SyntaxKind::RefMarker
+            | SyntaxKind::Dollar => true,
+            SyntaxKind::HeadingMarker
+            | SyntaxKind::ListMarker
+            | SyntaxKind::EnumMarker
+            | SyntaxKind::TermMarker => at_start,
+            _ => false,
+        }
+    }
+
+    fn too_deep(&mut self) {
+        self.balanced = false;
+        let message = "maximum nesting depth exceeded";
+        if self.eof() {
+            self.nodes.push(SyntaxNode::error(message, "", ErrorPos::Full));
+        } else {
+            let offset = self.nodes.len();
+            self.eat();
+            self.nodes[offset].convert_to_error(message);
+        }
+    }
+
     fn unexpected(&mut self) {
         self.unskip();
         while self
@@ -1641,3 +1709,26 @@ impl<'s> Parser<'s> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_nesting() {
+        for text in [
+            format!("#{}1{}", "(".repeat(10_000), ")".repeat(10_000)),
+            format!("#({}1)", "-".repeat(10_000)),
+            format!("${}$", "(".repeat(10_000)),
+            "*_".repeat(10_000),
+            "#[".repeat(10_000),
+        ] {
+            let root = parse(&text);
+            assert_eq!(root.len(), text.len());
+            assert!(root
+                .errors()
+                .iter()
+                .any(|error| error.message == "maximum nesting depth exceeded"));
+        }
+    }
+}
@@ -170,15 +170,16 @@ fn try_reparse(
             |kind| kind == stop_kind,
         );
 
-        if let Some(newborns) = reparsed {
+        if let Some(mut newborns) = reparsed {
             // If more children follow, at_start must match its previous value.
             // Similarly, if we children follow or we not top-level the nesting
             // must match its previous value.
             if (at_end || at_start == prev_at_start_after)
                 && ((at_end && parent_kind.is_none()) || nesting == prev_nesting_after)
             {
+                let range = keep_unchanged(&children[start..end], start, &mut newborns);
                 return node
-                    .replace_children(start..end, newborns)
+                    .replace_children(range, newborns)
                     .is_ok()
                     .then_some(new_range);
             }
@@ -196,6 +197,28 @@ fn try_reparse(
     None
 }
 
+/// Keep the previous children at both ends of a reparsed segment that came out
+/// the same, so that they stay shared with earlier versions of the tree.
+///
+/// Removes these children from the newborns and returns the range of children
+/// that still needs to be replaced.
+fn keep_unchanged(
+    prev: &[SyntaxNode],
+    start: usize,
+    newborns: &mut Vec<SyntaxNode>,
+) -> Range<usize> {
+    let same = |(a, b): &(&SyntaxNode, &SyntaxNode)| a.spanless_eq(b);
+    let prefix = prev.iter().zip(newborns.iter()).take_while(same).count();
+    newborns.drain(..prefix);
+
+    let prev = &prev[prefix..];
+    let suffix = prev.iter().rev().zip(newborns.iter().rev()).take_while(same).count();
+    newborns.truncate(newborns.len() - suffix);
+
+    let end = prefix + prev.len() - suffix;
+    start + prefix..start + end
+}
+
 /// Whether the inner range is fully contained in the outer one (no touching).
 fn includes(outer: &Range<usize>, inner: &Range<usize>) -> bool {
     outer.start < inner.start && outer.end > inner.end
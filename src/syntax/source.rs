@@ -29,7 +29,10 @@ pub struct Source {
 
 impl Source {
     /// Create a new source file.
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(path = %path.display()))
+    )]
     pub fn new(id: SourceId, path: &Path, text: String) -> Self {
         let mut root = parse(&text);
         root.numberize(id, Span::FULL).unwrap();
@@ -195,6 +198,17 @@ impl Source {
         Some(head.chars().count())
     }
 
+    /// Return the index of the UTF-16 column at the byte index.
+    ///
+    /// The column is defined as the number of UTF-16 code units in the line
+    /// before the byte index. This is the column unit used by the language
+    /// server protocol.
+    pub fn byte_to_utf16_column(&self, byte_idx: usize) -> Option<usize> {
+        let line = self.lines.get(self.byte_to_line(byte_idx)?)?;
+        let head = self.text.get(line.byte_idx..byte_idx)?;
+        Some(head.len_utf16())
+    }
+
     /// Return the byte index at the UTF-16 code unit.
     pub fn utf16_to_byte(&self, utf16_idx: usize) -> Option<usize> {
         let line = self.lines.get(
@@ -244,6 +258,28 @@ impl Source {
         }
         Some(range.start + (line.len() - chars.as_str().len()))
     }
+
+    /// Return the byte index of the given (line, UTF-16 column) pair.
+    ///
+    /// The column defines the number of UTF-16 code units to go beyond the
+    /// start of the line. Returns `None` if the column lies beyond the end of
+    /// the line or inside of a surrogate pair.
+    pub fn line_utf16_column_to_byte(
+        &self,
+        line_idx: usize,
+        column_idx: usize,
+    ) -> Option<usize> {
+        let range = self.line_to_range(line_idx)?;
+        let line = self.get(range.clone())?;
+        let mut k = 0;
+        for (i, c) in line.char_indices() {
+            if k >= column_idx {
+                return (k == column_idx).then_some(range.start + i);
+            }
+            k += c.len_utf16();
+        }
+        (k == column_idx).then_some(range.end)
+    }
 }
 
 impl Debug for Source {
@@ -292,7 +328,7 @@ impl SourceId {
 struct Line {
     /// The UTF-8 byte offset where the line starts.
     byte_idx: usize,
-    /// The UTF-16 codepoint offset where the line starts.
+    /// The UTF-16 code unit offset where the line starts.
     utf16_idx: usize,
 }
 
@@ -395,6 +431,28 @@ mod tests {
         assert_eq!(source.utf16_to_byte(19), None);
     }
 
+    #[test]
+    fn test_source_file_utf16_column() {
+        #[track_caller]
+        fn roundtrip(source: &Source, byte_idx: usize, column: usize) {
+            let line = source.byte_to_line(byte_idx).unwrap();
+            let middle = source.byte_to_utf16_column(byte_idx).unwrap();
+            let result = source.line_utf16_column_to_byte(line, middle).unwrap();
+            assert_eq!(middle, column);
+            assert_eq!(result, byte_idx);
+        }
+
+        let source = Source::detached(TEST);
+        roundtrip(&source, 0, 0);
+        roundtrip(&source, 6, 5);
+        roundtrip(&source, 7, 0);
+        roundtrip(&source, 8, 1);
+        roundtrip(&source, 12, 3);
+        roundtrip(&source, 21, 3);
+        assert_eq!(source.line_utf16_column_to_byte(1, 2), None);
+        assert_eq!(source.line_utf16_column_to_byte(3, 4), None);
+    }
+
     #[test]
     fn test_source_file_roundtrip() {
         #[track_caller]
@@ -77,6 +77,15 @@ impl Span {
     pub const fn number(self) -> u64 {
         self.0.get() & ((1 << Self::BITS) - 1)
     }
+
+    /// Return `other` if `self` is detached and `self` otherwise.
+    pub fn or(self, other: Self) -> Self {
+        if self.is_detached() {
+            other
+        } else {
+            self
+        }
+    }
 }
 
 /// A value with a span locating it in the source code.
@@ -94,6 +103,11 @@ impl<T> Spanned<T> {
         Self { v, span }
     }
 
+    /// Create a new instance with a detached span, e.g. for generated values.
+    pub fn detached(v: T) -> Self {
+        Self { v, span: Span::detached() }
+    }
+
     /// Convert from `&Spanned<T>` to `Spanned<&T>`
     pub fn as_ref(&self) -> Spanned<&T> {
         Spanned { v: &self.v, span: self.span }
@@ -125,4 +139,13 @@ mod tests {
         assert_eq!(span.source(), id);
         assert_eq!(span.number(), 10);
     }
+
+    #[test]
+    fn test_span_detached() {
+        let span = Span::new(SourceId::from_u16(5), 10);
+        assert!(Span::detached().is_detached());
+        assert!(!span.is_detached());
+        assert_eq!(Span::detached().or(span), span);
+        assert_eq!(span.or(Span::detached()), span);
+    }
 }
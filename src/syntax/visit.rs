@@ -0,0 +1,163 @@
+//! Walking over syntax trees.
+
+use super::LinkedNode;
+
+/// How a walk continues after a node was entered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Walk {
+    /// Descend into the node's children.
+    Continue,
+    /// Skip the node's children, but continue with its siblings.
+    Skip,
+    /// Stop the whole walk.
+    Stop,
+}
+
+/// Visits the nodes of a syntax tree during a [`walk`].
+///
+/// Both methods receive nodes with their position in the tree, so that the
+/// visitor can look at the node's offset, parent and siblings. To work with
+/// the typed AST instead, cast the node with [`cast`](super::SyntaxNode::cast).
+pub trait Visitor {
+    /// Called before the node's children are visited.
+    fn enter(&mut self, node: &LinkedNode) -> Walk {
+        let _ = node;
+        Walk::Continue
+    }
+
+    /// Called after the node's children were visited.
+    ///
+    /// Not called for nodes whose children were skipped or if the walk was
+    /// stopped within the node.
+    fn leave(&mut self, node: &LinkedNode) {
+        let _ = node;
+    }
+}
+
+/// Walk a syntax tree depth-first, visiting the nodes in source order.
+///
+/// Trivia like spaces and comments are visited as well. Returns `false` if the
+/// visitor stopped the walk.
+///
+/// ```
+/// # use typst::syntax::{parse, LinkedNode, SyntaxKind};
+/// # use typst::syntax::visit::{walk, Visitor, Walk};
+/// struct Idents(Vec<String>);
+///
+/// impl Visitor for Idents {
+///     fn enter(&mut self, node: &LinkedNode) -> Walk {
+///         if node.kind() == SyntaxKind::Ident {
+///             self.0.push(node.text().to_string());
+///         }
+///         Walk::Continue
+///     }
+/// }
+///
+/// let root = parse("#let x = 1; #x");
+/// let mut idents = Idents(vec![]);
+/// walk(&LinkedNode::new(&root), &mut idents);
+/// assert_eq!(idents.0, ["x", "x"]);
+/// ```
+pub fn walk<V: Visitor + ?Sized>(node: &LinkedNode, visitor: &mut V) -> bool {
+    match visitor.enter(node) {
+        Walk::Continue => {}
+        Walk::Skip => return true,
+        Walk::Stop => return false,
+    }
+
+    for child in node.children() {
+        if !walk(&child, visitor) {
+            return false;
+        }
+    }
+
+    visitor.leave(node);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::{parse, SyntaxKind};
+
+    /// Records the kinds of entered and left nodes.
+    struct Recorder {
+        events: Vec<(bool, SyntaxKind)>,
+        skip: Option<SyntaxKind>,
+        stop: Option<SyntaxKind>,
+    }
+
+    impl Visitor for Recorder {
+        fn enter(&mut self, node: &LinkedNode) -> Walk {
+            self.events.push((true, node.kind()));
+            if Some(node.kind()) == self.stop {
+                Walk::Stop
+            } else if Some(node.kind()) == self.skip {
+                Walk::Skip
+            } else {
+                Walk::Continue
+            }
+        }
+
+        fn leave(&mut self, node: &LinkedNode) {
+            self.events.push((false, node.kind()));
+        }
+    }
+
+    fn record(
+        text: &str,
+        skip: Option<SyntaxKind>,
+        stop: Option<SyntaxKind>,
+    ) -> Recorder {
+        let root = parse(text);
+        let mut recorder = Recorder { events: vec![], skip, stop };
+        walk(&LinkedNode::new(&root), &mut recorder);
+        recorder
+    }
+
+    #[test]
+    fn test_walk_order() {
+        use SyntaxKind::{Markup, Star, Strong, Text};
+        let recorder = record("*a*", None, None);
+        assert_eq!(
+            recorder.events,
+            [
+                (true, Markup),
+                (true, Strong),
+                (true, Star),
+                (false, Star),
+                (true, Markup),
+                (true, Text),
+                (false, Text),
+                (false, Markup),
+                (true, Star),
+                (false, Star),
+                (false, Strong),
+                (false, Markup),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_skip_and_stop() {
+        use SyntaxKind::{Emph, Markup, Strong, Text};
+        let recorder = record("*a* _b_", Some(Strong), None);
+        let texts = recorder.events.iter().filter(|&&e| e == (true, Text)).count();
+        assert_eq!(texts, 1);
+        assert!(!recorder.events.contains(&(false, Strong)));
+        assert!(recorder.events.contains(&(true, Emph)));
+
+        let recorder = record("*a* _b_", None, Some(Strong));
+        assert_eq!(recorder.events, [(true, Markup), (true, Strong)]);
+    }
+
+    #[test]
+    fn test_all_kinds() {
+        let names: Vec<_> = SyntaxKind::ALL.iter().map(|kind| kind.name()).collect();
+        assert_eq!(SyntaxKind::ALL.first(), Some(&SyntaxKind::Markup));
+        assert_eq!(SyntaxKind::ALL.last(), Some(&SyntaxKind::Eof));
+        for (i, &kind) in SyntaxKind::ALL.iter().enumerate() {
+            assert_eq!(kind as usize, i, "{}", names[i]);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Size-bounded caches with least-recently-used eviction.
+//!
+//! Most caching in Typst happens through [`comemo`], whose results are dropped
+//! by age with [`comemo::evict`]. Caches whose entries can get large, like
+//! shaped text runs, decoded images and rasterized glyphs, instead account
+//! for the approximate size of their entries and evict the least recently used
+//! ones once they exceed their limit. This keeps long-running watch and editor
+//! sessions from growing unboundedly.
+//!
+//! All such caches are registered globally by name, so that their limits can
+//! be configured with [`set_limit`] and their usage inspected with [`stats`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+
+/// The caches created so far.
+static REGISTRY: Lazy<Mutex<Vec<Arc<dyn Account>>>> = Lazy::new(Mutex::default);
+
+/// The limits configured by name, which also apply to caches created later.
+static LIMITS: Lazy<Mutex<HashMap<String, usize>>> = Lazy::new(Mutex::default);
+
+/// Set the maximum size in bytes of all caches with the given name.
+///
+/// Entries are evicted immediately if a cache exceeds its new limit.
+pub fn set_limit(name: &str, limit: usize) {
+    LIMITS.lock().unwrap().insert(name.into(), limit);
+    for cache in REGISTRY.lock().unwrap().iter() {
+        if cache.name() == name {
+            cache.set_limit(limit);
+        }
+    }
+}
+
+/// Usage statistics of all caches, in the order they were created.
+pub fn stats() -> Vec<Stats> {
+    REGISTRY.lock().unwrap().iter().map(|cache| cache.stats()).collect()
+}
+
+/// Remove all entries from all caches.
+pub fn clear() {
+    for cache in REGISTRY.lock().unwrap().iter() {
+        cache.clear();
+    }
+}
+
+/// Usage statistics of a cache.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Stats {
+    /// The name of the cache.
+    pub name: &'static str,
+    /// The number of entries.
+    pub entries: usize,
+    /// The approximate size of the entries in bytes.
+    pub size: usize,
+    /// The maximum size of the entries in bytes.
+    pub limit: usize,
+    /// How often a requested entry was found.
+    pub hits: u64,
+    /// How often a requested entry had to be computed.
+    pub misses: u64,
+}
+
+/// A thread-safe cache that evicts its least recently used entries once their
+/// approximate size exceeds a limit.
+pub struct Lru<K, V> {
+    inner: Arc<Inner<K, V>>,
+}
+
+impl<K, V> Lru<K, V>
+where
+    K: Hash + Eq + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    /// Create and register a new cache with a default limit in bytes.
+    ///
+    /// If a limit was already configured for the name, it takes precedence.
+    pub fn new(name: &'static str, limit: usize) -> Self {
+        let limit = LIMITS.lock().unwrap().get(name).copied().unwrap_or(limit);
+        let inner = Arc::new(Inner {
+            name,
+            state: Mutex::new(State {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                tick: 0,
+                size: 0,
+                limit,
+                hits: 0,
+                misses: 0,
+            }),
+        });
+        REGISTRY.lock().unwrap().push(inner.clone());
+        Self { inner }
+    }
+
+    /// Get the value for a key or compute and insert it.
+    ///
+    /// The `size` of a value should approximate the number of bytes it keeps
+    /// alive. The lock is not held while computing the value, so that it may
+    /// use the cache itself. Values larger than the limit are not cached.
+    pub fn get_or_insert(
+        &self,
+        key: K,
+        compute: impl FnOnce() -> V,
+        size: impl FnOnce(&V) -> usize,
+    ) -> V {
+        if let Some(value) = self.inner.state.lock().unwrap().get(&key) {
+            return value;
+        }
+
+        let value = compute();
+        let size = size(&value);
+        self.inner.state.lock().unwrap().insert(key, value.clone(), size);
+        value
+    }
+
+    /// Usage statistics of this cache.
+    pub fn stats(&self) -> Stats {
+        self.inner.stats()
+    }
+}
+
+/// The shared part of a cache.
+struct Inner<K, V> {
+    name: &'static str,
+    state: Mutex<State<K, V>>,
+}
+
+/// The entries of a cache and their bookkeeping.
+struct State<K, V> {
+    /// The entries with their size and the time of their last use.
+    entries: HashMap<K, (V, usize, u64)>,
+    /// The keys by the time of their last use.
+    order: BTreeMap<u64, K>,
+    /// Increased on every use.
+    tick: u64,
+    size: usize,
+    limit: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> State<K, V> {
+    /// Look up an entry and mark it as recently used.
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let tick = self.tick;
+        let Some((value, _, used)) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+
+        self.hits += 1;
+        let key = self.order.remove(&*used).unwrap();
+        *used = tick;
+        let value = value.clone();
+        self.order.insert(tick, key);
+        Some(value)
+    }
+
+    /// Insert an entry and evict old ones until the cache fits its limit.
+    fn insert(&mut self, key: K, value: V, size: usize) {
+        if size > self.limit {
+            return;
+        }
+
+        self.tick += 1;
+        if let Some((_, old, used)) =
+            self.entries.insert(key.clone(), (value, size, self.tick))
+        {
+            self.order.remove(&used);
+            self.size -= old;
+        }
+        self.order.insert(self.tick, key);
+        self.size += size;
+        self.evict();
+    }
+
+    /// Remove the least recently used entries until the cache fits its limit.
+    fn evict(&mut self) {
+        while self.size > self.limit {
+            let Some((_, key)) = self.order.pop_first() else { break };
+            if let Some((_, size, _)) = self.entries.remove(&key) {
+                self.size -= size;
+            }
+        }
+    }
+}
+
+/// Type-erased access to a cache for the registry.
+trait Account: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn set_limit(&self, limit: usize);
+    fn stats(&self) -> Stats;
+    fn clear(&self);
+}
+
+impl<K, V> Account for Inner<K, V>
+where
+    K: Hash + Eq + Clone + Send,
+    V: Clone + Send,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn set_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = limit;
+        state.evict();
+    }
+
+    fn stats(&self) -> Stats {
+        let state = self.state.lock().unwrap();
+        Stats {
+            name: self.name,
+            entries: state.entries.len(),
+            size: state.size,
+            limit: state.limit,
+            hits: state.hits,
+            misses: state.misses,
+        }
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.order.clear();
+        state.size = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        let cache = Lru::new("test-evict", 3);
+        cache.get_or_insert(1, || 'a', |_| 1);
+        cache.get_or_insert(2, || 'b', |_| 1);
+        cache.get_or_insert(3, || 'c', |_| 1);
+
+        // Use the first entry so that the second one is the oldest.
+        assert_eq!(cache.get_or_insert(1, || 'x', |_| 1), 'a');
+        cache.get_or_insert(4, || 'd', |_| 1);
+        assert_eq!(cache.get_or_insert(2, || 'y', |_| 1), 'y');
+        assert_eq!(cache.get_or_insert(1, || 'x', |_| 1), 'a');
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.size, 3);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 5);
+    }
+
+    #[test]
+    fn test_lru_respects_size() {
+        let cache = Lru::new("test-size", 10);
+        cache.get_or_insert("big", || 1, |_| 8);
+        cache.get_or_insert("small", || 2, |_| 4);
+        cache.get_or_insert("huge", || 3, |_| 11);
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.size, 4);
+    }
+
+    #[test]
+    fn test_lru_limit_by_name() {
+        set_limit("test-limit", 2);
+        let cache = Lru::new("test-limit", 100);
+        assert_eq!(cache.stats().limit, 2);
+        cache.get_or_insert(1, || (), |_| 1);
+        cache.get_or_insert(2, || (), |_| 1);
+        set_limit("test-limit", 1);
+        assert_eq!(cache.stats().entries, 1);
+        assert!(stats().iter().any(|stats| stats.name == "test-limit"));
+    }
+}
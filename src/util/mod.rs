@@ -1,6 +1,7 @@
 //! Utilities.
 
 pub mod fat;
+pub mod lru;
 
 mod buffer;
 
@@ -128,7 +129,7 @@ pub trait PathExt {
 }
 
 impl PathExt for Path {
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn normalize(&self) -> PathBuf {
         let mut out = PathBuf::new();
         for component in self.components() {
@@ -1,18 +1,15 @@
-use std::path::Path;
-
-use comemo::{Prehashed, Track, Tracked};
+use comemo::Track;
 use iai::{black_box, main, Iai};
-use typst::diag::{FileError, FileResult};
-use typst::eval::{Datetime, Library};
-use typst::font::{Font, FontBook};
 use typst::geom::Color;
-use typst::syntax::{Source, SourceId};
-use typst::util::Buffer;
-use typst::World;
+use typst::syntax::Source;
 use unscanny::Scanner;
 
+use self::world::BenchWorld;
+
+mod world;
+
 const TEXT: &str = include_str!("../typ/compiler/bench.typ");
-const FONT: &[u8] = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+const REPEATED: &str = include_str!("../typ/benches/repeated.typ");
 
 main!(
     bench_decode,
@@ -22,6 +19,7 @@ main!(
     bench_eval,
     bench_typeset,
     bench_compile,
+    bench_compile_repeated,
     bench_render,
 );
 
@@ -59,7 +57,7 @@ fn bench_edit(iai: &mut Iai) {
 }
 
 fn bench_eval(iai: &mut Iai) {
-    let world = BenchWorld::new();
+    let world = BenchWorld::with_text(TEXT);
     let route = typst::eval::Route::default();
     let mut tracer = typst::eval::Tracer::default();
     iai.run(|| {
@@ -69,7 +67,7 @@ fn bench_eval(iai: &mut Iai) {
 }
 
 fn bench_typeset(iai: &mut Iai) {
-    let world = BenchWorld::new();
+    let world = BenchWorld::with_text(TEXT);
     let route = typst::eval::Route::default();
     let mut tracer = typst::eval::Tracer::default();
     let module = typst::eval::eval(
@@ -84,71 +82,17 @@ fn bench_typeset(iai: &mut Iai) {
 }
 
 fn bench_compile(iai: &mut Iai) {
-    let world = BenchWorld::new();
+    let world = BenchWorld::with_text(TEXT);
+    iai.run(|| typst::compile(&world));
+}
+
+fn bench_compile_repeated(iai: &mut Iai) {
+    let world = BenchWorld::with_text(REPEATED);
     iai.run(|| typst::compile(&world));
 }
 
 fn bench_render(iai: &mut Iai) {
-    let world = BenchWorld::new();
+    let world = BenchWorld::with_text(TEXT);
     let document = typst::compile(&world).unwrap();
     iai.run(|| typst::export::render(&document.pages[0], 1.0, Color::WHITE))
 }
-
-struct BenchWorld {
-    library: Prehashed<Library>,
-    book: Prehashed<FontBook>,
-    font: Font,
-    source: Source,
-}
-
-impl BenchWorld {
-    fn new() -> Self {
-        let font = Font::new(FONT.into(), 0).unwrap();
-        let book = FontBook::from_fonts([&font]);
-
-        Self {
-            library: Prehashed::new(typst_library::build()),
-            book: Prehashed::new(book),
-            font,
-            source: Source::detached(TEXT),
-        }
-    }
-
-    fn track(&self) -> Tracked<dyn World> {
-        (self as &dyn World).track()
-    }
-}
-
-impl World for BenchWorld {
-    fn library(&self) -> &Prehashed<Library> {
-        &self.library
-    }
-
-    fn main(&self) -> &Source {
-        &self.source
-    }
-
-    fn resolve(&self, path: &Path) -> FileResult<SourceId> {
-        Err(FileError::NotFound(path.into()))
-    }
-
-    fn source(&self, _: SourceId) -> &Source {
-        &self.source
-    }
-
-    fn book(&self) -> &Prehashed<FontBook> {
-        &self.book
-    }
-
-    fn font(&self, _: usize) -> Option<Font> {
-        Some(self.font.clone())
-    }
-
-    fn file(&self, path: &Path) -> FileResult<Buffer> {
-        Err(FileError::NotFound(path.into()))
-    }
-
-    fn today(&self, _: Option<i64>) -> Option<Datetime> {
-        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
-    }
-}
@@ -0,0 +1,129 @@
+//! Wall-clock benchmarks of the compilation phases on small, medium and large
+//! documents.
+//!
+//! To compare a change with the state before it, save a baseline before the
+//! change and compare with it afterwards:
+//! ```bash
+//! cargo bench --bench criterion -- --save-baseline before
+//! cargo bench --bench criterion -- --baseline before
+//! ```
+
+use comemo::Track;
+use criterion::measurement::WallTime;
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkGroup, BenchmarkId,
+    Criterion, Throughput,
+};
+use typst::eval::{Route, Tracer};
+
+use self::world::BenchWorld;
+
+mod world;
+
+const SMALL: &str = include_str!("../typ/compiler/bench.typ");
+const MEDIUM: &str = include_str!("../typ/benches/medium.typ");
+
+criterion_group!(
+    benches,
+    bench_tokenize,
+    bench_parse,
+    bench_eval,
+    bench_layout,
+    bench_export,
+);
+criterion_main!(benches);
+
+/// The representative documents with their names.
+fn documents() -> [(&'static str, String); 3] {
+    [("small", SMALL.into()), ("medium", MEDIUM.into()), ("large", MEDIUM.repeat(10))]
+}
+
+/// Run a benchmark for each document.
+fn bench_documents(
+    c: &mut Criterion,
+    name: &str,
+    samples: usize,
+    mut f: impl FnMut(&mut BenchmarkGroup<WallTime>, BenchmarkId, &BenchWorld),
+) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(samples);
+    for (name, text) in documents() {
+        let world = BenchWorld::with_text(&text);
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        f(&mut group, BenchmarkId::from_parameter(name), &world);
+    }
+    group.finish();
+}
+
+fn bench_tokenize(c: &mut Criterion) {
+    bench_documents(c, "tokenize", 100, |group, id, world| {
+        let text = world.source.text();
+        group.bench_function(id, |b| {
+            b.iter(|| typst::syntax::tokenize(black_box(text)).count())
+        });
+    });
+}
+
+fn bench_parse(c: &mut Criterion) {
+    bench_documents(c, "parse", 100, |group, id, world| {
+        let text = world.source.text();
+        group.bench_function(id, |b| b.iter(|| typst::syntax::parse(black_box(text))));
+    });
+}
+
+// Evaluation and layout are memoized, so the caches are evicted before each
+// iteration to measure cold runs.
+
+fn bench_eval(c: &mut Criterion) {
+    bench_documents(c, "eval", 20, |group, id, world| {
+        let route = Route::default();
+        let mut tracer = Tracer::default();
+        group.bench_function(id, |b| {
+            b.iter_batched(
+                || comemo::evict(0),
+                |_| {
+                    typst::eval::eval(
+                        world.track(),
+                        route.track(),
+                        tracer.track_mut(),
+                        &world.source,
+                    )
+                    .unwrap()
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    });
+}
+
+fn bench_layout(c: &mut Criterion) {
+    bench_documents(c, "layout", 10, |group, id, world| {
+        let route = Route::default();
+        let mut tracer = Tracer::default();
+        let module = typst::eval::eval(
+            world.track(),
+            route.track(),
+            tracer.track_mut(),
+            &world.source,
+        )
+        .unwrap();
+        let content = module.content();
+        group.bench_function(id, |b| {
+            b.iter_batched(
+                || comemo::evict(0),
+                |_| {
+                    typst::model::typeset(world.track(), tracer.track_mut(), &content)
+                        .unwrap()
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    });
+}
+
+fn bench_export(c: &mut Criterion) {
+    bench_documents(c, "export", 10, |group, id, world| {
+        let document = typst::compile(world).unwrap();
+        group.bench_function(id, |b| b.iter(|| typst::export::pdf(&document).unwrap()));
+    });
+}
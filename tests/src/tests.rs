@@ -22,7 +22,7 @@ use walkdir::WalkDir;
 
 use typst::diag::{bail, FileError, FileResult, StrResult};
 use typst::doc::{Document, Frame, FrameItem, Meta};
-use typst::eval::{func, Datetime, Library, NoneValue, Value};
+use typst::eval::{func, Datetime, Feature, Library, NoneValue, Value};
 use typst::font::{Font, FontBook};
 use typst::geom::{Abs, Color, RgbaColor, Smart};
 use typst::syntax::{Source, SourceId, Span, SyntaxNode};
@@ -35,6 +35,7 @@ const TYP_DIR: &str = "typ";
 const REF_DIR: &str = "ref";
 const PNG_DIR: &str = "png";
 const PDF_DIR: &str = "pdf";
+const SNAP_DIR: &str = "snap";
 const FONT_DIR: &str = "../assets/fonts";
 const FILE_DIR: &str = "../assets/files";
 
@@ -153,6 +154,21 @@ fn library() -> Library {
         Ok(NoneValue)
     }
 
+    /// Display: Renamed
+    /// Category: test
+    #[func]
+    fn renamed(
+        #[named]
+        #[default(0)]
+        value: i64,
+        #[named]
+        #[default]
+        #[deprecated("use `value` instead")]
+        old: Option<i64>,
+    ) -> i64 {
+        old.unwrap_or(value)
+    }
+
     /// Display: Print
     /// Category: test
     #[func]
@@ -185,6 +201,7 @@ fn library() -> Library {
     // Hook up helpers into the global scope.
     lib.global.scope_mut().define("test", test_func());
     lib.global.scope_mut().define("print", print_func());
+    lib.global.scope_mut().define("renamed", renamed_func());
     lib.global
         .scope_mut()
         .define("conifer", RgbaColor::new(0x9f, 0xEB, 0x52, 0xFF));
@@ -199,6 +216,8 @@ fn library() -> Library {
 struct TestWorld {
     print: PrintConfig,
     library: Prehashed<Library>,
+    stable: Prehashed<Library>,
+    experimental: bool,
     book: Prehashed<FontBook>,
     fonts: Vec<Font>,
     paths: RefCell<HashMap<PathBuf, PathSlot>>,
@@ -211,6 +230,8 @@ impl Clone for TestWorld {
         Self {
             print: self.print,
             library: self.library.clone(),
+            stable: self.stable.clone(),
+            experimental: self.experimental,
             book: self.book.clone(),
             fonts: self.fonts.clone(),
             paths: self.paths.clone(),
@@ -240,9 +261,16 @@ impl TestWorld {
             fonts.extend(Font::iter(data.into()));
         }
 
+        // Test experimental features as well, except for tests that opt out.
+        let stable = library();
+        let mut library = stable.clone();
+        library.features = Feature::ALL.iter().copied().collect();
+
         Self {
             print,
-            library: Prehashed::new(library()),
+            library: Prehashed::new(library),
+            stable: Prehashed::new(stable),
+            experimental: true,
             book: Prehashed::new(FontBook::from_fonts(&fonts)),
             fonts,
             paths: RefCell::default(),
@@ -258,7 +286,11 @@ impl World for TestWorld {
     }
 
     fn library(&self) -> &Prehashed<Library> {
-        &self.library
+        if self.experimental {
+            &self.library
+        } else {
+            &self.stable
+        }
     }
 
     fn main(&self) -> &Source {
@@ -367,6 +399,9 @@ fn test(
     let text = fs::read_to_string(src_path).unwrap();
     let _guard = PanicGuard(name);
 
+    // Experimental features are enabled unless the header disables them.
+    world.experimental = true;
+
     let mut output = String::new();
     let mut ok = true;
     let mut updated = false;
@@ -374,6 +409,7 @@ fn test(
     let mut line = 0;
     let mut compare_ref = true;
     let mut compare_ever = false;
+    let mut snapshot = None;
     let mut rng = LinearShift::new();
 
     let parts: Vec<_> = text
@@ -400,9 +436,18 @@ fn test(
 
         if is_header {
             for line in part.lines() {
+                if line.starts_with("// Features: none") {
+                    world.experimental = false;
+                }
                 if line.starts_with("// Ref: false") {
                     compare_ref = false;
                 }
+                if line.starts_with("// Snapshot: true") {
+                    snapshot = Some(typst::ide::Snapshot {
+                        ast: String::new(),
+                        eval: String::new(),
+                    });
+                }
             }
         } else {
             let (part_ok, compare_here, part_frames) = test_part(
@@ -419,6 +464,12 @@ fn test(
             ok &= part_ok;
             compare_ever |= compare_here;
             frames.extend(part_frames);
+
+            if let Some(snapshot) = &mut snapshot {
+                let part = typst::ide::snapshot(&*world);
+                writeln!(snapshot.ast, "// Subtest {i}\n{}", part.ast).unwrap();
+                writeln!(snapshot.eval, "// Subtest {i}\n{}", part.eval).unwrap();
+            }
         }
 
         line += part.lines().count() + 1;
@@ -427,7 +478,7 @@ fn test(
     let document = Document { pages: frames, ..Default::default() };
     if compare_ever {
         if let Some(pdf_path) = pdf_path {
-            let pdf_data = typst::export::pdf(&document);
+            let pdf_data = typst::export::pdf(&document).unwrap();
             fs::create_dir_all(pdf_path.parent().unwrap()).unwrap();
             fs::write(pdf_path, pdf_data).unwrap();
         }
@@ -470,6 +521,39 @@ fn test(
         }
     }
 
+    if let Some(snapshot) = snapshot {
+        let snap_path = Path::new(SNAP_DIR).join(name);
+        for (kind, text) in [("ast", snapshot.ast), ("eval", snapshot.eval)] {
+            let path = snap_path.with_extension(kind);
+            match fs::read_to_string(&path) {
+                Ok(expected) if expected == text => {}
+                result => {
+                    if args.update {
+                        fs::create_dir_all(path.parent().unwrap()).unwrap();
+                        fs::write(&path, text).unwrap();
+                        updated = true;
+                    } else if let Ok(expected) = result {
+                        let (line, (exp, act)) = expected
+                            .lines()
+                            .chain(std::iter::repeat(""))
+                            .zip(text.lines().chain(std::iter::repeat("")))
+                            .enumerate()
+                            .find(|(_, (a, b))| a != b)
+                            .unwrap_or((0, ("", "")));
+                        writeln!(output, "  Does not match {kind} snapshot.").unwrap();
+                        writeln!(output, "    Line {}:", line + 1).unwrap();
+                        writeln!(output, "    Expected | {exp}").unwrap();
+                        writeln!(output, "    Found    | {act}").unwrap();
+                        ok = false;
+                    } else {
+                        writeln!(output, "  Failed to open {kind} snapshot.").unwrap();
+                        ok = false;
+                    }
+                }
+            }
+        }
+    }
+
     {
         let mut stdout = io::stdout().lock();
         stdout.write_all(name.to_string_lossy().as_bytes()).unwrap();
@@ -479,7 +563,7 @@ fn test(
             writeln!(stdout, " ❌").unwrap();
         }
         if updated {
-            writeln!(stdout, "  Updated reference image.").unwrap();
+            writeln!(stdout, "  Updated reference image or snapshots.").unwrap();
         }
         if !output.is_empty() {
             stdout.write_all(output.as_bytes()).unwrap();
@@ -517,7 +601,7 @@ fn test_part(
         writeln!(output, "Syntax Tree:\n{:#?}\n", source.root()).unwrap();
     }
 
-    let (local_compare_ref, mut ref_errors) = parse_metadata(source);
+    let (local_compare_ref, ref_errors, ref_warnings) = parse_metadata(source);
     let compare_ref = local_compare_ref.unwrap_or(compare_ref);
 
     ok &= test_spans(output, source.root());
@@ -532,6 +616,8 @@ fn test_part(
         writeln!(output, "Model:\n{:#?}\n", module.content()).unwrap();
     }
 
+    ok &= test_structure(output, world, src_path, i);
+
     let (mut frames, errors) = match typst::compile(world) {
         Ok(document) => (document.pages, vec![]),
         Err(errors) => (vec![], *errors),
@@ -544,41 +630,91 @@ fn test_part(
 
     // Map errors to range and message format, discard traces and errors from
     // other files.
-    let mut errors: Vec<_> = errors
+    let errors: Vec<_> = errors
         .into_iter()
         .filter(|error| error.span.source() == id)
         .map(|error| (error.range(world), error.message.replace('\\', "/")))
         .collect();
 
-    errors.sort_by_key(|error| error.0.start);
-    ref_errors.sort_by_key(|error| error.0.start);
+    ok &= compare_diagnostics(
+        output,
+        world.source(id),
+        i,
+        line,
+        "Error",
+        errors,
+        ref_errors,
+    );
+
+    // Most tests aren't complete documents, so structural warnings are only
+    // checked for tests that expect some. Evaluation warnings, like those
+    // about deprecated definitions, are always checked.
+    let warnings =
+        if ref_warnings.is_empty() { typst::warnings(world) } else { typst::lint(world) };
+
+    let warnings: Vec<_> = warnings
+        .into_iter()
+        .filter(|warning| warning.span.source() == id)
+        .map(|warning| (warning.range(world), warning.message.to_string()))
+        .collect();
+
+    ok &= compare_diagnostics(
+        output,
+        world.source(id),
+        i,
+        line,
+        "Warning",
+        warnings,
+        ref_warnings,
+    );
 
-    if errors != ref_errors {
-        writeln!(output, "  Subtest {i} does not match expected errors.").unwrap();
-        ok = false;
+    (ok, compare_ref, frames)
+}
 
-        let source = world.source(id);
-        for error in errors.iter() {
-            if !ref_errors.contains(error) {
-                write!(output, "    Not annotated | ").unwrap();
-                print_error(output, source, line, error);
-            }
+/// Compare emitted with annotated diagnostics and print the differences.
+fn compare_diagnostics(
+    output: &mut String,
+    source: &Source,
+    i: usize,
+    line: usize,
+    kind: &str,
+    mut found: Vec<(Range<usize>, String)>,
+    mut expected: Vec<(Range<usize>, String)>,
+) -> bool {
+    found.sort_by_key(|diag| diag.0.start);
+    expected.sort_by_key(|diag| diag.0.start);
+
+    if found == expected {
+        return true;
+    }
+
+    let name = kind.to_lowercase();
+    writeln!(output, "  Subtest {i} does not match expected {name}s.").unwrap();
+
+    for diag in found.iter() {
+        if !expected.contains(diag) {
+            write!(output, "    Not annotated | ").unwrap();
+            print_diagnostic(output, source, line, kind, diag);
         }
+    }
 
-        for error in ref_errors.iter() {
-            if !errors.contains(error) {
-                write!(output, "    Not emitted   | ").unwrap();
-                print_error(output, source, line, error);
-            }
+    for diag in expected.iter() {
+        if !found.contains(diag) {
+            write!(output, "    Not emitted   | ").unwrap();
+            print_diagnostic(output, source, line, kind, diag);
         }
     }
 
-    (ok, compare_ref, frames)
+    false
 }
 
-fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>) {
+/// Diagnostics annotated in a test.
+type Annotations = Vec<(Range<usize>, String)>;
+
+fn parse_metadata(source: &Source) -> (Option<bool>, Annotations, Annotations) {
     let mut compare_ref = None;
     let mut errors = vec![];
+    let mut warnings = vec![];
 
     let lines: Vec<_> = source.text().lines().map(str::trim).collect();
     for (i, line) in lines.iter().enumerate() {
@@ -605,29 +741,37 @@ fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>
             source.line_column_to_byte(line, column).unwrap()
         };
 
-        let Some(rest) = line.strip_prefix("// Error: ") else { continue; };
+        let (rest, list) = if let Some(rest) = line.strip_prefix("// Error: ") {
+            (rest, &mut errors)
+        } else if let Some(rest) = line.strip_prefix("// Warning: ") {
+            (rest, &mut warnings)
+        } else {
+            continue;
+        };
+
         let mut s = Scanner::new(rest);
         let start = pos(&mut s);
         let end = if s.eat_if('-') { pos(&mut s) } else { start };
         let range = start..end;
 
-        errors.push((range, s.after().trim().to_string()));
+        list.push((range, s.after().trim().to_string()));
     }
 
-    (compare_ref, errors)
+    (compare_ref, errors, warnings)
 }
 
-fn print_error(
+fn print_diagnostic(
     output: &mut String,
     source: &Source,
     line: usize,
+    kind: &str,
     (range, message): &(Range<usize>, String),
 ) {
     let start_line = 1 + line + source.byte_to_line(range.start).unwrap();
     let start_col = 1 + source.byte_to_column(range.start).unwrap();
     let end_line = 1 + line + source.byte_to_line(range.end).unwrap();
     let end_col = 1 + source.byte_to_column(range.end).unwrap();
-    writeln!(output, "Error: {start_line}:{start_col}-{end_line}:{end_col}: {message}")
+    writeln!(output, "{kind}: {start_line}:{start_col}-{end_line}:{end_col}: {message}")
         .unwrap();
 }
 
@@ -747,6 +891,47 @@ fn test_reparse(
     ok
 }
 
+/// Evaluate the source file again with shifted spans and test whether the
+/// structural hash of its content stays the same.
+///
+/// The spans are shifted by appending a comment, which adds a syntax node but
+/// no content.
+fn test_structure(
+    output: &mut String,
+    world: &mut TestWorld,
+    src_path: &Path,
+    i: usize,
+) -> bool {
+    let text = world.source(world.main).text().to_string();
+    if !text.ends_with('\n') {
+        return true;
+    }
+
+    let mut hash = |text: String| {
+        let id = world.set(src_path, text);
+        let world = (&*world as &dyn World).track();
+        let route = typst::eval::Route::default();
+        let mut tracer = typst::eval::Tracer::default();
+        typst::eval::eval(world, route.track(), tracer.track_mut(), world.source(id))
+            .ok()
+            .map(|module| module.content().structural_hash())
+    };
+
+    let shifted = hash(format!("{text}/**/"));
+    let original = hash(text);
+    match (original, shifted) {
+        (Some(original), Some(shifted)) if original != shifted => {
+            writeln!(
+                output,
+                "    Subtest {i} content changes structurally when spans shift ❌"
+            )
+            .unwrap();
+            false
+        }
+        _ => true,
+    }
+}
+
 /// Returns all leaf descendants of a node (may include itself).
 fn leafs(node: &SyntaxNode) -> Vec<SyntaxNode> {
     if node.children().len() == 0 {
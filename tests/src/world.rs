@@ -0,0 +1,73 @@
+//! A world for benchmarking with a single source file and font.
+
+use std::path::Path;
+
+use comemo::{Prehashed, Track, Tracked};
+use typst::diag::{FileError, FileResult};
+use typst::eval::{Datetime, Library};
+use typst::font::{Font, FontBook};
+use typst::syntax::{Source, SourceId};
+use typst::util::Buffer;
+use typst::World;
+
+const FONT: &[u8] = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+
+/// A world that serves one source file and the regular Linux Libertine font.
+pub struct BenchWorld {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    font: Font,
+    pub source: Source,
+}
+
+impl BenchWorld {
+    pub fn with_text(text: &str) -> Self {
+        let font = Font::new(FONT.into(), 0).unwrap();
+        let book = FontBook::from_fonts([&font]);
+
+        Self {
+            library: Prehashed::new(typst_library::build()),
+            book: Prehashed::new(book),
+            font,
+            source: Source::detached(text),
+        }
+    }
+
+    pub fn track(&self) -> Tracked<dyn World> {
+        (self as &dyn World).track()
+    }
+}
+
+impl World for BenchWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn main(&self) -> &Source {
+        &self.source
+    }
+
+    fn resolve(&self, path: &Path) -> FileResult<SourceId> {
+        Err(FileError::NotFound(path.into()))
+    }
+
+    fn source(&self, _: SourceId) -> &Source {
+        &self.source
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn font(&self, _: usize) -> Option<Font> {
+        Some(self.font.clone())
+    }
+
+    fn file(&self, path: &Path) -> FileResult<Buffer> {
+        Err(FileError::NotFound(path.into()))
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
+}
@@ -0,0 +1,16 @@
+// A long document that repeats the same runs of text over and over again,
+// exercising the shaping cache.
+
+#set page(width: 450pt, margin: 1cm)
+#set par(justify: true)
+
+#for i in range(8) [
+  = Section #(i + 1)
+  #lorem(150)
+
+  #table(
+    columns: 4,
+    [*Name*], [*Value*], [*Unit*], [*Note*],
+    ..range(24).map(j => ([Entry], [#calc.rem(j, 3)], [pt], [Same as above])).flatten()
+  )
+]
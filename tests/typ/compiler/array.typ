@@ -228,6 +228,37 @@
 #test((2, 1, 3, -10, -5, 8, 6, -7, 2).sorted(key: x => x), (-10, -7, -5, 1, 2, 2, 3, 6, 8))
 #test((2, 1, 3, -10, -5, 8, 6, -7, 2).sorted(key: x => x * x), (1, 2, 2, 3, -5, 6, -7, 8, -10))
 
+---
+// Test sorting with a locale.
+#let names = ("Örn", "zebra", "Åsa", "Ahlberg", "Zorn", "ähnlich")
+#test(names.sorted(), ("Ahlberg", "Zorn", "zebra", "Åsa", "Örn", "ähnlich"))
+#test(names.sorted(locale: "en"), ("Ahlberg", "ähnlich", "Åsa", "Örn", "zebra", "Zorn"))
+#test(names.sorted(locale: "sv"), ("Ahlberg", "zebra", "Zorn", "Åsa", "ähnlich", "Örn"))
+#test(("ñu", "nube", "oso").sorted(locale: "es"), ("nube", "ñu", "oso"))
+#test(((2, "b"), (1, "ä")).sorted(key: p => p.at(1), locale: "de"), ((1, "ä"), (2, "b")))
+#test((3, 1, 2).sorted(locale: "sv"), (1, 2, 3))
+
+---
+// Error: 28-31 expected two or three letter language code (ISO 639-1/2/3)
+#("a", "b").sorted(locale: "x")
+
+---
+// Test the set-like methods.
+#test(().dedup(), ())
+#test((1, 2, 1, 3, 2).dedup(), (1, 2, 3))
+#test((1, 1.0, "1").dedup(), (1, "1"))
+#test(("Mia", "mia", "Ben").dedup(key: lower), ("Mia", "Ben"))
+#test((1, 2).union((2, 3, 3)), (1, 2, 3))
+#test(("a", "B").union(("b", "c"), key: lower), ("a", "B", "c"))
+#test((1, 2, 3, 2).intersect((2, 3, 4)), (2, 3))
+#test((1, 2).intersect(()), ())
+#test((1, 2, 3, 1).difference((2,)), (1, 3))
+#test(("Ada", "Grace").difference(("grace",), key: lower), ("Ada",))
+#test(("Alice", "Bob").contains("bob"), false)
+#test(("Alice", "Bob").contains("bob", key: lower), true)
+#test(range(5).intersect((3, 7)), (3,))
+#test(range(5).contains(-4, key: calc.abs), true)
+
 ---
 // Test the `zip` method.
 #test(().zip(()), ())
@@ -237,6 +268,36 @@
 #test((1, 2, 3, 4).zip((5, 6)), ((1, 5), (2, 6)))
 #test(((1, 2), 3).zip((4, 5)), (((1, 2), 4), (3, 5)))
 #test((1, "hi").zip((true, false)), ((1, true), ("hi", false)))
+#test((1, 2, 3).zip((4, 5), (6, 7, 8)), ((1, 4, 6), (2, 5, 7)))
+#test(range(3).zip("abc".clusters()), ((0, "a"), (1, "b"), (2, "c")))
+
+---
+// Error: 12-14 missing argument: other
+#(1, 2).zip()
+
+---
+// Test the `enumerate` method.
+#test(().enumerate(), ())
+#test(("a", "b").enumerate(), ((0, "a"), (1, "b")))
+#test(("a", "b").enumerate(start: 1), ((1, "a"), (2, "b")))
+#test((1,).enumerate(start: 9223372036854775807), ((9223372036854775807, 1),))
+
+---
+// Error: 2-46 array index is too large
+#(1, 2).enumerate(start: 9223372036854775807)
+
+---
+// Test the `chunks` method.
+#test(().chunks(2), ())
+#test((1, 2, 3, 4).chunks(2), ((1, 2), (3, 4)))
+#test((1, 2, 3, 4, 5).chunks(2), ((1, 2), (3, 4), (5,)))
+#test((1, 2, 3, 4, 5).chunks(2, exact: true), ((1, 2), (3, 4)))
+#test((1, 2).chunks(5), ((1, 2),))
+#test(range(7).chunks(3).len(), 3)
+
+---
+// Error: 16-17 number must be positive
+#(1, 2).chunks(0)
 
 
 ---
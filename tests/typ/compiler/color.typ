@@ -2,11 +2,11 @@
 
 ---
 // Test CMYK color conversion.
-#let c = cmyk(50%, 64%, 16%, 17%)
+#let c = color.cmyk(50%, 64%, 16%, 17%)
 #stack(
   dir: ltr,
   spacing: 1fr,
-  rect(width: 1cm, fill: cmyk(69%, 11%, 69%, 41%)),
+  rect(width: 1cm, fill: color.cmyk(69%, 11%, 69%, 41%)),
   rect(width: 1cm, fill: c),
   rect(width: 1cm, fill: c.negate()),
 )
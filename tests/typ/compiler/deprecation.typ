@@ -0,0 +1,20 @@
+// Test warnings for deprecated definitions.
+// Ref: false
+
+---
+// Warning: 7-11 `cmyk` is deprecated
+#test(cmyk(0%, 0%, 0%, 100%), color.cmyk(0%, 0%, 0%, 100%))
+
+---
+// Warning: 13-17 `cmyk` is deprecated
+#let f(x) = cmyk(x, 0%, 0%, 0%)
+#test(f(10%), color.cmyk(10%, 0%, 0%, 0%))
+
+---
+// Warning: 14-20 parameter `old` is deprecated
+#test(renamed(old: 2), 2)
+
+---
+// The replacements don't warn.
+#test(type(color.cmyk(0%, 0%, 0%, 0%)), "color")
+#test(renamed(value: 2), 2)
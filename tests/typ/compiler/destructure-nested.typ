@@ -0,0 +1,45 @@
+// Test nested destructuring in assignments.
+// Ref: false
+
+---
+#{
+  let (a, b, c) = (0, 0, 0)
+  ((a, b), c) = ((1, 2), 3)
+  test((a, b, c), (1, 2, 3))
+}
+
+---
+#{
+  let (a, b, c) = (0, 0, 0)
+  (a, (b, ..c)) = (1, (2, 3, 4))
+  test((a, b, c), (1, 2, (3, 4)))
+}
+
+---
+#{
+  let dict = (x: 0, y: 0)
+  let z = 0
+  ((dict.x, dict.y), z) = ((1, 2), 3)
+  test(dict, (x: 1, y: 2))
+}
+
+---
+#{
+  let (a, b) = (0, 0)
+  // Error: 4-10 not enough elements to destructure
+  ((a, b),) = ((1,),)
+}
+
+---
+#{
+  let a = 0
+  // Error: 4-8 too many elements to destructure
+  ((a,),) = ((1, 2),)
+}
+
+---
+#{
+  let (a, b) = (0, 0)
+  // Error: 4-10 cannot destructure integer
+  ((a, b),) = (1,)
+}
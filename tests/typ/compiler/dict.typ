@@ -66,6 +66,20 @@
 #dict.remove("b")
 #test(dict.keys(), ("a", "c", "d"))
 
+---
+// Test that overwriting and joining keep the position of existing keys.
+#let dict = (b: 1, a: 2)
+#(dict.b = 3)
+#(dict.c = 4)
+#test(dict.keys(), ("b", "a", "c"))
+#test((dict + (a: 5, d: 6)).pairs(), (("b", 3), ("a", 5), ("c", 4), ("d", 6)))
+#test(repr((z: 1, y: 2)), "(z: 1, y: 2)")
+
+---
+// Unexpected keys are reported in the order they were written.
+// Error: 29-69 unexpected key "foo", valid keys are "paint", "thickness", "cap", "join", "dash", and "miter-limit"
+#line(length: 60pt, stroke: (paint: red, foo: 1pt, bar: 2pt, baz: 3))
+
 ---
 // Error: 24-29 duplicate key: first
 #(first: 1, second: 2, first: 3)
@@ -0,0 +1,18 @@
+// Test that experimental features must be enabled.
+// Features: none
+// Ref: false
+
+---
+// Error: 3:4-3:10 nested destructuring is an experimental feature, enable it with `--features nested-destructuring`
+#{
+  let (a, b, c) = (0, 0, 0)
+  ((a, b), c) = ((1, 2), 3)
+}
+
+---
+// Flat destructuring is stable.
+#{
+  let (a, b) = (0, 0)
+  (a, b) = (1, 2)
+  test((a, b), (1, 2))
+}
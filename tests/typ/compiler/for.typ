@@ -78,6 +78,27 @@
 #test(for v in "" [], none)
 #test(type(for v in "1" []), "content")
 
+---
+// Integer ranges.
+#let out = ()
+#for i in 1..4 {
+  out.push(i)
+}
+#test(out, (1, 2, 3))
+#test(1..1, ())
+#test(0..2 * 2, (0, 1, 2, 3))
+#test(3 in 1..5, true)
+
+#let steps = ()
+#for i in range(10, 0, step: -4) {
+  steps.push(i)
+}
+#test(steps, (10, 6, 2))
+
+---
+// Error: 3-9 cannot create range from integer and float
+#(1..2.5)
+
 ---
 // Uniterable expression.
 // Error: 11-15 cannot loop over boolean
@@ -171,3 +171,8 @@ This is never reached.
 ---
 // Error: 14 expected comma
 #import "": a b
+
+---
+// The test world has no network access.
+// Error: 9-38 failed to download file (network access is not available)
+#import "https://example.com/lib.typ": a
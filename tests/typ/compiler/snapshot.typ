@@ -0,0 +1,11 @@
+// Test snapshots of the syntax tree and evaluation result.
+// Ref: false
+// Snapshot: true
+
+---
+#let x = 1 + 2
+#x
+
+---
+// Error: 9-16 cannot add integer and string
+#let y = 1 + "a"
@@ -212,6 +212,54 @@
 #test(range(5, 2, step: -1), (5, 4, 3))
 #test(range(10, 0, step: -3), (10, 7, 4, 1))
 
+---
+// Test that ranges are lazy.
+#let r = range(1000000000)
+#test(type(r), "array")
+#test(r.len(), 1000000000)
+#test(r.first(), 0)
+#test(r.last(), 999999999)
+#test(r.at(-2), 999999998)
+#test(r.at(1000000000, default: none), none)
+#test(r.slice(10, count: 3), (10, 11, 12))
+#test(r.slice(-2), range(999999998, 1000000000))
+#test(999999 in r, true)
+#test(-1 in r, false)
+#test(2.0 in range(0, 10, step: 2), true)
+#test(3 in range(0, 10, step: 2), false)
+#test(r.rev().first(), 999999999)
+#test(range(1, 8, step: 3).rev(), (7, 4, 1))
+#test(range(0, 6, step: 2), range(0, 5, step: 2))
+#test(range(3, 3), range(5, 1))
+#test(repr(range(1, 10, step: 4)), "(1, 5, 9)")
+#test(repr(range(3, 4)), "(3,)")
+#test(repr(range(0)), "()")
+
+---
+// Ranges turn into arrays when arrays are needed.
+#test(range(3).map(x => x * 2), (0, 2, 4))
+#test(range(5).sum(), 10)
+#test(range(2) + (5,), (0, 1, 5))
+#test(range(2) * 2, (0, 1, 0, 1))
+#test((..range(3), 3), (0, 1, 2, 3))
+#test(calc.max(..range(4)), 3)
+#test(range(2).zip((3, 4)), ((0, 3), (1, 4)))
+#test(((range(2), 5), range(3, 5)).flatten(), (0, 1, 5, 3, 4))
+#let (a, .., b) = range(10)
+#test((a, b), (0, 9))
+#let r = range(3)
+#{ r.push(3) }
+#test(r, (0, 1, 2, 3))
+#test(type(r), "array")
+
+---
+// Error: 2-16 array index out of bounds (index: 5, len: 3) and no default value was specified
+#range(3).at(5)
+
+---
+// Error: 2-17 array is empty
+#range(0).last()
+
 ---
 // Error: 7-9 missing argument: end
 #range()
@@ -191,3 +191,86 @@
 ---
 // Error: 26-36 failed to format datetime in the requested format
 #datetime.today().display("[hour]")
+
+---
+// Test durations.
+#let d = duration(hours: 1, minutes: 30)
+#test(d.display(), "1:30:00")
+#test(d.minutes(), 90)
+#test(d.hours(), 1.5)
+#test(repr(duration(days: 8, seconds: 5)), "duration(weeks: 1, days: 1, seconds: 5)")
+#test(repr(duration()), "duration(seconds: 0)")
+#test(duration(days: 1, hours: 5).display("[days] days, [hours] hours"), "1 days, 5 hours")
+#test(duration(days: 1, hours: 5).display("[hours]:[minutes]"), "29:00")
+#test((-duration(minutes: 5)).display("[minutes]:[seconds]"), "-5:00")
+#test((duration(hours: 2) * 1.5).minutes(), 180)
+#test((duration(hours: 2) / 4).minutes(), 30)
+#test(duration(hours: 3) / duration(minutes: 45), 4.0)
+#test(duration(weeks: 1) - duration(days: 2), duration(days: 5))
+#test(duration(minutes: 90) > duration(hours: 1), true)
+
+---
+// Test arithmetic with datetimes.
+#test(
+  datetime(year: 2023, month: 12, day: 30) + duration(days: 3),
+  datetime(year: 2024, month: 1, day: 2),
+)
+#test(
+  duration(weeks: 1) + datetime(year: 2023, month: 6, day: 1),
+  datetime(year: 2023, month: 6, day: 8),
+)
+#test(
+  datetime(hour: 23, minute: 0, second: 0) + duration(hours: 2),
+  datetime(hour: 1, minute: 0, second: 0),
+)
+#test(
+  datetime(year: 2023, month: 6, day: 1, hour: 12, minute: 0, second: 0)
+    - duration(hours: 13),
+  datetime(year: 2023, month: 5, day: 31, hour: 23, minute: 0, second: 0),
+)
+#test(
+  (datetime(year: 2023, month: 3, day: 1) - datetime(year: 2023, month: 2, day: 1)).days(),
+  28,
+)
+#test(datetime(year: 2023, month: 3, day: 1) < datetime(year: 2023, month: 3, day: 2), true)
+
+---
+// Error: 3-62 cannot add a duration with a time component to a date
+#(datetime(year: 2023, month: 1, day: 1) + duration(hours: 1))
+
+---
+// Error: 3-83 cannot subtract a time from a date
+#(datetime(year: 2023, month: 1, day: 1) - datetime(hour: 1, minute: 0, second: 0))
+
+---
+// Error: 28-39 invalid component name 'years' at index 1
+#duration(hours: 1).display("[years]")
+
+---
+// Test versions.
+#let v = version(0, 5, 1)
+#test(v.major, 0)
+#test(v.minor, 5)
+#test(v.patch, 1)
+#test(v.at(1), 5)
+#test(v.at(-1), 1)
+#test(v.at(7), 0)
+#test(version(1, 2).patch, 0)
+#test(repr(version((1, 2), 3)), "version(1, 2, 3)")
+#test(version("1.2.0"), version(1, 2))
+#test(version(0, 10) > version(0, 9, 9), true)
+#test(version(1) >= version(1, 0, 0), true)
+#test(type(sys.version), "version")
+#test(sys.version >= version(0, 5), true)
+
+---
+// Error: 10-15 invalid version component `a`
+#version("1.a")
+
+---
+// Error: 13-18 unknown version component `build`
+#version(1).build
+
+---
+// Error: 3-20 cannot compare version and integer
+#(version(1, 2) < 2)
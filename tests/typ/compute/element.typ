@@ -0,0 +1,60 @@
+// Test user-defined element types.
+// Ref: false
+
+---
+#let theorem = element(
+  "theorem",
+  fields: (title: "content", body: "content"),
+  display: it => [Theorem: #it.title],
+)
+
+#let thm = theorem(title: "Pythagoras")[$a^2 + b^2 = c^2$]
+#test(thm.func(), theorem)
+#test(thm.title, [Pythagoras])
+#test(thm.has("body"), true)
+#test(theorem([A], [B]).body, [B])
+#test(theorem().title, none)
+#test(repr(theorem), "theorem")
+
+---
+// Show rules and where selectors.
+#let note = element("note", fields: (kind: "string", body: "content"))
+#show note.where(kind: "warning"): it => text(red, it.body)
+#show note: it => {
+  test(it.func(), note)
+  it.body
+}
+#note(kind: "warning")[Careful]
+#note(kind: "info")[Nothing to see]
+
+---
+// Queries and counters.
+#let lemma = element("lemma", fields: (body: "content"))
+#show lemma: it => [Lemma #counter(lemma).display(): #it.body]
+#lemma[First]
+#lemma[Second]
+#locate(loc => {
+  test(query(lemma, loc).len(), 2)
+  test(counter(lemma).final(loc), (2,))
+})
+
+---
+// Elements with the same name are still distinct.
+#let a = element("same")
+#let b = element("same")
+#test(a == b, false)
+#test(a == a, true)
+
+---
+#let theorem = element("theorem", fields: (count: "integer"))
+// Error: 10-15 expected integer, found string
+#theorem("ten")
+
+---
+// Error: 2-39 the field name `element` is reserved
+#element("x", fields: (element: auto))
+
+---
+#let theorem = element("theorem")
+// Error: 6-13 only element functions can be used in set rules
+#set theorem(title: none)
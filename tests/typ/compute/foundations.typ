@@ -120,3 +120,59 @@ _No relative giraffe!_
 ---
 // Error: 7-12 expected semicolon or line break
 #eval("1 2")
+
+---
+#test(eval("1 + 2", mode: "code"), 3)
+#test(eval("*Hello*", mode: "markup"), strong[Hello])
+
+---
+// Error: 7-31 cannot access file system from here
+#eval("#image(\"/tiger.jpg\")", mode: "markup")
+
+---
+// Test passing definitions to evaluated code.
+#test(eval("x + y", scope: (x: 1, y: 2)), 3)
+#test(eval("calc.abs(x)", scope: (x: -2)), 2)
+#test(eval("(x: x)", scope: (x: none)), (x: none))
+
+---
+// Test sandboxed code.
+#test(eval("upper(x)", scope: (x: "a", upper: upper), sandbox: true), "A")
+#test(eval("*Hello*", mode: "markup", sandbox: true), strong[Hello])
+
+---
+// Error: 7-16 unknown variable: calc
+#eval("calc.pi", sandbox: true)
+
+---
+// Error: 7-30 cannot access file system from here
+#eval("image(\"/tiger.jpg\")", scope: (image: image), sandbox: true)
+
+---
+// Test inheriting the visible variables.
+#let x = 1
+#{
+  let y = 2
+  test(eval("x + y", inherit: true), 3)
+  test(eval("x + y", inherit: true, scope: (y: 3)), 4)
+  test(eval("(x, upper)", inherit: true, sandbox: true, scope: (upper: 0)), (1, 0))
+}
+
+---
+// The evaluated code works on a copy of the variables.
+#let x = 1
+#test(eval("{ x += 1; x }", inherit: true), 2)
+#test(x, 1)
+
+---
+// Error: 2:7-2:10 unknown variable: x
+#let x = 1
+#eval("x")
+
+---
+// Error: 7-46 cannot access network from here
+#eval("image(\"https://typst.app/logo.png\")")
+
+---
+// Error: 7-50 cannot access network from here
+#eval("import \"https://example.com/lib.typ\": a")
@@ -0,0 +1,6 @@
+// Test the inputs the compiler was invoked with.
+// Ref: false
+
+---
+#test(sys.inputs, (:))
+#test(sys.inputs.at("author", default: "Anonymous"), "Anonymous")
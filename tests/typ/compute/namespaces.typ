@@ -0,0 +1,37 @@
+// Test the namespaced modules of the standard library.
+// Ref: false
+
+---
+// The namespaced definitions are the same as the global ones.
+#test(color.red, red)
+#test(color.rgb("#ff4136"), red)
+#test(color.luma(0), luma(0))
+#test(array.range(3), range(3))
+#test(array.range(1, 7, step: 2), (1, 3, 5))
+#test(str.regex("a+"), regex("a+"))
+#test(str.upper("abc"), "ABC")
+#test(layout.page, page)
+#test(layout.left, left)
+#test(calc.min(1, 2), 1)
+
+---
+// The namespaces stay reachable when global names are shadowed.
+#let red = "Rot"
+#let range = none
+#let page = 1
+#test(color.red, rgb("#ff4136"))
+#test(array.range(2), (0, 1))
+#test(type(layout.page), "function")
+
+---
+// The layout function still works.
+#layout(size => test(type(size.width), "length"))
+
+---
+// Error: 2-18 module `color` does not contain `purple-ish`
+#color.purple-ish
+
+---
+// The old global names of moved definitions still work, but warn.
+// Warning: 7-11 `cmyk` is deprecated
+#test(cmyk(0%, 0%, 0%, 100%), color.cmyk(0%, 0%, 0%, 100%))
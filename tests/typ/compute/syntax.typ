@@ -0,0 +1,32 @@
+// Test accessing the unevaluated syntax of content.
+// Ref: false
+
+---
+#let it = [*Hello* _world_]
+#test(it.syntax(), "[*Hello* _world_]")
+#test(strong[A].syntax(), "strong[A]")
+#test(raw("x").syntax(), "raw(\"x\")")
+
+---
+// Content built by code has the syntax of the expression that produced it.
+#test(([A] + [B]).syntax(), "[A] + [B]")
+#let greet(name) = [Hello #name]
+#test(greet("you").syntax(), "[Hello #name]")
+
+---
+// Show rules receive the source of their target.
+#show raw.where(lang: "sum"): it => {
+  test(it.syntax(), "```sum 1 2 3```")
+  let total = it.text.split().map(int).sum()
+  test(total, 6)
+}
+
+```sum 1 2 3```
+
+---
+// Evaluating the syntax doesn't see the surrounding variables.
+#let x = 1
+#let code = [#x].syntax()
+#test(code, "[#x]")
+// Error: 7-24 unknown variable: x
+#eval(code.slice(1, -1), mode: "markup")
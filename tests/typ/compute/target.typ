@@ -0,0 +1,10 @@
+// Test querying the export target.
+// Ref: false
+
+---
+#test(sys.target(), "pdf")
+#test(type(sys.target()), "string")
+
+---
+// Error: 13-18 unexpected argument
+#sys.target("png")
@@ -0,0 +1,8 @@
+// Test change bars.
+// Ref: false
+
+---
+#set page(height: 60pt)
+#changebar(stroke: 2pt + red, gap: 4pt)[
+  #lorem(30)
+]
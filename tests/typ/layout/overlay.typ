@@ -0,0 +1,19 @@
+// Test stacking content in the size of a base.
+// Ref: false
+
+---
+#let badge = circle(radius: 4pt, fill: red)
+#overlay(rect(width: 40pt, height: 30pt), badge, top + right)
+#overlay(rect(width: 40pt, height: 30pt), badge, alignment: bottom)
+#underlay([*Text*], rect(width: 100%, height: 100%, fill: yellow))
+
+---
+// The base determines the size.
+#style(styles => {
+  let size = measure(overlay(rect(width: 20pt, height: 10pt), rect(width: 50pt)), styles)
+  test(size, (width: 20pt, height: 10pt))
+})
+
+---
+// Error: 9-12 missing argument: layer
+#overlay[A]
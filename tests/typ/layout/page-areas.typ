@@ -0,0 +1,25 @@
+// Test flowing content through multiple areas per page.
+// Ref: false
+
+---
+#set page(height: 120pt, margin: 10pt, areas: (
+  (x: 0pt, y: 0pt, width: 60%, height: 45%),
+  (x: 40%, y: 55%, width: 60%, height: 45%),
+))
+#lorem(30)
+#colbreak()
+Second area.
+#colbreak()
+First area on the next page.
+
+---
+// Error: 18-49 missing key: "height"
+#set page(areas: ((x: 0pt, y: 0pt, width: 50%),))
+
+---
+// Error: 18-73 unexpected key "fill", valid keys are "x", "y", "width", and "height"
+#set page(areas: ((x: 0pt, y: 0pt, width: 1pt, height: 1pt, fill: red),))
+
+---
+// Error: 2-76 pages with areas must have a fixed width and height
+#page(height: auto, areas: ((x: 0pt, y: 0pt, width: 1pt, height: 1pt),))[A]
@@ -0,0 +1,51 @@
+// Test overriding the marginals of single pages.
+// Ref: false
+
+---
+// Each laid out header and footer counts itself.
+#let headers = state("headers", 0)
+#let footers = state("footers", 0)
+#set page(
+  height: 60pt,
+  header: headers.update(n => n + 1),
+  footer: footers.update(n => n + 1),
+)
+
+#show heading: it => {
+  pagebreak(weak: true)
+  page-override(header: none)
+  it
+}
+
+= One
+#pagebreak()
+Two
+= Three
+#page-override(footer: none)
+
+#locate(loc => {
+  test(headers.final(loc), 1)
+  test(footers.final(loc), 2)
+})
+
+---
+// Later overrides win and replacements count as well.
+#let backgrounds = state("backgrounds", 0)
+#set page(height: 60pt, background: [Draft])
+#page-override(background: none)
+#page-override(background: backgrounds.update(n => n + 1))
+#locate(loc => test(backgrounds.final(loc), 1))
+
+---
+// Set rules apply to the overrides.
+#let footers = state("footers", 0)
+#set page(height: 60pt, footer: footers.update(n => n + 1))
+#set page-override(footer: none)
+#page-override()
+#pagebreak()
+Two
+#locate(loc => test(footers.final(loc), 1))
+
+---
+// Error: 28-31 expected content, none, or auto, found length
+#page-override(foreground: 1pt)
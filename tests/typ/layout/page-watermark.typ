@@ -0,0 +1,8 @@
+// Test watermarks.
+// Ref: false
+
+---
+#set page(width: 100pt, height: 100pt, background: watermark[DRAFT])
+#set page(background: watermark(angle: 30deg, opacity: 50%, fill: red, size: 20pt)[Copy])
+Body
+
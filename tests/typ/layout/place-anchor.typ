@@ -0,0 +1,34 @@
+// Test placement relative to labelled elements.
+// Ref: false
+
+---
+#box(rect[Figure]) <fig>
+#place(top + right, anchor: <fig>, dx: 4pt, dy: -4pt, circle(radius: 3pt))
+#place(bottom, anchor: <fig>, dy: 50%)[Annotation]
+
+---
+// Alignment and displacement are relative to the anchored element.
+#let mark = box(width: 4pt, height: 4pt)
+#box(width: 30pt, height: 20pt) <fig>
+#place(bottom + right, anchor: <fig>, dx: 5pt)[#mark <corner>]
+#place(top + left, anchor: <fig>, dy: 50%)[#mark <half>]
+#locate(loc => {
+  let offset(label) = {
+    let fig = query(<fig>, loc).first().location().position()
+    let pos = query(label, loc).first().location().position()
+    test(pos.page, fig.page)
+    (calc.round((pos.x - fig.x) / 1pt, digits: 3), calc.round((pos.y - fig.y) / 1pt, digits: 3))
+  }
+  test(offset(<corner>), (31.0, 16.0))
+  test(offset(<half>), (0.0, 10.0))
+})
+
+---
+// Error: 2-32 label does not exist in the document
+#place(anchor: <missing>)[Text]
+
+---
+#box[A] <a>
+#pagebreak()
+// Error: 2-26 anchored element must be on the same page
+#place(anchor: <a>)[Text]
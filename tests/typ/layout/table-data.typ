@@ -0,0 +1,27 @@
+// Test tables with rows generated from data.
+// Ref: false
+
+---
+#let fruits = (
+  (name: "Apples", count: 12),
+  (name: "Pears", count: 7),
+)
+
+#table(
+  columns: 2,
+  data: fruits,
+  row: fruit => ([#fruit.name], [#fruit.count]),
+  [*Fruit*], [*Count*],
+)
+
+---
+// Error: 2-33 cannot generate rows from data without a row function
+#table(columns: 2, data: (1, 2))
+
+---
+// Error: 37-43 expected array, found integer
+#table(columns: 2, data: (1,), row: x => x)
+
+---
+// Error: 37-57 expected at most 2 cells, found 3
+#table(columns: 2, data: (1,), row: x => ([a], [b], [c]))
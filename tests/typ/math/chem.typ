@@ -0,0 +1,46 @@
+// Test chemistry and unit notations.
+// Ref: false
+
+---
+// Element symbols are upright, counts become subscripts and charges
+// superscripts.
+#let up = math.upright
+#test(math.ce("H2O"), math.attach(up("H"), br: up("2")) + math.attach(up("O")))
+#test(math.ce("Na+"), math.attach(up("Na"), tr: up("+")))
+#test(
+  math.ce("SO4^2-"),
+  math.attach(up("S")) + math.attach(up("O"), br: up("4"), tr: up("2−")),
+)
+
+---
+// Coefficients stay in line and arrows are replaced.
+#let up = math.upright
+#test(
+  math.ce("2H2 -> H2"),
+  up("2") + math.attach(up("H"), br: up("2")) + [ ] + [→] + [ ]
+    + math.attach(up("H"), br: up("2")),
+)
+#test(
+  math.ce("A <=> B"),
+  math.attach(up("A")) + [ ] + [⇌] + [ ] + math.attach(up("B")),
+)
+
+---
+#let up = math.upright
+#test(math.isotope("C", 14), math.attach(up("C"), tl: [14], bl: none))
+#test(math.isotope("U", 235, number: 92), math.attach(up("U"), tl: [235], bl: [92]))
+
+---
+// Factors are separated by thin spaces and exponents are superscripts.
+#let up = math.upright
+#test(
+  math.unit("m/s^2"),
+  math.attach(up("m")) + up("/") + math.attach(up("s"), tr: up("2")),
+)
+#test(
+  math.unit("N*A^-2"),
+  math.attach(up("N")) + h(1em / 6) + math.attach(up("A"), tr: up("−2")),
+)
+
+---
+$ ce("2H2 + O2 -> 2H2O") quad isotope("U", 235, number: 92) quad 9.81 thin unit("m/s^2") $
@@ -0,0 +1,47 @@
+// Test the gaps and augmentation lines of matrices, vectors and cases.
+// Ref: false
+
+---
+// Gaps lie between the rows and columns, but not around them.
+#let mat = math.mat.with(delim: none, (1, 2), (3, 4))
+#style(styles => {
+  let size(body) = measure(math.equation(body), styles)
+  let grown(body) = {
+    let a = size(body)
+    let b = size(mat(gap: 0pt))
+    let round(x) = calc.round(x / 1pt, digits: 3)
+    (round(a.width - b.width), round(a.height - b.height))
+  }
+  test(grown(mat(row-gap: 0pt, column-gap: 10pt)), (10.0, 0.0))
+  test(grown(mat(row-gap: 10pt, column-gap: 0pt)), (0.0, 10.0))
+  test(grown(mat(gap: 10pt)), (10.0, 10.0))
+  test(grown(mat(gap: 10pt, row-gap: 0pt)), (10.0, 0.0))
+})
+
+---
+// Vectors and cases have a gap between each two of their elements.
+#style(styles => {
+  let height(body) = measure(math.equation(body), styles).height
+  let vec = math.vec.with(delim: none, $1$, $2$, $3$)
+  let grown = height(vec(gap: 10pt)) - height(vec(gap: 0pt))
+  test(calc.round(grown / 1pt, digits: 3), 20.0)
+  let cases = math.cases.with($1$, $2$)
+  test(height(cases(gap: 30pt)) > height(cases(gap: 0pt)), true)
+})
+
+---
+// Augmentation lines don't take any space.
+#style(styles => {
+  let size(body) = measure(math.equation(body), styles)
+  let mat = math.mat.with((1, 2), (3, 4))
+  test(size(mat(augment: 1)), size(mat()))
+  test(size(mat(augment: (hline: 1, vline: -1, stroke: 4pt))), size(mat()))
+})
+
+---
+// Error: 3-40 cannot draw a horizontal line at offset 2
+$ mat(augment: #(hline: 2), 1, 2; 3, 4) $
+
+---
+// Error: 3-41 cannot draw a vertical line at offset -2
+$ mat(augment: #(vline: -2), 1, 2; 3, 4) $
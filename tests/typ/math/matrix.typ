@@ -63,3 +63,7 @@ $ mat(B, A B) $
 $ mat(B, A B, dots) $
 $ mat(B, A B, dots;) $
 $ mat(#1, #(foo: "bar")) $
+
+---
+// Error: 3-31 cannot draw a vertical line at offset 3
+$ mat(augment: #3, 1, 2; 3, 4) $
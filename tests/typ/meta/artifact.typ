@@ -0,0 +1,28 @@
+// Test marking content as decorative.
+// Ref: false
+
+---
+#set page(header: [Header], background: circle(radius: 1cm))
+= Chapter
+#artifact(align(center)[❦])
+Some text with an #artifact[inline] ornament.
+
+#text(lang: "de")[Ein deutscher Satz.]
+
+---
+// Artifacts still take part in introspection.
+#artifact[= Hidden heading]
+#locate(loc => test(query(heading, loc).len(), 1))
+
+---
+// Error: 10-12 missing argument: body
+#artifact()
+
+---
+// Placed content can move in the reading order.
+#place(bottom, order: -1)[Footnote read first]
+Body
+
+---
+// Error: 15-18 expected integer or none, found string
+#place(order: "1")[A]
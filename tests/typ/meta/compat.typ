@@ -0,0 +1,64 @@
+// Test pinning the language version.
+// Ref: false
+
+---
+// Older versions restore start-aligned enum numbers. The marks record where
+// each number ends.
+#let ends = state("ends", ())
+#let mark = locate(loc => ends.update(v => v + (loc.position().x,)))
+#set enum(numbering: n => [#n.#mark])
+
+#set std(version: "0.3")
+1. One
+10. Ten
+
+#locate(loc => {
+  let (one, ten) = ends.final(loc)
+  test(one < ten, true)
+})
+
+---
+// Newer versions and later set rules align the numbers to the end.
+#let ends = state("ends", ())
+#let mark = locate(loc => ends.update(v => v + (loc.position().x,)))
+#set enum(numbering: n => [#n.#mark])
+
+#set std(version: "0.3")
+#set std(version: sys.version)
+#set enum(number-align: end)
+1. One
+10. Ten
+
+#locate(loc => {
+  let (one, ten) = ends.final(loc)
+  test(one, ten)
+})
+
+---
+// Since 0.4, the columns between alignment points alternate between right
+// and left alignment, before they were all aligned to the right.
+#let ends = state("ends", ())
+#let mark = locate(loc => ends.update(v => v + (loc.position().x,)))
+
+$ a & b #mark \ a a & b b b #mark $
+
+#set std(version: "0.3")
+$ a & b #mark \ a a & b b b #mark $
+
+#locate(loc => {
+  let (new-short, new-long, old-short, old-long) = ends.final(loc)
+  test(new-short < new-long, true)
+  test(old-short, old-long)
+})
+
+---
+// Error: 19-25 version 99.0 is newer than this compiler
+#set std(version: "99.0")
+
+---
+// Error: 19-29 invalid version component `latest`
+#set std(version: "0.latest")
+
+---
+// Error: 5-7 can only be used in set rules
+#std()
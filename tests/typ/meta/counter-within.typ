@@ -0,0 +1,41 @@
+// Test shared counters and counters that restart within sections.
+// Ref: false
+
+---
+#let theorem = element("theorem", fields: (body: "content"))
+#let lemma = element("lemma", fields: (body: "content"))
+#let shared = counter((theorem, lemma))
+#theorem[A]
+#lemma[B]
+#theorem[C]
+#locate(loc => test(shared.final(loc), (3,)))
+
+---
+#let thm = counter("thm", within: heading.where(level: 1))
+#thm.step()
+#locate(loc => test(thm.at(loc), (0, 1)))
+
+= First
+#thm.step()
+#thm.step()
+#locate(loc => test(thm.at(loc), (1, 2)))
+
+== Nested
+#thm.step()
+#locate(loc => test(thm.at(loc), (1, 3)))
+
+= Second
+#thm.step()
+#locate(loc => test(thm.at(loc), (2, 1)))
+#locate(loc => test(thm.final(loc), (2, 1)))
+
+---
+// Counters with a different `within` are distinct.
+#let a = counter("x")
+#let b = counter("x", within: heading)
+#a.step()
+#locate(loc => test(b.at(loc), (0, 0)))
+
+---
+// Error: 10-20 expected function, label, or selector, found string
+#counter(("a", "b"))
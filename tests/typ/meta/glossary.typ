@@ -0,0 +1,43 @@
+// Test glossary entries.
+// Ref: false
+
+---
+// Only the first use of a term is expanded. The show rule counts the
+// expansions.
+#let expanded = state("expanded", 0)
+#show "Hypertext Transfer Protocol (HTTP)": it => {
+  expanded.update(n => n + 1)
+  it
+}
+
+#glossary-entry("http", "HTTP", long: "Hypertext Transfer Protocol")
+#gls("http") and #gls("http").
+
+#locate(loc => {
+  test(query(gls, loc).len(), 2)
+  test(query(glossary-entry.where(key: "http"), loc).first().short, "HTTP")
+  test(expanded.final(loc), 1)
+})
+
+---
+// Set rules apply to the entries and the glossary lists their long forms.
+#let listed = state("listed", ())
+#show glossary: it => {
+  show regex("^ \(.*\)$"): it => {
+    listed.update(v => v + (it.text,))
+    it
+  }
+  it
+}
+
+#set glossary-entry(long: "Portable Document Format")
+#glossary-entry("pdf", "PDF")
+#glossary-entry("pdf", "Ignored", long: "Ignored Definition")
+#gls("pdf")
+#glossary()
+
+#locate(loc => test(listed.final(loc), (" (Portable Document Format)",)))
+
+---
+// Error: 2-13 glossary entry `nope` is not defined
+#gls("nope")
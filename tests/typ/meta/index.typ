@@ -0,0 +1,63 @@
+// Test index entries.
+// Ref: false
+
+---
+#index-entry("Typst")
+#index-entry("Typst", sub: "syntax")
+#index-entry("LaTeX", see: "Typst")
+#locate(loc => {
+  let entries = query(index-entry, loc)
+  test(entries.len(), 3)
+  test(entries.at(1).sub, "syntax")
+  test(entries.at(2).see, "Typst")
+})
+
+---
+// Terms are sorted case-insensitively, followed by their sub-entries. The
+// show rule records the listed terms and cross references.
+#let seen = state("seen", ())
+#show index: it => {
+  show regex("^(Typst|LaTeX|syntax|see|zebra)$"): it => {
+    seen.update(v => v + (it.text,))
+    it
+  }
+  it
+}
+
+#index-entry("zebra")
+#index-entry("Typst", sub: "syntax")
+#index-entry("Typst")
+#index-entry("LaTeX", see: "Typst")
+#index()
+
+#locate(loc => {
+  test(seen.final(loc), ("LaTeX", "see", "Typst", "syntax", "zebra"))
+})
+
+---
+// Set rules apply to the entries.
+#set index-entry(see: "Typst")
+#index-entry("LaTeX")
+#locate(loc => test(query(index-entry, loc).first().see, "Typst"))
+
+---
+// Consecutive pages are merged into ranges.
+#set page(height: 40pt)
+#let ranges = state("ranges", 0)
+#show index: it => {
+  show "–": it => {
+    ranges.update(n => n + 1)
+    it
+  }
+  it
+}
+
+#index-entry("A")
+#pagebreak()
+#index-entry("A")
+#pagebreak()
+#pagebreak()
+#index-entry("A")
+#index(title: none)
+
+#locate(loc => test(ranges.final(loc), 1))
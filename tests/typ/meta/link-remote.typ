@@ -0,0 +1,20 @@
+// Test links into other PDF files.
+// Ref: false
+
+---
+#test(link((file: "notes.pdf", page: 5))[A].dest, (file: "notes.pdf", page: 5))
+#test(link((file: "notes.pdf", name: "intro"))[A].dest, (file: "notes.pdf", name: "intro"))
+#test(link((file: "notes.pdf"))[A].dest, (file: "notes.pdf", page: 1))
+
+---
+// Labels become named destinations.
+= Introduction <intro>
+#link((file: "notes.pdf", name: "intro"))[Same section in the notes]
+
+---
+// Error: 7-51 cannot link to both a page and a name
+#link((file: "notes.pdf", page: 1, name: "intro"))[A]
+
+---
+// Error: 7-35 unexpected key "x", valid keys are "file", "page", and "name"
+#link((file: "notes.pdf", x: 1pt))[A]
@@ -0,0 +1,28 @@
+// Test structural warnings.
+// Ref: false
+
+---
+= Intro
+Text.
+
+// Warning: 1-9 heading level jumps from 1 to 3
+=== Deep
+More text.
+
+---
+// Warning: 1-8 section is empty
+= First
+= Second
+Text.
+
+---
+#figure[A] <fig>
+
+// Warning: 2-11 label `<fig>` occurs multiple times
+#figure[B] <fig>
+
+---
+#image("/tiger.jpg", width: 10pt, alt: "A tiger")
+
+// Warning: 2-34 image has no alternative text
+#image("/tiger.jpg", width: 10pt)
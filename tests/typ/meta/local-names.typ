@@ -0,0 +1,23 @@
+// Test overriding the names of built-in elements.
+// Ref: false
+
+---
+#set text(names: (figure: (de: "Abb.", de-CH: "Abbildg.", en: "Fig.")))
+#set text(names: (heading: "Part"))
+
+#figure(rect(), caption: [A]) <a>
+#set text(lang: "de")
+#figure(rect(), caption: [B]) <b>
+#set text(region: "CH")
+#figure(rect(), caption: [C]) <c>
+= Intro <d>
+#set text(lang: "fr", region: none)
+#figure(table[x], caption: [D]) <e>
+
+#locate(loc => {
+  test(query(<a>, loc).first().supplement.text, "Fig.")
+  test(query(<b>, loc).first().supplement.text, "Abb.")
+  test(query(<c>, loc).first().supplement.text, "Abbildg.")
+  test(query(<d>, loc).first().supplement.text, "Part")
+  test(query(<e>, loc).first().supplement.text, "Tableau")
+})
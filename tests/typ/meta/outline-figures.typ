@@ -0,0 +1,29 @@
+// Test the automatic titles of outlines of figures.
+// Ref: false
+
+---
+#outline(target: figure.where(kind: table))
+#set text(lang: "de")
+#outline(target: figure.where(kind: image))
+#set text(lang: "zh", region: "TW")
+#outline(target: figure.where(kind: image))
+#set text(lang: "ar", region: none)
+#outline(target: figure.where(kind: table))
+#set text(lang: "fr")
+#outline(target: heading)
+
+// Overridden names take precedence.
+#set text(names: (outline: "Verzeichnis"))
+#outline(target: figure.where(kind: table))
+
+#locate(loc => test(
+  query(heading, loc).map(it => it.body.text),
+  (
+    "List of Tables",
+    "Abbildungsverzeichnis",
+    "插圖",
+    "المحتويات",
+    "Table des matières",
+    "Verzeichnis",
+  ),
+))
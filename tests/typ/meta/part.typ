@@ -0,0 +1,34 @@
+// Test front matter, main matter and appendix parts.
+// Ref: false
+
+---
+#set page(height: 80pt)
+#set heading(numbering: "1.")
+#show: part.with("front", title: [Front matter])
+= Preface
+#locate(loc => {
+  test(counter(page).at(loc), (1,))
+  test(query(heading, loc).first().numbering, none)
+})
+
+#show: part.with("main")
+= Introduction
+#locate(loc => test(counter(page).at(loc), (1,)))
+#locate(loc => test(counter(heading).at(loc), (1,)))
+
+= Background
+#show: part.with("appendix", title: [Appendices])
+= Proofs
+#locate(loc => {
+  test(counter(heading).at(loc), (1,))
+  test(query(heading, loc).last().numbering, "A.1")
+})
+
+---
+#show: part.with("appendix", heading-numbering: "I.")
+= First
+#locate(loc => test(query(heading, loc).first().numbering, "I."))
+
+---
+// Error: 18-27 expected "front", "main", or "appendix"
+#show: part.with("preface")
@@ -0,0 +1,42 @@
+// Test code examples with their results.
+// Ref: false
+
+---
+#example("*Hello* _world_")
+#example("calc.pow(2, 10)", mode: "code", dir: ltr)
+#example("#lorem(5)", result: false)
+
+---
+// The code is shown as a raw block and the result is stacked next to it.
+#let code = raw("*Hello*", block: true, lang: "typ")
+#let result = eval("*Hello*", mode: "markup")
+#style(styles => {
+  let code = measure(code, styles)
+  let result = measure(result, styles)
+  let round(x) = calc.round(x / 1pt, digits: 3)
+
+  let alone = measure(example("*Hello*", result: false), styles)
+  test(alone, code)
+
+  let below = measure(example("*Hello*", gap: 5pt), styles)
+  test(round(below.width), round(calc.max(code.width, result.width)))
+  test(round(below.height), round(code.height + 5pt + result.height))
+
+  let beside = measure(example("*Hello*", dir: ltr, gap: 5pt), styles)
+  test(round(beside.width), round(code.width + 5pt + result.width))
+  test(round(beside.height), round(calc.max(code.height, result.height)))
+})
+
+---
+// Code mode is highlighted as Typst code and evaluated as such.
+#style(styles => {
+  let size = measure(example("1 + 2", mode: "code", result: false), styles)
+  test(size, measure(raw("1 + 2", block: true, lang: "typc"), styles))
+  let full = measure(example("1 + 2", mode: "code", gap: 0pt), styles)
+  let sum = size.height + measure([3], styles).height
+  test(calc.round(full.height / 1pt, digits: 3), calc.round(sum / 1pt, digits: 3))
+})
+
+---
+// Error: 2-18 unknown variable: oops
+#example("#oops")
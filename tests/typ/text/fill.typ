@@ -0,0 +1,42 @@
+// Test gradient and function fills for text.
+// Ref: false
+
+---
+#set text(fill: gradient(red, orange, green, blue, purple))
+= A rainbow heading
+Gradients span each run of text.
+
+---
+#test(text(fill: gradient(red, blue))[A].fill, gradient(red, blue))
+#test(repr(gradient(red, blue)), "gradient(rgb(\"#ff4136\"), rgb(\"#0074d9\"))")
+
+---
+// A function is called once per run with the relative positions of the
+// glyphs' centers, in order.
+#let check(positions) = {
+  test(positions.len(), 5)
+  test(positions.sorted(), positions)
+  test(positions.first() > 0%, true)
+  test(positions.last() < 100%, true)
+  black
+}
+#text(fill: check)[Zebra]
+
+---
+// A function may also return one paint per glyph.
+#let striped(positions) = range(positions.len())
+  .map(i => if calc.even(i) { black } else { eastern })
+#text(fill: striped)[Zebra text]
+#text(fill: positions => positions.map(pos => luma(pos)))[Fading away]
+
+---
+// Error: 2-15 gradient needs at least two colors
+#gradient(red)
+
+---
+// Error: 13-37 expected 5 paints, found 2
+#text(fill: positions => (red, blue))[Zebra]
+
+---
+// Error: 13-19 expected color or array, found integer
+#text(fill: x => 1)[Oops]
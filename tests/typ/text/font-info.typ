@@ -0,0 +1,19 @@
+// Test the font introspection function.
+// Ref: false
+
+---
+#let info = font-info("Linux Libertine", coverage: "Füße")
+#test(info.family, "Linux Libertine")
+#test(info.variants.len(), 4)
+#test(info.variants.map(v => v.style).contains("italic"), true)
+#test(info.features.contains("liga"), true)
+#test(info.covers, true)
+#test(type(info.metrics.x-height), "length")
+
+---
+#test(font-info("IBM Plex Sans", coverage: "ก").covers, false)
+#test("covers" in font-info("IBM Plex Sans"), false)
+
+---
+// Error: 12-18 unknown font family: Nope
+#font-info("Nope")
@@ -0,0 +1,25 @@
+// Test shaped glyph positions.
+// Ref: false
+
+---
+#style(styles => {
+  let gs = glyphs("ab", styles)
+  test(gs.len(), 2)
+  test(gs.map(g => g.text), ("a", "b"))
+  test(gs.at(0).x, 0pt)
+  test(gs.at(1).x, gs.at(0).advance)
+  test(gs.at(0).font, "Linux Libertine")
+})
+
+---
+// Ligatures form a single glyph for the whole cluster.
+#style(styles => {
+  let gs = glyphs("fi", styles)
+  test(gs.len(), 1)
+  test(gs.at(0).text, "fi")
+})
+
+---
+#style(styles => {
+  test(glyphs("", styles), ())
+})
@@ -0,0 +1,21 @@
+// Test hyphenation exceptions.
+// Ref: false
+
+---
+#set text(hyphenation-exceptions: ("Typ-st",))
+#set text(hyphenation-exceptions: (de: ("Sil-ben-tren-nung",), all: ("table",)))
+
+---
+// Error: 35-52 expected two or three letter language code (ISO 639-1/2/3)
+#set text(hyphenation-exceptions: (english: ("a",)))
+
+---
+// Only the exception's break points are used.
+#set text(hyphenate: true)
+#style(styles => {
+  let height(exceptions) = measure(
+    block(width: 20pt, text(hyphenation-exceptions: exceptions)[Typst]),
+    styles,
+  ).height
+  test(height(("Typ-st",)) > height(("Typst",)), true)
+})
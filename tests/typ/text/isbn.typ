@@ -0,0 +1,54 @@
+// Test ISBN, phone number and currency elements.
+// Ref: false
+
+---
+#isbn("978-3-16-148410-0") \
+#isbn("0-306-40615-2", prefix: false) \
+#isbn("0 8044 2957 X")
+
+---
+#show isbn: it => test(it.number, "978-3-16-148410-0")
+#isbn("978-3-16-148410-0")
+
+---
+// Error: 7-26 ISBN has invalid checksum
+#isbn("978-3-16-148410-1")
+
+---
+// Error: 7-14 ISBN must have 10 or 13 digits
+#isbn("12345")
+
+---
+#phone("+49 30 123-4567") \
+#phone("+1 555 0100", link: true)
+
+---
+#show phone: it => test(it.link, true)
+#phone("+1 555 0100", link: true)
+
+---
+// Amounts are written like in the text's language and kept on one line.
+#style(styles => {
+  let same(a, b) = test(measure(a, styles), measure(b, styles))
+  same(currency(1234.5, "USD"), [\$1,234.50])
+  same(text(lang: "de", currency(1234.5, "EUR")), [1.234,50~€])
+  same(currency(1234.5, "EUR", symbol: false), [EUR~1,234.50])
+  same(currency(1999.99, "EUR", digits: 0), [€2,000])
+  same(currency(1200, "JPY"), [¥1,200])
+
+  let amount = currency(-1234567, "EUR", symbol: false)
+  let narrow = measure(block(width: 0pt, amount), styles)
+  test(narrow.height, measure(amount, styles).height)
+})
+
+---
+#show currency: it => test((it.amount, it.code), (12.5, "CHF"))
+#currency(12.5, "CHF")
+
+---
+// Error: 14-20 currency code must consist of three uppercase letters
+#currency(1, "euro")
+
+---
+// Error: 11-21 amount must be finite
+#currency(1e308 * 10, "EUR")
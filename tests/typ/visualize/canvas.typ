@@ -0,0 +1,62 @@
+// Test canvas drawing.
+// Ref: false
+
+---
+#import canvas: node, edge, walk
+#canvas(length: 1.5cm)[
+  #node((0, 0), [Start], name: "a")
+  #node((2, 0), [End], name: "b", stroke: 0.5pt)
+  #node("b.north-east", $x$)
+  #edge("a", "b", marks: "->")
+  #edge("a.south", (2, -1), marks: "<->", stroke: 2pt + red)
+  #walk("b.south", (0, -1), (-2, 0), (0, 0.8), stroke: blue)
+  #set text(red)
+  #walk((3, 0), (1, 0), (0, 1), close: true, fill: aqua, stroke: none)
+]
+
+---
+// The canvas grows to fit everything drawn on it.
+#import canvas: node, walk
+#style(styles => {
+  let size(body) = measure(canvas(length: 10pt, body), styles)
+  test(size[], (width: 0pt, height: 0pt))
+  test(size(walk((0, 0), (3, 0), (0, 2), stroke: none)), (width: 30pt, height: 20pt))
+  test(size(walk((0, 0), (3, 0), (0, 2), stroke: 2pt)), (width: 32pt, height: 22pt))
+
+  // A node is as large as its content plus its inset on each side.
+  let b = box(width: 10pt, height: 6pt)
+  test(size(node((0, 0), b, inset: 2pt)), (width: 14pt, height: 10pt))
+  test(size[#node((0, 0), b, inset: 2pt) #node((2, 0), b, inset: 2pt)], (width: 34pt, height: 10pt))
+
+  // Anchors refer to points on the border of a node.
+  test(size[
+    #node((0, 0), b, inset: 2pt, name: "a")
+    #node("a.north-east", b, inset: 2pt)
+  ], (width: 21pt, height: 15pt))
+  test(size[
+    #node((0, 0), b, inset: 2pt, name: "a")
+    #walk("a.south-west", (-1, 0), stroke: none)
+  ], (width: 24pt, height: 10pt))
+})
+
+---
+// Error: 10-37 canvas contains no node named `nope`
+#canvas[#canvas.edge("nope", (1, 1))]
+
+---
+// Error: 2:40-2:64 unknown anchor `middle`
+#import canvas: node, edge
+#canvas[#node((0, 0), [A], name: "a") #edge("a.middle", (1, 1))]
+
+---
+// Error: 9-12 canvas can only contain nodes, edges and walks
+#canvas[Hey]
+
+---
+// Error: 2-29 nodes can only be used inside of a canvas
+#canvas.node((0, 0), [Text])
+
+---
+// The elements aren't available globally.
+// Error: 2-6 unknown variable: node
+#node((0, 0), [Text])
@@ -0,0 +1,22 @@
+// Test bar, line and pie charts.
+// Ref: false
+
+---
+#bar-chart((("Q1", 12), ("Q2", 18), ("Q3", 9.5), ("Q4", 21)))
+#bar-chart((a: -3, b: 0.4, c: 1.2), fill: eastern, bar-width: 100%, legend: [Delta])
+
+---
+#line-chart((Mon: 3, Tue: 5, Wed: 4, Thu: 8), stroke: 2pt + red, marks: false)
+#line-chart(((strong[First], 0.01), ([Second], 0.02)), height: 3cm, legend: [Rate])
+
+---
+#pie-chart((Rent: 1200, Food: 450, Travel: 300))
+#pie-chart((("Only", 1),), legend: false, fills: (red,))
+
+---
+// Error: 12-19 data point must contain exactly two entries
+#bar-chart(((1,),))
+
+---
+// Error: 2-26 pie chart values must be positive
+#pie-chart((a: 1, b: -1))
@@ -0,0 +1,23 @@
+// Test QR codes and barcodes.
+// Ref: false
+
+---
+#qrcode("https://typst.app", size: 2cm)
+#qrcode("Ticket #1234", error-correction: "high", quiet-zone: 0)
+#qrcode("a" * 1000, error-correction: "low")
+
+---
+#barcode("INV-2023-0042", height: 1cm)
+#barcode("400638133393", format: "ean13")
+
+---
+// Error: 2-46 text is too long for a QR code
+#qrcode("a" * 3000, error-correction: "high")
+
+---
+// Error: 2-17 code 128 barcodes can only contain printable ASCII characters
+#barcode("a\nb")
+
+---
+// Error: 2-43 EAN-13 check digit is wrong (expected 1)
+#barcode("4006381333932", format: "ean13")
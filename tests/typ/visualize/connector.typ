@@ -0,0 +1,24 @@
+// Test connectors between labelled elements.
+// Ref: false
+
+---
+#box(rect[Start]) <start>
+#h(1fr)
+#box(rect[End]) <end>
+#v(1cm)
+#box(rect[Detour]) <detour>
+
+#connector(<start>, <end>)
+#connector(<start>, <detour>, route: "orthogonal", start-mark: "dot")
+#connector(<detour>, <end>, route: "curved", stroke: blue, end-mark: none)
+
+---
+// Error: 2-30 label does not exist in the document
+#connector(<nope>, <nowhere>)
+
+---
+// Error: 2-21 connected element must be on the same page
+#connector(<x>, <y>)
+#box[X] <x>
+#pagebreak()
+#box[Y] <y>
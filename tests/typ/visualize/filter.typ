@@ -0,0 +1,28 @@
+// Test raster filters.
+// Ref: false
+
+---
+#set page(width: 120pt, height: auto)
+#filter(grayscale: true, image("/tiger.jpg", width: 2cm))
+#filter(blur: 2pt, image("/tiger.jpg", width: 2cm))
+#filter(brightness: 50%)[Darker text]
+#filter(blur: 1000pt, grayscale: true, rect(fill: red))
+
+---
+// Filters don't affect layout.
+#let body = rect(width: 20pt, height: 10pt)
+#style(styles => {
+  let size = measure(body, styles)
+  test(measure(filter(blur: 2pt, body), styles), size)
+  test(measure(filter(blur: 2pt, grayscale: true, brightness: 50%, body), styles), size)
+})
+
+---
+#let filtered = filter(blur: 2pt, brightness: 50%)[Text]
+#test(filtered.blur, 2pt)
+#test(filtered.brightness, 50%)
+#test(filtered.has("grayscale"), false)
+
+---
+// Error: 15-18 expected length, found color
+#filter(blur: red)[]
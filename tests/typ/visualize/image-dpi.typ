@@ -0,0 +1,32 @@
+// Test that images are sized according to their resolution.
+// Ref: false
+
+---
+// A JPEG with 16x8 pixels at 144 DPI that is rotated by 90 degrees.
+#let info = image-info("/rotated.jpg")
+#test(info, (width: 8, height: 16, format: "jpg", frames: 1, dpi: 144.0, color: "luma"))
+#test(calc.round(image-info("/dpi.png").dpi, digits: 2), 143.99)
+#test(image-info("/blinker.gif").dpi, none)
+
+---
+// Without an explicit size, an image is shown at its natural size.
+#style(styles => {
+  test(measure(image("/rotated.jpg"), styles), (width: 4pt, height: 8pt))
+  let size = measure(image("/dpi.png"), styles)
+  test(calc.round(size.width / 1pt, digits: 2), 30.0)
+  test(calc.round(size.height / 1pt, digits: 2), 15.0)
+
+  // Images that don't specify their resolution use one point per pixel.
+  test(measure(image("/blinker.gif"), styles), (width: 8pt, height: 8pt))
+})
+
+---
+// An explicit size overrides the natural size.
+#style(styles => {
+  let size = measure(image("/rotated.jpg", width: 20pt), styles)
+  test(size, (width: 20pt, height: 40pt))
+  let size = measure(image("/rotated.jpg", height: 2pt), styles)
+  test(size, (width: 1pt, height: 2pt))
+  let size = measure(block(width: 2pt, image("/rotated.jpg")), styles)
+  test(size, (width: 2pt, height: 4pt))
+})
@@ -0,0 +1,28 @@
+// Test animated images.
+// Ref: false
+
+---
+#let info = image-info("/blinker.gif")
+#test(info, (width: 8, height: 8, format: "gif", frames: 3, dpi: none, color: "rgb"))
+#test(image-info("/tiger.jpg").frames, 1)
+#test(image-info("/monkey.svg").format, "svg")
+#test(image-info("/monkey.svg").color, none)
+
+---
+#image("/blinker.gif", width: 1cm, frame: 3)
+
+---
+// Error: 2-33 image has only 3 frames
+#image("/blinker.gif", frame: 4)
+
+---
+// Error: 2-31 image has only one frame
+#image("/tiger.jpg", frame: 2)
+
+---
+// Error: 31-32 number must be positive
+#image("/blinker.gif", frame: 0)
+
+---
+// Error: 13-27 file not found (searched at /missing.gif)
+#image-info("/missing.gif")
@@ -60,3 +60,7 @@ A #box(image("/tiger.jpg", height: 1cm, width: 80%)) B
 ---
 // Error: 8-18 failed to parse svg: found closing tag 'g' instead of 'style' in line 4
 #image("/bad.svg")
+
+---
+// Error: 8-24 failed to decode image
+#image("/truncated.png")
@@ -0,0 +1,37 @@
+// Test markers on lines and paths.
+// Ref: false
+
+---
+#set page(height: 80pt)
+#line(length: 3cm, start-mark: "dot", end-mark: "arrow")
+#line(end: (2cm, 1cm), stroke: 2pt + eastern, start-mark: "bar", end-mark: "bar")
+
+---
+#path(
+  stroke: 1.5pt,
+  start-mark: "arrow",
+  end-mark: "arrow",
+  (0pt, 20pt),
+  ((60pt, 0pt), (-20pt, 0pt)),
+  ((100pt, 20pt), (0pt, -20pt)),
+)
+
+---
+// A closed path has no markers.
+#path(closed: true, end-mark: "arrow", (0pt, 0pt), (10pt, 10pt), (0pt, 10pt))
+
+---
+// Markers don't take up space.
+#let marked = line(length: 3cm, start-mark: "dot", end-mark: "arrow")
+#test(marked.start-mark, "dot")
+#test(marked.end-mark, "arrow")
+#test(line(length: 3cm).has("end-mark"), false)
+#style(styles => {
+  test(measure(marked, styles), measure(line(length: 3cm), styles))
+  let size = measure(path(end-mark: "bar", (0pt, 0pt), (20pt, 10pt)), styles)
+  test(size, (width: 20pt, height: 10pt))
+})
+
+---
+// Error: 32-38 expected "arrow", "dot", "bar", or none
+#line(length: 1cm, start-mark: "star")
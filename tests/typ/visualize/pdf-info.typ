@@ -0,0 +1,20 @@
+// Test inspecting PDF files.
+// Ref: false
+
+---
+#let info = pdf-info("/report.pdf")
+#test(info.version, "1.7")
+#test(info.pages, 2)
+#test(info.width, 595pt)
+#test(info.height, 842pt)
+
+---
+// The page tree is in a compressed object stream.
+#let info = pdf-info("/compressed.pdf")
+#test(info.version, "1.5")
+#test(info.pages, 1)
+#test(info.width, 612pt)
+
+---
+// Error: 11-24 file is not a valid PDF
+#pdf-info("/hello.txt")
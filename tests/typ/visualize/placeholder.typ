@@ -0,0 +1,25 @@
+// Test placeholder content generators.
+// Ref: false
+
+---
+#placeholder()
+#placeholder(width: 50%, height: 2cm)
+#placeholder(label: "Logo")
+
+---
+#style(styles => {
+  let size = measure(placeholder(), styles)
+  test(size, (width: 120pt, height: 80pt))
+})
+
+---
+#let t = lorem-table(3, 2)
+#test(t.func(), table)
+#test(t.children.len(), 9)
+#test(t.children.first(), strong[Column 1])
+#test(lorem-table(2, 2, header: false).children.len(), 4)
+
+---
+#set page(width: auto)
+// Error: 2-26 cannot create placeholder with infinite size
+#placeholder(width: 100%)
@@ -0,0 +1,21 @@
+// Test drop shadows for boxes, blocks and text.
+// Ref: false
+
+---
+#box(fill: white, inset: 8pt, radius: 4pt, shadow: shadow())[Sharp]
+#box(fill: white, inset: 8pt, radius: 4pt, shadow: shadow(offset: (2pt, 3pt), blur: 4pt))[Blurred]
+#block(fill: white, inset: 8pt, shadow: shadow(offset: (0pt, 10%), blur: 100pt, color: blue))[
+  Many layers are capped.
+]
+
+---
+#set text(shadow: shadow(offset: (1pt, 1pt), color: silver))
+Embossed text with an #underline[underline].
+
+---
+#test(type(shadow()), "shadow")
+#test(repr(shadow(blur: 2pt)), "shadow(offset: (2pt, 2pt), blur: 2pt, color: rgb(\"#aaaaaa\"))")
+
+---
+// Error: 27-30 expected length, found color
+#box(shadow: shadow(blur: red))
@@ -0,0 +1,23 @@
+// Test text along circles and paths.
+// Ref: false
+
+---
+#set page(width: 120pt, height: auto)
+#text-path(radius: 40pt)[Round and round it goes]
+#text-path(radius: 30pt, start: 90deg, align: center, spacing: 1pt)[Below]
+#text-path(
+  align: end,
+  ((0pt, 40pt), (-20pt, 20pt)),
+  ((100pt, 40pt), (-20pt, -40pt)),
+)[Follow the curve]
+
+---
+// Error: 2-22 text path needs a radius or at least two vertices
+#text-path([Nowhere])
+
+---
+// A circle without radius puts all glyphs onto its center.
+#style(styles => {
+  let size = measure(text-path(radius: 0pt)[Dot], styles)
+  test(size, (width: 0pt, height: 0pt))
+})
@@ -0,0 +1,234 @@
+//! Typst compiled to WebAssembly.
+//!
+//! Built for the `wasm32-unknown-unknown` target, for example with
+//! `wasm-pack build wasm --target web`. It isn't part of the default build.
+//!
+//! The browser has no file system and no installed fonts, so the compiler
+//! keeps all files and fonts in memory. JavaScript adds them upfront, then
+//! compiles a main file into a document, which can be exported to PDF or
+//! rendered page by page into SVG.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use comemo::Prehashed;
+use elsa::FrozenVec;
+use typst::diag::{FileError, FileResult, SourceError};
+use typst::eval::{Datetime, Library};
+use typst::font::{Font, FontBook};
+use typst::syntax::{Source, SourceId};
+use typst::util::{Buffer, PathExt};
+use typst::World;
+use wasm_bindgen::prelude::*;
+
+/// A reusable compiler with in-memory files and fonts.
+#[wasm_bindgen]
+pub struct Compiler {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    fonts: Vec<Font>,
+    files: HashMap<PathBuf, Buffer>,
+    today: Option<Datetime>,
+}
+
+#[wasm_bindgen]
+impl Compiler {
+    /// Create a new compiler without any files or fonts.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            library: Prehashed::new(typst_library::build()),
+            book: Prehashed::new(FontBook::new()),
+            fonts: vec![],
+            files: HashMap::new(),
+            today: None,
+        }
+    }
+
+    /// Add all fonts in a font file or collection. Returns how many fonts
+    /// were added.
+    #[wasm_bindgen(js_name = addFont)]
+    pub fn add_font(&mut self, data: Vec<u8>) -> usize {
+        let before = self.fonts.len();
+        self.fonts.extend(Font::iter(Buffer::from(data)));
+        self.book = Prehashed::new(FontBook::from_fonts(&self.fonts));
+        self.fonts.len() - before
+    }
+
+    /// Add a file or replace its contents. Paths are resolved from the root
+    /// `/`.
+    #[wasm_bindgen(js_name = addFile)]
+    pub fn add_file(&mut self, path: &str, data: Vec<u8>) {
+        self.files.insert(normalize(path), Buffer::from(data));
+    }
+
+    /// Remove a file. Returns whether it existed.
+    #[wasm_bindgen(js_name = removeFile)]
+    pub fn remove_file(&mut self, path: &str) -> bool {
+        self.files.remove(&normalize(path)).is_some()
+    }
+
+    /// Remove all files.
+    #[wasm_bindgen(js_name = clearFiles)]
+    pub fn clear_files(&mut self) {
+        self.files.clear();
+    }
+
+    /// The paths of all files, in no particular order.
+    #[wasm_bindgen(js_name = listFiles)]
+    pub fn list_files(&self) -> Vec<JsValue> {
+        self.files
+            .keys()
+            .map(|path| JsValue::from_str(&path.to_string_lossy()))
+            .collect()
+    }
+
+    /// Set the date returned by `datetime.today()`. Returns false if the date
+    /// is invalid. Without a date, `datetime.today()` fails.
+    #[wasm_bindgen(js_name = setToday)]
+    pub fn set_today(&mut self, year: i32, month: u8, day: u8) -> bool {
+        self.today = Datetime::from_ymd(year, month, day);
+        self.today.is_some()
+    }
+
+    /// Compile the given main file. Throws a string with one error per line
+    /// if compilation fails.
+    pub fn compile(&self, main: &str) -> Result<Document, JsValue> {
+        let world = MemoryWorld::new(self, &normalize(main))?;
+        match typst::compile(&world) {
+            Ok(document) => Ok(Document(document)),
+            Err(errors) => {
+                let messages: Vec<_> =
+                    errors.iter().map(|error| describe(&world, error)).collect();
+                Err(JsValue::from_str(&messages.join("\n")))
+            }
+        }
+    }
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A compiled document.
+#[wasm_bindgen]
+pub struct Document(typst::doc::Document);
+
+#[wasm_bindgen]
+impl Document {
+    /// The number of pages.
+    #[wasm_bindgen(getter, js_name = pageCount)]
+    pub fn page_count(&self) -> usize {
+        self.0.pages.len()
+    }
+
+    /// Export the document into a PDF file.
+    pub fn pdf(&self) -> Result<Vec<u8>, JsValue> {
+        typst::export::pdf(&self.0).map_err(|message| JsValue::from_str(&message))
+    }
+
+    /// Render the page with the given zero-based index into an SVG image.
+    pub fn svg(&self, page: usize) -> Result<String, JsValue> {
+        self.0
+            .pages
+            .get(page)
+            .map(typst::export::svg)
+            .ok_or_else(|| JsValue::from_str("page index out of bounds"))
+    }
+}
+
+/// Turn a path into an absolute, normalized path.
+fn normalize(path: &str) -> PathBuf {
+    Path::new("/").join(path).normalize()
+}
+
+/// Describe an error together with its location.
+fn describe(world: &MemoryWorld, error: &SourceError) -> String {
+    if error.span.is_detached() {
+        return format!("error: {}", error.message);
+    }
+
+    let source = world.source(error.span.source());
+    let start = error.range(world).start;
+    let line = source.byte_to_line(start).unwrap_or(0) + 1;
+    let column = source.byte_to_column(start).unwrap_or(0) + 1;
+    format!("{}:{}:{}: error: {}", source.path().display(), line, column, error.message)
+}
+
+/// A world that gets its files from the compiler's memory.
+struct MemoryWorld<'a> {
+    compiler: &'a Compiler,
+    sources: FrozenVec<Box<Source>>,
+    paths: RefCell<HashMap<PathBuf, FileResult<SourceId>>>,
+}
+
+impl<'a> MemoryWorld<'a> {
+    fn new(compiler: &'a Compiler, main: &Path) -> Result<Self, JsValue> {
+        let world = Self {
+            compiler,
+            sources: FrozenVec::new(),
+            paths: RefCell::default(),
+        };
+        world
+            .resolve(main)
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+        Ok(world)
+    }
+}
+
+impl World for MemoryWorld<'_> {
+    fn root(&self) -> &Path {
+        Path::new("/")
+    }
+
+    fn library(&self) -> &Prehashed<Library> {
+        &self.compiler.library
+    }
+
+    fn main(&self) -> &Source {
+        &self.sources[0]
+    }
+
+    fn resolve(&self, path: &Path) -> FileResult<SourceId> {
+        if let Some(result) = self.paths.borrow().get(path) {
+            return result.clone();
+        }
+
+        let result = self.file(path).and_then(|data| {
+            let text = std::str::from_utf8(&data).map_err(|_| FileError::InvalidUtf8)?;
+            let id = SourceId::from_u16(self.sources.len() as u16);
+            self.sources.push(Box::new(Source::new(id, path, text.into())));
+            Ok(id)
+        });
+
+        self.paths.borrow_mut().insert(path.into(), result.clone());
+        result
+    }
+
+    fn source(&self, id: SourceId) -> &Source {
+        &self.sources[id.as_u16() as usize]
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.compiler.book
+    }
+
+    fn font(&self, id: usize) -> Option<Font> {
+        self.compiler.fonts.get(id).cloned()
+    }
+
+    fn file(&self, path: &Path) -> FileResult<Buffer> {
+        self.compiler
+            .files
+            .get(&path.normalize())
+            .cloned()
+            .ok_or_else(|| FileError::NotFound(path.into()))
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        self.compiler.today
+    }
+}